@@ -0,0 +1,184 @@
+use crate::{
+    adapters::traits::PlatformAdapter,
+    core::{lock::write_atomic, metrics, report::PlatformReport, store::content_hash},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+};
+
+/// 内存中最多保留的适配结果数
+const MAX_MEMORY_ENTRIES: usize = 256;
+/// 磁盘上最多保留的适配结果数，超过时删除最早写入的
+const MAX_DISK_ENTRIES: usize = 2000;
+
+/// 一次适配的输出和报告中记录的改动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedAdaptation {
+    html: String,
+    report: PlatformReport,
+}
+
+/// 按（输入 HTML 哈希、适配器版本和设置）缓存平台适配结果。
+///
+/// 同一篇文章在预览中切换平台、或调整主题后批量重新处理时，
+/// 只有输入或设置变化的适配器需要重新运行。指定目录时每个结果单独存为一个文件，
+/// 供之后的 CLI 运行复用
+pub struct AdapterCache {
+    dir: Option<PathBuf>,
+    memory: HashMap<String, CachedAdaptation>,
+    order: VecDeque<String>,
+}
+
+impl Default for AdapterCache {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl AdapterCache {
+    pub fn in_memory() -> Self {
+        Self {
+            dir: None,
+            memory: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            ..Self::in_memory()
+        }
+    }
+
+    /// `~/.markflow/adapter_cache/`
+    pub fn open_default() -> Self {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self::open(home_dir.join(".markflow").join("adapter_cache"))
+    }
+
+    fn entry_path(&self, key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(format!("{}.json", content_hash(key.as_bytes()))))
+    }
+
+    fn get(&self, key: &str) -> Option<CachedAdaptation> {
+        if let Some(entry) = self.memory.get(key) {
+            return Some(entry.clone());
+        }
+        let raw = std::fs::read_to_string(self.entry_path(key)?).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    fn remember(&mut self, key: String, entry: CachedAdaptation) {
+        if self.memory.insert(key.clone(), entry).is_none() {
+            self.order.push_back(key);
+        }
+        while self.order.len() > MAX_MEMORY_ENTRIES {
+            if let Some(oldest) = self.order.pop_front() {
+                self.memory.remove(&oldest);
+            }
+        }
+    }
+
+    fn insert(&mut self, key: String, entry: CachedAdaptation) -> Result<()> {
+        if let Some(path) = self.entry_path(&key) {
+            write_atomic(&path, serde_json::to_string(&entry)?)?;
+            self.prune_disk()?;
+        }
+        self.remember(key, entry);
+        Ok(())
+    }
+
+    fn prune_disk(&self) -> Result<()> {
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        let mut entries: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect();
+        if entries.len() <= MAX_DISK_ENTRIES {
+            return Ok(());
+        }
+        entries.sort();
+        for (_, path) in entries.iter().take(entries.len() - MAX_DISK_ENTRIES) {
+            let _ = std::fs::remove_file(path);
+        }
+        Ok(())
+    }
+
+    /// 用缓存的结果代替适配；未命中时适配并保存结果。
+    /// 适配器返回的报告改动同样被缓存，命中时合并到 `report` 中
+    pub fn adapt(
+        &mut self,
+        adapter: &dyn PlatformAdapter,
+        html: &str,
+        report: &mut PlatformReport,
+    ) -> Result<String> {
+        let Some(adapter_key) = adapter.cache_key() else {
+            return adapter.adapt_html_with_report(html, report);
+        };
+        let key = format!("{}-{}", adapter_key, content_hash(html.as_bytes()));
+
+        if let Some(entry) = self.get(&key) {
+            metrics().record_cache("adapters", true);
+            report.merge(&entry.report);
+            let html = entry.html.clone();
+            self.remember(key, entry);
+            return Ok(html);
+        }
+        metrics().record_cache("adapters", false);
+
+        let mut fresh = PlatformReport::new(adapter.platform());
+        let adapted = adapter.adapt_html_with_report(html, &mut fresh)?;
+        report.merge(&fresh);
+        let entry = CachedAdaptation {
+            html: adapted.clone(),
+            report: fresh,
+        };
+        if let Err(e) = self.insert(key, entry) {
+            tracing::warn!("保存适配缓存失败: {}", e);
+        }
+        Ok(adapted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{adapters::WeChatStyleAdapter, cli::args::StyleConfig};
+
+    #[test]
+    fn test_adapter_cache_keyed_by_html_and_style() {
+        let dir = tempfile::tempdir().unwrap();
+        let html = "<h1>标题</h1><p>正文<script>x</script></p>";
+        let adapter = WeChatStyleAdapter::new();
+
+        let mut report = PlatformReport::new(adapter.platform());
+        let expected = adapter.adapt_html_with_report(html, &mut report).unwrap();
+
+        let mut cache = AdapterCache::open(dir.path());
+        let mut first = PlatformReport::new(adapter.platform());
+        assert_eq!(cache.adapt(&adapter, html, &mut first).unwrap(), expected);
+
+        // 新的进程从磁盘读取，报告中的改动同样保留
+        let mut cache = AdapterCache::open(dir.path());
+        let mut second = PlatformReport::new(adapter.platform());
+        assert_eq!(cache.adapt(&adapter, html, &mut second).unwrap(), expected);
+        assert_eq!(second.stripped_elements, report.stripped_elements);
+
+        // 主题变量变化后缓存标识不同
+        let themed = WeChatStyleAdapter::from_style(&StyleConfig {
+            primary_color: "#ff6600".to_string(),
+            ..StyleConfig::default()
+        });
+        assert_ne!(themed.cache_key(), adapter.cache_key());
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -0,0 +1,279 @@
+use crate::{error::Error, Result};
+use html5ever::{local_name, namespace_url, ns, QualName};
+use scraper::{ElementRef, Html, Node, Selector};
+
+/// 一条声明，如 `color: red`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Declaration {
+    property: String,
+    value: String,
+    important: bool,
+}
+
+#[derive(Debug, Clone)]
+struct StyleRule {
+    selector: Selector,
+    /// (id 数, 类/属性/伪类数, 标签数)
+    specificity: (u32, u32, u32),
+    declarations: Vec<Declaration>,
+}
+
+/// 解析后的样式表，按 CSS 层叠规则把样式写入元素的 `style` 属性。
+///
+/// 支持选择器组、注释和 `!important`；`@media` 等 at 规则在内联样式中无法表达，直接忽略
+#[derive(Debug, Clone, Default)]
+pub struct Stylesheet {
+    rules: Vec<StyleRule>,
+}
+
+impl Stylesheet {
+    pub fn parse(css: &str) -> Result<Self> {
+        let css = strip_comments(css);
+        let mut rules = Vec::new();
+        let mut rest = css.as_str();
+        while let Some(open) = rest.find('{') {
+            let prelude = rest[..open].trim();
+            let close = matching_brace(rest, open)
+                .ok_or_else(|| Error::Config(format!("样式表缺少 `}}`: {}", prelude)))?;
+            let body = &rest[open + 1..close];
+            rest = &rest[close + 1..];
+
+            // 上一条 at 规则（如 `@import ...;`）没有块时会残留在选择器前面
+            let prelude = prelude.rsplit(';').next().unwrap_or_default().trim();
+            if prelude.is_empty() || prelude.starts_with('@') {
+                continue;
+            }
+            let declarations = parse_declarations(body);
+            for selector in split_top_level(prelude, ',') {
+                let selector = selector.trim();
+                let parsed = Selector::parse(selector).map_err(|e| {
+                    Error::Config(format!("无法解析 CSS 选择器 `{}`: {:?}", selector, e))
+                })?;
+                rules.push(StyleRule {
+                    selector: parsed,
+                    specificity: specificity(selector),
+                    declarations: declarations.clone(),
+                });
+            }
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// 解析 HTML 片段，把匹配的规则写入各元素的 `style` 属性后重新序列化。
+    ///
+    /// 元素原有的内联样式优先于普通规则，`!important` 规则优先于原有内联样式
+    pub fn inline(&self, html: &str) -> Result<String> {
+        let mut fragment = Html::parse_fragment(html);
+        let styles: Vec<_> = fragment
+            .root_element()
+            .descendants()
+            .filter_map(ElementRef::wrap)
+            .filter_map(|element| {
+                let style = self.computed_style(&element)?;
+                Some((element.id(), style))
+            })
+            .collect();
+
+        for (id, style) in styles {
+            if let Some(mut node) = fragment.tree.get_mut(id) {
+                if let Node::Element(element) = node.value() {
+                    element.attrs.insert(
+                        QualName::new(None, ns!(), local_name!("style")),
+                        style.into(),
+                    );
+                }
+            }
+        }
+        Ok(fragment.root_element().inner_html())
+    }
+
+    /// 元素最终的内联样式；没有匹配的规则时返回 `None`，保持元素不变
+    fn computed_style(&self, element: &ElementRef) -> Option<String> {
+        let mut matched: Vec<(usize, &StyleRule)> = self
+            .rules
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.selector.matches(element))
+            .collect();
+        if matched.is_empty() {
+            return None;
+        }
+        matched.sort_by_key(|(order, rule)| (rule.specificity, *order));
+
+        let inline = parse_declarations(element.value().attr("style").unwrap_or_default());
+        let normal = matched
+            .iter()
+            .flat_map(|(_, rule)| rule.declarations.iter())
+            .filter(|d| !d.important);
+        let important = matched
+            .iter()
+            .flat_map(|(_, rule)| rule.declarations.iter())
+            .filter(|d| d.important);
+
+        // 后出现的同名属性覆盖先出现的
+        let mut computed: Vec<&Declaration> = Vec::new();
+        for declaration in normal.chain(inline.iter()).chain(important) {
+            computed.retain(|d| d.property != declaration.property);
+            computed.push(declaration);
+        }
+        Some(
+            computed
+                .iter()
+                .map(|d| format!("{}: {};", d.property, d.value))
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+fn strip_comments(css: &str) -> String {
+    let mut result = String::with_capacity(css.len());
+    let mut rest = css;
+    while let Some(start) = rest.find("/*") {
+        result.push_str(&rest[..start]);
+        rest = match rest[start + 2..].find("*/") {
+            Some(end) => &rest[start + 2 + end + 2..],
+            None => "",
+        };
+    }
+    result.push_str(rest);
+    result
+}
+
+/// `open` 处的 `{` 对应的 `}`（跳过嵌套块，如 `@media` 中的规则）
+fn matching_brace(css: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in css[open..].char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// 按分隔符切分，忽略括号和引号中的分隔符（如 `url(data:...;base64,...)`）
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(' | '[') => depth += 1,
+            (None, ')' | ']') => depth -= 1,
+            (None, _) if c == separator && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn parse_declarations(block: &str) -> Vec<Declaration> {
+    split_top_level(block, ';')
+        .into_iter()
+        .filter_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            let property = property.trim().to_ascii_lowercase();
+            let mut value = value.trim();
+            let important = match value.strip_suffix("!important") {
+                Some(stripped) => {
+                    value = stripped.trim_end();
+                    true
+                }
+                None => false,
+            };
+            (!property.is_empty() && !value.is_empty()).then(|| Declaration {
+                property,
+                value: value.to_string(),
+                important,
+            })
+        })
+        .collect()
+}
+
+/// 单个选择器的优先级：`#id` 计入第一位，`.class`、`[attr]` 和伪类计入第二位，标签名计入第三位
+fn specificity(selector: &str) -> (u32, u32, u32) {
+    let (mut ids, mut classes, mut tags) = (0, 0, 0);
+    for compound in selector.split(|c: char| c.is_whitespace() || matches!(c, '>' | '+' | '~')) {
+        if compound.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            tags += 1;
+        }
+        let mut chars = compound.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '#' => ids += 1,
+                '.' | '[' => classes += 1,
+                ':' if chars.peek() == Some(&':') => {
+                    chars.next();
+                    tags += 1;
+                }
+                ':' => classes += 1,
+                _ => {}
+            }
+        }
+    }
+    (ids, classes, tags)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cascade_and_nested_tags() {
+        let stylesheet = Stylesheet::parse(
+            r#"
+            /* 默认主题 */
+            p { color: #333; margin: 0 }
+            p.note, blockquote p { color: gray; }
+            #lead { color: red !important; }
+            img { max-width: 100%; }
+            @media (max-width: 600px) { p { color: blue; } }
+            "#,
+        )
+        .unwrap();
+
+        let html = stylesheet
+            .inline(concat!(
+                r#"<p>普通<img src="a.png"/></p>"#,
+                r#"<p class="note" style="margin: 4px">说明</p>"#,
+                r#"<blockquote><p id="lead" style="color: green">引用</p></blockquote>"#,
+                "<span>无样式</span>"
+            ))
+            .unwrap();
+
+        assert!(html.contains(r#"<p style="color: #333; margin: 0;">普通"#));
+        assert!(html.contains(r#"<img src="a.png" style="max-width: 100%;">"#));
+        assert!(html.contains(r#"<p class="note" style="color: gray; margin: 4px;">说明"#));
+        assert!(html.contains(r#"<p id="lead" style="margin: 0; color: red;">引用"#));
+        assert!(html.contains("<span>无样式</span>"));
+        assert!(!html.contains("blue"));
+    }
+
+    #[test]
+    fn test_specificity_and_invalid_selector() {
+        assert_eq!(specificity("p"), (0, 0, 1));
+        assert_eq!(specificity("blockquote > p.note:first-child"), (0, 2, 2));
+        assert_eq!(specificity("#lead a[href]::after"), (1, 1, 2));
+        assert!(Stylesheet::parse("p[ { color: red }").is_err());
+        assert!(Stylesheet::parse("p { color: red").is_err());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    adapters::traits::{
+        AdapterCapabilities, PlatformAdapter, ValidationReport, ValidationSeverity,
+    },
+    core::{
+        content::{Content, Platform},
+        footnotes::render_footnote_tooltips,
+        media::render_media_placeholders,
+        report::PlatformReport,
+    },
+    Result,
+};
+use async_trait::async_trait;
+
+/// 通用 HTML 适配器，用于预览页、静态站点等可以运行完整 HTML/CSS 的目标。
+///
+/// 通过 `register_adapter` 注册为自定义平台后即可在命令行中使用
+pub struct HtmlAdapter {
+    platform: Platform,
+    capabilities: AdapterCapabilities,
+}
+
+impl HtmlAdapter {
+    pub fn new(platform: Platform) -> Self {
+        Self {
+            platform,
+            capabilities: AdapterCapabilities {
+                inline_footnotes: true,
+            },
+        }
+    }
+
+    pub fn with_capabilities(mut self, capabilities: AdapterCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+}
+
+#[async_trait]
+impl PlatformAdapter for HtmlAdapter {
+    fn platform(&self) -> Platform {
+        self.platform.clone()
+    }
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        self.capabilities
+    }
+
+    fn adapt_html_with_report(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
+        let html = render_media_placeholders(html, &self.platform, report);
+        if self.capabilities().inline_footnotes {
+            Ok(render_footnote_tooltips(&html))
+        } else {
+            Ok(html)
+        }
+    }
+
+    fn validate_content(&self, content: &Content) -> Result<()> {
+        self.validation_report(content)
+            .into_result(&self.platform.to_string())
+    }
+
+    fn validation_report(&self, content: &Content) -> ValidationReport {
+        let mut report = ValidationReport::default();
+        if content.title.is_empty() {
+            report.push(
+                "title_empty",
+                "title",
+                "标题不能为空",
+                ValidationSeverity::Error,
+            );
+        }
+        report
+    }
+
+    async fn preprocess_images(&self, html: &str) -> Result<String> {
+        Ok(html.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r##"<p>正文<sup class="footnote-ref"><a href="#fn-1" id="fnref-1" data-footnote-ref>1</a></sup></p>
+<section class="footnotes" data-footnotes>
+<ol>
+<li id="fn-1">
+<p>脚注 <a href="#fnref-1" class="footnote-backref" data-footnote-backref aria-label="Back to content">↩</a></p>
+</li>
+</ol>
+</section>
+"##;
+
+    #[test]
+    fn test_footnotes_follow_capabilities() {
+        let adapter = HtmlAdapter::new(Platform::Custom("blog".to_string()));
+        let html = adapter.adapt_html(HTML).unwrap();
+        assert!(html.contains("footnote-tooltip"));
+        assert!(!html.contains("<section class=\"footnotes\""));
+
+        let adapter = adapter.with_capabilities(AdapterCapabilities::default());
+        assert_eq!(adapter.adapt_html(HTML).unwrap(), HTML);
+    }
+}
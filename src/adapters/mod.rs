@@ -1,7 +1,84 @@
+pub mod cache;
+pub mod css;
+pub mod html;
 pub mod traits;
 pub mod wechat;
 pub mod zhihu;
 
+pub use cache::*;
+pub use css::*;
+pub use html::*;
 pub use traits::*;
 pub use wechat::*;
 pub use zhihu::*;
+
+use crate::{
+    cli::args::AppConfig,
+    core::{register_platform, Platform},
+    error::Error,
+    Result,
+};
+use std::{
+    collections::HashMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+/// 自定义平台的适配器工厂
+pub type AdapterFactory = Arc<dyn Fn(&AppConfig) -> Box<dyn PlatformAdapter> + Send + Sync>;
+
+fn adapter_factories() -> &'static RwLock<HashMap<String, AdapterFactory>> {
+    static FACTORIES: OnceLock<RwLock<HashMap<String, AdapterFactory>>> = OnceLock::new();
+    FACTORIES.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// 注册自定义平台及其适配器，需要在 `cli::run()` 之前调用，注册后即可在 `--platform` 中使用
+pub fn register_adapter<F>(name: &str, factory: F) -> Result<Platform>
+where
+    F: Fn(&AppConfig) -> Box<dyn PlatformAdapter> + Send + Sync + 'static,
+{
+    let platform = register_platform(name)?;
+    adapter_factories()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(platform.to_string(), Arc::new(factory));
+    Ok(platform)
+}
+
+/// 按平台和配置创建适配器
+pub fn create_adapter(platform: &Platform, config: &AppConfig) -> Result<Box<dyn PlatformAdapter>> {
+    match platform {
+        Platform::WeChat => {
+            let mut adapter = WeChatStyleAdapter::from_style(&config.style()?)
+                .with_paragraph_split(
+                    config
+                        .wechat
+                        .split_long_paragraphs
+                        .then_some(config.wechat.max_paragraph_chars),
+                )
+                .with_allowed_tags(config.wechat.allowed_tags.clone());
+            if let Some(theme) = &config.wechat.theme {
+                let css = std::fs::read_to_string(theme)
+                    .map_err(|e| Error::Config(format!("无法读取公众号主题 {:?}: {}", theme, e)))?;
+                adapter = adapter.with_theme_css(&css)?;
+            }
+            Ok(Box::new(adapter))
+        }
+        Platform::Zhihu => Ok(Box::new(
+            ZhihuStyleAdapter::new()
+                .with_math(config.zhihu.enable_math)
+                .with_code_theme(config.zhihu.code_theme.clone())
+                .with_math_image_service(config.zhihu.math_image_service.clone())
+                .with_forbidden_tags(config.zhihu.forbidden_tags.clone()),
+        )),
+        Platform::All => Err(Error::InvalidPlatform("all 需要展开为具体平台".to_string())),
+        Platform::Custom(name) => {
+            let factory = adapter_factories()
+                .read()
+                .unwrap_or_else(|e| e.into_inner())
+                .get(name)
+                .cloned()
+                .ok_or_else(|| Error::InvalidPlatform(format!("平台 {} 没有注册适配器", name)))?;
+            Ok(factory(config))
+        }
+    }
+}
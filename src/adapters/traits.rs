@@ -1,32 +1,233 @@
 use crate::{
-    core::content::{Content, Platform},
+    core::{
+        content::{Content, Platform},
+        report::PlatformReport,
+    },
+    error::Error,
     Result,
 };
 use async_trait::async_trait;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// 目标平台支持的能力，用于选择渲染方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AdapterCapabilities {
+    /// 支持悬停提示等交互，脚注渲染为行内提示框而不是文末列表
+    pub inline_footnotes: bool,
+}
 
 #[async_trait]
 pub trait PlatformAdapter: Send + Sync {
     fn platform(&self) -> Platform;
-    fn adapt_html(&self, html: &str) -> Result<String>;
+
+    fn capabilities(&self) -> AdapterCapabilities {
+        AdapterCapabilities::default()
+    }
+
+    /// 适配结果的缓存标识，由适配器版本和所有影响输出的设置（主题变量、段落拆分等）组成；
+    /// 返回 `None` 时不缓存适配结果
+    fn cache_key(&self) -> Option<String> {
+        None
+    }
+
+    fn adapt_html(&self, html: &str) -> Result<String> {
+        let mut report = PlatformReport::new(self.platform());
+        self.adapt_html_with_report(html, &mut report)
+    }
+
+    /// 适配HTML，同时把移除的元素、转换的链接等改动记录到报告中
+    fn adapt_html_with_report(&self, html: &str, report: &mut PlatformReport) -> Result<String>;
+
+    /// 与 `adapt_html_with_report` 相同，每个适配步骤完成后回调 `(步骤名, HTML)`，用于调试导出；
+    /// 默认把整个适配当作一个步骤
+    fn adapt_html_with_steps(
+        &self,
+        html: &str,
+        report: &mut PlatformReport,
+        on_step: &mut dyn FnMut(&str, &str) -> Result<()>,
+    ) -> Result<String> {
+        let adapted = self.adapt_html_with_report(html, report)?;
+        on_step("适配", &adapted)?;
+        Ok(adapted)
+    }
+
     fn validate_content(&self, content: &Content) -> Result<()>;
+
+    /// 返回全部验证结果（包括不会中断处理的警告）
+    fn validation_report(&self, content: &Content) -> ValidationReport;
+
     async fn preprocess_images(&self, html: &str) -> Result<String>;
 }
 
 #[derive(Debug, Clone)]
 pub struct ValidationError {
+    /// 检查规则名，如 `tags_count`，用于在 `[validation]` 中调整级别
+    pub rule: String,
     pub field: String,
     pub message: String,
     pub severity: ValidationSeverity,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValidationSeverity {
     Error,
     Warning,
     Info,
 }
 
+impl FromStr for ValidationSeverity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "error" => Ok(ValidationSeverity::Error),
+            "warning" | "warn" => Ok(ValidationSeverity::Warning),
+            "info" => Ok(ValidationSeverity::Info),
+            _ => Err(Error::Config(format!(
+                "无效的验证级别: {}（可选: error、warning、info、off）",
+                s
+            ))),
+        }
+    }
+}
+
+/// 解析 `[validation]` 中的级别设置，`off` 表示忽略该检查
+pub fn parse_validation_level(value: &str) -> Result<Option<ValidationSeverity>> {
+    if value.eq_ignore_ascii_case("off") {
+        Ok(None)
+    } else {
+        value.parse().map(Some)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ValidationReport {
+    pub findings: Vec<ValidationError>,
+}
+
+impl ValidationReport {
+    pub fn push(
+        &mut self,
+        rule: &str,
+        field: impl Into<String>,
+        message: impl Into<String>,
+        severity: ValidationSeverity,
+    ) {
+        self.findings.push(ValidationError {
+            rule: rule.to_string(),
+            field: field.into(),
+            message: message.into(),
+            severity,
+        });
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &ValidationError> {
+        self.findings
+            .iter()
+            .filter(|e| matches!(e.severity, ValidationSeverity::Error))
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &ValidationError> {
+        self.findings
+            .iter()
+            .filter(|e| !matches!(e.severity, ValidationSeverity::Error))
+    }
+
+    /// 按配置调整各规则的级别：`规则名 → error/warning/info/off`，`off` 的结果直接丢弃
+    pub fn apply_levels(&mut self, levels: &BTreeMap<String, String>) -> Result<()> {
+        let mut findings = Vec::with_capacity(self.findings.len());
+        for mut finding in std::mem::take(&mut self.findings) {
+            match levels.get(&finding.rule) {
+                Some(level) => {
+                    if let Some(severity) = parse_validation_level(level)? {
+                        finding.severity = severity;
+                        findings.push(finding);
+                    }
+                }
+                None => findings.push(finding),
+            }
+        }
+        self.findings = findings;
+        Ok(())
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    /// 存在错误级别的结果时返回验证失败
+    pub fn into_result(self, platform_name: &str) -> Result<()> {
+        let error_messages: Vec<String> = self
+            .errors()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+
+        if error_messages.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Publishing(format!(
+                "{}内容验证失败: {}",
+                platform_name,
+                error_messages.join("; ")
+            )))
+        }
+    }
+}
+
 pub trait StyleProvider {
     fn get_styles(&self) -> &str;
     fn apply_inline_styles(&self, html: &str) -> Result<String>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_validation_levels() {
+        let mut report = ValidationReport::default();
+        report.push(
+            "tags_count",
+            "tags",
+            "标签过多",
+            ValidationSeverity::Warning,
+        );
+        report.push(
+            "forbidden_keyword",
+            "content",
+            "广告",
+            ValidationSeverity::Warning,
+        );
+        report.push(
+            "title_empty",
+            "title",
+            "标题为空",
+            ValidationSeverity::Error,
+        );
+
+        let levels: BTreeMap<String, String> = [
+            ("tags_count".to_string(), "error".to_string()),
+            ("forbidden_keyword".to_string(), "off".to_string()),
+            ("title_empty".to_string(), "warning".to_string()),
+        ]
+        .into();
+        report.apply_levels(&levels).unwrap();
+
+        let errors: Vec<&str> = report.errors().map(|e| e.rule.as_str()).collect();
+        let warnings: Vec<&str> = report.warnings().map(|e| e.rule.as_str()).collect();
+        assert_eq!(errors, vec!["tags_count"]);
+        assert_eq!(warnings, vec!["title_empty"]);
+        assert!(report.into_result("知乎").is_err());
+
+        let invalid: BTreeMap<String, String> =
+            [("tags_count".to_string(), "fatal".to_string())].into();
+        let mut report = ValidationReport::default();
+        report.push(
+            "tags_count",
+            "tags",
+            "标签过多",
+            ValidationSeverity::Warning,
+        );
+        assert!(report.apply_levels(&invalid).is_err());
+    }
+}
@@ -1,50 +1,142 @@
 use crate::{
-    adapters::traits::{PlatformAdapter, StyleProvider, ValidationError, ValidationSeverity},
-    core::content::{Content, Platform},
+    adapters::{
+        css::Stylesheet,
+        traits::{PlatformAdapter, StyleProvider, ValidationReport, ValidationSeverity},
+    },
+    cli::args::StyleConfig,
+    core::{
+        content::{Content, Platform},
+        links::canonical_url,
+        math::rendered_math_ranges,
+        media::render_media_placeholders,
+        readability::split_long_paragraphs,
+        report::PlatformReport,
+        store::content_hash,
+    },
     error::Error,
     Result,
 };
 use async_trait::async_trait;
 use regex::Regex;
-use scraper::{Html, Selector};
+use scraper::Html;
 use std::collections::HashMap;
 
+/// 适配逻辑变化导致输出不同时递增，使旧的缓存结果失效
+const ADAPTER_VERSION: u32 = 5;
+
+/// 公众号编辑器接受的标签
+pub const DEFAULT_WECHAT_ALLOWED_TAGS: &[&str] = &[
+    "p",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "br",
+    "hr",
+    "strong",
+    "b",
+    "em",
+    "i",
+    "u",
+    "s",
+    "del",
+    "ins",
+    "sup",
+    "sub",
+    "mark",
+    "kbd",
+    "small",
+    "blockquote",
+    "pre",
+    "code",
+    "span",
+    "div",
+    "ul",
+    "ol",
+    "li",
+    "dl",
+    "dt",
+    "dd",
+    "table",
+    "caption",
+    "colgroup",
+    "col",
+    "thead",
+    "tbody",
+    "tfoot",
+    "tr",
+    "th",
+    "td",
+    "img",
+    "figure",
+    "figcaption",
+    "a",
+    "section",
+    "article",
+    "aside",
+    "nav",
+];
+
 pub struct WeChatStyleAdapter {
-    inline_styles: HashMap<String, String>,
+    /// 内联到各元素的主题样式
+    stylesheet: Stylesheet,
+    /// 主题样式的 CSS 源码，供 `StyleProvider::get_styles` 返回
+    css: String,
+    primary_color: String,
     max_content_length: usize,
-    #[allow(dead_code)]
-    allowed_tags: Vec<&'static str>,
+    /// 超过该字数的段落按句子拆分，`None` 表示不拆分
+    max_paragraph_chars: Option<usize>,
+    /// 公众号编辑器保留的标签，其余标签清理时去掉、保留内容
+    allowed_tags: Vec<String>,
 }
 
 impl WeChatStyleAdapter {
     pub fn new() -> Self {
+        Self::from_style(&StyleConfig::default())
+    }
+
+    /// 按 `[style]` 主题变量生成内置的默认主题
+    pub fn from_style(style: &StyleConfig) -> Self {
+        let StyleConfig {
+            primary_color: primary,
+            text_color: text,
+            heading_color: heading,
+            code_color: code,
+            font_size,
+            h1_font_size,
+            h2_font_size,
+            h3_font_size,
+            ..
+        } = style;
         let mut inline_styles = HashMap::new();
 
         // 微信公众号样式规则
-        inline_styles.insert("body".to_string(), 
-            "font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; color: #333; line-height: 1.6; margin: 0; padding: 20px;".to_string());
+        inline_styles.insert("body".to_string(), format!(
+            "font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; color: {text}; line-height: 1.6; margin: 0; padding: 20px;"));
 
         inline_styles.insert(
             "p".to_string(),
-            "font-size: 16px; line-height: 1.8; margin: 20px 0; color: #333; text-align: justify;"
-                .to_string(),
+            format!("font-size: {font_size}; line-height: 1.8; margin: 20px 0; color: {text}; text-align: justify;"),
         );
 
-        inline_styles.insert("h1".to_string(), 
-            "font-size: 24px; font-weight: bold; text-align: center; margin: 30px 0 20px 0; color: #2c3e50; border-bottom: 2px solid #3498db; padding-bottom: 10px;".to_string());
+        inline_styles.insert("h1".to_string(), format!(
+            "font-size: {h1_font_size}; font-weight: bold; text-align: center; margin: 30px 0 20px 0; color: {heading}; border-bottom: 2px solid {primary}; padding-bottom: 10px;"));
 
-        inline_styles.insert("h2".to_string(), 
-            "font-size: 20px; font-weight: bold; margin: 25px 0 15px 0; color: #2c3e50; border-left: 4px solid #3498db; padding-left: 15px;".to_string());
+        inline_styles.insert("h2".to_string(), format!(
+            "font-size: {h2_font_size}; font-weight: bold; margin: 25px 0 15px 0; color: {heading}; border-left: 4px solid {primary}; padding-left: 15px;"));
 
         inline_styles.insert(
             "h3".to_string(),
-            "font-size: 18px; font-weight: bold; margin: 20px 0 10px 0; color: #34495e;"
-                .to_string(),
+            format!("font-size: {h3_font_size}; font-weight: bold; margin: 20px 0 10px 0; color: #34495e;"),
         );
 
         inline_styles.insert(
             "h4".to_string(),
-            "font-size: 16px; font-weight: bold; margin: 15px 0 8px 0; color: #34495e;".to_string(),
+            format!(
+                "font-size: {font_size}; font-weight: bold; margin: 15px 0 8px 0; color: #34495e;"
+            ),
         );
 
         inline_styles.insert("blockquote".to_string(), 
@@ -53,8 +145,8 @@ impl WeChatStyleAdapter {
         inline_styles.insert("pre".to_string(), 
             "background-color: #f8f8f8; border: 1px solid #ddd; border-radius: 6px; padding: 15px; margin: 20px 0; overflow-x: auto; font-family: 'Consolas', 'Monaco', 'Courier New', monospace; font-size: 14px; line-height: 1.4;".to_string());
 
-        inline_styles.insert("code".to_string(), 
-            "background-color: #f1f2f3; padding: 2px 6px; border-radius: 3px; font-family: 'Consolas', 'Monaco', 'Courier New', monospace; font-size: 14px; color: #e96900;".to_string());
+        inline_styles.insert("code".to_string(), format!(
+            "background-color: #f1f2f3; padding: 2px 6px; border-radius: 3px; font-family: 'Consolas', 'Monaco', 'Courier New', monospace; font-size: 14px; color: {code};"));
 
         inline_styles.insert("img".to_string(), 
             "max-width: 100%; height: auto; display: block; margin: 20px auto; border-radius: 8px; box-shadow: 0 2px 8px rgba(0,0,0,0.1);".to_string());
@@ -89,12 +181,14 @@ impl WeChatStyleAdapter {
 
         inline_styles.insert(
             "a".to_string(),
-            "color: #3498db; text-decoration: none; border-bottom: 1px dotted #3498db;".to_string(),
+            format!(
+                "color: {primary}; text-decoration: none; border-bottom: 1px dotted {primary};"
+            ),
         );
 
         inline_styles.insert(
             "strong".to_string(),
-            "font-weight: bold; color: #2c3e50;".to_string(),
+            format!("font-weight: bold; color: {heading};"),
         );
 
         inline_styles.insert(
@@ -102,98 +196,56 @@ impl WeChatStyleAdapter {
             "font-style: italic; color: #7f8c8d;".to_string(),
         );
 
+        let mut selectors: Vec<&String> = inline_styles.keys().collect();
+        selectors.sort();
+        let css: String = selectors
+            .into_iter()
+            .map(|selector| format!("{} {{ {} }}\n", selector, inline_styles[selector]))
+            .collect();
+
         Self {
-            inline_styles,
+            stylesheet: Stylesheet::parse(&css).expect("内置主题是有效的 CSS"),
+            css,
+            primary_color: primary.clone(),
             max_content_length: 20000, // 微信公众号字数限制
-            allowed_tags: vec![
-                "p",
-                "h1",
-                "h2",
-                "h3",
-                "h4",
-                "h5",
-                "h6",
-                "br",
-                "hr",
-                "strong",
-                "b",
-                "em",
-                "i",
-                "u",
-                "s",
-                "del",
-                "ins",
-                "blockquote",
-                "pre",
-                "code",
-                "span",
-                "div",
-                "ul",
-                "ol",
-                "li",
-                "dl",
-                "dt",
-                "dd",
-                "table",
-                "thead",
-                "tbody",
-                "tr",
-                "th",
-                "td",
-                "img",
-                "a",
-                "section",
-                "article",
-                "aside",
-                "nav",
-            ],
+            max_paragraph_chars: None,
+            allowed_tags: DEFAULT_WECHAT_ALLOWED_TAGS
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
         }
     }
 
-    fn inline_all_styles(&self, html: &str) -> Result<String> {
-        let _document = Html::parse_document(html);
-        let mut result = html.to_string();
+    /// 用主题 CSS 文件（`wechat.theme` / `--theme`）代替内置的默认主题
+    pub fn with_theme_css(mut self, css: &str) -> Result<Self> {
+        self.stylesheet = Stylesheet::parse(css)?;
+        self.css = css.to_string();
+        Ok(self)
+    }
 
-        // 预编译正则表达式，避免在循环中重复创建
-        let style_regex = Regex::new(r#"style="([^"]*)""#)
-            .map_err(|e| Error::Html(format!("正则表达式创建失败: {e}")))?;
-
-        for (selector_str, style) in &self.inline_styles {
-            let _selector = Selector::parse(selector_str)
-                .map_err(|e| Error::Html(format!("CSS选择器解析失败: {e}")))?;
-
-            // 使用正则表达式来替换标签，添加内联样式
-            let tag_regex = Regex::new(&format!(r"<{selector_str}(\s[^>]*)?>"))
-                .map_err(|e| Error::Html(format!("正则表达式创建失败: {e}")))?;
-
-            result = tag_regex
-                .replace_all(&result, |caps: &regex::Captures| {
-                    let existing_attrs = caps.get(1).map_or("", |m| m.as_str());
-
-                    // 检查是否已有style属性
-                    if existing_attrs.contains("style=") {
-                        style_regex
-                            .replace(existing_attrs, |style_caps: &regex::Captures| {
-                                let existing_style = style_caps.get(1).map_or("", |m| m.as_str());
-                                format!(r#"style="{existing_style}; {style}""#)
-                            })
-                            .to_string()
-                    } else {
-                        format!("<{selector_str}{existing_attrs} style=\"{style}\">")
-                    }
-                })
-                .to_string();
-        }
+    /// 清理时保留的标签，不在列表中的标签去掉、保留其中的内容
+    pub fn with_allowed_tags(mut self, tags: Vec<String>) -> Self {
+        self.allowed_tags = tags;
+        self
+    }
 
-        Ok(result)
+    /// 拆分过长的段落，便于手机阅读
+    pub fn with_paragraph_split(mut self, max_chars: Option<usize>) -> Self {
+        self.max_paragraph_chars = max_chars;
+        self
+    }
+
+    fn inline_all_styles(&self, html: &str) -> Result<String> {
+        self.stylesheet.inline(html)
     }
 
-    fn convert_external_links(&self, html: &str) -> Result<String> {
+    fn convert_external_links(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
         let link_regex = Regex::new(r#"<a\s+[^>]*href="([^"]*)"[^>]*>([^<]*)</a>"#)
             .map_err(|e| Error::Html(format!("链接正则表达式失败: {}", e)))?;
 
         let mut footnotes = Vec::new();
-        let mut footnote_counter = 1;
+        // 规范化后的地址 -> 脚注编号，同一链接多次出现时复用编号
+        let mut numbers: HashMap<String, usize> = HashMap::new();
 
         let result = link_regex
             .replace_all(html, |caps: &regex::Captures| {
@@ -202,20 +254,23 @@ impl WeChatStyleAdapter {
 
                 if url.starts_with("http") {
                     // 外部链接转换为脚注
-                    footnotes.push(format!("[{}] {}", footnote_counter, url));
-                    let footnote_mark = format!("{}[{}]", text, footnote_counter);
-                    footnote_counter += 1;
-                    footnote_mark
+                    let number = *numbers.entry(canonical_url(url)).or_insert_with(|| {
+                        footnotes.push(format!("[{}] {}", footnotes.len() + 1, url));
+                        footnotes.len()
+                    });
+                    format!("{}[{}]", text, number)
                 } else {
                     // 保留内部链接
                     format!(
-                        r#"<span style="color: #3498db; text-decoration: underline;">{}</span>"#,
-                        text
+                        r#"<span style="color: {}; text-decoration: underline;">{}</span>"#,
+                        self.primary_color, text
                     )
                 }
             })
             .to_string();
 
+        report.converted_links.extend(footnotes.iter().cloned());
+
         // 添加脚注
         if !footnotes.is_empty() {
             let footnotes_section = format!(
@@ -241,7 +296,8 @@ impl WeChatStyleAdapter {
         // 确保图片响应式
         let img_regex = Regex::new(r#"<img([^>]*)>"#).unwrap();
         result = img_regex.replace_all(&result, |caps: &regex::Captures| {
-            let attrs = &caps[1];
+            // 自闭合的 `/` 要留在新属性之后，否则会变成无效属性
+            let attrs = caps[1].trim_end_matches('/').trim_end();
             if !attrs.contains("style=") {
                 format!(r#"<img{} style="max-width: 100%; height: auto; display: block; margin: 20px auto;">"#, attrs)
             } else {
@@ -253,29 +309,69 @@ impl WeChatStyleAdapter {
         let table_regex = Regex::new(r#"<table([^>]*)>"#).unwrap();
         result = table_regex.replace_all(&result, |caps: &regex::Captures| {
             let attrs = &caps[1];
-            format!(r#"<table{} style="width: 100%; border-collapse: collapse; margin: 20px 0; font-size: 14px; overflow-x: auto;">"#, attrs)
+            // 已内联样式的表格不再追加第二个 style 属性
+            if !attrs.contains("style=") {
+                format!(r#"<table{} style="width: 100%; border-collapse: collapse; margin: 20px 0; font-size: 14px; overflow-x: auto;">"#, attrs)
+            } else {
+                caps[0].to_string()
+            }
         }).to_string();
 
         Ok(result)
     }
 
-    fn sanitize_html(&self, html: &str) -> Result<String> {
+    fn sanitize_html(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
         let _document = Html::parse_document(html);
 
         // 移除不允许的标签和属性
         let mut result = html.to_string();
 
         // 移除script和style标签
-        let script_regex = Regex::new(r"<script[^>]*>[\s\S]*?</script>").unwrap();
-        result = script_regex.replace_all(&result, "").to_string();
+        for tag in ["script", "style"] {
+            let tag_regex = Regex::new(&format!(r"<{tag}[^>]*>[\s\S]*?</{tag}>")).unwrap();
+            for element in tag_regex.find_iter(&result) {
+                report.record_degraded(&result, element.range(), "移除");
+            }
+            result = tag_regex.replace_all(&result, "").to_string();
+        }
 
-        let style_regex = Regex::new(r"<style[^>]*>[\s\S]*?</style>").unwrap();
-        result = style_regex.replace_all(&result, "").to_string();
+        // 不在允许列表中的标签去掉，保留其中的内容；公式渲染阶段生成的 SVG 公式不受限制
+        let math = rendered_math_ranges(&result);
+        let in_math = |pos: usize| math.iter().any(|range| range.contains(&pos));
+        static ANY_TAG_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+        let any_tag_regex = ANY_TAG_REGEX
+            .get_or_init(|| Regex::new(r"</?([a-zA-Z][a-zA-Z0-9-]*)\b[^>]*>").unwrap());
+        let allowed = |tag: &str| {
+            self.allowed_tags
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(tag))
+        };
+        for caps in any_tag_regex.captures_iter(&result) {
+            let element = caps.get(0).unwrap();
+            if !allowed(&caps[1])
+                && !in_math(element.start())
+                && !element.as_str().starts_with("</")
+            {
+                report.record_degraded(&result, element.range(), "去掉标签，保留内容");
+            }
+        }
+        result = any_tag_regex
+            .replace_all(&result, |caps: &regex::Captures| {
+                if allowed(&caps[1]) || in_math(caps.get(0).unwrap().start()) {
+                    caps[0].to_string()
+                } else {
+                    String::new()
+                }
+            })
+            .to_string();
 
         // 移除危险属性
         let dangerous_attrs = ["onclick", "onload", "onerror", "javascript:"];
         for attr in dangerous_attrs {
             let attr_regex = Regex::new(&format!(r#"{}="[^"]*""#, attr)).unwrap();
+            for _ in attr_regex.find_iter(&result) {
+                report.stripped_elements.push(format!("{attr} 属性"));
+            }
             result = attr_regex.replace_all(&result, "").to_string();
         }
 
@@ -295,85 +391,122 @@ impl PlatformAdapter for WeChatStyleAdapter {
         Platform::WeChat
     }
 
-    fn adapt_html(&self, html: &str) -> Result<String> {
+    fn cache_key(&self) -> Option<String> {
+        let settings = format!(
+            "{}|{}|{}|{:?}|{:?}",
+            self.css,
+            self.primary_color,
+            self.max_content_length,
+            self.max_paragraph_chars,
+            self.allowed_tags
+        );
+        Some(format!(
+            "wechat-v{}-{}",
+            ADAPTER_VERSION,
+            content_hash(settings.as_bytes())
+        ))
+    }
+
+    fn adapt_html_with_report(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
+        self.adapt_html_with_steps(html, report, &mut |_, _| Ok(()))
+    }
+
+    fn adapt_html_with_steps(
+        &self,
+        html: &str,
+        report: &mut PlatformReport,
+        on_step: &mut dyn FnMut(&str, &str) -> Result<()>,
+    ) -> Result<String> {
         tracing::info!("开始适配微信公众号样式");
 
         // 1. 清理和消毒HTML
-        let sanitized = self.sanitize_html(html)?;
+        let sanitized = self.sanitize_html(html, report)?;
+        on_step("清理", &sanitized)?;
+
+        // 2. 音视频占位替换为封面图+提示
+        let with_media = render_media_placeholders(&sanitized, &Platform::WeChat, report);
+        on_step("音视频占位", &with_media)?;
+
+        // 3. 按需拆分过长段落
+        let split = match self.max_paragraph_chars {
+            Some(max_chars) => {
+                let (split, changes) = split_long_paragraphs(&with_media, max_chars);
+                report.readability_changes.extend(changes);
+                on_step("段落拆分", &split)?;
+                split
+            }
+            None => with_media,
+        };
 
-        // 2. 内联所有样式
-        let styled = self.inline_all_styles(&sanitized)?;
+        // 4. 内联所有样式
+        let styled = self.inline_all_styles(&split)?;
+        on_step("内联样式", &styled)?;
 
-        // 3. 转换外部链接为脚注
-        let with_footnotes = self.convert_external_links(&styled)?;
+        // 5. 转换外部链接为脚注
+        let with_footnotes = self.convert_external_links(&styled, report)?;
+        on_step("链接脚注", &with_footnotes)?;
 
-        // 4. 移动端优化
+        // 6. 移动端优化
         let optimized = self.optimize_for_mobile(&with_footnotes)?;
+        on_step("移动端优化", &optimized)?;
 
         tracing::info!("微信公众号样式适配完成");
         Ok(optimized)
     }
 
     fn validate_content(&self, content: &Content) -> Result<()> {
-        let mut errors = Vec::new();
+        self.validation_report(content).into_result("微信公众号")
+    }
+
+    fn validation_report(&self, content: &Content) -> ValidationReport {
+        let mut report = ValidationReport::default();
 
         // 检查内容长度
         if content.markdown.len() > self.max_content_length {
-            errors.push(ValidationError {
-                field: "content".to_string(),
-                message: format!(
+            report.push(
+                "content_length",
+                "content",
+                format!(
                     "内容长度超过限制（当前：{}，限制：{}）",
                     content.markdown.len(),
                     self.max_content_length
                 ),
-                severity: ValidationSeverity::Error,
-            });
+                ValidationSeverity::Error,
+            );
         }
 
         // 检查标题
         if content.title.is_empty() {
-            errors.push(ValidationError {
-                field: "title".to_string(),
-                message: "标题不能为空".to_string(),
-                severity: ValidationSeverity::Error,
-            });
+            report.push(
+                "title_empty",
+                "title",
+                "标题不能为空",
+                ValidationSeverity::Error,
+            );
         }
 
         if content.title.len() > 64 {
-            errors.push(ValidationError {
-                field: "title".to_string(),
-                message: "标题长度不能超过64个字符".to_string(),
-                severity: ValidationSeverity::Error,
-            });
+            report.push(
+                "title_length",
+                "title",
+                "标题长度不能超过64个字符",
+                ValidationSeverity::Error,
+            );
         }
 
         // 检查封面图片
         if let Some(ref cover) = content.metadata.cover_image {
             if !cover.starts_with("http") && !cover.starts_with("data:") {
-                errors.push(ValidationError {
-                    field: "cover_image".to_string(),
-                    message: "封面图片必须是有效的URL或base64数据".to_string(),
-                    severity: ValidationSeverity::Warning,
-                });
+                report.push(
+                    "cover_url",
+                    "cover_image",
+                    "封面图片必须是有效的URL或base64数据",
+                    ValidationSeverity::Warning,
+                );
             }
         }
 
-        if !errors.is_empty() {
-            let error_messages: Vec<String> = errors
-                .iter()
-                .filter(|e| matches!(e.severity, ValidationSeverity::Error))
-                .map(|e| format!("{}: {}", e.field, e.message))
-                .collect();
-
-            if !error_messages.is_empty() {
-                return Err(Error::Publishing(format!(
-                    "微信公众号内容验证失败: {}",
-                    error_messages.join("; ")
-                )));
-            }
-        }
-
-        Ok(())
+        report
     }
 
     async fn preprocess_images(&self, html: &str) -> Result<String> {
@@ -386,14 +519,7 @@ impl PlatformAdapter for WeChatStyleAdapter {
 
 impl StyleProvider for WeChatStyleAdapter {
     fn get_styles(&self) -> &str {
-        // 返回完整的CSS样式字符串
-        r#"
-        body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, 'Helvetica Neue', Arial, sans-serif; color: #333; line-height: 1.6; margin: 0; padding: 20px; }
-        p { font-size: 16px; line-height: 1.8; margin: 20px 0; color: #333; text-align: justify; }
-        h1 { font-size: 24px; font-weight: bold; text-align: center; margin: 30px 0 20px 0; color: #2c3e50; border-bottom: 2px solid #3498db; padding-bottom: 10px; }
-        h2 { font-size: 20px; font-weight: bold; margin: 25px 0 15px 0; color: #2c3e50; border-left: 4px solid #3498db; padding-left: 15px; }
-        /* ... 更多样式 ... */
-        "#
+        &self.css
     }
 
     fn apply_inline_styles(&self, html: &str) -> Result<String> {
@@ -410,7 +536,7 @@ mod tests {
         let adapter = WeChatStyleAdapter::new();
         assert_eq!(adapter.platform(), Platform::WeChat);
         assert_eq!(adapter.max_content_length, 20000);
-        assert!(!adapter.inline_styles.is_empty());
+        assert!(!adapter.stylesheet.is_empty());
     }
 
     #[test]
@@ -425,12 +551,52 @@ mod tests {
         assert!(result.contains("font-size: 16px"));
     }
 
+    #[test]
+    fn test_style_tokens() {
+        let adapter = WeChatStyleAdapter::from_style(&StyleConfig {
+            primary_color: "#ff6600".to_string(),
+            font_size: "15px".to_string(),
+            ..StyleConfig::default()
+        });
+        let result = adapter
+            .inline_all_styles(r#"<h2>标题</h2><p>正文 <a href="/x">链接</a></p>"#)
+            .unwrap();
+
+        assert!(result.contains("border-left: 4px solid #ff6600"));
+        assert!(result.contains("font-size: 15px"));
+        assert!(!result.contains("#3498db"));
+        assert!(adapter.get_styles().contains("h2 { font-size: 20px;"));
+    }
+
+    #[test]
+    fn test_theme_css_replaces_default() {
+        let adapter = WeChatStyleAdapter::new()
+            .with_theme_css("h2 { color: #07c160; }\nh2 span { font-weight: bold; }")
+            .unwrap();
+        let result = adapter
+            .inline_all_styles(r#"<h2><span>标题</span></h2><p>正文<br/></p>"#)
+            .unwrap();
+
+        assert_eq!(
+            result,
+            r#"<h2 style="color: #07c160;"><span style="font-weight: bold;">标题</span></h2><p>正文<br></p>"#
+        );
+        assert_eq!(
+            adapter.get_styles(),
+            "h2 { color: #07c160; }\nh2 span { font-weight: bold; }"
+        );
+        assert_ne!(adapter.cache_key(), WeChatStyleAdapter::new().cache_key());
+        assert!(WeChatStyleAdapter::new().with_theme_css("h2 {").is_err());
+    }
+
     #[test]
     fn test_external_links_conversion() {
         let adapter = WeChatStyleAdapter::new();
         let html = r#"<p>Visit <a href="https://example.com">Example</a> and <a href="/internal">Internal</a>.</p>"#;
 
-        let result = adapter.convert_external_links(html).unwrap();
+        let result = adapter
+            .convert_external_links(html, &mut PlatformReport::new(Platform::WeChat))
+            .unwrap();
 
         assert!(result.contains("Example[1]"));
         assert!(result.contains("参考链接"));
@@ -438,6 +604,22 @@ mod tests {
         assert!(result.contains("Internal")); // Internal link preserved
     }
 
+    #[test]
+    fn test_duplicate_links_share_footnote() {
+        let adapter = WeChatStyleAdapter::new();
+        let html = r#"<p><a href="https://example.com/a?utm_source=x">A</a> <a href="https://example.com/a/">B</a> <a href="https://example.com/b">C</a></p>"#;
+
+        let result = adapter
+            .convert_external_links(html, &mut PlatformReport::new(Platform::WeChat))
+            .unwrap();
+
+        assert!(result.contains("A[1]"));
+        assert!(result.contains("B[1]"));
+        assert!(result.contains("C[2]"));
+        assert!(result.contains("[1] https://example.com/a?utm_source=x"));
+        assert!(!result.contains("[3]"));
+    }
+
     #[test]
     fn test_mobile_optimization() {
         let adapter = WeChatStyleAdapter::new();
@@ -454,7 +636,9 @@ mod tests {
         let adapter = WeChatStyleAdapter::new();
         let html = r#"<script>alert('test')</script><p onclick="alert('click')">Content</p><style>body{color:red}</style>"#;
 
-        let result = adapter.sanitize_html(html).unwrap();
+        let result = adapter
+            .sanitize_html(html, &mut PlatformReport::new(Platform::WeChat))
+            .unwrap();
 
         assert!(!result.contains("<script>"));
         assert!(!result.contains("<style>"));
@@ -462,6 +646,42 @@ mod tests {
         assert!(result.contains("Content"));
     }
 
+    #[test]
+    fn test_allowed_tags_enforced() {
+        let html = r#"<p>前文<svg viewBox="0 0 10 10"><text>图</text></svg><sup>1</sup></p>"#;
+
+        let mut report = PlatformReport::new(Platform::WeChat);
+        let result = WeChatStyleAdapter::new()
+            .sanitize_html(html, &mut report)
+            .unwrap();
+        assert_eq!(result, "<p>前文图<sup>1</sup></p>");
+        let elements: Vec<&str> = report
+            .degraded_elements
+            .iter()
+            .map(|d| d.element.as_str())
+            .collect();
+        assert_eq!(elements, vec![r#"<svg viewBox="0 0 10 10">"#, "<text>"]);
+
+        let mut report = PlatformReport::new(Platform::WeChat);
+        let result = WeChatStyleAdapter::new()
+            .with_allowed_tags(vec!["p".to_string(), "svg".to_string(), "text".to_string()])
+            .sanitize_html(html, &mut report)
+            .unwrap();
+        assert_eq!(
+            result,
+            r#"<p>前文<svg viewBox="0 0 10 10"><text>图</text></svg>1</p>"#
+        );
+
+        // 公式渲染阶段生成的 SVG 公式不受允许列表限制
+        let math = r#"<p><span class="math-inline" data-tex="x"><svg viewBox="0 0 1 1"><g><path d="M0 0"></path></g></svg></span></p>"#;
+        let mut report = PlatformReport::new(Platform::WeChat);
+        let result = WeChatStyleAdapter::new()
+            .sanitize_html(math, &mut report)
+            .unwrap();
+        assert_eq!(result, math);
+        assert!(report.degraded_elements.is_empty());
+    }
+
     #[test]
     fn test_content_validation() {
         let adapter = WeChatStyleAdapter::new();
@@ -491,4 +711,21 @@ mod tests {
         assert!(result.contains("参考链接"));
         assert!(!result.contains("<script>"));
     }
+
+    #[test]
+    fn test_long_paragraph_split_reported() {
+        let html = "<p>第一句话。第二句话。第三句话。</p>";
+
+        let mut report = PlatformReport::new(Platform::WeChat);
+        let result = WeChatStyleAdapter::new()
+            .with_paragraph_split(Some(10))
+            .adapt_html_with_report(html, &mut report)
+            .unwrap();
+        assert_eq!(result.matches("<p ").count(), 2);
+        assert_eq!(report.readability_changes.len(), 1);
+
+        // 默认不拆分
+        let result = WeChatStyleAdapter::new().adapt_html(html).unwrap();
+        assert_eq!(result.matches("<p ").count(), 1);
+    }
 }
@@ -1,17 +1,86 @@
 use crate::{
-    adapters::traits::{PlatformAdapter, StyleProvider, ValidationError, ValidationSeverity},
-    core::content::{Content, Platform},
+    adapters::traits::{PlatformAdapter, StyleProvider, ValidationReport, ValidationSeverity},
+    core::{
+        content::{Content, Platform},
+        media::render_media_placeholders,
+        report::PlatformReport,
+        store::content_hash,
+    },
     error::Error,
     Result,
 };
 use async_trait::async_trait;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use regex::Regex;
 
+/// 适配逻辑变化导致输出不同时递增，使旧的缓存结果失效
+const ADAPTER_VERSION: u32 = 3;
+
+/// 知乎公式图片服务，`tex` 参数为 URL 编码后的公式
+pub const DEFAULT_MATH_IMAGE_SERVICE: &str = "https://www.zhihu.com/equation?tex=";
+
+/// 知乎编辑器不接受的标签，连同内容一起移除
+pub const DEFAULT_ZHIHU_FORBIDDEN_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "form", "input", "button", "meta", "link",
+];
+
+/// 知乎编辑器不接受的 LaTeX 环境，使用这些环境的公式转为图片
+const UNSUPPORTED_MATH_ENVIRONMENTS: &[&str] = &[
+    "align",
+    "align*",
+    "alignat",
+    "alignat*",
+    "eqnarray",
+    "eqnarray*",
+    "gather",
+    "gather*",
+    "multline",
+    "multline*",
+    "tikzpicture",
+    "tikzcd",
+    "xy",
+];
+
+/// 知乎编辑器不接受的 LaTeX 命令
+const UNSUPPORTED_MATH_COMMANDS: &[&str] = &[
+    "\\label",
+    "\\tag",
+    "\\newcommand",
+    "\\renewcommand",
+    "\\def",
+    "\\require",
+    "\\href",
+];
+
+/// 公式中第一个知乎不支持的结构，全部支持时返回 `None`
+pub fn unsupported_math_construct(formula: &str) -> Option<String> {
+    static ENVIRONMENT_REGEX: std::sync::OnceLock<Regex> = std::sync::OnceLock::new();
+    let environment_regex =
+        ENVIRONMENT_REGEX.get_or_init(|| Regex::new(r"\\begin\{([A-Za-z*]+)\}").unwrap());
+    let environment = environment_regex
+        .captures_iter(formula)
+        .map(|caps| caps[1].to_string())
+        .find(|env| UNSUPPORTED_MATH_ENVIRONMENTS.contains(&env.as_str()))
+        .map(|env| format!("\\begin{{{}}}", env));
+
+    environment.or_else(|| {
+        UNSUPPORTED_MATH_COMMANDS
+            .iter()
+            .find(|command| {
+                formula.match_indices(*command).any(|(i, _)| {
+                    !formula[i + command.len()..].starts_with(|c: char| c.is_ascii_alphabetic())
+                })
+            })
+            .map(|command| command.to_string())
+    })
+}
+
 pub struct ZhihuStyleAdapter {
     math_enabled: bool,
     code_highlight_theme: String,
     max_content_length: usize,
-    forbidden_tags: Vec<&'static str>,
+    forbidden_tags: Vec<String>,
+    math_image_service: String,
 }
 
 impl ZhihuStyleAdapter {
@@ -20,10 +89,11 @@ impl ZhihuStyleAdapter {
             math_enabled: true,
             code_highlight_theme: "github".to_string(),
             max_content_length: 30000, // 知乎字数限制相对宽松
-            forbidden_tags: vec![
-                "script", "style", "iframe", "object", "embed", "form", "input", "button", "meta",
-                "link",
-            ],
+            forbidden_tags: DEFAULT_ZHIHU_FORBIDDEN_TAGS
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            math_image_service: DEFAULT_MATH_IMAGE_SERVICE.to_string(),
         }
     }
 
@@ -37,7 +107,19 @@ impl ZhihuStyleAdapter {
         self
     }
 
-    fn render_math_expressions(&self, html: &str) -> Result<String> {
+    /// 清理时连同内容一起移除的标签
+    pub fn with_forbidden_tags(mut self, tags: Vec<String>) -> Self {
+        self.forbidden_tags = tags;
+        self
+    }
+
+    /// 不支持的公式回退为图片时使用的渲染服务，公式 URL 编码后拼接在其后
+    pub fn with_math_image_service(mut self, service: String) -> Self {
+        self.math_image_service = service;
+        self
+    }
+
+    fn render_math_expressions(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
         if !self.math_enabled {
             return Ok(html.to_string());
         }
@@ -52,7 +134,10 @@ impl ZhihuStyleAdapter {
         let mut result = inline_math_regex
             .replace_all(html, |caps: &regex::Captures| {
                 let formula = &caps[1];
-                self.render_katex_inline(formula)
+                match unsupported_math_construct(formula) {
+                    Some(construct) => self.render_math_image(formula, false, &construct, report),
+                    None => self.render_katex_inline(formula),
+                }
             })
             .to_string();
 
@@ -63,8 +148,11 @@ impl ZhihuStyleAdapter {
 
         result = block_math_regex
             .replace_all(&result, |caps: &regex::Captures| {
-                let formula = &caps[1].trim();
-                self.render_katex_block(formula)
+                let formula = caps[1].trim();
+                match unsupported_math_construct(formula) {
+                    Some(construct) => self.render_math_image(formula, true, &construct, report),
+                    None => self.render_katex_block(formula),
+                }
             })
             .to_string();
 
@@ -81,6 +169,35 @@ impl ZhihuStyleAdapter {
         )
     }
 
+    /// 知乎不支持的公式渲染为图片，并在处理报告中记录
+    fn render_math_image(
+        &self,
+        formula: &str,
+        display: bool,
+        construct: &str,
+        report: &mut PlatformReport,
+    ) -> String {
+        report.warnings.push(format!(
+            "公式使用了知乎不支持的 {}，已转为图片: {}",
+            construct,
+            formula.chars().take(40).collect::<String>()
+        ));
+        // 公式取自 HTML 正文，拼接 URL 前先还原其中的实体（如 `&amp;`）
+        let tex = html_escape::decode_html_entities(formula);
+        let img = format!(
+            r#"<img class="ztext-math-image" src="{}{}" alt="{}" data-tex="{}">"#,
+            self.math_image_service,
+            utf8_percent_encode(&tex, NON_ALPHANUMERIC),
+            html_escape::encode_double_quoted_attribute(&tex),
+            html_escape::encode_double_quoted_attribute(&tex)
+        );
+        if display {
+            format!(r#"<div style="text-align: center;">{}</div>"#, img)
+        } else {
+            img
+        }
+    }
+
     fn render_katex_block(&self, formula: &str) -> String {
         // 块级数学公式渲染
         format!(
@@ -125,18 +242,25 @@ impl ZhihuStyleAdapter {
         Ok(result)
     }
 
-    fn sanitize_html(&self, html: &str) -> Result<String> {
+    fn sanitize_html(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
         let mut result = html.to_string();
 
         // 移除禁用的标签
         for tag in &self.forbidden_tags {
-            let tag_regex = Regex::new(&format!(r"<{}[^>]*>[\s\S]*?</{}>", tag, tag))
+            let tag = regex::escape(tag);
+            let tag_regex = Regex::new(&format!(r"<{}\b[^>]*>[\s\S]*?</{}>", tag, tag))
                 .map_err(|e| Error::Html(format!("清理标签正则表达式失败: {}", e)))?;
+            for element in tag_regex.find_iter(&result) {
+                report.record_degraded(&result, element.range(), "移除");
+            }
             result = tag_regex.replace_all(&result, "").to_string();
 
-            // 也移除自闭合标签
-            let self_closing_regex = Regex::new(&format!(r"<{}\s*[^>]*/>", tag))
+            // 也移除自闭合标签和没有结束标签的空元素（如 `<input>`）
+            let self_closing_regex = Regex::new(&format!(r"<{}\b[^>]*>", tag))
                 .map_err(|e| Error::Html(format!("清理自闭合标签正则表达式失败: {}", e)))?;
+            for element in self_closing_regex.find_iter(&result) {
+                report.record_degraded(&result, element.range(), "移除");
+            }
             result = self_closing_regex.replace_all(&result, "").to_string();
         }
 
@@ -145,6 +269,9 @@ impl ZhihuStyleAdapter {
         for attr in dangerous_attrs {
             let attr_regex = Regex::new(&format!(r#"{}="[^"]*""#, attr))
                 .map_err(|e| Error::Html(format!("清理属性正则表达式失败: {}", e)))?;
+            for _ in attr_regex.find_iter(&result) {
+                report.stripped_elements.push(format!("{} 属性", attr));
+            }
             result = attr_regex.replace_all(&result, "").to_string();
         }
 
@@ -160,17 +287,17 @@ impl ZhihuStyleAdapter {
 
         let result = img_regex
             .replace_all(html, |caps: &regex::Captures| {
-                let attrs = &caps[1];
+                // 自闭合的 `/` 要留在新属性之后，否则会变成无效属性
+                let attrs = caps[1].trim_end_matches('/').trim_end();
 
                 // 检查是否已有class属性
                 if attrs.contains("class=") {
                     let class_regex = Regex::new(r#"class="([^"]*)""#).unwrap();
-                    class_regex
-                        .replace(attrs, |class_caps: &regex::Captures| {
-                            let existing_classes = class_caps.get(1).map_or("", |m| m.as_str());
-                            format!(r#"class="{} ztext-image""#, existing_classes)
-                        })
-                        .to_string()
+                    let attrs = class_regex.replace(attrs, |class_caps: &regex::Captures| {
+                        let existing_classes = class_caps.get(1).map_or("", |m| m.as_str());
+                        format!(r#"class="{} ztext-image""#, existing_classes)
+                    });
+                    format!("<img{}>", attrs)
                 } else {
                     format!(r#"<img{} class="ztext-image">"#, attrs)
                 }
@@ -258,101 +385,130 @@ impl PlatformAdapter for ZhihuStyleAdapter {
         Platform::Zhihu
     }
 
-    fn adapt_html(&self, html: &str) -> Result<String> {
+    fn cache_key(&self) -> Option<String> {
+        let settings = format!(
+            "{}|{}|{}|{}|{:?}",
+            self.math_enabled,
+            self.code_highlight_theme,
+            self.max_content_length,
+            self.math_image_service,
+            self.forbidden_tags
+        );
+        Some(format!(
+            "zhihu-v{}-{}",
+            ADAPTER_VERSION,
+            content_hash(settings.as_bytes())
+        ))
+    }
+
+    fn adapt_html_with_report(&self, html: &str, report: &mut PlatformReport) -> Result<String> {
+        self.adapt_html_with_steps(html, report, &mut |_, _| Ok(()))
+    }
+
+    fn adapt_html_with_steps(
+        &self,
+        html: &str,
+        report: &mut PlatformReport,
+        on_step: &mut dyn FnMut(&str, &str) -> Result<()>,
+    ) -> Result<String> {
         tracing::info!("开始适配知乎样式");
 
         // 1. 清理和消毒HTML
-        let sanitized = self.sanitize_html(html)?;
+        let sanitized = self.sanitize_html(html, report)?;
+        on_step("清理", &sanitized)?;
 
-        // 2. 渲染数学公式
-        let with_math = self.render_math_expressions(&sanitized)?;
+        // 2. 音视频占位替换为链接卡片
+        let with_media = render_media_placeholders(&sanitized, &Platform::Zhihu, report);
+        on_step("音视频占位", &with_media)?;
 
-        // 3. 增强代码块
+        // 3. 渲染数学公式
+        let with_math = self.render_math_expressions(&with_media, report)?;
+        on_step("数学公式", &with_math)?;
+
+        // 4. 增强代码块
         let enhanced_code = self.enhance_code_blocks(&with_math)?;
+        on_step("代码块", &enhanced_code)?;
 
-        // 4. 优化图片
+        // 5. 优化图片
         let optimized_images = self.optimize_images(&enhanced_code)?;
+        on_step("图片", &optimized_images)?;
 
-        // 5. 增强表格
+        // 6. 增强表格
         let enhanced_tables = self.enhance_tables(&optimized_images)?;
+        on_step("表格", &enhanced_tables)?;
 
-        // 6. 处理列表
+        // 7. 处理列表
         let processed_lists = self.process_lists(&enhanced_tables)?;
+        on_step("列表", &processed_lists)?;
 
         tracing::info!("知乎样式适配完成");
         Ok(processed_lists)
     }
 
     fn validate_content(&self, content: &Content) -> Result<()> {
-        let mut errors = Vec::new();
+        self.validation_report(content).into_result("知乎")
+    }
+
+    fn validation_report(&self, content: &Content) -> ValidationReport {
+        let mut report = ValidationReport::default();
 
         // 检查内容长度
         if content.markdown.len() > self.max_content_length {
-            errors.push(ValidationError {
-                field: "content".to_string(),
-                message: format!(
+            report.push(
+                "content_length",
+                "content",
+                format!(
                     "内容长度超过限制（当前：{}，限制：{}）",
                     content.markdown.len(),
                     self.max_content_length
                 ),
-                severity: ValidationSeverity::Error,
-            });
+                ValidationSeverity::Error,
+            );
         }
 
         // 检查标题
         if content.title.is_empty() {
-            errors.push(ValidationError {
-                field: "title".to_string(),
-                message: "标题不能为空".to_string(),
-                severity: ValidationSeverity::Error,
-            });
+            report.push(
+                "title_empty",
+                "title",
+                "标题不能为空",
+                ValidationSeverity::Error,
+            );
         }
 
         if content.title.len() > 100 {
-            errors.push(ValidationError {
-                field: "title".to_string(),
-                message: "标题长度不能超过100个字符".to_string(),
-                severity: ValidationSeverity::Warning,
-            });
+            report.push(
+                "title_length",
+                "title",
+                "标题长度不能超过100个字符",
+                ValidationSeverity::Warning,
+            );
         }
 
         // 检查标签数量
         if content.metadata.tags.len() > 5 {
-            errors.push(ValidationError {
-                field: "tags".to_string(),
-                message: "标签数量不能超过5个".to_string(),
-                severity: ValidationSeverity::Warning,
-            });
+            report.push(
+                "tags_count",
+                "tags",
+                "标签数量不能超过5个",
+                ValidationSeverity::Warning,
+            );
         }
 
         // 检查是否包含禁用内容
         let forbidden_keywords = ["广告", "推广", "联系方式"];
         for keyword in forbidden_keywords {
             if content.markdown.contains(keyword) {
-                errors.push(ValidationError {
-                    field: "content".to_string(),
-                    message: format!("内容包含可能被禁止的关键词: {}", keyword),
-                    severity: ValidationSeverity::Warning,
-                });
-            }
-        }
-
-        if !errors.is_empty() {
-            let error_messages: Vec<String> = errors
-                .iter()
-                .filter(|e| matches!(e.severity, ValidationSeverity::Error))
-                .map(|e| format!("{}: {}", e.field, e.message))
-                .collect();
-
-            if !error_messages.is_empty() {
-                return Err(Error::Publishing(format!(
-                    "知乎内容验证失败: {}",
-                    error_messages.join("; ")
-                )));
+                report.push(
+                    "forbidden_keyword",
+                    "content",
+                    format!("内容包含可能被禁止的关键词: {}", keyword),
+                    ValidationSeverity::Warning,
+                );
             }
         }
 
-        Ok(())
+        report
     }
 
     async fn preprocess_images(&self, html: &str) -> Result<String> {
@@ -396,3 +552,33 @@ impl StyleProvider for ZhihuStyleAdapter {
         Ok(html.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsupported_math_falls_back_to_image() {
+        let adapter = ZhihuStyleAdapter::new();
+        let html = "<p>$E = mc^2$</p>\n<p>$$\n\\begin{align}a &amp;= b \\\\ c &amp;= d\\end{align}\n$$</p>";
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        let adapted = adapter.adapt_html_with_report(html, &mut report).unwrap();
+
+        assert!(adapted.contains(r#"data-tex="E = mc^2" data-mode="inline""#));
+        assert!(adapted
+            .contains(r#"src="https://www.zhihu.com/equation?tex=%5Cbegin%7Balign%7Da%20%26%3D"#));
+        assert!(adapted.contains(r#"data-tex="\begin{align}a &amp;= b"#));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("\\begin{align}"));
+
+        assert_eq!(
+            unsupported_math_construct(r"x \tag{1}").as_deref(),
+            Some(r"\tag")
+        );
+        assert_eq!(
+            unsupported_math_construct(r"\begin{aligned}x\end{aligned}"),
+            None
+        );
+        assert_eq!(unsupported_math_construct(r"\tagged"), None);
+    }
+}
@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::RwLock,
+};
+
+/// 命令行 `--config` 指定的配置文件，设置后代替 `~/.markflow/config.toml`
+static CONFIG_PATH_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct AppConfig {
@@ -8,50 +15,1226 @@ pub struct AppConfig {
     pub zhihu: ZhihuConfig,
     pub templates: TemplateConfig,
     pub output: OutputConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub assets: AssetsConfig,
+    #[serde(default)]
+    pub lint: LintConfig,
+    #[serde(default)]
+    pub snapshot: SnapshotConfig,
+    #[serde(default)]
+    pub links: LinksConfig,
+    #[serde(default)]
+    pub alt_text: AltTextConfig,
+    #[serde(default)]
+    pub images: ImagesConfig,
+    #[serde(default)]
+    pub math: MathConfig,
+    #[serde(default)]
+    pub cross_link: CrossLinkConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub style: StyleConfig,
+    #[serde(default)]
+    pub update: UpdateConfig,
+    #[serde(default)]
+    pub plugins: PluginsConfig,
+    #[serde(default)]
+    pub browser: BrowserConfig,
+    #[serde(default)]
+    pub workflow: WorkflowConfig,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub annotations: AnnotationsConfig,
+    #[serde(default)]
+    pub security: SecurityConfig,
+    #[serde(default)]
+    pub accessibility: AccessibilityConfig,
+    #[serde(default)]
+    pub publish: PublishConfig,
+    #[serde(default)]
+    pub format: FormatConfig,
+    /// front matter 字段到平台字段的映射：平台名 → (front matter 字段 → 平台字段)
+    #[serde(default)]
+    pub field_mapping: BTreeMap<String, BTreeMap<String, String>>,
+    /// 验证规则的级别：平台名（或 `all`）→ (规则名 → error/warning/info/off)
+    #[serde(default)]
+    pub validation: BTreeMap<String, BTreeMap<String, String>>,
+    /// 配置文件所在目录，加载时设置，用于查找主题文件
+    #[serde(skip)]
+    pub config_dir: Option<PathBuf>,
+}
+
+/// 主题变量：同时用于微信内联样式和模板（模板中为 `style.primary_color` 等）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StyleConfig {
+    /// 主题名：使用配置目录下 `themes/<name>.toml` 中的变量覆盖以下各项
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+    /// 主色：标题边框、链接等
+    #[serde(default = "default_primary_color")]
+    pub primary_color: String,
+    #[serde(default = "default_text_color")]
+    pub text_color: String,
+    #[serde(default = "default_heading_color")]
+    pub heading_color: String,
+    /// 行内代码文字颜色
+    #[serde(default = "default_code_color")]
+    pub code_color: String,
+    /// 正文字号
+    #[serde(default = "default_font_size")]
+    pub font_size: String,
+    #[serde(default = "default_h1_font_size")]
+    pub h1_font_size: String,
+    #[serde(default = "default_h2_font_size")]
+    pub h2_font_size: String,
+    #[serde(default = "default_h3_font_size")]
+    pub h3_font_size: String,
+    /// 页面背景色，无障碍检查以此计算文字对比度
+    #[serde(default = "default_background_color")]
+    pub background_color: String,
+}
+
+fn default_primary_color() -> String {
+    "#3498db".to_string()
+}
+
+fn default_text_color() -> String {
+    "#333".to_string()
+}
+
+fn default_heading_color() -> String {
+    "#2c3e50".to_string()
+}
+
+fn default_code_color() -> String {
+    "#e96900".to_string()
+}
+
+fn default_font_size() -> String {
+    "16px".to_string()
+}
+
+fn default_h1_font_size() -> String {
+    "24px".to_string()
+}
+
+fn default_h2_font_size() -> String {
+    "20px".to_string()
+}
+
+fn default_h3_font_size() -> String {
+    "18px".to_string()
+}
+
+fn default_background_color() -> String {
+    "#ffffff".to_string()
+}
+
+impl Default for StyleConfig {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            primary_color: default_primary_color(),
+            text_color: default_text_color(),
+            heading_color: default_heading_color(),
+            code_color: default_code_color(),
+            font_size: default_font_size(),
+            h1_font_size: default_h1_font_size(),
+            h2_font_size: default_h2_font_size(),
+            h3_font_size: default_h3_font_size(),
+            background_color: default_background_color(),
+        }
+    }
+}
+
+/// 注释、批注和未完成标记
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnotationsConfig {
+    /// 从输出中移除 HTML 注释和编辑器批注
+    #[serde(default = "default_true")]
+    pub strip: bool,
+    /// 正文残留 TODO/FIXME 或修订痕迹时："off"、"warn"（写入报告）或 "error"（中止处理）
+    #[serde(default = "default_unfinished_policy")]
+    pub unfinished: String,
+}
+
+fn default_unfinished_policy() -> String {
+    "warn".to_string()
+}
+
+impl Default for AnnotationsConfig {
+    fn default() -> Self {
+        Self {
+            strip: true,
+            unfinished: default_unfinished_policy(),
+        }
+    }
+}
+
+/// 发布后的处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishConfig {
+    /// 发布成功后把文章地址和发布时间写回源文件的 front matter（`published.<平台>.url`、`published.<平台>.date`）
+    #[serde(default)]
+    pub write_back: bool,
+    /// `watch --auto-draft` 为 front matter 含 `auto_draft: true` 的文章创建草稿的平台
+    #[serde(default = "default_auto_draft_platforms")]
+    pub auto_draft_platforms: Vec<String>,
+    /// `watch --auto-draft` 每小时最多为多少篇文章创建草稿
+    #[serde(default = "default_auto_draft_max_per_hour")]
+    pub auto_draft_max_per_hour: usize,
+}
+
+fn default_auto_draft_platforms() -> Vec<String> {
+    vec!["wechat".to_string(), "zhihu".to_string()]
+}
+
+fn default_auto_draft_max_per_hour() -> usize {
+    5
+}
+
+impl Default for PublishConfig {
+    fn default() -> Self {
+        Self {
+            write_back: false,
+            auto_draft_platforms: default_auto_draft_platforms(),
+            auto_draft_max_per_hour: default_auto_draft_max_per_hour(),
+        }
+    }
+}
+
+/// `markflow fmt` 格式化源文件的方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormatConfig {
+    /// 段落折行宽度，0 表示保留原有换行。中文段落只在空格处断开
+    #[serde(default)]
+    pub line_width: usize,
+    /// 无序列表标记："-"、"*" 或 "+"
+    #[serde(default = "default_list_marker")]
+    pub list_marker: String,
+}
+
+fn default_list_marker() -> String {
+    "-".to_string()
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        Self {
+            line_width: 0,
+            list_marker: default_list_marker(),
+        }
+    }
+}
+
+/// 发布前的敏感信息检查
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityConfig {
+    /// 正文或代码块中疑似含有密钥、令牌、邮箱、手机号时："off"、"warn"（写入报告）或 "error"（中止处理）
+    #[serde(default = "default_secrets_policy")]
+    pub secrets: String,
+    /// 允许出现的内容（正则），如示例邮箱 `@example\.com$`
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+fn default_secrets_policy() -> String {
+    "warn".to_string()
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            secrets: default_secrets_policy(),
+            allow: Vec::new(),
+        }
+    }
+}
+
+/// 输出 HTML 的无障碍检查，结果按平台写入处理报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessibilityConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// 文字与背景的最低对比度（WCAG AA 正文为 4.5）
+    #[serde(default = "default_min_contrast")]
+    pub min_contrast: f64,
+}
+
+fn default_min_contrast() -> f64 {
+    4.5
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_contrast: default_min_contrast(),
+        }
+    }
+}
+
+/// 文章审核流程
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkflowConfig {
+    /// 只允许发布已批准（approved）的文章
+    #[serde(default)]
+    pub require_approval: bool,
+}
+
+/// 通知
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    #[serde(default)]
+    pub email: EmailNotificationConfig,
+}
+
+/// 通过 SMTP 发送处理和发布活动的摘要邮件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailNotificationConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 摘要频率："daily" 或 "weekly"
+    #[serde(default = "default_digest_frequency")]
+    pub frequency: String,
+    #[serde(default)]
+    pub smtp_host: Option<String>,
+    #[serde(default = "default_smtp_port")]
+    pub smtp_port: u16,
+    /// 连接加密方式："starttls"、"tls" 或 "none"
+    #[serde(default = "default_smtp_tls")]
+    pub tls: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// 保存 SMTP 密码的环境变量名
+    #[serde(default = "default_smtp_password_env")]
+    pub password_env: String,
+    #[serde(default)]
+    pub from: Option<String>,
+    #[serde(default)]
+    pub to: Vec<String>,
+}
+
+fn default_digest_frequency() -> String {
+    "daily".to_string()
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+fn default_smtp_tls() -> String {
+    "starttls".to_string()
+}
+
+fn default_smtp_password_env() -> String {
+    "MARKFLOW_SMTP_PASSWORD".to_string()
+}
+
+impl Default for EmailNotificationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            frequency: default_digest_frequency(),
+            smtp_host: None,
+            smtp_port: default_smtp_port(),
+            tls: default_smtp_tls(),
+            username: None,
+            password_env: default_smtp_password_env(),
+            from: None,
+            to: Vec::new(),
+        }
+    }
+}
+
+/// 版本更新检查（默认关闭）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateConfig {
+    /// 启动时检查 GitHub 上是否有新版本
+    #[serde(default)]
+    pub check: bool,
+    /// 两次检查的最小间隔（小时）
+    #[serde(default = "default_update_interval_hours")]
+    pub interval_hours: u64,
+    /// 发布版本的 GitHub 仓库
+    #[serde(default = "default_update_repository")]
+    pub repository: String,
+    /// 校验发布包签名的 minisign 公钥，未配置时 self-update 拒绝安装
+    #[serde(default)]
+    pub public_key: Option<String>,
+}
+
+fn default_update_interval_hours() -> u64 {
+    24
+}
+
+fn default_update_repository() -> String {
+    "foxzool/markflow".to_string()
+}
+
+impl Default for UpdateConfig {
+    fn default() -> Self {
+        Self {
+            check: false,
+            interval_hours: default_update_interval_hours(),
+            repository: default_update_repository(),
+            public_key: None,
+        }
+    }
+}
+
+/// 无头浏览器：截图和浏览器自动化共用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrowserConfig {
+    /// Chrome/Chromium 可执行文件，未配置时依次查找 CHROME_PATH、已下载的浏览器和 chromedriver 的默认位置
+    #[serde(default)]
+    pub executable: Option<PathBuf>,
+    /// chromedriver 可执行文件，未配置时查找已下载的版本和 PATH
+    #[serde(default)]
+    pub driver: Option<PathBuf>,
+    /// 已运行的 WebDriver 服务地址；配置后不再启动本地 chromedriver
+    #[serde(default)]
+    pub webdriver_url: Option<String>,
+    /// 找不到 chromedriver 时自动下载 Chrome for Testing
+    #[serde(default)]
+    pub auto_download: bool,
+    /// 下载的浏览器存放目录
+    #[serde(default = "default_browser_cache_dir")]
+    pub cache_dir: PathBuf,
+    /// 关闭 Chrome 沙箱；未配置时在容器中或以 root 运行时自动关闭
+    #[serde(default)]
+    pub no_sandbox: Option<bool>,
+    /// 额外的浏览器启动参数
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// 保留以供复用的空闲浏览器会话数
+    #[serde(default = "default_browser_pool_size")]
+    pub pool_size: usize,
+    /// 模拟的设备（视口和 User-Agent）：内置 iphone、wechat、android、desktop，或 `devices` 中自定义的设备
+    #[serde(default)]
+    pub device: Option<String>,
+    /// 自定义设备，名称 -> 设备
+    #[serde(default)]
+    pub devices: BTreeMap<String, crate::core::DeviceProfile>,
+}
+
+fn default_browser_cache_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".markflow").join("browser")
+}
+
+fn default_browser_pool_size() -> usize {
+    2
+}
+
+impl Default for BrowserConfig {
+    fn default() -> Self {
+        Self {
+            executable: None,
+            driver: None,
+            webdriver_url: None,
+            auto_download: false,
+            cache_dir: default_browser_cache_dir(),
+            no_sandbox: None,
+            args: Vec::new(),
+            pool_size: default_browser_pool_size(),
+            device: None,
+            devices: BTreeMap::new(),
+        }
+    }
+}
+
+/// 外部插件阶段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginsConfig {
+    /// 插件安装目录，每个插件一个子目录
+    #[serde(default = "default_plugins_dir")]
+    pub dir: PathBuf,
+    /// 已启用的插件，按顺序执行
+    #[serde(default)]
+    pub enabled: Vec<String>,
+}
+
+fn default_plugins_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".markflow").join("plugins")
+}
+
+impl Default for PluginsConfig {
+    fn default() -> Self {
+        Self {
+            dir: default_plugins_dir(),
+            enabled: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkConfig {
+    /// 离线模式：跳过链接检查、图片下载、图片描述生成，发布直接报错
+    #[serde(default)]
+    pub offline: bool,
+    /// 所有 HTTP 请求使用的代理，支持 http://、https://、socks5://
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// 不走代理的主机（如 localhost、.internal.example.com）
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// 额外信任的根证书（PEM，可包含多张），用于自签名的镜像或公司网关
+    #[serde(default)]
+    pub ca_certs: Vec<PathBuf>,
+    /// 建立连接的超时时间（秒）
+    #[serde(default = "default_connect_timeout")]
+    pub connect_timeout_secs: u64,
+    /// 检查单个链接的超时时间（秒）
+    #[serde(default = "default_link_check_timeout")]
+    pub link_check_timeout_secs: u64,
+    /// 下载图片、网页等资源的超时时间（秒）
+    #[serde(default = "default_download_timeout")]
+    pub download_timeout_secs: u64,
+    /// 发布、上传等平台接口的超时时间（秒）
+    #[serde(default = "default_publish_timeout")]
+    pub publish_timeout_secs: u64,
+    /// 调用视觉模型等 LLM 接口的超时时间（秒）
+    #[serde(default = "default_llm_timeout")]
+    pub llm_timeout_secs: u64,
+    /// 链接检查、图片下载等同时进行的最大请求数
+    #[serde(default = "default_max_concurrency")]
+    pub max_concurrency: usize,
+    /// 超过该大小（MB）的图片在平台支持时分片上传，中断后可续传
+    #[serde(default = "default_chunked_upload_threshold")]
+    pub chunked_upload_threshold_mb: u64,
+    /// 分片上传的每片大小（MB）
+    #[serde(default = "default_upload_chunk_size")]
+    pub upload_chunk_size_mb: u64,
+    /// 上传图片或分片失败后的重试次数，重试间隔逐次加倍
+    #[serde(default = "default_upload_retries")]
+    pub upload_retries: u32,
+}
+
+fn default_connect_timeout() -> u64 {
+    10
+}
+
+fn default_link_check_timeout() -> u64 {
+    10
+}
+
+fn default_download_timeout() -> u64 {
+    30
+}
+
+fn default_publish_timeout() -> u64 {
+    60
+}
+
+fn default_llm_timeout() -> u64 {
+    60
+}
+
+fn default_max_concurrency() -> usize {
+    4
+}
+
+fn default_chunked_upload_threshold() -> u64 {
+    5
+}
+
+fn default_upload_chunk_size() -> u64 {
+    2
+}
+
+fn default_upload_retries() -> u32 {
+    3
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            offline: false,
+            proxy: None,
+            no_proxy: Vec::new(),
+            ca_certs: Vec::new(),
+            connect_timeout_secs: default_connect_timeout(),
+            link_check_timeout_secs: default_link_check_timeout(),
+            download_timeout_secs: default_download_timeout(),
+            publish_timeout_secs: default_publish_timeout(),
+            llm_timeout_secs: default_llm_timeout(),
+            max_concurrency: default_max_concurrency(),
+            chunked_upload_threshold_mb: default_chunked_upload_threshold(),
+            upload_chunk_size_mb: default_upload_chunk_size(),
+            upload_retries: default_upload_retries(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GeneralConfig {
+    pub author: Option<String>,
+    pub default_platform: Option<String>,
+    pub auto_save: bool,
+    pub backup_enabled: bool,
+    pub watch_interval: u64, // 秒
+    #[serde(default = "default_timezone")]
+    pub timezone: String, // "local"、"utc"、"+08:00" 或 "Asia/Shanghai"
+    /// 智能标点："latin"（只处理拉丁文片段）、"all" 或 "off"
+    #[serde(default = "default_smart_punctuation")]
+    pub smart_punctuation: String,
+    /// 自有站点地址，设置后按 `permalink` 为每篇文章生成规范地址
+    #[serde(default)]
+    pub site_url: Option<String>,
+    /// 固定链接的路径模式，可使用 `{slug}`、`{year}`、`{month}`、`{day}`
+    #[serde(default = "default_permalink")]
+    pub permalink: String,
+    /// 模板中日期、星期和数字的格式："zh-CN"、"ja"、"ko" 或 "en"
+    #[serde(default = "default_locale")]
+    pub locale: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeChatConfig {
+    pub app_id: Option<String>,
+    pub app_secret: Option<String>,
+    pub access_token: Option<String>,
+    pub default_thumb_media_id: Option<String>,
+    pub auto_publish: bool,
+    pub draft_mode: bool,
+    #[serde(default)]
+    pub split_long_paragraphs: bool, // 按句子拆分过长段落，便于手机阅读
+    #[serde(default = "default_max_paragraph_chars")]
+    pub max_paragraph_chars: usize,
+    #[serde(default = "default_wechat_backend")]
+    pub backend: String, // 发布途径："api"（开放接口）或 "browser"（后台编辑器）
+    #[serde(default)]
+    pub cookies_file: Option<PathBuf>, // browser 途径使用的公众号后台登录 Cookie
+    #[serde(default)]
+    pub author: Option<String>, // front matter 没有作者时使用的作者名
+    #[serde(default)]
+    pub declare_original: bool, // 声明原创
+    #[serde(default)]
+    pub open_comment: bool, // 开启留言
+    #[serde(default)]
+    pub fans_only_comment: bool, // 仅粉丝可留言
+    #[serde(default)]
+    pub reward: bool, // 开启赞赏（需要先声明原创）
+    #[serde(default = "default_wechat_allowed_tags")]
+    pub allowed_tags: Vec<String>, // 保留的标签，其余标签去掉、保留内容
+    #[serde(default)]
+    pub theme: Option<PathBuf>, // 主题 CSS 文件，代替内置的默认主题内联到正文
+}
+
+fn default_wechat_allowed_tags() -> Vec<String> {
+    crate::adapters::DEFAULT_WECHAT_ALLOWED_TAGS
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ZhihuConfig {
+    pub username: Option<String>,
+    pub cookies_file: Option<PathBuf>,
+    pub auto_publish: bool,
+    pub default_column: Option<String>,
+    pub enable_math: bool,
+    pub code_theme: String,
+    /// 知乎不支持的公式（如 align 环境）回退为图片时使用的渲染服务
+    #[serde(default = "default_math_image_service")]
+    pub math_image_service: String,
+    /// 登录 Cookie 失效时，在终端中运行的发布打开浏览器窗口重新登录，并把新的 Cookie 写回 `cookies_file`
+    #[serde(default = "default_true")]
+    pub interactive_login: bool,
+    /// 清理时连同内容一起移除的标签
+    #[serde(default = "default_zhihu_forbidden_tags")]
+    pub forbidden_tags: Vec<String>,
+}
+
+/// 逗号分隔的标签列表，空值表示恢复默认列表
+fn tag_list(value: &str) -> Option<Vec<String>> {
+    let tags: Vec<String> = value
+        .split(',')
+        .map(|tag| tag.trim().trim_matches(['<', '>']).to_ascii_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect();
+    (!tags.is_empty()).then_some(tags)
+}
+
+fn default_zhihu_forbidden_tags() -> Vec<String> {
+    crate::adapters::DEFAULT_ZHIHU_FORBIDDEN_TAGS
+        .iter()
+        .map(|tag| tag.to_string())
+        .collect()
+}
+
+fn default_math_image_service() -> String {
+    crate::adapters::DEFAULT_MATH_IMAGE_SERVICE.to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateConfig {
+    pub templates_dir: PathBuf,
+    pub default_template: Option<String>,
+    pub custom_templates: HashMap<String, PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    pub output_dir: PathBuf,
+    pub create_subdirs: bool,     // 是否为每个平台创建子目录
+    pub filename_pattern: String, // 文件名模式，如 "{title}_{platform}.html"
+    pub backup_dir: Option<PathBuf>,
+    #[serde(default = "default_true")]
+    pub report_enabled: bool, // 每次运行后生成处理报告
+    #[serde(default = "default_report_format")]
+    pub report_format: String, // 报告格式："markdown" 或 "html"
+    #[serde(default = "default_output_encoding")]
+    pub encoding: String, // 输出编码，如 "utf-8"、"utf-8-bom"、"gbk"
+    #[serde(default)]
+    pub encodings: BTreeMap<String, String>, // 按平台覆盖输出编码
+    #[serde(default = "default_embed_source")]
+    pub embed_source: String, // 在输出中嵌入 Markdown 源文："none"、"comment" 或 "base64"
+    #[serde(default = "default_true")]
+    pub validate_html: bool, // 检查适配后 HTML 的标签结构，问题写入报告
+    #[serde(default = "default_references")]
+    pub references: String, // 在输出旁生成参考资料文件（外部链接和脚注）："none"、"markdown" 或 "json"
+    #[serde(default = "default_document")]
+    pub document: String, // 输出形式："fragment"（正文片段）或 "full"（套用模板的完整文档）
+    #[serde(default)]
+    pub documents: BTreeMap<String, String>, // 按平台覆盖输出形式
+}
+
+impl OutputConfig {
+    /// 指定平台的输出编码
+    pub fn encoding_for(&self, platform: &str) -> &str {
+        self.encodings
+            .get(platform)
+            .map(String::as_str)
+            .unwrap_or(&self.encoding)
+    }
+
+    /// 指定平台的输出形式
+    pub fn document_for(&self, platform: &str) -> &str {
+        self.documents
+            .get(platform)
+            .map(String::as_str)
+            .unwrap_or(&self.document)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    /// watch/serve 守护进程是否同时输出 JSON 日志文件
+    #[serde(default)]
+    pub file_enabled: bool,
+    #[serde(default = "default_log_dir")]
+    pub dir: PathBuf,
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    #[serde(default = "default_log_rotation")]
+    pub rotation: String, // "minutely"、"hourly"、"daily"、"never" 或 "size"
+    #[serde(default = "default_log_max_size_mb")]
+    pub max_size_mb: u64, // rotation = "size" 时单个文件的上限
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize, // 保留的历史日志文件数量
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetsConfig {
+    /// 上传文件的存放目录，默认为工作区下的 `assets`
+    #[serde(default)]
+    pub dir: Option<PathBuf>,
+    /// 返回给编辑器的地址前缀（如 CDN），默认使用 Web 服务自身的地址
+    #[serde(default)]
+    pub base_url: Option<String>,
+    #[serde(default = "default_assets_max_size_mb")]
+    pub max_size_mb: u64,
+    #[serde(default = "default_assets_extensions")]
+    pub allowed_extensions: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LintConfig {
+    /// 一个词至少出现这么多次才检查是否过度重复
+    #[serde(default = "default_repeated_word_min_count")]
+    pub repeated_word_min_count: usize,
+    /// 单个词占全文有效词的比例上限（百分比）
+    #[serde(default = "default_repeated_word_max_density")]
+    pub repeated_word_max_density: f64,
+    /// 不参与重复词检查的词（如文章主题词）
+    #[serde(default)]
+    pub ignore_words: Vec<String>,
+    #[serde(default)]
+    pub terminology: TerminologyConfig,
+    #[serde(default)]
+    pub duplication: DuplicationConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicationConfig {
+    /// 与台账中的历史文章比对，提示大段重复内容
+    #[serde(default)]
+    pub enabled: bool,
+    /// 新文章被某篇历史文章覆盖的比例达到该值时提示（0-1）
+    #[serde(default = "default_duplication_threshold")]
+    pub threshold: f64,
+    /// 单个段落达到该长度（字符数）且基本重复时单独提示
+    #[serde(default = "default_duplication_min_passage_chars")]
+    pub min_passage_chars: usize,
+}
+
+fn default_duplication_threshold() -> f64 {
+    0.3
+}
+
+fn default_duplication_min_passage_chars() -> usize {
+    50
+}
+
+impl Default for DuplicationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: default_duplication_threshold(),
+            min_passage_chars: default_duplication_min_passage_chars(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TerminologyConfig {
+    /// 是否启用内置的常见术语词典（Github -> GitHub 等）
+    #[serde(default = "default_true")]
+    pub builtin: bool,
+    /// 在输出中自动修正术语，源文件保持不变
+    #[serde(default)]
+    pub auto_fix: bool,
+    /// 外部词典文件（TOML，每行 `"错误写法" = "推荐写法"`）
+    #[serde(default)]
+    pub dictionary_file: Option<PathBuf>,
+    /// 错误写法 -> 推荐写法
+    #[serde(default)]
+    pub terms: BTreeMap<String, String>,
+}
+
+fn default_repeated_word_min_count() -> usize {
+    5
+}
+
+fn default_repeated_word_max_density() -> f64 {
+    3.0
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotConfig {
+    /// 找不到本地 chromedriver 且未配置 browser.webdriver_url 时连接的 WebDriver 服务地址
+    #[serde(default = "default_webdriver_url")]
+    pub webdriver_url: String,
+    /// 截图视口宽度（像素），默认模拟手机屏幕
+    #[serde(default = "default_snapshot_width")]
+    pub width: u32,
+    #[serde(default = "default_snapshot_height")]
+    pub height: u32,
+    /// 基准截图目录
+    #[serde(default = "default_snapshot_dir")]
+    pub dir: PathBuf,
+    /// 允许的不同像素占比（百分比）
+    #[serde(default = "default_snapshot_max_diff")]
+    pub max_diff_percent: f64,
+    /// 单个颜色通道允许的差值，用于忽略抗锯齿带来的细微差异
+    #[serde(default = "default_snapshot_tolerance")]
+    pub pixel_tolerance: u8,
+    /// 截图模拟的设备，设置后代替 `width`、`height`，未设置时使用 `browser.device`
+    #[serde(default)]
+    pub device: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossLinkConfig {
+    /// 自动为提到的历史文章添加链接（基于本地台账）
+    #[serde(default)]
+    pub enabled: bool,
+    /// 默认方式："link"（链接）、"footnote"（脚注）或 "note"（文末“见我之前的文章”）
+    #[serde(default = "default_cross_link_policy")]
+    pub default_policy: String,
+    /// 平台名 -> 方式，覆盖默认方式
+    #[serde(default = "default_cross_link_platforms")]
+    pub platforms: BTreeMap<String, String>,
+    /// 不参与自动链接的文章标题或关键词
+    #[serde(default)]
+    pub opt_out: Vec<String>,
+    /// 每篇文章最多添加的链接数
+    #[serde(default = "default_cross_link_max")]
+    pub max_links: usize,
+}
+
+fn default_cross_link_policy() -> String {
+    "link".to_string()
+}
+
+fn default_cross_link_platforms() -> BTreeMap<String, String> {
+    // 微信正文不能放外链
+    BTreeMap::from([("wechat".to_string(), "note".to_string())])
+}
+
+fn default_cross_link_max() -> usize {
+    3
+}
+
+impl Default for CrossLinkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            default_policy: default_cross_link_policy(),
+            platforms: default_cross_link_platforms(),
+            opt_out: Vec::new(),
+            max_links: default_cross_link_max(),
+        }
+    }
+}
+
+/// 处理阶段的图片本地化和压缩
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagesConfig {
+    /// 下载正文引用的网络图片，改为引用本地缓存（随输出复制、发布时上传到平台）
+    #[serde(default)]
+    pub download_remote: bool,
+    /// 宽度超过该值的图片按比例缩小，0 表示不缩放
+    #[serde(default)]
+    pub max_width: u32,
+    /// 缩小后重新编码 JPEG 的质量（1-100）
+    #[serde(default = "default_images_quality")]
+    pub quality: u8,
+    /// 下载和缩放后的图片缓存目录
+    #[serde(default = "default_images_cache_dir")]
+    pub cache_dir: PathBuf,
+}
+
+fn default_images_quality() -> u8 {
+    85
+}
+
+fn default_images_cache_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".markflow").join("image_cache")
+}
+
+impl Default for ImagesConfig {
+    fn default() -> Self {
+        Self {
+            download_remote: false,
+            max_width: 0,
+            quality: default_images_quality(),
+            cache_dir: default_images_cache_dir(),
+        }
+    }
+}
+
+/// 在本地把公式渲染为 SVG，用于不支持公式的平台（如公众号）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MathConfig {
+    /// 默认关闭：开启后正文中的 `$` 按公式定界符处理
+    #[serde(default)]
+    pub enabled: bool,
+    /// 公式渲染为 SVG 的平台；不在其中的平台保持原样（知乎由适配器转为 data-tex 公式）
+    #[serde(default = "default_math_platforms")]
+    pub platforms: Vec<String>,
+}
+
+fn default_math_platforms() -> Vec<String> {
+    vec!["wechat".to_string()]
+}
+
+impl Default for MathConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            platforms: default_math_platforms(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AltTextConfig {
+    /// 为缺少 alt 的图片调用视觉模型生成描述
+    #[serde(default)]
+    pub enabled: bool,
+    /// OpenAI 兼容的 chat completions 接口地址
+    #[serde(default = "default_alt_text_endpoint")]
+    pub endpoint: String,
+    #[serde(default = "default_alt_text_model")]
+    pub model: String,
+    /// 保存 API Key 的环境变量名
+    #[serde(default = "default_alt_text_api_key_env")]
+    pub api_key_env: String,
+    /// 描述语言："zh" 或 "en"
+    #[serde(default = "default_alt_text_language")]
+    pub language: String,
+    /// 按图片哈希缓存生成结果
+    #[serde(default = "default_alt_text_cache_file")]
+    pub cache_file: PathBuf,
+}
+
+fn default_alt_text_endpoint() -> String {
+    "https://api.openai.com/v1/chat/completions".to_string()
+}
+
+fn default_alt_text_model() -> String {
+    "gpt-4o-mini".to_string()
+}
+
+fn default_alt_text_api_key_env() -> String {
+    "OPENAI_API_KEY".to_string()
+}
+
+fn default_alt_text_language() -> String {
+    "zh".to_string()
+}
+
+fn default_alt_text_cache_file() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".markflow").join("alt_text_cache.json")
+}
+
+impl Default for AltTextConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            endpoint: default_alt_text_endpoint(),
+            model: default_alt_text_model(),
+            api_key_env: default_alt_text_api_key_env(),
+            language: default_alt_text_language(),
+            cache_file: default_alt_text_cache_file(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinksConfig {
+    /// 处理时检查外部链接是否可访问，失效链接写入验证警告
+    #[serde(default)]
+    pub check: bool,
+    #[serde(default)]
+    pub utm: UtmConfig,
+    /// 链接改写规则，按顺序应用，在追加 UTM 参数之前执行
+    #[serde(default)]
+    pub rewrite: Vec<LinkRewriteRule>,
+    /// 把正文中的裸网址（`https://…`、`www.…`）自动识别为链接
+    #[serde(default = "default_true")]
+    pub autolink: bool,
+    #[serde(default)]
+    pub bare_urls: BareUrlConfig,
+    /// 链接检查结果的缓存文件，有效期内不重复请求同一地址
+    #[serde(default = "default_link_cache_file")]
+    pub cache_file: PathBuf,
+    /// 检查结果的有效期（小时），0 表示不使用缓存
+    #[serde(default = "default_link_cache_ttl_hours")]
+    pub cache_ttl_hours: u64,
+    /// 拒绝 HEAD 请求的网站（含子域名），直接用 GET 检查
+    #[serde(default)]
+    pub get_only_domains: Vec<String>,
+}
+
+fn default_link_cache_file() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".markflow").join("link_cache.json")
+}
+
+fn default_link_cache_ttl_hours() -> u64 {
+    24
+}
+
+impl Default for LinksConfig {
+    fn default() -> Self {
+        Self {
+            check: false,
+            utm: UtmConfig::default(),
+            rewrite: Vec::new(),
+            autolink: true,
+            bare_urls: BareUrlConfig::default(),
+            cache_file: default_link_cache_file(),
+            cache_ttl_hours: default_link_cache_ttl_hours(),
+            get_only_domains: Vec::new(),
+        }
+    }
+}
+
+/// 自动识别出的裸链接的处理方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BareUrlConfig {
+    /// 默认方式："keep"（保持链接）、"readable"（域名 · 页面标题）、"text"（只保留网址文字）或 "strip"（移除）
+    #[serde(default = "default_bare_url_policy")]
+    pub default_policy: String,
+    /// 平台名 -> 方式，覆盖默认方式
+    #[serde(default)]
+    pub platforms: BTreeMap<String, String>,
+}
+
+fn default_bare_url_policy() -> String {
+    "keep".to_string()
+}
+
+impl Default for BareUrlConfig {
+    fn default() -> Self {
+        Self {
+            default_policy: default_bare_url_policy(),
+            platforms: BTreeMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct GeneralConfig {
-    pub author: Option<String>,
-    pub default_platform: Option<String>,
-    pub auto_save: bool,
-    pub backup_enabled: bool,
-    pub watch_interval: u64, // 秒
+pub struct LinkRewriteRule {
+    /// 匹配链接地址的正则表达式
+    pub pattern: String,
+    /// 替换内容，可以用 `$1`、`${name}` 引用捕获组
+    pub replacement: String,
+    /// 生效的平台，留空表示所有平台
+    #[serde(default)]
+    pub platforms: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WeChatConfig {
-    pub app_id: Option<String>,
-    pub app_secret: Option<String>,
-    pub access_token: Option<String>,
-    pub default_thumb_media_id: Option<String>,
-    pub auto_publish: bool,
-    pub draft_mode: bool,
+pub struct UtmConfig {
+    /// 为外部链接追加 UTM 参数
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_utm_medium")]
+    pub medium: String,
+    /// 默认 utm_campaign，front matter 中的 `campaign` 优先
+    #[serde(default)]
+    pub campaign: Option<String>,
+    /// 平台名 -> utm_source，未配置时使用平台名
+    #[serde(default)]
+    pub sources: BTreeMap<String, String>,
+    /// 不追加参数的域名（含子域名）
+    #[serde(default)]
+    pub exclude_domains: Vec<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ZhihuConfig {
-    pub username: Option<String>,
-    pub cookies_file: Option<PathBuf>,
-    pub auto_publish: bool,
-    pub default_column: Option<String>,
-    pub enable_math: bool,
-    pub code_theme: String,
+fn default_utm_medium() -> String {
+    "article".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TemplateConfig {
-    pub templates_dir: PathBuf,
-    pub default_template: Option<String>,
-    pub custom_templates: HashMap<String, PathBuf>,
+impl Default for UtmConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            medium: default_utm_medium(),
+            campaign: None,
+            sources: BTreeMap::new(),
+            exclude_domains: Vec::new(),
+        }
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct OutputConfig {
-    pub output_dir: PathBuf,
-    pub create_subdirs: bool,     // 是否为每个平台创建子目录
-    pub filename_pattern: String, // 文件名模式，如 "{title}_{platform}.html"
-    pub backup_dir: Option<PathBuf>,
+fn default_webdriver_url() -> String {
+    "http://localhost:9515".to_string()
+}
+
+fn default_snapshot_width() -> u32 {
+    375
+}
+
+fn default_snapshot_height() -> u32 {
+    812
+}
+
+fn default_snapshot_dir() -> PathBuf {
+    PathBuf::from("snapshots")
+}
+
+fn default_snapshot_max_diff() -> f64 {
+    0.1
+}
+
+fn default_snapshot_tolerance() -> u8 {
+    16
+}
+
+fn default_assets_max_size_mb() -> u64 {
+    20
+}
+
+fn default_assets_extensions() -> Vec<String> {
+    [
+        "png", "jpg", "jpeg", "gif", "webp", "svg", "mp3", "mp4", "pdf",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect()
+}
+
+fn default_log_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".markflow").join("logs")
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+fn default_log_rotation() -> String {
+    "daily".to_string()
+}
+
+fn default_log_max_size_mb() -> u64 {
+    10
+}
+
+fn default_log_max_files() -> usize {
+    7
+}
+
+fn default_locale() -> String {
+    "zh-CN".to_string()
+}
+
+fn default_timezone() -> String {
+    "local".to_string()
+}
+
+fn default_smart_punctuation() -> String {
+    "latin".to_string()
+}
+
+fn default_permalink() -> String {
+    "/{slug}/".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_paragraph_chars() -> usize {
+    150
+}
+
+fn default_wechat_backend() -> String {
+    "api".to_string()
+}
+
+fn default_report_format() -> String {
+    "markdown".to_string()
+}
+
+fn default_embed_source() -> String {
+    "none".to_string()
+}
+
+fn default_references() -> String {
+    "none".to_string()
+}
+
+fn default_document() -> String {
+    "fragment".to_string()
+}
+
+fn default_output_encoding() -> String {
+    "utf-8".to_string()
 }
 
 impl Default for GeneralConfig {
@@ -62,6 +1245,72 @@ impl Default for GeneralConfig {
             auto_save: true,
             backup_enabled: true,
             watch_interval: 2,
+            timezone: default_timezone(),
+            smart_punctuation: default_smart_punctuation(),
+            site_url: None,
+            permalink: default_permalink(),
+            locale: default_locale(),
+        }
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            file_enabled: false,
+            dir: default_log_dir(),
+            level: default_log_level(),
+            rotation: default_log_rotation(),
+            max_size_mb: default_log_max_size_mb(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+impl Default for AssetsConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            base_url: None,
+            max_size_mb: default_assets_max_size_mb(),
+            allowed_extensions: default_assets_extensions(),
+        }
+    }
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        Self {
+            webdriver_url: default_webdriver_url(),
+            width: default_snapshot_width(),
+            height: default_snapshot_height(),
+            dir: default_snapshot_dir(),
+            max_diff_percent: default_snapshot_max_diff(),
+            pixel_tolerance: default_snapshot_tolerance(),
+            device: None,
+        }
+    }
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            repeated_word_min_count: default_repeated_word_min_count(),
+            repeated_word_max_density: default_repeated_word_max_density(),
+            ignore_words: Vec::new(),
+            terminology: TerminologyConfig::default(),
+            duplication: DuplicationConfig::default(),
+        }
+    }
+}
+
+impl Default for TerminologyConfig {
+    fn default() -> Self {
+        Self {
+            builtin: true,
+            auto_fix: false,
+            dictionary_file: None,
+            terms: BTreeMap::new(),
         }
     }
 }
@@ -75,6 +1324,17 @@ impl Default for WeChatConfig {
             default_thumb_media_id: None,
             auto_publish: false,
             draft_mode: true,
+            split_long_paragraphs: false,
+            max_paragraph_chars: default_max_paragraph_chars(),
+            backend: default_wechat_backend(),
+            cookies_file: None,
+            author: None,
+            declare_original: false,
+            open_comment: false,
+            fans_only_comment: false,
+            reward: false,
+            allowed_tags: default_wechat_allowed_tags(),
+            theme: None,
         }
     }
 }
@@ -88,6 +1348,9 @@ impl Default for ZhihuConfig {
             default_column: None,
             enable_math: true,
             code_theme: "github".to_string(),
+            math_image_service: default_math_image_service(),
+            interactive_login: true,
+            forbidden_tags: default_zhihu_forbidden_tags(),
         }
     }
 }
@@ -110,36 +1373,169 @@ impl Default for OutputConfig {
             create_subdirs: true,
             filename_pattern: "{title}_{platform}.html".to_string(),
             backup_dir: Some(PathBuf::from("./backup")),
+            report_enabled: true,
+            report_format: default_report_format(),
+            encoding: default_output_encoding(),
+            encodings: BTreeMap::new(),
+            embed_source: default_embed_source(),
+            validate_html: true,
+            references: default_references(),
+            document: default_document(),
+            documents: BTreeMap::new(),
         }
     }
 }
 
 impl AppConfig {
-    pub fn load_from_file(path: &PathBuf) -> crate::Result<Self> {
+    pub fn load_from_file(path: &Path) -> crate::Result<Self> {
         if !path.exists() {
             return Ok(Self::default());
         }
 
         let content = std::fs::read_to_string(path)?;
-        let config: AppConfig = toml::from_str(&content)
+        let mut config: AppConfig = toml::from_str(&content)
             .map_err(|e| crate::error::Error::Config(format!("配置文件解析失败: {}", e)))?;
+        config.normalize_paths();
+        config.config_dir = path.parent().map(Path::to_path_buf);
 
         Ok(config)
     }
 
-    pub fn save_to_file(&self, path: &PathBuf) -> crate::Result<()> {
-        if let Some(parent) = path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+    pub fn save_to_file(&self, path: &Path) -> crate::Result<()> {
+        let _lock = crate::core::FileLock::exclusive(path)?;
+        self.write_unlocked(path)
+    }
+
+    /// 在同一把排他锁内完成读取、修改和写回，避免与其他 markflow 实例的写入交错
+    pub fn update_file<F>(path: &Path, f: F) -> crate::Result<Self>
+    where
+        F: FnOnce(&mut Self) -> crate::Result<()>,
+    {
+        let _lock = crate::core::FileLock::exclusive(path)?;
+        let mut config = Self::load_from_file(path)?;
+        f(&mut config)?;
+        config.write_unlocked(path)?;
+        Ok(config)
+    }
 
+    fn write_unlocked(&self, path: &Path) -> crate::Result<()> {
         let content = toml::to_string_pretty(self)
             .map_err(|e| crate::error::Error::Config(format!("配置序列化失败: {}", e)))?;
 
-        std::fs::write(path, content)?;
-        Ok(())
+        crate::core::write_atomic(path, content)
+    }
+
+    /// 统一配置中各路径的分隔符并展开 `~`，使同一份配置可以在不同系统间共享
+    pub fn normalize_paths(&mut self) {
+        use crate::core::normalize_config_path;
+
+        self.output.output_dir = normalize_config_path(&self.output.output_dir);
+        self.output.backup_dir = self.output.backup_dir.as_deref().map(normalize_config_path);
+        self.templates.templates_dir = normalize_config_path(&self.templates.templates_dir);
+        self.logging.dir = normalize_config_path(&self.logging.dir);
+        self.assets.dir = self.assets.dir.as_deref().map(normalize_config_path);
+        self.alt_text.cache_file = normalize_config_path(&self.alt_text.cache_file);
+        self.links.cache_file = normalize_config_path(&self.links.cache_file);
+        self.plugins.dir = normalize_config_path(&self.plugins.dir);
+        self.browser.cache_dir = normalize_config_path(&self.browser.cache_dir);
+        self.browser.executable = self
+            .browser
+            .executable
+            .as_deref()
+            .map(normalize_config_path);
+        self.browser.driver = self.browser.driver.as_deref().map(normalize_config_path);
+        self.lint.terminology.dictionary_file = self
+            .lint
+            .terminology
+            .dictionary_file
+            .as_deref()
+            .map(normalize_config_path);
+        self.wechat.cookies_file = self
+            .wechat
+            .cookies_file
+            .as_deref()
+            .map(normalize_config_path);
+        self.wechat.theme = self.wechat.theme.as_deref().map(normalize_config_path);
+        self.zhihu.cookies_file = self
+            .zhihu
+            .cookies_file
+            .as_deref()
+            .map(normalize_config_path);
+        for path in self.templates.custom_templates.values_mut() {
+            *path = normalize_config_path(path);
+        }
+        for path in &mut self.network.ca_certs {
+            *path = normalize_config_path(path);
+        }
+    }
+
+    pub fn timezone(&self) -> crate::core::TimeZoneSetting {
+        crate::core::TimeZoneSetting::from_config(&self.general.timezone)
+    }
+
+    /// 某个平台的验证级别设置：`[validation.all]` 加上平台自己的设置，后者优先
+    pub fn validation_levels(&self, platform: &crate::core::Platform) -> BTreeMap<String, String> {
+        let mut levels = self.validation.get("all").cloned().unwrap_or_default();
+        if let Some(own) = self.validation.get(&platform.to_string()) {
+            levels.extend(own.clone());
+        }
+        levels
+    }
+
+    /// 生效的主题变量：设置了 `style.theme` 时叠加主题文件
+    pub fn style(&self) -> crate::Result<StyleConfig> {
+        let config_dir = self
+            .config_dir
+            .clone()
+            .unwrap_or_else(crate::core::WorkspaceManager::default_root);
+        crate::core::apply_theme(&self.style, &config_dir.join(crate::core::THEMES_DIR))
+    }
+
+    /// 模板格式化日期和数字使用的语言，无效时回退到中文
+    pub fn locale(&self) -> crate::core::Language {
+        self.general.locale.parse().unwrap_or_else(|e| {
+            tracing::warn!("{}，使用中文格式", e);
+            crate::core::Language::Zh
+        })
+    }
+
+    pub fn smart_punctuation(&self) -> crate::core::SmartPunctuation {
+        crate::core::SmartPunctuation::from_config(&self.general.smart_punctuation)
+    }
+
+    /// 按名称查找设备（包括 `browser.devices` 中自定义的设备）
+    pub fn device_profile(&self, name: &str) -> crate::Result<crate::core::DeviceProfile> {
+        crate::core::device_profile(name, &self.browser.devices)
+    }
+
+    /// 空值表示不模拟设备，其余值必须是已知的设备名
+    fn optional_device(&self, value: &str) -> crate::Result<Option<String>> {
+        if value.trim().is_empty() {
+            return Ok(None);
+        }
+        self.device_profile(value)?;
+        Ok(Some(value.trim().to_string()))
+    }
+
+    pub fn permalink(&self) -> Option<crate::core::Permalink> {
+        crate::core::Permalink::from_config(&self.general)
+    }
+
+    /// 使用指定的配置文件（`--config`），对本进程内之后的配置读写生效；`None` 恢复默认位置
+    pub fn set_config_path(path: Option<PathBuf>) {
+        *CONFIG_PATH_OVERRIDE
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = path;
     }
 
     pub fn get_config_path() -> PathBuf {
+        if let Some(path) = CONFIG_PATH_OVERRIDE
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+        {
+            return path;
+        }
         let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
         home_dir.join(".markflow").join("config.toml")
     }
@@ -151,20 +1547,487 @@ impl AppConfig {
             "general.auto_save" => self.general.auto_save = value.parse().unwrap_or(true),
             "general.backup_enabled" => self.general.backup_enabled = value.parse().unwrap_or(true),
             "general.watch_interval" => self.general.watch_interval = value.parse().unwrap_or(2),
+            "general.timezone" => {
+                value.parse::<crate::core::TimeZoneSetting>()?;
+                self.general.timezone = value.to_string()
+            }
+            "general.smart_punctuation" => {
+                value.parse::<crate::core::SmartPunctuation>()?;
+                self.general.smart_punctuation = value.to_string()
+            }
+            "general.site_url" => {
+                self.general.site_url = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "general.permalink" => self.general.permalink = value.to_string(),
+            "general.locale" => {
+                value.parse::<crate::core::Language>()?;
+                self.general.locale = value.to_string();
+            }
 
             "wechat.app_id" => self.wechat.app_id = Some(value.to_string()),
             "wechat.app_secret" => self.wechat.app_secret = Some(value.to_string()),
             "wechat.auto_publish" => self.wechat.auto_publish = value.parse().unwrap_or(false),
             "wechat.draft_mode" => self.wechat.draft_mode = value.parse().unwrap_or(true),
+            "wechat.split_long_paragraphs" => {
+                self.wechat.split_long_paragraphs = value.parse().unwrap_or(false)
+            }
+            "wechat.max_paragraph_chars" => {
+                self.wechat.max_paragraph_chars = value.parse().unwrap_or(150)
+            }
+            "wechat.backend" => {
+                value.parse::<crate::publishers::PublishBackendKind>()?;
+                self.wechat.backend = value.to_string();
+            }
+            "wechat.cookies_file" => {
+                self.wechat.cookies_file =
+                    Some(crate::core::normalize_config_path(Path::new(value)))
+            }
+            "wechat.author" => {
+                self.wechat.author = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "wechat.declare_original" => {
+                self.wechat.declare_original = value.parse().unwrap_or(false)
+            }
+            "wechat.open_comment" => self.wechat.open_comment = value.parse().unwrap_or(false),
+            "wechat.fans_only_comment" => {
+                self.wechat.fans_only_comment = value.parse().unwrap_or(false)
+            }
+            "wechat.reward" => self.wechat.reward = value.parse().unwrap_or(false),
+            "wechat.allowed_tags" => {
+                self.wechat.allowed_tags =
+                    tag_list(value).unwrap_or_else(default_wechat_allowed_tags)
+            }
+            "wechat.theme" => {
+                self.wechat.theme = Some(value)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| crate::core::normalize_config_path(Path::new(v)))
+            }
 
             "zhihu.username" => self.zhihu.username = Some(value.to_string()),
             "zhihu.auto_publish" => self.zhihu.auto_publish = value.parse().unwrap_or(false),
             "zhihu.enable_math" => self.zhihu.enable_math = value.parse().unwrap_or(true),
             "zhihu.code_theme" => self.zhihu.code_theme = value.to_string(),
+            "zhihu.math_image_service" => self.zhihu.math_image_service = value.to_string(),
+            "zhihu.interactive_login" => {
+                self.zhihu.interactive_login = value.parse().unwrap_or(true)
+            }
+            "zhihu.forbidden_tags" => {
+                self.zhihu.forbidden_tags =
+                    tag_list(value).unwrap_or_else(default_zhihu_forbidden_tags)
+            }
+            "zhihu.default_column" => {
+                self.zhihu.default_column = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
 
-            "output.output_dir" => self.output.output_dir = PathBuf::from(value),
+            "output.output_dir" => {
+                self.output.output_dir = crate::core::normalize_config_path(Path::new(value))
+            }
             "output.create_subdirs" => self.output.create_subdirs = value.parse().unwrap_or(true),
             "output.filename_pattern" => self.output.filename_pattern = value.to_string(),
+            "output.report_enabled" => self.output.report_enabled = value.parse().unwrap_or(true),
+            "output.report_format" => self.output.report_format = value.to_string(),
+            "output.encoding" => {
+                crate::core::OutputEncoding::from_label(value)?;
+                self.output.encoding = value.to_string();
+            }
+            "output.embed_source" => {
+                value.parse::<crate::core::SourceEmbedMode>()?;
+                self.output.embed_source = value.to_string();
+            }
+            "output.validate_html" => self.output.validate_html = value.parse().unwrap_or(true),
+            "output.references" => {
+                value.parse::<crate::core::ReferencesFormat>()?;
+                self.output.references = value.to_string();
+            }
+            "output.document" => {
+                value.parse::<crate::core::DocumentMode>()?;
+                self.output.document = value.to_string();
+            }
+            key if key.starts_with("output.documents.") => {
+                value.parse::<crate::core::DocumentMode>()?;
+                let platform = &key["output.documents.".len()..];
+                self.output
+                    .documents
+                    .insert(platform.to_string(), value.to_string());
+            }
+            key if key.starts_with("output.encodings.") => {
+                crate::core::OutputEncoding::from_label(value)?;
+                let platform = &key["output.encodings.".len()..];
+                self.output
+                    .encodings
+                    .insert(platform.to_string(), value.to_string());
+            }
+
+            "logging.file_enabled" => self.logging.file_enabled = value.parse().unwrap_or(false),
+            "logging.dir" => {
+                self.logging.dir = crate::core::normalize_config_path(Path::new(value))
+            }
+            "logging.level" => self.logging.level = value.to_string(),
+            "logging.rotation" => {
+                if !matches!(value, "minutely" | "hourly" | "daily" | "never" | "size") {
+                    return Err(crate::error::Error::Config(format!(
+                        "无效的日志轮转方式: {}（可选 minutely/hourly/daily/never/size）",
+                        value
+                    )));
+                }
+                self.logging.rotation = value.to_string()
+            }
+            "logging.max_size_mb" => self.logging.max_size_mb = value.parse().unwrap_or(10),
+            "logging.max_files" => self.logging.max_files = value.parse().unwrap_or(7),
+
+            "assets.dir" => {
+                self.assets.dir = Some(crate::core::normalize_config_path(Path::new(value)))
+            }
+            "assets.base_url" => self.assets.base_url = Some(value.to_string()),
+            "assets.max_size_mb" => self.assets.max_size_mb = value.parse().unwrap_or(20),
+
+            "lint.repeated_word_min_count" => {
+                self.lint.repeated_word_min_count = value.parse().unwrap_or(5)
+            }
+            "lint.repeated_word_max_density" => {
+                self.lint.repeated_word_max_density = value.parse().unwrap_or(3.0)
+            }
+            "lint.terminology.builtin" => {
+                self.lint.terminology.builtin = value.parse().unwrap_or(true)
+            }
+            "lint.terminology.auto_fix" => {
+                self.lint.terminology.auto_fix = value.parse().unwrap_or(false)
+            }
+            "lint.terminology.dictionary_file" => {
+                self.lint.terminology.dictionary_file =
+                    Some(crate::core::normalize_config_path(Path::new(value)))
+            }
+            "lint.duplication.enabled" => {
+                self.lint.duplication.enabled = value.parse().unwrap_or(false)
+            }
+            "lint.duplication.threshold" => {
+                self.lint.duplication.threshold = value.parse().unwrap_or(0.3)
+            }
+            "lint.duplication.min_passage_chars" => {
+                self.lint.duplication.min_passage_chars = value.parse().unwrap_or(50)
+            }
+            "lint.ignore_words" => {
+                self.lint.ignore_words = value
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            }
+
+            "snapshot.webdriver_url" => self.snapshot.webdriver_url = value.to_string(),
+            "browser.executable" => {
+                self.browser.executable = Some(value)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| crate::core::normalize_config_path(Path::new(v)))
+            }
+            "browser.driver" => {
+                self.browser.driver = Some(value)
+                    .filter(|v| !v.is_empty())
+                    .map(|v| crate::core::normalize_config_path(Path::new(v)))
+            }
+            "browser.webdriver_url" => {
+                self.browser.webdriver_url = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "browser.auto_download" => self.browser.auto_download = value.parse().unwrap_or(false),
+            "browser.cache_dir" => {
+                self.browser.cache_dir = crate::core::normalize_config_path(Path::new(value))
+            }
+            "browser.no_sandbox" => self.browser.no_sandbox = value.parse().ok(),
+            "browser.args" => {
+                self.browser.args = value
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            }
+            "browser.pool_size" => self.browser.pool_size = value.parse().unwrap_or(2),
+            "browser.device" => {
+                self.browser.device = self.optional_device(value)?;
+            }
+            "snapshot.device" => {
+                self.snapshot.device = self.optional_device(value)?;
+            }
+            "snapshot.width" => self.snapshot.width = value.parse().unwrap_or(375),
+            "snapshot.height" => self.snapshot.height = value.parse().unwrap_or(812),
+            "snapshot.dir" => self.snapshot.dir = PathBuf::from(value),
+            "snapshot.max_diff_percent" => {
+                self.snapshot.max_diff_percent = value.parse().unwrap_or(0.1)
+            }
+            "snapshot.pixel_tolerance" => {
+                self.snapshot.pixel_tolerance = value.parse().unwrap_or(16)
+            }
+            "links.utm.enabled" => self.links.utm.enabled = value.parse().unwrap_or(false),
+            "links.utm.medium" => self.links.utm.medium = value.to_string(),
+            "links.utm.campaign" => self.links.utm.campaign = Some(value.to_string()),
+            "links.utm.exclude_domains" => {
+                self.links.utm.exclude_domains = value
+                    .split(',')
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty())
+                    .collect()
+            }
+            "alt_text.enabled" => self.alt_text.enabled = value.parse().unwrap_or(false),
+            "alt_text.endpoint" => self.alt_text.endpoint = value.to_string(),
+            "alt_text.model" => self.alt_text.model = value.to_string(),
+            "alt_text.api_key_env" => self.alt_text.api_key_env = value.to_string(),
+            "alt_text.language" => self.alt_text.language = value.to_string(),
+            "alt_text.cache_file" => {
+                self.alt_text.cache_file = crate::core::normalize_config_path(Path::new(value))
+            }
+            "images.download_remote" => {
+                self.images.download_remote = value.parse().unwrap_or(false)
+            }
+            "images.max_width" => self.images.max_width = value.parse().unwrap_or(0),
+            "images.quality" => {
+                self.images.quality = value
+                    .parse()
+                    .ok()
+                    .filter(|quality| (1..=100).contains(quality))
+                    .ok_or_else(|| {
+                        crate::error::Error::Config(format!(
+                            "无效的图片质量: {}（可选: 1-100）",
+                            value
+                        ))
+                    })?
+            }
+            "images.cache_dir" => {
+                self.images.cache_dir = crate::core::normalize_config_path(Path::new(value))
+            }
+            "math.enabled" => self.math.enabled = value.parse().unwrap_or(true),
+            "math.platforms" => {
+                self.math.platforms = value
+                    .split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            }
+            "cross_link.enabled" => self.cross_link.enabled = value.parse().unwrap_or(false),
+            "cross_link.default_policy" => {
+                value.parse::<crate::core::CrossLinkPolicy>()?;
+                self.cross_link.default_policy = value.to_string();
+            }
+            "cross_link.opt_out" => {
+                self.cross_link.opt_out = value
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            }
+            "cross_link.max_links" => self.cross_link.max_links = value.parse().unwrap_or(3),
+            "network.offline" => self.network.offline = value.parse().unwrap_or(false),
+            "style.theme" => self.style.theme = Some(value.to_string()).filter(|v| !v.is_empty()),
+            "style.primary_color" => self.style.primary_color = value.to_string(),
+            "style.text_color" => self.style.text_color = value.to_string(),
+            "style.heading_color" => self.style.heading_color = value.to_string(),
+            "style.code_color" => self.style.code_color = value.to_string(),
+            "style.font_size" => self.style.font_size = value.to_string(),
+            "style.h1_font_size" => self.style.h1_font_size = value.to_string(),
+            "style.h2_font_size" => self.style.h2_font_size = value.to_string(),
+            "style.h3_font_size" => self.style.h3_font_size = value.to_string(),
+            "style.background_color" => self.style.background_color = value.to_string(),
+            "workflow.require_approval" => {
+                self.workflow.require_approval = value.parse().unwrap_or(false)
+            }
+            "annotations.strip" => self.annotations.strip = value.parse().unwrap_or(true),
+            "annotations.unfinished" => {
+                value.parse::<crate::core::UnfinishedPolicy>()?;
+                self.annotations.unfinished = value.to_string();
+            }
+            "security.secrets" => {
+                value.parse::<crate::core::SecretPolicy>()?;
+                self.security.secrets = value.to_string();
+            }
+            "accessibility.enabled" => self.accessibility.enabled = value.parse().unwrap_or(true),
+            "accessibility.min_contrast" => {
+                self.accessibility.min_contrast = value.parse().unwrap_or(4.5)
+            }
+            "publish.write_back" => self.publish.write_back = value.parse().unwrap_or(false),
+            "publish.auto_draft_platforms" => {
+                let platforms: Vec<String> = value
+                    .split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect();
+                for platform in &platforms {
+                    platform.parse::<crate::core::Platform>()?;
+                }
+                self.publish.auto_draft_platforms = platforms;
+            }
+            "publish.auto_draft_max_per_hour" => {
+                self.publish.auto_draft_max_per_hour = value.parse().unwrap_or(5)
+            }
+            "format.line_width" => self.format.line_width = value.parse().unwrap_or(0),
+            "format.list_marker" => {
+                value.parse::<crate::core::ListMarker>()?;
+                self.format.list_marker = value.trim().to_string();
+            }
+            "security.allow" => {
+                self.security.allow = value
+                    .split(',')
+                    .map(|p| p.trim().to_string())
+                    .filter(|p| !p.is_empty())
+                    .collect()
+            }
+            "notifications.email.enabled" => {
+                self.notifications.email.enabled = value.parse().unwrap_or(false)
+            }
+            "notifications.email.frequency" => {
+                value.parse::<crate::core::DigestFrequency>()?;
+                self.notifications.email.frequency = value.to_string();
+            }
+            "notifications.email.smtp_host" => {
+                self.notifications.email.smtp_host =
+                    Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "notifications.email.smtp_port" => {
+                self.notifications.email.smtp_port = value.parse().unwrap_or(587)
+            }
+            "notifications.email.tls" => {
+                value.parse::<crate::core::SmtpTls>()?;
+                self.notifications.email.tls = value.to_string();
+            }
+            "notifications.email.username" => {
+                self.notifications.email.username =
+                    Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "notifications.email.password_env" => {
+                self.notifications.email.password_env = value.to_string()
+            }
+            "notifications.email.from" => {
+                self.notifications.email.from = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "notifications.email.to" => {
+                self.notifications.email.to = value
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect()
+            }
+            key if key.starts_with("field_mapping.") => {
+                let (platform, field) =
+                    key["field_mapping.".len()..]
+                        .split_once('.')
+                        .ok_or_else(|| {
+                            crate::error::Error::Config(format!(
+                                "字段映射的键应为 field_mapping.<平台>.<front matter 字段>: {}",
+                                key
+                            ))
+                        })?;
+                self.field_mapping
+                    .entry(platform.to_string())
+                    .or_default()
+                    .insert(field.to_string(), value.trim().to_string());
+            }
+            key if key.starts_with("validation.") => {
+                let (platform, rule) =
+                    key["validation.".len()..].split_once('.').ok_or_else(|| {
+                        crate::error::Error::Config(format!(
+                            "验证级别的键应为 validation.<平台或 all>.<规则名>: {}",
+                            key
+                        ))
+                    })?;
+                crate::adapters::traits::parse_validation_level(value.trim())?;
+                self.validation
+                    .entry(platform.to_string())
+                    .or_default()
+                    .insert(rule.to_string(), value.trim().to_lowercase());
+            }
+            "update.check" => self.update.check = value.parse().unwrap_or(false),
+            "update.interval_hours" => self.update.interval_hours = value.parse().unwrap_or(24),
+            "update.repository" => self.update.repository = value.to_string(),
+            "update.public_key" => {
+                self.update.public_key = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "network.proxy" => {
+                self.network.proxy = Some(value.to_string()).filter(|v| !v.is_empty())
+            }
+            "network.connect_timeout_secs" => {
+                self.network.connect_timeout_secs = value.parse().unwrap_or(10)
+            }
+            "network.link_check_timeout_secs" => {
+                self.network.link_check_timeout_secs = value.parse().unwrap_or(10)
+            }
+            "network.download_timeout_secs" => {
+                self.network.download_timeout_secs = value.parse().unwrap_or(30)
+            }
+            "network.publish_timeout_secs" => {
+                self.network.publish_timeout_secs = value.parse().unwrap_or(60)
+            }
+            "network.llm_timeout_secs" => {
+                self.network.llm_timeout_secs = value.parse().unwrap_or(60)
+            }
+            "network.max_concurrency" => self.network.max_concurrency = value.parse().unwrap_or(4),
+            "network.chunked_upload_threshold_mb" => {
+                self.network.chunked_upload_threshold_mb = value.parse().unwrap_or(5)
+            }
+            "network.upload_chunk_size_mb" => {
+                self.network.upload_chunk_size_mb = value.parse().unwrap_or(2)
+            }
+            "network.upload_retries" => self.network.upload_retries = value.parse().unwrap_or(3),
+            "links.check" => self.links.check = value.parse().unwrap_or(false),
+            "links.autolink" => self.links.autolink = value.parse().unwrap_or(true),
+            "links.cache_file" => {
+                self.links.cache_file = crate::core::normalize_config_path(Path::new(value))
+            }
+            "links.cache_ttl_hours" => self.links.cache_ttl_hours = value.parse().unwrap_or(24),
+            "links.get_only_domains" => {
+                self.links.get_only_domains = value
+                    .split(',')
+                    .map(|d| d.trim().to_lowercase())
+                    .filter(|d| !d.is_empty())
+                    .collect()
+            }
+            "links.bare_urls.default_policy" => {
+                value.parse::<crate::core::BareUrlPolicy>()?;
+                self.links.bare_urls.default_policy = value.to_string();
+            }
+            "plugins.dir" => {
+                self.plugins.dir = crate::core::normalize_config_path(Path::new(value))
+            }
+            "plugins.enabled" => {
+                self.plugins.enabled = value
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            }
+            "network.no_proxy" => {
+                self.network.no_proxy = value
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            }
+            "network.ca_certs" => {
+                self.network.ca_certs = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|w| !w.is_empty())
+                    .map(|w| crate::core::normalize_config_path(Path::new(w)))
+                    .collect()
+            }
+            key if key.starts_with("cross_link.platforms.") => {
+                value.parse::<crate::core::CrossLinkPolicy>()?;
+                let platform = &key["cross_link.platforms.".len()..];
+                self.cross_link
+                    .platforms
+                    .insert(platform.to_string(), value.to_string());
+            }
+            key if key.starts_with("links.bare_urls.platforms.") => {
+                value.parse::<crate::core::BareUrlPolicy>()?;
+                let platform = &key["links.bare_urls.platforms.".len()..];
+                self.links
+                    .bare_urls
+                    .platforms
+                    .insert(platform.to_string(), value.to_string());
+            }
+            key if key.starts_with("links.utm.sources.") => {
+                let platform = &key["links.utm.sources.".len()..];
+                self.links
+                    .utm
+                    .sources
+                    .insert(platform.to_string(), value.to_string());
+            }
 
             _ => {
                 return Err(crate::error::Error::Config(format!(
@@ -183,20 +2046,235 @@ impl AppConfig {
             "general.auto_save" => Some(self.general.auto_save.to_string()),
             "general.backup_enabled" => Some(self.general.backup_enabled.to_string()),
             "general.watch_interval" => Some(self.general.watch_interval.to_string()),
+            "general.timezone" => Some(self.general.timezone.clone()),
+            "general.smart_punctuation" => Some(self.general.smart_punctuation.clone()),
+            "general.site_url" => self.general.site_url.clone(),
+            "general.permalink" => Some(self.general.permalink.clone()),
+            "general.locale" => Some(self.general.locale.clone()),
 
             "wechat.app_id" => self.wechat.app_id.clone(),
             "wechat.app_secret" => self.wechat.app_secret.clone(),
             "wechat.auto_publish" => Some(self.wechat.auto_publish.to_string()),
             "wechat.draft_mode" => Some(self.wechat.draft_mode.to_string()),
+            "wechat.split_long_paragraphs" => Some(self.wechat.split_long_paragraphs.to_string()),
+            "wechat.max_paragraph_chars" => Some(self.wechat.max_paragraph_chars.to_string()),
+            "wechat.backend" => Some(self.wechat.backend.clone()),
+            "wechat.cookies_file" => self
+                .wechat
+                .cookies_file
+                .as_ref()
+                .map(|p| p.display().to_string()),
+            "wechat.author" => self.wechat.author.clone(),
+            "wechat.declare_original" => Some(self.wechat.declare_original.to_string()),
+            "wechat.open_comment" => Some(self.wechat.open_comment.to_string()),
+            "wechat.fans_only_comment" => Some(self.wechat.fans_only_comment.to_string()),
+            "wechat.reward" => Some(self.wechat.reward.to_string()),
+            "wechat.allowed_tags" => Some(self.wechat.allowed_tags.join(",")),
+            "wechat.theme" => self.wechat.theme.as_ref().map(|p| p.display().to_string()),
 
             "zhihu.username" => self.zhihu.username.clone(),
             "zhihu.auto_publish" => Some(self.zhihu.auto_publish.to_string()),
             "zhihu.enable_math" => Some(self.zhihu.enable_math.to_string()),
             "zhihu.code_theme" => Some(self.zhihu.code_theme.clone()),
+            "zhihu.math_image_service" => Some(self.zhihu.math_image_service.clone()),
+            "zhihu.interactive_login" => Some(self.zhihu.interactive_login.to_string()),
+            "zhihu.forbidden_tags" => Some(self.zhihu.forbidden_tags.join(",")),
+            "zhihu.default_column" => self.zhihu.default_column.clone(),
 
             "output.output_dir" => Some(self.output.output_dir.display().to_string()),
             "output.create_subdirs" => Some(self.output.create_subdirs.to_string()),
             "output.filename_pattern" => Some(self.output.filename_pattern.clone()),
+            "output.report_enabled" => Some(self.output.report_enabled.to_string()),
+            "output.report_format" => Some(self.output.report_format.clone()),
+            "output.encoding" => Some(self.output.encoding.clone()),
+            "output.embed_source" => Some(self.output.embed_source.clone()),
+            "output.validate_html" => Some(self.output.validate_html.to_string()),
+            "output.references" => Some(self.output.references.clone()),
+            "output.document" => Some(self.output.document.clone()),
+            key if key.starts_with("output.documents.") => self
+                .output
+                .documents
+                .get(&key["output.documents.".len()..])
+                .cloned(),
+            key if key.starts_with("output.encodings.") => self
+                .output
+                .encodings
+                .get(&key["output.encodings.".len()..])
+                .cloned(),
+
+            "logging.file_enabled" => Some(self.logging.file_enabled.to_string()),
+            "logging.dir" => Some(self.logging.dir.display().to_string()),
+            "logging.level" => Some(self.logging.level.clone()),
+            "logging.rotation" => Some(self.logging.rotation.clone()),
+            "logging.max_size_mb" => Some(self.logging.max_size_mb.to_string()),
+            "logging.max_files" => Some(self.logging.max_files.to_string()),
+
+            "assets.dir" => self.assets.dir.as_ref().map(|p| p.display().to_string()),
+            "assets.base_url" => self.assets.base_url.clone(),
+            "assets.max_size_mb" => Some(self.assets.max_size_mb.to_string()),
+
+            "lint.repeated_word_min_count" => Some(self.lint.repeated_word_min_count.to_string()),
+            "lint.repeated_word_max_density" => {
+                Some(self.lint.repeated_word_max_density.to_string())
+            }
+            "lint.ignore_words" => Some(self.lint.ignore_words.join(",")),
+            "lint.duplication.enabled" => Some(self.lint.duplication.enabled.to_string()),
+            "lint.duplication.threshold" => Some(self.lint.duplication.threshold.to_string()),
+            "lint.duplication.min_passage_chars" => {
+                Some(self.lint.duplication.min_passage_chars.to_string())
+            }
+            "lint.terminology.builtin" => Some(self.lint.terminology.builtin.to_string()),
+            "lint.terminology.auto_fix" => Some(self.lint.terminology.auto_fix.to_string()),
+            "lint.terminology.dictionary_file" => self
+                .lint
+                .terminology
+                .dictionary_file
+                .as_ref()
+                .map(|p| p.display().to_string()),
+
+            "snapshot.webdriver_url" => Some(self.snapshot.webdriver_url.clone()),
+            "browser.executable" => self
+                .browser
+                .executable
+                .as_ref()
+                .map(|p| p.display().to_string()),
+            "browser.driver" => self
+                .browser
+                .driver
+                .as_ref()
+                .map(|p| p.display().to_string()),
+            "browser.webdriver_url" => self.browser.webdriver_url.clone(),
+            "browser.auto_download" => Some(self.browser.auto_download.to_string()),
+            "browser.cache_dir" => Some(self.browser.cache_dir.display().to_string()),
+            "browser.no_sandbox" => self.browser.no_sandbox.map(|v| v.to_string()),
+            "browser.args" => Some(self.browser.args.join(",")),
+            "browser.pool_size" => Some(self.browser.pool_size.to_string()),
+            "browser.device" => self.browser.device.clone(),
+            "snapshot.device" => self.snapshot.device.clone(),
+            "snapshot.width" => Some(self.snapshot.width.to_string()),
+            "snapshot.height" => Some(self.snapshot.height.to_string()),
+            "snapshot.dir" => Some(self.snapshot.dir.display().to_string()),
+            "snapshot.max_diff_percent" => Some(self.snapshot.max_diff_percent.to_string()),
+            "snapshot.pixel_tolerance" => Some(self.snapshot.pixel_tolerance.to_string()),
+            "links.utm.enabled" => Some(self.links.utm.enabled.to_string()),
+            "links.utm.medium" => Some(self.links.utm.medium.clone()),
+            "links.utm.campaign" => self.links.utm.campaign.clone(),
+            "links.utm.exclude_domains" => Some(self.links.utm.exclude_domains.join(",")),
+            "alt_text.enabled" => Some(self.alt_text.enabled.to_string()),
+            "alt_text.endpoint" => Some(self.alt_text.endpoint.clone()),
+            "alt_text.model" => Some(self.alt_text.model.clone()),
+            "alt_text.api_key_env" => Some(self.alt_text.api_key_env.clone()),
+            "alt_text.language" => Some(self.alt_text.language.clone()),
+            "alt_text.cache_file" => Some(self.alt_text.cache_file.display().to_string()),
+            "images.download_remote" => Some(self.images.download_remote.to_string()),
+            "images.max_width" => Some(self.images.max_width.to_string()),
+            "images.quality" => Some(self.images.quality.to_string()),
+            "images.cache_dir" => Some(self.images.cache_dir.display().to_string()),
+            "math.enabled" => Some(self.math.enabled.to_string()),
+            "math.platforms" => Some(self.math.platforms.join(",")),
+            "cross_link.enabled" => Some(self.cross_link.enabled.to_string()),
+            "cross_link.default_policy" => Some(self.cross_link.default_policy.clone()),
+            "cross_link.opt_out" => Some(self.cross_link.opt_out.join(",")),
+            "cross_link.max_links" => Some(self.cross_link.max_links.to_string()),
+            "network.offline" => Some(self.network.offline.to_string()),
+            "style.theme" => self.style.theme.clone(),
+            "style.primary_color" => Some(self.style.primary_color.clone()),
+            "style.text_color" => Some(self.style.text_color.clone()),
+            "style.heading_color" => Some(self.style.heading_color.clone()),
+            "style.code_color" => Some(self.style.code_color.clone()),
+            "style.font_size" => Some(self.style.font_size.clone()),
+            "style.h1_font_size" => Some(self.style.h1_font_size.clone()),
+            "style.h2_font_size" => Some(self.style.h2_font_size.clone()),
+            "style.h3_font_size" => Some(self.style.h3_font_size.clone()),
+            "style.background_color" => Some(self.style.background_color.clone()),
+            "workflow.require_approval" => Some(self.workflow.require_approval.to_string()),
+            "annotations.strip" => Some(self.annotations.strip.to_string()),
+            "annotations.unfinished" => Some(self.annotations.unfinished.clone()),
+            "security.secrets" => Some(self.security.secrets.clone()),
+            "security.allow" => Some(self.security.allow.join(",")),
+            "publish.write_back" => Some(self.publish.write_back.to_string()),
+            "publish.auto_draft_platforms" => Some(self.publish.auto_draft_platforms.join(",")),
+            "publish.auto_draft_max_per_hour" => {
+                Some(self.publish.auto_draft_max_per_hour.to_string())
+            }
+            "format.line_width" => Some(self.format.line_width.to_string()),
+            "format.list_marker" => Some(self.format.list_marker.clone()),
+            "accessibility.enabled" => Some(self.accessibility.enabled.to_string()),
+            "accessibility.min_contrast" => Some(self.accessibility.min_contrast.to_string()),
+            "notifications.email.enabled" => Some(self.notifications.email.enabled.to_string()),
+            "notifications.email.frequency" => Some(self.notifications.email.frequency.clone()),
+            "notifications.email.smtp_host" => self.notifications.email.smtp_host.clone(),
+            "notifications.email.smtp_port" => Some(self.notifications.email.smtp_port.to_string()),
+            "notifications.email.tls" => Some(self.notifications.email.tls.clone()),
+            "notifications.email.username" => self.notifications.email.username.clone(),
+            "notifications.email.password_env" => {
+                Some(self.notifications.email.password_env.clone())
+            }
+            "notifications.email.from" => self.notifications.email.from.clone(),
+            "notifications.email.to" => Some(self.notifications.email.to.join(",")),
+            key if key.starts_with("field_mapping.") => key["field_mapping.".len()..]
+                .split_once('.')
+                .and_then(|(platform, field)| {
+                    crate::core::FieldMapping::from_config(&self.field_mapping)
+                        .rules_for(platform)?
+                        .get(field)
+                        .cloned()
+                }),
+            key if key.starts_with("validation.") => key["validation.".len()..]
+                .split_once('.')
+                .and_then(|(platform, rule)| self.validation.get(platform)?.get(rule).cloned()),
+            "update.check" => Some(self.update.check.to_string()),
+            "update.interval_hours" => Some(self.update.interval_hours.to_string()),
+            "update.repository" => Some(self.update.repository.clone()),
+            "update.public_key" => self.update.public_key.clone(),
+            "network.proxy" => self.network.proxy.clone(),
+            "plugins.dir" => Some(self.plugins.dir.display().to_string()),
+            "plugins.enabled" => Some(self.plugins.enabled.join(",")),
+            "network.no_proxy" => Some(self.network.no_proxy.join(",")),
+            "network.connect_timeout_secs" => Some(self.network.connect_timeout_secs.to_string()),
+            "network.link_check_timeout_secs" => {
+                Some(self.network.link_check_timeout_secs.to_string())
+            }
+            "network.download_timeout_secs" => Some(self.network.download_timeout_secs.to_string()),
+            "network.publish_timeout_secs" => Some(self.network.publish_timeout_secs.to_string()),
+            "network.llm_timeout_secs" => Some(self.network.llm_timeout_secs.to_string()),
+            "network.max_concurrency" => Some(self.network.max_concurrency.to_string()),
+            "network.chunked_upload_threshold_mb" => {
+                Some(self.network.chunked_upload_threshold_mb.to_string())
+            }
+            "network.upload_chunk_size_mb" => Some(self.network.upload_chunk_size_mb.to_string()),
+            "network.upload_retries" => Some(self.network.upload_retries.to_string()),
+            "links.check" => Some(self.links.check.to_string()),
+            "links.autolink" => Some(self.links.autolink.to_string()),
+            "links.cache_file" => Some(self.links.cache_file.display().to_string()),
+            "links.cache_ttl_hours" => Some(self.links.cache_ttl_hours.to_string()),
+            "links.get_only_domains" => Some(self.links.get_only_domains.join(",")),
+            "links.bare_urls.default_policy" => Some(self.links.bare_urls.default_policy.clone()),
+            "network.ca_certs" => Some(
+                self.network
+                    .ca_certs
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            key if key.starts_with("cross_link.platforms.") => self
+                .cross_link
+                .platforms
+                .get(&key["cross_link.platforms.".len()..])
+                .cloned(),
+            key if key.starts_with("links.bare_urls.platforms.") => self
+                .links
+                .bare_urls
+                .platforms
+                .get(&key["links.bare_urls.platforms.".len()..])
+                .cloned(),
+            key if key.starts_with("links.utm.sources.") => self
+                .links
+                .utm
+                .sources
+                .get(&key["links.utm.sources.".len()..])
+                .cloned(),
 
             _ => None,
         }
@@ -1,11 +1,44 @@
 use crate::{
-    adapters::{PlatformAdapter, WeChatStyleAdapter, ZhihuStyleAdapter},
-    cli::{args::AppConfig, ConfigAction, Platform, TemplateAction},
-    core::{MarkdownProcessor, ProcessingPipeline},
+    adapters::{create_adapter, AdapterCache},
+    cli::{
+        args::AppConfig,
+        progress::{self, status},
+        BrowserAction, ConfigAction, ContentAction, FrontmatterAction, JobsAction, NotifyAction,
+        PluginAction, TemplateAction, WorkspaceAction,
+    },
+    core::{
+        auto_draft_requested, browser_args, build_digest, capture_screenshot,
+        changed_markdown_files, check_accessibility, check_html, compare_png, content_hash,
+        copy_html, create_template, delete_template, embed_source, ensure_online, export_bundle,
+        export_workspace, extract_source, fetch_latest_release, find_driver, find_executable,
+        fix_front_matter, http_client_for, import_workspace, inherited_front_matter,
+        install_browser, installed_browser, is_container, is_directory_defaults_file, is_draft,
+        is_offline, keyword_density, list_templates, major_version, network_config, pinyin_slug,
+        plain_text, references_path, release_asset_name, render_document, render_named_template,
+        replace_executable, replace_title_heading, run_doctor, send_digest, series_articles,
+        slugify, snapshot_document, suggest_tags, title_variants, variant_output_key, variant_path,
+        verify_release_signature, write_atomic, Activity, ActivityLog, ArticleReport,
+        AutoDraftDecision, AutoDraftGuard, BrowserPool, CheckResult, CheckStatus, ContentRecord,
+        ContentStore, DigestState, DocumentMode, DuplicationChecker, EmailNotifier,
+        FrontMatterDefaults, LinkChecker, LintSeverity, Linter, MarkdownProcessor,
+        NetworkOperation, OutputEncoding, Platform, PlatformReport, PluginManager, ProcessCache,
+        ProcessingPipeline, ProcessingReport, PublishResult, PublishStatus, References,
+        ReferencesFormat, Role, SeriesCompiler, SourceEmbedMode, SourceFormatter, StageDump,
+        WorkflowState, WorkspaceManager, CURRENT_VERSION,
+    },
+    importers::{
+        convert_docx, import_document, import_html, notion_page_name, page_file_stem,
+        read_notion_file, read_notion_zip, ImportOptions, ImportResult, ImportSource,
+    },
+    publishers::{
+        create_publisher, parse_interval, prepare_article, publish_record, record_publish_result,
+        upload_article_images, PublishQueue, Publisher, QueueItemStatus,
+    },
+    web::{AppState, JobStore, LivePreview, WebServer},
     Result,
 };
 use notify::{Event, EventKind, RecursiveMode, Watcher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::{fs, sync::mpsc};
 use tracing::{debug, error, info, warn};
 
@@ -14,12 +47,381 @@ pub async fn process_command(
     output: Option<PathBuf>,
     platform: Option<Platform>,
     preview: bool,
+    debug_stages: Option<PathBuf>,
+    shift_headings: Option<u8>,
+    theme: Option<PathBuf>,
 ) -> Result<()> {
     info!("处理文件: {:?}", input);
 
     // 读取配置
-    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let mut config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    if theme.is_some() {
+        config.wechat.theme = theme;
+    }
+
+    let article_report = process_file(
+        &input,
+        &output,
+        platform,
+        preview,
+        debug_stages.as_deref(),
+        shift_headings,
+        &config,
+    )
+    .await?;
+
+    if !preview {
+        if config.output.report_enabled {
+            let mut report = ProcessingReport::new();
+            report.add_article(article_report);
+            write_report(&report, &output, &config).await?;
+        }
+        info!("处理完成！");
+    }
+
+    Ok(())
+}
+
+/// 只处理 `directory` 中自 git 引用 `since` 以来新增或修改的 Markdown 文件，适合在 CI 中使用
+#[allow(clippy::too_many_arguments)]
+pub async fn process_changed_command(
+    since: String,
+    directory: PathBuf,
+    output: Option<PathBuf>,
+    platform: Option<Platform>,
+    preview: bool,
+    debug_stages: Option<PathBuf>,
+    shift_headings: Option<u8>,
+    theme: Option<PathBuf>,
+) -> Result<()> {
+    let batch = Batch::new(
+        output,
+        platform,
+        preview,
+        debug_stages,
+        shift_headings,
+        theme,
+    )?;
+    let files = batch.sources(changed_markdown_files(&directory, &since)?);
+
+    if files.is_empty() {
+        info!("自 {} 以来没有变化的 Markdown 文件", since);
+        return Ok(());
+    }
+    info!("自 {} 以来有 {} 个 Markdown 文件变化", since, files.len());
+
+    let outcome = batch.run(files, 1, |_, _| {}).await?;
+    status!("处理摘要（自 {}）:", since);
+    outcome.finish(&batch).await
+}
+
+/// 目录批量处理的选项
+#[derive(Debug, Clone, Default)]
+pub struct BatchOptions {
+    /// 只处理相对路径匹配该模式的文件
+    pub glob: Option<String>,
+    /// 同时处理的文件数，默认为 CPU 核数
+    pub jobs: Option<usize>,
+    /// 忽略缓存，重新处理所有文件
+    pub force: bool,
+}
+
+/// 递归处理目录中的 Markdown 文件：按 `jobs` 限制并发，
+/// 内容和处理设置都没有变化的文件按输出目录中的缓存清单跳过（预览模式不使用缓存）
+#[allow(clippy::too_many_arguments)]
+pub async fn process_directory_command(
+    directory: PathBuf,
+    output: Option<PathBuf>,
+    platform: Option<Platform>,
+    preview: bool,
+    debug_stages: Option<PathBuf>,
+    shift_headings: Option<u8>,
+    theme: Option<PathBuf>,
+    options: BatchOptions,
+) -> Result<()> {
+    let batch = Batch::new(
+        output,
+        platform,
+        preview,
+        debug_stages,
+        shift_headings,
+        theme,
+    )?;
+    let pattern = options
+        .glob
+        .as_deref()
+        .map(glob::Pattern::new)
+        .transpose()
+        .map_err(|e| crate::error::Error::Config(format!("无效的文件模式: {}", e)))?;
+
+    let mut files = Vec::new();
+    collect_markdown_files(&directory, &mut files)?;
+    files.retain(|file| {
+        pattern.as_ref().is_none_or(|pattern| {
+            pattern.matches_path(file.strip_prefix(&directory).unwrap_or(file))
+        })
+    });
+    let files = batch.sources(files);
+
+    // 处理设置变化（配置、主题、模板、平台、标题降级）时所有文件都要重新处理
+    let settings =
+        ProcessCache::settings(&batch.config, batch.platform.as_ref(), batch.shift_headings)?;
+    let mut cache = if preview {
+        ProcessCache::default()
+    } else {
+        ProcessCache::load(&batch.output_dir())?
+    };
+    let mut pending = Vec::new();
+    let mut fingerprints = std::collections::HashMap::new();
+    let mut skipped = 0;
+    for file in files {
+        let markdown = std::fs::read_to_string(&file)?;
+        let source = std::fs::canonicalize(&file).unwrap_or_else(|_| file.clone());
+        let fingerprint =
+            ProcessCache::fingerprint(&markdown, &inherited_front_matter(&file)?, &settings)?;
+        if !preview && !options.force && cache.is_fresh(&source, &fingerprint) {
+            debug!("未变化，跳过: {:?}", file);
+            skipped += 1;
+            continue;
+        }
+        fingerprints.insert(file.clone(), (source, fingerprint));
+        pending.push(file);
+    }
+
+    if pending.is_empty() {
+        info!(
+            "{:?} 中没有需要处理的文件（{} 个未变化）",
+            directory, skipped
+        );
+        return Ok(());
+    }
+    let jobs = options
+        .jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()))
+        .max(1);
+    info!(
+        "处理 {} 个文件（{} 个未变化已跳过），并发 {}",
+        pending.len(),
+        skipped,
+        jobs
+    );
+
+    let outcome = batch
+        .run(pending, jobs, |file, article_report| {
+            if let Some((source, fingerprint)) = fingerprints.remove(file) {
+                let outputs = article_report
+                    .platforms
+                    .iter()
+                    .filter_map(|p| p.output.clone())
+                    .collect();
+                cache.update(source, fingerprint, outputs);
+            }
+        })
+        .await?;
+
+    status!("处理摘要（{}）:", directory.display());
+    if !preview {
+        cache.save()?;
+    }
+    let result = outcome.finish(&batch).await;
+    if skipped > 0 {
+        status!("未变化已跳过 {}（使用 --force 重新处理）", skipped);
+    }
+    result
+}
+
+/// 批量处理（`process --since` 和目录处理）共用的配置和处理参数
+struct Batch {
+    config: std::sync::Arc<AppConfig>,
+    output: Option<PathBuf>,
+    platform: Option<Platform>,
+    preview: bool,
+    debug_stages: Option<PathBuf>,
+    shift_headings: Option<u8>,
+}
+
+/// 批量处理的结果，逐篇输出后由 [`BatchOutcome::finish`] 写报告并汇总失败
+struct BatchOutcome {
+    report: ProcessingReport,
+    failures: Vec<(PathBuf, crate::error::Error)>,
+    total: usize,
+}
+
+impl Batch {
+    fn new(
+        output: Option<PathBuf>,
+        platform: Option<Platform>,
+        preview: bool,
+        debug_stages: Option<PathBuf>,
+        shift_headings: Option<u8>,
+        theme: Option<PathBuf>,
+    ) -> Result<Self> {
+        let mut config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+        if theme.is_some() {
+            config.wechat.theme = theme;
+        }
+        Ok(Self {
+            config: std::sync::Arc::new(config),
+            output,
+            platform,
+            preview,
+            debug_stages,
+            shift_headings,
+        })
+    }
+
+    fn output_dir(&self) -> PathBuf {
+        self.output
+            .clone()
+            .unwrap_or_else(|| self.config.output.output_dir.clone())
+    }
+
+    /// 去掉输出目录中的文件和草稿，剩下需要处理的源文件
+    fn sources(&self, files: Vec<PathBuf>) -> Vec<PathBuf> {
+        // 输出目录中的报告也是 Markdown，不能当作源文件再次处理
+        let output_dir = self.output_dir();
+        let output_dir = std::fs::canonicalize(&output_dir).unwrap_or(output_dir);
+        files
+            .into_iter()
+            .filter(|file| {
+                !std::fs::canonicalize(file)
+                    .unwrap_or_else(|_| file.clone())
+                    .starts_with(&output_dir)
+            })
+            .filter(|file| {
+                // front matter 有误的文件照常处理，由处理报告给出错误
+                let draft = std::fs::read_to_string(file)
+                    .ok()
+                    .and_then(|markdown| is_draft(file, &markdown).ok())
+                    .unwrap_or(false);
+                if draft {
+                    info!("跳过草稿: {:?}", file);
+                }
+                !draft
+            })
+            .collect()
+    }
+
+    /// 最多 `jobs` 个文件同时处理，每个文件成功后调用 `on_success`
+    async fn run(
+        &self,
+        files: Vec<PathBuf>,
+        jobs: usize,
+        mut on_success: impl FnMut(&Path, &ArticleReport),
+    ) -> Result<BatchOutcome> {
+        let semaphore = std::sync::Arc::new(tokio::sync::Semaphore::new(jobs.max(1)));
+        let bar = progress::bar(files.len(), "处理");
+        let mut tasks = tokio::task::JoinSet::new();
+        for file in files {
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("信号量不会被关闭");
+            let (output, platform, debug_stages, config) = (
+                self.output.clone(),
+                self.platform.clone(),
+                self.debug_stages.clone(),
+                self.config.clone(),
+            );
+            let (preview, shift_headings) = (self.preview, self.shift_headings);
+            tasks.spawn(async move {
+                let _permit = permit;
+                info!("处理文件: {:?}", file);
+                let result = process_file(
+                    &file,
+                    &output,
+                    platform,
+                    preview,
+                    debug_stages.as_deref(),
+                    shift_headings,
+                    &config,
+                )
+                .await;
+                (file, result)
+            });
+        }
 
+        let total = tasks.len();
+        let mut report = ProcessingReport::new();
+        let mut failures = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            let (file, result) = joined
+                .map_err(|e| crate::error::Error::Other(format!("处理任务异常退出: {}", e)))?;
+            bar.set_message(file.display().to_string());
+            match result {
+                Ok(article_report) => {
+                    on_success(&file, &article_report);
+                    report.add_article(article_report);
+                }
+                Err(e) => {
+                    error!("处理失败 {:?}: {}", file, e);
+                    failures.push((file, e));
+                }
+            }
+            bar.inc(1);
+        }
+        bar.finish_and_clear();
+
+        Ok(BatchOutcome {
+            report,
+            failures,
+            total,
+        })
+    }
+}
+
+impl BatchOutcome {
+    /// 逐篇列出结果并写入处理报告，有文件失败时返回 `PartialFailure`
+    async fn finish(self, batch: &Batch) -> Result<()> {
+        print_batch_summary(&self.report, &self.failures);
+
+        if !batch.preview && batch.config.output.report_enabled && !self.report.articles.is_empty()
+        {
+            write_report(&self.report, &batch.output, &batch.config).await?;
+        }
+
+        if self.failures.is_empty() {
+            Ok(())
+        } else {
+            Err(crate::error::Error::PartialFailure {
+                failed: self.failures.len(),
+                total: self.total,
+            })
+        }
+    }
+}
+
+/// 逐篇列出批量处理的结果
+fn print_batch_summary<P: AsRef<Path>>(
+    report: &ProcessingReport,
+    failures: &[(P, crate::error::Error)],
+) {
+    for article in &report.articles {
+        let source = article
+            .source
+            .as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        status!("  ✓ {} ({})", source, article.title);
+    }
+    for (file, e) in failures {
+        status!("  ✗ {} - {}", file.as_ref().display(), e);
+    }
+    status!("成功 {}，失败 {}", report.articles.len(), failures.len());
+}
+
+/// 处理单个 Markdown 文件：转换、按平台适配、保存输出并记录到台账。
+/// 指定 `debug_stages` 时把每个阶段之后的内容导出到该目录；`shift_headings` 覆盖 front matter 中的标题降级数
+async fn process_file(
+    input: &PathBuf,
+    output: &Option<PathBuf>,
+    platform: Option<Platform>,
+    preview: bool,
+    debug_stages: Option<&Path>,
+    shift_headings: Option<u8>,
+    config: &AppConfig,
+) -> Result<ArticleReport> {
     // 检查输入文件是否存在
     if !input.exists() {
         return Err(crate::error::Error::IO(std::io::Error::new(
@@ -29,78 +431,258 @@ pub async fn process_command(
     }
 
     // 读取Markdown内容
-    let markdown_content = fs::read_to_string(&input).await?;
+    let markdown_content = fs::read_to_string(input).await?;
 
     // 处理Markdown
-    let processor = MarkdownProcessor::new();
-    let pipeline = ProcessingPipeline::default();
+    let processor = MarkdownProcessor::new()
+        .with_timezone(config.timezone())
+        .with_autolink(config.links.autolink)
+        .with_smart_punctuation(config.smart_punctuation())
+        .with_math(config.math.enabled)
+        .with_permalink(config.permalink())
+        .with_inherited_front_matter(inherited_front_matter(input)?)
+        .with_heading_shift(shift_headings);
+    let pipeline = ProcessingPipeline::from_config_for_source(config, input)?;
 
+    let spinner = progress::spinner(format!("{}", input.display()));
     let content = processor.process(&markdown_content)?;
-    let processed_content = pipeline.process(content).await?;
+    let mut dump = debug_stages
+        .map(|dir| StageDump::new(dir, input))
+        .transpose()?;
+    if let Some(dump) = dump.as_mut() {
+        dump.content("解析", &content, "")?;
+    }
+    let mut previous_markdown = content.markdown.clone();
+    let processed_content = pipeline
+        .process_observed(
+            content,
+            |i, total, stage| {
+                spinner.set_message(format!(
+                    "{} [{}/{}] {}",
+                    input.display(),
+                    i + 1,
+                    total,
+                    stage
+                ));
+            },
+            |stage, content| {
+                if let Some(dump) = dump.as_mut() {
+                    dump.content(stage, content, &previous_markdown)?;
+                    previous_markdown.clone_from(&content.markdown);
+                }
+                Ok(())
+            },
+        )
+        .await?;
 
     // 确定目标平台
-    let target_platforms = determine_target_platforms(platform, &config);
+    let target_platforms = determine_target_platforms(platform, config);
+
+    let mut article_report = ArticleReport::new(&processed_content.title, Some(input.clone()));
+
+    // 与台账中的历史文章比对，重复内容作为验证警告提示
+    let duplicates = if config.lint.duplication.enabled {
+        DuplicationChecker::from_store(
+            &ContentStore::open_default()?,
+            input,
+            &config.lint.duplication,
+        )?
+        .check(&plain_text(&markdown_content))
+    } else {
+        Vec::new()
+    };
+    let broken_links = if config.links.check && !is_offline() {
+        LinkChecker::from_config(&config.links, &network_config())?
+            .check_html(&processed_content.html)
+            .await
+    } else {
+        Vec::new()
+    };
 
+    let mut adapter_cache = AdapterCache::open_default();
+    // 各平台（及标题变体）输出的 HTML，保存到台账
+    let mut outputs_html = std::collections::BTreeMap::new();
     for target_platform in target_platforms {
-        match target_platform {
-            Platform::WeChat => {
-                let adapter = WeChatStyleAdapter::new();
-                adapter.validate_content(&processed_content)?;
-                let adapted_html = adapter.adapt_html(&processed_content.html)?;
-
-                if preview {
-                    println!("=== 微信公众号 HTML 预览 ===");
-                    println!("{}", adapted_html);
-                } else {
-                    save_output(
-                        &processed_content,
-                        &adapted_html,
-                        &target_platform,
-                        &output,
-                        &config,
-                    )
-                    .await?;
+        spinner.set_message(format!("{} 适配 {}", input.display(), target_platform));
+        let adapter = create_adapter(&target_platform, config)?;
+
+        let mut platform_report = PlatformReport::new(adapter.platform());
+        let mut previous_markdown = processed_content.markdown.clone();
+        let platform_content = pipeline.process_for_platform_observed(
+            &processed_content,
+            &target_platform,
+            &mut platform_report,
+            |stage, content| {
+                if let Some(dump) = dump.as_mut() {
+                    let label = format!("{}-{}", target_platform, stage);
+                    dump.content(&label, content, &previous_markdown)?;
+                    previous_markdown.clone_from(&content.markdown);
                 }
+                Ok(())
+            },
+        )?;
+
+        let mut validation = adapter.validation_report(&platform_content);
+        validation.apply_levels(&config.validation_levels(&target_platform))?;
+        platform_report.warnings.extend(
+            validation
+                .warnings()
+                .map(|w| format!("{}: {}", w.field, w.message)),
+        );
+        platform_report.warnings.extend(
+            duplicates
+                .iter()
+                .map(|d| format!("duplication: {}", d.message())),
+        );
+        platform_report.warnings.extend(
+            broken_links
+                .iter()
+                .map(|l| format!("link: {} 无法访问（{}）", l.url, l.reason)),
+        );
+        validation.into_result(&target_platform.to_string())?;
+
+        // 导出各适配步骤时需要实际运行适配器，不使用缓存
+        let adapted_html = match dump.as_mut() {
+            Some(dump) => adapter.adapt_html_with_steps(
+                &platform_content.html,
+                &mut platform_report,
+                &mut |step, html| dump.html(&format!("{}-{}", target_platform, step), html),
+            )?,
+            None => adapter_cache.adapt(
+                adapter.as_ref(),
+                &platform_content.html,
+                &mut platform_report,
+            )?,
+        };
+        if config.output.validate_html {
+            platform_report.warnings.extend(
+                check_html(&adapted_html)
+                    .iter()
+                    .map(|issue| format!("html: {}", issue)),
+            );
+        }
+        if config.accessibility.enabled {
+            platform_report.warnings.extend(
+                check_accessibility(
+                    &adapted_html,
+                    &config.style()?.background_color,
+                    config.accessibility.min_contrast,
+                )
+                .iter()
+                .map(|issue| format!("a11y: {}", issue)),
+            );
+        }
+
+        if preview {
+            match target_platform {
+                Platform::WeChat => println!("=== 微信公众号 HTML 预览 ==="),
+                Platform::Zhihu => println!("=== 知乎 HTML 预览 ==="),
+                other => println!("=== {} HTML 预览 ===", other),
+            }
+            println!("{}", adapted_html);
+        } else {
+            let document_mode: DocumentMode = config
+                .output
+                .document_for(&target_platform.to_string())
+                .parse()?;
+            let document_html = match document_mode {
+                DocumentMode::Fragment => adapted_html,
+                DocumentMode::Full => render_document(
+                    &adapted_html,
+                    &platform_content,
+                    &target_platform.to_string(),
+                    &config.templates,
+                    &config.style()?,
+                    config.locale(),
+                )?,
+            };
+            let embed_mode: SourceEmbedMode = config.output.embed_source.parse()?;
+            let output_html = embed_source(&document_html, &markdown_content, embed_mode);
+            let (output_path, output_html) = save_output(
+                &processed_content,
+                input,
+                &output_html,
+                &target_platform,
+                output,
+                config,
+            )
+            .await?;
+            if let Some(dump) = dump.as_mut() {
+                dump.html(&format!("{}-输出", target_platform), &output_html)?;
             }
-            Platform::Zhihu => {
-                let adapter = ZhihuStyleAdapter::new()
-                    .with_math(config.zhihu.enable_math)
-                    .with_code_theme(config.zhihu.code_theme.clone());
-                adapter.validate_content(&processed_content)?;
-                let adapted_html = adapter.adapt_html(&processed_content.html)?;
 
-                if preview {
-                    println!("=== 知乎 HTML 预览 ===");
-                    println!("{}", adapted_html);
-                } else {
-                    save_output(
-                        &processed_content,
-                        &adapted_html,
-                        &target_platform,
-                        &output,
-                        &config,
-                    )
-                    .await?;
-                }
+            // 标题 A/B 变体各输出一份，文件名带变体标识
+            for variant in title_variants(&processed_content.metadata) {
+                let html =
+                    replace_title_heading(&output_html, &processed_content.title, &variant.title);
+                let path = variant_path(&output_path, &variant.label);
+                write_encoded(&path, &html, &target_platform, config).await?;
+                outputs_html.insert(
+                    variant_output_key(&target_platform.to_string(), &variant.label),
+                    html,
+                );
+                platform_report.variant_outputs.insert(variant.label, path);
             }
-            Platform::All => {
-                // 已经在外层循环处理
-                unreachable!()
+
+            // 外部链接和脚注取自适配前的 HTML，此时平台适配器尚未改写或移除链接
+            let references_format: ReferencesFormat = config.output.references.parse()?;
+            let references = References::collect(
+                &processed_content.title,
+                &target_platform.to_string(),
+                &platform_content.html,
+            );
+            if let Some(text) = references.render(references_format)? {
+                let path = references_path(&output_path, references_format);
+                fs::write(&path, text).await?;
+                info!("参考资料已保存到: {:?}", path);
             }
+            platform_report.output = Some(output_path);
+            outputs_html.insert(target_platform.to_string(), output_html);
         }
+
+        article_report.platforms.push(platform_report);
+    }
+
+    spinner.finish_and_clear();
+    if let Some(dump) = &dump {
+        info!("各阶段的中间结果已导出到: {:?}", dump.dir());
     }
 
     if !preview {
-        info!("处理完成！");
+        record_in_ledger(
+            input,
+            &markdown_content,
+            &processed_content,
+            &article_report,
+            outputs_html,
+        )?;
+        log_activity(Activity::Processed {
+            title: processed_content.title.clone(),
+            source: Some(input.clone()),
+            platforms: article_report
+                .platforms
+                .iter()
+                .map(|p| p.platform.to_string())
+                .collect(),
+        });
+        for link in broken_links {
+            log_activity(Activity::BrokenLink {
+                title: processed_content.title.clone(),
+                url: link.url,
+                reason: link.reason,
+            });
+        }
     }
 
-    Ok(())
+    Ok(article_report)
 }
 
 pub async fn watch_command(
     directory: PathBuf,
     output: Option<PathBuf>,
     pattern: String,
+    auto_draft: bool,
+    preview_port: Option<u16>,
 ) -> Result<()> {
     info!("开始监控目录: {:?}", directory);
     info!("文件模式: {}", pattern);
@@ -132,19 +714,87 @@ pub async fn watch_command(
 
     info!("文件监控已启动，按 Ctrl+C 停止");
 
+    // 开启邮件摘要时每小时检查一次是否到期
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let email = config.notifications.email;
+
+    // 自动草稿只在显式开启时生效，平台在启动时校验
+    let mut auto_draft = if auto_draft {
+        let platforms = config
+            .publish
+            .auto_draft_platforms
+            .iter()
+            .map(|p| p.parse::<Platform>())
+            .collect::<Result<Vec<_>>>()?;
+        let names: Vec<String> = platforms.iter().map(Platform::to_string).collect();
+        info!(
+            "自动草稿已开启：{}，每小时最多 {} 篇",
+            names.join("、"),
+            config.publish.auto_draft_max_per_hour
+        );
+        Some((
+            AutoDraftGuard::new(config.publish.auto_draft_max_per_hour),
+            platforms,
+        ))
+    } else {
+        None
+    };
+    let preview = match preview_port {
+        Some(port) => {
+            let preview = LivePreview::new();
+            let addr = preview.start(([127, 0, 0, 1], port).into()).await?;
+            info!("实时预览: http://{}/", addr);
+            Some(preview)
+        }
+        None => None,
+    };
+    let mut digest_timer = tokio::time::interval(std::time::Duration::from_secs(3600));
+
     // 处理文件事件
-    while let Some(event) = rx.recv().await {
+    loop {
+        let event = tokio::select! {
+            event = rx.recv() => match event {
+                Some(event) => event,
+                None => break,
+            },
+            _ = digest_timer.tick(), if email.enabled && !is_offline() => {
+                match send_digest(&email, false).await {
+                    Ok(true) => info!("已发送摘要邮件"),
+                    Ok(false) => {}
+                    Err(e) => warn!("发送摘要邮件失败: {}", e),
+                }
+                continue;
+            }
+        };
         if let EventKind::Modify(_) | EventKind::Create(_) = event.kind {
             for path in &event.paths {
-                if path.extension().and_then(|s| s.to_str()) == Some("md") {
+                if path.extension().and_then(|s| s.to_str()) == Some("md")
+                    && !is_directory_defaults_file(path)
+                {
                     info!("检测到文件变化: {:?}", path);
 
                     // 处理文件
-                    if let Err(e) =
-                        process_command(path.clone(), output.clone(), Some(Platform::All), false)
-                            .await
+                    if let Err(e) = process_command(
+                        path.clone(),
+                        output.clone(),
+                        Some(Platform::All),
+                        false,
+                        None,
+                        None,
+                        None,
+                    )
+                    .await
                     {
                         error!("处理文件失败: {}", e);
+                        continue;
+                    }
+                    if let Some(preview) = &preview {
+                        if let Err(e) = refresh_live_preview(preview, path) {
+                            warn!("更新实时预览失败: {}", e);
+                        }
+                    }
+                    if let Some((guard, platforms)) = auto_draft.as_mut() {
+                        create_auto_drafts(path, guard, platforms).await;
                     }
                 }
             }
@@ -154,46 +804,227 @@ pub async fn watch_command(
     Ok(())
 }
 
-pub async fn publish_command(_content: String, platform: Platform, draft: bool) -> Result<()> {
-    info!("发布内容到平台: {}", platform);
+/// 把刚处理完的文章在各平台的输出文件推送到实时预览，预览内容与输出文件完全一致
+fn refresh_live_preview(preview: &LivePreview, path: &Path) -> Result<()> {
+    let store = ContentStore::open_default()?;
+    let record = find_record(&store, &path.display().to_string())?;
+    let mut outputs = std::collections::BTreeMap::new();
+    for (platform, output) in &record.outputs {
+        outputs.insert(platform.clone(), std::fs::read_to_string(output)?);
+    }
+    preview.update(record.title.clone(), outputs);
+    Ok(())
+}
 
-    // 这里应该实现发布逻辑
-    // 由于需要浏览器自动化和API集成，这里提供一个框架
-    match platform {
-        Platform::WeChat => {
-            info!("正在发布到微信公众号...");
-            if draft {
-                info!("创建草稿模式");
-                // TODO: 实现微信公众号草稿创建
-            } else {
-                warn!("微信公众号不支持直接发布，将创建草稿");
-                // TODO: 实现微信公众号草稿创建
-            }
+/// 为要求自动草稿的文章在各平台创建草稿，并记录汇总日志
+async fn create_auto_drafts(path: &Path, guard: &mut AutoDraftGuard, platforms: &[Platform]) {
+    let markdown = match std::fs::read_to_string(path) {
+        Ok(markdown) => markdown,
+        Err(e) => {
+            warn!("读取 {:?} 失败，跳过自动草稿: {}", path, e);
+            return;
         }
-        Platform::Zhihu => {
-            info!("正在发布到知乎...");
-            // TODO: 实现知乎自动发布
-            warn!("知乎发布功能正在开发中");
+    };
+    match auto_draft_requested(path, &markdown) {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            warn!("读取 {:?} 的 front matter 失败，跳过自动草稿: {}", path, e);
+            return;
+        }
+    }
+
+    let now = chrono::Utc::now();
+    match guard.admit(path, &content_hash(markdown.as_bytes()), now) {
+        AutoDraftDecision::Create => {}
+        AutoDraftDecision::Unchanged => {
+            debug!("内容未变化，不重复创建草稿: {:?}", path);
+            return;
+        }
+        AutoDraftDecision::RateLimited { retry_after } => {
+            warn!(
+                "自动草稿已达每小时上限（{} 篇），跳过 {:?}，约 {} 分钟后恢复",
+                guard.max_per_hour(),
+                path,
+                retry_after.num_minutes() + 1
+            );
+            return;
+        }
+    }
+
+    let mut created = Vec::new();
+    let mut failed = Vec::new();
+    for platform in platforms {
+        match publish_command(path.display().to_string(), platform.clone(), true).await {
+            Ok(()) => created.push(platform.to_string()),
+            Err(e) => failed.push(format!("{}（{}）", platform, e)),
         }
-        Platform::All => {
-            return Err(crate::error::Error::Other(
-                "发布时不能选择'all'平台".to_string(),
+    }
+    info!(
+        "自动草稿 {:?}：成功 {}，失败 {}；最近一小时 {}/{} 篇",
+        path,
+        if created.is_empty() {
+            "无".to_string()
+        } else {
+            created.join("、")
+        },
+        if failed.is_empty() {
+            "无".to_string()
+        } else {
+            failed.join("、")
+        },
+        guard.created_in_last_hour(now),
+        guard.max_per_hour()
+    );
+}
+
+pub async fn publish_command(content: String, platform: Platform, draft: bool) -> Result<()> {
+    ensure_online("发布内容")?;
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let ledger = ContentStore::default_path();
+
+    // 台账只在读取和记录结果时短暂打开，上传和发布请求期间不持有台账锁
+    let record = find_record(&ContentStore::open(&ledger)?, &content)?.clone();
+    let spinner = progress::spinner(format!("发布到 {}", platform));
+    let result = publish_record(&record, &platform, &config, draft, &ledger).await;
+    spinner.finish_and_clear();
+    log_publish_activity(&record.title, &platform, &result);
+    let result = result?;
+
+    status!("{}: {}", platform, result.message);
+    if let Some(url) = &result.url {
+        status!("  {}", url);
+    }
+    record_publish_result(&ledger, &record.id, result, &config)
+}
+
+/// 按时间间隔逐篇发布目录中的平台输出，进度写入目录中的进度文件，中断后重新运行会继续
+pub async fn batch_publish_command(
+    dir: PathBuf,
+    platform: Platform,
+    draft: bool,
+    interval: String,
+) -> Result<()> {
+    ensure_online("发布内容")?;
+    let interval = parse_interval(&interval)?;
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let ledger = ContentStore::default_path();
+    let mut publisher = create_publisher(&platform, &config)?;
+    let mut queue = PublishQueue::load(&dir)?;
+
+    let remaining = queue.remaining();
+    if remaining.is_empty() {
+        status!("{} 中的文章已全部发布", dir.display());
+        return Ok(());
+    }
+    status!(
+        "待发布 {} 篇（共 {} 篇），间隔 {} 秒",
+        remaining.len(),
+        queue.items().len(),
+        interval.as_secs()
+    );
+
+    let platform_name = platform.to_string();
+    let mut failed = 0;
+    for (n, &index) in remaining.iter().enumerate() {
+        let file = queue.path_of(index);
+        let output = std::fs::canonicalize(&file).unwrap_or_else(|_| file.clone());
+        // 每篇文章单独打开台账，等待间隔和发布请求期间不持有台账锁
+        let record = ContentStore::open(&ledger)?
+            .records()
+            .iter()
+            .find(|r| r.outputs.get(&platform_name) == Some(&output))
+            .cloned();
+        let prepared = match record {
+            Some(record) => match prepare_article(&record, &platform, &config, draft).await {
+                Ok(mut article) => upload_article_images(
+                    &publisher,
+                    &mut article,
+                    record.source.as_deref(),
+                    &ledger,
+                )
+                .await
+                .map(|_| (record.id, article)),
+                Err(e) => Err(e),
+            },
+            None => Err(crate::error::Error::Other(
+                "台账中没有该输出文件对应的内容（请先运行 process）".to_string(),
+            )),
+        };
+        let (id, article) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                status!("  ✗ {} - {}", file.display(), e);
+                queue.finish(index, QueueItemStatus::Failed, e.to_string(), false)?;
+                failed += 1;
+                continue;
+            }
+        };
+
+        let wait = queue.wait_time(interval, chrono::Utc::now());
+        if !wait.is_zero() {
+            let spinner = progress::spinner(format!(
+                "等待 {} 秒后发布《{}》",
+                wait.as_secs(),
+                article.title
             ));
+            tokio::time::sleep(wait).await;
+            spinner.finish_and_clear();
+        }
+
+        let spinner = progress::spinner(format!(
+            "[{}/{}] 发布《{}》到 {}",
+            n + 1,
+            remaining.len(),
+            article.title,
+            platform
+        ));
+        let result = if draft {
+            publisher.create_draft(&article).await
+        } else {
+            publisher.publish(&article).await
+        };
+        spinner.finish_and_clear();
+        log_publish_activity(&article.title, &platform, &result);
+
+        match result {
+            Ok(result) => {
+                status!("  ✓ {} - {}", file.display(), result.message);
+                queue.finish(index, QueueItemStatus::Done, result.message.clone(), true)?;
+                record_publish_result(&ledger, &id, result, &config)?;
+            }
+            Err(e) => {
+                status!("  ✗ {} - {}", file.display(), e);
+                queue.finish(index, QueueItemStatus::Failed, e.to_string(), true)?;
+                failed += 1;
+            }
         }
     }
+    publisher.shutdown().await;
 
-    Ok(())
+    status!("成功 {}，失败 {}", remaining.len() - failed, failed);
+    if failed == 0 {
+        Ok(())
+    } else {
+        Err(crate::error::Error::PartialFailure {
+            failed,
+            total: remaining.len(),
+        })
+    }
 }
 
-pub async fn serve_command(port: u16, host: String, _static_dir: Option<PathBuf>) -> Result<()> {
+pub async fn serve_command(port: u16, host: String, static_dir: Option<PathBuf>) -> Result<()> {
     info!("启动Web服务器 {}:{}", host, port);
 
-    // TODO: 实现Web服务器
-    warn!("Web服务器功能正在开发中");
-
-    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    let addr: std::net::SocketAddr = format!("{}:{}", host, port).parse().map_err(|e| {
+        crate::error::Error::Config(format!("无效的监听地址 {}:{}: {}", host, port, e))
+    })?;
 
-    Ok(())
+    let state = AppState::new(WorkspaceManager::default_root());
+    WebServer::new(state)
+        .with_static_dir(static_dir)
+        .run(addr)
+        .await
 }
 
 pub async fn config_command(action: ConfigAction) -> Result<()> {
@@ -206,9 +1037,7 @@ pub async fn config_command(action: ConfigAction) -> Result<()> {
             println!("{}", toml::to_string_pretty(&config).unwrap());
         }
         ConfigAction::Set { key, value } => {
-            let mut config = AppConfig::load_from_file(&config_path)?;
-            config.set_value(&key, &value)?;
-            config.save_to_file(&config_path)?;
+            AppConfig::update_file(&config_path, |config| config.set_value(&key, &value))?;
             info!("配置已更新: {} = {}", key, value);
         }
         ConfigAction::Get { key } => {
@@ -230,124 +1059,1365 @@ pub async fn config_command(action: ConfigAction) -> Result<()> {
 }
 
 pub async fn template_command(action: TemplateAction) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let templates = &config.templates;
     match action {
         TemplateAction::List => {
-            info!("列出模板功能正在开发中");
+            for entry in list_templates(templates)? {
+                let marker = if templates.default_template.as_ref() == Some(&entry.name) {
+                    "*"
+                } else {
+                    " "
+                };
+                println!("{}{}\t{}", marker, entry.name, entry.path.display());
+            }
         }
         TemplateAction::Create { name, file } => {
-            info!("创建模板 '{}' from {:?}", name, file);
+            let source = match &file {
+                Some(file) => Some(fs::read_to_string(file).await?),
+                None => None,
+            };
+            let path = create_template(templates, &name, source.as_deref())?;
+            info!("已创建模板 {}: {:?}", name, path);
         }
         TemplateAction::Delete { name } => {
-            info!("删除模板 '{}'", name);
+            let path = delete_template(templates, &name)?;
+            info!("已删除模板 {}: {:?}", name, path);
         }
         TemplateAction::Apply {
             name,
             input,
-            output: _,
+            output,
         } => {
-            info!("应用模板 '{}' 到 {:?}", name, input);
+            let markdown = fs::read_to_string(&input).await?;
+            let content = MarkdownProcessor::new()
+                .with_timezone(config.timezone())
+                .with_autolink(config.links.autolink)
+                .with_smart_punctuation(config.smart_punctuation())
+                .with_math(config.math.enabled)
+                .with_permalink(config.permalink())
+                .with_inherited_front_matter(inherited_front_matter(&input)?)
+                .process(&markdown)?;
+            let content = ProcessingPipeline::from_config_for_source(&config, &input)?
+                .process(content)
+                .await?;
+            let html = render_named_template(
+                templates,
+                &name,
+                &content.html,
+                &content,
+                "",
+                &config.style()?,
+                config.locale(),
+            )?;
+            match output {
+                Some(output) => {
+                    write_atomic(&output, html)?;
+                    info!("已用模板 {} 生成 {:?}", name, output);
+                }
+                None => println!("{}", html),
+            }
         }
     }
 
     Ok(())
 }
 
-// 辅助函数
-fn determine_target_platforms(platform: Option<Platform>, config: &AppConfig) -> Vec<Platform> {
-    match platform {
-        Some(Platform::All) => vec![Platform::WeChat, Platform::Zhihu],
-        Some(platform) => vec![platform],
-        None => {
-            // 使用配置中的默认平台
-            match config.general.default_platform.as_deref() {
-                Some("wechat") => vec![Platform::WeChat],
-                Some("zhihu") => vec![Platform::Zhihu],
-                Some("all") | None => vec![Platform::WeChat, Platform::Zhihu],
-                _ => vec![Platform::WeChat, Platform::Zhihu],
+pub async fn frontmatter_command(action: FrontmatterAction) -> Result<()> {
+    match action {
+        FrontmatterAction::Fix { paths, dry_run } => {
+            let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+            let defaults = FrontMatterDefaults {
+                author: config.general.author.clone(),
+                date: config.timezone().now(),
+            };
+
+            let mut files = Vec::new();
+            for path in &paths {
+                collect_markdown_files(path, &mut files)?;
+            }
+
+            let mut changed = 0;
+            for file in &files {
+                let markdown = fs::read_to_string(file).await?;
+                let (fixed, added) = fix_front_matter(&markdown, &defaults);
+                if added.is_empty() {
+                    continue;
+                }
+
+                changed += 1;
+                status!("{}: 补充 {}", file.display(), added.join(", "));
+                if !dry_run {
+                    write_atomic(file, fixed)?;
+                }
+            }
+
+            if dry_run {
+                info!("共 {} 个文件需要补全（未写入）", changed);
+            } else {
+                info!("已补全 {} 个文件", changed);
             }
         }
     }
+
+    Ok(())
 }
 
-async fn save_output(
-    content: &crate::core::Content,
-    html: &str,
-    platform: &Platform,
-    output_override: &Option<PathBuf>,
-    config: &AppConfig,
+pub async fn import_command(
+    source: String,
+    output: Option<PathBuf>,
+    no_images: bool,
 ) -> Result<()> {
-    let output_dir = output_override
-        .as_ref()
-        .unwrap_or(&config.output.output_dir);
+    let source = ImportSource::parse(&source);
+    let extension = match &source {
+        ImportSource::File(path) => path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default(),
+        ImportSource::Url(_) => String::new(),
+    };
 
-    // 创建输出目录
-    if !output_dir.exists() {
-        fs::create_dir_all(output_dir).await?;
+    // Notion 导出的 ZIP 包含多个页面，输出到目录
+    if extension == "zip" {
+        let ImportSource::File(path) = &source else {
+            unreachable!()
+        };
+        let output_dir = output.unwrap_or_else(|| PathBuf::from("."));
+        info!("导入 Notion 导出: {:?}", path);
+        let pages = read_notion_zip(std::fs::File::open(path)?)?;
+        for page in pages {
+            let stem = page_file_stem(&page.name);
+            let output = output_dir.join(format!("{}.md", stem));
+            let result = import_document(
+                page.document,
+                &path.display().to_string(),
+                &import_options(&output, true),
+            )?;
+            write_import_result(&output, &result).await?;
+        }
+        return Ok(());
     }
 
-    // 生成文件名
-    let filename = generate_filename(&content.title, platform, &config.output.filename_pattern);
+    let output = output.unwrap_or_else(|| {
+        let stem = match &source {
+            ImportSource::Url(url) => url
+                .path_segments()
+                .and_then(|mut segments| segments.rfind(|s| !s.is_empty()))
+                .map(|s| s.trim_end_matches(".html").to_string()),
+            ImportSource::File(path) if extension == "md" => path
+                .file_stem()
+                .map(|s| page_file_stem(&notion_page_name(&s.to_string_lossy()))),
+            ImportSource::File(path) => path.file_stem().map(|s| s.to_string_lossy().to_string()),
+        };
+        let stem = stem
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "imported".to_string());
+        PathBuf::from(format!("{}.md", stem))
+    });
 
-    let output_path = if config.output.create_subdirs {
-        let platform_dir = output_dir.join(platform.to_string());
-        if !platform_dir.exists() {
-            fs::create_dir_all(&platform_dir).await?;
+    // markflow 输出中嵌入了源文时直接还原
+    if let (ImportSource::File(path), "html" | "htm") = (&source, extension.as_str()) {
+        let html = String::from_utf8_lossy(&fs::read(path).await?).into_owned();
+        if let Some(markdown) = extract_source(&html) {
+            if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+                fs::create_dir_all(parent).await?;
+            }
+            write_atomic(&output, &markdown)?;
+            status!("已从嵌入的源文还原: {}", output.display());
+            return Ok(());
         }
-        platform_dir.join(filename)
-    } else {
-        output_dir.join(filename)
+    }
+
+    info!("导入文章: {:?}", source);
+    let result = match (&source, extension.as_str()) {
+        // docx 和 Notion 的图片内嵌在源文件中，总是导出到 assets 目录
+        (ImportSource::File(path), "docx") => import_document(
+            convert_docx(std::fs::File::open(path)?)?,
+            &path.display().to_string(),
+            &import_options(&output, true),
+        )?,
+        (ImportSource::File(path), "md") => import_document(
+            read_notion_file(path)?.document,
+            &path.display().to_string(),
+            &import_options(&output, true),
+        )?,
+        _ => import_html(&source, &import_options(&output, !no_images)).await?,
     };
+    write_import_result(&output, &result).await
+}
 
-    // 写入文件
-    fs::write(&output_path, html).await?;
+/// 图片保存在输出文件旁的 assets/<文件名>/ 下，避免多篇文章的图片互相覆盖
+fn import_options(output: &std::path::Path, save_images: bool) -> ImportOptions {
+    let stem = output
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "imported".to_string());
+    ImportOptions {
+        assets_dir: save_images.then(|| {
+            output
+                .parent()
+                .unwrap_or_else(|| std::path::Path::new(""))
+                .join("assets")
+                .join(&stem)
+        }),
+        assets_prefix: format!("assets/{}/", stem),
+    }
+}
 
-    info!("已保存到: {:?}", output_path);
+async fn write_import_result(output: &std::path::Path, result: &ImportResult) -> Result<()> {
+    if let Some(parent) = output.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).await?;
+    }
+    write_atomic(output, &result.markdown)?;
 
-    // 备份功能
-    if config.general.backup_enabled {
-        if let Some(backup_dir) = &config.output.backup_dir {
-            backup_file(&output_path, backup_dir).await?;
-        }
+    status!(
+        "已导入: {} -> {}",
+        result.article.title.as_deref().unwrap_or("（无标题）"),
+        output.display()
+    );
+    if !result.downloaded_images.is_empty() {
+        status!("保存图片 {} 张", result.downloaded_images.len());
+    }
+    for image in &result.failed_images {
+        warn!("图片未能保存，保留原地址: {}", image);
     }
 
     Ok(())
 }
 
-fn generate_filename(title: &str, platform: &Platform, pattern: &str) -> String {
-    // 清理标题作为文件名
-    let safe_title = title
-        .chars()
-        .map(|c| match c {
-            '/' | '\\' | '?' | '%' | '*' | ':' | '|' | '"' | '<' | '>' => '_',
-            c => c,
-        })
-        .collect::<String>();
-
-    // 应用模式
-    pattern
-        .replace("{title}", &safe_title)
-        .replace("{platform}", &platform.to_string())
-        .replace(
-            "{timestamp}",
-            &chrono::Utc::now().format("%Y%m%d_%H%M%S").to_string(),
-        )
+pub async fn lint_command(paths: Vec<PathBuf>) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let linter = Linter::from_config(&config.lint)?;
+
+    let mut files = Vec::new();
+    for path in &paths {
+        collect_markdown_files(path, &mut files)?;
+    }
+
+    let mut errors = 0;
+    let mut total = 0;
+    for file in &files {
+        let markdown = fs::read_to_string(file).await?;
+        for finding in linter.lint(&markdown) {
+            total += 1;
+            if finding.severity == LintSeverity::Error {
+                errors += 1;
+            }
+            let location = match finding.line {
+                Some(line) => format!("{}:{}", file.display(), line),
+                None => file.display().to_string(),
+            };
+            println!(
+                "{}: {} [{}] {}",
+                location, finding.severity, finding.rule, finding.message
+            );
+        }
+    }
+
+    info!("检查了 {} 个文件，发现 {} 个问题", files.len(), total);
+    if errors > 0 {
+        return Err(crate::error::Error::Validation(format!(
+            "lint 发现 {} 个错误",
+            errors
+        )));
+    }
+    Ok(())
 }
 
-async fn backup_file(source: &PathBuf, backup_dir: &PathBuf) -> Result<()> {
-    if !backup_dir.exists() {
-        fs::create_dir_all(backup_dir).await?;
+pub async fn fmt_command(paths: Vec<PathBuf>, check: bool) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let formatter = SourceFormatter::from_config(&config.format)?;
+
+    let mut files = Vec::new();
+    for path in &paths {
+        collect_markdown_files(path, &mut files)?;
     }
 
-    let filename = source
-        .file_name()
-        .ok_or_else(|| crate::error::Error::Other("无法获取文件名".to_string()))?;
+    let mut changed = 0;
+    for file in &files {
+        let markdown = fs::read_to_string(file).await?;
+        let formatted = formatter.format(&markdown)?;
+        if formatted == markdown {
+            continue;
+        }
 
-    let timestamp = chrono::Utc::now().format("%Y%m%d_%H%M%S");
-    let backup_filename = format!("{}_{}", timestamp, filename.to_string_lossy());
-    let backup_path = backup_dir.join(backup_filename);
+        changed += 1;
+        if check {
+            println!("{}", file.display());
+        } else {
+            write_atomic(file, formatted)?;
+            status!("已格式化 {}", file.display());
+        }
+    }
 
-    fs::copy(source, &backup_path).await?;
-    debug!("已备份到: {:?}", backup_path);
+    if check {
+        info!("检查了 {} 个文件，{} 个需要格式化", files.len(), changed);
+        if changed > 0 {
+            return Err(crate::error::Error::Validation(format!(
+                "{} 个文件需要格式化",
+                changed
+            )));
+        }
+    } else {
+        info!("已格式化 {} 个文件（共 {} 个）", changed, files.len());
+    }
+    Ok(())
+}
+
+pub async fn compile_command(series: String, output: Option<PathBuf>) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let store = ContentStore::open_default()?;
+    let articles = series_articles(store.records(), &series)?;
+    drop(store);
+
+    let compiler = SeriesCompiler::new(SourceFormatter::from_config(&config.format)?);
+    let compiled = compiler.compile(&series, &articles)?;
+    let output = output.unwrap_or_else(|| PathBuf::from(format!("{}.md", pinyin_slug(&series))));
+    write_atomic(&output, compiled)?;
+
+    for (i, article) in articles.iter().enumerate() {
+        status!(
+            "  {}. {}（{}）",
+            i + 1,
+            article.title,
+            article.source.display()
+        );
+    }
+    info!("已合并 {} 篇文章到 {}", articles.len(), output.display());
+    Ok(())
+}
+
+pub async fn analyze_command(file: PathBuf, top: usize) -> Result<()> {
+    let markdown = fs::read_to_string(&file).await?;
+    let processor = MarkdownProcessor::new();
+    let content = processor.process(&markdown)?;
+
+    println!("关键词密度:");
+    for stat in keyword_density(&content.markdown, top) {
+        println!(
+            "  {:<12} {:>4} 次  {:>5.2}%",
+            stat.word,
+            stat.count,
+            stat.density * 100.0
+        );
+    }
+    println!(
+        "建议标签: {}",
+        suggest_tags(&content.markdown, 5).join(", ")
+    );
+    println!("建议 slug: {}", slugify(&content.title));
+
+    Ok(())
+}
+
+pub async fn snapshot_command(
+    input: PathBuf,
+    platform: Option<Platform>,
+    dir: Option<PathBuf>,
+    update: bool,
+) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let dir = dir.unwrap_or_else(|| config.snapshot.dir.clone());
+    fs::create_dir_all(&dir).await?;
+
+    // 各平台截图复用同一个浏览器会话；`snapshot.device` 优先于 `browser.device`
+    let mut pool = BrowserPool::from_config(&config);
+    if let Some(device) = &config.snapshot.device {
+        pool = pool.with_device(Some(config.device_profile(device)?));
+    }
+    let result = capture_snapshots(&input, platform, &dir, update, &config, &pool).await;
+    pool.shutdown().await;
+    result
+}
+
+async fn capture_snapshots(
+    input: &Path,
+    platform: Option<Platform>,
+    dir: &Path,
+    update: bool,
+    config: &AppConfig,
+    pool: &BrowserPool,
+) -> Result<()> {
+    let markdown = fs::read_to_string(input).await?;
+    let processor = MarkdownProcessor::new()
+        .with_timezone(config.timezone())
+        .with_autolink(config.links.autolink)
+        .with_smart_punctuation(config.smart_punctuation())
+        .with_math(config.math.enabled)
+        .with_permalink(config.permalink())
+        .with_inherited_front_matter(inherited_front_matter(input)?);
+    let pipeline = ProcessingPipeline::from_config_for_source(config, input)?;
+    let content = pipeline.process(processor.process(&markdown)?).await?;
+    let stem = input
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "snapshot".to_string());
+
+    let mut failed = Vec::new();
+    for target_platform in determine_target_platforms(platform, config) {
+        let adapter = create_adapter(&target_platform, config)?;
+        let mut platform_report = PlatformReport::new(target_platform.clone());
+        let platform_content =
+            pipeline.process_for_platform(&content, &target_platform, &mut platform_report)?;
+        let html = adapter.adapt_html_with_report(&platform_content.html, &mut platform_report)?;
+
+        let name = format!("{}.{}", stem, target_platform);
+        let page = dir.join(format!("{}.html", name));
+        fs::write(&page, snapshot_document(&html)).await?;
+        let screenshot = capture_screenshot(&page, &config.snapshot, pool).await?;
+
+        let baseline_path = dir.join(format!("{}.png", name));
+        let actual_path = dir.join(format!("{}.actual.png", name));
+        let diff_path = dir.join(format!("{}.diff.png", name));
+
+        if update || !baseline_path.exists() {
+            write_atomic(&baseline_path, &screenshot)?;
+            let _ = fs::remove_file(&actual_path).await;
+            let _ = fs::remove_file(&diff_path).await;
+            status!(
+                "✓ {} 已保存基准截图 {}",
+                target_platform,
+                baseline_path.display()
+            );
+            continue;
+        }
+
+        let baseline = fs::read(&baseline_path).await?;
+        let diff = compare_png(&baseline, &screenshot, config.snapshot.pixel_tolerance)?;
+        if diff.is_match(config.snapshot.max_diff_percent / 100.0) {
+            let _ = fs::remove_file(&actual_path).await;
+            let _ = fs::remove_file(&diff_path).await;
+            status!(
+                "✓ {} 与基准一致（差异 {:.3}%）",
+                target_platform,
+                diff.ratio * 100.0
+            );
+        } else {
+            write_atomic(&actual_path, &screenshot)?;
+            write_atomic(&diff_path, &diff.diff_png)?;
+            println!(
+                "✗ {} 与基准不一致：{} 个像素不同（{:.3}%），差异图 {}",
+                target_platform,
+                diff.different_pixels,
+                diff.ratio * 100.0,
+                diff_path.display()
+            );
+            failed.push(target_platform.to_string());
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(crate::error::Error::Validation(format!(
+            "截图与基准不一致: {}（确认无误后使用 --update 更新基准）",
+            failed.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+fn collect_markdown_files(path: &std::path::Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    if path.is_dir() {
+        let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .collect();
+        entries.sort();
+        for entry in entries {
+            let hidden = entry
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with('.'));
+            if !hidden {
+                collect_markdown_files(&entry, files)?;
+            }
+        }
+    } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
+        if !is_directory_defaults_file(path) {
+            files.push(path.to_path_buf());
+        }
+    } else if !path.exists() {
+        return Err(crate::error::Error::IO(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!("路径不存在: {:?}", path),
+        )));
+    }
+    Ok(())
+}
+
+pub async fn content_command(action: ContentAction) -> Result<()> {
+    match action {
+        ContentAction::Due { date } => {
+            let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+            let today = match date {
+                Some(date) => {
+                    chrono::NaiveDate::parse_from_str(&date, "%Y-%m-%d").map_err(|e| {
+                        crate::error::Error::Other(format!("无效的日期 {}: {}", date, e))
+                    })?
+                }
+                None => config.timezone().now().date_naive(),
+            };
+
+            let store = ContentStore::open_default()?;
+            let due = store.due_for_review(today);
+            if due.is_empty() {
+                println!("没有到期需要复查的文章");
+                return Ok(());
+            }
+
+            for record in due {
+                let review_after = record.review_after.unwrap_or(today);
+                let source = record
+                    .source
+                    .as_ref()
+                    .map(|s| s.display().to_string())
+                    .unwrap_or_else(|| record.id.to_string());
+                println!(
+                    "{}\t已过期 {} 天\t{}\t{}",
+                    review_after,
+                    (today - review_after).num_days(),
+                    record.title,
+                    source
+                );
+            }
+        }
+        ContentAction::Submit { target, by, note } => {
+            transition_content(&target, WorkflowState::InReview, by, note)?
+        }
+        ContentAction::Approve { target, by, note } => {
+            transition_content(&target, WorkflowState::Approved, by, note)?
+        }
+        ContentAction::Reject { target, by, note } => {
+            transition_content(&target, WorkflowState::Draft, by, note)?
+        }
+        ContentAction::Status { target } => {
+            let store = ContentStore::open_default()?;
+            match target {
+                Some(target) => {
+                    let record = find_record(&store, &target)?;
+                    println!("《{}》: {}", record.title, record.workflow.state);
+                    for transition in &record.workflow.history {
+                        println!(
+                            "  {}\t{} → {}\t{}\t{}",
+                            transition.at.format("%Y-%m-%d %H:%M"),
+                            transition.from,
+                            transition.to,
+                            transition.by.as_deref().unwrap_or("-"),
+                            transition.note.as_deref().unwrap_or("")
+                        );
+                    }
+                }
+                None => {
+                    for record in store.records() {
+                        println!("{}\t{}\t{}", record.workflow.state, record.id, record.title);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 变更文章的审核状态并写回台账
+fn transition_content(
+    target: &str,
+    state: WorkflowState,
+    by: Option<String>,
+    note: Option<String>,
+) -> Result<()> {
+    let mut store = ContentStore::open_default()?;
+    let id = find_record(&store, target)?.id;
+    let by = by.or_else(|| {
+        std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .ok()
+    });
+
+    let record = store.get_mut(&id).expect("find_record 返回的记录一定存在");
+    record.workflow.transition(state, by, note)?;
+    status!("《{}》: {}", record.title, state);
+    store.save()
+}
+
+pub async fn workspace_command(action: WorkspaceAction) -> Result<()> {
+    let manager = WorkspaceManager::new(WorkspaceManager::default_root());
+
+    match action {
+        WorkspaceAction::List => {
+            for name in manager.list()? {
+                let workspace = manager.get(&name)?;
+                println!("{}\t{}", name, workspace.root().display());
+            }
+        }
+        WorkspaceAction::Create { name } => {
+            let workspace = manager.create(&name)?;
+            info!("已创建工作区 {}: {:?}", name, workspace.root());
+            info!(
+                "Web 服务中访问该工作区需要密钥，请运行 markflow workspace token {} --role editor 生成",
+                name
+            );
+        }
+        WorkspaceAction::Token { name, role, label } => {
+            let role: Role = role.parse()?;
+            let mut workspace = manager.get(&name)?;
+            let token = uuid::Uuid::new_v4().simple().to_string();
+            workspace.add_api_key(token.clone(), role, label)?;
+            info!("已为工作区 {} 生成 {} 密钥，请妥善保存：", name, role);
+            println!("{}", token);
+        }
+        WorkspaceAction::Export { file, workspace } => {
+            let workspace = manager.get(&workspace)?;
+            let summary = export_workspace(&workspace, &file)?;
+            info!(
+                "已导出工作区 {}（{} 个文件）到 {:?}",
+                workspace.name(),
+                summary.files,
+                file
+            );
+        }
+        WorkspaceAction::Import {
+            file,
+            workspace,
+            force,
+        } => {
+            let workspace = match manager.get(&workspace) {
+                Ok(workspace) => workspace,
+                Err(_) => manager.create(&workspace)?,
+            };
+            let summary = import_workspace(&workspace, &file, force)?;
+            info!(
+                "已导入 {} 个文件到工作区 {}（密钥需要重新配置）",
+                summary.files,
+                workspace.name()
+            );
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn open_command(target: String, platform: Option<Platform>, local: bool) -> Result<()> {
+    let store = ContentStore::open_default()?;
+    let record = find_record(&store, &target)?;
+
+    let platform_name = match platform {
+        Some(Platform::All) | None => None,
+        Some(platform) => Some(platform.to_string()),
+    };
+
+    let url = if local {
+        None
+    } else {
+        published_url(record, platform_name.as_deref())
+    };
+
+    let location = match url {
+        Some(url) => url,
+        None => {
+            let output = match &platform_name {
+                Some(name) => record.outputs.get(name),
+                None => record.outputs.values().next(),
+            }
+            .ok_or_else(|| {
+                crate::error::Error::Other(format!("《{}》没有生成的输出文件", record.title))
+            })?;
+            output.display().to_string()
+        }
+    };
+
+    info!("打开: {}", location);
+    open_in_browser(&location)
+}
+
+pub async fn copy_command(target: String, platform: Option<Platform>) -> Result<()> {
+    let store = ContentStore::open_default()?;
+    let record = find_record(&store, &target)?;
+
+    let output = match platform {
+        Some(Platform::All) | None => record.outputs.values().next(),
+        Some(platform) => record.outputs.get(&platform.to_string()),
+    }
+    .ok_or_else(|| crate::error::Error::Other(format!("《{}》没有生成的输出文件", record.title)))?;
+
+    let html = fs::read_to_string(output).await?;
+    copy_html(&html)?;
+    info!("已复制《{}》的 {:?} 到剪贴板", record.title, output);
+    Ok(())
+}
+
+// 辅助函数
+/// 写入活动日志（邮件摘要的数据来源），失败时只记录警告
+fn log_activity(activity: Activity) {
+    if let Err(e) = ActivityLog::open_default().append(activity) {
+        warn!("写入活动日志失败: {}", e);
+    }
+}
+
+fn log_publish_activity(title: &str, platform: &Platform, result: &Result<PublishResult>) {
+    let result = match result {
+        Ok(result) => result.clone(),
+        Err(e) => PublishResult {
+            platform: platform.clone(),
+            url: None,
+            draft_id: None,
+            publish_id: None,
+            status: PublishStatus::Failed,
+            message: e.to_string(),
+            title_variant: None,
+        },
+    };
+    log_activity(Activity::Published {
+        title: title.to_string(),
+        result,
+    });
+}
+
+pub async fn bundle_command(target: String, output: Option<PathBuf>) -> Result<()> {
+    let store = ContentStore::open_default()?;
+    let record = find_record(&store, &target)?;
+    let output = output.unwrap_or_else(|| {
+        let id = record.id.simple().to_string();
+        PathBuf::from(format!("{}-{}.zip", slugify(&record.title), &id[..8]))
+    });
+
+    let summary = export_bundle(record, &output)?;
+    for missing in &summary.missing {
+        warn!("文件已不存在，未打包: {:?}", missing);
+    }
+    info!(
+        "已打包《{}》（{} 个文件）到 {:?}",
+        record.title, summary.files, output
+    );
+    Ok(())
+}
+
+pub async fn list_command() -> Result<()> {
+    let store = ContentStore::open_default()?;
+    if store.records().is_empty() {
+        println!("台账中还没有文章（请先运行 process）");
+        return Ok(());
+    }
+
+    for record in store.records() {
+        let mut platforms: Vec<String> = record
+            .outputs
+            .keys()
+            .filter(|key| !key.contains('.'))
+            .cloned()
+            .collect();
+        for publication in &record.publications {
+            let platform = publication.platform.to_string();
+            if !platforms.contains(&platform) {
+                platforms.push(platform);
+            }
+        }
+        let states: Vec<String> = platforms
+            .iter()
+            .map(|platform| match record.latest_publication(platform) {
+                Some(publication) => format!("{}:{}", platform, publication.status),
+                None => format!("{}:未发布", platform),
+            })
+            .collect();
+        println!(
+            "{}\t{}\t{}\t{}",
+            record.id,
+            record.updated_at.format("%Y-%m-%d %H:%M"),
+            record.title,
+            states.join(" ")
+        );
+    }
+    Ok(())
+}
+
+pub async fn status_command(target: String) -> Result<()> {
+    let store = ContentStore::open_default()?;
+    let record = find_record(&store, &target)?;
+
+    println!("《{}》", record.title);
+    println!("  ID: {}", record.id);
+    if let Some(source) = &record.source {
+        println!("  源文件: {}", source.display());
+    }
+    println!(
+        "  内容哈希: {}",
+        &record.content_hash[..16.min(record.content_hash.len())]
+    );
+    println!("  审核状态: {}", record.workflow.state);
+    println!(
+        "  更新时间: {}",
+        record.updated_at.format("%Y-%m-%d %H:%M:%S")
+    );
+
+    if !record.outputs.is_empty() {
+        println!("输出:");
+        for (platform, path) in &record.outputs {
+            println!("  {}\t{}", platform, path.display());
+        }
+    }
+
+    if record.publications.is_empty() {
+        println!("发布: 无");
+    } else {
+        println!("发布:");
+        for publication in &record.publications {
+            println!(
+                "  {}\t{}\t草稿 {}\t{}\t{}",
+                publication.platform,
+                publication.status,
+                publication.draft_id.as_deref().unwrap_or("-"),
+                publication.url.as_deref().unwrap_or("-"),
+                publication.message
+            );
+        }
+    }
+    Ok(())
+}
+
+pub async fn jobs_command(action: JobsAction) -> Result<()> {
+    let store = JobStore::in_data_root(&WorkspaceManager::default_root());
+    match action {
+        JobsAction::List { workspace, status } => {
+            let jobs: Vec<_> = store
+                .list()?
+                .into_iter()
+                .filter(|job| workspace.as_ref().is_none_or(|w| &job.workspace == w))
+                .filter(|job| {
+                    status
+                        .as_ref()
+                        .is_none_or(|s| job.status.as_str().eq_ignore_ascii_case(s))
+                })
+                .collect();
+            if jobs.is_empty() {
+                println!("没有任务");
+                return Ok(());
+            }
+            for job in jobs {
+                println!(
+                    "{}	{}	{}	{}	{}	{}",
+                    &job.id.simple().to_string()[..8],
+                    job.status.as_str(),
+                    job.workspace,
+                    job.request.describe(),
+                    job.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    job.error.as_deref().unwrap_or_default()
+                );
+            }
+        }
+        JobsAction::Cancel { id } => {
+            let job = store.cancel(&store.find(&id)?.id)?;
+            info!("已取消任务 {}", job.id);
+        }
+        JobsAction::Retry { id } => {
+            let job = store.retry(&store.find(&id)?.id)?;
+            info!(
+                "任务 {} 已重新排队，运行中的 Web 服务会在几秒内开始执行",
+                job.id
+            );
+        }
+    }
+    Ok(())
+}
+
+/// 按内容 ID 或源文件路径查找台账记录
+fn find_record<'a>(store: &'a ContentStore, target: &str) -> Result<&'a ContentRecord> {
+    match uuid::Uuid::parse_str(target) {
+        Ok(id) => store.get(&id),
+        Err(_) => {
+            let path = PathBuf::from(target);
+            let path = std::fs::canonicalize(&path).unwrap_or(path);
+            store.find_by_source(&path)
+        }
+    }
+    .ok_or_else(|| {
+        crate::error::Error::Other(format!(
+            "台账中没有找到内容: {}（请先运行 process）",
+            target
+        ))
+    })
+}
+
+fn record_in_ledger(
+    input: &PathBuf,
+    markdown: &str,
+    content: &crate::core::Content,
+    article_report: &ArticleReport,
+    html: std::collections::BTreeMap<String, String>,
+) -> Result<()> {
+    let mut store = ContentStore::open_default()?;
+    let source = std::fs::canonicalize(input).unwrap_or_else(|_| input.clone());
+
+    let canonical = |path: &PathBuf| std::fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+    let mut outputs = std::collections::BTreeMap::new();
+    for platform in &article_report.platforms {
+        let name = platform.platform.to_string();
+        if let Some(output) = &platform.output {
+            outputs.insert(name.clone(), canonical(output));
+        }
+        for (label, output) in &platform.variant_outputs {
+            outputs.insert(variant_output_key(&name, label), canonical(output));
+        }
+    }
+    let title_variants = title_variants(&content.metadata)
+        .into_iter()
+        .map(|v| (v.label, v.title))
+        .collect();
+
+    let (publications, workflow) = store
+        .find_by_source(&source)
+        .map(|r| (r.publications.clone(), r.workflow.clone()))
+        .unwrap_or_default();
+
+    let id = store.upsert(ContentRecord {
+        id: content.id,
+        title: content.title.clone(),
+        source: Some(source),
+        content_hash: content_hash(markdown.as_bytes()),
+        outputs,
+        html,
+        publications,
+        review_after: content.metadata.review_after,
+        title_variants,
+        tags: content.metadata.tags.clone(),
+        slug: Some(content.slug()),
+        canonical_url: content.metadata.canonical_url.clone(),
+        workflow,
+        report: Some(article_report.clone()),
+        updated_at: chrono::Utc::now(),
+    });
+    store.save()?;
+
+    debug!("已记录到台账: {}", id);
+    Ok(())
+}
+
+/// 台账中已发布的文章地址或微信草稿编辑地址
+fn published_url(record: &ContentRecord, platform: Option<&str>) -> Option<String> {
+    record
+        .publications
+        .iter()
+        .rev()
+        .filter(|p| platform.is_none_or(|name| p.platform.to_string() == name))
+        .find_map(|p| {
+            p.url.clone().or_else(|| match (&p.platform, &p.draft_id) {
+                (crate::core::Platform::WeChat, Some(draft_id)) => Some(format!(
+                    "https://mp.weixin.qq.com/cgi-bin/appmsg?t=media/appmsg_edit&action=edit&type=77&appmsgid={}",
+                    draft_id
+                )),
+                _ => None,
+            })
+        })
+}
+
+fn open_in_browser(location: &str) -> Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        // 不经过 cmd，地址中的 `&`（如公众号草稿地址的 `&token=`）不会被当作命令分隔符
+        let mut command = std::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+
+    command
+        .arg(location)
+        .spawn()
+        .map_err(|e| crate::error::Error::Other(format!("无法打开浏览器: {}", e)))?;
+
+    Ok(())
+}
+
+fn determine_target_platforms(platform: Option<Platform>, config: &AppConfig) -> Vec<Platform> {
+    match platform {
+        Some(platform) => platform.expand(),
+        None => {
+            // 使用配置中的默认平台，无法识别时回退到全部平台
+            config
+                .general
+                .default_platform
+                .as_deref()
+                .and_then(|name| name.parse::<Platform>().ok())
+                .unwrap_or(Platform::All)
+                .expand()
+        }
+    }
+}
+
+async fn save_output(
+    content: &crate::core::Content,
+    source: &std::path::Path,
+    html: &str,
+    platform: &Platform,
+    output_override: &Option<PathBuf>,
+    config: &AppConfig,
+) -> Result<(PathBuf, String)> {
+    let output_dir = output_override
+        .as_ref()
+        .unwrap_or(&config.output.output_dir);
+
+    // 生成相对路径，模式中可以包含目录，如 "{year}/{month}/{slug}/index.html"
+    let pattern = &config.output.filename_pattern;
+    let relative = generate_filename(content, platform, pattern, &config.timezone());
+
+    let base_dir = if config.output.create_subdirs {
+        output_dir.join(platform.to_string())
+    } else {
+        output_dir.clone()
+    };
+    let output_path = base_dir.join(relative);
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+
+    // 本地图片（包括 `<文章名>.assets/`、`images/` 中的图片）随文章复制到输出文件旁的 assets/，
+    // 输出目录与源文件不在一起时相对地址仍然有效；按文章分目录输出时直接对应静态站点的目录结构
+    let assets = crate::core::ArticleAssets::for_source(source);
+    let (html, copied) = crate::core::localize_assets(html, &assets, &output_path)?;
+    if !copied.is_empty() {
+        debug!(
+            "已复制 {} 张图片到 {:?}",
+            copied.len(),
+            output_path.parent()
+        );
+    }
+
+    write_encoded(&output_path, &html, platform, config).await?;
+
+    // 备份功能
+    if config.general.backup_enabled {
+        if let Some(backup_dir) = &config.output.backup_dir {
+            backup_file(&output_path, backup_dir, &config.timezone()).await?;
+        }
+    }
+
+    Ok((output_path, html))
+}
+
+/// 按平台配置的编码写入输出文件
+async fn write_encoded(
+    path: &std::path::Path,
+    html: &str,
+    platform: &Platform,
+    config: &AppConfig,
+) -> Result<()> {
+    let encoding = OutputEncoding::from_label(config.output.encoding_for(&platform.to_string()))?;
+    fs::write(path, encoding.encode_html(html)).await?;
+    info!("已保存到: {:?} ({})", path, encoding.name());
+    Ok(())
+}
+
+async fn write_report(
+    report: &ProcessingReport,
+    output_override: &Option<PathBuf>,
+    config: &AppConfig,
+) -> Result<()> {
+    let output_dir = output_override
+        .as_ref()
+        .unwrap_or(&config.output.output_dir);
+    let report_dir = output_dir.join("reports");
+    fs::create_dir_all(&report_dir).await?;
+
+    let timestamp = config.timezone().now().format("%Y%m%d_%H%M%S");
+    let (filename, body) = match config.output.report_format.as_str() {
+        "html" => (format!("report_{}.html", timestamp), report.to_html()),
+        _ => (format!("report_{}.md", timestamp), report.to_markdown()),
+    };
+
+    let report_path = report_dir.join(filename);
+    fs::write(&report_path, body).await?;
+    info!("处理报告已生成: {:?}", report_path);
+
+    Ok(())
+}
+
+fn generate_filename(
+    content: &crate::core::Content,
+    platform: &Platform,
+    pattern: &str,
+    timezone: &crate::core::TimeZoneSetting,
+) -> PathBuf {
+    let now = timezone.now();
+    // 归档目录按文章日期划分，没有日期时使用当前时间
+    let date = content.metadata.date.unwrap_or(now);
+    let vars = [
+        ("title", content.title.clone()),
+        ("slug", content.slug()),
+        ("platform", platform.to_string()),
+        ("timestamp", now.format("%Y%m%d_%H%M%S").to_string()),
+        ("date", now.format("%Y-%m-%d").to_string()),
+        ("year", date.format("%Y").to_string()),
+        ("month", date.format("%m").to_string()),
+        ("day", date.format("%d").to_string()),
+    ];
+    crate::core::render_output_path(pattern, &vars)
+}
+
+async fn backup_file(
+    source: &PathBuf,
+    backup_dir: &PathBuf,
+    timezone: &crate::core::TimeZoneSetting,
+) -> Result<()> {
+    if !backup_dir.exists() {
+        fs::create_dir_all(backup_dir).await?;
+    }
+
+    let filename = source
+        .file_name()
+        .ok_or_else(|| crate::error::Error::Other("无法获取文件名".to_string()))?;
+
+    let timestamp = timezone.now().format("%Y%m%d_%H%M%S");
+    let backup_filename = format!("{}_{}", timestamp, filename.to_string_lossy());
+    let backup_path = backup_dir.join(backup_filename);
+
+    fs::copy(source, &backup_path).await?;
+    debug!("已备份到: {:?}", backup_path);
+
+    Ok(())
+}
+
+pub async fn doctor_command() -> Result<()> {
+    let config_path = AppConfig::get_config_path();
+    let checks = match AppConfig::load_from_file(&config_path) {
+        Ok(config) => run_doctor(&config).await,
+        Err(e) => vec![CheckResult::new("config", CheckStatus::Fail, e.to_string())
+            .with_fix(format!("修正 {} 中的语法错误", config_path.display()))],
+    };
+
+    for check in &checks {
+        println!(
+            "{} {:<12} {}",
+            check.status.symbol(),
+            check.name,
+            check.message
+        );
+        if let (Some(fix), false) = (&check.fix, check.status == CheckStatus::Ok) {
+            println!("    修复: {}", fix);
+        }
+    }
+
+    let failed = checks
+        .iter()
+        .filter(|c| c.status == CheckStatus::Fail)
+        .count();
+    if failed > 0 {
+        return Err(crate::error::Error::Config(format!(
+            "{} 项检查未通过",
+            failed
+        )));
+    }
+    status!("环境检查通过");
+    Ok(())
+}
+
+pub async fn plugin_command(action: PluginAction) -> Result<()> {
+    let config_path = AppConfig::get_config_path();
+    let config = AppConfig::load_from_file(&config_path)?;
+    let manager = PluginManager::new(&config.plugins.dir);
+
+    match action {
+        PluginAction::List => {
+            for (name, plugin) in manager.list()? {
+                match plugin {
+                    Ok(plugin) => {
+                        let manifest = plugin.manifest();
+                        let enabled = config.plugins.enabled.contains(&name);
+                        println!(
+                            "{}\t{}\t{}\t{}",
+                            name,
+                            manifest.version,
+                            manifest.stage,
+                            if enabled { "已启用" } else { "未启用" }
+                        );
+                    }
+                    Err(e) => println!("{}\t无效: {}", name, e),
+                }
+            }
+        }
+        PluginAction::Install { source, force } => {
+            let plugin = manager.install(&source, force)?;
+            status!(
+                "已安装插件 {} {}（{} 阶段），运行 markflow plugin enable {} 启用",
+                plugin.name(),
+                plugin.manifest().version,
+                plugin.manifest().stage,
+                plugin.name()
+            );
+        }
+        PluginAction::Remove { name } => {
+            manager.remove(&name)?;
+            AppConfig::update_file(&config_path, |config| {
+                config.plugins.enabled.retain(|n| n != &name);
+                Ok(())
+            })?;
+            status!("已删除插件 {}", name);
+        }
+        PluginAction::Enable { name } => {
+            // 启用前校验清单，避免处理时才发现插件无效
+            manager.get(&name)?;
+            AppConfig::update_file(&config_path, |config| {
+                if !config.plugins.enabled.contains(&name) {
+                    config.plugins.enabled.push(name.clone());
+                }
+                Ok(())
+            })?;
+            status!("已启用插件 {}", name);
+        }
+        PluginAction::Disable { name } => {
+            AppConfig::update_file(&config_path, |config| {
+                config.plugins.enabled.retain(|n| n != &name);
+                Ok(())
+            })?;
+            status!("已停用插件 {}", name);
+        }
+    }
+    Ok(())
+}
+
+pub async fn browser_command(action: BrowserAction) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let browser = &config.browser;
+
+    match action {
+        BrowserAction::Install => {
+            let spinner = progress::spinner("正在下载 Chrome for Testing...");
+            let install = install_browser(&browser.cache_dir).await?;
+            spinner.finish_and_clear();
+            status!("已安装浏览器 {}", install.version);
+            status!("  浏览器: {}", install.executable.display());
+            status!("  chromedriver: {}", install.driver.display());
+        }
+        BrowserAction::Status => {
+            let describe = |path: Option<PathBuf>| match path {
+                Some(path) => match major_version(&path) {
+                    Some(version) => format!("{}（{}）", path.display(), version),
+                    None => path.display().to_string(),
+                },
+                None => "未找到".to_string(),
+            };
+            if let Some(url) = &browser.webdriver_url {
+                println!("WebDriver 服务: {}", url);
+            }
+            println!("chromedriver: {}", describe(find_driver(browser)));
+            println!("浏览器: {}", describe(find_executable(browser)));
+            if let Some(install) = installed_browser(&browser.cache_dir) {
+                println!(
+                    "已下载: {}（{}）",
+                    install.version,
+                    browser.cache_dir.display()
+                );
+            }
+            println!("容器环境: {}", if is_container() { "是" } else { "否" });
+            println!("启动参数: {}", browser_args(browser).join(" "));
+            if let Some(device) = &browser.device {
+                let profile = config.device_profile(device)?;
+                println!(
+                    "模拟设备: {}（{}x{}）",
+                    device, profile.width, profile.height
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn notify_command(action: NotifyAction) -> Result<()> {
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+    let email = &config.notifications.email;
+
+    match action {
+        NotifyAction::Digest { force, dry_run } => {
+            if dry_run {
+                let (subject, body) = build_digest(
+                    &ActivityLog::open_default(),
+                    &DigestState::new(DigestState::default_path()),
+                    email.frequency.parse()?,
+                    chrono::Utc::now(),
+                )?;
+                println!("{}\n\n{}", subject, body);
+                return Ok(());
+            }
+
+            ensure_online("发送摘要邮件")?;
+            if send_digest(email, force).await? {
+                status!("已发送摘要邮件到 {}", email.to.join(", "));
+            } else {
+                status!("距上次发送未满一个周期，跳过（使用 --force 立即发送）");
+            }
+        }
+        NotifyAction::Test => {
+            ensure_online("发送测试邮件")?;
+            EmailNotifier::from_config(email)?
+                .send(
+                    "MarkFlow 测试邮件",
+                    "SMTP 配置正常，可以接收活动摘要。".to_string(),
+                )
+                .await?;
+            status!("已发送测试邮件到 {}", email.to.join(", "));
+        }
+    }
+    Ok(())
+}
+
+pub async fn self_update_command(check: bool) -> Result<()> {
+    ensure_online("检查更新")?;
+    let config = AppConfig::load_from_file(&AppConfig::get_config_path())?;
+
+    let spinner = progress::spinner("正在检查最新版本...");
+    let release = fetch_latest_release(&config.update.repository).await?;
+    spinner.finish_and_clear();
+    if !release.is_newer_than(CURRENT_VERSION) {
+        status!("已是最新版本 {}", CURRENT_VERSION);
+        return Ok(());
+    }
+    status!(
+        "发现新版本 {}（当前 {}）: {}",
+        release.version,
+        CURRENT_VERSION,
+        release.url
+    );
+    if check {
+        return Ok(());
+    }
+
+    let public_key = config.update.public_key.as_deref().ok_or_else(|| {
+        crate::error::Error::Config(
+            "未配置 update.public_key，无法校验发布包签名，拒绝安装".to_string(),
+        )
+    })?;
+    let asset_name = release_asset_name();
+    let (binary, signature) = match (
+        release.asset(&asset_name),
+        release.asset(&format!("{}.minisig", asset_name)),
+    ) {
+        (Some(binary), Some(signature)) => (binary, signature),
+        _ => {
+            return Err(crate::error::Error::Other(format!(
+                "版本 {} 没有提供带签名的 {} 发布包",
+                release.version, asset_name
+            )))
+        }
+    };
+
+    let client = http_client_for(NetworkOperation::Download)?;
+    let spinner = progress::spinner(format!("正在下载 {}...", asset_name));
+    let data = client
+        .get(&binary.download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    let signature = client
+        .get(&signature.download_url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    spinner.finish_and_clear();
 
+    verify_release_signature(&data, &signature, public_key)?;
+    replace_executable(&std::env::current_exe()?, &data)?;
+    status!("已更新到 {}", release.version);
     Ok(())
 }
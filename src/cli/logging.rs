@@ -0,0 +1,158 @@
+use crate::{cli::args::LoggingConfig, error::Error, Result};
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+use tracing_appender::{
+    non_blocking::{NonBlocking, WorkerGuard},
+    rolling::{RollingFileAppender, Rotation},
+};
+
+const LOG_FILE_PREFIX: &str = "markflow";
+const LOG_FILE_SUFFIX: &str = "log";
+
+/// 按配置创建日志文件写入器。返回的 guard 需要一直持有，drop 时会刷新剩余日志。
+pub fn file_writer(config: &LoggingConfig) -> Result<(NonBlocking, WorkerGuard)> {
+    std::fs::create_dir_all(&config.dir)?;
+
+    let rotation = match config.rotation.as_str() {
+        "size" => {
+            let writer = SizeRotatingWriter::new(
+                &config.dir,
+                config.max_size_mb.max(1) * 1024 * 1024,
+                config.max_files,
+            )?;
+            return Ok(tracing_appender::non_blocking(writer));
+        }
+        "minutely" => Rotation::MINUTELY,
+        "hourly" => Rotation::HOURLY,
+        "daily" => Rotation::DAILY,
+        "never" => Rotation::NEVER,
+        other => {
+            return Err(Error::Config(format!("无效的日志轮转方式: {}", other)));
+        }
+    };
+
+    let appender = RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(config.max_files.max(1))
+        .build(&config.dir)
+        .map_err(|e| Error::Config(format!("创建日志文件失败 {:?}: {}", config.dir, e)))?;
+
+    Ok(tracing_appender::non_blocking(appender))
+}
+
+/// 按文件大小轮转：`markflow.log` 写满后依次重命名为 `markflow.log.1`、`markflow.log.2`……
+pub struct SizeRotatingWriter {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl SizeRotatingWriter {
+    pub fn new(dir: &Path, max_bytes: u64, max_files: usize) -> Result<Self> {
+        let path = dir.join(format!("{}.{}", LOG_FILE_PREFIX, LOG_FILE_SUFFIX));
+        let file = Self::open(&path)?;
+        let written = file.metadata()?.len();
+
+        Ok(Self {
+            path,
+            file,
+            written,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    fn open(path: &Path) -> io::Result<File> {
+        OpenOptions::new().create(true).append(true).open(path)
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(format!(".{}", index));
+        PathBuf::from(name)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.max_files == 0 {
+            self.file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&self.path)?;
+        } else {
+            let _ = std::fs::remove_file(self.rotated_path(self.max_files));
+            for index in (1..self.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    std::fs::rename(&from, self.rotated_path(index + 1))?;
+                }
+            }
+            std::fs::rename(&self.path, self.rotated_path(1))?;
+            self.file = Self::open(&self.path)?;
+        }
+
+        self.written = 0;
+        Ok(())
+    }
+}
+
+impl Write for SizeRotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_size_rotation_keeps_max_files() {
+        let dir = TempDir::new().unwrap();
+        let mut writer = SizeRotatingWriter::new(dir.path(), 16, 2).unwrap();
+
+        for i in 0..5 {
+            writer
+                .write_all(format!("line-{:02}-abcdef\n", i).as_bytes())
+                .unwrap();
+        }
+        writer.flush().unwrap();
+
+        let current = std::fs::read_to_string(dir.path().join("markflow.log")).unwrap();
+        assert_eq!(current, "line-04-abcdef\n");
+        let previous = std::fs::read_to_string(dir.path().join("markflow.log.1")).unwrap();
+        assert_eq!(previous, "line-03-abcdef\n");
+        assert!(dir.path().join("markflow.log.2").exists());
+        assert!(!dir.path().join("markflow.log.3").exists());
+    }
+
+    #[test]
+    fn test_invalid_rotation() {
+        let dir = TempDir::new().unwrap();
+        let config = LoggingConfig {
+            dir: dir.path().to_path_buf(),
+            rotation: "weekly".to_string(),
+            ..LoggingConfig::default()
+        };
+        assert!(file_writer(&config).is_err());
+    }
+}
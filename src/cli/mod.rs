@@ -1,10 +1,23 @@
 pub mod args;
 pub mod commands;
+pub mod logging;
+pub mod progress;
 
-use crate::Result;
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use crate::{
+    core::{
+        process_capabilities, publish_capabilities, registered_platforms, Capability, Platform,
+    },
+    Result,
+};
+use clap::{
+    builder::{PossibleValue, TypedValueParser},
+    error::ErrorKind,
+    Parser, Subcommand, ValueEnum,
+};
+use std::{ffi::OsStr, path::PathBuf};
 use tracing::info;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::Layer;
 
 pub use args::*;
 pub use commands::*;
@@ -20,34 +33,79 @@ pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
 
-    /// 启用调试日志
-    #[arg(short, long, global = true)]
+    /// 启用调试日志（写在子命令之前时可简写为 -d）
+    #[arg(long, global = true)]
     pub debug: bool,
 
-    /// 配置文件路径
-    #[arg(short, long, global = true)]
+    /// 配置文件路径（写在子命令之前时可简写为 -c）
+    #[arg(long, global = true)]
     pub config: Option<PathBuf>,
+
+    /// `--debug` 的简写。子命令中的 `-d`、`-c` 另有含义（`watch -d <目录>`、`publish -c <内容>`），
+    /// 因此这两个简写不向子命令传递，只能写在子命令之前
+    #[arg(short = 'd', hide = true, conflicts_with = "quiet")]
+    debug_short: bool,
+
+    /// `--config` 的简写，只能写在子命令之前
+    #[arg(short = 'c', hide = true, value_name = "CONFIG")]
+    config_short: Option<PathBuf>,
+
+    /// 离线模式：跳过链接检查、图片下载等网络操作，发布命令直接报错
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// 安静模式：不显示进度条和状态信息，只输出错误和命令结果
+    #[arg(short, long, global = true, conflicts_with = "debug")]
+    pub quiet: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// 处理Markdown文件
     Process {
-        /// 输入的Markdown文件路径
-        #[arg(short, long)]
-        input: PathBuf,
+        /// 输入的Markdown文件或目录（目录会递归处理其中的 .md 文件；配合 --since 时为要检查的目录，默认当前目录）
+        #[arg(short, long, required_unless_present = "since")]
+        input: Option<PathBuf>,
 
         /// 输出目录（可选）
         #[arg(short, long)]
         output: Option<PathBuf>,
 
         /// 目标平台
-        #[arg(short, long)]
+        #[arg(short, long, value_parser = PlatformValueParser)]
         platform: Option<Platform>,
 
         /// 预览模式（不写入文件）
         #[arg(long)]
         preview: bool,
+
+        /// 只处理自该 git 引用（如 HEAD~1）以来变化的 Markdown 文件
+        #[arg(long)]
+        since: Option<String>,
+
+        /// 把每个处理阶段和适配步骤之后的 Markdown/HTML 按顺序编号导出到该目录，用于排查输出问题
+        #[arg(long, value_name = "DIR")]
+        debug_stages: Option<PathBuf>,
+
+        /// 把所有标题降低 N 级（如一级标题作为平台标题时正文从二级开始），覆盖 front matter 的 shift_headings
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(0..=5))]
+        shift_headings: Option<u8>,
+
+        /// 公众号主题 CSS 文件，覆盖 wechat.theme
+        #[arg(long, value_name = "FILE")]
+        theme: Option<PathBuf>,
+
+        /// 输入为目录时只处理相对路径匹配该模式的文件（如 "posts/**/*.md"）
+        #[arg(long, value_name = "PATTERN", conflicts_with = "since")]
+        glob: Option<String>,
+
+        /// 输入为目录时同时处理的文件数（默认为 CPU 核数）
+        #[arg(short, long, value_name = "N", conflicts_with = "since")]
+        jobs: Option<usize>,
+
+        /// 输入为目录时忽略缓存，重新处理所有文件
+        #[arg(long, conflicts_with = "since")]
+        force: bool,
     },
 
     /// 监控目录变化并自动处理
@@ -63,16 +121,33 @@ pub enum Commands {
         /// 文件模式匹配（如 "*.md"）
         #[arg(short, long, default_value = "*.md")]
         pattern: String,
+
+        /// 处理后为 front matter 含 `auto_draft: true` 的文章创建平台草稿
+        /// （平台和每小时上限见 publish.auto_draft_platforms、publish.auto_draft_max_per_hour）
+        #[arg(long)]
+        auto_draft: bool,
+
+        /// 在该端口启动实时预览（http://127.0.0.1:<端口>/），文件重新处理后浏览器中的各平台输出自动刷新
+        #[arg(long, value_name = "PORT")]
+        preview_port: Option<u16>,
     },
 
     /// 发布内容到平台
     Publish {
         /// 内容ID或文件路径
-        #[arg(short, long)]
-        content: String,
+        #[arg(short, long, required_unless_present = "dir", conflicts_with = "dir")]
+        content: Option<String>,
+
+        /// 批量发布目录中的平台输出文件（如 ./output/wechat），进度保存在该目录中，中断后可继续
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// 批量发布时两篇文章之间的间隔，如 30s、10m、1h
+        #[arg(long, default_value = "10m", requires = "dir")]
+        interval: String,
 
         /// 目标平台
-        #[arg(short, long)]
+        #[arg(short, long, value_parser = PlatformValueParser)]
         platform: Platform,
 
         /// 是否为草稿模式
@@ -87,7 +162,7 @@ pub enum Commands {
         port: u16,
 
         /// 绑定地址
-        #[arg(short, long, default_value = "127.0.0.1")]
+        #[arg(long, default_value = "127.0.0.1")]
         host: String,
 
         /// 静态文件目录
@@ -106,6 +181,316 @@ pub enum Commands {
         #[command(subcommand)]
         action: TemplateAction,
     },
+
+    /// Front matter 管理
+    Frontmatter {
+        #[command(subcommand)]
+        action: FrontmatterAction,
+    },
+
+    /// 把网页、微信公众号文章、Word 文档或 Notion 导出导入为 Markdown
+    Import {
+        /// 文章地址，或本地 .html/.docx 文件、Notion 导出的 .md/.zip
+        source: String,
+
+        /// 输出 Markdown 文件（默认根据来源生成；导入 Notion ZIP 时为输出目录）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// 不下载网页中的图片，保留原图片地址
+        #[arg(long)]
+        no_images: bool,
+    },
+
+    /// 工作区管理（Web 服务中按工作区隔离配置、模板和内容）
+    Workspace {
+        #[command(subcommand)]
+        action: WorkspaceAction,
+    },
+
+    /// 检查 Markdown 写作问题（如过度重复的词）
+    Lint {
+        /// 要检查的文件或目录
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+    },
+
+    /// 按统一风格格式化 Markdown 源文件（标题空行、列表标记、链接写法、折行）
+    Fmt {
+        /// 要格式化的文件或目录
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// 只检查是否需要格式化，不修改文件；有需要格式化的文件时返回错误
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// 把台账中同一系列的文章合并为一篇合集（Markdown），可再用 process 转换或导出 EPUB
+    Compile {
+        /// 系列名称（front matter `series`）
+        #[arg(long)]
+        series: String,
+
+        /// 输出文件（默认为系列名的 slug，如 rust-rumen.md）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// 分析文章的关键词密度，并给出标签和 slug 建议
+    Analyze {
+        /// Markdown 文件
+        file: PathBuf,
+
+        /// 显示的关键词数量
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+
+    /// 在无头浏览器中按手机宽度渲染各平台输出并截图，与基准截图比较
+    Snapshot {
+        /// Markdown 文件
+        input: PathBuf,
+
+        /// 目标平台（默认使用配置中的默认平台）
+        #[arg(short, long, value_parser = PlatformValueParser)]
+        platform: Option<Platform>,
+
+        /// 基准截图目录（默认取配置 snapshot.dir）
+        #[arg(long)]
+        dir: Option<PathBuf>,
+
+        /// 用本次截图覆盖基准截图
+        #[arg(long)]
+        update: bool,
+    },
+
+    /// 在浏览器中打开生成的HTML或已发布的文章
+    Open {
+        /// 内容ID或源文件路径
+        target: String,
+
+        /// 目标平台（默认取第一个有记录的平台）
+        #[arg(short, long, value_parser = PlatformValueParser)]
+        platform: Option<Platform>,
+
+        /// 总是打开本地生成的HTML，而不是平台上的草稿或文章
+        #[arg(long)]
+        local: bool,
+    },
+
+    /// 把生成的 HTML 以富文本复制到剪贴板，可直接粘贴到公众号等网页编辑器
+    Copy {
+        /// 内容ID或源文件路径
+        target: String,
+
+        /// 目标平台（默认取第一个有记录的平台）
+        #[arg(short, long, value_parser = PlatformValueParser)]
+        platform: Option<Platform>,
+    },
+
+    /// 把文章的源文件、各平台 HTML、图片、台账记录和处理报告打包为 zip，用于归档或交接
+    Bundle {
+        /// 内容ID或源文件路径
+        target: String,
+
+        /// 输出的 zip 文件（默认为当前目录下的 `<slug>-<ID前8位>.zip`）
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// 列出台账中处理过的文章及各平台最近的发布状态
+    List,
+
+    /// 查看文章的输出文件和各平台的发布记录（草稿 ID、地址）
+    Status {
+        /// 内容ID或源文件路径
+        target: String,
+    },
+
+    /// 管理台账中的文章
+    Content {
+        #[command(subcommand)]
+        action: ContentAction,
+    },
+
+    /// 管理 Web 服务的后台任务（任务表保存在数据根目录的 SQLite 数据库 jobs.db 中）
+    Jobs {
+        #[command(subcommand)]
+        action: JobsAction,
+    },
+
+    /// 诊断运行环境：配置、平台凭据、浏览器、模板目录和写权限
+    Doctor,
+
+    /// 无头浏览器管理（截图等浏览器自动化使用）
+    Browser {
+        #[command(subcommand)]
+        action: BrowserAction,
+    },
+
+    /// 插件管理（插件目录由 plugins.dir 配置）
+    Plugin {
+        #[command(subcommand)]
+        action: PluginAction,
+    },
+
+    /// 活动摘要邮件
+    Notify {
+        #[command(subcommand)]
+        action: NotifyAction,
+    },
+
+    /// 从 GitHub Releases 下载并安装最新版本（校验 minisign 签名）
+    SelfUpdate {
+        /// 只检查是否有新版本，不安装
+        #[arg(long)]
+        check: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ContentAction {
+    /// 列出已到复查日期（front matter `review_after`）的文章
+    Due {
+        /// 以该日期为准（YYYY-MM-DD，默认今天）
+        #[arg(long)]
+        date: Option<String>,
+    },
+
+    /// 提交审核（draft → in-review）
+    Submit {
+        /// 内容ID或文件路径
+        target: String,
+
+        /// 操作人（默认当前系统用户）
+        #[arg(long)]
+        by: Option<String>,
+
+        /// 备注
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// 批准发布（in-review → approved），记录批准人
+    Approve {
+        /// 内容ID或文件路径
+        target: String,
+
+        /// 批准人（默认当前系统用户）
+        #[arg(long)]
+        by: Option<String>,
+
+        /// 备注
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// 退回草稿重新修改
+    Reject {
+        /// 内容ID或文件路径
+        target: String,
+
+        /// 操作人（默认当前系统用户）
+        #[arg(long)]
+        by: Option<String>,
+
+        /// 退回原因
+        #[arg(long)]
+        note: Option<String>,
+    },
+
+    /// 查看审核状态；不指定内容时列出所有文章
+    Status {
+        /// 内容ID或文件路径
+        target: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum JobsAction {
+    /// 列出任务（最近创建的在后）
+    List {
+        /// 只列出该工作区的任务
+        #[arg(short, long)]
+        workspace: Option<String>,
+
+        /// 只列出该状态的任务：queued、running、completed、failed、cancelled
+        #[arg(short, long)]
+        status: Option<String>,
+    },
+
+    /// 取消排队中或执行中的任务（执行中的任务在处理完当前文档后停止）
+    Cancel {
+        /// 任务ID（可只写前几位）
+        id: String,
+    },
+
+    /// 把失败或已取消的任务重新排队，由运行中的 Web 服务重新执行
+    Retry {
+        /// 任务ID（可只写前几位）
+        id: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BrowserAction {
+    /// 下载最新稳定版的 Chrome for Testing 无头浏览器和配套的 chromedriver
+    Install,
+
+    /// 显示找到的浏览器、chromedriver、版本和启动参数
+    Status,
+}
+
+#[derive(Subcommand)]
+pub enum NotifyAction {
+    /// 汇总上次发送以来的处理、发布和失效链接并发送邮件（未到期时跳过，适合放在定时任务中）
+    Digest {
+        /// 不论是否到期都立即发送
+        #[arg(long)]
+        force: bool,
+
+        /// 只打印摘要内容，不发送
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// 发送一封测试邮件，检查 SMTP 配置
+    Test,
+}
+
+#[derive(Subcommand)]
+pub enum PluginAction {
+    /// 列出已安装的插件及其版本、阶段位置和启用状态
+    List,
+
+    /// 从本地目录安装插件（目录中需包含 plugin.toml）
+    Install {
+        /// 插件源目录
+        source: PathBuf,
+
+        /// 覆盖已安装的同名插件
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// 删除插件
+    Remove {
+        /// 插件名称
+        name: String,
+    },
+
+    /// 启用插件，按启用顺序执行
+    Enable {
+        /// 插件名称
+        name: String,
+    },
+
+    /// 停用插件
+    Disable {
+        /// 插件名称
+        name: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -131,6 +516,70 @@ pub enum ConfigAction {
     Init,
 }
 
+#[derive(Subcommand)]
+pub enum FrontmatterAction {
+    /// 补全缺失的 date、author、description、slug 字段并写回源文件
+    Fix {
+        /// Markdown 文件或目录（目录会递归查找 .md 文件）
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// 只显示将要补充的字段，不修改文件
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum WorkspaceAction {
+    /// 列出所有工作区
+    List,
+
+    /// 创建新工作区
+    Create {
+        /// 工作区名称（字母、数字、- 和 _）
+        name: String,
+    },
+
+    /// 为工作区生成 API 密钥
+    Token {
+        /// 工作区名称
+        name: String,
+
+        /// 角色：viewer（查看）、editor（处理与上传）、publisher（发布）
+        #[arg(long, default_value = "editor")]
+        role: String,
+
+        /// 备注，如密钥持有人
+        #[arg(long)]
+        label: Option<String>,
+    },
+
+    /// 把工作区导出为 tar.gz（配置中的密钥不会导出）
+    Export {
+        /// 导出文件路径
+        file: PathBuf,
+
+        /// 工作区名称
+        #[arg(long, default_value = "default")]
+        workspace: String,
+    },
+
+    /// 从 tar.gz 导入工作区
+    Import {
+        /// 归档文件路径
+        file: PathBuf,
+
+        /// 导入到的工作区，不存在时自动创建
+        #[arg(long, default_value = "default")]
+        workspace: String,
+
+        /// 覆盖已存在的文件
+        #[arg(long)]
+        force: bool,
+    },
+}
+
 #[derive(Subcommand)]
 pub enum TemplateAction {
     /// 列出所有模板
@@ -140,8 +589,8 @@ pub enum TemplateAction {
     Create {
         /// 模板名称
         name: String,
-        /// 模板文件路径
-        file: PathBuf,
+        /// 模板文件路径（省略时使用内置的页眉、署名页脚模板）
+        file: Option<PathBuf>,
     },
 
     /// 删除模板
@@ -156,53 +605,175 @@ pub enum TemplateAction {
         name: String,
         /// 输入文件
         input: PathBuf,
-        /// 输出文件
+        /// 输出文件（省略时输出到标准输出）
         output: Option<PathBuf>,
     },
 }
 
-#[derive(clap::ValueEnum, Clone, Debug)]
-pub enum Platform {
-    WeChat,
-    Zhihu,
-    All,
+/// `--platform` 的解析器：接受内置平台和已注册的自定义平台
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PlatformValueParser;
+
+impl TypedValueParser for PlatformValueParser {
+    type Value = Platform;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        _arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> std::result::Result<Self::Value, clap::Error> {
+        let value = value.to_string_lossy();
+        value.parse::<Platform>().map_err(|_| {
+            let choices: Vec<String> = self
+                .possible_values()
+                .into_iter()
+                .flatten()
+                .map(|v| v.get_name().to_string())
+                .collect();
+            clap::Error::raw(
+                ErrorKind::InvalidValue,
+                format!("无效的平台 '{}'（可选: {}）\n", value, choices.join(", ")),
+            )
+            .with_cmd(cmd)
+        })
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let builtin = Platform::value_variants()
+            .iter()
+            .filter_map(|p| p.to_possible_value());
+        let custom = registered_platforms()
+            .into_iter()
+            .map(|p| PossibleValue::new(p.to_string()));
+        Some(Box::new(builtin.chain(custom)))
+    }
 }
 
-impl std::fmt::Display for Platform {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Platform::WeChat => write!(f, "wechat"),
-            Platform::Zhihu => write!(f, "zhihu"),
-            Platform::All => write!(f, "all"),
+impl Cli {
+    /// 解析命令行参数，并把子命令之前的 `-d`、`-c` 合并到 `--debug`、`--config`
+    pub fn parse_args() -> Self {
+        Self::parse().merge_short_flags()
+    }
+
+    fn merge_short_flags(mut self) -> Self {
+        self.debug |= self.debug_short;
+        if self.config.is_none() {
+            self.config = self.config_short.take();
         }
+        self
     }
 }
 
 pub async fn run() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_args();
+    // `--config` 对本进程内所有配置读写生效，包括下面的日志和网络配置
+    AppConfig::set_config_path(cli.config.clone());
 
-    // 初始化日志
-    init_logging(cli.debug)?;
+    // 初始化日志：watch/serve 作为常驻进程时可额外写入 JSON 日志文件
+    let is_daemon = matches!(cli.command, Commands::Watch { .. } | Commands::Serve { .. });
+    progress::set_quiet(cli.quiet);
+    let _log_guard = init_logging(cli.debug, cli.quiet, is_daemon)?;
 
     info!("MarkFlow 启动中...");
 
-    match cli.command {
+    // 代理、根证书、离线模式等网络配置对本进程内所有 HTTP 请求生效；配置文件有误时由具体命令报告
+    let loaded = AppConfig::load_from_file(&AppConfig::get_config_path());
+    let config_valid = loaded.is_ok();
+    let config = loaded.unwrap_or_default();
+    let mut network = config.network.clone();
+    network.offline |= cli.offline;
+    if network.offline {
+        info!("离线模式：跳过所有网络操作");
+    }
+    crate::core::configure_network(network);
+
+    let update_check = if matches!(cli.command, Commands::SelfUpdate { .. }) {
+        None
+    } else {
+        start_update_check(config.update.clone())
+    };
+
+    // 命令依赖的浏览器、平台凭据等不可用时直接给出修复方法，不必执行到一半才失败；
+    // 配置文件有误时跳过，由具体命令报告配置错误
+    if config_valid {
+        required_capabilities(&cli.command, &config)
+            .and_then(|capabilities| crate::core::require_capabilities(&config, &capabilities))?;
+    }
+
+    let result = match cli.command {
         Commands::Process {
             input,
             output,
             platform,
             preview,
-        } => commands::process_command(input, output, platform, preview).await,
+            since,
+            debug_stages,
+            shift_headings,
+            theme,
+            glob,
+            jobs,
+            force,
+        } => match (since, input) {
+            (Some(since), input) => {
+                let directory = input.unwrap_or_else(|| PathBuf::from("."));
+                commands::process_changed_command(
+                    since,
+                    directory,
+                    output,
+                    platform,
+                    preview,
+                    debug_stages,
+                    shift_headings,
+                    theme,
+                )
+                .await
+            }
+            (None, Some(input)) if input.is_dir() => {
+                commands::process_directory_command(
+                    input,
+                    output,
+                    platform,
+                    preview,
+                    debug_stages,
+                    shift_headings,
+                    theme,
+                    commands::BatchOptions { glob, jobs, force },
+                )
+                .await
+            }
+            (None, Some(input)) => {
+                commands::process_command(
+                    input,
+                    output,
+                    platform,
+                    preview,
+                    debug_stages,
+                    shift_headings,
+                    theme,
+                )
+                .await
+            }
+            (None, None) => unreachable!("clap 保证未指定 --since 时必须提供 --input"),
+        },
         Commands::Watch {
             directory,
             output,
             pattern,
-        } => commands::watch_command(directory, output, pattern).await,
+            auto_draft,
+            preview_port,
+        } => commands::watch_command(directory, output, pattern, auto_draft, preview_port).await,
         Commands::Publish {
             content,
+            dir,
+            interval,
             platform,
             draft,
-        } => commands::publish_command(content, platform, draft).await,
+        } => match (content, dir) {
+            (_, Some(dir)) => commands::batch_publish_command(dir, platform, draft, interval).await,
+            (Some(content), None) => commands::publish_command(content, platform, draft).await,
+            (None, None) => unreachable!("clap 保证 --content 或 --dir 至少提供一个"),
+        },
         Commands::Serve {
             port,
             host,
@@ -210,24 +781,189 @@ pub async fn run() -> Result<()> {
         } => commands::serve_command(port, host, static_dir).await,
         Commands::Config { action } => commands::config_command(action).await,
         Commands::Template { action } => commands::template_command(action).await,
+        Commands::Frontmatter { action } => commands::frontmatter_command(action).await,
+        Commands::Import {
+            source,
+            output,
+            no_images,
+        } => commands::import_command(source, output, no_images).await,
+        Commands::Workspace { action } => commands::workspace_command(action).await,
+        Commands::Lint { paths } => commands::lint_command(paths).await,
+        Commands::Fmt { paths, check } => commands::fmt_command(paths, check).await,
+        Commands::Compile { series, output } => commands::compile_command(series, output).await,
+        Commands::Analyze { file, top } => commands::analyze_command(file, top).await,
+        Commands::Snapshot {
+            input,
+            platform,
+            dir,
+            update,
+        } => commands::snapshot_command(input, platform, dir, update).await,
+        Commands::Open {
+            target,
+            platform,
+            local,
+        } => commands::open_command(target, platform, local).await,
+        Commands::Copy { target, platform } => commands::copy_command(target, platform).await,
+        Commands::Bundle { target, output } => commands::bundle_command(target, output).await,
+        Commands::List => commands::list_command().await,
+        Commands::Status { target } => commands::status_command(target).await,
+        Commands::Content { action } => commands::content_command(action).await,
+        Commands::Jobs { action } => commands::jobs_command(action).await,
+        Commands::Doctor => commands::doctor_command().await,
+        Commands::Browser { action } => commands::browser_command(action).await,
+        Commands::Plugin { action } => commands::plugin_command(action).await,
+        Commands::Notify { action } => commands::notify_command(action).await,
+        Commands::SelfUpdate { check } => commands::self_update_command(check).await,
+    };
+
+    // 给后台的更新检查一点时间写入缓存，结果在下次启动时提示
+    if let Some(handle) = update_check {
+        let _ = tokio::time::timeout(std::time::Duration::from_secs(2), handle).await;
+    }
+    result
+}
+
+/// 命令开始前需要检查的能力
+fn required_capabilities(command: &Commands, config: &AppConfig) -> Result<Vec<Capability>> {
+    let capabilities = match command {
+        Commands::Process { .. } => process_capabilities(config),
+        Commands::Watch { auto_draft, .. } => {
+            let mut capabilities = process_capabilities(config);
+            if *auto_draft {
+                for platform in &config.publish.auto_draft_platforms {
+                    capabilities.extend(publish_capabilities(&platform.parse()?, config)?);
+                }
+            }
+            if config.notifications.email.enabled && !crate::core::is_offline() {
+                capabilities.push(Capability::Email);
+            }
+            capabilities
+        }
+        Commands::Publish { platform, .. } => publish_capabilities(platform, config)?,
+        Commands::Snapshot { .. } => vec![Capability::Browser],
+        Commands::Notify {
+            action: NotifyAction::Digest { dry_run: true, .. },
+        } => Vec::new(),
+        Commands::Notify { .. } => vec![Capability::Network, Capability::Email],
+        Commands::SelfUpdate { .. } => vec![Capability::Network],
+        _ => Vec::new(),
+    };
+    Ok(capabilities)
+}
+
+/// 开启 `update.check` 时提示已发现的新版本，并按间隔在后台刷新检查结果
+fn start_update_check(config: UpdateConfig) -> Option<tokio::task::JoinHandle<()>> {
+    if !config.check || crate::core::is_offline() {
+        return None;
+    }
+    let checker =
+        crate::core::UpdateChecker::new(config, crate::core::UpdateChecker::default_cache_path());
+    // 写到 stderr，不影响输出到管道的内容
+    if let Some(notice) = checker.notice().filter(|_| !progress::is_quiet()) {
+        eprintln!("{}", notice);
     }
+    if !checker.needs_refresh(chrono::Utc::now()) {
+        return None;
+    }
+    Some(tokio::spawn(async move {
+        if let Err(e) = checker.refresh().await {
+            tracing::debug!("检查更新失败: {}", e);
+        }
+    }))
 }
 
-fn init_logging(debug: bool) -> Result<()> {
-    use tracing_subscriber::{fmt, EnvFilter};
+fn init_logging(debug: bool, quiet: bool, is_daemon: bool) -> Result<Option<WorkerGuard>> {
+    use tracing_subscriber::{fmt, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
-    let level = if debug { "debug" } else { "info" };
+    let level = if debug {
+        "debug"
+    } else if quiet {
+        "error"
+    } else {
+        "info"
+    };
 
     let env_filter = EnvFilter::try_from_default_env()
         .unwrap_or_else(|_| EnvFilter::new(format!("markflow={}", level)));
 
-    fmt()
-        .with_env_filter(env_filter)
+    let console_layer = fmt::layer()
         .with_target(false)
         .with_thread_ids(false)
         .with_file(debug)
         .with_line_number(debug)
+        .with_filter(env_filter);
+
+    let logging_config = if is_daemon {
+        AppConfig::load_from_file(&AppConfig::get_config_path())
+            .map(|config| config.logging)
+            .unwrap_or_default()
+    } else {
+        LoggingConfig::default()
+    };
+
+    let mut guard = None;
+    let file_layer = if logging_config.file_enabled {
+        let (writer, worker_guard) = logging::file_writer(&logging_config)?;
+        guard = Some(worker_guard);
+        let file_level = if debug {
+            "debug"
+        } else {
+            logging_config.level.as_str()
+        };
+        Some(
+            fmt::layer()
+                .json()
+                .with_writer(writer)
+                .with_current_span(false)
+                .with_filter(EnvFilter::new(format!("markflow={}", file_level))),
+        )
+    } else {
+        None
+    };
+
+    tracing_subscriber::registry()
+        .with(console_layer)
+        .with(file_layer)
         .init();
 
-    Ok(())
+    Ok(guard)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_top_level_short_flags() {
+        let cli = Cli::try_parse_from(["markflow", "-d", "-c", "a.toml", "watch", "-d", "docs"])
+            .unwrap()
+            .merge_short_flags();
+        assert!(cli.debug);
+        assert_eq!(cli.config, Some(PathBuf::from("a.toml")));
+        assert!(
+            matches!(cli.command, Commands::Watch { directory, .. } if directory == std::path::Path::new("docs"))
+        );
+
+        let cli = Cli::try_parse_from(["markflow", "serve", "--host", "0.0.0.0"]).unwrap();
+        assert!(matches!(cli.command, Commands::Serve { host, .. } if host == "0.0.0.0"));
+    }
+
+    #[test]
+    fn test_config_flag_sets_config_path() {
+        let default_path = AppConfig::get_config_path();
+        let cli = Cli::try_parse_from(["markflow", "template", "list", "--config", "custom.toml"])
+            .unwrap()
+            .merge_short_flags();
+
+        AppConfig::set_config_path(cli.config);
+        assert_eq!(AppConfig::get_config_path(), PathBuf::from("custom.toml"));
+        AppConfig::set_config_path(None);
+        assert_eq!(AppConfig::get_config_path(), default_path);
+    }
 }
@@ -0,0 +1,64 @@
+use indicatif::{MultiProgress, ProgressBar, ProgressFinish, ProgressStyle};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        OnceLock,
+    },
+    time::Duration,
+};
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// `--quiet`：不显示进度条和状态信息，只输出错误和命令本身的结果
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// 所有进度条共用一个 MultiProgress，整体进度和单个文件的进度可以同时显示
+fn multi() -> &'static MultiProgress {
+    static MULTI: OnceLock<MultiProgress> = OnceLock::new();
+    MULTI.get_or_init(MultiProgress::new)
+}
+
+/// 带总数的进度条，如批量处理的文件数、上传的图片数。
+/// 安静模式或输出不是终端时不显示；进度条被 drop 时自动清除（包括出错提前返回）
+pub fn bar(len: usize, prefix: &str) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let bar = multi().add(ProgressBar::new(len as u64).with_finish(ProgressFinish::AndClear));
+    bar.set_style(
+        ProgressStyle::with_template("{prefix} [{bar:30}] {pos}/{len} {wide_msg}")
+            .unwrap()
+            .progress_chars("=> "),
+    );
+    bar.set_prefix(prefix.to_string());
+    bar
+}
+
+/// 无总数的进度提示，如单个文件的处理阶段、发布请求
+pub fn spinner(message: impl Into<String>) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let spinner = multi().add(ProgressBar::new_spinner().with_finish(ProgressFinish::AndClear));
+    spinner.set_style(ProgressStyle::with_template("{spinner} {wide_msg}").unwrap());
+    spinner.set_message(message.into());
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
+/// 输出状态信息（处理摘要等），安静模式下不输出
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if !$crate::cli::progress::is_quiet() {
+            println!($($arg)*);
+        }
+    };
+}
+
+pub(crate) use status;
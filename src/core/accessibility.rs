@@ -0,0 +1,236 @@
+use regex::Regex;
+use std::{collections::BTreeMap, fmt, sync::OnceLock};
+
+/// 一条无障碍问题
+#[derive(Debug, Clone, PartialEq)]
+pub enum A11yIssue {
+    /// 图片没有 alt 属性，`src` 为图片地址
+    MissingAlt { src: String },
+    /// 文字颜色与背景色对比度不足，`count` 为使用该配色的元素数
+    LowContrast {
+        color: String,
+        background: String,
+        ratio: f64,
+        count: usize,
+    },
+    /// 标题层级跳跃，如 h2 之后直接出现 h4
+    HeadingSkip { from: u8, to: u8 },
+}
+
+impl fmt::Display for A11yIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            A11yIssue::MissingAlt { src } => write!(f, "图片缺少 alt 描述: {}", src),
+            A11yIssue::LowContrast {
+                color,
+                background,
+                ratio,
+                count,
+            } => write!(
+                f,
+                "文字颜色 {} 与背景 {} 的对比度为 {:.1}:1，不易阅读（{} 处）",
+                color, background, ratio, count
+            ),
+            A11yIssue::HeadingSkip { from, to } => {
+                write!(f, "标题层级跳跃: h{} 之后直接是 h{}", from, to)
+            }
+        }
+    }
+}
+
+/// 解析 CSS 颜色：`#rgb`、`#rrggbb`（可带透明度，忽略）、`rgb()`/`rgba()` 和常用颜色名
+pub fn parse_css_color(value: &str) -> Option<[u8; 3]> {
+    let value = value.trim().trim_end_matches("!important").trim();
+    let lower = value.to_ascii_lowercase();
+    if let Some(hex) = lower.strip_prefix('#') {
+        let digits: Vec<u8> = hex
+            .chars()
+            .map(|c| c.to_digit(16).map(|d| d as u8))
+            .collect::<Option<_>>()?;
+        return match digits.len() {
+            3 | 4 => Some([digits[0] * 17, digits[1] * 17, digits[2] * 17]),
+            6 | 8 => Some([
+                digits[0] * 16 + digits[1],
+                digits[2] * 16 + digits[3],
+                digits[4] * 16 + digits[5],
+            ]),
+            _ => None,
+        };
+    }
+    if let Some(args) = lower
+        .strip_prefix("rgba(")
+        .or_else(|| lower.strip_prefix("rgb("))
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let channels: Vec<u8> = args
+            .split([',', ' ', '/'])
+            .filter(|s| !s.is_empty())
+            .take(3)
+            .map(|s| {
+                s.trim()
+                    .parse::<f64>()
+                    .ok()
+                    .map(|v| v.clamp(0.0, 255.0) as u8)
+            })
+            .collect::<Option<_>>()?;
+        return (channels.len() == 3).then(|| [channels[0], channels[1], channels[2]]);
+    }
+    match lower.as_str() {
+        "black" => Some([0, 0, 0]),
+        "white" => Some([255, 255, 255]),
+        "gray" | "grey" => Some([128, 128, 128]),
+        "silver" => Some([192, 192, 192]),
+        "red" => Some([255, 0, 0]),
+        "green" => Some([0, 128, 0]),
+        "blue" => Some([0, 0, 255]),
+        "yellow" => Some([255, 255, 0]),
+        "orange" => Some([255, 165, 0]),
+        _ => None,
+    }
+}
+
+fn relative_luminance(rgb: [u8; 3]) -> f64 {
+    let channel = |c: u8| {
+        let c = c as f64 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(rgb[0]) + 0.7152 * channel(rgb[1]) + 0.0722 * channel(rgb[2])
+}
+
+/// WCAG 对比度，范围 1~21
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    let (light, dark) = if la > lb { (la, lb) } else { (lb, la) };
+    (light + 0.05) / (dark + 0.05)
+}
+
+/// 行内样式中的声明，同名属性以最后一个为准
+fn style_value<'a>(style: &'a str, property: &str) -> Option<&'a str> {
+    style
+        .split(';')
+        .rev()
+        .filter_map(|decl| decl.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case(property))
+        .map(|(_, value)| value.trim())
+}
+
+/// 检查输出 HTML 的无障碍问题：图片缺少 alt、行内文字颜色对比度不足、标题层级跳跃。
+///
+/// 对比度只比较同一元素的 `color` 与 `background-color`，元素没有背景色时与主题背景
+/// `background` 比较；同一配色只报告一次
+pub fn check_accessibility(html: &str, background: &str, min_contrast: f64) -> Vec<A11yIssue> {
+    static IMG_REGEX: OnceLock<Regex> = OnceLock::new();
+    static ALT_REGEX: OnceLock<Regex> = OnceLock::new();
+    static SRC_REGEX: OnceLock<Regex> = OnceLock::new();
+    static STYLE_REGEX: OnceLock<Regex> = OnceLock::new();
+    static HEADING_REGEX: OnceLock<Regex> = OnceLock::new();
+    let img_regex = IMG_REGEX.get_or_init(|| Regex::new(r"(?i)<img\b[^>]*>").unwrap());
+    let alt_regex = ALT_REGEX.get_or_init(|| Regex::new(r"(?i)\salt\s*=").unwrap());
+    let src_regex = SRC_REGEX.get_or_init(|| Regex::new(r#"(?i)\ssrc\s*=\s*"([^"]*)""#).unwrap());
+    let style_regex = STYLE_REGEX.get_or_init(|| {
+        Regex::new(r#"(?i)<[a-z][a-z0-9]*\b[^>]*?\sstyle\s*=\s*"([^"]*)""#).unwrap()
+    });
+    let heading_regex = HEADING_REGEX.get_or_init(|| Regex::new(r"(?i)<h([1-6])\b").unwrap());
+
+    let mut issues = Vec::new();
+
+    for img in img_regex.find_iter(html) {
+        if !alt_regex.is_match(img.as_str()) {
+            let src = src_regex
+                .captures(img.as_str())
+                .map(|caps| caps[1].to_string())
+                .unwrap_or_default();
+            issues.push(A11yIssue::MissingAlt { src });
+        }
+    }
+
+    let page_background = parse_css_color(background).unwrap_or([255, 255, 255]);
+    // (文字颜色, 背景色) → (对比度, 出现次数)
+    let mut low_contrast: BTreeMap<(String, String), (f64, usize)> = BTreeMap::new();
+    for caps in style_regex.captures_iter(html) {
+        let style = html_escape::decode_html_entities(&caps[1]).to_string();
+        let Some(color_value) = style_value(&style, "color") else {
+            continue;
+        };
+        let Some(color) = parse_css_color(color_value) else {
+            continue;
+        };
+        let element_background = style_value(&style, "background-color")
+            .or_else(|| style_value(&style, "background"))
+            .and_then(|value| parse_css_color(value).map(|rgb| (value.to_string(), rgb)));
+        let (background_value, background_rgb) =
+            element_background.unwrap_or_else(|| (background.to_string(), page_background));
+        let ratio = contrast_ratio(color, background_rgb);
+        if ratio < min_contrast {
+            low_contrast
+                .entry((color_value.to_string(), background_value))
+                .or_insert((ratio, 0))
+                .1 += 1;
+        }
+    }
+    issues.extend(
+        low_contrast
+            .into_iter()
+            .map(
+                |((color, background), (ratio, count))| A11yIssue::LowContrast {
+                    color,
+                    background,
+                    ratio,
+                    count,
+                },
+            ),
+    );
+
+    let mut previous: Option<u8> = None;
+    for caps in heading_regex.captures_iter(html) {
+        let level: u8 = caps[1].parse().unwrap_or(1);
+        if let Some(from) = previous {
+            if level > from + 1 {
+                issues.push(A11yIssue::HeadingSkip { from, to: level });
+            }
+        }
+        previous = Some(level);
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert_eq!(parse_css_color("#fff"), Some([255, 255, 255]));
+        assert_eq!(
+            parse_css_color("rgba(52, 152, 219, 0.5)"),
+            Some([52, 152, 219])
+        );
+        assert_eq!(parse_css_color("var(--text)"), None);
+        let ratio = contrast_ratio([0, 0, 0], [255, 255, 255]);
+        assert!((ratio - 21.0).abs() < 0.01);
+        let ratio = contrast_ratio(parse_css_color("#3498db").unwrap(), [255, 255, 255]);
+        assert!(ratio > 3.0 && ratio < 3.3);
+    }
+
+    #[test]
+    fn test_check_accessibility() {
+        let html = r#"<h1>标题</h1><h2>小节</h2><h4>跳级</h4>
+<p style="color: #333;">正文</p>
+<p style="color: #aaa">浅色</p><span style="color: #aaa">浅色</span>
+<code style="background-color: #222; color: #333">代码</code>
+<img src="a.png"><img src="b.png" alt="">"#;
+        let issues = check_accessibility(html, "#ffffff", 4.5);
+        let messages: Vec<String> = issues.iter().map(|i| i.to_string()).collect();
+        assert_eq!(messages.len(), 4);
+        assert_eq!(messages[0], "图片缺少 alt 描述: a.png");
+        assert!(messages[1].starts_with("文字颜色 #333 与背景 #222 的对比度为 1.3:1"));
+        assert!(messages[2].starts_with("文字颜色 #aaa 与背景 #ffffff"));
+        assert!(messages[2].ends_with("（2 处）"));
+        assert_eq!(messages[3], "标题层级跳跃: h2 之后直接是 h4");
+    }
+}
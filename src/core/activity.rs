@@ -0,0 +1,243 @@
+use crate::{
+    core::{
+        content::PublishResult,
+        lock::{write_atomic, FileLock},
+    },
+    Result,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Write as _,
+    io::Write as _,
+    path::{Path, PathBuf},
+};
+
+/// 一条处理或发布活动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Activity {
+    /// 文章处理完成
+    Processed {
+        title: String,
+        source: Option<PathBuf>,
+        platforms: Vec<String>,
+    },
+    /// 一次发布（含失败）
+    Published {
+        title: String,
+        result: PublishResult,
+    },
+    /// 处理时检测到的失效链接
+    BrokenLink {
+        title: String,
+        url: String,
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub activity: Activity,
+}
+
+/// 活动日志（JSON Lines），供邮件摘要汇总 watch 和发布的结果
+pub struct ActivityLog {
+    path: PathBuf,
+}
+
+impl ActivityLog {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn default_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow").join("activity.jsonl")
+    }
+
+    pub fn open_default() -> Self {
+        Self::new(Self::default_path())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn append(&self, activity: Activity) -> Result<()> {
+        let _lock = FileLock::exclusive(&self.path)?;
+        let event = ActivityEvent {
+            at: Utc::now(),
+            activity,
+        };
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(&event)?)?;
+        Ok(())
+    }
+
+    /// 读取 `since` 之后的活动；无法解析的行（如写入中断）直接跳过
+    pub fn since(&self, since: DateTime<Utc>) -> Result<Vec<ActivityEvent>> {
+        let raw = match std::fs::read_to_string(&self.path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(raw
+            .lines()
+            .filter_map(|line| serde_json::from_str::<ActivityEvent>(line).ok())
+            .filter(|event| event.at >= since)
+            .collect())
+    }
+
+    /// 删除 `before` 之前的活动，避免日志无限增长
+    pub fn prune(&self, before: DateTime<Utc>) -> Result<()> {
+        let _lock = FileLock::exclusive(&self.path)?;
+        let kept = self.since(before)?;
+        let mut raw = String::new();
+        for event in kept {
+            raw.push_str(&serde_json::to_string(&event)?);
+            raw.push('\n');
+        }
+        write_atomic(&self.path, raw)
+    }
+}
+
+/// 一段时间内活动的汇总
+#[derive(Debug, Default)]
+pub struct ActivityDigest {
+    pub processed: Vec<ActivityEvent>,
+    pub published: Vec<ActivityEvent>,
+    pub broken_links: Vec<ActivityEvent>,
+}
+
+impl ActivityDigest {
+    pub fn from_events(events: Vec<ActivityEvent>) -> Self {
+        let mut digest = Self::default();
+        for event in events {
+            match event.activity {
+                Activity::Processed { .. } => digest.processed.push(event),
+                Activity::Published { .. } => digest.published.push(event),
+                Activity::BrokenLink { .. } => digest.broken_links.push(event),
+            }
+        }
+        digest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processed.is_empty() && self.published.is_empty() && self.broken_links.is_empty()
+    }
+
+    /// 纯文本邮件正文
+    pub fn render_text(&self) -> String {
+        let mut text = String::new();
+        if self.is_empty() {
+            text.push_str("这段时间没有处理或发布活动。\n");
+            return text;
+        }
+
+        let _ = writeln!(text, "处理文章 {} 次", self.processed.len());
+        for event in &self.processed {
+            if let Activity::Processed {
+                title,
+                source,
+                platforms,
+            } = &event.activity
+            {
+                let source = source
+                    .as_ref()
+                    .map(|s| format!("（{}）", s.display()))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    text,
+                    "  - {} {}{} → {}",
+                    event.at.format("%m-%d %H:%M"),
+                    title,
+                    source,
+                    platforms.join(", ")
+                );
+            }
+        }
+
+        let _ = writeln!(text, "\n发布 {} 次", self.published.len());
+        for event in &self.published {
+            if let Activity::Published { title, result } = &event.activity {
+                let _ = writeln!(
+                    text,
+                    "  - {} [{}] {}：{}{}",
+                    event.at.format("%m-%d %H:%M"),
+                    result.platform,
+                    title,
+                    result.message,
+                    result
+                        .url
+                        .as_ref()
+                        .map(|url| format!(" {}", url))
+                        .unwrap_or_default()
+                );
+            }
+        }
+
+        let _ = writeln!(text, "\n失效链接 {} 个", self.broken_links.len());
+        for event in &self.broken_links {
+            if let Activity::BrokenLink { title, url, reason } = &event.activity {
+                let _ = writeln!(text, "  - {}（{}）— {}", url, reason, title);
+            }
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::content::{Platform, PublishStatus};
+
+    #[test]
+    fn test_activity_log_and_digest() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = ActivityLog::new(dir.path().join("activity.jsonl"));
+        let start = Utc::now();
+
+        log.append(Activity::Processed {
+            title: "文章".to_string(),
+            source: Some(PathBuf::from("a.md")),
+            platforms: vec!["wechat".to_string()],
+        })
+        .unwrap();
+        log.append(Activity::BrokenLink {
+            title: "文章".to_string(),
+            url: "https://example.com/gone".to_string(),
+            reason: "HTTP 404".to_string(),
+        })
+        .unwrap();
+        log.append(Activity::Published {
+            title: "文章".to_string(),
+            result: PublishResult {
+                platform: Platform::WeChat,
+                url: None,
+                draft_id: Some("draft".to_string()),
+                publish_id: None,
+                status: PublishStatus::Draft,
+                message: "已保存草稿".to_string(),
+                title_variant: None,
+            },
+        })
+        .unwrap();
+
+        let digest = ActivityDigest::from_events(log.since(start).unwrap());
+        assert_eq!(digest.processed.len(), 1);
+        assert_eq!(digest.published.len(), 1);
+        assert_eq!(digest.broken_links.len(), 1);
+        let text = digest.render_text();
+        assert!(text.contains("[wechat] 文章：已保存草稿"));
+        assert!(text.contains("https://example.com/gone（HTTP 404）"));
+
+        log.prune(Utc::now()).unwrap();
+        assert!(log.since(start).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{
+    cli::args::AltTextConfig,
+    core::{
+        content::Content, lock::write_atomic, metrics::metrics, pipeline::ProcessingStage,
+        store::content_hash,
+    },
+    error::Error,
+    Result,
+};
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use regex::Regex;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+};
+
+/// 能为图片生成文字描述的视觉模型
+#[async_trait]
+pub trait VisionModel: Send + Sync {
+    /// `language` 为 "zh" 或 "en"
+    async fn describe(&self, image: &[u8], mime_type: &str, language: &str) -> Result<String>;
+}
+
+/// OpenAI 兼容的 chat completions 接口
+pub struct OpenAiVisionModel {
+    client: reqwest::Client,
+    endpoint: String,
+    model: String,
+    api_key: String,
+}
+
+impl OpenAiVisionModel {
+    pub fn new(
+        endpoint: impl Into<String>,
+        model: impl Into<String>,
+        api_key: impl Into<String>,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: endpoint.into(),
+            model: model.into(),
+            api_key: api_key.into(),
+        }
+    }
+
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// 从配置创建，API Key 读取自 `api_key_env` 指定的环境变量
+    pub fn from_config(config: &AltTextConfig) -> Result<Self> {
+        let api_key = std::env::var(&config.api_key_env).map_err(|_| {
+            Error::Config(format!(
+                "生成图片描述需要设置环境变量 {}",
+                config.api_key_env
+            ))
+        })?;
+        Ok(
+            Self::new(&config.endpoint, &config.model, api_key).with_client(
+                crate::core::http_client_for(crate::core::NetworkOperation::Llm)?,
+            ),
+        )
+    }
+}
+
+#[async_trait]
+impl VisionModel for OpenAiVisionModel {
+    async fn describe(&self, image: &[u8], mime_type: &str, language: &str) -> Result<String> {
+        let prompt = match language {
+            "en" => "Write concise alt text (under 20 words) for this image. Reply with the alt text only.",
+            _ => "用一句简洁的中文（30 字以内）描述这张图片，作为网页的 alt 文本，只回复描述本身。",
+        };
+        let body = serde_json::json!({
+            "model": self.model,
+            "max_tokens": 100,
+            "messages": [{
+                "role": "user",
+                "content": [
+                    { "type": "text", "text": prompt },
+                    {
+                        "type": "image_url",
+                        "image_url": {
+                            "url": format!("data:{};base64,{}", mime_type, STANDARD.encode(image))
+                        }
+                    }
+                ]
+            }]
+        });
+
+        let response: serde_json::Value = self
+            .client
+            .post(&self.endpoint)
+            .bearer_auth(&self.api_key)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        response["choices"][0]["message"]["content"]
+            .as_str()
+            .map(|text| text.trim().trim_matches(['"', '“', '”']).to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| Error::Other("视觉模型没有返回描述".to_string()))
+    }
+}
+
+/// 按“图片哈希:语言”缓存生成的描述，避免重复调用模型
+pub struct AltTextCache {
+    path: Option<PathBuf>,
+    entries: BTreeMap<String, String>,
+}
+
+impl AltTextCache {
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .map_err(|e| Error::Config(format!("图片描述缓存解析失败 {:?}: {}", path, e)))?,
+            Err(_) => BTreeMap::new(),
+        };
+        Ok(Self {
+            path: Some(path),
+            entries,
+        })
+    }
+
+    fn key(hash: &str, language: &str) -> String {
+        format!("{}:{}", hash, language)
+    }
+
+    pub fn get(&self, hash: &str, language: &str) -> Option<&str> {
+        self.entries
+            .get(&Self::key(hash, language))
+            .map(String::as_str)
+    }
+
+    pub fn insert(&mut self, hash: &str, language: &str, alt: String) {
+        self.entries.insert(Self::key(hash, language), alt);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => write_atomic(path, serde_json::to_string_pretty(&self.entries)?),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 为缺少 alt 的图片生成描述，改善导出 HTML 的无障碍和 SEO
+pub struct AltTextStage {
+    model: Arc<dyn VisionModel>,
+    cache: Mutex<AltTextCache>,
+    language: String,
+    base_dir: Option<PathBuf>,
+}
+
+impl AltTextStage {
+    pub fn new(model: Arc<dyn VisionModel>, cache: AltTextCache) -> Self {
+        Self {
+            model,
+            cache: Mutex::new(cache),
+            language: "zh".to_string(),
+            base_dir: None,
+        }
+    }
+
+    pub fn from_config(config: &AltTextConfig) -> Result<Self> {
+        let model = OpenAiVisionModel::from_config(config)?;
+        Ok(
+            Self::new(Arc::new(model), AltTextCache::open(&config.cache_file)?)
+                .with_language(&config.language),
+        )
+    }
+
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.language = language.to_string();
+        self
+    }
+
+    /// 相对路径图片的基准目录，默认为当前目录
+    pub fn with_base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = Some(base_dir.into());
+        self
+    }
+
+    async fn load_image(&self, src: &str) -> Result<Vec<u8>> {
+        if src.starts_with("http://") || src.starts_with("https://") {
+            let response = crate::core::http_client_for(crate::core::NetworkOperation::Download)?
+                .get(src)
+                .send()
+                .await?
+                .error_for_status()?;
+            return Ok(response.bytes().await?.to_vec());
+        }
+        let path = match &self.base_dir {
+            Some(dir) => dir.join(src),
+            None => PathBuf::from(src),
+        };
+        Ok(tokio::fs::read(path).await?)
+    }
+
+    async fn alt_for(&self, src: &str) -> Result<String> {
+        let image = self.load_image(src).await?;
+        let hash = content_hash(&image);
+
+        let cached = self
+            .cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&hash, &self.language)
+            .map(str::to_string);
+        metrics().record_cache("alt_text", cached.is_some());
+        if let Some(alt) = cached {
+            return Ok(alt);
+        }
+
+        let alt = self
+            .model
+            .describe(&image, mime_type(src), &self.language)
+            .await?;
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            &hash,
+            &self.language,
+            alt.clone(),
+        );
+        Ok(alt)
+    }
+}
+
+fn mime_type(src: &str) -> &'static str {
+    let path = src.split(['?', '#']).next().unwrap_or(src);
+    match Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        Some("svg") => "image/svg+xml",
+        _ => "image/jpeg",
+    }
+}
+
+#[async_trait]
+impl ProcessingStage for AltTextStage {
+    async fn process(&self, content: &mut Content) -> Result<()> {
+        static IMG_REGEX: OnceLock<Regex> = OnceLock::new();
+        static ALT_REGEX: OnceLock<Regex> = OnceLock::new();
+        static SRC_REGEX: OnceLock<Regex> = OnceLock::new();
+        let img_regex = IMG_REGEX.get_or_init(|| Regex::new(r"<img\b[^>]*>").unwrap());
+        let alt_regex = ALT_REGEX.get_or_init(|| Regex::new(r#"\salt="([^"]*)""#).unwrap());
+        let src_regex = SRC_REGEX.get_or_init(|| Regex::new(r#"\ssrc="([^"]+)""#).unwrap());
+
+        let mut html = String::with_capacity(content.html.len());
+        let mut last = 0;
+        for tag in img_regex.find_iter(&content.html) {
+            html.push_str(&content.html[last..tag.start()]);
+            last = tag.end();
+
+            let tag = tag.as_str();
+            let has_alt = alt_regex
+                .captures(tag)
+                .is_some_and(|caps| !caps[1].trim().is_empty());
+            let src = src_regex
+                .captures(tag)
+                .map(|caps| html_escape::decode_html_entities(&caps[1]).into_owned())
+                .filter(|src| !src.starts_with("data:"));
+            let (false, Some(src)) = (has_alt, src) else {
+                html.push_str(tag);
+                continue;
+            };
+
+            match self.alt_for(&src).await {
+                Ok(alt) => {
+                    let alt = format!(
+                        r#" alt="{}""#,
+                        html_escape::encode_double_quoted_attribute(&alt)
+                    );
+                    let tag = if alt_regex.is_match(tag) {
+                        alt_regex.replace(tag, alt.as_str()).into_owned()
+                    } else {
+                        tag.replacen("<img", &format!("<img{}", alt), 1)
+                    };
+                    html.push_str(&tag);
+                }
+                Err(e) => {
+                    tracing::warn!("生成图片描述失败 {}: {}", src, e);
+                    html.push_str(tag);
+                }
+            }
+        }
+        html.push_str(&content.html[last..]);
+        content.html = html;
+
+        self.cache.lock().unwrap_or_else(|e| e.into_inner()).save()
+    }
+
+    fn name(&self) -> &'static str {
+        "图片描述"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct FakeModel {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl VisionModel for FakeModel {
+        async fn describe(&self, image: &[u8], mime_type: &str, language: &str) -> Result<String> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(format!(
+                "{} \"{}\" {}",
+                language,
+                String::from_utf8_lossy(image),
+                mime_type
+            ))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_generates_missing_alt_with_cache() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.png"), b"cat").unwrap();
+        std::fs::write(dir.path().join("b.jpg"), b"cat").unwrap();
+
+        let model = Arc::new(FakeModel {
+            calls: AtomicUsize::new(0),
+        });
+        let cache_path = dir.path().join("cache.json");
+        let stage = AltTextStage::new(model.clone(), AltTextCache::open(&cache_path).unwrap())
+            .with_base_dir(dir.path());
+
+        let mut content = Content::new("标题".to_string(), String::new());
+        content.html = r#"<p><img src="a.png" alt=""><img alt="已有" src="a.png"><img src="b.jpg"><img src="missing.png"></p>"#.to_string();
+        stage.process(&mut content).await.unwrap();
+
+        assert_eq!(
+            content.html,
+            r#"<p><img src="a.png" alt="zh &quot;cat&quot; image/png"><img alt="已有" src="a.png"><img alt="zh &quot;cat&quot; image/png" src="b.jpg"><img src="missing.png"></p>"#
+        );
+        // 两张图片内容相同，只调用一次模型
+        assert_eq!(model.calls.load(Ordering::SeqCst), 1);
+
+        let cache = AltTextCache::open(&cache_path).unwrap();
+        assert!(cache.get(&content_hash(b"cat"), "zh").is_some());
+        assert!(cache.get(&content_hash(b"cat"), "en").is_none());
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    core::{
+        content::{Content, Platform},
+        pipeline::PlatformStage,
+        report::PlatformReport,
+    },
+    error::Error,
+    Result,
+};
+use regex::Regex;
+use std::{str::FromStr, sync::OnceLock};
+
+/// 正文中残留未完成标记时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnfinishedPolicy {
+    Off,
+    Warn,
+    Error,
+}
+
+impl FromStr for UnfinishedPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(UnfinishedPolicy::Off),
+            "warn" => Ok(UnfinishedPolicy::Warn),
+            "error" => Ok(UnfinishedPolicy::Error),
+            _ => Err(Error::Config(format!(
+                "无效的未完成标记处理方式: {}（可选: off、warn、error）",
+                s
+            ))),
+        }
+    }
+}
+
+/// 正文中的一处未完成标记
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnfinishedMarker {
+    /// 从 1 开始的行号
+    pub line: usize,
+    pub marker: String,
+    pub text: String,
+}
+
+impl UnfinishedMarker {
+    pub fn message(&self) -> String {
+        format!("第 {} 行残留 {}: {}", self.line, self.marker, self.text)
+    }
+}
+
+/// 移除 HTML 注释和编辑器批注：`<!-- -->`、Obsidian 的 `%% %%`、CriticMarkup 的 `{>> <<}`；
+/// CriticMarkup 高亮 `{== ==}` 只保留文字。返回处理后的文本和移除的批注数
+pub fn strip_annotations(text: &str) -> (String, usize) {
+    static COMMENT_REGEX: OnceLock<Regex> = OnceLock::new();
+    static HIGHLIGHT_REGEX: OnceLock<Regex> = OnceLock::new();
+    let comment_regex =
+        COMMENT_REGEX.get_or_init(|| Regex::new(r"(?s)<!--.*?-->\n?|%%.*?%%|\{>>.*?<<\}").unwrap());
+    let highlight_regex = HIGHLIGHT_REGEX.get_or_init(|| Regex::new(r"(?s)\{==(.*?)==\}").unwrap());
+
+    let removed = comment_regex.find_iter(text).count() + highlight_regex.find_iter(text).count();
+    let text = comment_regex.replace_all(text, "");
+    let text = highlight_regex.replace_all(&text, "$1");
+    (text.into_owned(), removed)
+}
+
+/// 查找 TODO/FIXME 等未完成标记和 CriticMarkup 修订痕迹（`{++ ++}`、`{-- --}`、`{~~ ~~}`），
+/// 代码块和行内代码中的内容不检查
+pub fn find_unfinished(markdown: &str) -> Vec<UnfinishedMarker> {
+    static MARKER_REGEX: OnceLock<Regex> = OnceLock::new();
+    static INLINE_CODE_REGEX: OnceLock<Regex> = OnceLock::new();
+    let marker_regex = MARKER_REGEX
+        .get_or_init(|| Regex::new(r"\b(TODO|FIXME|XXX|TBD)\b|(\{\+\+|\{--|\{~~)").unwrap());
+    let inline_code_regex = INLINE_CODE_REGEX.get_or_init(|| Regex::new(r"`[^`\n]*`").unwrap());
+
+    let mut markers = Vec::new();
+    let mut fence: Option<&str> = None;
+    for (index, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if let Some(open) = fence {
+            if trimmed.starts_with(open) {
+                fence = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            fence = Some(&trimmed[..3]);
+            continue;
+        }
+
+        let line = inline_code_regex.replace_all(line, "");
+        for caps in marker_regex.captures_iter(&line) {
+            let marker = match caps.get(1) {
+                Some(word) => word.as_str().to_string(),
+                None => "修订痕迹".to_string(),
+            };
+            markers.push(UnfinishedMarker {
+                line: index + 1,
+                marker,
+                text: line.trim().to_string(),
+            });
+        }
+    }
+    markers
+}
+
+/// 输出前移除批注，并检查正文中是否还有未完成的标记。
+/// 需要在平台条件块之后执行，条件块的注释标记由条件块阶段处理
+pub struct AnnotationStage {
+    strip: bool,
+    policy: UnfinishedPolicy,
+}
+
+impl AnnotationStage {
+    pub fn new(strip: bool, policy: UnfinishedPolicy) -> Self {
+        Self { strip, policy }
+    }
+}
+
+impl PlatformStage for AnnotationStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        _platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        // 注释里的 TODO 不会进入输出，只检查去掉批注后的正文；
+        // Markdown 源文保持不变（代码示例中可能有注释），只清理输出的 HTML
+        let (markdown, _) = strip_annotations(&content.markdown);
+        if self.strip {
+            let (html, removed) = strip_annotations(&content.html);
+            content.html = html;
+            if removed > 0 {
+                report
+                    .stripped_elements
+                    .push(format!("{} 处注释或批注", removed));
+            }
+        }
+
+        let markers = match self.policy {
+            UnfinishedPolicy::Off => return Ok(()),
+            _ => find_unfinished(&markdown),
+        };
+        if markers.is_empty() {
+            return Ok(());
+        }
+        if self.policy == UnfinishedPolicy::Error {
+            return Err(Error::Validation(format!(
+                "《{}》还有未完成的内容：\n{}",
+                content.title,
+                markers
+                    .iter()
+                    .map(|m| format!("  - {}", m.message()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )));
+        }
+        report.warnings.extend(
+            markers
+                .iter()
+                .map(|m| format!("unfinished: {}", m.message())),
+        );
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "批注清理"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_annotations() {
+        let text = "正文<!-- 待补充 TODO -->继续\n%%私人笔记%%\n这是{==重点==}{>>需要数据<<}。";
+        let (stripped, removed) = strip_annotations(text);
+        assert_eq!(stripped, "正文继续\n\n这是重点。");
+        assert_eq!(removed, 4);
+    }
+
+    #[test]
+    fn test_find_unfinished_skips_code() {
+        let markdown = "# 标题\n\n正文 TODO: 补充图片\n\n```rust\n// TODO: 示例代码\n```\n\n用 `FIXME` 标注\n\n这里{++新增++}一句\n";
+        let markers = find_unfinished(markdown);
+        assert_eq!(markers.len(), 2);
+        assert_eq!(markers[0].line, 3);
+        assert_eq!(markers[0].marker, "TODO");
+        assert_eq!(markers[1].marker, "修订痕迹");
+        assert!(find_unfinished("TODOS 不算，todo 小写也不算").is_empty());
+    }
+
+    #[test]
+    fn test_stage_policy() {
+        let mut content = crate::core::MarkdownProcessor::new()
+            .process("# 标题\n\n正文 FIXME\n\n<!-- 注释 -->\n")
+            .unwrap();
+        let mut report = PlatformReport::new(Platform::WeChat);
+        AnnotationStage::new(true, UnfinishedPolicy::Warn)
+            .process(&mut content, &Platform::WeChat, &mut report)
+            .unwrap();
+        assert!(!content.html.contains("<!--"));
+        assert_eq!(report.warnings.len(), 1);
+
+        let result = AnnotationStage::new(true, UnfinishedPolicy::Error).process(
+            &mut content,
+            &Platform::WeChat,
+            &mut report,
+        );
+        assert!(matches!(result, Err(Error::Validation(_))));
+    }
+}
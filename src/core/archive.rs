@@ -0,0 +1,313 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{lock::FileLock, store::LEDGER_FILE, workspace::Workspace},
+    error::Error,
+    Result,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
+
+/// 归档中包含的目录（模板、主题、片段和上传的资源）
+const ARCHIVE_DIRS: &[&str] = &["templates", "themes", "snippets", "assets"];
+const ARCHIVE_MANIFEST: &str = "markflow-archive.json";
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchiveManifest {
+    version: u32,
+    workspace: String,
+    exported_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 导出/导入结果摘要
+#[derive(Debug, Default)]
+pub struct ArchiveSummary {
+    pub files: usize,
+}
+
+/// 把工作区打包为 tar.gz：配置（去除密钥）、模板、主题、片段、资源和内容台账。
+///
+/// 工作区的 API 密钥（`workspace.toml`）不会被导出。
+pub fn export_workspace(workspace: &Workspace, dest: &Path) -> Result<ArchiveSummary> {
+    let mut summary = ArchiveSummary::default();
+    let file = File::create(dest)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+
+    let manifest = ArchiveManifest {
+        version: ARCHIVE_VERSION,
+        workspace: workspace.name().to_string(),
+        exported_at: chrono::Utc::now(),
+    };
+    append_bytes(
+        &mut builder,
+        ARCHIVE_MANIFEST,
+        &serde_json::to_vec_pretty(&manifest)?,
+    )?;
+
+    let config = portable_config(workspace.load_config()?, workspace.root());
+    let config = toml::to_string_pretty(&config)
+        .map_err(|e| Error::Config(format!("配置序列化失败: {}", e)))?;
+    append_bytes(&mut builder, "config.toml", config.as_bytes())?;
+    summary.files += 1;
+
+    let ledger = workspace.ledger_path();
+    if ledger.exists() {
+        let _lock = FileLock::shared(&ledger)?;
+        builder.append_path_with_name(&ledger, LEDGER_FILE)?;
+        summary.files += 1;
+    }
+
+    for dir in ARCHIVE_DIRS {
+        let path = workspace.root().join(dir);
+        if path.is_dir() {
+            summary.files += count_files(&path)?;
+            builder.append_dir_all(dir, &path)?;
+        }
+    }
+
+    builder.into_inner()?.finish()?;
+    Ok(summary)
+}
+
+/// 把归档解压到工作区。目标中已有文件时需要 `force`，且会保留目标配置中的密钥
+pub fn import_workspace(workspace: &Workspace, src: &Path, force: bool) -> Result<ArchiveSummary> {
+    let mut summary = ArchiveSummary::default();
+    let existing_config = workspace.config_path();
+    let previous = if existing_config.exists() {
+        Some(workspace.load_config()?)
+    } else {
+        None
+    };
+
+    let mut archive = tar::Archive::new(GzDecoder::new(File::open(src)?));
+    let mut manifest_found = false;
+    let mut imported_config = None;
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let name = path.to_string_lossy().replace('\\', "/");
+
+        if name == ARCHIVE_MANIFEST {
+            let manifest: ArchiveManifest = serde_json::from_reader(&mut entry)?;
+            if manifest.version > ARCHIVE_VERSION {
+                return Err(Error::Config(format!(
+                    "归档版本 {} 高于当前支持的版本 {}",
+                    manifest.version, ARCHIVE_VERSION
+                )));
+            }
+            manifest_found = true;
+            continue;
+        }
+
+        if name == "config.toml" {
+            let mut raw = String::new();
+            std::io::Read::read_to_string(&mut entry, &mut raw)?;
+            imported_config = Some(raw);
+            continue;
+        }
+
+        let allowed = name == LEDGER_FILE
+            || ARCHIVE_DIRS
+                .iter()
+                .any(|dir| name == *dir || name.starts_with(&format!("{}/", dir)));
+        if !allowed {
+            tracing::warn!("忽略归档中的未知条目: {}", name);
+            continue;
+        }
+
+        let target = workspace.root().join(&path);
+        if entry.header().entry_type().is_file() {
+            if target.exists() && !force {
+                return Err(Error::Config(format!(
+                    "文件已存在: {:?}（使用 --force 覆盖）",
+                    target
+                )));
+            }
+            summary.files += 1;
+        }
+
+        if name == LEDGER_FILE {
+            let _lock = FileLock::exclusive(&target)?;
+            entry.unpack_in(workspace.root())?;
+        } else if !entry.unpack_in(workspace.root())? {
+            return Err(Error::Config(format!("归档包含非法路径: {}", name)));
+        }
+    }
+
+    if !manifest_found {
+        return Err(Error::Config(format!(
+            "不是 markflow 工作区归档: {:?}",
+            src
+        )));
+    }
+
+    if let Some(raw) = imported_config {
+        if previous.is_some() && !force {
+            return Err(Error::Config(format!(
+                "配置已存在: {:?}（使用 --force 覆盖）",
+                existing_config
+            )));
+        }
+        let mut config: AppConfig = toml::from_str(&raw)
+            .map_err(|e| Error::Config(format!("归档中的配置解析失败: {}", e)))?;
+        localize_paths(&mut config, workspace.root());
+        if let Some(previous) = previous {
+            config.wechat.app_secret = previous.wechat.app_secret;
+            config.wechat.access_token = previous.wechat.access_token;
+            config.zhihu.cookies_file = previous.zhihu.cookies_file;
+        }
+        config.save_to_file(&existing_config)?;
+        summary.files += 1;
+    }
+
+    Ok(summary)
+}
+
+/// 去除密钥，并把工作区内的绝对路径改为相对路径，便于在其他机器上导入
+fn portable_config(mut config: AppConfig, root: &Path) -> AppConfig {
+    config.wechat.app_secret = None;
+    config.wechat.access_token = None;
+    config.zhihu.cookies_file = None;
+
+    let relative = |path: &Path| -> PathBuf {
+        path.strip_prefix(root)
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|_| path.to_path_buf())
+    };
+    config.templates.templates_dir = relative(&config.templates.templates_dir);
+    config.output.output_dir = relative(&config.output.output_dir);
+    config.output.backup_dir = config.output.backup_dir.as_deref().map(relative);
+    config.assets.dir = config.assets.dir.as_deref().map(relative);
+    for path in config.templates.custom_templates.values_mut() {
+        *path = relative(path);
+    }
+    config
+}
+
+/// `portable_config` 的逆过程：相对路径重新挂到导入的工作区下
+fn localize_paths(config: &mut AppConfig, root: &Path) {
+    let absolute = |path: &Path| -> PathBuf {
+        if path.is_relative() && !path.starts_with(".") {
+            root.join(path)
+        } else {
+            path.to_path_buf()
+        }
+    };
+    config.templates.templates_dir = absolute(&config.templates.templates_dir);
+    config.output.output_dir = absolute(&config.output.output_dir);
+    config.output.backup_dir = config.output.backup_dir.as_deref().map(absolute);
+    config.assets.dir = config.assets.dir.as_deref().map(absolute);
+    for path in config.templates.custom_templates.values_mut() {
+        *path = absolute(path);
+    }
+}
+
+fn append_bytes<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    name: &str,
+    data: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+fn count_files(dir: &Path) -> Result<usize> {
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            count += count_files(&path)?;
+        } else {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{content_hash, ContentRecord, WorkspaceManager};
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let source_root = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(source_root.path());
+        let workspace = manager.create("alice").unwrap();
+
+        AppConfig::update_file(&workspace.config_path(), |config| {
+            config.general.author = Some("Alice".to_string());
+            config.wechat.app_id = Some("wx123".to_string());
+            config.wechat.app_secret = Some("top-secret".to_string());
+            Ok(())
+        })
+        .unwrap();
+        std::fs::write(workspace.templates_dir().join("post.html"), "{{ content }}").unwrap();
+        std::fs::create_dir_all(workspace.root().join("snippets")).unwrap();
+        std::fs::write(workspace.root().join("snippets/footer.md"), "关注我们").unwrap();
+        {
+            let mut store = workspace.open_store().unwrap();
+            store.upsert(ContentRecord {
+                id: uuid::Uuid::new_v4(),
+                title: "迁移文章".to_string(),
+                source: None,
+                content_hash: content_hash(b"x"),
+                outputs: Default::default(),
+                html: Default::default(),
+                publications: Vec::new(),
+                review_after: None,
+                title_variants: Default::default(),
+                tags: Vec::new(),
+                slug: None,
+                canonical_url: None,
+                workflow: Default::default(),
+                report: None,
+                updated_at: chrono::Utc::now(),
+            });
+            store.save().unwrap();
+        }
+
+        let archive = source_root.path().join("alice.tar.gz");
+        let exported = export_workspace(&workspace, &archive).unwrap();
+        assert_eq!(exported.files, 4);
+
+        let target_root = TempDir::new().unwrap();
+        let target = WorkspaceManager::new(target_root.path())
+            .default_workspace()
+            .unwrap();
+        import_workspace(&target, &archive, false).unwrap();
+
+        let config = target.load_config().unwrap();
+        assert_eq!(config.general.author.as_deref(), Some("Alice"));
+        assert_eq!(config.wechat.app_id.as_deref(), Some("wx123"));
+        assert!(config.wechat.app_secret.is_none());
+        assert_eq!(config.templates.templates_dir, target.templates_dir());
+        assert_eq!(
+            std::fs::read_to_string(target.root().join("snippets/footer.md")).unwrap(),
+            "关注我们"
+        );
+        assert_eq!(target.open_store().unwrap().records()[0].title, "迁移文章");
+
+        // 再次导入需要 --force，且保留本机的密钥
+        assert!(import_workspace(&target, &archive, false).is_err());
+        AppConfig::update_file(&target.config_path(), |config| {
+            config.wechat.app_secret = Some("local-secret".to_string());
+            Ok(())
+        })
+        .unwrap();
+        import_workspace(&target, &archive, true).unwrap();
+        let config = target.load_config().unwrap();
+        assert_eq!(config.wechat.app_secret.as_deref(), Some("local-secret"));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::core::output::is_local_src;
+use std::path::{Path, PathBuf};
+
+/// 与文章同级、多篇文章共用的图片目录
+const IMAGE_DIR: &str = "images";
+
+/// 一篇文章的本地图片查找规则。
+///
+/// 图片地址先按相对文章所在目录解析；找不到时再到与文章同名的 `<文章名>.assets/`
+/// （Typora 等编辑器的默认设置）和同级的 `images/` 中
+/// 按同样的相对路径、最后按文件名查找，因此 `![](cover.png)` 也能找到
+/// `article.assets/cover.png`
+#[derive(Debug, Clone)]
+pub struct ArticleAssets {
+    source_dir: PathBuf,
+    asset_dirs: Vec<PathBuf>,
+}
+
+impl ArticleAssets {
+    pub fn for_source(source: &Path) -> Self {
+        let source_dir = source
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf();
+        let mut asset_dirs = Vec::new();
+        if let Some(stem) = source.file_stem() {
+            let mut name = stem.to_os_string();
+            name.push(".assets");
+            asset_dirs.push(source_dir.join(name));
+        }
+        asset_dirs.push(source_dir.join(IMAGE_DIR));
+        asset_dirs.retain(|dir| dir.is_dir());
+        Self {
+            source_dir,
+            asset_dirs,
+        }
+    }
+
+    pub fn source_dir(&self) -> &Path {
+        &self.source_dir
+    }
+
+    /// 图片地址（可带查询参数和锚点，已解码 HTML 实体）对应的本地文件；
+    /// 网络地址、站点绝对路径和找不到的文件返回 `None`，本机上存在的绝对路径原样返回
+    pub fn resolve(&self, src: &str) -> Option<PathBuf> {
+        let relative = src.split(['?', '#']).next().unwrap_or_default();
+        // 图片处理阶段改写出的本机绝对路径（下载或缩小后的缓存文件）
+        let absolute = Path::new(relative);
+        if absolute.is_absolute() && absolute.is_file() {
+            return Some(absolute.to_path_buf());
+        }
+        if !is_local_src(relative) {
+            return None;
+        }
+        let decoded = percent_encoding::percent_decode_str(relative).decode_utf8_lossy();
+        let file_name = Path::new(decoded.as_ref()).file_name();
+
+        std::iter::once(self.source_dir.join(relative))
+            .chain(std::iter::once(self.source_dir.join(decoded.as_ref())))
+            .chain(self.asset_dirs.iter().map(|dir| dir.join(decoded.as_ref())))
+            .chain(
+                file_name
+                    .into_iter()
+                    .flat_map(|name| self.asset_dirs.iter().map(move |dir| dir.join(name))),
+            )
+            .find(|path| path.is_file())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_article_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("hello world.md");
+        std::fs::create_dir_all(dir.path().join("hello world.assets")).unwrap();
+        std::fs::create_dir_all(dir.path().join("images/diagrams")).unwrap();
+        std::fs::write(dir.path().join("hello world.assets/cover.png"), b"a").unwrap();
+        std::fs::write(dir.path().join("images/diagrams/flow.png"), b"b").unwrap();
+        std::fs::write(dir.path().join("local.png"), b"c").unwrap();
+
+        let assets = ArticleAssets::for_source(&source);
+        let resolve = |src: &str| {
+            assets
+                .resolve(src)
+                .map(|p| p.strip_prefix(dir.path()).unwrap().to_path_buf())
+        };
+        assert_eq!(
+            resolve("hello%20world.assets/cover.png?v=2"),
+            Some(PathBuf::from("hello world.assets/cover.png"))
+        );
+        assert_eq!(
+            resolve("cover.png"),
+            Some(PathBuf::from("hello world.assets/cover.png"))
+        );
+        assert_eq!(
+            resolve("diagrams/flow.png"),
+            Some(PathBuf::from("images/diagrams/flow.png"))
+        );
+        assert_eq!(resolve("local.png"), Some(PathBuf::from("local.png")));
+        assert_eq!(resolve("missing.png"), None);
+        assert_eq!(resolve("https://example.com/cover.png"), None);
+    }
+}
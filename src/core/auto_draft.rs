@@ -0,0 +1,124 @@
+use crate::{
+    core::frontmatter::{front_matter_fields, inherited_front_matter},
+    Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+/// 文章是否要求 watch 自动创建草稿：front matter（包括目录默认值）中 `auto_draft: true`
+pub fn auto_draft_requested(source: &Path, markdown: &str) -> Result<bool> {
+    let mut fields = inherited_front_matter(source)?;
+    fields.extend(front_matter_fields(markdown)?);
+    Ok(matches!(fields.get("auto_draft"), Some(Value::Bool(true))))
+}
+
+/// 是否为文章创建草稿
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AutoDraftDecision {
+    Create,
+    /// 内容与上次创建草稿时相同
+    Unchanged,
+    /// 最近一小时已达到上限，`retry_after` 后恢复
+    RateLimited {
+        retry_after: Duration,
+    },
+}
+
+/// watch 自动创建草稿的护栏：滑动一小时内最多为 `max_per_hour` 篇文章创建草稿，
+/// 同一文件内容没有变化时不重复创建
+#[derive(Debug)]
+pub struct AutoDraftGuard {
+    max_per_hour: usize,
+    created: VecDeque<DateTime<Utc>>,
+    /// 源文件 -> 上次创建草稿时的内容哈希
+    drafted: HashMap<PathBuf, String>,
+}
+
+impl AutoDraftGuard {
+    pub fn new(max_per_hour: usize) -> Self {
+        Self {
+            max_per_hour,
+            created: VecDeque::new(),
+            drafted: HashMap::new(),
+        }
+    }
+
+    pub fn max_per_hour(&self) -> usize {
+        self.max_per_hour
+    }
+
+    /// 最近一小时内创建草稿的文章数
+    pub fn created_in_last_hour(&mut self, now: DateTime<Utc>) -> usize {
+        while self
+            .created
+            .front()
+            .is_some_and(|at| now - *at >= Duration::hours(1))
+        {
+            self.created.pop_front();
+        }
+        self.created.len()
+    }
+
+    /// 判断能否为 `source` 创建草稿，允许时立即计入配额（创建失败也不退还，避免反复重试）
+    pub fn admit(&mut self, source: &Path, hash: &str, now: DateTime<Utc>) -> AutoDraftDecision {
+        if self.drafted.get(source).is_some_and(|last| last == hash) {
+            return AutoDraftDecision::Unchanged;
+        }
+        if self.created_in_last_hour(now) >= self.max_per_hour {
+            let retry_after = self.created.front().map_or(Duration::zero(), |oldest| {
+                *oldest + Duration::hours(1) - now
+            });
+            return AutoDraftDecision::RateLimited { retry_after };
+        }
+        self.created.push_back(now);
+        self.drafted.insert(source.to_path_buf(), hash.to_string());
+        AutoDraftDecision::Create
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_draft_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("a.md");
+        assert!(
+            auto_draft_requested(&source, "---\ntitle: A\nauto_draft: true\n---\n正文").unwrap()
+        );
+        assert!(!auto_draft_requested(&source, "---\nauto_draft: \"yes\"\n---\n正文").unwrap());
+        assert!(!auto_draft_requested(&source, "没有 front matter").unwrap());
+
+        std::fs::write(dir.path().join("_index.yaml"), "auto_draft: true\n").unwrap();
+        assert!(auto_draft_requested(&source, "正文").unwrap());
+        assert!(!auto_draft_requested(&source, "---\nauto_draft: false\n---\n正文").unwrap());
+    }
+
+    #[test]
+    fn test_auto_draft_guard() {
+        let mut guard = AutoDraftGuard::new(2);
+        let start = Utc::now();
+        let (a, b, c) = (Path::new("a.md"), Path::new("b.md"), Path::new("c.md"));
+
+        assert_eq!(guard.admit(a, "1", start), AutoDraftDecision::Create);
+        assert_eq!(guard.admit(a, "1", start), AutoDraftDecision::Unchanged);
+        let later = start + Duration::minutes(20);
+        assert_eq!(guard.admit(b, "1", later), AutoDraftDecision::Create);
+        assert_eq!(
+            guard.admit(c, "1", later),
+            AutoDraftDecision::RateLimited {
+                retry_after: Duration::minutes(40)
+            }
+        );
+        assert_eq!(guard.created_in_last_hour(later), 2);
+
+        let next_hour = start + Duration::minutes(61);
+        assert_eq!(guard.admit(a, "2", next_hour), AutoDraftDecision::Create);
+        assert_eq!(guard.created_in_last_hour(next_hour), 2);
+    }
+}
@@ -0,0 +1,303 @@
+use crate::{
+    cli::args::{BareUrlConfig, NetworkConfig},
+    core::{
+        content::{Content, Platform},
+        http::{client_for, map_concurrent, NetworkOperation},
+        pipeline::{PlatformStage, ProcessingStage},
+        report::PlatformReport,
+    },
+    error::Error,
+    Result,
+};
+use async_trait::async_trait;
+use regex::{Captures, Regex};
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, OnceLock, RwLock},
+};
+use url::Url;
+
+/// 可读链接中标题的最大字符数
+const MAX_TITLE_CHARS: usize = 40;
+
+/// 正文中裸链接（自动识别出的网址）的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BareUrlPolicy {
+    /// 保持自动链接
+    Keep,
+    /// 链接文字改为“域名 · 页面标题”
+    Readable,
+    /// 去掉链接，只保留网址文字
+    Text,
+    /// 连同网址文字一起移除
+    Strip,
+}
+
+impl FromStr for BareUrlPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "keep" => Ok(Self::Keep),
+            "readable" => Ok(Self::Readable),
+            "text" => Ok(Self::Text),
+            "strip" => Ok(Self::Strip),
+            other => Err(Error::Config(format!(
+                "无效的裸链接处理方式: {}（可选: keep、readable、text、strip）",
+                other
+            ))),
+        }
+    }
+}
+
+fn anchor_regex() -> &'static Regex {
+    static ANCHOR_REGEX: OnceLock<Regex> = OnceLock::new();
+    ANCHOR_REGEX.get_or_init(|| Regex::new(r#"<a href="([^"]+)">([^<]+)</a>"#).unwrap())
+}
+
+/// 链接文字就是网址本身（含 `www.` 自动补全的 `http://`）时视为裸链接，返回网址
+fn bare_url(caps: &Captures) -> Option<String> {
+    let href = html_escape::decode_html_entities(&caps[1]).to_string();
+    let text = html_escape::decode_html_entities(&caps[2]).to_string();
+    let is_bare =
+        text == href || format!("http://{}", text) == href || format!("https://{}", text) == href;
+    (is_bare && (href.starts_with("http://") || href.starts_with("https://"))).then_some(href)
+}
+
+/// HTML 中的裸链接地址（去重）
+pub fn find_bare_urls(html: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for caps in anchor_regex().captures_iter(html) {
+        if let Some(url) = bare_url(&caps) {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    urls
+}
+
+/// 可读的链接文字：有标题时为“域名 · 标题”，否则为域名加路径
+pub fn readable_label(url: &str, title: Option<&str>) -> String {
+    let Ok(parsed) = Url::parse(url) else {
+        return url.to_string();
+    };
+    let host = parsed
+        .host_str()
+        .unwrap_or_default()
+        .trim_start_matches("www.");
+    match title.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(title) => format!("{} · {}", host, title),
+        None => format!("{}{}", host, parsed.path().trim_end_matches('/')),
+    }
+}
+
+/// 按方式改写 HTML 中的裸链接，返回改写后的 HTML 和处理的链接数
+pub fn rewrite_bare_urls(
+    html: &str,
+    policy: BareUrlPolicy,
+    titles: &HashMap<String, String>,
+) -> (String, usize) {
+    if policy == BareUrlPolicy::Keep {
+        return (html.to_string(), 0);
+    }
+    let mut count = 0;
+    let html = anchor_regex().replace_all(html, |caps: &Captures| {
+        let Some(url) = bare_url(caps) else {
+            return caps[0].to_string();
+        };
+        count += 1;
+        match policy {
+            BareUrlPolicy::Keep => caps[0].to_string(),
+            BareUrlPolicy::Readable => format!(
+                r#"<a href="{}">{}</a>"#,
+                &caps[1],
+                html_escape::encode_text(&readable_label(
+                    &url,
+                    titles.get(&url).map(String::as_str)
+                ))
+            ),
+            BareUrlPolicy::Text => caps[2].to_string(),
+            BareUrlPolicy::Strip => String::new(),
+        }
+    });
+    (html.into_owned(), count)
+}
+
+/// 从网页中提取 `<title>`，合并空白并截断过长的标题
+pub fn extract_page_title(page: &str) -> Option<String> {
+    static TITLE_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = TITLE_REGEX.get_or_init(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+    let raw = regex.captures(page)?.get(1)?.as_str();
+    let title = html_escape::decode_html_entities(raw)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    Some(if title.chars().count() > MAX_TITLE_CHARS {
+        format!(
+            "{}…",
+            title.chars().take(MAX_TITLE_CHARS).collect::<String>()
+        )
+    } else {
+        title
+    })
+}
+
+type TitleCache = Arc<RwLock<HashMap<String, String>>>;
+
+/// 按平台配置改写正文中的裸链接
+pub struct BareUrlStage {
+    config: BareUrlConfig,
+    /// 由 [`BareUrlTitleStage`] 预先抓取的网页标题
+    titles: TitleCache,
+}
+
+impl BareUrlStage {
+    pub fn new(config: BareUrlConfig) -> Self {
+        Self {
+            config,
+            titles: TitleCache::default(),
+        }
+    }
+
+    fn policy_for(&self, platform: &str) -> Result<BareUrlPolicy> {
+        self.config
+            .platforms
+            .get(platform)
+            .unwrap_or(&self.config.default_policy)
+            .parse()
+    }
+
+    /// 有平台使用 readable 方式时，返回抓取网页标题的通用阶段（与本阶段共享标题缓存）
+    pub fn title_fetcher(&self, network: &NetworkConfig) -> Result<Option<BareUrlTitleStage>> {
+        let policies = std::iter::once(&self.config.default_policy)
+            .chain(self.config.platforms.values())
+            .map(|p| p.parse::<BareUrlPolicy>())
+            .collect::<Result<Vec<_>>>()?;
+        if !policies.contains(&BareUrlPolicy::Readable) {
+            return Ok(None);
+        }
+        Ok(Some(BareUrlTitleStage {
+            client: client_for(network, NetworkOperation::LinkCheck)?,
+            max_concurrency: network.max_concurrency,
+            titles: self.titles.clone(),
+        }))
+    }
+}
+
+impl PlatformStage for BareUrlStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        let policy = self.policy_for(&platform.to_string())?;
+        let titles = self.titles.read().unwrap_or_else(|e| e.into_inner());
+        let (html, count) = rewrite_bare_urls(&content.html, policy, &titles);
+        content.html = html;
+        match policy {
+            BareUrlPolicy::Strip if count > 0 => {
+                report.stripped_elements.push(format!("{} 个裸链接", count))
+            }
+            BareUrlPolicy::Readable | BareUrlPolicy::Text if count > 0 => {
+                report.converted_links.push(format!("{} 个裸链接", count))
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "裸链接"
+    }
+}
+
+/// 抓取裸链接的网页标题，供 readable 方式生成链接文字；失败时只用域名和路径
+pub struct BareUrlTitleStage {
+    client: reqwest::Client,
+    max_concurrency: usize,
+    titles: TitleCache,
+}
+
+#[async_trait]
+impl ProcessingStage for BareUrlTitleStage {
+    async fn process(&self, content: &mut Content) -> Result<()> {
+        let urls: Vec<String> = {
+            let titles = self.titles.read().unwrap_or_else(|e| e.into_inner());
+            find_bare_urls(&content.html)
+                .into_iter()
+                .filter(|url| !titles.contains_key(url))
+                .collect()
+        };
+        let client = self.client.clone();
+        let fetched = map_concurrent(urls, self.max_concurrency, move |url| {
+            let client = client.clone();
+            async move {
+                let page = client.get(&url).send().await.ok()?.text().await.ok()?;
+                extract_page_title(&page).map(|title| (url, title))
+            }
+        })
+        .await;
+        self.titles
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(fetched.into_iter().flatten());
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "网页标题"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HTML: &str = r#"<p>见 <a href="https://www.rust-lang.org/learn">https://www.rust-lang.org/learn</a> 和 <a href="http://www.example.com">www.example.com</a>，<a href="https://docs.rs">文档</a></p>"#;
+
+    #[test]
+    fn test_rewrite_bare_urls() {
+        assert_eq!(
+            find_bare_urls(HTML),
+            vec!["https://www.rust-lang.org/learn", "http://www.example.com"]
+        );
+
+        let titles = HashMap::from([(
+            "https://www.rust-lang.org/learn".to_string(),
+            "Learn Rust".to_string(),
+        )]);
+        let (readable, count) = rewrite_bare_urls(HTML, BareUrlPolicy::Readable, &titles);
+        assert_eq!(count, 2);
+        assert!(readable.contains(
+            r#"<a href="https://www.rust-lang.org/learn">rust-lang.org · Learn Rust</a>"#
+        ));
+        assert!(readable.contains(r#"<a href="http://www.example.com">example.com</a>"#));
+        assert!(readable.contains(r#"<a href="https://docs.rs">文档</a>"#));
+
+        let (text, _) = rewrite_bare_urls(HTML, BareUrlPolicy::Text, &titles);
+        assert!(text.contains("见 https://www.rust-lang.org/learn 和 www.example.com，"));
+        let (stripped, _) = rewrite_bare_urls(HTML, BareUrlPolicy::Strip, &titles);
+        assert!(stripped.starts_with("<p>见  和 ，"));
+    }
+
+    #[test]
+    fn test_extract_page_title() {
+        assert_eq!(
+            extract_page_title("<html><head><TITLE>\n  Rust &amp; Cargo\n</TITLE></head>")
+                .as_deref(),
+            Some("Rust & Cargo")
+        );
+        assert_eq!(extract_page_title("<title> </title>"), None);
+        let long = format!("<title>{}</title>", "长".repeat(50));
+        assert_eq!(
+            extract_page_title(&long).unwrap().chars().count(),
+            MAX_TITLE_CHARS + 1
+        );
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{core::store::content_hash, Result};
+use std::collections::{HashMap, HashSet};
+
+/// 缓存条目数超过该值时，只保留最近一次渲染用到的块
+const MAX_ENTRIES: usize = 4096;
+
+/// 按顶层 Markdown 块（段落、列表、代码块等）内容哈希缓存渲染结果，
+/// 实时预览编辑长文时只需重新渲染改动过的块
+#[derive(Debug, Default)]
+pub struct BlockCache {
+    entries: HashMap<String, String>,
+    used: HashSet<String>,
+    /// 影响渲染结果的选项，变化时缓存失效
+    options: String,
+    hits: usize,
+    misses: usize,
+}
+
+impl BlockCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 开始一次新的渲染，重置统计。`options` 描述影响渲染结果的选项
+    /// （如按文章语言调整的智能标点），与上次不同时清空缓存
+    pub fn begin(&mut self, options: &str) {
+        if self.options != options {
+            self.entries.clear();
+            self.options = options.to_string();
+        }
+        self.used.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    pub fn get_or_render<F>(&mut self, block: &str, render: F) -> Result<String>
+    where
+        F: FnOnce(&str) -> Result<String>,
+    {
+        let key = content_hash(block.as_bytes());
+        let html = match self.entries.get(&key) {
+            Some(html) => {
+                self.hits += 1;
+                html.clone()
+            }
+            None => {
+                self.misses += 1;
+                let html = render(block)?;
+                self.entries.insert(key.clone(), html.clone());
+                html
+            }
+        };
+        self.used.insert(key);
+        Ok(html)
+    }
+
+    /// 结束本次渲染，缓存过大时淘汰本次未用到的块
+    pub fn finish(&mut self) {
+        if self.entries.len() > MAX_ENTRIES {
+            let used = &self.used;
+            self.entries.retain(|key, _| used.contains(key));
+        }
+    }
+
+    /// 最近一次渲染中命中缓存的块数
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// 最近一次渲染中重新渲染的块数
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
@@ -0,0 +1,596 @@
+use crate::{
+    cli::args::{AppConfig, BrowserConfig},
+    core::{
+        device::DeviceProfile, ensure_online, http_client_for, paths::find_in_path,
+        NetworkOperation,
+    },
+    error::Error,
+    Result,
+};
+use serde::Deserialize;
+use std::{
+    collections::HashMap,
+    io::{Cursor, Read, Seek},
+    path::{Path, PathBuf},
+    process::Stdio,
+    time::Duration,
+};
+use thirtyfour::{ChromeCapabilities, DesiredCapabilities, WebDriver};
+
+/// Chrome for Testing 的版本清单，提供各平台配套的无头浏览器和 chromedriver
+const CHROME_FOR_TESTING_URL: &str = "https://googlechromelabs.github.io/chrome-for-testing/last-known-good-versions-with-downloads.json";
+
+const CHROME_BINARIES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+    "chrome",
+];
+
+/// 下载的无头浏览器和 chromedriver 在压缩包中的名称
+const HEADLESS_SHELL: &str = "chrome-headless-shell";
+const CHROMEDRIVER: &str = "chromedriver";
+
+/// 等待 chromedriver 开始监听端口的最长时间
+const DRIVER_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 下载到本地的一套浏览器和 chromedriver
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrowserInstall {
+    pub version: String,
+    pub executable: PathBuf,
+    pub driver: PathBuf,
+}
+
+/// 是否运行在 Docker、Podman、Kubernetes 等容器中
+pub fn is_container() -> bool {
+    if Path::new("/.dockerenv").exists()
+        || Path::new("/run/.containerenv").exists()
+        || std::env::var_os("container").is_some()
+    {
+        return true;
+    }
+    std::fs::read_to_string("/proc/1/cgroup").is_ok_and(|cgroup| {
+        ["docker", "kubepods", "containerd", "podman"]
+            .iter()
+            .any(|name| cgroup.contains(name))
+    })
+}
+
+fn is_root() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::MetadataExt;
+        std::fs::metadata("/proc/self").is_ok_and(|meta| meta.uid() == 0)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// Chrome 在容器中通常无法创建沙箱，以 root 运行时也会拒绝启动
+pub fn sandbox_disabled(config: &BrowserConfig) -> bool {
+    config
+        .no_sandbox
+        .unwrap_or_else(|| is_container() || is_root())
+}
+
+/// 启动浏览器的参数：无头模式、固定缩放，容器中关闭沙箱并不使用容量很小的 /dev/shm
+pub fn browser_args(config: &BrowserConfig) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--headless",
+        "--hide-scrollbars",
+        "--disable-gpu",
+        "--force-device-scale-factor=1",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    if sandbox_disabled(config) {
+        args.push("--no-sandbox".to_string());
+        args.push("--disable-dev-shm-usage".to_string());
+    }
+    args.extend(config.args.iter().cloned());
+    args
+}
+
+/// Chrome for Testing 的平台标识，没有对应构建的平台（如 Linux ARM）返回 `None`
+fn chrome_for_testing_platform() -> Option<&'static str> {
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => Some("linux64"),
+        ("macos", "aarch64") => Some("mac-arm64"),
+        ("macos", "x86_64") => Some("mac-x64"),
+        ("windows", "x86_64") => Some("win64"),
+        ("windows", "x86") => Some("win32"),
+        _ => None,
+    }
+}
+
+fn binary_name(name: &str) -> String {
+    format!("{}{}", name, std::env::consts::EXE_SUFFIX)
+}
+
+fn parse_version(version: &str) -> Vec<u32> {
+    version
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+/// 缓存目录中版本最新、且浏览器和 chromedriver 都齐全的一套
+pub fn installed_browser(cache_dir: &Path) -> Option<BrowserInstall> {
+    let platform = chrome_for_testing_platform()?;
+    std::fs::read_dir(cache_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|version| version.starts_with(|c: char| c.is_ascii_digit()))
+        .filter_map(|version| {
+            let root = cache_dir.join(&version);
+            let install = BrowserInstall {
+                executable: root
+                    .join(format!("{}-{}", HEADLESS_SHELL, platform))
+                    .join(binary_name(HEADLESS_SHELL)),
+                driver: root
+                    .join(format!("{}-{}", CHROMEDRIVER, platform))
+                    .join(binary_name(CHROMEDRIVER)),
+                version,
+            };
+            (install.executable.is_file() && install.driver.is_file()).then_some(install)
+        })
+        .max_by_key(|install| parse_version(&install.version))
+}
+
+/// 依次查找 `browser.driver`、已下载的 chromedriver 和 PATH
+pub fn find_driver(config: &BrowserConfig) -> Option<PathBuf> {
+    config
+        .driver
+        .clone()
+        .filter(|path| path.is_file())
+        .or_else(|| installed_browser(&config.cache_dir).map(|install| install.driver))
+        .or_else(|| find_in_path(CHROMEDRIVER))
+}
+
+/// 依次查找 `browser.executable`、环境变量 CHROME_PATH、已下载的浏览器和 PATH；
+/// 都找不到时交给 chromedriver 按默认位置查找
+pub fn find_executable(config: &BrowserConfig) -> Option<PathBuf> {
+    config
+        .executable
+        .clone()
+        .filter(|path| path.is_file())
+        .or_else(|| {
+            std::env::var_os("CHROME_PATH")
+                .map(PathBuf::from)
+                .filter(|path| path.is_file())
+        })
+        .or_else(|| installed_browser(&config.cache_dir).map(|install| install.executable))
+        .or_else(|| CHROME_BINARIES.iter().find_map(|name| find_in_path(name)))
+}
+
+/// 从 `--version` 的输出中取主版本号，如 `ChromeDriver 120.0.6099.109 (...)` 返回 120
+pub fn parse_major_version(output: &str) -> Option<u32> {
+    output
+        .split_whitespace()
+        .find(|token| token.contains('.') && token.starts_with(|c: char| c.is_ascii_digit()))
+        .and_then(|token| token.split('.').next())
+        .and_then(|major| major.parse().ok())
+}
+
+/// 运行 `<程序> --version` 获取主版本号，用于检查浏览器与 chromedriver 是否匹配
+pub fn major_version(program: &Path) -> Option<u32> {
+    let output = std::process::Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    parse_major_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[derive(Deserialize)]
+struct ChromeForTesting {
+    channels: HashMap<String, ChromeForTestingChannel>,
+}
+
+#[derive(Deserialize)]
+struct ChromeForTestingChannel {
+    version: String,
+    downloads: HashMap<String, Vec<ChromeForTestingDownload>>,
+}
+
+#[derive(Deserialize)]
+struct ChromeForTestingDownload {
+    platform: String,
+    url: String,
+}
+
+/// 下载最新稳定版的 Chrome for Testing 无头浏览器和配套的 chromedriver 到缓存目录
+pub async fn install_browser(cache_dir: &Path) -> Result<BrowserInstall> {
+    ensure_online("下载浏览器")?;
+    let platform = chrome_for_testing_platform().ok_or_else(|| {
+        Error::Browser(format!(
+            "没有适用于 {}-{} 的 Chrome for Testing，请自行安装 Chromium 和 chromedriver 并配置 browser.executable、browser.driver",
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        ))
+    })?;
+
+    let client = http_client_for(NetworkOperation::Download)?;
+    let manifest: ChromeForTesting = client
+        .get(CHROME_FOR_TESTING_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    let stable = manifest
+        .channels
+        .get("Stable")
+        .ok_or_else(|| Error::Browser("Chrome for Testing 清单中没有 Stable 版本".to_string()))?;
+
+    if let Some(install) =
+        installed_browser(cache_dir).filter(|install| install.version == stable.version)
+    {
+        return Ok(install);
+    }
+
+    // 先解压到临时目录，全部完成后再改名，避免中断后留下不完整的版本目录
+    let staging = cache_dir.join(format!(".{}.partial", stable.version));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    std::fs::create_dir_all(&staging)?;
+    for kind in [HEADLESS_SHELL, CHROMEDRIVER] {
+        let url = stable
+            .downloads
+            .get(kind)
+            .and_then(|downloads| downloads.iter().find(|d| d.platform == platform))
+            .map(|download| download.url.clone())
+            .ok_or_else(|| {
+                Error::Browser(format!(
+                    "Chrome for Testing 没有提供 {} 的 {}",
+                    platform, kind
+                ))
+            })?;
+        tracing::info!("下载 {} {}: {}", kind, stable.version, url);
+        let archive = client
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        extract_zip(Cursor::new(archive), &staging)?;
+    }
+
+    let target = cache_dir.join(&stable.version);
+    if target.exists() {
+        std::fs::remove_dir_all(&target)?;
+    }
+    std::fs::rename(&staging, &target)?;
+    installed_browser(cache_dir)
+        .filter(|install| install.version == stable.version)
+        .ok_or_else(|| Error::Browser(format!("下载的浏览器不完整: {:?}", target)))
+}
+
+/// 解压整个压缩包（无头浏览器依赖同目录下的资源文件），保留可执行权限
+fn extract_zip<R: Read + Seek>(reader: R, target: &Path) -> Result<()> {
+    let mut archive = zip::ZipArchive::new(reader)
+        .map_err(|e| Error::Browser(format!("无法读取浏览器压缩包: {}", e)))?;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| Error::Browser(format!("无法读取浏览器压缩包: {}", e)))?;
+        // 跳过试图写到目标目录之外的条目
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let path = target.join(name);
+        if entry.is_dir() {
+            std::fs::create_dir_all(&path)?;
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::File::create(&path)?;
+        std::io::copy(&mut entry, &mut file)?;
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+    }
+    Ok(())
+}
+
+/// 由本进程启动的 chromedriver，进程结束时随之退出
+struct DriverService {
+    child: tokio::process::Child,
+    url: String,
+}
+
+impl DriverService {
+    async fn start(driver: &Path) -> Result<Self> {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")?
+            .local_addr()?
+            .port();
+        let mut child = tokio::process::Command::new(driver)
+            .arg(format!("--port={}", port))
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| Error::Browser(format!("无法启动 {:?}: {}", driver, e)))?;
+
+        let started = std::time::Instant::now();
+        while tokio::net::TcpStream::connect(("127.0.0.1", port))
+            .await
+            .is_err()
+        {
+            if let Some(status) = child.try_wait()? {
+                return Err(Error::Browser(format!(
+                    "chromedriver 启动后立即退出（{}）",
+                    status
+                )));
+            }
+            if started.elapsed() > DRIVER_STARTUP_TIMEOUT {
+                return Err(Error::Browser("等待 chromedriver 启动超时".to_string()));
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        tracing::debug!("已启动 chromedriver {:?}，端口 {}", driver, port);
+
+        Ok(Self {
+            child,
+            url: format!("http://127.0.0.1:{}", port),
+        })
+    }
+}
+
+/// 复用浏览器会话和 chromedriver 进程，多个任务（如逐个平台截图）不必每次都重新启动浏览器
+pub struct BrowserPool {
+    config: BrowserConfig,
+    /// 没有本地 chromedriver 时连接的 WebDriver 服务（`snapshot.webdriver_url`）
+    fallback_url: String,
+    service: tokio::sync::Mutex<Option<DriverService>>,
+    idle: std::sync::Mutex<Vec<WebDriver>>,
+    /// 无头会话模拟的设备
+    device: Option<DeviceProfile>,
+}
+
+impl BrowserPool {
+    pub fn new(config: BrowserConfig, fallback_url: impl Into<String>) -> Self {
+        Self {
+            config,
+            fallback_url: fallback_url.into(),
+            service: tokio::sync::Mutex::new(None),
+            idle: std::sync::Mutex::new(Vec::new()),
+            device: None,
+        }
+    }
+
+    /// 使用 `browser.device` 模拟设备；设备名无效时记录警告并使用浏览器默认设置
+    pub fn from_config(config: &AppConfig) -> Self {
+        let device =
+            config
+                .browser
+                .device
+                .as_deref()
+                .and_then(|name| match config.device_profile(name) {
+                    Ok(device) => Some(device),
+                    Err(e) => {
+                        tracing::warn!("{}", e);
+                        None
+                    }
+                });
+        Self::new(
+            config.browser.clone(),
+            config.snapshot.webdriver_url.clone(),
+        )
+        .with_device(device)
+    }
+
+    pub fn with_device(mut self, device: Option<DeviceProfile>) -> Self {
+        self.device = device;
+        self
+    }
+
+    pub fn device(&self) -> Option<&DeviceProfile> {
+        self.device.as_ref()
+    }
+
+    /// WebDriver 服务地址：优先使用 `browser.webdriver_url`，其次启动本地 chromedriver
+    /// （按需下载），最后退回 `snapshot.webdriver_url`
+    async fn endpoint(&self) -> Result<String> {
+        if let Some(url) = &self.config.webdriver_url {
+            return Ok(url.clone());
+        }
+
+        let mut service = self.service.lock().await;
+        if let Some(running) = service.as_mut() {
+            if running.child.try_wait()?.is_none() {
+                return Ok(running.url.clone());
+            }
+        }
+
+        let driver = match find_driver(&self.config) {
+            Some(driver) => driver,
+            None if self.config.auto_download => {
+                install_browser(&self.config.cache_dir).await?.driver
+            }
+            None => return Ok(self.fallback_url.clone()),
+        };
+        let started = DriverService::start(&driver).await?;
+        let url = started.url.clone();
+        *service = Some(started);
+        Ok(url)
+    }
+
+    fn capabilities(&self, headless: bool) -> Result<ChromeCapabilities> {
+        let mut caps = DesiredCapabilities::chrome();
+        if let Some(executable) = find_executable(&self.config) {
+            caps.set_binary(&executable.to_string_lossy())
+                .map_err(browser_error)?;
+        }
+        for arg in browser_args(&self.config) {
+            if !headless && arg == "--headless" {
+                continue;
+            }
+            caps.add_chrome_arg(&arg).map_err(browser_error)?;
+        }
+        // 有界面的窗口（如手动登录）保持桌面浏览器的样子
+        if let Some(device) = self.device.as_ref().filter(|_| headless) {
+            for arg in device.browser_args() {
+                caps.add_chrome_arg(&arg).map_err(browser_error)?;
+            }
+        }
+        Ok(caps)
+    }
+
+    async fn connect(&self, headless: bool) -> Result<WebDriver> {
+        let url = self.endpoint().await?;
+        WebDriver::new(&url, self.capabilities(headless)?)
+            .await
+            .map_err(|e| {
+                Error::Browser(format!(
+                    "无法连接 WebDriver {}（运行 markflow browser install 下载浏览器，或先启动 chromedriver）: {}",
+                    url, e
+                ))
+            })
+    }
+
+    /// 取出一个空闲会话，没有时新建
+    pub async fn acquire(&self) -> Result<WebDriver> {
+        let idle = self.idle.lock().unwrap().pop();
+        if let Some(driver) = idle {
+            return Ok(driver);
+        }
+
+        self.connect(true).await
+    }
+
+    /// 打开一个有界面的浏览器窗口（如让用户手动登录），用完后直接 `quit`，不放回池中
+    pub async fn open_window(&self) -> Result<WebDriver> {
+        self.connect(false).await
+    }
+
+    /// 归还会话；空闲会话已满或会话已失效时直接关闭
+    pub async fn release(&self, driver: WebDriver) {
+        let driver = if driver.goto("about:blank").await.is_ok() {
+            let mut idle = self.idle.lock().unwrap();
+            if idle.len() < self.config.pool_size {
+                idle.push(driver);
+                None
+            } else {
+                Some(driver)
+            }
+        } else {
+            Some(driver)
+        };
+        if let Some(driver) = driver {
+            let _ = driver.quit().await;
+        }
+    }
+
+    /// 关闭所有空闲会话和本进程启动的 chromedriver
+    pub async fn shutdown(&self) {
+        let idle = std::mem::take(&mut *self.idle.lock().unwrap());
+        for driver in idle {
+            let _ = driver.quit().await;
+        }
+        if let Some(mut service) = self.service.lock().await.take() {
+            let _ = service.child.kill().await;
+        }
+    }
+}
+
+pub(crate) fn browser_error(e: thirtyfour::error::WebDriverError) -> Error {
+    Error::Browser(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::TempDir;
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    #[test]
+    fn test_parse_major_version() {
+        assert_eq!(
+            parse_major_version("ChromeDriver 120.0.6099.109 (3419140ab665596f21b385ce136419fde0924272-refs/branch-heads/6099@{#1483})"),
+            Some(120)
+        );
+        assert_eq!(
+            parse_major_version("Google Chrome for Testing 121.0.6167.85 \n"),
+            Some(121)
+        );
+        assert_eq!(parse_major_version("Chromium"), None);
+    }
+
+    #[test]
+    fn test_browser_args_sandbox() {
+        let mut config = BrowserConfig {
+            no_sandbox: Some(true),
+            args: vec!["--lang=zh-CN".to_string()],
+            ..BrowserConfig::default()
+        };
+        let args = browser_args(&config);
+        assert!(args.contains(&"--no-sandbox".to_string()));
+        assert!(args.contains(&"--disable-dev-shm-usage".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("--lang=zh-CN"));
+
+        config.no_sandbox = Some(false);
+        assert!(!browser_args(&config).contains(&"--no-sandbox".to_string()));
+    }
+
+    #[test]
+    fn test_extract_and_find_installed_browser() {
+        let Some(platform) = chrome_for_testing_platform() else {
+            return;
+        };
+        let dir = TempDir::new().unwrap();
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().unix_permissions(0o755);
+        for kind in [HEADLESS_SHELL, CHROMEDRIVER] {
+            writer
+                .start_file(
+                    format!("{}-{}/{}", kind, platform, binary_name(kind)),
+                    options,
+                )
+                .unwrap();
+            writer.write_all(b"binary").unwrap();
+        }
+        writer.start_file("../escape", options).unwrap();
+        let archive = writer.finish().unwrap().into_inner();
+
+        extract_zip(Cursor::new(archive.clone()), &dir.path().join("119.0.1")).unwrap();
+        extract_zip(Cursor::new(archive), &dir.path().join("120.0.2")).unwrap();
+        // 缺少 chromedriver 的版本不算安装完整
+        std::fs::create_dir_all(dir.path().join("121.0.0")).unwrap();
+        assert!(!dir.path().join("escape").exists());
+
+        let install = installed_browser(dir.path()).unwrap();
+        assert_eq!(install.version, "120.0.2");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&install.driver)
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o111, 0o111);
+        }
+
+        let config = BrowserConfig {
+            cache_dir: dir.path().to_path_buf(),
+            ..BrowserConfig::default()
+        };
+        assert_eq!(find_driver(&config), Some(install.driver));
+    }
+}
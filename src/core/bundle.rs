@@ -0,0 +1,287 @@
+use crate::{
+    core::{
+        article_assets::ArticleAssets,
+        output::{img_src_regex, is_local_src},
+        paths::sanitize_filename,
+        report::ProcessingReport,
+        store::{content_hash, ContentRecord},
+    },
+    error::Error,
+    Result,
+};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use zip::{write::SimpleFileOptions, ZipWriter};
+
+const BUNDLE_MANIFEST: &str = "manifest.json";
+const BUNDLE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize)]
+struct BundleManifest {
+    version: u32,
+    id: uuid::Uuid,
+    title: String,
+    exported_at: chrono::DateTime<chrono::Utc>,
+    files: Vec<BundleFile>,
+}
+
+/// 包内文件及其 SHA-256，便于归档后核对内容未被改动
+#[derive(Debug, Serialize)]
+struct BundleFile {
+    path: String,
+    sha256: String,
+}
+
+/// 打包结果摘要
+#[derive(Debug, Default)]
+pub struct BundleSummary {
+    pub files: usize,
+    /// 台账中记录了、但已经找不到的源文件或输出文件
+    pub missing: Vec<PathBuf>,
+}
+
+struct BundleWriter {
+    zip: ZipWriter<File>,
+    files: Vec<BundleFile>,
+    /// 图片内容哈希 → 包内文件名
+    images: HashMap<String, String>,
+}
+
+fn zip_error(e: zip::result::ZipError) -> Error {
+    Error::Other(format!("写入 ZIP 失败: {}", e))
+}
+
+impl BundleWriter {
+    fn add(&mut self, path: &str, data: &[u8]) -> Result<()> {
+        self.zip
+            .start_file(path, SimpleFileOptions::default())
+            .map_err(zip_error)?;
+        self.zip.write_all(data)?;
+        self.files.push(BundleFile {
+            path: path.to_string(),
+            sha256: content_hash(data),
+        });
+        Ok(())
+    }
+
+    /// 把图片放进 `images/`，返回包内文件名；相同内容只放一份，同名不同内容的图片加哈希前缀
+    fn add_image(&mut self, path: &Path) -> Result<Option<String>> {
+        let Ok(data) = std::fs::read(path) else {
+            return Ok(None);
+        };
+        let hash = content_hash(&data);
+        if let Some(name) = self.images.get(&hash) {
+            return Ok(Some(name.clone()));
+        }
+        let mut name = sanitize_filename(
+            &path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        );
+        if self.images.values().any(|n| n == &name) {
+            name = format!("{}-{}", &hash[..8], name);
+        }
+        self.add(&format!("images/{}", name), &data)?;
+        self.images.insert(hash, name.clone());
+        Ok(Some(name))
+    }
+}
+
+/// Markdown 中 `![alt](path)` 引用的图片地址
+fn markdown_images(markdown: &str) -> Vec<String> {
+    static IMAGE_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = IMAGE_REGEX.get_or_init(|| Regex::new(r#"!\[[^\]]*\]\(<?([^)\s>]+)"#).unwrap());
+    regex
+        .captures_iter(markdown)
+        .map(|caps| caps[1].to_string())
+        .collect()
+}
+
+/// 把一篇文章打包为 zip，供合规归档或交接：
+///
+/// - `source/`：源 Markdown
+/// - `html/<平台>.html`：各平台适配后的 HTML，本地图片改为引用包内的 `images/`
+/// - `images/`：源文件和输出中引用的本地图片（已上传到平台的网络图片不下载）
+/// - `ledger.json`：台账记录，含发布结果和审核状态
+/// - `report.md`：最近一次处理的报告
+/// - `manifest.json`：包内文件清单及 SHA-256
+pub fn export_bundle(record: &ContentRecord, dest: &Path) -> Result<BundleSummary> {
+    let mut summary = BundleSummary::default();
+    let mut writer = BundleWriter {
+        zip: ZipWriter::new(File::create(dest)?),
+        files: Vec::new(),
+        images: HashMap::new(),
+    };
+
+    if let Some(source) = &record.source {
+        match std::fs::read_to_string(source) {
+            Ok(markdown) => {
+                let name = source
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "article.md".to_string());
+                writer.add(&format!("source/{}", name), markdown.as_bytes())?;
+                let assets = ArticleAssets::for_source(source);
+                for src in markdown_images(&markdown) {
+                    if let Some(path) = assets.resolve(&src) {
+                        writer.add_image(&path)?;
+                    }
+                }
+            }
+            Err(_) => summary.missing.push(source.clone()),
+        }
+    }
+
+    let assets = record.source.as_deref().map(ArticleAssets::for_source);
+    for (key, output) in &record.outputs {
+        let Ok(html) = std::fs::read_to_string(output) else {
+            summary.missing.push(output.clone());
+            continue;
+        };
+        // 输出中的本地图片相对输出文件（资源目录）或源文件
+        let output_dir = output.parent().unwrap_or_else(|| Path::new("."));
+        let mut error = None;
+        let html = img_src_regex().replace_all(&html, |caps: &Captures| {
+            let src = html_escape::decode_html_entities(&caps[2]).to_string();
+            let relative = src.split(['?', '#']).next().unwrap_or_default();
+            if !is_local_src(relative) {
+                return caps[0].to_string();
+            }
+            let candidates = std::iter::once(output_dir.join(relative))
+                .chain(assets.as_ref().and_then(|assets| assets.resolve(&src)));
+            for candidate in candidates.filter(|p| p.is_file()) {
+                match writer.add_image(&candidate) {
+                    Ok(Some(name)) => {
+                        return format!(
+                            "{}../images/{}{}",
+                            &caps[1],
+                            html_escape::encode_double_quoted_attribute(&name),
+                            &caps[3]
+                        )
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+            caps[0].to_string()
+        });
+        if let Some(e) = error {
+            return Err(e);
+        }
+        writer.add(
+            &format!("html/{}.html", sanitize_filename(key)),
+            html.as_bytes(),
+        )?;
+    }
+
+    writer.add("ledger.json", &serde_json::to_vec_pretty(record)?)?;
+    if let Some(article) = &record.report {
+        let report = ProcessingReport {
+            generated_at: record.updated_at,
+            articles: vec![article.clone()],
+        };
+        writer.add("report.md", report.to_markdown().as_bytes())?;
+    }
+
+    summary.files = writer.files.len();
+    let manifest = BundleManifest {
+        version: BUNDLE_VERSION,
+        id: record.id,
+        title: record.title.clone(),
+        exported_at: chrono::Utc::now(),
+        files: std::mem::take(&mut writer.files),
+    };
+    writer.add(BUNDLE_MANIFEST, &serde_json::to_vec_pretty(&manifest)?)?;
+    writer.zip.finish().map_err(zip_error)?;
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{content::Platform, report::ArticleReport, report::PlatformReport};
+    use std::io::Read;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_export_bundle() {
+        let dir = TempDir::new().unwrap();
+        let source = dir.path().join("post.md");
+        std::fs::write(&source, "# 标题\n\n![图](images/cover.png)\n").unwrap();
+        std::fs::create_dir_all(dir.path().join("images")).unwrap();
+        std::fs::write(dir.path().join("images/cover.png"), b"png").unwrap();
+        let output = dir.path().join("post_wechat.html");
+        std::fs::write(
+            &output,
+            r#"<img src="images/cover.png"><img src="https://cdn.example.com/a.png">"#,
+        )
+        .unwrap();
+
+        let mut report = ArticleReport::new("标题", Some(source.clone()));
+        let mut platform = PlatformReport::new(Platform::WeChat);
+        platform
+            .warnings
+            .push("secret: 第 3 行疑似邮箱地址".to_string());
+        report.platforms.push(platform);
+        let record = ContentRecord {
+            id: uuid::Uuid::new_v4(),
+            title: "标题".to_string(),
+            source: Some(source),
+            content_hash: content_hash(b"x"),
+            outputs: [
+                ("wechat".to_string(), output),
+                ("zhihu".to_string(), dir.path().join("gone.html")),
+            ]
+            .into_iter()
+            .collect(),
+            html: Default::default(),
+            publications: Vec::new(),
+            review_after: None,
+            title_variants: Default::default(),
+            tags: Vec::new(),
+            slug: None,
+            canonical_url: None,
+            workflow: Default::default(),
+            report: Some(report),
+            updated_at: chrono::Utc::now(),
+        };
+
+        let dest = dir.path().join("bundle.zip");
+        let summary = export_bundle(&record, &dest).unwrap();
+        assert_eq!(summary.files, 5);
+        assert_eq!(summary.missing, vec![dir.path().join("gone.html")]);
+
+        let mut archive = zip::ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+        let mut names: Vec<_> = archive.file_names().map(str::to_string).collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "html/wechat.html",
+                "images/cover.png",
+                "ledger.json",
+                "manifest.json",
+                "report.md",
+                "source/post.md"
+            ]
+        );
+        let mut html = String::new();
+        archive
+            .by_name("html/wechat.html")
+            .unwrap()
+            .read_to_string(&mut html)
+            .unwrap();
+        assert!(html.contains(r#"<img src="../images/cover.png">"#));
+        assert!(html.contains("https://cdn.example.com/a.png"));
+    }
+}
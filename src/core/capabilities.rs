@@ -0,0 +1,236 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{
+        browser::find_driver,
+        content::Platform,
+        http::{ensure_online, is_offline},
+    },
+    error::Error,
+    publishers::PublishBackendKind,
+    Result,
+};
+use std::{
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// 命令依赖的可选子系统。命令开始执行前逐项检查，缺少配置时直接给出具体的修复方法，
+/// 而不是执行到一半才报错
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// 联网（未开启离线模式）
+    Network,
+    /// 浏览器自动化：WebDriver 服务或本地 chromedriver
+    Browser,
+    /// 公众号开放接口凭据
+    WeChatApi,
+    /// 公众号后台登录 Cookie（browser 发布途径）
+    WeChatLogin,
+    /// 知乎登录 Cookie
+    ZhihuLogin,
+    /// 生成图片描述的视觉模型
+    VisionModel,
+    /// 摘要邮件的 SMTP 配置
+    Email,
+}
+
+impl Capability {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Network => "网络访问",
+            Self::Browser => "浏览器自动化",
+            Self::WeChatApi => "公众号接口",
+            Self::WeChatLogin => "公众号后台登录",
+            Self::ZhihuLogin => "知乎登录",
+            Self::VisionModel => "图片描述模型",
+            Self::Email => "摘要邮件",
+        }
+    }
+
+    /// 检查当前配置能否提供该能力，不发起网络请求
+    pub fn check(&self, config: &AppConfig) -> Result<()> {
+        match self {
+            Self::Network => ensure_online("访问网络"),
+            Self::Browser => {
+                let browser = &config.browser;
+                if browser.webdriver_url.is_some()
+                    || find_driver(browser).is_some()
+                    || (browser.auto_download && !is_offline())
+                    || webdriver_reachable(&config.snapshot.webdriver_url)
+                {
+                    return Ok(());
+                }
+                Err(self.unavailable(
+                    format!(
+                        "找不到 chromedriver，{} 也无法连接",
+                        config.snapshot.webdriver_url
+                    ),
+                    "运行 markflow browser install，或设置 browser.webdriver_url 指向已运行的 WebDriver 服务",
+                ))
+            }
+            Self::WeChatApi => {
+                let wechat = &config.wechat;
+                if wechat.access_token.is_some()
+                    || (wechat.app_id.is_some() && wechat.app_secret.is_some())
+                {
+                    return Ok(());
+                }
+                Err(self.unavailable(
+                    "未配置 app_id/app_secret",
+                    "markflow config set wechat.app_id <AppID> 并设置 wechat.app_secret，或设置 wechat.backend = \"browser\"",
+                ))
+            }
+            Self::WeChatLogin => match &config.wechat.cookies_file {
+                Some(_) => Ok(()),
+                None => Err(self.unavailable(
+                    "未配置 wechat.cookies_file",
+                    "markflow config set wechat.cookies_file <Cookie 文件路径>，或设置 wechat.backend = \"api\"",
+                )),
+            },
+            // 登录过期由发布器在运行时处理（可以交互登录），这里只要求配置了 Cookie 文件
+            Self::ZhihuLogin => match &config.zhihu.cookies_file {
+                Some(_) => Ok(()),
+                None => Err(self.unavailable(
+                    "未配置 zhihu.cookies_file",
+                    "markflow config set zhihu.cookies_file <Cookie 文件路径>",
+                )),
+            },
+            Self::VisionModel => {
+                let env = &config.alt_text.api_key_env;
+                if std::env::var_os(env).is_some() {
+                    return Ok(());
+                }
+                Err(self.unavailable(
+                    format!("未设置环境变量 {}（API Key）", env),
+                    format!(
+                        "设置环境变量 {}，或 markflow config set alt_text.enabled false",
+                        env
+                    ),
+                ))
+            }
+            Self::Email => {
+                let email = &config.notifications.email;
+                if email.smtp_host.is_none() || email.from.is_none() || email.to.is_empty() {
+                    return Err(self.unavailable(
+                        "SMTP 服务器、发件人或收件人未配置",
+                        "设置 notifications.email.smtp_host、notifications.email.from 和 notifications.email.to",
+                    ));
+                }
+                if email.username.is_some() && std::env::var_os(&email.password_env).is_none() {
+                    return Err(self.unavailable(
+                        format!("未设置环境变量 {}（SMTP 密码）", email.password_env),
+                        format!("设置环境变量 {}", email.password_env),
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn unavailable(&self, reason: impl std::fmt::Display, fix: impl std::fmt::Display) -> Error {
+        Error::Config(format!("{}不可用：{}（{}）", self.label(), reason, fix))
+    }
+}
+
+/// 发布到平台需要的能力，按平台配置的发布途径（如 `wechat.backend`）决定
+pub fn publish_capabilities(platform: &Platform, config: &AppConfig) -> Result<Vec<Capability>> {
+    let capabilities = match platform {
+        Platform::WeChat => match config.wechat.backend.parse()? {
+            PublishBackendKind::Api => vec![Capability::Network, Capability::WeChatApi],
+            PublishBackendKind::Browser => vec![
+                Capability::Network,
+                Capability::Browser,
+                Capability::WeChatLogin,
+            ],
+        },
+        Platform::Zhihu => vec![
+            Capability::Network,
+            Capability::Browser,
+            Capability::ZhihuLogin,
+        ],
+        // 不支持发布的平台由发布器给出错误
+        Platform::All | Platform::Custom(_) => Vec::new(),
+    };
+    Ok(capabilities)
+}
+
+/// 处理文章需要的能力：开启图片描述且联网时需要视觉模型（离线时该阶段会被跳过）
+pub fn process_capabilities(config: &AppConfig) -> Vec<Capability> {
+    if config.alt_text.enabled && !is_offline() {
+        vec![Capability::VisionModel]
+    } else {
+        Vec::new()
+    }
+}
+
+/// 依次检查，返回第一个不可用能力的错误
+pub fn require_capabilities(config: &AppConfig, capabilities: &[Capability]) -> Result<()> {
+    capabilities
+        .iter()
+        .try_for_each(|capability| capability.check(config))
+}
+
+/// WebDriver 服务是否在监听（只尝试建立 TCP 连接）
+fn webdriver_reachable(url: &str) -> bool {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return false;
+    };
+    (host, port)
+        .to_socket_addrs()
+        .map(|mut addrs| {
+            addrs.any(|addr| TcpStream::connect_timeout(&addr, Duration::from_millis(300)).is_ok())
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credential_capabilities() {
+        let mut config = AppConfig::default();
+        let err = Capability::WeChatApi.check(&config).unwrap_err();
+        assert!(err.to_string().contains("公众号接口不可用"));
+        assert!(err.to_string().contains("wechat.app_id"));
+        config.wechat.app_id = Some("wx1".to_string());
+        config.wechat.app_secret = Some("secret".to_string());
+        Capability::WeChatApi.check(&config).unwrap();
+
+        assert!(Capability::ZhihuLogin.check(&config).is_err());
+        config.zhihu.cookies_file = Some("zhihu.json".into());
+        Capability::ZhihuLogin.check(&config).unwrap();
+
+        let err = Capability::Email.check(&config).unwrap_err();
+        assert!(err.to_string().contains("notifications.email.smtp_host"));
+
+        config.alt_text.api_key_env = "MARKFLOW_TEST_MISSING_KEY".to_string();
+        let err = require_capabilities(&config, &[Capability::WeChatApi, Capability::VisionModel])
+            .unwrap_err();
+        assert!(err.to_string().contains("MARKFLOW_TEST_MISSING_KEY"));
+    }
+
+    #[test]
+    fn test_publish_capabilities() {
+        let mut config = AppConfig::default();
+        config.wechat.backend = "browser".to_string();
+        assert_eq!(
+            publish_capabilities(&Platform::WeChat, &config).unwrap(),
+            vec![
+                Capability::Network,
+                Capability::Browser,
+                Capability::WeChatLogin
+            ]
+        );
+        config.wechat.backend = "api".to_string();
+        assert_eq!(
+            publish_capabilities(&Platform::WeChat, &config).unwrap(),
+            vec![Capability::Network, Capability::WeChatApi]
+        );
+        config.wechat.backend = "ftp".to_string();
+        assert!(publish_capabilities(&Platform::WeChat, &config).is_err());
+    }
+}
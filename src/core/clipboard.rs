@@ -0,0 +1,166 @@
+use crate::{error::Error, Result};
+use regex::Regex;
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+const FRAGMENT_START: &str = "<!--StartFragment-->";
+const FRAGMENT_END: &str = "<!--EndFragment-->";
+
+/// 完整 HTML 文档中 `<body>` 的内容；片段原样返回
+pub fn body_fragment(html: &str) -> &str {
+    static BODY_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = BODY_REGEX.get_or_init(|| Regex::new(r"(?is)<body\b[^>]*>(.*)</body\s*>").unwrap());
+    regex
+        .captures(html)
+        .and_then(|caps| caps.get(1))
+        .map_or(html, |m| m.as_str())
+}
+
+/// 去掉标签后的纯文本，供只接受纯文本的程序粘贴
+fn html_text(html: &str) -> String {
+    static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = TAG_REGEX.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    html_escape::decode_html_entities(regex.replace_all(html, "").trim()).into_owned()
+}
+
+/// 生成 Windows 剪贴板的 `HTML Format`（CF_HTML）内容。
+///
+/// 头部记录 HTML 和片段在整段 UTF-8 字节中的起止偏移，编辑器粘贴时只取片段部分；
+/// 偏移固定为 10 位数字，头部长度因此不随内容变化
+pub fn cf_html(fragment: &str) -> String {
+    let header = |start_html: usize,
+                  end_html: usize,
+                  start_fragment: usize,
+                  end_fragment: usize| {
+        format!(
+            "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n",
+            start_html, end_html, start_fragment, end_fragment
+        )
+    };
+    let prefix = format!("<html>\r\n<body>\r\n{}", FRAGMENT_START);
+    let suffix = format!("{}\r\n</body>\r\n</html>", FRAGMENT_END);
+
+    let start_html = header(0, 0, 0, 0).len();
+    let start_fragment = start_html + prefix.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + suffix.len();
+    format!(
+        "{}{}{}{}",
+        header(start_html, end_html, start_fragment, end_fragment),
+        prefix,
+        fragment,
+        suffix
+    )
+}
+
+fn run_with_stdin(mut command: Command, input: &[u8]) -> Result<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| Error::Other(format!("无法执行 {}: {}", program, e)))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(Error::Other(format!(
+            "{} 写入剪贴板失败: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )))
+    }
+}
+
+/// 把 HTML（完整文档时只取 `<body>` 内容）作为富文本写入系统剪贴板，同时附带纯文本，
+/// 粘贴到公众号等网页编辑器时保留样式。
+///
+/// - Windows：通过 PowerShell 写入 CF_HTML 和 Unicode 文本
+/// - macOS：通过 osascript 写入 HTML 和文本
+/// - Linux：Wayland 下使用 `wl-copy`，否则使用 `xclip`（只写入 HTML）
+pub fn copy_html(html: &str) -> Result<()> {
+    let fragment = body_fragment(html);
+    let text = html_text(fragment);
+    if cfg!(target_os = "windows") {
+        let stem =
+            std::env::temp_dir().join(format!("markflow-clipboard-{}", uuid::Uuid::new_v4()));
+        let html_path = stem.with_extension("html");
+        let text_path = stem.with_extension("txt");
+        std::fs::write(&html_path, cf_html(fragment))?;
+        std::fs::write(&text_path, &text)?;
+        // HTML Format 按字节读取，保证 UTF-8 偏移与头部一致
+        let script = format!(
+            "Add-Type -AssemblyName System.Windows.Forms; \
+             $data = New-Object System.Windows.Forms.DataObject; \
+             $bytes = [System.IO.File]::ReadAllBytes('{}'); \
+             $data.SetData('HTML Format', (New-Object System.IO.MemoryStream(,$bytes))); \
+             $data.SetText([System.IO.File]::ReadAllText('{}', [System.Text.Encoding]::UTF8)); \
+             [System.Windows.Forms.Clipboard]::SetDataObject($data, $true)",
+            html_path.display().to_string().replace('\'', "''"),
+            text_path.display().to_string().replace('\'', "''"),
+        );
+        let mut command = Command::new("powershell");
+        command.args(["-NoProfile", "-STA", "-Command", &script]);
+        let result = run_with_stdin(command, b"");
+        let _ = std::fs::remove_file(&html_path);
+        let _ = std::fs::remove_file(&text_path);
+        result
+    } else if cfg!(target_os = "macos") {
+        let hex: String = fragment.bytes().map(|b| format!("{:02X}", b)).collect();
+        let text = text.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            "set the clipboard to {{«class HTML»:«data HTML{}», string:\"{}\"}}",
+            hex, text
+        );
+        let mut command = Command::new("osascript");
+        command.args(["-e", &script]);
+        run_with_stdin(command, b"")
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        let mut command = Command::new("wl-copy");
+        command.args(["--type", "text/html"]);
+        run_with_stdin(command, fragment.as_bytes())
+    } else {
+        let mut command = Command::new("xclip");
+        command.args(["-selection", "clipboard", "-t", "text/html"]);
+        run_with_stdin(command, fragment.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cf_html_offsets() {
+        let fragment = "<section style=\"color: #333\"><p>你好，世界</p></section>";
+        let payload = cf_html(fragment);
+
+        let offset = |name: &str| -> usize {
+            let line = payload.lines().find(|l| l.starts_with(name)).unwrap();
+            line[name.len() + 1..].parse().unwrap()
+        };
+        let bytes = payload.as_bytes();
+        assert!(payload.starts_with("Version:0.9\r\nStartHTML:"));
+        assert_eq!(
+            &bytes[offset("StartHTML")..offset("StartHTML") + 6],
+            b"<html>"
+        );
+        assert_eq!(
+            &bytes[offset("StartFragment")..offset("EndFragment")],
+            fragment.as_bytes()
+        );
+        assert_eq!(offset("EndHTML"), bytes.len());
+
+        assert_eq!(
+            body_fragment("<html><head></head><body class=\"a\"><p>正文</p></body></html>"),
+            "<p>正文</p>"
+        );
+    }
+}
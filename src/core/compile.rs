@@ -0,0 +1,396 @@
+use crate::{
+    core::{
+        frontmatter::{front_matter_fields, front_matter_text, split_front_matter},
+        source_format::SourceFormatter,
+        store::ContentRecord,
+    },
+    error::Error,
+    Result,
+};
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    Arena,
+};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// 系列中的一篇文章，来自台账中记录的源文件
+#[derive(Debug, Clone)]
+pub struct SeriesArticle {
+    pub source: PathBuf,
+    pub title: String,
+    /// front matter `part`，决定文章在合集中的顺序
+    pub part: Option<f64>,
+    pub date: Option<String>,
+    /// 去掉 front matter 的正文
+    pub body: String,
+}
+
+impl SeriesArticle {
+    /// 读取源文件；front matter `series` 包含 `series` 时返回文章
+    pub fn load(source: &Path, series: &str) -> Result<Option<Self>> {
+        let markdown = std::fs::read_to_string(source)?;
+        let (_, body) = split_front_matter(&markdown);
+        let fields = front_matter_fields(&markdown)
+            .map_err(|e| Error::Validation(format!("{:?}: {}", source, e)))?;
+
+        let in_series = match fields.get("series") {
+            Some(Value::Array(items)) => items
+                .iter()
+                .filter_map(front_matter_text)
+                .any(|name| same_series(&name, series)),
+            Some(value) => front_matter_text(value).is_some_and(|name| same_series(&name, series)),
+            None => false,
+        };
+        if !in_series {
+            return Ok(None);
+        }
+
+        let title = fields
+            .get("title")
+            .and_then(front_matter_text)
+            .or_else(|| {
+                body.lines()
+                    .find_map(|line| line.strip_prefix("# "))
+                    .map(|t| t.trim().to_string())
+            })
+            .unwrap_or_else(|| {
+                source
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into()
+            });
+        let part = fields.get("part").and_then(|v| match v {
+            Value::Number(n) => n.as_f64(),
+            other => front_matter_text(other)?.trim().parse().ok(),
+        });
+
+        Ok(Some(Self {
+            source: source.to_path_buf(),
+            title,
+            part,
+            date: fields.get("date").and_then(front_matter_text),
+            body: body.to_string(),
+        }))
+    }
+}
+
+fn same_series(a: &str, b: &str) -> bool {
+    a.trim().to_lowercase() == b.trim().to_lowercase()
+}
+
+/// 从台账中找出系列的所有文章，按 `part`、日期、标题排序；同一源文件只取一次
+pub fn series_articles(records: &[ContentRecord], series: &str) -> Result<Vec<SeriesArticle>> {
+    let mut sources: Vec<&PathBuf> = records
+        .iter()
+        .filter_map(|r| r.source.as_ref())
+        .filter(|s| s.exists())
+        .collect();
+    sources.sort();
+    sources.dedup();
+
+    let mut articles = Vec::new();
+    for source in sources {
+        if let Some(article) = SeriesArticle::load(source, series)? {
+            articles.push(article);
+        }
+    }
+    articles.sort_by(|a, b| {
+        a.part
+            .unwrap_or(f64::MAX)
+            .total_cmp(&b.part.unwrap_or(f64::MAX))
+            .then_with(|| a.date.cmp(&b.date))
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    Ok(articles)
+}
+
+fn node_text<'a>(node: &'a AstNode<'a>) -> String {
+    let mut text = String::new();
+    for descendant in node.descendants() {
+        match &descendant.data.borrow().value {
+            NodeValue::Text(t) | NodeValue::Code(comrak::nodes::NodeCode { literal: t, .. }) => {
+                text.push_str(t)
+            }
+            NodeValue::SoftBreak | NodeValue::LineBreak => text.push(' '),
+            _ => {}
+        }
+    }
+    text.trim().to_string()
+}
+
+fn is_external(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+/// 把多篇文章合并为一篇长文（合集）：生成篇章标题和统一目录，
+/// 各篇的标题层级整体下移，脚注按内容去重后统一编号，外部链接汇总为参考资料
+pub struct SeriesCompiler {
+    formatter: SourceFormatter,
+}
+
+/// 合并过程中跨文章累积的脚注和参考链接
+#[derive(Default)]
+struct Collected {
+    /// 脚注内容 -> 统一编号
+    footnote_labels: HashMap<String, usize>,
+    /// 按编号排列的脚注定义（Markdown）
+    footnotes: Vec<String>,
+    /// 外部链接：地址和第一次出现时的文字
+    links: Vec<(String, String)>,
+}
+
+impl Default for SeriesCompiler {
+    fn default() -> Self {
+        Self::new(SourceFormatter::new(0, Default::default()))
+    }
+}
+
+impl SeriesCompiler {
+    pub fn new(formatter: SourceFormatter) -> Self {
+        Self { formatter }
+    }
+
+    pub fn compile(&self, series: &str, articles: &[SeriesArticle]) -> Result<String> {
+        if articles.is_empty() {
+            return Err(Error::Validation(format!(
+                "台账中没有属于系列「{}」的文章（front matter series）",
+                series
+            )));
+        }
+
+        let mut collected = Collected::default();
+        let mut toc = String::new();
+        let mut parts = String::new();
+        for (i, article) in articles.iter().enumerate() {
+            let number = i + 1;
+            let (body, sections) = self.compile_part(article, &mut collected)?;
+
+            let _ = writeln!(
+                toc,
+                "{}. [{}](#part-{})",
+                number,
+                article.title.replace(['[', ']'], ""),
+                number
+            );
+            for section in sections {
+                let _ = writeln!(toc, "    - {}", section);
+            }
+
+            let _ = write!(
+                parts,
+                "\n<a id=\"part-{}\"></a>\n\n## 第 {} 篇：{}\n\n{}",
+                number, number, article.title, body
+            );
+        }
+
+        let mut output = format!(
+            "---\ntitle: \"{}\"\n---\n\n# {}\n\n## 目录\n\n{}",
+            series.replace('"', "'"),
+            series,
+            toc
+        );
+        output.push_str(&parts);
+        if !collected.links.is_empty() {
+            output.push_str("\n## 参考资料\n\n");
+            for (url, text) in &collected.links {
+                let text = if text.is_empty() { url } else { text };
+                let _ = writeln!(output, "- [{}]({})", text.replace(['[', ']'], ""), url);
+            }
+        }
+        if !collected.footnotes.is_empty() {
+            output.push('\n');
+            for footnote in &collected.footnotes {
+                output.push_str(footnote);
+            }
+        }
+        Ok(output)
+    }
+
+    /// 处理一篇文章，返回正文和目录中列出的小节标题
+    fn compile_part(
+        &self,
+        article: &SeriesArticle,
+        collected: &mut Collected,
+    ) -> Result<(String, Vec<String>)> {
+        let arena = Arena::new();
+        let root = self.formatter.parse(&arena, &article.body);
+
+        // 文章自己的一级标题由篇章标题代替
+        if let Some(first) = root.first_child() {
+            if matches!(&first.data.borrow().value, NodeValue::Heading(h) if h.level == 1) {
+                first.detach();
+            }
+        }
+
+        // 篇章标题为二级，文章中最高的标题下移到三级
+        let min_level = root
+            .descendants()
+            .filter_map(|n| match &n.data.borrow().value {
+                NodeValue::Heading(h) => Some(h.level),
+                _ => None,
+            })
+            .min()
+            .unwrap_or(3);
+        let mut sections = Vec::new();
+        let source_dir = article
+            .source
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        let mut footnote_labels: HashMap<String, String> = HashMap::new();
+        let mut definitions = Vec::new();
+
+        for node in root.descendants() {
+            let mut data = node.data.borrow_mut();
+            match &mut data.value {
+                NodeValue::Heading(heading) => {
+                    heading.level = (heading.level + 3 - min_level).min(6);
+                    if heading.level == 3 {
+                        drop(data);
+                        sections.push(node_text(node));
+                    }
+                }
+                NodeValue::Link(link) if is_external(&link.url) => {
+                    let url = link.url.clone();
+                    drop(data);
+                    if !collected.links.iter().any(|(u, _)| *u == url) {
+                        collected.links.push((url, node_text(node)));
+                    }
+                }
+                // 相对路径的图片改为源文件所在目录下的路径，合集输出到其他目录时仍能找到
+                NodeValue::Image(image)
+                    if !image.url.contains("://") && !image.url.starts_with(['/', '#']) =>
+                {
+                    let path = source_dir.join(image.url.trim_start_matches("./"));
+                    image.url = path.to_string_lossy().replace('\\', "/");
+                }
+                NodeValue::FootnoteDefinition(_) => {
+                    drop(data);
+                    definitions.push(node);
+                }
+                _ => {}
+            }
+        }
+
+        // 内容相同的脚注共用一个编号，其余按出现顺序统一编号，避免各篇的 [^1] 冲突
+        for definition in definitions {
+            let NodeValue::FootnoteDefinition(label) = definition.data.borrow().value.clone()
+            else {
+                continue;
+            };
+            let key = node_text(definition);
+            let number = match collected.footnote_labels.get(&key) {
+                Some(number) => *number,
+                None => {
+                    let number = collected.footnotes.len() + 1;
+                    definition.data.borrow_mut().value =
+                        NodeValue::FootnoteDefinition(number.to_string());
+                    collected.footnotes.push(self.formatter.render(definition)?);
+                    collected.footnote_labels.insert(key, number);
+                    number
+                }
+            };
+            footnote_labels.insert(label, number.to_string());
+            definition.detach();
+        }
+        for node in root.descendants() {
+            if let NodeValue::FootnoteReference(label) = &mut node.data.borrow_mut().value {
+                if let Some(number) = footnote_labels.get(label.as_str()) {
+                    *label = number.clone();
+                }
+            }
+        }
+
+        Ok((self.formatter.render(root)?, sections))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(title: &str, part: f64, body: &str) -> SeriesArticle {
+        SeriesArticle {
+            source: PathBuf::from("posts").join(format!("{}.md", part)),
+            title: title.to_string(),
+            part: Some(part),
+            date: None,
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compile_series() {
+        let articles = vec![
+            article(
+                "入门",
+                1.0,
+                "# 入门\n\n## 安装\n\n见[官网](https://www.rust-lang.org/)[^1]。\n\n![图](images/a.png)\n\n[^1]: 官方文档\n",
+            ),
+            article(
+                "进阶",
+                2.0,
+                "# 进阶\n\n# 所有权\n\n再看[官网](https://www.rust-lang.org/)[^1]，另见[^2]。\n\n[^1]: 官方文档\n\n[^2]: 另一条说明\n",
+            ),
+        ];
+        let compiled = SeriesCompiler::default()
+            .compile("Rust 入门", &articles)
+            .unwrap();
+
+        assert!(compiled.contains("# Rust 入门\n\n## 目录\n\n1. [入门](#part-1)\n    - 安装\n2. [进阶](#part-2)\n    - 所有权\n"));
+        assert!(compiled.contains("## 第 1 篇：入门\n\n### 安装"));
+        assert!(compiled.contains("## 第 2 篇：进阶\n\n### 所有权"));
+        assert!(compiled.contains("![图](posts/images/a.png)"));
+        // 相同内容的脚注合并，外部链接去重后列入参考资料
+        assert!(compiled.contains("再看[官网](https://www.rust-lang.org/)[^1]，另见[^2]"));
+        assert_eq!(compiled.matches("官方文档").count(), 1);
+        assert_eq!(
+            compiled
+                .matches("- [官网](https://www.rust-lang.org/)")
+                .count(),
+            1
+        );
+
+        let err = SeriesCompiler::default().compile("空", &[]).unwrap_err();
+        assert!(matches!(err, Error::Validation(_)));
+    }
+
+    #[test]
+    fn test_series_articles_from_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, front_matter: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, format!("---\n{}\n---\n# {}\n", front_matter, name)).unwrap();
+            path
+        };
+        let records: Vec<ContentRecord> = [
+            write("b.md", "series: [Rust 入门, 其他]\npart: 2"),
+            write("a.md", "series: rust 入门\npart: 1"),
+            write("c.md", "series: 其他"),
+        ]
+        .into_iter()
+        .map(|source| {
+            let mut record: ContentRecord = serde_json::from_value(serde_json::json!({
+                "id": uuid::Uuid::new_v4(),
+                "title": "",
+                "source": source,
+                "content_hash": "",
+                "updated_at": chrono::Utc::now(),
+            }))
+            .unwrap();
+            record.title = source.display().to_string();
+            record
+        })
+        .collect();
+
+        let articles = series_articles(&records, "Rust 入门").unwrap();
+        let titles: Vec<&str> = articles.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["a.md", "b.md"]);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{
+    core::{
+        content::{Content, Platform},
+        pipeline::PlatformStage,
+        report::PlatformReport,
+    },
+    Result,
+};
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// 按目标平台解析条件块，不匹配的块连同标记一起移除：
+///
+/// - `<!-- only:wechat -->…<!-- /only -->` 只在列出的平台保留（可用逗号列出多个平台）
+/// - `<!-- skip:zhihu -->…<!-- /skip -->` 在列出的平台移除
+///
+/// 同类条件块不支持嵌套。返回处理后的文本和被移除的块数
+pub fn resolve_platform_blocks(text: &str, platform: &str) -> (String, usize) {
+    static ONLY_REGEX: OnceLock<Regex> = OnceLock::new();
+    static SKIP_REGEX: OnceLock<Regex> = OnceLock::new();
+    let only_regex = ONLY_REGEX.get_or_init(|| block_regex("only"));
+    let skip_regex = SKIP_REGEX.get_or_init(|| block_regex("skip"));
+
+    let mut removed = 0;
+    let text = only_regex.replace_all(text, |caps: &regex::Captures| {
+        if lists_platform(&caps[1], platform) {
+            caps[2].to_string()
+        } else {
+            removed += 1;
+            String::new()
+        }
+    });
+    let text = skip_regex.replace_all(&text, |caps: &regex::Captures| {
+        if lists_platform(&caps[1], platform) {
+            removed += 1;
+            String::new()
+        } else {
+            caps[2].to_string()
+        }
+    });
+
+    (text.into_owned(), removed)
+}
+
+fn block_regex(kind: &str) -> Regex {
+    Regex::new(&format!(
+        r"(?s)<!--\s*{kind}:\s*([\w\s,-]+?)\s*-->\n?(.*?)<!--\s*/{kind}\s*-->\n?"
+    ))
+    .unwrap()
+}
+
+fn lists_platform(list: &str, platform: &str) -> bool {
+    list.split(',')
+        .any(|name| name.trim().eq_ignore_ascii_case(platform))
+}
+
+/// 按平台保留或移除 Markdown 中的条件块
+pub struct PlatformBlockStage;
+
+impl PlatformStage for PlatformBlockStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        let platform = platform.to_string();
+        let (markdown, _) = resolve_platform_blocks(&content.markdown, &platform);
+        let (html, removed) = resolve_platform_blocks(&content.html, &platform);
+        content.markdown = markdown;
+        content.html = html;
+
+        if removed > 0 {
+            report
+                .stripped_elements
+                .push(format!("{} 个不适用于 {} 的条件块", removed, platform));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "平台条件块"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MarkdownProcessor;
+
+    const MARKDOWN: &str = "# 标题\n\n正文\n\n<!-- only:wechat -->\n**长按关注公众号**\n<!-- /only -->\n\n<!-- skip:zhihu -->\n点击阅读原文\n<!-- /skip -->\n\n<!-- only: zhihu, blog -->\n欢迎关注专栏\n<!-- /only -->\n\n结尾\n";
+
+    #[test]
+    fn test_resolve_blocks_per_platform() {
+        let (wechat, removed) = resolve_platform_blocks(MARKDOWN, "wechat");
+        assert_eq!(removed, 1);
+        assert!(wechat.contains("长按关注公众号"));
+        assert!(wechat.contains("点击阅读原文"));
+        assert!(!wechat.contains("欢迎关注专栏"));
+        assert!(!wechat.contains("<!--"));
+
+        let (zhihu, removed) = resolve_platform_blocks(MARKDOWN, "zhihu");
+        assert_eq!(removed, 2);
+        assert!(!zhihu.contains("长按关注公众号"));
+        assert!(!zhihu.contains("点击阅读原文"));
+        assert!(zhihu.contains("欢迎关注专栏"));
+    }
+
+    #[test]
+    fn test_stage_on_rendered_html() {
+        let content = MarkdownProcessor::new().process(MARKDOWN).unwrap();
+        assert!(content.html.contains("<!-- only:wechat -->"));
+
+        let mut zhihu = content.clone();
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        PlatformBlockStage
+            .process(&mut zhihu, &Platform::Zhihu, &mut report)
+            .unwrap();
+
+        assert!(!zhihu.html.contains("长按关注公众号"));
+        assert!(zhihu.html.contains("<p>欢迎关注专栏</p>"));
+        assert!(zhihu.html.contains("<p>结尾</p>"));
+        assert!(!zhihu.markdown.contains("点击阅读原文"));
+        assert_eq!(
+            report.stripped_elements,
+            vec!["2 个不适用于 zhihu 的条件块"]
+        );
+    }
+}
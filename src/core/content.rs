@@ -1,5 +1,13 @@
+use crate::core::{
+    frontmatter::pinyin_slug,
+    language::{detect_language, Language, ScriptCounts},
+    text::plain_text,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{OnceLock, RwLock},
+};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,7 +29,76 @@ pub struct ContentMetadata {
     pub cover_image: Option<String>,
     pub reading_time: Option<u32>, // 分钟
     pub word_count: Option<u32>,
-    pub custom_fields: HashMap<String, String>,
+    #[serde(default)]
+    pub date: Option<chrono::DateTime<chrono::FixedOffset>>,
+    /// 到期后需要复查更新的日期（front matter `review_after`）
+    #[serde(default)]
+    pub review_after: Option<chrono::NaiveDate>,
+    /// 标题 A/B 测试的候选标题（front matter `titles`）
+    #[serde(default)]
+    pub title_variants: Vec<String>,
+    /// 标题整体降低的级数（front matter `shift_headings`）
+    #[serde(default)]
+    pub shift_headings: Option<u8>,
+    /// 主要语言（front matter `lang`，未设置时按正文检测）
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// 固定链接使用的 slug（front matter `slug`），用于文件名、静态站点路径和交叉链接
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// 文章在自有站点上的规范地址（front matter `canonical_url`，或按 `general.site_url` 生成）
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    /// 草稿（front matter `draft: true`）
+    #[serde(default)]
+    pub draft: bool,
+    /// 按平台覆盖的标题、作者、摘要和封面（front matter `wechat: { title: ... }`）
+    #[serde(default)]
+    pub platform_overrides: HashMap<String, PlatformOverrides>,
+    /// 其余 front matter 字段，保留数组、数字和布尔等类型。
+    /// 旧版本保存的字符串值按字符串读取，读取文本时用 [`ContentMetadata::custom_text`]
+    pub custom_fields: HashMap<String, serde_json::Value>,
+}
+
+impl ContentMetadata {
+    /// 自定义字段的文本形式，数组按 `, ` 连接
+    pub fn custom_text(&self, key: &str) -> Option<String> {
+        self.custom_fields
+            .get(key)
+            .and_then(crate::core::frontmatter::front_matter_text)
+    }
+}
+
+/// 只对某个平台生效的 front matter 字段
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformOverrides {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub cover: Option<String>,
+}
+
+impl PlatformOverrides {
+    pub const FIELDS: &'static [&'static str] = &["title", "author", "description", "cover"];
+
+    /// 设置字段，`key` 为 front matter 中的完整字段名，用于错误提示
+    pub fn set(&mut self, field: &str, value: Option<String>, key: &str) -> crate::Result<()> {
+        let slot = match field {
+            "title" => &mut self.title,
+            "author" => &mut self.author,
+            "description" => &mut self.description,
+            "cover" => &mut self.cover,
+            _ => {
+                return Err(crate::error::Error::Validation(format!(
+                    "front matter 字段 {} 不支持按平台覆盖（可覆盖: {}）",
+                    key,
+                    Self::FIELDS.join("、")
+                )))
+            }
+        };
+        *slot = value;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,11 +108,83 @@ pub struct ProcessedContent {
     pub zhihu_html: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+/// 发布平台。CLI 参数、配置和 API 共用这一个类型；
+/// 内置平台之外的平台需要先通过 [`register_platform`] 注册
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, clap::ValueEnum)]
 pub enum Platform {
+    #[value(name = "wechat")]
     WeChat,
     Zhihu,
     All,
+    /// 运行时注册的自定义平台
+    #[value(skip)]
+    Custom(String),
+}
+
+fn custom_platforms() -> &'static RwLock<BTreeSet<String>> {
+    static REGISTRY: OnceLock<RwLock<BTreeSet<String>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(BTreeSet::new()))
+}
+
+/// 注册自定义平台名（小写字母、数字、`-` 或 `_`），注册后可用于 CLI 参数和配置
+pub fn register_platform(name: &str) -> crate::Result<Platform> {
+    let name = name.trim().to_lowercase();
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(crate::error::Error::InvalidPlatform(format!(
+            "平台名只能包含字母、数字、- 和 _: {}",
+            name
+        )));
+    }
+    if Platform::builtin(&name).is_some() {
+        return Err(crate::error::Error::InvalidPlatform(format!(
+            "不能覆盖内置平台: {}",
+            name
+        )));
+    }
+
+    custom_platforms()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(name.clone());
+    Ok(Platform::Custom(name))
+}
+
+/// 已注册的自定义平台
+pub fn registered_platforms() -> Vec<Platform> {
+    custom_platforms()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .map(|name| Platform::Custom(name.clone()))
+        .collect()
+}
+
+impl Platform {
+    fn builtin(name: &str) -> Option<Self> {
+        <Self as clap::ValueEnum>::value_variants()
+            .iter()
+            .find(|p| p.to_string() == name)
+            .cloned()
+    }
+
+    /// 可以直接输出的具体平台（不含 `All`）：内置平台加上已注册的自定义平台
+    pub fn concrete() -> Vec<Platform> {
+        let mut platforms = vec![Platform::WeChat, Platform::Zhihu];
+        platforms.extend(registered_platforms());
+        platforms
+    }
+
+    /// 把 `All` 展开为全部具体平台
+    pub fn expand(&self) -> Vec<Platform> {
+        match self {
+            Platform::All => Self::concrete(),
+            other => vec![other.clone()],
+        }
+    }
 }
 
 impl std::fmt::Display for Platform {
@@ -44,6 +193,7 @@ impl std::fmt::Display for Platform {
             Platform::WeChat => write!(f, "wechat"),
             Platform::Zhihu => write!(f, "zhihu"),
             Platform::All => write!(f, "all"),
+            Platform::Custom(name) => write!(f, "{}", name),
         }
     }
 }
@@ -52,11 +202,23 @@ impl std::str::FromStr for Platform {
     type Err = crate::error::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s.to_lowercase().as_str() {
-            "wechat" => Ok(Platform::WeChat),
-            "zhihu" => Ok(Platform::Zhihu),
-            "all" => Ok(Platform::All),
-            _ => Err(crate::error::Error::InvalidPlatform(s.to_string())),
+        let name = s.trim().to_lowercase();
+        if let Some(platform) = Self::builtin(&name) {
+            return Ok(platform);
+        }
+        // 兼容 clap 早期生成的 "we-chat"
+        if name == "we-chat" {
+            return Ok(Platform::WeChat);
+        }
+
+        let registered = custom_platforms()
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(&name);
+        if registered {
+            Ok(Platform::Custom(name))
+        } else {
+            Err(crate::error::Error::InvalidPlatform(s.to_string()))
         }
     }
 }
@@ -66,8 +228,14 @@ pub struct PublishResult {
     pub platform: Platform,
     pub url: Option<String>,
     pub draft_id: Option<String>,
+    /// 平台返回的发布任务 ID（如微信 freepublish 的 publish_id），用于查询发布状态
+    #[serde(default)]
+    pub publish_id: Option<String>,
     pub status: PublishStatus,
     pub message: String,
+    /// 发布时使用的标题变体标识
+    #[serde(default)]
+    pub title_variant: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -78,6 +246,17 @@ pub enum PublishStatus {
     Pending,
 }
 
+impl std::fmt::Display for PublishStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PublishStatus::Success => write!(f, "已发布"),
+            PublishStatus::Draft => write!(f, "草稿"),
+            PublishStatus::Failed => write!(f, "失败"),
+            PublishStatus::Pending => write!(f, "发布中"),
+        }
+    }
+}
+
 impl Content {
     pub fn new(title: String, markdown: String) -> Self {
         let now = chrono::Utc::now();
@@ -92,11 +271,56 @@ impl Content {
         }
     }
 
+    /// front matter 中的 slug，没有时由标题生成拼音 slug
+    pub fn slug(&self) -> String {
+        self.metadata
+            .slug
+            .clone()
+            .filter(|slug| !slug.is_empty())
+            .unwrap_or_else(|| pinyin_slug(&self.title))
+    }
+
+    /// 用 front matter 中该平台的覆盖字段替换标题、作者、摘要和封面
+    pub fn apply_platform_overrides(&mut self, platform: &Platform) {
+        let Some(overrides) = self
+            .metadata
+            .platform_overrides
+            .get(&platform.to_string())
+            .cloned()
+        else {
+            return;
+        };
+        if let Some(title) = overrides.title {
+            self.title = title;
+        }
+        if let Some(author) = overrides.author {
+            self.metadata.author = Some(author);
+        }
+        if let Some(description) = overrides.description {
+            self.metadata.description = Some(description);
+        }
+        if let Some(cover) = overrides.cover {
+            self.metadata.cover_image = Some(cover);
+        }
+    }
+
+    /// 文章的主要语言，元数据中没有时按正文检测
+    pub fn language(&self) -> Language {
+        self.metadata
+            .language
+            .unwrap_or_else(|| detect_language(&plain_text(&self.markdown)))
+    }
+
+    /// 按语言计算字数（中日韩文字按字、拉丁文按词）和阅读时间
     pub fn calculate_reading_time(&mut self) {
-        // 按平均阅读速度200字/分钟计算
-        let word_count = self.markdown.chars().count() as u32;
+        let text = plain_text(&self.markdown);
+        let language = self
+            .metadata
+            .language
+            .unwrap_or_else(|| detect_language(&text));
+        let word_count = ScriptCounts::of(&text).words() as u32;
         self.metadata.word_count = Some(word_count);
-        self.metadata.reading_time = Some((word_count / 200).max(1));
+        self.metadata.reading_time = Some((word_count / language.reading_speed()).max(1));
     }
 
     pub fn from_markdown_with_front_matter(markdown: String) -> Result<Self, crate::error::Error> {
@@ -138,9 +362,53 @@ mod tests {
         assert!(metadata.cover_image.is_none());
         assert!(metadata.reading_time.is_none());
         assert!(metadata.word_count.is_none());
+        assert!(metadata.date.is_none());
         assert!(metadata.custom_fields.is_empty());
     }
 
+    #[test]
+    fn test_custom_fields_keep_types_and_read_legacy_strings() {
+        let content = Content::from_markdown_with_front_matter(
+            "---\nseries: [rust, 异步]\npart: 2\nfeatured: false\nissue: \"42\"\nsources:\n  - a.md\n  - b.md\n---\n# 标题".to_string(),
+        )
+        .unwrap();
+        let fields = &content.metadata.custom_fields;
+        assert_eq!(fields["series"], serde_json::json!(["rust", "异步"]));
+        assert_eq!(fields["part"], 2);
+        assert_eq!(fields["featured"], false);
+        assert_eq!(fields["issue"], "42");
+        assert_eq!(fields["sources"], serde_json::json!(["a.md", "b.md"]));
+        assert_eq!(
+            content.metadata.custom_text("series").as_deref(),
+            Some("rust, 异步")
+        );
+
+        // 旧版本保存的元数据中自定义字段都是字符串
+        let legacy: ContentMetadata = serde_json::from_str(
+            r#"{"author":null,"tags":[],"description":null,"cover_image":null,"reading_time":null,"word_count":null,"custom_fields":{"draft":"false"}}"#,
+        )
+        .unwrap();
+        assert_eq!(legacy.custom_text("draft").as_deref(), Some("false"));
+    }
+
+    #[test]
+    fn test_platform_overrides() {
+        let mut content = Content::from_markdown_with_front_matter(
+            "---\ntitle: 通用标题\nauthor: 张三\nwechat:\n  title: 公众号标题\n  cover: wechat.png\n---\n正文".to_string(),
+        )
+        .unwrap();
+        assert!(content.metadata.custom_fields.is_empty());
+
+        let mut zhihu = content.clone();
+        zhihu.apply_platform_overrides(&Platform::Zhihu);
+        assert_eq!(zhihu.title, "通用标题");
+
+        content.apply_platform_overrides(&Platform::WeChat);
+        assert_eq!(content.title, "公众号标题");
+        assert_eq!(content.metadata.cover_image.as_deref(), Some("wechat.png"));
+        assert_eq!(content.metadata.author.as_deref(), Some("张三"));
+    }
+
     #[test]
     fn test_platform_enum() {
         assert_eq!(Platform::WeChat.to_string(), "wechat");
@@ -156,6 +424,18 @@ mod tests {
         assert!(Platform::from_str("invalid").is_err());
     }
 
+    #[test]
+    fn test_register_custom_platform() {
+        assert!(Platform::from_str("medium").is_err());
+        let medium = register_platform("Medium").unwrap();
+        assert_eq!(medium, Platform::Custom("medium".to_string()));
+        assert_eq!(Platform::from_str("medium").unwrap(), medium);
+        assert!(Platform::All.expand().contains(&medium));
+
+        assert!(register_platform("wechat").is_err());
+        assert!(register_platform("bad name").is_err());
+    }
+
     #[test]
     fn test_front_matter_parsing() {
         let content_with_front_matter = r#"---
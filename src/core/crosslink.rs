@@ -0,0 +1,497 @@
+use crate::{
+    cli::args::CrossLinkConfig,
+    core::{
+        content::{Content, Platform},
+        pipeline::PlatformStage,
+        report::PlatformReport,
+        store::{ContentRecord, ContentStore},
+    },
+    error::Error,
+    Result,
+};
+use regex::Regex;
+use std::{fmt::Write, str::FromStr, sync::OnceLock};
+
+/// 提到历史文章时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossLinkPolicy {
+    /// 把提到的标题/关键词改为链接
+    Link,
+    /// 标注脚注编号，文末列出相关文章地址
+    Footnote,
+    /// 正文不变，文末追加“见我之前的文章”
+    Note,
+}
+
+impl FromStr for CrossLinkPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "link" => Ok(Self::Link),
+            "footnote" => Ok(Self::Footnote),
+            "note" => Ok(Self::Note),
+            other => Err(Error::Config(format!(
+                "无效的交叉链接方式: {}（可选: link、footnote、note）",
+                other
+            ))),
+        }
+    }
+}
+
+/// 在正文中找到的一处历史文章引用
+struct Mention<'a> {
+    record: &'a ContentRecord,
+    url: Option<&'a str>,
+}
+
+/// 根据本地台账，为正文中提到的历史文章标题或关键词添加链接
+pub struct CrossLinkStage {
+    articles: Vec<ContentRecord>,
+    config: CrossLinkConfig,
+}
+
+impl CrossLinkStage {
+    pub fn new(articles: Vec<ContentRecord>, config: CrossLinkConfig) -> Self {
+        Self { articles, config }
+    }
+
+    /// 读取默认台账中的文章
+    pub fn from_config(config: &CrossLinkConfig) -> Result<Self> {
+        let articles = ContentStore::open_default()?.records().to_vec();
+        Ok(Self::new(articles, config.clone()))
+    }
+
+    fn policy_for(&self, platform: &str) -> Result<CrossLinkPolicy> {
+        self.config
+            .platforms
+            .get(platform)
+            .unwrap_or(&self.config.default_policy)
+            .parse()
+    }
+
+    fn is_opted_out(&self, term: &str) -> bool {
+        self.config
+            .opt_out
+            .iter()
+            .any(|o| o.trim().eq_ignore_ascii_case(term.trim()))
+    }
+
+    /// 候选文章的匹配词：先标题后关键词，较长的词优先
+    fn terms<'a>(&'a self, current_title: &str) -> Vec<(&'a ContentRecord, &'a str)> {
+        let mut terms: Vec<(&ContentRecord, &str)> = Vec::new();
+        for record in &self.articles {
+            if record.title == current_title || self.is_opted_out(&record.title) {
+                continue;
+            }
+            terms.push((record, record.title.as_str()));
+        }
+        let mut keywords: Vec<(&ContentRecord, &str)> = self
+            .articles
+            .iter()
+            .filter(|r| r.title != current_title && !self.is_opted_out(&r.title))
+            .flat_map(|r| r.tags.iter().map(move |tag| (r, tag.as_str())))
+            .filter(|(_, tag)| tag.chars().count() >= 2 && !self.is_opted_out(tag))
+            .collect();
+        terms.sort_by_key(|(_, term)| std::cmp::Reverse(term.chars().count()));
+        keywords.sort_by_key(|(_, term)| std::cmp::Reverse(term.chars().count()));
+        terms.extend(keywords);
+        terms
+    }
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches(['<', '/'])
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// 不应插入链接的元素
+fn is_protected(name: &str) -> bool {
+    matches!(
+        name,
+        "a" | "code" | "pre" | "kbd" | "script" | "style" | "h1" | "h2" | "h3" | "h4" | "h5" | "h6"
+    )
+}
+
+impl PlatformStage for CrossLinkStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        if content
+            .metadata
+            .custom_text("cross_link")
+            .is_some_and(|v| v == "false")
+        {
+            return Ok(());
+        }
+
+        static TOKEN_REGEX: OnceLock<Regex> = OnceLock::new();
+        let token_regex = TOKEN_REGEX.get_or_init(|| Regex::new(r"<[^>]*>|[^<]+").unwrap());
+
+        let platform = platform.to_string();
+        let policy = self.policy_for(&platform)?;
+        let terms = self.terms(&content.title);
+
+        let mut mentions: Vec<Mention> = Vec::new();
+        let mut html = String::with_capacity(content.html.len());
+        let mut protected = 0usize;
+
+        for token in token_regex.find_iter(&content.html) {
+            let token = token.as_str();
+            if token.starts_with('<') {
+                let name = tag_name(token);
+                if is_protected(&name) {
+                    if token.starts_with("</") {
+                        protected = protected.saturating_sub(1);
+                    } else {
+                        protected += 1;
+                    }
+                }
+                html.push_str(token);
+                continue;
+            }
+
+            let mut text = token.to_string();
+            if protected == 0 {
+                for (record, term) in &terms {
+                    if mentions.len() >= self.config.max_links {
+                        break;
+                    }
+                    if mentions.iter().any(|m| m.record.id == record.id) {
+                        continue;
+                    }
+                    let Some(pos) = text.find(*term) else {
+                        continue;
+                    };
+
+                    let url = record.public_url(&platform);
+                    let marker = match (policy, url) {
+                        (CrossLinkPolicy::Link, Some(url)) => format!(
+                            r#"<a href="{}" class="markflow-cross-link">{}</a>"#,
+                            html_escape::encode_double_quoted_attribute(url),
+                            term
+                        ),
+                        (CrossLinkPolicy::Footnote, Some(_)) => format!(
+                            r#"{}<sup class="markflow-cross-ref">[{}]</sup>"#,
+                            term,
+                            mentions.iter().filter(|m| m.url.is_some()).count() + 1
+                        ),
+                        _ => term.to_string(),
+                    };
+                    text.replace_range(pos..pos + term.len(), &marker);
+                    mentions.push(Mention { record, url });
+                    // 替换后的文本包含标签，不再在其中继续匹配
+                    break;
+                }
+            }
+            html.push_str(&text);
+        }
+
+        if mentions.is_empty() {
+            return Ok(());
+        }
+
+        let mut related = String::new();
+        match policy {
+            CrossLinkPolicy::Footnote => {
+                let linked: Vec<&Mention> = mentions.iter().filter(|m| m.url.is_some()).collect();
+                if !linked.is_empty() {
+                    let _ = write!(
+                        related,
+                        r#"<section class="markflow-related"><p>相关文章：</p>"#
+                    );
+                    for (i, mention) in linked.iter().enumerate() {
+                        let _ = write!(
+                            related,
+                            "<p>[{}] 《{}》 {}</p>",
+                            i + 1,
+                            html_escape::encode_text(&mention.record.title),
+                            html_escape::encode_text(mention.url.unwrap_or_default())
+                        );
+                    }
+                    related.push_str("</section>");
+                }
+            }
+            CrossLinkPolicy::Note | CrossLinkPolicy::Link => {}
+        }
+        let unlinked: Vec<String> = mentions
+            .iter()
+            .filter(|m| policy == CrossLinkPolicy::Note || m.url.is_none())
+            .map(|m| format!("《{}》", html_escape::encode_text(&m.record.title)))
+            .collect();
+        if !unlinked.is_empty() {
+            let _ = write!(
+                related,
+                r#"<p class="markflow-related">见我之前的文章：{}</p>"#,
+                unlinked.join("、")
+            );
+        }
+
+        if !related.is_empty() {
+            html.push('\n');
+            html.push_str(&related);
+            html.push('\n');
+        }
+        content.html = html;
+
+        for mention in &mentions {
+            report.rewritten_links.push(format!(
+                "交叉链接《{}》{}",
+                mention.record.title,
+                mention
+                    .url
+                    .map(|u| format!(" -> {}", u))
+                    .unwrap_or_default()
+            ));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "交叉链接"
+    }
+}
+
+/// 解析正文中 `[文字](slug:文章slug)` 形式的站内链接：按台账中文章的 slug
+/// 换成该平台的发布地址，没有发布地址时使用规范地址；找不到时去掉链接只保留文字
+pub struct SlugLinkStage {
+    articles: OnceLock<Vec<ContentRecord>>,
+}
+
+impl SlugLinkStage {
+    pub fn new(articles: Vec<ContentRecord>) -> Self {
+        Self {
+            articles: OnceLock::from(articles),
+        }
+    }
+
+    /// 使用默认台账，只有正文中出现 `slug:` 链接时才读取
+    pub fn from_default_store() -> Self {
+        Self {
+            articles: OnceLock::new(),
+        }
+    }
+
+    fn articles(&self) -> Result<&[ContentRecord]> {
+        if let Some(articles) = self.articles.get() {
+            return Ok(articles);
+        }
+        let records = ContentStore::open_default()?.records().to_vec();
+        Ok(self.articles.get_or_init(|| records))
+    }
+
+    fn resolve<'a>(articles: &'a [ContentRecord], slug: &str, platform: &str) -> Option<&'a str> {
+        let record = articles.iter().find(|r| r.slug.as_deref() == Some(slug))?;
+        record
+            .public_url(platform)
+            .or(record.canonical_url.as_deref())
+    }
+}
+
+impl PlatformStage for SlugLinkStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        static SLUG_LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+        let regex = SLUG_LINK_REGEX.get_or_init(|| {
+            Regex::new(r#"(?s)<a\s([^>]*?)\bhref="slug:([^"]*)"([^>]*)>(.*?)</a>"#).unwrap()
+        });
+        if !regex.is_match(&content.html) {
+            return Ok(());
+        }
+
+        let articles = self.articles()?;
+        let platform = platform.to_string();
+        let html = regex.replace_all(&content.html, |caps: &regex::Captures| {
+            let slug = caps[2].trim_matches('/');
+            match Self::resolve(articles, slug, &platform) {
+                Some(url) => {
+                    report
+                        .rewritten_links
+                        .push(format!("slug:{} -> {}", slug, url));
+                    format!(
+                        r#"<a {}href="{}"{}>{}</a>"#,
+                        &caps[1],
+                        html_escape::encode_double_quoted_attribute(url),
+                        &caps[3],
+                        &caps[4]
+                    )
+                }
+                None => {
+                    report.warnings.push(format!(
+                        "站内链接 slug:{} 找不到对应的文章，已去掉链接",
+                        slug
+                    ));
+                    caps[4].to_string()
+                }
+            }
+        });
+        content.html = html.into_owned();
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "站内链接"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{
+        content::{PublishResult, PublishStatus},
+        store::content_hash,
+    };
+    use std::collections::BTreeMap;
+
+    fn record(title: &str, tags: &[&str], url: Option<&str>) -> ContentRecord {
+        ContentRecord {
+            id: uuid::Uuid::new_v4(),
+            title: title.to_string(),
+            source: None,
+            content_hash: content_hash(title.as_bytes()),
+            outputs: BTreeMap::new(),
+            html: BTreeMap::new(),
+            publications: url
+                .map(|url| PublishResult {
+                    platform: Platform::Zhihu,
+                    url: Some(url.to_string()),
+                    draft_id: None,
+                    publish_id: None,
+                    status: PublishStatus::Success,
+                    message: String::new(),
+                    title_variant: None,
+                })
+                .into_iter()
+                .collect(),
+            review_after: None,
+            title_variants: BTreeMap::new(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            slug: None,
+            canonical_url: None,
+            workflow: Default::default(),
+            report: None,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn content() -> Content {
+        let mut content = Content::new("新文章".to_string(), String::new());
+        content.html = "<h2>Rust 异步编程</h2>\n<p>上次在 Rust 异步编程 里讲过 tokio，<code>所有权</code> 见所有权一文。</p>".to_string();
+        content
+    }
+
+    fn stage(policy: &str) -> CrossLinkStage {
+        CrossLinkStage::new(
+            vec![
+                record(
+                    "Rust 异步编程",
+                    &["tokio"],
+                    Some("https://zhuanlan.zhihu.com/p/1"),
+                ),
+                record("所有权详解", &["所有权"], None),
+                record("新文章", &[], Some("https://zhuanlan.zhihu.com/p/self")),
+                record("不要链接", &["tokio"], None),
+            ],
+            CrossLinkConfig {
+                enabled: true,
+                default_policy: policy.to_string(),
+                opt_out: vec!["不要链接".to_string()],
+                ..CrossLinkConfig::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_link_policy() {
+        let mut content = content();
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        stage("link")
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+
+        // 标题和代码中的文字不会被链接，同一篇文章只链接一次
+        assert!(content.html.starts_with("<h2>Rust 异步编程</h2>"));
+        assert!(content.html.contains(r#"上次在 <a href="https://zhuanlan.zhihu.com/p/1" class="markflow-cross-link">Rust 异步编程</a> 里讲过 tokio"#));
+        assert!(content.html.contains("<code>所有权</code>"));
+        // 没有发布地址的文章退化为文末提示
+        assert!(content.html.contains("见我之前的文章：《所有权详解》"));
+        assert!(!content.html.contains("不要链接"));
+        assert_eq!(report.rewritten_links.len(), 2);
+    }
+
+    #[test]
+    fn test_footnote_and_note_policies() {
+        let mut content = content();
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        stage("footnote")
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+        assert!(content
+            .html
+            .contains(r#"Rust 异步编程<sup class="markflow-cross-ref">[1]</sup>"#));
+        assert!(content
+            .html
+            .contains("[1] 《Rust 异步编程》 https://zhuanlan.zhihu.com/p/1"));
+
+        // 微信默认使用文末提示
+        let mut content = self::content();
+        let mut report = PlatformReport::new(Platform::WeChat);
+        stage("link")
+            .process(&mut content, &Platform::WeChat, &mut report)
+            .unwrap();
+        assert!(!content.html.contains("<a "));
+        assert!(content
+            .html
+            .contains("见我之前的文章：《Rust 异步编程》、《所有权详解》"));
+
+        // front matter 可以关闭
+        let mut content = self::content();
+        content
+            .metadata
+            .custom_fields
+            .insert("cross_link".to_string(), false.into());
+        let original = content.html.clone();
+        stage("link")
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+        assert_eq!(content.html, original);
+    }
+
+    #[test]
+    fn test_slug_links() {
+        let mut published = record("Rust 异步编程", &[], Some("https://zhuanlan.zhihu.com/p/1"));
+        published.slug = Some("async-rust".to_string());
+        let mut own_site = record("所有权详解", &[], None);
+        own_site.slug = Some("ownership".to_string());
+        own_site.canonical_url = Some("https://blog.example.com/ownership/".to_string());
+        let stage = SlugLinkStage::new(vec![published, own_site]);
+
+        let mut content = crate::core::MarkdownProcessor::new()
+            .process("见[异步](slug:async-rust)、[所有权](slug:ownership)和[旧文](slug:missing)。")
+            .unwrap();
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        stage
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+
+        assert!(content
+            .html
+            .contains(r#"<a href="https://zhuanlan.zhihu.com/p/1">异步</a>"#));
+        assert!(content
+            .html
+            .contains(r#"<a href="https://blog.example.com/ownership/">所有权</a>"#));
+        assert!(content.html.contains("和旧文。"));
+        assert_eq!(report.rewritten_links.len(), 2);
+        assert_eq!(report.warnings.len(), 1);
+    }
+}
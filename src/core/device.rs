@@ -0,0 +1,106 @@
+use crate::{error::Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 无头浏览器模拟的设备：视口大小和 User-Agent，用于截图和平台自动化，
+/// 让预览与读者在手机上看到的一致
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeviceProfile {
+    /// 视口宽度（CSS 像素）
+    pub width: u32,
+    pub height: u32,
+    /// 未设置时使用浏览器自带的 User-Agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+}
+
+const IPHONE_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+/// 微信内置浏览器（iOS）的 User-Agent，公众号文章页按它判断是否在微信中打开
+const WECHAT_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Mobile/15E148 MicroMessenger/8.0.49(0x18003137) NetType/WIFI Language/zh_CN";
+
+const ANDROID_UA: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Mobile Safari/537.36";
+
+/// 内置设备：名称、宽、高、User-Agent
+const BUILTIN_DEVICES: &[(&str, u32, u32, Option<&str>)] = &[
+    ("iphone", 390, 844, Some(IPHONE_UA)),
+    ("wechat", 390, 844, Some(WECHAT_UA)),
+    ("android", 412, 915, Some(ANDROID_UA)),
+    ("desktop", 1280, 800, None),
+];
+
+/// 按名称查找设备，`browser.devices` 中的自定义设备优先于同名的内置设备
+pub fn device_profile(
+    name: &str,
+    custom: &BTreeMap<String, DeviceProfile>,
+) -> Result<DeviceProfile> {
+    let name = name.trim().to_lowercase();
+    if let Some(profile) = custom.get(&name) {
+        return Ok(profile.clone());
+    }
+    BUILTIN_DEVICES
+        .iter()
+        .find(|(builtin, ..)| *builtin == name)
+        .map(|(_, width, height, user_agent)| DeviceProfile {
+            width: *width,
+            height: *height,
+            user_agent: user_agent.map(str::to_string),
+        })
+        .ok_or_else(|| {
+            let mut names: Vec<&str> = BUILTIN_DEVICES.iter().map(|(n, ..)| *n).collect();
+            names.extend(custom.keys().map(String::as_str));
+            Error::Config(format!(
+                "未知的设备: {}（可选: {}）",
+                name,
+                names.join("、")
+            ))
+        })
+}
+
+impl DeviceProfile {
+    /// 启动浏览器时附加的参数
+    pub fn browser_args(&self) -> Vec<String> {
+        let mut args = vec![format!("--window-size={},{}", self.width, self.height)];
+        if let Some(user_agent) = &self.user_agent {
+            args.push(format!("--user-agent={}", user_agent));
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_profile() {
+        let wechat = device_profile("WeChat", &BTreeMap::new()).unwrap();
+        assert_eq!(wechat.width, 390);
+        assert!(wechat
+            .user_agent
+            .as_deref()
+            .unwrap()
+            .contains("MicroMessenger"));
+        assert!(wechat
+            .browser_args()
+            .contains(&"--window-size=390,844".to_string()));
+        assert_eq!(
+            device_profile("desktop", &BTreeMap::new())
+                .unwrap()
+                .browser_args(),
+            vec!["--window-size=1280,800"]
+        );
+
+        let custom = BTreeMap::from([(
+            "iphone".to_string(),
+            DeviceProfile {
+                width: 375,
+                height: 667,
+                user_agent: None,
+            },
+        )]);
+        assert_eq!(device_profile("iphone", &custom).unwrap().width, 375);
+        let err = device_profile("nokia", &custom).unwrap_err();
+        assert!(err.to_string().contains("可选: iphone、wechat"));
+    }
+}
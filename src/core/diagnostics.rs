@@ -0,0 +1,485 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{find_driver, find_executable, find_in_path, major_version, sandbox_disabled},
+    publishers::{load_cookies, wechat_access_token, ZHIHU_AUTH_COOKIE},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 浏览器自动化（知乎发布）依赖的 WebDriver 可执行文件
+const WEBDRIVER_BINARIES: &[&str] = &["chromedriver", "geckodriver", "msedgedriver"];
+
+/// 知乎 Cookie 剩余有效期不足该天数时提醒重新登录
+const COOKIE_EXPIRY_WARN_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    /// 不影响就绪，但相关功能不可用
+    Warn,
+    Fail,
+}
+
+impl CheckStatus {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            CheckStatus::Ok => "✓",
+            CheckStatus::Warn => "!",
+            CheckStatus::Fail => "✗",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    /// 修复建议
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fix: Option<String>,
+}
+
+impl CheckResult {
+    pub fn new(name: &'static str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    pub fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// `markflow doctor` 的完整检查：在就绪检查之外实际获取凭据、检查模板和目录写权限
+pub async fn run_doctor(config: &AppConfig) -> Vec<CheckResult> {
+    let mut checks = vec![check_config(config), check_browser(config)];
+    checks.extend(check_browser_version(config));
+    checks.extend([
+        check_wechat_token(config).await,
+        check_zhihu_cookies(config, Utc::now()),
+        check_templates(config),
+    ]);
+    checks.extend(check_write_permissions(config));
+    checks
+}
+
+pub fn check_config(config: &AppConfig) -> CheckResult {
+    if let Err(e) = config
+        .general
+        .timezone
+        .parse::<crate::core::TimeZoneSetting>()
+    {
+        return CheckResult::new("config", CheckStatus::Fail, e.to_string())
+            .with_fix("markflow config set general.timezone local");
+    }
+    if !matches!(config.output.report_format.as_str(), "markdown" | "html") {
+        return CheckResult::new(
+            "config",
+            CheckStatus::Fail,
+            format!("无效的报告格式: {}", config.output.report_format),
+        )
+        .with_fix("markflow config set output.report_format markdown");
+    }
+    CheckResult::new("config", CheckStatus::Ok, "配置有效")
+}
+
+pub fn check_browser(config: &AppConfig) -> CheckResult {
+    let sandbox = if sandbox_disabled(&config.browser) {
+        "，已关闭沙箱"
+    } else {
+        ""
+    };
+    if let Some(url) = &config.browser.webdriver_url {
+        return CheckResult::new(
+            "browser",
+            CheckStatus::Ok,
+            format!("使用 WebDriver 服务 {}{}", url, sandbox),
+        );
+    }
+    let driver = find_driver(&config.browser).or_else(|| {
+        WEBDRIVER_BINARIES
+            .iter()
+            .find_map(|name| find_in_path(name))
+    });
+    match driver {
+        Some(path) => CheckResult::new(
+            "browser",
+            CheckStatus::Ok,
+            format!("找到 WebDriver: {}{}", path.display(), sandbox),
+        ),
+        None if config.browser.auto_download => CheckResult::new(
+            "browser",
+            CheckStatus::Warn,
+            "未找到 chromedriver，首次使用浏览器时自动下载",
+        )
+        .with_fix("markflow browser install"),
+        None => {
+            // 只有开启知乎自动发布时浏览器自动化才是必需的
+            let status = if config.zhihu.auto_publish {
+                CheckStatus::Fail
+            } else {
+                CheckStatus::Warn
+            };
+            CheckResult::new("browser", status, "未找到 chromedriver/geckodriver").with_fix(
+                "markflow browser install，或安装与浏览器版本匹配的 chromedriver 并加入 PATH",
+            )
+        }
+    }
+}
+
+/// chromedriver 只支持同一主版本的浏览器；找不到其中之一或无法获取版本时不检查
+pub fn check_browser_version(config: &AppConfig) -> Option<CheckResult> {
+    let driver = major_version(&find_driver(&config.browser)?)?;
+    let browser = major_version(&find_executable(&config.browser)?)?;
+    Some(if driver == browser {
+        CheckResult::new(
+            "browser",
+            CheckStatus::Ok,
+            format!("浏览器与 chromedriver 版本匹配（{}）", browser),
+        )
+    } else {
+        CheckResult::new(
+            "browser",
+            CheckStatus::Fail,
+            format!("chromedriver {} 与浏览器 {} 主版本不一致", driver, browser),
+        )
+        .with_fix(
+            "markflow browser install，或配置 browser.executable、browser.driver 指向同一版本",
+        )
+    })
+}
+
+/// 就绪探针使用的凭据检查，不发起网络请求
+pub fn check_publishers(config: &AppConfig) -> Vec<CheckResult> {
+    vec![
+        check_wechat_credentials(config),
+        check_zhihu_cookies(config, Utc::now()),
+    ]
+}
+
+fn check_wechat_credentials(config: &AppConfig) -> CheckResult {
+    let wechat_ready = config.wechat.access_token.is_some()
+        || (config.wechat.app_id.is_some() && config.wechat.app_secret.is_some());
+    if wechat_ready {
+        return CheckResult::new("wechat_auth", CheckStatus::Ok, "已配置公众号凭据");
+    }
+    let status = if config.wechat.auto_publish {
+        CheckStatus::Fail
+    } else {
+        CheckStatus::Warn
+    };
+    CheckResult::new("wechat_auth", status, "未配置 app_id/app_secret")
+        .with_fix("markflow config set wechat.app_id <AppID> 并设置 wechat.app_secret")
+}
+
+/// 实际获取一次 access_token，确认凭据和 IP 白名单可用
+pub async fn check_wechat_token(config: &AppConfig) -> CheckResult {
+    let configured = check_wechat_credentials(config);
+    if configured.status != CheckStatus::Ok {
+        return configured;
+    }
+    if config.wechat.access_token.is_some() {
+        return CheckResult::new(
+            "wechat_auth",
+            CheckStatus::Ok,
+            "使用配置中固定的 access_token",
+        );
+    }
+    if crate::core::is_offline() {
+        return CheckResult::new(
+            "wechat_auth",
+            CheckStatus::Warn,
+            "离线模式，跳过 access_token 获取",
+        );
+    }
+    match wechat_access_token(&config.wechat).await {
+        Ok(_) => CheckResult::new("wechat_auth", CheckStatus::Ok, "成功获取 access_token"),
+        Err(e) => CheckResult::new(
+            "wechat_auth",
+            CheckStatus::Fail,
+            format!("获取 access_token 失败: {}", e),
+        )
+        .with_fix("核对 wechat.app_id/app_secret，并在公众号后台把本机出口 IP 加入白名单"),
+    }
+}
+
+/// 检查知乎 Cookie 文件是否存在、包含登录态且未过期
+pub fn check_zhihu_cookies(config: &AppConfig, now: DateTime<Utc>) -> CheckResult {
+    let unavailable = if config.zhihu.auto_publish {
+        CheckStatus::Fail
+    } else {
+        CheckStatus::Warn
+    };
+    let relogin = "重新登录知乎后导出 Cookie 覆盖该文件";
+
+    let path = match &config.zhihu.cookies_file {
+        Some(path) if path.exists() => path,
+        Some(path) => {
+            return CheckResult::new(
+                "zhihu_auth",
+                unavailable,
+                format!("Cookie 文件不存在: {}", path.display()),
+            )
+            .with_fix(relogin)
+        }
+        None => {
+            return CheckResult::new("zhihu_auth", unavailable, "未配置 cookies_file")
+                .with_fix("markflow config set zhihu.cookies_file <Cookie 文件路径>")
+        }
+    };
+
+    let cookies = match load_cookies(path) {
+        Ok(cookies) => cookies,
+        Err(e) => {
+            return CheckResult::new("zhihu_auth", CheckStatus::Fail, e.to_string())
+                .with_fix("导出为 JSON 数组或 Netscape cookies.txt 格式")
+        }
+    };
+    let Some(auth) = cookies.iter().find(|c| c.name == ZHIHU_AUTH_COOKIE) else {
+        return CheckResult::new(
+            "zhihu_auth",
+            CheckStatus::Fail,
+            format!("Cookie 文件中没有登录态 {}", ZHIHU_AUTH_COOKIE),
+        )
+        .with_fix(relogin);
+    };
+
+    match auth.expires {
+        Some(expires) if expires <= now => CheckResult::new(
+            "zhihu_auth",
+            CheckStatus::Fail,
+            format!("知乎登录已于 {} 过期", expires.format("%Y-%m-%d")),
+        )
+        .with_fix(relogin),
+        Some(expires) if expires - now < Duration::days(COOKIE_EXPIRY_WARN_DAYS) => {
+            CheckResult::new(
+                "zhihu_auth",
+                CheckStatus::Warn,
+                format!("知乎登录将于 {} 过期", expires.format("%Y-%m-%d")),
+            )
+            .with_fix(relogin)
+        }
+        _ => CheckResult::new(
+            "zhihu_auth",
+            CheckStatus::Ok,
+            format!("Cookie 文件: {}", path.display()),
+        ),
+    }
+}
+
+/// 检查模板目录和自定义模板能否被 Tera 解析
+pub fn check_templates(config: &AppConfig) -> CheckResult {
+    let templates = &config.templates;
+    let mut missing: Vec<String> = templates
+        .custom_templates
+        .iter()
+        .filter(|(_, path)| !path.is_file())
+        .map(|(name, path)| format!("{} ({})", name, path.display()))
+        .collect();
+    if !missing.is_empty() {
+        missing.sort();
+        return CheckResult::new(
+            "templates",
+            CheckStatus::Fail,
+            format!("自定义模板文件不存在: {}", missing.join(", ")),
+        )
+        .with_fix("更新 templates.custom_templates 或恢复对应文件");
+    }
+
+    let dir = &templates.templates_dir;
+    if !dir.exists() {
+        return CheckResult::new(
+            "templates",
+            CheckStatus::Warn,
+            format!("模板目录不存在: {}", dir.display()),
+        )
+        .with_fix(format!("mkdir -p {}", dir.display()));
+    }
+    if !dir.is_dir() {
+        return CheckResult::new(
+            "templates",
+            CheckStatus::Fail,
+            format!("模板路径不是目录: {}", dir.display()),
+        )
+        .with_fix("修改 templates.templates_dir 指向一个目录");
+    }
+
+    let glob = dir.join("**").join("*.html");
+    let parsed = tera::Tera::new(&glob.to_string_lossy()).and_then(|mut tera| {
+        tera.add_template_files(
+            templates
+                .custom_templates
+                .iter()
+                .map(|(name, path)| (path, Some(name.as_str()))),
+        )?;
+        Ok(tera)
+    });
+    let tera = match parsed {
+        Ok(tera) => tera,
+        Err(e) => {
+            return CheckResult::new(
+                "templates",
+                CheckStatus::Fail,
+                format!("模板解析失败: {}", error_chain(&e)),
+            )
+            .with_fix("根据错误信息修正模板语法")
+        }
+    };
+
+    let names: Vec<&str> = tera.get_template_names().collect();
+    if let Some(default) = &templates.default_template {
+        let found = names
+            .iter()
+            .any(|name| *name == default || *name == format!("{}.html", default));
+        if !found {
+            return CheckResult::new(
+                "templates",
+                CheckStatus::Fail,
+                format!("默认模板 {} 不存在", default),
+            )
+            .with_fix("修改 templates.default_template 或添加同名模板");
+        }
+    }
+    CheckResult::new(
+        "templates",
+        CheckStatus::Ok,
+        format!("{} 个模板可用（{}）", names.len(), dir.display()),
+    )
+}
+
+/// 检查配置目录、输出目录和备份目录是否可写
+pub fn check_write_permissions(config: &AppConfig) -> Vec<CheckResult> {
+    let mut dirs = vec![AppConfig::get_config_path()
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| PathBuf::from("."))];
+    dirs.push(config.output.output_dir.clone());
+    dirs.extend(config.output.backup_dir.clone());
+
+    dirs.iter()
+        .map(|dir| match check_writable(dir) {
+            Ok(()) => {
+                CheckResult::new("write", CheckStatus::Ok, format!("可写: {}", dir.display()))
+            }
+            Err(e) => CheckResult::new(
+                "write",
+                CheckStatus::Fail,
+                format!("无法写入 {}: {}", dir.display(), e),
+            )
+            .with_fix(format!("检查 {} 的所有者和权限", dir.display())),
+        })
+        .collect()
+}
+
+/// 目录不存在时检查最近的已存在上级目录，不提前创建目录
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    let existing = dir
+        .ancestors()
+        .find(|p| !p.as_os_str().is_empty() && p.exists())
+        .unwrap_or(Path::new("."));
+    if !existing.is_dir() {
+        return Err(std::io::Error::other(format!(
+            "{} 不是目录",
+            existing.display()
+        )));
+    }
+    let probe = existing.join(format!(".markflow-doctor-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(e) = source {
+        message.push_str(": ");
+        message.push_str(&e.to_string());
+        source = e.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn config_in(dir: &Path) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.templates.templates_dir = dir.join("templates");
+        config.output.output_dir = dir.join("output");
+        config
+    }
+
+    #[test]
+    fn test_zhihu_cookie_expiry() {
+        let dir = TempDir::new().unwrap();
+        let mut config = config_in(dir.path());
+        let cookies = dir.path().join("cookies.json");
+        std::fs::write(
+            &cookies,
+            r#"[{"name": "z_c0", "value": "t", "domain": ".zhihu.com", "expirationDate": 1800000000}]"#,
+        )
+        .unwrap();
+        config.zhihu.cookies_file = Some(cookies);
+
+        let before = DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        assert_eq!(check_zhihu_cookies(&config, before).status, CheckStatus::Ok);
+
+        let almost = DateTime::from_timestamp(1_800_000_000 - 86_400, 0).unwrap();
+        assert_eq!(
+            check_zhihu_cookies(&config, almost).status,
+            CheckStatus::Warn
+        );
+
+        let after = DateTime::from_timestamp(1_800_000_001, 0).unwrap();
+        let result = check_zhihu_cookies(&config, after);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.fix.is_some());
+    }
+
+    #[test]
+    fn test_templates_check() {
+        let dir = TempDir::new().unwrap();
+        let mut config = config_in(dir.path());
+        assert_eq!(check_templates(&config).status, CheckStatus::Warn);
+
+        std::fs::create_dir_all(&config.templates.templates_dir).unwrap();
+        std::fs::write(
+            config.templates.templates_dir.join("post.html"),
+            "{{ content }}",
+        )
+        .unwrap();
+        config.templates.default_template = Some("post".to_string());
+        assert_eq!(check_templates(&config).status, CheckStatus::Ok);
+
+        std::fs::write(
+            config.templates.templates_dir.join("broken.html"),
+            "{% if %}",
+        )
+        .unwrap();
+        let result = check_templates(&config);
+        assert_eq!(result.status, CheckStatus::Fail);
+        assert!(result.message.contains("模板解析失败"));
+    }
+
+    #[test]
+    fn test_write_permissions_for_missing_dir() {
+        let dir = TempDir::new().unwrap();
+        let config = config_in(dir.path());
+        assert!(check_writable(&config.output.output_dir.join("nested")).is_ok());
+        assert!(!config.output.output_dir.exists());
+
+        let file = dir.path().join("file");
+        std::fs::write(&file, "").unwrap();
+        assert!(check_writable(&file.join("sub")).is_err());
+    }
+}
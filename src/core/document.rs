@@ -0,0 +1,194 @@
+use crate::{
+    cli::args::{StyleConfig, TemplateConfig},
+    core::{
+        content::Content,
+        language::Language,
+        style::{render_template_str, style_context},
+    },
+    error::Error,
+    Result,
+};
+use std::{path::PathBuf, str::FromStr};
+use tera::Context;
+
+/// 输出 HTML 的形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DocumentMode {
+    /// 只输出正文片段，便于粘贴到平台编辑器
+    #[default]
+    Fragment,
+    /// 套用当前模板输出可以直接打开的完整 HTML 文档
+    Full,
+}
+
+impl FromStr for DocumentMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "fragment" => Ok(Self::Fragment),
+            "full" | "document" => Ok(Self::Full),
+            other => Err(Error::Config(format!(
+                "无效的输出形式: {}（可选: fragment、full）",
+                other
+            ))),
+        }
+    }
+}
+
+/// 未配置 `templates.default_template` 时使用的文档骨架
+const BUILTIN_DOCUMENT: &str = r#"<!DOCTYPE html>
+<html lang="{{ lang }}">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{{ title | escape }}</title>
+{%- if description %}
+<meta name="description" content="{{ description | escape }}">
+{%- endif %}
+{%- if author %}
+<meta name="author" content="{{ author | escape }}">
+{%- endif %}
+</head>
+<body style="background-color: {{ style.background_color }}">
+{{ content }}
+</body>
+</html>
+"#;
+
+/// 模板名对应的文件：先在 `custom_templates` 中查找，再到模板目录中找同名文件（可省略 `.html`）
+pub fn find_template(templates: &TemplateConfig, name: &str) -> Option<PathBuf> {
+    if let Some(path) = templates.custom_templates.get(name) {
+        return Some(path.clone());
+    }
+    [name.to_string(), format!("{}.html", name)]
+        .iter()
+        .map(|file| templates.templates_dir.join(file))
+        .find(|path| path.is_file())
+}
+
+/// 当前模板（`templates.default_template`）的路径，未配置时返回 `None`
+pub fn active_template(templates: &TemplateConfig) -> Result<Option<PathBuf>> {
+    let Some(name) = &templates.default_template else {
+        return Ok(None);
+    };
+    find_template(templates, name)
+        .map(Some)
+        .ok_or_else(|| Error::Config(format!("默认模板 {} 不存在", name)))
+}
+
+/// 渲染文章模板的上下文：`content`（正文 HTML，也可写作 `html`）、`title`、`description`、
+/// `author`、`tags`、`date`、`reading_time`（分钟）、`word_count`、`lang`、`platform`、
+/// 其余 front matter 字段 `custom`，以及主题变量 `style`
+pub fn template_context(
+    html: &str,
+    content: &Content,
+    platform: &str,
+    style: &StyleConfig,
+) -> Context {
+    let metadata = &content.metadata;
+    let mut context = style_context(style);
+    context.insert("content", html);
+    context.insert("html", html);
+    context.insert("title", &content.title);
+    context.insert("description", &metadata.description);
+    context.insert("author", &metadata.author);
+    context.insert("tags", &metadata.tags);
+    context.insert("date", &metadata.date);
+    context.insert("reading_time", &metadata.reading_time);
+    context.insert("word_count", &metadata.word_count);
+    context.insert("custom", &metadata.custom_fields);
+    context.insert("lang", content.language().code());
+    context.insert("platform", platform);
+    context
+}
+
+/// 把平台适配后的正文片段套进当前模板，生成完整文档；日期和数字按 `locale` 格式化
+pub fn render_document(
+    html: &str,
+    content: &Content,
+    platform: &str,
+    templates: &TemplateConfig,
+    style: &StyleConfig,
+    locale: Language,
+) -> Result<String> {
+    let template = match active_template(templates)? {
+        Some(path) => std::fs::read_to_string(&path)?,
+        None => BUILTIN_DOCUMENT.to_string(),
+    };
+    render_template_str(
+        &template,
+        &template_context(html, content, platform, style),
+        locale,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn templates(dir: PathBuf, default: Option<&str>) -> TemplateConfig {
+        TemplateConfig {
+            templates_dir: dir,
+            default_template: default.map(str::to_string),
+            custom_templates: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_builtin_document() {
+        let mut content = Content::new("A <B>".to_string(), "Rust 异步编程".to_string());
+        content.metadata.description = Some("简介".to_string());
+        let dir = tempfile::tempdir().unwrap();
+
+        let html = render_document(
+            "<p>正文</p>",
+            &content,
+            "wechat",
+            &templates(dir.path().to_path_buf(), None),
+            &StyleConfig::default(),
+            Language::Zh,
+        )
+        .unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(r#"<html lang="zh">"#));
+        assert!(html.contains("<title>A &lt;B&gt;</title>"));
+        assert!(html.contains(r#"<meta name="description" content="简介">"#));
+        assert!(html.contains("<body style=\"background-color: "));
+        assert!(html.contains("<p>正文</p>"));
+        assert!(!html.contains("name=\"author\""));
+    }
+
+    #[test]
+    fn test_active_template() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("post.html"),
+            "<main data-platform=\"{{ platform }}\"><time>{{ date | local_date }}</time>{{ content }}</main>",
+        )
+        .unwrap();
+        let mut content = Content::new("标题".to_string(), "正文".to_string());
+        content.metadata.date =
+            chrono::DateTime::parse_from_rfc3339("2024-07-01T09:30:00+08:00").ok();
+
+        let html = render_document(
+            "<p>正文</p>",
+            &content,
+            "zhihu",
+            &templates(dir.path().to_path_buf(), Some("post")),
+            &StyleConfig::default(),
+            Language::Zh,
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<main data-platform=\"zhihu\"><time>2024年7月1日</time><p>正文</p></main>"
+        );
+
+        let missing = templates(dir.path().to_path_buf(), Some("page"));
+        assert!(active_template(&missing).is_err());
+        "fragment".parse::<DocumentMode>().unwrap();
+        assert!("pdf".parse::<DocumentMode>().is_err());
+    }
+}
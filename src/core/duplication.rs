@@ -0,0 +1,207 @@
+use crate::{
+    cli::args::DuplicationConfig,
+    core::{store::ContentStore, text::plain_text},
+    Result,
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+    path::Path,
+};
+
+/// 每个片段包含的字符数（去掉空白和标点后）
+const SHINGLE_CHARS: usize = 8;
+/// 段落中的片段至少有这么大比例出现在同一篇历史文章里才算重复段落
+const PASSAGE_RATIO: f64 = 0.8;
+
+/// 把文本切成重叠的字符片段并计算哈希，忽略大小写、空白和标点
+fn shingles(text: &str) -> HashSet<u64> {
+    let chars: Vec<char> = text
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect();
+    if chars.is_empty() {
+        return HashSet::new();
+    }
+    chars
+        .windows(SHINGLE_CHARS.min(chars.len()))
+        .map(|window| {
+            let mut hasher = DefaultHasher::new();
+            window.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect()
+}
+
+/// 与某篇历史文章的重复情况
+#[derive(Debug, Clone)]
+pub struct DuplicationMatch {
+    pub title: String,
+    /// 新文章的片段中出现在该文章里的比例（0-1）
+    pub coverage: f64,
+    /// 基本重复的段落（截取开头）
+    pub passages: Vec<String>,
+}
+
+impl DuplicationMatch {
+    pub fn message(&self) -> String {
+        let mut message = format!("与《{}》重复约 {:.0}%", self.title, self.coverage * 100.0);
+        if !self.passages.is_empty() {
+            message.push_str(&format!(
+                "，重复段落：{}",
+                self.passages
+                    .iter()
+                    .map(|p| format!("「{}」", p))
+                    .collect::<Vec<_>>()
+                    .join("、")
+            ));
+        }
+        message
+    }
+}
+
+/// 基于字符片段（shingling）的自我重复检查
+pub struct DuplicationChecker {
+    titles: Vec<String>,
+    /// 片段哈希 -> 包含该片段的文章序号
+    index: HashMap<u64, Vec<usize>>,
+    config: DuplicationConfig,
+}
+
+impl DuplicationChecker {
+    pub fn new(config: DuplicationConfig) -> Self {
+        Self {
+            titles: Vec::new(),
+            index: HashMap::new(),
+            config,
+        }
+    }
+
+    /// 用台账中仍能找到源文件的文章建立语料，跳过当前文章本身
+    pub fn from_store(
+        store: &ContentStore,
+        current: &Path,
+        config: &DuplicationConfig,
+    ) -> Result<Self> {
+        let current = std::fs::canonicalize(current).unwrap_or_else(|_| current.to_path_buf());
+        let mut checker = Self::new(config.clone());
+        for record in store.records() {
+            let Some(source) = &record.source else {
+                continue;
+            };
+            if *source == current {
+                continue;
+            }
+            let Ok(markdown) = std::fs::read_to_string(source) else {
+                continue;
+            };
+            checker.add_document(&record.title, &plain_text(&markdown));
+        }
+        Ok(checker)
+    }
+
+    pub fn add_document(&mut self, title: &str, text: &str) {
+        let id = self.titles.len();
+        self.titles.push(title.to_string());
+        for shingle in shingles(text) {
+            self.index.entry(shingle).or_default().push(id);
+        }
+    }
+
+    /// 每篇历史文章包含的片段数
+    fn overlap(&self, shingles: &HashSet<u64>) -> HashMap<usize, usize> {
+        let mut counts = HashMap::new();
+        for shingle in shingles {
+            for id in self.index.get(shingle).into_iter().flatten() {
+                *counts.entry(*id).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// 检查正文（纯文本，段落按行分隔），按重复比例从高到低返回
+    pub fn check(&self, text: &str) -> Vec<DuplicationMatch> {
+        let all = shingles(text);
+        if all.is_empty() {
+            return Vec::new();
+        }
+
+        let mut passages: HashMap<usize, Vec<String>> = HashMap::new();
+        for paragraph in text.lines().map(str::trim) {
+            if paragraph.chars().count() < self.config.min_passage_chars {
+                continue;
+            }
+            let paragraph_shingles = shingles(paragraph);
+            for (id, count) in self.overlap(&paragraph_shingles) {
+                if count as f64 / paragraph_shingles.len() as f64 >= PASSAGE_RATIO {
+                    let preview: String = paragraph.chars().take(30).collect();
+                    passages
+                        .entry(id)
+                        .or_default()
+                        .push(format!("{}…", preview));
+                }
+            }
+        }
+
+        let mut matches: Vec<DuplicationMatch> = self
+            .overlap(&all)
+            .into_iter()
+            .map(|(id, count)| DuplicationMatch {
+                title: self.titles[id].clone(),
+                coverage: count as f64 / all.len() as f64,
+                passages: passages.remove(&id).unwrap_or_default(),
+            })
+            .filter(|m| m.coverage >= self.config.threshold || !m.passages.is_empty())
+            .collect();
+        matches.sort_by(|a, b| b.coverage.total_cmp(&a.coverage));
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = "Rust 的所有权系统保证了内存安全，编译器在编译期检查借用规则，避免了悬垂指针和数据竞争等常见问题。\n生命周期标注帮助编译器理解引用之间的关系。";
+
+    fn checker() -> DuplicationChecker {
+        let mut checker = DuplicationChecker::new(DuplicationConfig {
+            enabled: true,
+            threshold: 0.3,
+            min_passage_chars: 20,
+        });
+        checker.add_document("所有权详解", OLD);
+        checker.add_document(
+            "无关文章",
+            "今天天气很好，我们去公园散步，看到了很多盛开的花。",
+        );
+        checker
+    }
+
+    #[test]
+    fn test_detects_duplicated_passage() {
+        let text = "这是一篇新文章的开头，介绍一些背景知识和写作动机，内容与之前完全不同。\nRust 的所有权系统保证了内存安全，编译器在编译期检查借用规则，避免了悬垂指针和数据竞争等常见问题。\n最后总结一下全文的主要观点以及后续计划，欢迎留言讨论。";
+        let matches = checker().check(text);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].title, "所有权详解");
+        assert_eq!(matches[0].passages.len(), 1);
+        assert!(matches[0].passages[0].starts_with("Rust 的所有权系统"));
+        assert!(matches[0].message().contains("《所有权详解》"));
+    }
+
+    #[test]
+    fn test_reformatted_copy_is_detected() {
+        // 空白、标点和大小写的差异不影响判断
+        let text = OLD.replace('，', ",").replace("Rust", "rust");
+        let matches = checker().check(&text);
+        assert!(matches[0].coverage > 0.9);
+    }
+
+    #[test]
+    fn test_unrelated_text() {
+        let matches =
+            checker().check("这是完全不同的内容，讲的是如何做一道红烧肉，需要准备五花肉和冰糖。");
+        assert!(matches.is_empty());
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{error::Error, Result};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use regex::Regex;
+use std::{str::FromStr, sync::OnceLock};
+
+const MARKER: &str = "markflow:source";
+
+/// 在输出 HTML 中嵌入 Markdown 源文的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceEmbedMode {
+    #[default]
+    None,
+    /// 以 HTML 注释保存原文，可直接阅读
+    Comment,
+    /// Base64 编码后放在注释中，不受输出编码影响
+    Base64,
+}
+
+impl FromStr for SourceEmbedMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "none" | "false" => Ok(Self::None),
+            "comment" => Ok(Self::Comment),
+            "base64" => Ok(Self::Base64),
+            other => Err(Error::Config(format!(
+                "无效的源文嵌入方式: {}（可选: none、comment、base64）",
+                other
+            ))),
+        }
+    }
+}
+
+/// 把 Markdown 源文以注释形式追加到 HTML 末尾
+pub fn embed_source(html: &str, markdown: &str, mode: SourceEmbedMode) -> String {
+    let payload = match mode {
+        SourceEmbedMode::None => return html.to_string(),
+        SourceEmbedMode::Comment => format!("encoding=comment\n{}\n", escape_comment(markdown)),
+        SourceEmbedMode::Base64 => format!("encoding=base64\n{}\n", STANDARD.encode(markdown)),
+    };
+
+    let mut out = html.trim_end().to_string();
+    out.push_str(&format!("\n<!-- {} {}-->\n", MARKER, payload));
+    out
+}
+
+/// 从输出 HTML 中取回嵌入的 Markdown 源文
+pub fn extract_source(html: &str) -> Option<String> {
+    static SOURCE_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = SOURCE_REGEX.get_or_init(|| {
+        Regex::new(r"(?s)<!-- markflow:source encoding=(comment|base64)\n(.*?)\n-->").unwrap()
+    });
+
+    let caps = regex.captures_iter(html).last()?;
+    match &caps[1] {
+        "comment" => Some(unescape_comment(&caps[2])),
+        _ => {
+            let compact: String = caps[2].split_whitespace().collect();
+            let bytes = STANDARD.decode(compact).ok()?;
+            String::from_utf8(bytes).ok()
+        }
+    }
+}
+
+// 注释内容不能出现 `--`，先转义 `&` 保证可逆
+fn escape_comment(text: &str) -> String {
+    text.replace('&', "&amp;").replace("--", "&#45;&#45;")
+}
+
+fn unescape_comment(text: &str) -> String {
+    text.replace("&#45;", "-").replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = "---\ntitle: 测试\n---\n\n# 标题\n\n正文 --> 注释 &#45; & `--flag`\n";
+
+    #[test]
+    fn test_embed_roundtrip() {
+        for mode in [SourceEmbedMode::Comment, SourceEmbedMode::Base64] {
+            let html = embed_source("<p>正文</p>\n", MARKDOWN, mode);
+            assert!(html.starts_with("<p>正文</p>\n<!-- markflow:source"));
+            assert_eq!(html.matches("-->").count(), 1, "{:?}", mode);
+            assert_eq!(extract_source(&html).as_deref(), Some(MARKDOWN));
+        }
+    }
+
+    #[test]
+    fn test_embed_none() {
+        let html = embed_source("<p>正文</p>", MARKDOWN, SourceEmbedMode::None);
+        assert_eq!(html, "<p>正文</p>");
+        assert_eq!(extract_source(&html), None);
+        assert_eq!(
+            "base64".parse::<SourceEmbedMode>().unwrap(),
+            SourceEmbedMode::Base64
+        );
+        assert!("zip".parse::<SourceEmbedMode>().is_err());
+    }
+}
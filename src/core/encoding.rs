@@ -0,0 +1,131 @@
+use crate::{error::Error, Result};
+use encoding_rs::Encoding;
+use regex::Regex;
+use std::sync::OnceLock;
+
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// 输出文件的字符编码
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutputEncoding {
+    encoding: &'static Encoding,
+    bom: bool,
+}
+
+impl Default for OutputEncoding {
+    fn default() -> Self {
+        Self {
+            encoding: encoding_rs::UTF_8,
+            bom: false,
+        }
+    }
+}
+
+impl OutputEncoding {
+    /// 解析编码名称，支持 WHATWG 标签（如 `gbk`、`gb18030`、`big5`）以及 `utf-8-bom`
+    pub fn from_label(label: &str) -> Result<Self> {
+        let label = label.trim().to_ascii_lowercase();
+        let (name, bom) = match label
+            .strip_suffix("-bom")
+            .or_else(|| label.strip_suffix("+bom"))
+        {
+            Some(name) => (name, true),
+            None => (label.as_str(), false),
+        };
+
+        let encoding = Encoding::for_label(name.as_bytes())
+            .filter(|e| e.output_encoding() == *e)
+            .ok_or_else(|| Error::Config(format!("不支持的输出编码: {}", label)))?;
+        if bom && encoding != encoding_rs::UTF_8 {
+            return Err(Error::Config(format!("只有 UTF-8 支持 BOM: {}", label)));
+        }
+
+        Ok(Self { encoding, bom })
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.encoding.name()
+    }
+
+    pub fn is_utf8(&self) -> bool {
+        self.encoding == encoding_rs::UTF_8
+    }
+
+    /// 转码 HTML，目标编码无法表示的字符写为 `&#NNNN;` 数字实体，
+    /// 已有的 `<meta charset>` 声明会同步改为目标编码
+    pub fn encode_html(&self, html: &str) -> Vec<u8> {
+        let html = rewrite_meta_charset(html, self.name());
+        let (bytes, _, _) = self.encoding.encode(&html);
+
+        let mut out = Vec::with_capacity(bytes.len() + UTF8_BOM.len());
+        if self.bom {
+            out.extend_from_slice(UTF8_BOM);
+        }
+        out.extend_from_slice(&bytes);
+        out
+    }
+
+    /// 按当前编码解码，自动去掉 BOM
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        let (text, _) = self.encoding.decode_with_bom_removal(bytes);
+        text.into_owned()
+    }
+}
+
+fn rewrite_meta_charset(html: &str, charset: &str) -> String {
+    static META_CHARSET_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = META_CHARSET_REGEX
+        .get_or_init(|| Regex::new(r#"(?i)(<meta\s+charset=["']?)[\w-]+"#).unwrap());
+    regex
+        .replace_all(html, |caps: &regex::Captures| {
+            format!("{}{}", &caps[1], charset)
+        })
+        .into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gbk_roundtrip_chinese() {
+        let encoding = OutputEncoding::from_label("GBK").unwrap();
+        let html = "<meta charset=\"utf-8\"><p>中文内容，测试 GBK 编码</p>";
+
+        let bytes = encoding.encode_html(html);
+        // “中文”的 GBK 编码
+        assert!(bytes.windows(4).any(|w| w == [0xD6, 0xD0, 0xCE, 0xC4]));
+        assert!(!bytes.starts_with(UTF8_BOM));
+
+        let decoded = encoding.decode(&bytes);
+        assert!(decoded.contains("中文内容，测试 GBK 编码"));
+        assert!(decoded.contains("charset=\"GBK\""));
+    }
+
+    #[test]
+    fn test_unmappable_chars_become_entities() {
+        let encoding = OutputEncoding::from_label("gbk").unwrap();
+        let decoded = encoding.decode(&encoding.encode_html("<p>表情😀</p>"));
+        assert_eq!(decoded, "<p>表情&#128512;</p>");
+    }
+
+    #[test]
+    fn test_utf8_bom() {
+        let encoding = OutputEncoding::from_label("utf-8-bom").unwrap();
+        let bytes = encoding.encode_html("<p>中文</p>");
+        assert!(bytes.starts_with(UTF8_BOM));
+        assert_eq!(&bytes[3..], "<p>中文</p>".as_bytes());
+        assert_eq!(encoding.decode(&bytes), "<p>中文</p>");
+
+        let plain = OutputEncoding::default().encode_html("<p>中文</p>");
+        assert_eq!(plain, "<p>中文</p>".as_bytes());
+    }
+
+    #[test]
+    fn test_invalid_labels() {
+        assert!(OutputEncoding::from_label("klingon").is_err());
+        assert!(OutputEncoding::from_label("gbk-bom").is_err());
+        // UTF-16 无法作为输出编码
+        assert!(OutputEncoding::from_label("utf-16le").is_err());
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    core::content::{Content, Platform},
+    error::Error,
+    Result,
+};
+use std::collections::BTreeMap;
+
+/// 内置的 front matter → 平台字段映射，配置 `[field_mapping.<平台>]` 可以覆盖或追加
+const BUILTIN_MAPPINGS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "wechat",
+        &[
+            ("author", "author"),
+            ("description", "digest"),
+            ("thumb_media_id", "thumb_media_id"),
+            ("source_url", "content_source_url"),
+            ("comment", "need_open_comment"),
+            ("fans_only_comment", "only_fans_can_comment"),
+            ("original", "declare_original"),
+            ("reward", "can_reward"),
+        ],
+    ),
+    ("zhihu", &[("column", "column"), ("tags", "topics")]),
+];
+
+/// 按平台解析出的字段，键为平台字段名
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlatformFields(BTreeMap<String, String>);
+
+impl PlatformFields {
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.0.get(field).map(String::as_str)
+    }
+
+    /// 开关类字段：true/false、yes/no、on/off、1/0、是/否
+    pub fn flag(&self, field: &str) -> Result<Option<bool>> {
+        let Some(value) = self.get(field) else {
+            return Ok(None);
+        };
+        match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" | "是" => Ok(Some(true)),
+            "false" | "no" | "off" | "0" | "否" => Ok(Some(false)),
+            _ => Err(Error::Validation(format!(
+                "平台字段 {} 的值无效: {}（应为 true 或 false）",
+                field, value
+            ))),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// front matter 字段到平台字段的映射表，发布器据此把文章元数据转为平台设置
+#[derive(Debug, Clone)]
+pub struct FieldMapping {
+    /// 平台名 → (front matter 字段 → 平台字段)
+    rules: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Default for FieldMapping {
+    fn default() -> Self {
+        Self::from_config(&BTreeMap::new())
+    }
+}
+
+impl FieldMapping {
+    /// 在内置映射上叠加配置；配置中目标为空字符串的条目会取消对应的内置映射
+    pub fn from_config(config: &BTreeMap<String, BTreeMap<String, String>>) -> Self {
+        let mut rules: BTreeMap<String, BTreeMap<String, String>> = BUILTIN_MAPPINGS
+            .iter()
+            .map(|(platform, pairs)| {
+                let pairs = pairs
+                    .iter()
+                    .map(|(key, field)| (key.to_string(), field.to_string()))
+                    .collect();
+                (platform.to_string(), pairs)
+            })
+            .collect();
+        for (platform, pairs) in config {
+            let entry = rules.entry(platform.to_lowercase()).or_default();
+            for (key, field) in pairs {
+                if field.trim().is_empty() {
+                    entry.remove(key);
+                } else {
+                    entry.insert(key.clone(), field.trim().to_string());
+                }
+            }
+        }
+        Self { rules }
+    }
+
+    /// 某个平台的映射规则（front matter 字段 → 平台字段）
+    pub fn rules_for(&self, platform: &str) -> Option<&BTreeMap<String, String>> {
+        self.rules.get(&platform.to_lowercase())
+    }
+
+    /// 从文章的 front matter 中取出映射到该平台的字段
+    pub fn resolve(&self, platform: &Platform, content: &Content) -> PlatformFields {
+        let mut fields = BTreeMap::new();
+        for (key, field) in self.rules_for(&platform.to_string()).into_iter().flatten() {
+            if let Some(value) = front_matter_value(content, key) {
+                fields.insert(field.clone(), value);
+            }
+        }
+        PlatformFields(fields)
+    }
+}
+
+/// 读取 front matter 字段；作者、摘要等已解析到元数据中的字段从元数据中取
+fn front_matter_value(content: &Content, key: &str) -> Option<String> {
+    let metadata = &content.metadata;
+    let value = match key {
+        "title" => Some(content.title.clone()),
+        "author" => metadata.author.clone(),
+        "description" => metadata.description.clone(),
+        "cover" => metadata.cover_image.clone(),
+        "tags" if !metadata.tags.is_empty() => Some(metadata.tags.join(",")),
+        "date" => metadata.date.map(|d| d.to_rfc3339()),
+        "slug" => Some(content.slug()),
+        // 未单独设置原文链接时使用文章的规范地址
+        "source_url" => metadata
+            .custom_text(key)
+            .or_else(|| metadata.canonical_url.clone()),
+        "canonical_url" => metadata.canonical_url.clone(),
+        _ => metadata.custom_text(key),
+    };
+    value.filter(|v| !v.trim().is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content() -> Content {
+        let mut content = Content {
+            id: uuid::Uuid::new_v4(),
+            title: "标题".to_string(),
+            markdown: String::new(),
+            html: String::new(),
+            metadata: Default::default(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        content.metadata.author = Some("张三".to_string());
+        content.metadata.tags = vec!["Rust".to_string(), "CLI".to_string()];
+        for (key, value) in [("column", "技术专栏"), ("open_comment", "否")] {
+            content
+                .metadata
+                .custom_fields
+                .insert(key.to_string(), value.into());
+        }
+        content
+    }
+
+    #[test]
+    fn test_resolve_with_config_overrides() {
+        let config = BTreeMap::from([(
+            "wechat".to_string(),
+            BTreeMap::from([
+                ("author".to_string(), String::new()),
+                ("open_comment".to_string(), "need_open_comment".to_string()),
+            ]),
+        )]);
+        let mapping = FieldMapping::from_config(&config);
+
+        let wechat = mapping.resolve(&Platform::WeChat, &content());
+        assert_eq!(wechat.get("author"), None);
+        assert_eq!(wechat.flag("need_open_comment").unwrap(), Some(false));
+        assert_eq!(wechat.flag("only_fans_can_comment").unwrap(), None);
+
+        let zhihu = mapping.resolve(&Platform::Zhihu, &content());
+        assert_eq!(zhihu.get("column"), Some("技术专栏"));
+        assert_eq!(zhihu.get("topics"), Some("Rust,CLI"));
+        assert!(mapping
+            .resolve(&Platform::Custom("blog".to_string()), &content())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_flag() {
+        let mut content = content();
+        content
+            .metadata
+            .custom_fields
+            .insert("comment".to_string(), "maybe".into());
+        let fields = FieldMapping::default().resolve(&Platform::WeChat, &content);
+        assert!(matches!(
+            fields.flag("need_open_comment"),
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,106 @@
+use regex::Regex;
+use std::{collections::HashMap, sync::OnceLock};
+
+/// 行内脚注提示框的样式，只在页面中出现一次
+const TOOLTIP_STYLE: &str = "<style>.footnote-tooltip{position:relative;cursor:help;color:#3498db;}.footnote-tooltip .footnote-content{display:none;position:absolute;left:0;bottom:1.6em;z-index:10;min-width:12em;max-width:24em;padding:8px 10px;font-size:14px;line-height:1.6;color:#333;background:#fff;border:1px solid #ddd;border-radius:4px;box-shadow:0 2px 8px rgba(0,0,0,.15);}.footnote-tooltip:hover .footnote-content,.footnote-tooltip:focus .footnote-content{display:block;}</style>";
+
+fn footnote_section(html: &str) -> Option<regex::Match<'_>> {
+    static SECTION_REGEX: OnceLock<Regex> = OnceLock::new();
+    let section_regex = SECTION_REGEX.get_or_init(|| {
+        Regex::new(r#"(?s)<section class="footnotes"[^>]*>.*?</section>\n?"#).unwrap()
+    });
+    section_regex.find(html)
+}
+
+/// 文末脚注列表中的脚注，按出现顺序返回（标识，去掉返回链接和外层段落的 HTML）
+pub fn collect_footnotes(html: &str) -> Vec<(String, String)> {
+    static ITEM_REGEX: OnceLock<Regex> = OnceLock::new();
+    static BACKREF_REGEX: OnceLock<Regex> = OnceLock::new();
+    let item_regex = ITEM_REGEX
+        .get_or_init(|| Regex::new(r#"(?s)<li id="fn-([^"]+)">\s*(.*?)\s*</li>"#).unwrap());
+    let backref_regex = BACKREF_REGEX
+        .get_or_init(|| Regex::new(r##"\s*<a href="#fnref-[^"]*"[^>]*>.*?</a>"##).unwrap());
+
+    let Some(section) = footnote_section(html) else {
+        return Vec::new();
+    };
+    item_regex
+        .captures_iter(section.as_str())
+        .map(|caps| {
+            let body = backref_regex.replace_all(&caps[2], "");
+            (caps[1].to_string(), unwrap_paragraphs(&body))
+        })
+        .collect()
+}
+
+/// 把文末的脚注列表改为行内提示框：悬停或聚焦脚注编号时显示内容，
+/// 同时写入 `title` 属性作为纯文本兜底
+pub fn render_footnote_tooltips(html: &str) -> String {
+    static REF_REGEX: OnceLock<Regex> = OnceLock::new();
+    let ref_regex = REF_REGEX.get_or_init(|| {
+        Regex::new(r##"<sup class="footnote-ref"><a href="#fn-([^"]+)"[^>]*>([^<]*)</a></sup>"##)
+            .unwrap()
+    });
+
+    let Some(section) = footnote_section(html) else {
+        return html.to_string();
+    };
+    let notes: HashMap<String, String> = collect_footnotes(html).into_iter().collect();
+
+    let without_section = format!("{}{}", &html[..section.start()], &html[section.end()..]);
+    let result = ref_regex.replace_all(&without_section, |caps: &regex::Captures| {
+        let Some(note) = notes.get(&caps[1]) else {
+            return caps[0].to_string();
+        };
+        format!(
+            r#"<span class="footnote-tooltip" tabindex="0" title="{}"><sup>{}</sup><span class="footnote-content" role="tooltip">{}</span></span>"#,
+            html_escape::encode_double_quoted_attribute(&html_plain_text(note)),
+            &caps[2],
+            note
+        )
+    });
+
+    format!("{}\n{}", TOOLTIP_STYLE, result)
+}
+
+/// 提示框位于段落内，块级的 `<p>` 改为换行
+fn unwrap_paragraphs(html: &str) -> String {
+    html.trim()
+        .trim_start_matches("<p>")
+        .trim_end_matches("</p>")
+        .replace("</p>\n<p>", "<br>")
+}
+
+/// 去掉 HTML 标签并还原实体
+pub(crate) fn html_plain_text(html: &str) -> String {
+    static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = TAG_REGEX.get_or_init(|| Regex::new(r"<[^>]*>").unwrap());
+    html_escape::decode_html_entities(&regex.replace_all(html, "")).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MarkdownProcessor;
+
+    #[test]
+    fn test_footnotes_become_tooltips() {
+        let content = MarkdownProcessor::new()
+            .process("正文[^1]，再来一个[^note]。\n\n[^1]: 第一条，见 [链接](https://a.com)。\n[^note]: 第二条 *强调*。\n")
+            .unwrap();
+
+        let html = render_footnote_tooltips(&content.html);
+        assert!(!html.contains("footnotes"));
+        assert!(!html.contains("↩"));
+        assert_eq!(html.matches("class=\"footnote-tooltip\"").count(), 2);
+        assert!(html.contains(r#"title="第一条，见 链接。""#));
+        assert!(html.contains(r#"<sup>2</sup><span class="footnote-content" role="tooltip">第二条 <em>强调</em>。</span>"#));
+        assert_eq!(html.matches("<style>").count(), 1);
+    }
+
+    #[test]
+    fn test_without_footnotes_unchanged() {
+        let html = "<p>没有脚注</p>";
+        assert_eq!(render_footnote_tooltips(html), html);
+    }
+}
@@ -0,0 +1,770 @@
+use crate::{
+    core::{
+        content::{Platform, PlatformOverrides},
+        language::{detect_language, Language},
+        text::tokenizer,
+        variants::parse_title_list,
+    },
+    error::Error,
+    Result,
+};
+use chrono::{DateTime, FixedOffset, SecondsFormat};
+use regex::Regex;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// 目录级默认 front matter 文件，同一目录中后面的优先。它们不是文章，批量处理时跳过
+pub const DIRECTORY_DEFAULTS_FILES: &[&str] = &["_index.yaml", "_defaults.md"];
+
+/// 只属于单篇文章、不从目录继承的字段
+const NON_INHERITED_KEYS: &[&str] = &[
+    "title",
+    "titles",
+    "slug",
+    "canonical_url",
+    "date",
+    "description",
+];
+
+/// 补全 front matter 时使用的值
+#[derive(Debug, Clone)]
+pub struct FrontMatterDefaults {
+    pub author: Option<String>,
+    pub date: DateTime<FixedOffset>,
+}
+
+/// 源文件中 front matter 的位置
+struct FrontMatterBlock<'a> {
+    /// `---` 之间的原始内容
+    body: &'a str,
+    /// 结束 `---` 所在行的起始位置
+    closing_start: usize,
+}
+
+fn find_front_matter(markdown: &str) -> Option<FrontMatterBlock<'_>> {
+    let rest = markdown
+        .strip_prefix("---\r\n")
+        .or_else(|| markdown.strip_prefix("---\n"))?;
+    let offset = markdown.len() - rest.len();
+
+    let mut position = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some(FrontMatterBlock {
+                body: &rest[..position],
+                closing_start: offset + position,
+            });
+        }
+        position += line.len();
+    }
+    None
+}
+
+/// 把源文件拆成 front matter（含首尾 `---` 行）和正文，没有 front matter 时前者为空
+pub fn split_front_matter(markdown: &str) -> (&str, &str) {
+    let Some(block) = find_front_matter(markdown) else {
+        return ("", markdown);
+    };
+    let end = markdown[block.closing_start..]
+        .find('\n')
+        .map(|i| block.closing_start + i + 1)
+        .unwrap_or(markdown.len());
+    markdown.split_at(end)
+}
+
+fn existing_keys(body: &str) -> Vec<String> {
+    body.lines()
+        .filter(|line| !line.starts_with([' ', '\t', '-', '#']))
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, _)| key.trim().to_string())
+        .collect()
+}
+
+/// 生成 URL 友好的 slug：按分词结果用 `-` 连接，ASCII 字母数字转小写，中文等字符保留
+pub fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    let mut pending_dash = false;
+    let mut previous_single = false;
+    for word in tokenizer().cut(text, true) {
+        // 连续的单字（如“我的”被切成“我/的”）不再拆开
+        let single = word.chars().count() == 1 && word.chars().all(|c| !c.is_ascii());
+        pending_dash = !(single && previous_single) || pending_dash;
+        previous_single = single;
+        for c in word.chars() {
+            if c.is_ascii_alphanumeric() || (!c.is_ascii() && c.is_alphanumeric()) {
+                if pending_dash && !slug.is_empty() {
+                    slug.push('-');
+                }
+                pending_dash = false;
+                slug.push(c.to_ascii_lowercase());
+            } else {
+                pending_dash = true;
+            }
+        }
+    }
+    slug
+}
+
+/// 生成只含 ASCII 的 slug，用作稳定的文件名和固定链接：中文按分词结果转为拼音，
+/// 如“Rust异步编程指南”为 `rust-yibu-biancheng-zhinan`
+pub fn pinyin_slug(text: &str) -> String {
+    slugify(text)
+        .split('-')
+        .map(|word| {
+            deunicode::deunicode(word)
+                .chars()
+                .filter(char::is_ascii_alphanumeric)
+                .collect::<String>()
+                .to_ascii_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// 取正文第一个普通段落作为描述，去掉常见的 Markdown 标记。
+/// 长度按语言限制（见 [`Language::description_max_chars`]），英文在词边界截断
+pub fn generate_description(body: &str, language: Language) -> Option<String> {
+    let mut paragraph = Vec::new();
+    let mut in_code = false;
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        if trimmed.is_empty() {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        if trimmed.starts_with(['#', '>', '|', '!', '<', '-', '*']) || trimmed.starts_with("$$") {
+            if !paragraph.is_empty() {
+                break;
+            }
+            continue;
+        }
+        paragraph.push(trimmed);
+    }
+
+    let text = strip_inline_markdown(&paragraph.join(" "));
+    if text.is_empty() {
+        return None;
+    }
+    let max_chars = language.description_max_chars();
+    if text.chars().count() <= max_chars {
+        return Some(text);
+    }
+    let mut truncated: String = text.chars().take(max_chars).collect();
+    if !language.is_cjk() {
+        if let Some(boundary) = truncated.rfind(char::is_whitespace) {
+            truncated.truncate(boundary);
+        }
+    }
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+fn strip_inline_markdown(text: &str) -> String {
+    static LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+    let link = LINK_REGEX.get_or_init(|| Regex::new(r"!?\[([^\]]*)\]\([^)]*\)").unwrap());
+    let text = link.replace_all(text, "$1");
+    text.chars()
+        .filter(|c| !matches!(c, '*' | '_' | '`' | '~'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn title_of(body: &str, keys_body: &str) -> Option<String> {
+    keys_body
+        .lines()
+        .find_map(|line| line.strip_prefix("title:"))
+        .map(|t| t.trim().trim_matches('"').to_string())
+        .filter(|t| !t.is_empty())
+        .or_else(|| {
+            body.lines()
+                .find_map(|line| line.strip_prefix("# "))
+                .map(|t| t.trim().to_string())
+        })
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "'"))
+}
+
+/// 解析 front matter（`---` 之间的 YAML）为字段表，值保留列表、嵌套映射、数字和布尔等类型。
+/// 日期按原文保留为字符串，由处理器按时区解析；YAML 格式错误时返回带行号的错误
+pub fn parse_front_matter_fields(yaml: &str) -> Result<HashMap<String, Value>> {
+    if yaml.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    let value: Value = serde_yaml::from_str(yaml)
+        .map_err(|e| Error::Validation(format!("front matter 不是有效的 YAML: {}", e)))?;
+    match value {
+        Value::Null => Ok(HashMap::new()),
+        Value::Object(fields) => Ok(fields.into_iter().collect()),
+        _ => Err(Error::Validation(
+            "front matter 必须是 `key: value` 形式的映射".to_string(),
+        )),
+    }
+}
+
+/// 源文件 front matter 中的字段，没有 front matter 时为空
+pub fn front_matter_fields(markdown: &str) -> Result<HashMap<String, Value>> {
+    find_front_matter(markdown)
+        .map(|block| parse_front_matter_fields(block.body))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// 文章是否为草稿：front matter（包括目录默认值）中 `draft: true`
+pub fn is_draft(source: &Path, markdown: &str) -> Result<bool> {
+    let mut fields = inherited_front_matter(source)?;
+    fields.extend(front_matter_fields(markdown)?);
+    Ok(FrontMatter::from_fields(&fields)?.draft)
+}
+
+/// front matter 中有固定含义的字段，其余字段保留在
+/// [`ContentMetadata::custom_fields`](crate::core::ContentMetadata::custom_fields)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontMatter {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// `tags: [a, b]`、块列表或逗号分隔的字符串
+    pub tags: Vec<String>,
+    pub cover: Option<String>,
+    /// 日期原文，由处理器按时区解析
+    pub date: Option<String>,
+    pub review_after: Option<String>,
+    pub titles: Vec<String>,
+    pub shift_headings: Option<String>,
+    pub lang: Option<String>,
+    pub slug: Option<String>,
+    pub canonical_url: Option<String>,
+    /// 草稿：批量处理和监控时跳过
+    pub draft: bool,
+    /// 按平台覆盖的字段：`wechat: { title: ... }` 或 `wechat.title: ...`
+    pub overrides: HashMap<String, PlatformOverrides>,
+}
+
+/// [`FrontMatter`] 中的固定字段名
+pub const FRONT_MATTER_KEYS: &[&str] = &[
+    "title",
+    "author",
+    "description",
+    "tags",
+    "cover",
+    "date",
+    "review_after",
+    "titles",
+    "shift_headings",
+    "lang",
+    "slug",
+    "canonical_url",
+    "draft",
+];
+
+impl FrontMatter {
+    /// 从字段表读取固定字段，类型不符（如 `draft: "yes"`、`title` 写成列表）时报错
+    pub fn from_fields(fields: &HashMap<String, Value>) -> Result<Self> {
+        let text = |key: &str| field_text(fields.get(key), key);
+        let mut front_matter = Self {
+            title: text("title")?,
+            author: text("author")?,
+            description: text("description")?,
+            tags: field_list(fields.get("tags"), "tags")?,
+            cover: text("cover")?,
+            date: text("date")?,
+            review_after: text("review_after")?,
+            titles: field_list(fields.get("titles"), "titles")?,
+            shift_headings: text("shift_headings")?,
+            lang: text("lang")?,
+            slug: text("slug")?,
+            canonical_url: text("canonical_url")?,
+            draft: match fields.get("draft") {
+                None | Some(Value::Null) => false,
+                Some(Value::Bool(draft)) => *draft,
+                Some(other) => return Err(invalid_field("draft", "true 或 false", other)),
+            },
+            overrides: HashMap::new(),
+        };
+
+        for (key, value) in fields {
+            if let Some((platform, field)) = override_key(key) {
+                let overrides = front_matter.overrides.entry(platform).or_default();
+                overrides.set(field, field_text(Some(value), key)?, key)?;
+            } else if let (Some(platform), Value::Object(map)) = (platform_key(key), value) {
+                let overrides = front_matter.overrides.entry(platform).or_default();
+                for (field, value) in map {
+                    let key = format!("{}.{}", key, field);
+                    overrides.set(field, field_text(Some(value), &key)?, &key)?;
+                }
+            }
+        }
+        Ok(front_matter)
+    }
+
+    /// 不属于固定字段和平台覆盖的字段
+    pub fn is_custom_field(key: &str, value: &Value) -> bool {
+        !FRONT_MATTER_KEYS.contains(&key)
+            && override_key(key).is_none()
+            && !(value.is_object() && platform_key(key).is_some())
+    }
+}
+
+/// 已注册的具体平台名（不含 `all`）
+fn platform_key(key: &str) -> Option<String> {
+    match key.parse::<Platform>() {
+        Ok(Platform::All) | Err(_) => None,
+        Ok(platform) => Some(platform.to_string()),
+    }
+}
+
+/// `wechat.title` 形式的平台覆盖字段，返回（平台, 字段）
+fn override_key(key: &str) -> Option<(String, &str)> {
+    let (platform, field) = key.split_once('.')?;
+    let platform = platform_key(platform)?;
+    PlatformOverrides::FIELDS
+        .contains(&field)
+        .then_some((platform, field))
+}
+
+fn field_text(value: Option<&Value>, key: &str) -> Result<Option<String>> {
+    match value {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(text)) => Ok(Some(text.clone())),
+        Some(value @ (Value::Number(_) | Value::Bool(_))) => Ok(Some(value.to_string())),
+        Some(other) => Err(invalid_field(key, "文本", other)),
+    }
+}
+
+fn field_list(value: Option<&Value>, key: &str) -> Result<Vec<String>> {
+    let items = match value {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::String(text)) => parse_title_list(text),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| field_text(Some(item), key))
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty())
+            .collect(),
+        Some(other) => return Err(invalid_field(key, "列表或逗号分隔的文本", other)),
+    };
+    Ok(items)
+}
+
+fn invalid_field(key: &str, expected: &str, value: &Value) -> Error {
+    Error::Validation(format!(
+        "front matter 字段 {} 应为{}，实际为 {}",
+        key, expected, value
+    ))
+}
+
+/// front matter 值的文本形式，数组按 `, ` 连接；`null` 返回 `None`
+pub fn front_matter_text(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::String(s) => Some(s.clone()),
+        Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(front_matter_text)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        other => Some(other.to_string()),
+    }
+}
+
+pub fn is_directory_defaults_file(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| DIRECTORY_DEFAULTS_FILES.contains(&n))
+}
+
+/// 文章从所在目录及上级目录的 `_index.yaml`、`_defaults.md` 继承的 front matter，
+/// 近的目录覆盖远的目录；向上查找到 git 仓库根目录为止。
+///
+/// `title`、`date`、`slug` 等只属于单篇文章的字段不继承；文章自身的字段始终优先，
+/// 合并由 [`MarkdownProcessor::with_inherited_front_matter`](crate::core::MarkdownProcessor::with_inherited_front_matter) 完成
+pub fn inherited_front_matter(source: &Path) -> Result<HashMap<String, Value>> {
+    let source = std::fs::canonicalize(source).unwrap_or_else(|_| source.to_path_buf());
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for dir in source.ancestors().skip(1) {
+        dirs.push(dir.to_path_buf());
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+
+    let mut fields = HashMap::new();
+    for dir in dirs.iter().rev() {
+        for name in DIRECTORY_DEFAULTS_FILES {
+            let path = dir.join(name);
+            if !path.is_file() {
+                continue;
+            }
+            let text = std::fs::read_to_string(&path)?;
+            let yaml = if name.ends_with(".md") {
+                find_front_matter(&text)
+                    .map(|block| block.body)
+                    .unwrap_or("")
+            } else {
+                text.as_str()
+            };
+            let parsed = parse_front_matter_fields(yaml)
+                .map_err(|e| Error::Validation(format!("{:?}: {}", path, e)))?;
+            fields.extend(
+                parsed
+                    .into_iter()
+                    .filter(|(key, _)| !NON_INHERITED_KEYS.contains(&key.as_str())),
+            );
+        }
+    }
+    Ok(fields)
+}
+
+/// 补全缺失的 front matter 字段（date、author、description、slug），返回新内容和补充的字段名。
+///
+/// 只在 front matter 末尾追加新行，已有字段和正文保持原样；没有 front matter 时在文件开头新建。
+pub fn fix_front_matter(markdown: &str, defaults: &FrontMatterDefaults) -> (String, Vec<String>) {
+    let newline = if markdown.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let block = find_front_matter(markdown);
+
+    let (keys_body, body) = match &block {
+        Some(block) => {
+            let after_closing = markdown[block.closing_start..]
+                .split_once('\n')
+                .map(|(_, rest)| rest)
+                .unwrap_or("");
+            (block.body, after_closing)
+        }
+        None => ("", markdown),
+    };
+    let keys = existing_keys(keys_body);
+    let has = |key: &str| keys.iter().any(|k| k == key);
+
+    let mut additions: Vec<(String, String)> = Vec::new();
+    if !has("date") {
+        additions.push((
+            "date".to_string(),
+            defaults.date.to_rfc3339_opts(SecondsFormat::Secs, false),
+        ));
+    }
+    if !has("author") {
+        if let Some(author) = &defaults.author {
+            additions.push(("author".to_string(), quote(author)));
+        }
+    }
+    if !has("description") {
+        let language = parse_front_matter_fields(keys_body)
+            .unwrap_or_default()
+            .get("lang")
+            .and_then(front_matter_text)
+            .and_then(|lang| lang.parse().ok())
+            .unwrap_or_else(|| detect_language(body));
+        if let Some(description) = generate_description(body, language) {
+            additions.push(("description".to_string(), quote(&description)));
+        }
+    }
+    if !has("slug") {
+        if let Some(slug) = title_of(body, keys_body).map(|t| pinyin_slug(&t)) {
+            if !slug.is_empty() {
+                additions.push(("slug".to_string(), slug));
+            }
+        }
+    }
+
+    if additions.is_empty() {
+        return (markdown.to_string(), Vec::new());
+    }
+
+    let lines: String = additions
+        .iter()
+        .map(|(key, value)| format!("{}: {}{}", key, value, newline))
+        .collect();
+    let added = additions.into_iter().map(|(key, _)| key).collect();
+
+    let fixed = match block {
+        Some(block) => {
+            let mut fixed = String::with_capacity(markdown.len() + lines.len());
+            fixed.push_str(&markdown[..block.closing_start]);
+            if !block.body.is_empty() && !block.body.ends_with('\n') {
+                fixed.push_str(newline);
+            }
+            fixed.push_str(&lines);
+            fixed.push_str(&markdown[block.closing_start..]);
+            fixed
+        }
+        None => format!("---{nl}{}---{nl}{nl}{}", lines, markdown, nl = newline),
+    };
+
+    (fixed, added)
+}
+
+/// 把 `fields` 合并到 front matter 的嵌套映射 `<section>.<name>` 中（如 `published.wechat`）：
+/// 已有的 `section` 映射保留其他条目，只替换这一段；其余字段、注释和正文保持原样。
+/// 没有 front matter 时在文件开头新建
+pub fn merge_front_matter_mapping(
+    markdown: &str,
+    section: &str,
+    name: &str,
+    fields: &[(&str, String)],
+) -> Result<String> {
+    let newline = if markdown.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let block = find_front_matter(markdown);
+    let yaml = block.as_ref().map_or("", |block| block.body);
+
+    let existing: serde_yaml::Value = if yaml.trim().is_empty() {
+        serde_yaml::Value::Null
+    } else {
+        serde_yaml::from_str(yaml)
+            .map_err(|e| Error::Validation(format!("front matter 不是有效的 YAML: {}", e)))?
+    };
+    let mut merged = match existing.get(section) {
+        Some(serde_yaml::Value::Mapping(mapping)) => mapping.clone(),
+        _ => serde_yaml::Mapping::new(),
+    };
+    let mut entry = match merged.get(name) {
+        Some(serde_yaml::Value::Mapping(mapping)) => mapping.clone(),
+        _ => serde_yaml::Mapping::new(),
+    };
+    for (key, value) in fields {
+        entry.insert((*key).into(), value.as_str().into());
+    }
+    merged.insert(name.into(), serde_yaml::Value::Mapping(entry));
+
+    let mut root = serde_yaml::Mapping::new();
+    root.insert(section.into(), serde_yaml::Value::Mapping(merged));
+    let replacement = serde_yaml::to_string(&root)
+        .map_err(|e| Error::Other(format!("front matter 序列化失败: {}", e)))?
+        .replace('\n', newline);
+
+    let Some(block) = block else {
+        return Ok(format!(
+            "---{nl}{}---{nl}{nl}{}",
+            replacement,
+            markdown,
+            nl = newline
+        ));
+    };
+
+    // 替换 `section:` 行及其后缩进的内容，其他行原样保留
+    let body_start = block.closing_start - block.body.len();
+    let mut body = String::with_capacity(block.body.len() + replacement.len());
+    let mut replaced = false;
+    let mut in_section = false;
+    for line in block.body.split_inclusive('\n') {
+        if in_section && line.starts_with([' ', '\t', '-']) {
+            continue;
+        }
+        in_section = false;
+        let key = line
+            .split_once(':')
+            .filter(|_| !line.starts_with([' ', '\t', '-', '#']))
+            .map(|(key, _)| key.trim());
+        if key == Some(section) && !replaced {
+            body.push_str(&replacement);
+            replaced = true;
+            in_section = true;
+        } else {
+            body.push_str(line);
+        }
+    }
+    if !replaced {
+        if !body.is_empty() && !body.ends_with('\n') {
+            body.push_str(newline);
+        }
+        body.push_str(&replacement);
+    }
+
+    Ok(format!(
+        "{}{}{}",
+        &markdown[..body_start],
+        body,
+        &markdown[block.closing_start..]
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> FrontMatterDefaults {
+        FrontMatterDefaults {
+            author: Some("张三".to_string()),
+            date: DateTime::parse_from_rfc3339("2024-05-01T08:00:00+08:00").unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_fill_missing_fields_preserving_existing() {
+        let markdown = "---\ntitle: Hello World\ntags: a, b   # 注释保留\n---\n\n# Hello World\n\n这是**第一段**，包含[链接](https://example.com)。\n\n第二段\n";
+        let (fixed, added) = fix_front_matter(markdown, &defaults());
+
+        assert_eq!(added, vec!["date", "author", "description", "slug"]);
+        assert_eq!(
+            fixed,
+            "---\ntitle: Hello World\ntags: a, b   # 注释保留\ndate: 2024-05-01T08:00:00+08:00\nauthor: \"张三\"\ndescription: \"这是第一段，包含链接。\"\nslug: hello-world\n---\n\n# Hello World\n\n这是**第一段**，包含[链接](https://example.com)。\n\n第二段\n"
+        );
+
+        // 再次运行不会重复添加
+        let (again, added) = fix_front_matter(&fixed, &defaults());
+        assert!(added.is_empty());
+        assert_eq!(again, fixed);
+    }
+
+    #[test]
+    fn test_create_front_matter_with_crlf() {
+        let markdown = "# 我的 文章\r\n\r\n正文内容\r\n";
+        let (fixed, added) = fix_front_matter(markdown, &defaults());
+        assert_eq!(added.len(), 4);
+        assert!(fixed.starts_with("---\r\ndate: "));
+        assert!(fixed.contains("slug: wode-wenzhang\r\n---\r\n\r\n# 我的 文章"));
+    }
+
+    #[test]
+    fn test_slug_and_description() {
+        assert_eq!(slugify("Rust 入门：所有权!"), "rust-入门-所有权");
+        assert_eq!(slugify("  --Hello, World--  "), "hello-world");
+        assert_eq!(slugify("Rust异步编程指南"), "rust-异步-编程-指南");
+        assert_eq!(
+            pinyin_slug("Rust异步编程指南"),
+            "rust-yibu-biancheng-zhinan"
+        );
+        assert_eq!(pinyin_slug("Hello, World"), "hello-world");
+
+        let long = "字".repeat(200);
+        let description = generate_description(&long, Language::Zh).unwrap();
+        assert_eq!(
+            description.chars().count(),
+            Language::Zh.description_max_chars() + 1
+        );
+        assert!(generate_description("# 只有标题\n\n```\ncode\n```\n", Language::Zh).is_none());
+
+        // 英文摘要更长，并在词边界截断
+        let long = "word ".repeat(100);
+        let description = generate_description(&long, Language::En).unwrap();
+        assert!(description.ends_with("word…"));
+        assert!(description.chars().count() <= Language::En.description_max_chars() + 1);
+    }
+
+    #[test]
+    fn test_inherited_front_matter() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("series/part1")).unwrap();
+        std::fs::write(root.join("_index.yaml"), "author: 编辑部\ntheme: light\n").unwrap();
+        std::fs::write(
+            root.join("series/_defaults.md"),
+            "---\nauthor: 张三\ntags: rust, 系列\ntitle: 不继承\n---\n说明文字\n",
+        )
+        .unwrap();
+        let article = root.join("series/part1/intro.md");
+        std::fs::write(&article, "# 第一篇").unwrap();
+
+        let fields = inherited_front_matter(&article).unwrap();
+        assert_eq!(fields["author"], "张三");
+        assert_eq!(fields["tags"], "rust, 系列");
+        assert_eq!(fields["theme"], "light");
+        assert!(!fields.contains_key("title"));
+        assert!(is_directory_defaults_file(
+            &root.join("series/_defaults.md")
+        ));
+    }
+
+    #[test]
+    fn test_typed_front_matter() {
+        let fields = parse_front_matter_fields(
+            "title: \"Rust: 入门\"\ntags:\n  - rust\n  - 异步\ndate: 2024-07-01\ndraft: true\ncover: cover.png\ndescription: |\n  第一行\n  第二行\nwechat:\n  title: 公众号标题\nzhihu.cover: zhihu.png\nseries:\n  name: rust\n  part: 2\n",
+        )
+        .unwrap();
+        let front_matter = FrontMatter::from_fields(&fields).unwrap();
+        assert_eq!(front_matter.title.as_deref(), Some("Rust: 入门"));
+        assert_eq!(front_matter.tags, vec!["rust", "异步"]);
+        assert_eq!(front_matter.date.as_deref(), Some("2024-07-01"));
+        assert_eq!(
+            front_matter.description.as_deref(),
+            Some("第一行\n第二行\n")
+        );
+        assert!(front_matter.draft);
+        assert_eq!(
+            front_matter.overrides["wechat"].title.as_deref(),
+            Some("公众号标题")
+        );
+        assert_eq!(
+            front_matter.overrides["zhihu"].cover.as_deref(),
+            Some("zhihu.png")
+        );
+        assert!(FrontMatter::is_custom_field("series", &fields["series"]));
+        assert!(!FrontMatter::is_custom_field("wechat", &fields["wechat"]));
+        assert_eq!(fields["series"]["part"], 2);
+
+        // 逗号分隔的标签仍然可用
+        let fields = parse_front_matter_fields("tags: rust, 系列").unwrap();
+        assert_eq!(
+            FrontMatter::from_fields(&fields).unwrap().tags,
+            vec!["rust", "系列"]
+        );
+    }
+
+    #[test]
+    fn test_invalid_front_matter() {
+        let err = parse_front_matter_fields("title: [未闭合\ntags: a").unwrap_err();
+        assert!(err.to_string().contains("front matter 不是有效的 YAML"));
+        assert!(parse_front_matter_fields("- 只是列表").is_err());
+
+        let fields = parse_front_matter_fields("draft: \"yes\"").unwrap();
+        let err = FrontMatter::from_fields(&fields).unwrap_err();
+        assert!(err.to_string().contains("draft 应为true 或 false"));
+        let fields = parse_front_matter_fields("wechat:\n  column: 技术").unwrap();
+        assert!(FrontMatter::from_fields(&fields)
+            .unwrap_err()
+            .to_string()
+            .contains("wechat.column 不支持按平台覆盖"));
+
+        let processed = crate::core::MarkdownProcessor::new().process("---\ntitle: [a\n---\n正文");
+        assert!(processed.is_err());
+        assert!(front_matter_fields("没有 front matter").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_merge_front_matter_mapping() {
+        let fields = [
+            ("url", "https://mp.weixin.qq.com/s/abc".to_string()),
+            ("date", "2025-03-01T10:00:00+08:00".to_string()),
+        ];
+        let markdown = "---\ntitle: 你好 # 注释保留\npublished:\n  zhihu:\n    url: https://zhuanlan.zhihu.com/p/1\n  wechat:\n    url: https://old\ntags: [a]\n---\n\n正文\n";
+        let updated = merge_front_matter_mapping(markdown, "published", "wechat", &fields).unwrap();
+        assert_eq!(
+            updated,
+            "---\ntitle: 你好 # 注释保留\npublished:\n  zhihu:\n    url: https://zhuanlan.zhihu.com/p/1\n  wechat:\n    url: https://mp.weixin.qq.com/s/abc\n    date: 2025-03-01T10:00:00+08:00\ntags: [a]\n---\n\n正文\n"
+        );
+
+        let updated = merge_front_matter_mapping("正文\n", "published", "wechat", &fields).unwrap();
+        let published = &front_matter_fields(&updated).unwrap()["published"];
+        assert_eq!(published["wechat"]["url"], "https://mp.weixin.qq.com/s/abc");
+        assert!(updated.ends_with("---\n\n正文\n"));
+    }
+}
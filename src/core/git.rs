@@ -0,0 +1,143 @@
+use crate::{core::frontmatter::is_directory_defaults_file, error::Error, Result};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown"];
+
+fn run_git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .map_err(|e| Error::Other(format!("无法执行 git（请确认已安装）: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(Error::Other(format!(
+            "git {} 失败: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+        MARKDOWN_EXTENSIONS
+            .iter()
+            .any(|m| e.eq_ignore_ascii_case(m))
+    })
+}
+
+/// 列出 `dir` 下自 `since`（任意 git 引用，如 `HEAD~1`、`origin/main`）以来新增或修改的
+/// Markdown 文件，包括工作区中尚未提交的修改和未跟踪的新文件。已删除的文件不会返回。
+pub fn changed_markdown_files(dir: &Path, since: &str) -> Result<Vec<PathBuf>> {
+    let top_level = PathBuf::from(run_git(dir, &["rev-parse", "--show-toplevel"])?.trim());
+
+    // 先确认引用存在，给出比 git diff 更清楚的错误
+    run_git(
+        dir,
+        &[
+            "rev-parse",
+            "--verify",
+            "--quiet",
+            &format!("{}^{{commit}}", since),
+        ],
+    )
+    .map_err(|_| Error::Other(format!("无效的 git 引用: {}", since)))?;
+
+    let diff = run_git(
+        dir,
+        &[
+            "diff",
+            "--name-only",
+            "--diff-filter=ACMR",
+            "-z",
+            since,
+            "--",
+            ".",
+        ],
+    )?;
+    let untracked = run_git(
+        dir,
+        &[
+            "ls-files",
+            "--others",
+            "--exclude-standard",
+            "--full-name",
+            "-z",
+            "--",
+            ".",
+        ],
+    )?;
+
+    let mut files: Vec<PathBuf> = diff
+        .split('\0')
+        .chain(untracked.split('\0'))
+        .filter(|name| !name.is_empty())
+        .map(|name| top_level.join(name))
+        .filter(|path| is_markdown(path) && !is_directory_defaults_file(path) && path.is_file())
+        .collect();
+    files.sort();
+    files.dedup();
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=markflow",
+                "-c",
+                "user.email=markflow@example.com",
+            ])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(status.status.success(), "{:?}", status);
+    }
+
+    #[test]
+    fn test_changed_markdown_files() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        git(root, &["init", "-q"]);
+        std::fs::write(root.join("old.md"), "# 旧文章").unwrap();
+        std::fs::write(root.join("keep.md"), "# 不变").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "init"]);
+
+        std::fs::write(root.join("old.md"), "# 旧文章（修改）").unwrap();
+        std::fs::create_dir(root.join("posts")).unwrap();
+        std::fs::write(root.join("posts/new.md"), "# 新文章").unwrap();
+        std::fs::write(root.join("notes.txt"), "不是 Markdown").unwrap();
+        git(root, &["add", "."]);
+        git(root, &["commit", "-q", "-m", "update"]);
+        std::fs::write(root.join("draft.markdown"), "# 未跟踪").unwrap();
+
+        let names: Vec<String> = changed_markdown_files(root, "HEAD~1")
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, vec!["draft.markdown", "old.md", "new.md"]);
+
+        // 只统计子目录
+        let posts = changed_markdown_files(&root.join("posts"), "HEAD~1").unwrap();
+        assert_eq!(posts.len(), 1);
+
+        assert!(changed_markdown_files(root, "no-such-ref").is_err());
+    }
+}
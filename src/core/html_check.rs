@@ -0,0 +1,189 @@
+use html5ever::tokenizer::{
+    states::RawKind, BufferQueue, Tag, TagKind, Token, TokenSink, TokenSinkResult, Tokenizer,
+    TokenizerOpts,
+};
+use std::fmt;
+
+/// 没有结束标签的空元素
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// 出现时会让浏览器隐式关闭 `<p>` 的块级元素
+const BLOCK_ELEMENTS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "details",
+    "div",
+    "dl",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "table",
+    "ul",
+];
+
+/// 最终 HTML 中的一处结构问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HtmlIssue {
+    /// 从 1 开始的行号
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for HtmlIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "第 {} 行 {}", self.line, self.message)
+    }
+}
+
+#[derive(Default)]
+struct CheckSink {
+    /// 尚未关闭的元素：(标签名, 行号)
+    open: Vec<(String, usize)>,
+    issues: Vec<HtmlIssue>,
+}
+
+impl CheckSink {
+    fn issue(&mut self, line: usize, message: String) {
+        self.issues.push(HtmlIssue { line, message });
+    }
+
+    fn start_tag(&mut self, tag: &Tag, line: usize) -> TokenSinkResult<()> {
+        let name = tag.name.to_string();
+        if VOID_ELEMENTS.contains(&name.as_str()) {
+            return TokenSinkResult::Continue;
+        }
+        if tag.self_closing {
+            // HTML 中非空元素的 `/>` 会被忽略，元素实际上没有关闭
+            self.issue(line, format!("<{}/> 不是空元素，自闭合写法无效", name));
+            return TokenSinkResult::Continue;
+        }
+        if BLOCK_ELEMENTS.contains(&name.as_str()) {
+            if let Some((_, p_line)) = self.open.iter().rev().find(|(n, _)| n == "p") {
+                let message = format!(
+                    "<p>（第 {} 行）中不能包含 <{}>，浏览器会提前结束段落",
+                    p_line, name
+                );
+                self.issue(line, message);
+            }
+        }
+        if name == "a" && self.open.iter().any(|(n, _)| n == "a") {
+            self.issue(line, "<a> 不能嵌套在另一个 <a> 中".to_string());
+        }
+        if name == "li" && !matches!(self.open.last(), Some((n, _)) if n == "ul" || n == "ol") {
+            self.issue(line, "<li> 不在 <ul> 或 <ol> 中".to_string());
+        }
+        self.open.push((name.clone(), line));
+        match name.as_str() {
+            "script" => TokenSinkResult::RawData(RawKind::ScriptData),
+            "style" => TokenSinkResult::RawData(RawKind::Rawtext),
+            "textarea" | "title" => TokenSinkResult::RawData(RawKind::Rcdata),
+            _ => TokenSinkResult::Continue,
+        }
+    }
+
+    fn end_tag(&mut self, tag: &Tag, line: usize) {
+        let name = tag.name.to_string();
+        if VOID_ELEMENTS.contains(&name.as_str()) {
+            self.issue(line, format!("空元素不需要结束标签 </{}>", name));
+            return;
+        }
+        let Some(index) = self.open.iter().rposition(|(n, _)| n == &name) else {
+            self.issue(line, format!("多余的结束标签 </{}>", name));
+            return;
+        };
+        for (inner, inner_line) in self.open.split_off(index + 1) {
+            self.issue(
+                line,
+                format!(
+                    "</{}> 之前 <{}>（第 {} 行）没有关闭，嵌套错误",
+                    name, inner, inner_line
+                ),
+            );
+        }
+        self.open.pop();
+    }
+}
+
+impl TokenSink for CheckSink {
+    type Handle = ();
+
+    fn process_token(&mut self, token: Token, line_number: u64) -> TokenSinkResult<()> {
+        let line = line_number as usize;
+        match token {
+            Token::TagToken(tag) => match tag.kind {
+                TagKind::StartTag => return self.start_tag(&tag, line),
+                TagKind::EndTag => self.end_tag(&tag, line),
+            },
+            Token::ParseError(error) => self.issue(line, format!("语法错误: {}", error)),
+            Token::EOFToken => {
+                for (name, open_line) in std::mem::take(&mut self.open) {
+                    self.issue(open_line, format!("<{}> 没有关闭", name));
+                }
+            }
+            _ => {}
+        }
+        TokenSinkResult::Continue
+    }
+}
+
+/// 检查 HTML 片段的结构：未关闭或多余的标签、交错嵌套、`<p>` 中的块级元素、
+/// 嵌套链接等。正则改写容易留下这类问题，平台编辑器会按自己的方式“修复”，导致排版错乱
+pub fn check_html(html: &str) -> Vec<HtmlIssue> {
+    let mut tokenizer = Tokenizer::new(CheckSink::default(), TokenizerOpts::default());
+    let mut input = BufferQueue::new();
+    input.push_back(html.into());
+    let _ = tokenizer.feed(&mut input);
+    tokenizer.end();
+
+    let mut issues = tokenizer.sink.issues;
+    issues.sort_by_key(|issue| issue.line);
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_html() {
+        let html = "<section><p>文字<br><img src=\"a.png\"><a href=\"#\">链接</a></p>\n<ul><li>项</li></ul>\n<script>if (a < b) {}</script></section>";
+        assert_eq!(check_html(html), Vec::new());
+    }
+
+    #[test]
+    fn test_structural_issues() {
+        let html = "<p>段落<div>块</div></p>\n<b><i>交错</b></i>\n<li>孤立</li>\n<span>未关闭";
+        let messages: Vec<String> = check_html(html).iter().map(|i| i.to_string()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "第 1 行 <p>（第 1 行）中不能包含 <div>，浏览器会提前结束段落",
+                "第 2 行 </b> 之前 <i>（第 2 行）没有关闭，嵌套错误",
+                "第 2 行 多余的结束标签 </i>",
+                "第 3 行 <li> 不在 <ul> 或 <ol> 中",
+                "第 4 行 <span> 没有关闭",
+            ]
+        );
+    }
+}
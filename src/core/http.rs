@@ -0,0 +1,285 @@
+use crate::{cli::args::NetworkConfig, error::Error, Result};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+
+/// 当前进程使用的网络配置，启动时由 CLI 根据配置文件设置
+static NETWORK_CONFIG: RwLock<Option<NetworkConfig>> = RwLock::new(None);
+
+/// 所有请求使用的 User-Agent，带上版本号便于对方定位问题
+pub const USER_AGENT: &str = concat!(
+    "markflow/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/foxzool/markflow)"
+);
+
+/// 连接池中每个主机保留的空闲连接数
+const POOL_MAX_IDLE_PER_HOST: usize = 8;
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// 按全局网络配置创建的共享客户端，键为操作类型（`None` 表示不限制请求超时）。
+/// `reqwest::Client` 内部共享连接池，克隆后在多个任务中并发使用
+static SHARED_CLIENTS: Mutex<Option<HashMap<Option<NetworkOperation>, reqwest::Client>>> =
+    Mutex::new(None);
+
+/// 设置全局网络配置，之后通过 [`http_client`] 创建的客户端都会使用它
+pub fn configure_network(config: NetworkConfig) {
+    *NETWORK_CONFIG.write().unwrap_or_else(|e| e.into_inner()) = Some(config);
+    // 配置变化后重新创建客户端
+    *SHARED_CLIENTS.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn network_config() -> NetworkConfig {
+    NETWORK_CONFIG
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .clone()
+        .unwrap_or_default()
+}
+
+/// 是否处于离线模式（`--offline` 或 `network.offline = true`）
+pub fn is_offline() -> bool {
+    network_config().offline
+}
+
+/// 离线模式下返回错误，用于必须联网的操作（如发布）提前失败
+pub fn ensure_online(action: &str) -> Result<()> {
+    if is_offline() {
+        return Err(offline_error(action));
+    }
+    Ok(())
+}
+
+fn offline_error(action: &str) -> Error {
+    Error::Network(format!(
+        "离线模式下无法{}（去掉 --offline 或设置 network.offline = false）",
+        action
+    ))
+}
+
+/// 网络操作类型，各自使用 `[network]` 中配置的超时时间
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NetworkOperation {
+    LinkCheck,
+    Download,
+    Publish,
+    Llm,
+}
+
+impl NetworkOperation {
+    pub fn timeout(self, config: &NetworkConfig) -> Duration {
+        Duration::from_secs(match self {
+            Self::LinkCheck => config.link_check_timeout_secs,
+            Self::Download => config.download_timeout_secs,
+            Self::Publish => config.publish_timeout_secs,
+            Self::Llm => config.llm_timeout_secs,
+        })
+    }
+}
+
+/// 按全局网络配置（代理、额外根证书、连接超时）获取共享的 HTTP 客户端，
+/// 同一进程中的发布器、链接检查和图片下载复用同一个连接池
+pub fn http_client() -> Result<reqwest::Client> {
+    shared_client(None)
+}
+
+/// 与 [`http_client`] 相同，并按操作类型设置整个请求的超时时间
+pub fn http_client_for(operation: NetworkOperation) -> Result<reqwest::Client> {
+    shared_client(Some(operation))
+}
+
+/// 使用指定的网络配置获取客户端：与全局配置相同时复用共享客户端，否则单独创建
+pub fn client_for(config: &NetworkConfig, operation: NetworkOperation) -> Result<reqwest::Client> {
+    if *config == network_config() {
+        http_client_for(operation)
+    } else {
+        build_client_for(config, operation)
+    }
+}
+
+fn shared_client(operation: Option<NetworkOperation>) -> Result<reqwest::Client> {
+    let config = network_config();
+    // 离线模式每次都返回错误，不缓存
+    if config.offline {
+        return Err(offline_error("发起网络请求"));
+    }
+
+    let mut clients = SHARED_CLIENTS.lock().unwrap_or_else(|e| e.into_inner());
+    let clients = clients.get_or_insert_with(HashMap::new);
+    if let Some(client) = clients.get(&operation) {
+        return Ok(client.clone());
+    }
+    let client = match operation {
+        Some(operation) => build_client_for(&config, operation)?,
+        None => build_client(&config)?,
+    };
+    clients.insert(operation, client.clone());
+    Ok(client)
+}
+
+pub fn build_client(config: &NetworkConfig) -> Result<reqwest::Client> {
+    Ok(client_builder(config)?.build()?)
+}
+
+pub fn build_client_for(
+    config: &NetworkConfig,
+    operation: NetworkOperation,
+) -> Result<reqwest::Client> {
+    Ok(client_builder(config)?
+        .timeout(operation.timeout(config))
+        .build()?)
+}
+
+fn client_builder(config: &NetworkConfig) -> Result<reqwest::ClientBuilder> {
+    if config.offline {
+        return Err(offline_error("发起网络请求"));
+    }
+
+    let mut builder = reqwest::Client::builder()
+        .user_agent(USER_AGENT)
+        .gzip(true)
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs));
+
+    if let Some(proxy) = config.proxy.as_deref().filter(|p| !p.is_empty()) {
+        let no_proxy = reqwest::NoProxy::from_string(&config.no_proxy.join(","));
+        let proxy = reqwest::Proxy::all(proxy)
+            .map_err(|e| Error::Config(format!("无效的代理地址 {}: {}", proxy, e)))?
+            .no_proxy(no_proxy);
+        builder = builder.proxy(proxy);
+    }
+
+    for path in &config.ca_certs {
+        let pem = std::fs::read(path)
+            .map_err(|e| Error::Config(format!("读取根证书失败 {:?}: {}", path, e)))?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem)
+            .map_err(|e| Error::Config(format!("根证书格式无效 {:?}: {}", path, e)))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// 并发执行 `f`，同时进行的任务不超过 `limit` 个，结果按输入顺序返回
+pub async fn map_concurrent<T, R, F, Fut>(items: Vec<T>, limit: usize, f: F) -> Vec<R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = R> + Send + 'static,
+{
+    let semaphore = Arc::new(Semaphore::new(limit.max(1)));
+    let mut tasks = JoinSet::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let semaphore = semaphore.clone();
+        let task = f(item);
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            (i, task.await)
+        });
+    }
+
+    let mut results = Vec::with_capacity(tasks.len());
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok(result) => results.push(result),
+            Err(e) => std::panic::resume_unwind(e.into_panic()),
+        }
+    }
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn test_build_client_with_proxy() {
+        let config = NetworkConfig {
+            proxy: Some("socks5://127.0.0.1:1080".to_string()),
+            no_proxy: vec!["localhost".to_string()],
+            ..NetworkConfig::default()
+        };
+        assert!(build_client(&config).is_ok());
+        assert!(build_client_for(&config, NetworkOperation::LinkCheck).is_ok());
+
+        let invalid = NetworkConfig {
+            proxy: Some("not a url".to_string()),
+            ..NetworkConfig::default()
+        };
+        assert!(matches!(build_client(&invalid), Err(Error::Config(_))));
+
+        let missing_ca = NetworkConfig {
+            ca_certs: vec!["/nonexistent/ca.pem".into()],
+            ..NetworkConfig::default()
+        };
+        assert!(matches!(build_client(&missing_ca), Err(Error::Config(_))));
+
+        let offline = NetworkConfig {
+            offline: true,
+            ..NetworkConfig::default()
+        };
+        let err = build_client_for(&offline, NetworkOperation::Download).unwrap_err();
+        assert!(err.to_string().contains("离线模式"));
+    }
+
+    #[tokio::test]
+    async fn test_shared_client_sends_user_agent() {
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::get(|headers: axum::http::HeaderMap| async move {
+                headers
+                    .get(axum::http::header::USER_AGENT)
+                    .and_then(|v| v.to_str().ok())
+                    .unwrap_or_default()
+                    .to_string()
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client =
+            build_client_for(&NetworkConfig::default(), NetworkOperation::Download).unwrap();
+        let agent = client
+            .get(format!("http://{}/", addr))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        assert_eq!(agent, USER_AGENT);
+        assert!(agent.contains(env!("CARGO_PKG_VERSION")));
+    }
+
+    #[tokio::test]
+    async fn test_map_concurrent_limit_and_order() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let results = map_concurrent((0..10).collect(), 3, |i: u64| {
+            let running = running.clone();
+            let peak = peak.clone();
+            async move {
+                let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(10 - i)).await;
+                running.fetch_sub(1, Ordering::SeqCst);
+                i * 2
+            }
+        })
+        .await;
+
+        assert_eq!(results, (0..10).map(|i| i * 2).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+}
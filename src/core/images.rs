@@ -0,0 +1,314 @@
+use crate::{
+    cli::args::ImagesConfig,
+    core::{
+        article_assets::ArticleAssets,
+        content::Content,
+        http::{http_client_for, is_offline, NetworkOperation},
+        output::img_src_regex,
+        pipeline::ProcessingStage,
+        store::content_hash,
+    },
+    error::Error,
+    Result,
+};
+use async_trait::async_trait;
+use image::{codecs::jpeg::JpegEncoder, imageops::FilterType, ImageOutputFormat};
+use regex::{Captures, Regex};
+use std::{
+    collections::HashMap,
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// Markdown 图片语法 `![alt](src ...)`：捕获组依次为 `![alt](` 和地址
+fn markdown_image_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(!\[[^\]]*\]\()(<[^>]+>|[^)\s]+)").unwrap())
+}
+
+/// 图片本地化阶段：按 `[images]` 配置下载网络图片、缩小过宽的图片，
+/// 并把 Markdown 和 HTML 中的地址改为本地缓存文件。
+///
+/// 改写后的地址是缓存文件的绝对路径，输出时随文章复制到 `assets/`，
+/// 发布时由平台的 [`ImageUploader`](crate::publishers::ImageUploader) 上传并替换为平台地址。
+/// 默认配置下不做任何改动；单张图片下载或解码失败只记录警告并保留原地址
+pub struct ImageProcessingStage {
+    config: ImagesConfig,
+    assets: Option<ArticleAssets>,
+}
+
+impl ImageProcessingStage {
+    pub fn new(config: ImagesConfig) -> Self {
+        Self {
+            config,
+            assets: None,
+        }
+    }
+
+    /// 本地图片按文章所在目录查找（未设置时相对当前目录）
+    pub fn with_assets(mut self, assets: ArticleAssets) -> Self {
+        self.assets = Some(assets);
+        self
+    }
+
+    fn enabled(&self) -> bool {
+        self.config.download_remote || self.config.max_width > 0
+    }
+
+    /// 图片需要改用的本地文件；保持原地址时返回 `None`
+    async fn localize(&self, client: &reqwest::Client, src: &str) -> Result<Option<PathBuf>> {
+        let remote = src.starts_with("http://") || src.starts_with("https://");
+        let path = if remote {
+            if !self.config.download_remote || is_offline() {
+                return Ok(None);
+            }
+            self.download(client, src).await?
+        } else {
+            let resolved = match &self.assets {
+                Some(assets) => assets.resolve(src),
+                None => Some(PathBuf::from(src)).filter(|path| path.is_file()),
+            };
+            match resolved {
+                Some(path) => path,
+                None => return Ok(None),
+            }
+        };
+
+        match self.resize(&path)? {
+            Some(resized) => Ok(Some(resized)),
+            None => Ok(remote.then_some(path)),
+        }
+    }
+
+    /// 下载到缓存目录，文件名取地址的哈希，已下载过的图片不再重复下载
+    async fn download(&self, client: &reqwest::Client, url: &str) -> Result<PathBuf> {
+        let path = self.config.cache_dir.join(format!(
+            "{}.{}",
+            &content_hash(url.as_bytes())[..16],
+            extension(url).unwrap_or("img")
+        ));
+        if path.is_file() {
+            return Ok(path);
+        }
+        tracing::info!("下载图片: {}", url);
+        let data = client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        std::fs::create_dir_all(&self.config.cache_dir)?;
+        std::fs::write(&path, &data)?;
+        Ok(path)
+    }
+
+    /// 宽度超过 `max_width` 的 PNG/JPEG 按比例缩小后写入缓存，返回缩小后的文件
+    fn resize(&self, path: &Path) -> Result<Option<PathBuf>> {
+        let max_width = self.config.max_width;
+        let jpeg = match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase())
+            .as_deref()
+        {
+            _ if max_width == 0 => return Ok(None),
+            Some("jpg" | "jpeg") => true,
+            Some("png") => false,
+            // GIF 动图、SVG、WebP 等保持原样
+            _ => return Ok(None),
+        };
+
+        let data = std::fs::read(path)?;
+        let target = self.config.cache_dir.join(format!(
+            "{}-w{}-q{}.{}",
+            &content_hash(&data)[..16],
+            max_width,
+            self.config.quality,
+            if jpeg { "jpg" } else { "png" }
+        ));
+        if target.is_file() {
+            return Ok(Some(target));
+        }
+
+        let image = image::load_from_memory(&data)
+            .map_err(|e| Error::Other(format!("无法解码图片 {:?}: {}", path, e)))?;
+        if image.width() <= max_width {
+            return Ok(None);
+        }
+        let resized = image.resize(max_width, u32::MAX, FilterType::Lanczos3);
+        let mut encoded = Vec::new();
+        let written = if jpeg {
+            JpegEncoder::new_with_quality(&mut encoded, self.config.quality)
+                .encode_image(&resized.to_rgb8())
+        } else {
+            resized.write_to(&mut Cursor::new(&mut encoded), ImageOutputFormat::Png)
+        };
+        written.map_err(|e| Error::Other(format!("无法编码图片 {:?}: {}", path, e)))?;
+
+        std::fs::create_dir_all(&self.config.cache_dir)?;
+        std::fs::write(&target, encoded)?;
+        tracing::debug!(
+            "已把 {:?} 从 {}px 缩小到 {}px",
+            path,
+            image.width(),
+            max_width
+        );
+        Ok(Some(target))
+    }
+}
+
+fn extension(url: &str) -> Option<&str> {
+    let path = url.split(['?', '#']).next()?;
+    let name = path.rsplit('/').next()?;
+    let (_, extension) = name.rsplit_once('.')?;
+    (!extension.is_empty() && extension.len() <= 5 && extension.chars().all(char::is_alphanumeric))
+        .then_some(extension)
+}
+
+/// Markdown 中的图片地址（去掉尖括号）
+fn markdown_src(raw: &str) -> &str {
+    raw.strip_prefix('<')
+        .and_then(|s| s.strip_suffix('>'))
+        .unwrap_or(raw)
+}
+
+#[async_trait]
+impl ProcessingStage for ImageProcessingStage {
+    async fn process(&self, content: &mut Content) -> Result<()> {
+        if !self.enabled() {
+            return Ok(());
+        }
+
+        let mut sources: Vec<String> = markdown_image_regex()
+            .captures_iter(&content.markdown)
+            .map(|caps| markdown_src(&caps[2]).to_string())
+            .collect();
+        sources.extend(
+            img_src_regex()
+                .captures_iter(&content.html)
+                .map(|caps| html_escape::decode_html_entities(&caps[2]).to_string()),
+        );
+        sources.sort();
+        sources.dedup();
+
+        let client = http_client_for(NetworkOperation::Download)?;
+        let mut replacements = HashMap::new();
+        for src in sources {
+            match self.localize(&client, &src).await {
+                Ok(Some(path)) => {
+                    replacements.insert(src, path.to_string_lossy().to_string());
+                }
+                Ok(None) => {}
+                Err(e) => tracing::warn!("处理图片 {} 失败，保留原地址: {}", src, e),
+            }
+        }
+        if replacements.is_empty() {
+            return Ok(());
+        }
+
+        content.markdown = markdown_image_regex()
+            .replace_all(&content.markdown, |caps: &Captures| {
+                match replacements.get(markdown_src(&caps[2])) {
+                    Some(path) if path.contains(char::is_whitespace) => {
+                        format!("{}<{}>", &caps[1], path)
+                    }
+                    Some(path) => format!("{}{}", &caps[1], path),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned();
+        content.html = img_src_regex()
+            .replace_all(&content.html, |caps: &Captures| {
+                let src = html_escape::decode_html_entities(&caps[2]);
+                match replacements.get(src.as_ref()) {
+                    Some(path) => format!(
+                        "{}{}{}",
+                        &caps[1],
+                        html_escape::encode_double_quoted_attribute(path),
+                        &caps[3]
+                    ),
+                    None => caps[0].to_string(),
+                }
+            })
+            .into_owned();
+        tracing::info!("已本地化 {} 张图片", replacements.len());
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "图片处理"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbImage};
+
+    #[tokio::test]
+    async fn test_resize_and_rewrite_local_images() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("post.md");
+        let mut png = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(1200, 600))
+            .write_to(&mut Cursor::new(&mut png), ImageOutputFormat::Png)
+            .unwrap();
+        std::fs::write(dir.path().join("wide.png"), &png).unwrap();
+        let mut small = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(100, 50))
+            .write_to(&mut Cursor::new(&mut small), ImageOutputFormat::Png)
+            .unwrap();
+        std::fs::write(dir.path().join("small.png"), &small).unwrap();
+
+        let config = ImagesConfig {
+            max_width: 600,
+            cache_dir: dir.path().join("cache"),
+            ..Default::default()
+        };
+        let stage =
+            ImageProcessingStage::new(config).with_assets(ArticleAssets::for_source(&source));
+        let mut content = Content::new(
+            "图片".to_string(),
+            "![宽图](wide.png)\n\n![小图](small.png)".to_string(),
+        );
+        content.html =
+            r#"<p><img src="wide.png" alt="宽图"></p><p><img src="small.png" alt="小图"></p>"#
+                .to_string();
+        stage.process(&mut content).await.unwrap();
+
+        let resized = std::fs::read_dir(dir.path().join("cache"))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+            .path();
+        let resized_src = resized.to_string_lossy().to_string();
+        assert_eq!(image::open(&resized).unwrap().width(), 600);
+        assert!(content
+            .markdown
+            .contains(&format!("![宽图]({})", resized_src)));
+        assert!(content.markdown.contains("![小图](small.png)"));
+        assert!(content
+            .html
+            .contains(&format!(r#"<img src="{}""#, resized_src)));
+        assert!(content.html.contains(r#"<img src="small.png""#));
+    }
+
+    #[tokio::test]
+    async fn test_disabled_by_default() {
+        let stage = ImageProcessingStage::new(ImagesConfig::default());
+        let mut content = Content::new(
+            "图片".to_string(),
+            "![远程](https://example.com/a.png)".to_string(),
+        );
+        content.html = r#"<img src="https://example.com/a.png">"#.to_string();
+        let before = content.clone();
+        stage.process(&mut content).await.unwrap();
+        assert_eq!(content.markdown, before.markdown);
+        assert_eq!(content.html, before.html);
+        assert_eq!(extension("https://example.com/a.JPG?x=1"), Some("JPG"));
+        assert_eq!(extension("https://example.com/image"), None);
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{core::typography::SmartPunctuation, error::Error, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 文章的主要语言，决定阅读速度、摘要长度和智能标点等处理参数。
+/// front matter `lang:` 优先，未设置时按正文检测
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Zh,
+    Ja,
+    Ko,
+    En,
+}
+
+impl FromStr for Language {
+    type Err = Error;
+
+    /// 接受 `zh`、`zh-CN`、`ja_JP`、`en-US` 等语言标签，只看主语言部分
+    fn from_str(s: &str) -> Result<Self> {
+        let primary = s
+            .trim()
+            .split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match primary.as_str() {
+            "zh" => Ok(Self::Zh),
+            "ja" => Ok(Self::Ja),
+            "ko" => Ok(Self::Ko),
+            "en" => Ok(Self::En),
+            _ => Err(Error::Config(format!(
+                "不支持的语言: {}（可选: zh、ja、ko、en）",
+                s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F)
+}
+
+fn is_kana(c: char) -> bool {
+    matches!(c as u32, 0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F)
+}
+
+fn is_hangul(c: char) -> bool {
+    matches!(c as u32, 0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF)
+}
+
+/// 文本中各类文字的数量：汉字、假名、谚文按字计，拉丁文按词计
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScriptCounts {
+    pub han: usize,
+    pub kana: usize,
+    pub hangul: usize,
+    pub latin_words: usize,
+}
+
+impl ScriptCounts {
+    pub fn of(text: &str) -> Self {
+        let mut counts = Self::default();
+        let mut in_word = false;
+        for c in text.chars() {
+            let latin = c.is_ascii_alphabetic()
+                || (c.is_alphabetic() && (c as u32) < 0x0250 && !c.is_ascii());
+            if latin && !in_word {
+                counts.latin_words += 1;
+            }
+            in_word = latin || (in_word && (c == '\'' || c == '-'));
+            if is_han(c) {
+                counts.han += 1;
+            } else if is_kana(c) {
+                counts.kana += 1;
+            } else if is_hangul(c) {
+                counts.hangul += 1;
+            }
+        }
+        counts
+    }
+
+    /// 字数：中日韩文字按字、拉丁文按词
+    pub fn words(&self) -> usize {
+        self.han + self.kana + self.hangul + self.latin_words
+    }
+}
+
+/// 检测正文的主要语言：中日韩文字的字数多于拉丁文词数时按文字判断，
+/// 其中出现假名的是日文、谚文为主的是韩文，否则为中文；其余情况为英文
+pub fn detect_language(text: &str) -> Language {
+    let counts = ScriptCounts::of(text);
+    let cjk = counts.han + counts.kana + counts.hangul;
+    if cjk == 0 || cjk < counts.latin_words {
+        return Language::En;
+    }
+    if counts.hangul * 2 > cjk {
+        Language::Ko
+    } else if counts.kana * 10 >= cjk {
+        Language::Ja
+    } else {
+        Language::Zh
+    }
+}
+
+impl Language {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Zh => "zh",
+            Self::Ja => "ja",
+            Self::Ko => "ko",
+            Self::En => "en",
+        }
+    }
+
+    pub fn is_cjk(&self) -> bool {
+        !matches!(self, Self::En)
+    }
+
+    /// 每分钟阅读的字数（与 [`ScriptCounts::words`] 的计数方式一致）
+    pub fn reading_speed(&self) -> u32 {
+        match self {
+            Self::Zh => 300,
+            Self::Ja => 400,
+            Self::Ko => 500,
+            Self::En => 230,
+        }
+    }
+
+    /// 自动生成的摘要最多保留的字符数
+    pub fn description_max_chars(&self) -> usize {
+        match self {
+            Self::En => 200,
+            _ => 120,
+        }
+    }
+
+    /// 按语言调整智能标点：默认的 `latin` 是为中英混排准备的，英文文章直接全文替换；
+    /// 显式配置的 `off`、`all` 保持不变
+    pub fn smart_punctuation(&self, configured: SmartPunctuation) -> SmartPunctuation {
+        match (self, configured) {
+            (Self::En, SmartPunctuation::Latin) => SmartPunctuation::All,
+            (_, mode) => mode,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language("这是一篇介绍 Rust 异步编程的文章。"),
+            Language::Zh
+        );
+        assert_eq!(detect_language("これは Rust の記事です。"), Language::Ja);
+        assert_eq!(detect_language("이것은 러스트 글입니다."), Language::Ko);
+        assert_eq!(
+            detect_language("An introduction to async Rust, with 少量中文."),
+            Language::En
+        );
+        assert_eq!("zh-CN".parse::<Language>().unwrap(), Language::Zh);
+        assert_eq!("en_US".parse::<Language>().unwrap(), Language::En);
+        assert!("fr".parse::<Language>().is_err());
+
+        assert_eq!(ScriptCounts::of("Rust 异步编程 don't panic").words(), 7);
+        assert_eq!(
+            Language::En.smart_punctuation(SmartPunctuation::Latin),
+            SmartPunctuation::All
+        );
+        assert_eq!(
+            Language::Zh.smart_punctuation(SmartPunctuation::Latin),
+            SmartPunctuation::Latin
+        );
+    }
+}
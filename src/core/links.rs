@@ -0,0 +1,653 @@
+use crate::{
+    cli::args::{LinkRewriteRule, LinksConfig, NetworkConfig, UtmConfig},
+    core::{
+        content::{Content, Platform},
+        http::{client_for, map_concurrent, NetworkOperation},
+        lock::write_atomic,
+        pipeline::PlatformStage,
+        report::PlatformReport,
+    },
+    error::Error,
+    Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
+use url::Url;
+
+/// 对 HTML 中每个 `<a href>` 的地址调用 `rewrite`，返回 `Some` 时替换为新地址
+pub fn rewrite_links<F>(html: &str, mut rewrite: F) -> String
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    static HREF_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = HREF_REGEX.get_or_init(|| Regex::new(r#"(<a\s[^>]*?\bhref=")([^"]*)""#).unwrap());
+
+    regex
+        .replace_all(html, |caps: &regex::Captures| {
+            let href = html_escape::decode_html_entities(&caps[2]);
+            match rewrite(&href) {
+                Some(new_href) => format!(
+                    "{}{}\"",
+                    &caps[1],
+                    html_escape::encode_double_quoted_attribute(&new_href)
+                ),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// 常见的跟踪参数，比较链接是否相同时忽略
+const TRACKING_PARAMS: &[&str] = &[
+    "spm",
+    "fbclid",
+    "gclid",
+    "from",
+    "scene",
+    "isappinstalled",
+    "share_token",
+    "utm_id",
+];
+
+/// 规范化链接用于去重：去掉跟踪参数（utm_* 等）、默认端口和路径末尾的斜杠，主机名小写。
+/// 无法解析的地址原样返回
+pub fn canonical_url(href: &str) -> String {
+    let Ok(mut url) = Url::parse(href) else {
+        return href.to_string();
+    };
+    if !matches!(url.scheme(), "http" | "https") {
+        return href.to_string();
+    }
+
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_ref()))
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    if pairs.is_empty() {
+        url.set_query(None);
+    } else {
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+
+    let path = url.path().trim_end_matches('/').to_string();
+    url.set_path(if path.is_empty() { "/" } else { &path });
+    url.to_string()
+}
+
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    platforms: Vec<String>,
+}
+
+/// 按用户配置的正则规则改写链接，例如换成带推广参数的地址或内部短域名跳转
+pub struct LinkRewriteStage {
+    rules: Vec<CompiledRule>,
+}
+
+impl LinkRewriteStage {
+    pub fn new(rules: &[LinkRewriteRule]) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern).map_err(|e| {
+                    Error::Config(format!("链接改写规则无效 {}: {}", rule.pattern, e))
+                })?;
+                Ok(CompiledRule {
+                    regex,
+                    replacement: rule.replacement.clone(),
+                    platforms: rule.platforms.iter().map(|p| p.to_lowercase()).collect(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules })
+    }
+
+    /// 依次应用对该平台生效的规则，地址没有变化时返回 `None`
+    pub fn rewrite_url(&self, href: &str, platform: &str) -> Option<String> {
+        let mut url = href.to_string();
+        for rule in &self.rules {
+            if !rule.platforms.is_empty() && !rule.platforms.iter().any(|p| p == platform) {
+                continue;
+            }
+            url = rule
+                .regex
+                .replace(&url, rule.replacement.as_str())
+                .into_owned();
+        }
+        (url != href).then_some(url)
+    }
+}
+
+impl PlatformStage for LinkRewriteStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        let platform = platform.to_string();
+        let mut rewritten = Vec::new();
+        content.html = rewrite_links(&content.html, |href| {
+            let url = self.rewrite_url(href, &platform)?;
+            rewritten.push(format!("{} -> {}", href, url));
+            Some(url)
+        });
+        report.rewritten_links.extend(rewritten);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "链接改写"
+    }
+}
+
+/// 为外部链接追加 UTM 参数，`utm_source` 为目标平台，便于统计各平台带来的流量
+pub struct UtmStage {
+    config: UtmConfig,
+}
+
+impl UtmStage {
+    pub fn new(config: UtmConfig) -> Self {
+        Self { config }
+    }
+
+    fn is_excluded(&self, url: &Url) -> bool {
+        let Some(host) = url.host_str() else {
+            return true;
+        };
+        self.config.exclude_domains.iter().any(|domain| {
+            let domain = domain.trim_start_matches('.');
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+    }
+
+    /// 给单个地址追加参数；非 http(s)、已带 UTM 参数或被排除的地址返回 `None`
+    pub fn tag_url(&self, href: &str, source: &str, campaign: Option<&str>) -> Option<String> {
+        let mut url = Url::parse(href).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || self.is_excluded(&url) {
+            return None;
+        }
+        if url.query_pairs().any(|(key, _)| key.starts_with("utm_")) {
+            return None;
+        }
+
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("utm_source", source);
+            query.append_pair("utm_medium", &self.config.medium);
+            if let Some(campaign) = campaign {
+                query.append_pair("utm_campaign", campaign);
+            }
+        }
+        Some(url.to_string())
+    }
+}
+
+impl PlatformStage for UtmStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        let platform_name = platform.to_string();
+        let source = self
+            .config
+            .sources
+            .get(&platform_name)
+            .cloned()
+            .unwrap_or(platform_name);
+        let campaign = content
+            .metadata
+            .custom_text("campaign")
+            .or_else(|| self.config.campaign.clone());
+
+        let mut tagged = 0;
+        content.html = rewrite_links(&content.html, |href| {
+            let url = self.tag_url(href, &source, campaign.as_deref());
+            tagged += url.is_some() as usize;
+            url
+        });
+
+        if tagged > 0 {
+            report
+                .rewritten_links
+                .push(format!("{} 个外部链接追加 UTM 参数", tagged));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "UTM 参数"
+    }
+}
+
+/// 检查失败的链接
+#[derive(Debug, Clone)]
+pub struct BrokenLink {
+    pub url: String,
+    pub reason: String,
+}
+
+/// 一次链接检查的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LinkCheckEntry {
+    checked_at: DateTime<Utc>,
+    /// 失败原因，可访问时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+}
+
+/// 链接检查结果缓存（地址 → 结果），监控模式下反复处理同一篇文章时不重复请求外部网站
+pub struct LinkCheckCache {
+    path: Option<PathBuf>,
+    entries: BTreeMap<String, LinkCheckEntry>,
+}
+
+impl LinkCheckCache {
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            entries: BTreeMap::new(),
+        }
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .map_err(|e| Error::Config(format!("链接检查缓存解析失败 {:?}: {}", path, e)))?,
+            Err(_) => BTreeMap::new(),
+        };
+        Ok(Self {
+            path: Some(path),
+            entries,
+        })
+    }
+
+    /// 有效期内的结果：`Some(None)` 表示可访问，`Some(Some(原因))` 表示失效
+    fn get(&self, url: &str, ttl: Duration, now: DateTime<Utc>) -> Option<Option<String>> {
+        self.entries
+            .get(url)
+            .filter(|entry| now - entry.checked_at < ttl)
+            .map(|entry| entry.reason.clone())
+    }
+
+    fn insert(&mut self, url: String, reason: Option<String>, now: DateTime<Utc>) {
+        self.entries.insert(
+            url,
+            LinkCheckEntry {
+                checked_at: now,
+                reason,
+            },
+        );
+    }
+
+    /// 保存时丢弃已过期的结果，避免缓存文件无限增长
+    fn save(&mut self, ttl: Duration, now: DateTime<Utc>) -> Result<()> {
+        self.entries.retain(|_, entry| now - entry.checked_at < ttl);
+        match &self.path {
+            Some(path) => write_atomic(path, serde_json::to_string_pretty(&self.entries)?),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 检查 HTML 中的外部链接是否可访问，超时和并发数取自 `[network]`
+pub struct LinkChecker {
+    client: reqwest::Client,
+    max_concurrency: usize,
+    cache: Option<(Mutex<LinkCheckCache>, Duration)>,
+    get_only_domains: Vec<String>,
+}
+
+impl LinkChecker {
+    pub fn new(client: reqwest::Client, max_concurrency: usize) -> Self {
+        Self {
+            client,
+            max_concurrency,
+            cache: None,
+            get_only_domains: Vec::new(),
+        }
+    }
+
+    pub fn from_network(config: &NetworkConfig) -> Result<Self> {
+        Ok(Self::new(
+            client_for(config, NetworkOperation::LinkCheck)?,
+            config.max_concurrency,
+        ))
+    }
+
+    /// 按 `[links]` 设置缓存和只用 GET 检查的网站
+    pub fn from_config(links: &LinksConfig, network: &NetworkConfig) -> Result<Self> {
+        let mut checker =
+            Self::from_network(network)?.with_get_only_domains(links.get_only_domains.clone());
+        if links.cache_ttl_hours > 0 {
+            checker = checker.with_cache(
+                LinkCheckCache::open(&links.cache_file)?,
+                Duration::hours(links.cache_ttl_hours as i64),
+            );
+        }
+        Ok(checker)
+    }
+
+    /// 有效期 `ttl` 内检查过的地址直接使用缓存的结果
+    pub fn with_cache(mut self, cache: LinkCheckCache, ttl: Duration) -> Self {
+        self.cache = Some((Mutex::new(cache), ttl));
+        self
+    }
+
+    /// 这些网站（含子域名）拒绝 HEAD 请求，直接用 GET 检查
+    pub fn with_get_only_domains(mut self, domains: Vec<String>) -> Self {
+        self.get_only_domains = domains;
+        self
+    }
+
+    fn get_only(&self, url: &str) -> bool {
+        let Some(host) = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(str::to_lowercase))
+        else {
+            return false;
+        };
+        self.get_only_domains.iter().any(|domain| {
+            let domain = domain.trim_start_matches('.').to_lowercase();
+            host == domain || host.ends_with(&format!(".{}", domain))
+        })
+    }
+
+    pub async fn check_html(&self, html: &str) -> Vec<BrokenLink> {
+        let mut urls: Vec<String> = Vec::new();
+        rewrite_links(html, |href| {
+            if (href.starts_with("http://") || href.starts_with("https://"))
+                && !urls.iter().any(|u| u == href)
+            {
+                urls.push(href.to_string());
+            }
+            None
+        });
+
+        let now = Utc::now();
+        let mut results: BTreeMap<String, Option<String>> = BTreeMap::new();
+        if let Some((cache, ttl)) = &self.cache {
+            let cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+            for url in &urls {
+                if let Some(reason) = cache.get(url, *ttl, now) {
+                    results.insert(url.clone(), reason);
+                }
+            }
+        }
+
+        let pending: Vec<(String, bool)> = urls
+            .iter()
+            .filter(|url| !results.contains_key(*url))
+            .map(|url| (url.clone(), self.get_only(url)))
+            .collect();
+        let client = self.client.clone();
+        let checked = map_concurrent(pending, self.max_concurrency, move |(url, get_only)| {
+            let client = client.clone();
+            async move {
+                let reason = check_link(&client, &url, get_only).await;
+                (url, reason)
+            }
+        })
+        .await;
+
+        if let Some((cache, ttl)) = &self.cache {
+            let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+            for (url, reason) in &checked {
+                cache.insert(url.clone(), reason.clone(), now);
+            }
+            if let Err(e) = cache.save(*ttl, now) {
+                tracing::warn!("保存链接检查缓存失败: {}", e);
+            }
+        }
+        results.extend(checked);
+
+        // 按链接在文中出现的顺序返回
+        urls.into_iter()
+            .filter_map(|url| {
+                let reason = results.remove(&url).flatten()?;
+                Some(BrokenLink { url, reason })
+            })
+            .collect()
+    }
+}
+
+/// 返回失败原因，链接可访问时返回 None。部分网站不支持 HEAD，此时改用 GET 重试；
+/// `get_only` 时直接用 GET
+async fn check_link(client: &reqwest::Client, url: &str, get_only: bool) -> Option<String> {
+    let describe = |e: reqwest::Error| {
+        if e.is_timeout() {
+            "请求超时".to_string()
+        } else {
+            e.to_string()
+        }
+    };
+
+    let request = if get_only {
+        client.get(url)
+    } else {
+        client.head(url)
+    };
+    let mut response = request.send().await.map_err(describe);
+    if !get_only && matches!(&response, Ok(r) if matches!(r.status().as_u16(), 403 | 405 | 501)) {
+        response = client.get(url).send().await.map_err(describe);
+    }
+    match response {
+        Ok(r) if r.status().is_client_error() || r.status().is_server_error() => {
+            Some(format!("HTTP {}", r.status().as_u16()))
+        }
+        Ok(_) => None,
+        Err(reason) => Some(reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stage() -> UtmStage {
+        UtmStage::new(UtmConfig {
+            enabled: true,
+            campaign: Some("default".to_string()),
+            exclude_domains: vec!["example.org".to_string()],
+            ..UtmConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_rewrite_links_decodes_entities() {
+        let html =
+            r#"<p><a href="https://a.com/?x=1&amp;y=2">a</a><a title="t" href="/local">b</a></p>"#;
+        let result = rewrite_links(html, |href| {
+            assert!(!href.contains("&amp;"));
+            href.starts_with("https").then(|| format!("{}&z=3", href))
+        });
+        assert_eq!(
+            result,
+            r#"<p><a href="https://a.com/?x=1&amp;y=2&amp;z=3">a</a><a title="t" href="/local">b</a></p>"#
+        );
+    }
+
+    #[test]
+    fn test_utm_per_platform_with_front_matter_campaign() {
+        let mut content = Content::new("标题".to_string(), String::new());
+        content.html = r##"<a href="https://blog.rs/post">a</a> <a href="https://docs.example.org/">b</a> <a href="https://x.com/?utm_source=me">c</a> <a href="#top">d</a>"##.to_string();
+        content
+            .metadata
+            .custom_fields
+            .insert("campaign".to_string(), "rust 专题".into());
+
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        stage()
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+
+        assert!(content.html.contains(
+            "https://blog.rs/post?utm_source=zhihu&amp;utm_medium=article&amp;utm_campaign=rust+%E4%B8%93%E9%A2%98"
+        ));
+        assert!(content.html.contains(r#"href="https://docs.example.org/""#));
+        assert!(content
+            .html
+            .contains(r#"href="https://x.com/?utm_source=me""#));
+        assert!(content.html.contains(r##"href="#top""##));
+        assert_eq!(report.rewritten_links, vec!["1 个外部链接追加 UTM 参数"]);
+    }
+
+    #[test]
+    fn test_rewrite_rules_per_platform() {
+        let stage = LinkRewriteStage::new(&[
+            LinkRewriteRule {
+                pattern: r"^https://www\.amazon\.com/dp/(\w+).*$".to_string(),
+                replacement: "https://www.amazon.com/dp/$1?tag=markflow-20".to_string(),
+                platforms: Vec::new(),
+            },
+            LinkRewriteRule {
+                pattern: r"^https://blog\.example\.com/".to_string(),
+                replacement: "https://s.example.com/".to_string(),
+                platforms: vec!["WeChat".to_string()],
+            },
+        ])
+        .unwrap();
+
+        let mut content = Content::new("标题".to_string(), String::new());
+        content.html = r#"<a href="https://www.amazon.com/dp/B01ABC?ref=x">书</a> <a href="https://blog.example.com/post">文</a>"#.to_string();
+
+        let mut wechat = content.clone();
+        let mut report = PlatformReport::new(Platform::WeChat);
+        stage
+            .process(&mut wechat, &Platform::WeChat, &mut report)
+            .unwrap();
+        assert!(wechat
+            .html
+            .contains(r#"href="https://www.amazon.com/dp/B01ABC?tag=markflow-20""#));
+        assert!(wechat.html.contains(r#"href="https://s.example.com/post""#));
+        assert_eq!(report.rewritten_links.len(), 2);
+
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        stage
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+        assert!(content
+            .html
+            .contains(r#"href="https://blog.example.com/post""#));
+        assert_eq!(report.rewritten_links.len(), 1);
+
+        let invalid = LinkRewriteRule {
+            pattern: "(".to_string(),
+            replacement: String::new(),
+            platforms: Vec::new(),
+        };
+        assert!(LinkRewriteStage::new(&[invalid]).is_err());
+    }
+
+    #[test]
+    fn test_utm_default_campaign() {
+        let url = stage().tag_url("http://a.com", "wechat", Some("default"));
+        assert_eq!(
+            url.as_deref(),
+            Some("http://a.com/?utm_source=wechat&utm_medium=article&utm_campaign=default")
+        );
+        assert_eq!(stage().tag_url("mailto:a@b.com", "wechat", None), None);
+    }
+
+    #[tokio::test]
+    async fn test_link_checker() {
+        use axum::{http::StatusCode, routing::get, Router};
+        use std::time::Duration;
+
+        let app = Router::new()
+            .route("/ok", get(|| async { "ok" }))
+            .route(
+                "/slow",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    "slow"
+                }),
+            )
+            .route("/gone", get(|| async { StatusCode::NOT_FOUND }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(500))
+            .build()
+            .unwrap();
+        let html = format!(
+            r#"<a href="http://{0}/ok">a</a><a href="http://{0}/gone">b</a><a href="http://{0}/slow">c</a><a href="/local">d</a>"#,
+            addr
+        );
+        let broken = LinkChecker::new(client, 2).check_html(&html).await;
+
+        assert_eq!(broken.len(), 2);
+        assert!(broken[0].url.ends_with("/gone"));
+        assert_eq!(broken[0].reason, "HTTP 404");
+        assert_eq!(broken[1].reason, "请求超时");
+    }
+
+    #[tokio::test]
+    async fn test_link_checker_cache() {
+        use axum::{http::StatusCode, routing::get, Router};
+        use std::sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        };
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/gone",
+            get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { StatusCode::NOT_FOUND }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let dir = tempfile::tempdir().unwrap();
+        let cache_file = dir.path().join("links.json");
+        let html = format!(r#"<a href="http://{}/gone">a</a>"#, addr);
+        let checker = || {
+            LinkChecker::new(reqwest::Client::new(), 2)
+                .with_cache(
+                    LinkCheckCache::open(&cache_file).unwrap(),
+                    Duration::hours(1),
+                )
+                .with_get_only_domains(vec!["127.0.0.1".to_string()])
+        };
+
+        assert_eq!(checker().check_html(&html).await[0].reason, "HTTP 404");
+        // 第二次从缓存文件中读取结果，不再请求
+        assert_eq!(checker().check_html(&html).await[0].reason, "HTTP 404");
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let checker = checker().with_get_only_domains(vec!["example.com".to_string()]);
+        assert!(checker.get_only("https://www.example.com/a"));
+        assert!(!checker.get_only("https://notexample.com/a"));
+    }
+
+    #[test]
+    fn test_canonical_url() {
+        assert_eq!(
+            canonical_url("https://Example.com:443/a/?utm_source=wechat&id=1&spm=x"),
+            "https://example.com/a?id=1"
+        );
+        assert_eq!(
+            canonical_url("https://example.com/a"),
+            canonical_url("https://example.com/a/?from=timeline")
+        );
+        assert_eq!(canonical_url("mailto:a@b.c"), "mailto:a@b.c");
+    }
+}
@@ -0,0 +1,243 @@
+use crate::{
+    cli::args::LintConfig,
+    core::{
+        terminology::Terminology,
+        text::{keyword_density, plain_text, tokenizer, words, KeywordStat},
+    },
+    Result,
+};
+use jieba_rs::Jieba;
+use std::{cell::OnceCell, fmt};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintSeverity::Info => write!(f, "info"),
+            LintSeverity::Warning => write!(f, "warning"),
+            LintSeverity::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// 一条 lint 结果
+#[derive(Debug, Clone)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    pub message: String,
+    pub severity: LintSeverity,
+    /// 问题所在行（从 1 开始），无法定位时为 None
+    pub line: Option<usize>,
+}
+
+/// 规则检查时可用的上下文，正文文本和分词结果只在首次使用时计算
+pub struct LintContext<'a> {
+    markdown: &'a str,
+    text: OnceCell<String>,
+    words: OnceCell<Vec<String>>,
+}
+
+impl<'a> LintContext<'a> {
+    pub fn new(markdown: &'a str) -> Self {
+        Self {
+            markdown,
+            text: OnceCell::new(),
+            words: OnceCell::new(),
+        }
+    }
+
+    pub fn markdown(&self) -> &'a str {
+        self.markdown
+    }
+
+    /// 去掉代码、HTML 和链接地址后的正文
+    pub fn text(&self) -> &str {
+        self.text.get_or_init(|| plain_text(self.markdown))
+    }
+
+    /// 正文分词结果（已去掉标点、停用词）
+    pub fn words(&self) -> &[String] {
+        self.words.get_or_init(|| words(self.text()))
+    }
+
+    /// 共享的中文分词器，供需要自行切词的规则使用
+    pub fn tokenizer(&self) -> &'static Jieba {
+        tokenizer()
+    }
+
+    pub fn keyword_density(&self, limit: usize) -> Vec<KeywordStat> {
+        keyword_density(self.markdown, limit)
+    }
+}
+
+pub trait LintRule: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn check(&self, context: &LintContext) -> Vec<LintFinding>;
+}
+
+/// 检查过度重复的词：出现次数和占比同时超过阈值时给出警告
+pub struct RepeatedWordsRule {
+    pub min_count: usize,
+    /// 占比上限（0-1）
+    pub max_density: f64,
+    pub ignore_words: Vec<String>,
+}
+
+impl RepeatedWordsRule {
+    pub fn from_config(config: &LintConfig) -> Self {
+        Self {
+            min_count: config.repeated_word_min_count,
+            max_density: config.repeated_word_max_density / 100.0,
+            ignore_words: config
+                .ignore_words
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect(),
+        }
+    }
+}
+
+impl LintRule for RepeatedWordsRule {
+    fn name(&self) -> &'static str {
+        "repeated-words"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintFinding> {
+        context
+            .keyword_density(usize::MAX)
+            .into_iter()
+            .filter(|s| s.count >= self.min_count && s.density > self.max_density)
+            .filter(|s| !self.ignore_words.contains(&s.word))
+            .map(|s| LintFinding {
+                rule: self.name(),
+                message: format!(
+                    "“{}” 出现 {} 次，占全文 {:.1}%，考虑换用同义词",
+                    s.word,
+                    s.count,
+                    s.density * 100.0
+                ),
+                severity: LintSeverity::Warning,
+                line: None,
+            })
+            .collect()
+    }
+}
+
+/// 检查术语写法是否与词典一致（如 Github -> GitHub）
+pub struct TerminologyRule {
+    terminology: Terminology,
+}
+
+impl TerminologyRule {
+    pub fn new(terminology: Terminology) -> Self {
+        Self { terminology }
+    }
+}
+
+impl LintRule for TerminologyRule {
+    fn name(&self) -> &'static str {
+        "terminology"
+    }
+
+    fn check(&self, context: &LintContext) -> Vec<LintFinding> {
+        self.terminology
+            .check_markdown(context.markdown())
+            .into_iter()
+            .map(|issue| LintFinding {
+                rule: self.name(),
+                message: format!("“{}” 应写作 “{}”", issue.wrong, issue.preferred),
+                severity: LintSeverity::Warning,
+                line: Some(issue.line),
+            })
+            .collect()
+    }
+}
+
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+}
+
+impl Linter {
+    pub fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub fn add_rule<T: LintRule + 'static>(mut self, rule: T) -> Self {
+        self.rules.push(Box::new(rule));
+        self
+    }
+
+    /// 按配置启用内置规则
+    pub fn from_config(config: &LintConfig) -> Result<Self> {
+        let terminology = Terminology::from_config(&config.terminology)?;
+        Ok(Self::new()
+            .add_rule(RepeatedWordsRule::from_config(config))
+            .add_rule(TerminologyRule::new(terminology)))
+    }
+
+    pub fn lint(&self, markdown: &str) -> Vec<LintFinding> {
+        let context = LintContext::new(markdown);
+        let mut findings: Vec<LintFinding> = self
+            .rules
+            .iter()
+            .flat_map(|rule| rule.check(&context))
+            .collect();
+        findings.sort_by_key(|f| (f.line.unwrap_or(0), std::cmp::Reverse(f.severity)));
+        findings
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        let config = LintConfig::default();
+        Self::new()
+            .add_rule(RepeatedWordsRule::from_config(&config))
+            .add_rule(TerminologyRule::new(Terminology::builtin()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_words_rule() {
+        let markdown = "性能很重要。我们优化性能，测量性能，再比较性能，最后记录性能数据。\n";
+        let findings = Linter::default().lint(markdown);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "repeated-words");
+        assert!(findings[0].message.contains("“性能” 出现 5 次"));
+
+        let config = LintConfig {
+            ignore_words: vec!["性能".to_string()],
+            ..LintConfig::default()
+        };
+        assert!(Linter::from_config(&config)
+            .unwrap()
+            .lint(markdown)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_terminology_findings_have_lines() {
+        let findings = Linter::default().lint("# 标题\n\n托管在 Github 上\n");
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, "terminology");
+        assert_eq!(findings[0].line, Some(3));
+        assert!(findings[0].message.contains("GitHub"));
+    }
+
+    #[test]
+    fn test_context_exposes_tokenizer() {
+        let context = LintContext::new("# 标题\n\n中文分词测试 `code`");
+        assert!(context.words().iter().any(|w| w == "分词"));
+        assert!(!context.text().contains("code"));
+        assert!(context.tokenizer().has_word("中文"));
+    }
+}
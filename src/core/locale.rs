@@ -0,0 +1,230 @@
+use crate::core::language::Language;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Weekday};
+use std::collections::HashMap;
+use tera::{Tera, Value};
+
+/// 按语言格式化日期，如 `2024年7月1日`、`July 1, 2024`
+pub fn format_date(date: NaiveDate, locale: Language) -> String {
+    match locale {
+        Language::Zh | Language::Ja => {
+            format!("{}年{}月{}日", date.year(), date.month(), date.day())
+        }
+        Language::Ko => format!("{}년 {}월 {}일", date.year(), date.month(), date.day()),
+        Language::En => date.format("%B %-d, %Y").to_string(),
+    }
+}
+
+/// 星期名，如 `星期一`、`月曜日`、`Monday`
+pub fn weekday_name(weekday: Weekday, locale: Language) -> &'static str {
+    const ZH: [&str; 7] = [
+        "星期一",
+        "星期二",
+        "星期三",
+        "星期四",
+        "星期五",
+        "星期六",
+        "星期日",
+    ];
+    const JA: [&str; 7] = [
+        "月曜日",
+        "火曜日",
+        "水曜日",
+        "木曜日",
+        "金曜日",
+        "土曜日",
+        "日曜日",
+    ];
+    const KO: [&str; 7] = [
+        "월요일",
+        "화요일",
+        "수요일",
+        "목요일",
+        "금요일",
+        "토요일",
+        "일요일",
+    ];
+    const EN: [&str; 7] = [
+        "Monday",
+        "Tuesday",
+        "Wednesday",
+        "Thursday",
+        "Friday",
+        "Saturday",
+        "Sunday",
+    ];
+    let index = weekday.num_days_from_monday() as usize;
+    match locale {
+        Language::Zh => ZH[index],
+        Language::Ja => JA[index],
+        Language::Ko => KO[index],
+        Language::En => EN[index],
+    }
+}
+
+/// 千位分隔的数字，`decimals` 为保留的小数位数
+pub fn format_number(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = formatted
+        .split_once('.')
+        .map_or((formatted.as_str(), None), |(i, f)| (i, Some(f)));
+
+    let mut out = String::new();
+    for (i, c) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    if let Some(fraction) = fraction {
+        out.push('.');
+        out.push_str(fraction);
+    }
+    if value < 0.0 && out.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        out.insert(0, '-');
+    }
+    out
+}
+
+/// 简写的大数，中文用万、亿（如 `1.2万`），日文用万、億，韩文用만、억，英文用 K、M、B
+pub fn format_compact(value: f64, locale: Language) -> String {
+    let units: &[(f64, &str)] = match locale {
+        Language::Zh => &[(1e8, "亿"), (1e4, "万")],
+        Language::Ja => &[(1e8, "億"), (1e4, "万")],
+        Language::Ko => &[(1e8, "억"), (1e4, "만")],
+        Language::En => &[(1e9, "B"), (1e6, "M"), (1e3, "K")],
+    };
+    units
+        .iter()
+        .find(|(unit, _)| value.abs() >= *unit)
+        .map(|(unit, suffix)| format!("{}{}", trim_decimal(value / unit), suffix))
+        .unwrap_or_else(|| format_number(value, 0))
+}
+
+/// 保留一位小数并去掉末尾的 `.0`
+fn trim_decimal(value: f64) -> String {
+    let formatted = format!("{:.1}", value);
+    formatted
+        .strip_suffix(".0")
+        .unwrap_or(&formatted)
+        .to_string()
+}
+
+/// 解析模板中的日期值：RFC 3339 时间（front matter 日期、`now()`）、`YYYY-MM-DD` 或
+/// `YYYY-MM-DD HH:MM:SS`
+fn parse_date(value: &Value) -> Option<NaiveDate> {
+    let text = value.as_str()?.trim();
+    DateTime::parse_from_rfc3339(text)
+        .map(|dt| dt.date_naive())
+        .ok()
+        .or_else(|| NaiveDate::parse_from_str(text, "%Y-%m-%d").ok())
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S")
+                .ok()
+                .map(|dt| dt.date())
+        })
+}
+
+fn date_arg(value: &Value, filter: &str) -> tera::Result<NaiveDate> {
+    parse_date(value)
+        .ok_or_else(|| tera::Error::msg(format!("{} 过滤器需要日期，实际为 {}", filter, value)))
+}
+
+fn number_arg(value: &Value, filter: &str) -> tera::Result<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+        .ok_or_else(|| tera::Error::msg(format!("{} 过滤器需要数字，实际为 {}", filter, value)))
+}
+
+/// 注册按语言格式化的过滤器：
+///
+/// - `local_date`：`{{ date | local_date }}` → `2024年7月1日`
+/// - `weekday`：`{{ date | weekday }}` → `星期一`
+/// - `local_number`：`{{ 12345.6 | local_number(decimals=1) }}` → `12,345.6`
+/// - `compact_number`：`{{ 12345 | compact_number }}` → `1.2万`
+pub fn register_locale_filters(tera: &mut Tera, locale: Language) {
+    tera.register_filter(
+        "local_date",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            Ok(Value::String(format_date(
+                date_arg(value, "local_date")?,
+                locale,
+            )))
+        },
+    );
+    tera.register_filter(
+        "weekday",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            let date = date_arg(value, "weekday")?;
+            Ok(Value::String(
+                weekday_name(date.weekday(), locale).to_string(),
+            ))
+        },
+    );
+    tera.register_filter(
+        "local_number",
+        |value: &Value, args: &HashMap<String, Value>| {
+            let decimals = args.get("decimals").and_then(Value::as_u64).unwrap_or(0);
+            Ok(Value::String(format_number(
+                number_arg(value, "local_number")?,
+                decimals as usize,
+            )))
+        },
+    );
+    tera.register_filter(
+        "compact_number",
+        move |value: &Value, _: &HashMap<String, Value>| {
+            Ok(Value::String(format_compact(
+                number_arg(value, "compact_number")?,
+                locale,
+            )))
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locale_formatting() {
+        let date = NaiveDate::from_ymd_opt(2024, 7, 1).unwrap();
+        assert_eq!(format_date(date, Language::Zh), "2024年7月1日");
+        assert_eq!(format_date(date, Language::Ko), "2024년 7월 1일");
+        assert_eq!(format_date(date, Language::En), "July 1, 2024");
+        assert_eq!(weekday_name(date.weekday(), Language::Zh), "星期一");
+        assert_eq!(weekday_name(date.weekday(), Language::Ja), "月曜日");
+
+        assert_eq!(format_number(1234567.891, 2), "1,234,567.89");
+        assert_eq!(format_number(-999.0, 0), "-999");
+        assert_eq!(format_number(100.0, 0), "100");
+        assert_eq!(format_compact(12345.0, Language::Zh), "1.2万");
+        assert_eq!(format_compact(250_000_000.0, Language::Zh), "2.5亿");
+        assert_eq!(format_compact(250_000_000.0, Language::Ja), "2.5億");
+        assert_eq!(format_compact(1_500_000.0, Language::En), "1.5M");
+        assert_eq!(format_compact(800.0, Language::En), "800");
+    }
+
+    #[test]
+    fn test_locale_filters() {
+        let mut tera = Tera::default();
+        tera.add_raw_template(
+            "footer",
+            "{{ date | local_date }}（{{ date | weekday }}）· 阅读 {{ views | compact_number }} · {{ words | local_number }} 字",
+        )
+        .unwrap();
+        register_locale_filters(&mut tera, Language::Zh);
+
+        let mut context = tera::Context::new();
+        context.insert("date", "2024-07-01T09:30:00+08:00");
+        context.insert("views", &123456);
+        context.insert("words", &4321);
+        assert_eq!(
+            tera.render("footer", &context).unwrap(),
+            "2024年7月1日（星期一）· 阅读 12.3万 · 4,321 字"
+        );
+
+        context.insert("date", "昨天");
+        assert!(tera.render("footer", &context).is_err());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::{error::Error, Result};
+use fs4::fs_std::FileExt;
+use std::{
+    fs::{File, OpenOptions},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+/// 等待其他 markflow 实例释放锁的最长时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 基于旁路 `.lock` 文件的建议锁，防止 watch 守护进程和手动运行的 CLI 同时改写
+/// 配置、缓存、台账等共享状态。锁在值被 drop 时释放。
+#[derive(Debug)]
+pub struct FileLock {
+    file: File,
+    path: PathBuf,
+}
+
+impl FileLock {
+    /// 获取排他锁（写）
+    pub fn exclusive(target: &Path) -> Result<Self> {
+        Self::acquire(target, true, LOCK_TIMEOUT)
+    }
+
+    /// 获取共享锁（读）
+    pub fn shared(target: &Path) -> Result<Self> {
+        Self::acquire(target, false, LOCK_TIMEOUT)
+    }
+
+    pub fn lock_path(target: &Path) -> PathBuf {
+        let mut name = target
+            .file_name()
+            .map(|n| n.to_os_string())
+            .unwrap_or_default();
+        name.push(".lock");
+        target.with_file_name(name)
+    }
+
+    pub fn acquire(target: &Path, exclusive: bool, timeout: Duration) -> Result<Self> {
+        let path = Self::lock_path(target);
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let started = Instant::now();
+        loop {
+            let acquired = if exclusive {
+                FileExt::try_lock_exclusive(&file)?
+            } else {
+                FileExt::try_lock_shared(&file)?
+            };
+
+            if acquired {
+                return Ok(Self { file, path });
+            }
+
+            if started.elapsed() >= timeout {
+                return Err(Error::Other(format!(
+                    "等待文件锁超时: {:?}，可能有另一个 markflow 实例正在运行",
+                    target
+                )));
+            }
+
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        if let Err(e) = FileExt::unlock(&self.file) {
+            tracing::warn!("释放文件锁失败 {:?}: {}", self.path, e);
+        }
+    }
+}
+
+/// 先写入临时文件再重命名，避免其他进程读到写了一半的文件
+pub fn write_atomic(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut tmp_name = path
+        .file_name()
+        .map(|n| n.to_os_string())
+        .unwrap_or_default();
+    tmp_name.push(format!(".{}.tmp", std::process::id()));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    std::fs::write(&tmp_path, contents)?;
+    std::fs::rename(&tmp_path, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp_path);
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_exclusive_lock_blocks_second_holder() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("ledger.db");
+
+        let lock = FileLock::exclusive(&target).unwrap();
+        assert!(lock.path().ends_with("ledger.db.lock"));
+
+        let second = FileLock::acquire(&target, true, Duration::from_millis(100));
+        assert!(second.is_err());
+
+        drop(lock);
+        assert!(FileLock::acquire(&target, true, Duration::from_millis(100)).is_ok());
+    }
+
+    #[test]
+    fn test_shared_locks_coexist() {
+        let dir = TempDir::new().unwrap();
+        let target = dir.path().join("config.toml");
+
+        let _a = FileLock::shared(&target).unwrap();
+        let _b = FileLock::acquire(&target, false, Duration::from_millis(100)).unwrap();
+        assert!(FileLock::acquire(&target, true, Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn test_write_atomic() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("nested").join("manifest.json");
+
+        write_atomic(&path, "{}").unwrap();
+        write_atomic(&path, "{\"a\":1}").unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\":1}");
+        assert_eq!(
+            std::fs::read_dir(path.parent().unwrap()).unwrap().count(),
+            1
+        );
+    }
+}
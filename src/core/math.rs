@@ -0,0 +1,368 @@
+use crate::{
+    cli::args::MathConfig,
+    core::{
+        content::{Content, Platform},
+        pipeline::PlatformStage,
+        report::PlatformReport,
+    },
+    error::Error,
+    Result,
+};
+use regex::Regex;
+use rquickjs::{Context, Ctx, Function, Module, Object, Runtime};
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, ops::Range, sync::OnceLock};
+
+/// 内置的 MathJax（TeX 输入、SVG 输出），来源和重新生成方法见 `assets/mathjax/README.md`
+const MATHJAX_BUNDLE: &str = include_str!("../../assets/mathjax/tex-svg.mjs");
+
+/// 渲染前替换 Markdown 中转义的 `\$`，comrak 会把转义直接输出为 `$`
+const ESCAPED_DOLLAR: &str = "\u{FDD0}";
+
+/// 把转义的 `\$`（前面有奇数个反斜杠）替换为占位符；`\\$` 是转义的反斜杠，其后的 `$` 仍是定界符。
+/// 只在开启公式渲染时使用，见 [`MarkdownProcessor::with_math`](crate::core::MarkdownProcessor::with_math)
+pub(crate) fn protect_escaped_dollars(markdown: &str) -> Cow<'_, str> {
+    if !markdown.contains("\\$") {
+        return Cow::Borrowed(markdown);
+    }
+    let mut result = String::with_capacity(markdown.len());
+    let mut backslashes = 0;
+    for c in markdown.chars() {
+        if c == '$' && backslashes % 2 == 1 {
+            result.pop();
+            result.push_str(ESCAPED_DOLLAR);
+        } else {
+            result.push(c);
+        }
+        backslashes = if c == '\\' { backslashes + 1 } else { 0 };
+    }
+    Cow::Owned(result)
+}
+
+/// 转义的 `\$` 在正文中输出为 `&#36;`，公式渲染不会把它当作定界符；代码中保持 `\$` 原样
+pub(crate) fn restore_escaped_dollars(html: String) -> String {
+    if !html.contains(ESCAPED_DOLLAR) {
+        return html;
+    }
+    let mut result = String::with_capacity(html.len());
+    let mut last = 0;
+    for code in code_regex().find_iter(&html) {
+        result.push_str(&html[last..code.start()].replace(ESCAPED_DOLLAR, "&#36;"));
+        result.push_str(&code.as_str().replace(ESCAPED_DOLLAR, "\\$"));
+        last = code.end();
+    }
+    result.push_str(&html[last..].replace(ESCAPED_DOLLAR, "&#36;"));
+    result
+}
+
+/// 代码块和行内代码中的 `$` 不是公式
+fn code_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| Regex::new(r"(?s)<pre\b.*?</pre>|<code\b.*?</code>").unwrap())
+}
+
+/// 块级公式 `$$...$$`（第 1 组）或行内公式 `$...$`（第 2 组）。
+/// 行内公式按 pandoc 的规则，紧挨定界符的不能是空白，结尾的 `$` 后不能紧跟数字（在 `process` 中检查），
+/// 避免把 `$5 和 $10`、`$10到$20` 当作公式；公式不能跨越标签。转义的 `\$` 输出为 `&#36;`，不是定界符
+fn math_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX
+        .get_or_init(|| Regex::new(r"\$\$([^$<]+?)\$\$|\$([^\s$<](?:[^$<\n]*[^\s$<])?)\$").unwrap())
+}
+
+/// 公式渲染阶段生成的公式（包含 SVG 的 `math-inline`、`math-display`）
+fn rendered_math_regex() -> &'static Regex {
+    static REGEX: OnceLock<Regex> = OnceLock::new();
+    REGEX.get_or_init(|| {
+        Regex::new(r#"(?s)<span class="math-(?:inline|display)" data-tex="[^"]*"[^>]*><svg\b.*?</svg></span>"#)
+            .unwrap()
+    })
+}
+
+/// 公式渲染阶段输出的 SVG 公式在 HTML 中的范围，平台适配器据此保留其中的 SVG 标签
+pub fn rendered_math_ranges(html: &str) -> Vec<Range<usize>> {
+    if !html.contains("math-") {
+        return Vec::new();
+    }
+    rendered_math_regex()
+        .find_iter(html)
+        .map(|m| m.range())
+        .collect()
+}
+
+/// 在 QuickJS 中运行的 MathJax。解析整个 MathJax 需要一两百毫秒，每个线程只初始化一次
+struct MathJax {
+    context: Context,
+}
+
+/// 全局对象上保存 MathJax 转换函数的属性名
+const CONVERT_FUNCTION: &str = "__markflowTexToSvg";
+
+thread_local! {
+    static MATHJAX: RefCell<Option<MathJax>> = const { RefCell::new(None) };
+}
+
+impl MathJax {
+    fn new() -> Result<Self> {
+        let engine_error = |e: rquickjs::Error| Error::Other(format!("公式引擎初始化失败: {}", e));
+        let runtime = Runtime::new().map_err(engine_error)?;
+        let context = Context::full(&runtime).map_err(engine_error)?;
+        context.with(|ctx| {
+            let result = (|| {
+                let (module, promise) =
+                    Module::declare(ctx.clone(), "mathjax", MATHJAX_BUNDLE)?.eval()?;
+                promise.finish::<()>()?;
+                let convert: Function = module.get("default")?;
+                ctx.globals().set(CONVERT_FUNCTION, convert)
+            })();
+            result.map_err(|e| {
+                Error::Other(format!(
+                    "公式引擎初始化失败: {}",
+                    exception_message(&ctx, e)
+                ))
+            })
+        })?;
+        Ok(Self { context })
+    }
+
+    /// 把 TeX 公式转换为 `<svg>`，公式有误时返回 MathJax 的错误信息
+    fn to_svg(&self, tex: &str, display: bool) -> Result<String> {
+        self.context.with(|ctx| {
+            let result = (|| {
+                let convert: Function = ctx.globals().get(CONVERT_FUNCTION)?;
+                let options = Object::new(ctx.clone())?;
+                options.set("display", display)?;
+                convert.call::<_, String>((tex, options))
+            })();
+            result.map_err(|e| Error::Markdown(exception_message(&ctx, e)))
+        })
+    }
+}
+
+/// JavaScript 异常取出其中的错误信息，其他错误原样转为文字
+fn exception_message(ctx: &Ctx, error: rquickjs::Error) -> String {
+    if matches!(error, rquickjs::Error::Exception) {
+        let exception = ctx.catch();
+        if let Some(message) = exception.as_exception().and_then(|e| e.message()) {
+            return message;
+        }
+    }
+    error.to_string()
+}
+
+/// 在当前线程的 MathJax 中渲染公式，首次调用时初始化
+fn tex_to_svg(tex: &str, display: bool) -> Result<String> {
+    MATHJAX.with(|cell| {
+        let mut engine = cell.borrow_mut();
+        if engine.is_none() {
+            *engine = Some(MathJax::new()?);
+        }
+        engine
+            .as_ref()
+            .expect("公式引擎已初始化")
+            .to_svg(tex, display)
+    })
+}
+
+/// 把 `<defs>` 中的字形展开到引用处。公众号编辑器会去掉 `id`，
+/// 同一篇文章中多个公式的字形 id 也会重复，`<use>` 引用的字形因此丢失
+fn inline_glyphs(svg: &str) -> String {
+    static DEFS_REGEX: OnceLock<Regex> = OnceLock::new();
+    static GLYPH_REGEX: OnceLock<Regex> = OnceLock::new();
+    static USE_REGEX: OnceLock<Regex> = OnceLock::new();
+    let defs_regex = DEFS_REGEX.get_or_init(|| Regex::new(r"(?s)<defs>.*?</defs>").unwrap());
+    let glyph_regex = GLYPH_REGEX
+        .get_or_init(|| Regex::new(r#"<path id="([^"]+)" d="([^"]*)"></path>"#).unwrap());
+    let use_regex = USE_REGEX.get_or_init(|| {
+        Regex::new(r##"<use\b([^>]*?) xlink:href="#([^"]+)"([^>]*)></use>"##).unwrap()
+    });
+
+    let Some(defs) = defs_regex.find(svg) else {
+        return svg.to_string();
+    };
+    let glyphs: HashMap<&str, &str> = glyph_regex
+        .captures_iter(defs.as_str())
+        .map(|caps| (caps.get(1).unwrap().as_str(), caps.get(2).unwrap().as_str()))
+        .collect();
+    let svg = format!("{}{}", &svg[..defs.start()], &svg[defs.end()..]);
+    use_regex
+        .replace_all(&svg, |caps: &regex::Captures| match glyphs.get(&caps[2]) {
+            Some(d) => format!(r#"<path{} d="{}"{}></path>"#, &caps[1], d, &caps[3]),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// 公式渲染阶段：在本地用内置的 MathJax 把 `math.platforms` 中平台的行内公式和块级公式
+/// 渲染为内联的 SVG，不需要联网，也不需要上传图片。
+///
+/// `data-tex` 保留原公式；公式有误时保持原文并写入处理报告的警告。不在列表中的平台保持原样，
+/// 知乎由适配器转为编辑器的 `data-tex` 公式
+pub struct MathRenderStage {
+    config: MathConfig,
+}
+
+impl MathRenderStage {
+    pub fn new(config: MathConfig) -> Self {
+        Self { config }
+    }
+
+    fn applies_to(&self, platform: &Platform) -> bool {
+        let name = platform.to_string();
+        self.config.enabled && self.config.platforms.contains(&name)
+    }
+
+    /// 渲染后的公式；`formula` 取自 HTML 正文，可能含有实体
+    pub fn render(&self, formula: &str, display: bool) -> Result<String> {
+        let tex = html_escape::decode_html_entities(formula.trim());
+        let svg = inline_glyphs(&tex_to_svg(&tex, display)?);
+        let attr = html_escape::encode_double_quoted_attribute(&tex);
+        Ok(if display {
+            format!(
+                r#"<span class="math-display" data-tex="{}" style="display: block; margin: 16px 0; text-align: center; overflow-x: auto;">{}</span>"#,
+                attr, svg
+            )
+        } else {
+            format!(
+                r#"<span class="math-inline" data-tex="{}">{}</span>"#,
+                attr, svg
+            )
+        })
+    }
+}
+
+impl PlatformStage for MathRenderStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()> {
+        if !self.applies_to(platform) || !content.html.contains('$') {
+            return Ok(());
+        }
+
+        let html = &content.html;
+        let code: Vec<Range<usize>> = code_regex().find_iter(html).map(|m| m.range()).collect();
+        let mut result = String::with_capacity(html.len());
+        let mut rendered = Vec::new();
+        let mut last = 0;
+        let mut pos = 0;
+        while let Some(caps) = math_regex().captures_at(html, pos) {
+            let matched = caps.get(0).expect("第 0 组总是存在");
+            let followed_by_digit = caps.get(2).is_some()
+                && html[matched.end()..].starts_with(|c: char| c.is_ascii_digit());
+            if followed_by_digit || code.iter().any(|range| range.contains(&matched.start())) {
+                // 开头的 `$` 不是定界符，从下一个字符继续查找
+                pos = matched.start() + 1;
+                continue;
+            }
+            let formula = match caps.get(1) {
+                Some(block) => self.render(block.as_str(), true),
+                None => self.render(&caps[2], false),
+            };
+            pos = matched.end();
+            let formula = match formula {
+                Ok(formula) => formula,
+                Err(e) => {
+                    report.warnings.push(format!(
+                        "公式 {} 渲染失败，保持原样: {}",
+                        matched.as_str(),
+                        e
+                    ));
+                    continue;
+                }
+            };
+            result.push_str(&html[last..matched.start()]);
+            result.push_str(&formula);
+            last = matched.end();
+            rendered.push(matched.range());
+        }
+        if rendered.is_empty() {
+            return Ok(());
+        }
+        result.push_str(&html[last..]);
+
+        for range in rendered {
+            report.record_degraded(html, range, "渲染为 SVG 公式");
+        }
+        content.html = result;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "公式渲染"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(platform: Platform, html: &str) -> (String, PlatformReport) {
+        let stage = MathRenderStage::new(MathConfig {
+            enabled: true,
+            ..Default::default()
+        });
+        let mut content = Content::new("公式".to_string(), String::new());
+        content.html = html.to_string();
+        let mut report = PlatformReport::new(platform.clone());
+        stage.process(&mut content, &platform, &mut report).unwrap();
+        (content.html, report)
+    }
+
+    #[test]
+    fn test_render_formulas_for_wechat() {
+        let (html, report) = render(
+            Platform::WeChat,
+            concat!(
+                "<p>质能方程 $E = mc^2$，价格 $5 和 $10。</p>\n",
+                "<p>$$\n\\sum_{i=1}^n i &lt; n^2\n$$</p>\n",
+                "<pre><code>echo $HOME $PATH</code></pre>"
+            ),
+        );
+
+        assert!(html.contains(r#"<span class="math-inline" data-tex="E = mc^2"><svg "#));
+        assert!(html.contains("价格 $5 和 $10。"));
+        assert!(html.contains(r#"<span class="math-display" data-tex="\sum_{i=1}^n i &lt; n^2""#));
+        assert!(html.contains("<pre><code>echo $HOME $PATH</code></pre>"));
+        // 字形已展开，不依赖 id 引用
+        assert!(html.contains("<path data-c="));
+        assert!(!html.contains("<use") && !html.contains("<defs>"));
+        assert_eq!(rendered_math_ranges(&html).len(), 2);
+        assert_eq!(report.degraded_elements.len(), 2);
+        assert_eq!(report.degraded_elements[0].element, "$E = mc^2$");
+
+        let (html, report) = render(Platform::WeChat, r"<p>$\frac{a$</p>");
+        assert_eq!(html, r"<p>$\frac{a$</p>");
+        assert!(report.warnings[0].contains("Missing close brace"));
+    }
+
+    #[test]
+    fn test_other_platforms_unchanged() {
+        let html = "<p>$E = mc^2$</p>";
+        let (zhihu, report) = render(Platform::Zhihu, html);
+        assert_eq!(zhihu, html);
+        assert!(report.degraded_elements.is_empty());
+
+        let stage = MathRenderStage::new(MathConfig::default());
+        assert!(!stage.applies_to(&Platform::WeChat));
+    }
+
+    #[test]
+    fn test_currency_is_not_math() {
+        for text in [
+            "<p>售价 $10 到 $20</p>",
+            "<p>售价 $10到$20</p>",
+            "<p>定价 &#36;x&#36; 美元</p>",
+        ] {
+            let (html, report) = render(Platform::WeChat, text);
+            assert_eq!(html, text);
+            assert!(report.degraded_elements.is_empty());
+        }
+
+        // 被拒绝的 `$` 之后仍能识别公式
+        let (html, report) = render(Platform::WeChat, "<p>$10到$20 和 $x$</p>");
+        assert!(html.starts_with(r#"<p>$10到$20 和 <span class="math-inline""#));
+        assert_eq!(report.degraded_elements[0].element, "$x$");
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{
+    core::{
+        content::{Content, Platform},
+        pipeline::ProcessingStage,
+        report::{element_location, DegradedElement, PlatformReport},
+    },
+    Result,
+};
+use async_trait::async_trait;
+use regex::{Captures, Regex};
+use std::sync::OnceLock;
+
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "webm", "mov", "m4v", "m3u8", "ogv"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "m4a", "wav", "ogg", "oga", "flac", "aac"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MediaKind {
+    Audio,
+    Video,
+}
+
+impl MediaKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MediaKind::Audio => "audio",
+            MediaKind::Video => "video",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            MediaKind::Audio => "音频",
+            MediaKind::Video => "视频",
+        }
+    }
+
+    /// 根据文件扩展名判断媒体类型
+    pub fn from_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        let ext = path.rsplit_once('.')?.1.to_lowercase();
+
+        if VIDEO_EXTENSIONS.contains(&ext.as_str()) {
+            Some(MediaKind::Video)
+        } else if AUDIO_EXTENSIONS.contains(&ext.as_str()) {
+            Some(MediaKind::Audio)
+        } else {
+            None
+        }
+    }
+}
+
+/// 文中嵌入的一个音视频资源
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaEmbed {
+    pub kind: MediaKind,
+    pub src: String,
+    pub poster: Option<String>,
+    pub title: Option<String>,
+}
+
+impl MediaEmbed {
+    /// 平台无关的占位元素，由各平台适配器渲染成最终形式
+    pub fn to_placeholder(&self) -> String {
+        let mut html = format!(
+            r#"<div class="markflow-media" data-kind="{}" data-src="{}""#,
+            self.kind.as_str(),
+            html_escape::encode_double_quoted_attribute(&self.src)
+        );
+        if let Some(poster) = &self.poster {
+            html.push_str(&format!(
+                r#" data-poster="{}""#,
+                html_escape::encode_double_quoted_attribute(poster)
+            ));
+        }
+        if let Some(title) = &self.title {
+            html.push_str(&format!(
+                r#" data-title="{}""#,
+                html_escape::encode_double_quoted_attribute(title)
+            ));
+        }
+        html.push_str("></div>");
+        html
+    }
+
+    fn display_title(&self) -> String {
+        self.title
+            .clone()
+            .unwrap_or_else(|| self.kind.label().to_string())
+    }
+
+    /// 渲染为指定平台的HTML
+    pub fn render(&self, platform: &Platform) -> String {
+        let title = html_escape::encode_text(&self.display_title()).into_owned();
+        let src = html_escape::encode_double_quoted_attribute(&self.src).into_owned();
+
+        match platform {
+            Platform::WeChat => {
+                // 微信不支持外部音视频，使用封面图+提示语，引导读者通过“阅读原文”观看
+                let poster = self
+                    .poster
+                    .as_ref()
+                    .map(|p| {
+                        format!(
+                            r#"<img src="{}" alt="{}">"#,
+                            html_escape::encode_double_quoted_attribute(p),
+                            title
+                        )
+                    })
+                    .unwrap_or_default();
+                format!(
+                    r#"<section class="markflow-media-poster" style="margin: 20px 0; text-align: center;">{}<p style="font-size: 14px; color: #888; text-align: center;">▶ {} · 点击阅读原文观看</p></section>"#,
+                    poster, title
+                )
+            }
+            _ => {
+                // 知乎等平台渲染为链接卡片
+                let image = self
+                    .poster
+                    .as_ref()
+                    .map(|p| {
+                        format!(
+                            r#" data-image="{}""#,
+                            html_escape::encode_double_quoted_attribute(p)
+                        )
+                    })
+                    .unwrap_or_default();
+                format!(
+                    r#"<p><a href="{}" class="LinkCard" data-draft-type="link-card"{}>{}：{}</a></p>"#,
+                    src,
+                    image,
+                    self.kind.label(),
+                    title
+                )
+            }
+        }
+    }
+}
+
+fn attr_value(attrs: &str, name: &str) -> Option<String> {
+    static ATTR_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = ATTR_REGEX.get_or_init(|| Regex::new(r#"([\w-]+)="([^"]*)""#).unwrap());
+    regex
+        .captures_iter(attrs)
+        .find(|caps| caps[1].eq_ignore_ascii_case(name))
+        .map(|caps| html_escape::decode_html_entities(&caps[2]).into_owned())
+        .filter(|value| !value.is_empty())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    let value = html_escape::decode_html_entities(value.trim()).into_owned();
+    (!value.is_empty()).then_some(value)
+}
+
+/// 将HTML中的 `<video>`/`<audio>` 标签以及指向音视频文件的链接替换为占位元素
+pub fn replace_media_embeds(html: &str) -> (String, Vec<MediaEmbed>) {
+    static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+    static SOURCE_REGEX: OnceLock<Regex> = OnceLock::new();
+    static LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+    static IMG_REGEX: OnceLock<Regex> = OnceLock::new();
+
+    let tag_regex = TAG_REGEX.get_or_init(|| {
+        Regex::new(r"(?s)<(video|audio)\b([^>]*)>(.*?)</(?:video|audio)>").unwrap()
+    });
+    let source_regex = SOURCE_REGEX.get_or_init(|| Regex::new(r"<source\b([^>]*)>").unwrap());
+    let link_regex = LINK_REGEX
+        .get_or_init(|| Regex::new(r#"<p><a href="([^"]+)"[^>]*>([^<]*)</a></p>"#).unwrap());
+    let img_regex = IMG_REGEX
+        .get_or_init(|| Regex::new(r#"<p><img src="([^"]+)" alt="([^"]*)"[^>]*>\s*</p>"#).unwrap());
+
+    let mut embeds = Vec::new();
+
+    // HTML5 音视频标签
+    let result = tag_regex
+        .replace_all(html, |caps: &Captures| {
+            let kind = if &caps[1] == "video" {
+                MediaKind::Video
+            } else {
+                MediaKind::Audio
+            };
+            let attrs = &caps[2];
+            let src = attr_value(attrs, "src").or_else(|| {
+                source_regex
+                    .captures_iter(&caps[3])
+                    .find_map(|source| attr_value(&source[1], "src"))
+            });
+
+            match src {
+                Some(src) => {
+                    let embed = MediaEmbed {
+                        kind,
+                        src,
+                        poster: attr_value(attrs, "poster"),
+                        title: attr_value(attrs, "title"),
+                    };
+                    let placeholder = embed.to_placeholder();
+                    embeds.push(embed);
+                    placeholder
+                }
+                None => caps[0].to_string(),
+            }
+        })
+        .to_string();
+
+    // 独占一段的音视频链接 [标题](video.mp4) 以及图片语法引用的 ![标题](video.mp4)
+    let mut replace_links = |regex: &Regex, html: &str| {
+        regex
+            .replace_all(html, |caps: &Captures| {
+                let src = html_escape::decode_html_entities(&caps[1]).into_owned();
+                match MediaKind::from_url(&src) {
+                    Some(kind) => {
+                        let embed = MediaEmbed {
+                            kind,
+                            src,
+                            poster: None,
+                            title: non_empty(&caps[2]),
+                        };
+                        let placeholder = embed.to_placeholder();
+                        embeds.push(embed);
+                        placeholder
+                    }
+                    None => caps[0].to_string(),
+                }
+            })
+            .to_string()
+    };
+    let result = replace_links(link_regex, &result);
+    let result = replace_links(img_regex, &result);
+
+    (result, embeds)
+}
+
+/// 将占位元素渲染为目标平台的形式，供适配器调用；替换记录到报告的降级元素中
+pub fn render_media_placeholders(
+    html: &str,
+    platform: &Platform,
+    report: &mut PlatformReport,
+) -> String {
+    static PLACEHOLDER_REGEX: OnceLock<Regex> = OnceLock::new();
+    let placeholder_regex = PLACEHOLDER_REGEX
+        .get_or_init(|| Regex::new(r#"<div class="markflow-media"([^>]*)></div>"#).unwrap());
+
+    placeholder_regex
+        .replace_all(html, |caps: &Captures| {
+            let attrs = &caps[1];
+            let kind = match attr_value(attrs, "data-kind").as_deref() {
+                Some("audio") => MediaKind::Audio,
+                _ => MediaKind::Video,
+            };
+            match attr_value(attrs, "data-src") {
+                Some(src) => {
+                    let action = match platform {
+                        Platform::WeChat => "替换为封面图和提示语",
+                        _ => "替换为链接卡片",
+                    };
+                    report.degraded_elements.push(DegradedElement {
+                        element: format!(
+                            r#"<{} src="{}">"#,
+                            kind.as_str(),
+                            html_escape::encode_double_quoted_attribute(&src)
+                        ),
+                        action: action.to_string(),
+                        location: element_location(&html[..caps.get(0).unwrap().start()]),
+                    });
+                    MediaEmbed {
+                        kind,
+                        src,
+                        poster: attr_value(attrs, "data-poster"),
+                        title: attr_value(attrs, "data-title"),
+                    }
+                    .render(platform)
+                }
+                None => String::new(),
+            }
+        })
+        .to_string()
+}
+
+// 音视频嵌入阶段
+pub struct MediaEmbedStage;
+
+#[async_trait]
+impl ProcessingStage for MediaEmbedStage {
+    async fn process(&self, content: &mut Content) -> Result<()> {
+        let (html, embeds) = replace_media_embeds(&content.html);
+
+        for embed in &embeds {
+            tracing::debug!("发现{}嵌入: {}", embed.kind.label(), embed.src);
+        }
+
+        content.html = html;
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "音视频嵌入"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_kind_from_url() {
+        assert_eq!(
+            MediaKind::from_url("https://a.com/v.MP4?t=1"),
+            Some(MediaKind::Video)
+        );
+        assert_eq!(MediaKind::from_url("song.mp3"), Some(MediaKind::Audio));
+        assert_eq!(MediaKind::from_url("https://a.com/page.html"), None);
+        assert_eq!(MediaKind::from_url("https://a.com/"), None);
+    }
+
+    #[test]
+    fn test_replace_video_tag() {
+        let html = r#"<video src="demo.mp4" poster="cover.jpg" title="演示"></video>"#;
+        let (result, embeds) = replace_media_embeds(html);
+
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(embeds[0].kind, MediaKind::Video);
+        assert_eq!(embeds[0].poster.as_deref(), Some("cover.jpg"));
+        assert!(result.contains(r#"class="markflow-media""#));
+        assert!(!result.contains("<video"));
+    }
+
+    #[test]
+    fn test_replace_audio_with_source_and_links() {
+        let html = r#"<audio controls><source src="talk.mp3" type="audio/mpeg"></audio>
+<p><a href="https://a.com/clip.mp4">精彩片段</a></p>
+<p><a href="https://a.com/article">普通链接</a></p>"#;
+        let (result, embeds) = replace_media_embeds(html);
+
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[0].src, "talk.mp3");
+        assert_eq!(embeds[1].title.as_deref(), Some("精彩片段"));
+        assert!(result.contains("普通链接"));
+    }
+
+    #[test]
+    fn test_render_per_platform() {
+        let (html, _) =
+            replace_media_embeds(r#"<video src="demo.mp4" poster="cover.jpg"></video>"#);
+
+        let mut report = PlatformReport::new(Platform::WeChat);
+        let wechat = render_media_placeholders(&html, &Platform::WeChat, &mut report);
+        assert!(wechat.contains("点击阅读原文观看"));
+        assert!(wechat.contains(r#"<img src="cover.jpg""#));
+        assert_eq!(
+            report.degraded_elements[0].element,
+            r#"<video src="demo.mp4">"#
+        );
+        assert_eq!(report.degraded_elements[0].location, "正文开头");
+
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        let zhihu = render_media_placeholders(&html, &Platform::Zhihu, &mut report);
+        assert!(zhihu.contains("LinkCard"));
+        assert!(zhihu.contains(r#"href="demo.mp4""#));
+    }
+}
@@ -0,0 +1,235 @@
+use crate::core::content::Platform;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{Mutex, OnceLock},
+    time::Duration,
+};
+
+/// 处理耗时直方图的桶上限（秒）
+const DURATION_BUCKETS: &[f64] = &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// 进程内的运行指标，由 Web 服务的 `/metrics` 以 Prometheus 文本格式导出
+#[derive(Debug, Default)]
+pub struct Metrics {
+    inner: Mutex<MetricsData>,
+}
+
+#[derive(Debug, Default)]
+struct MetricsData {
+    /// (method, path, status) -> 次数
+    http_requests: BTreeMap<(String, String, u16), u64>,
+    processing: Histogram,
+    /// (platform, outcome) -> 次数
+    publishes: BTreeMap<(String, &'static str), u64>,
+    /// (cache, hit) -> 次数
+    cache_lookups: BTreeMap<(String, bool), u64>,
+}
+
+#[derive(Debug)]
+struct Histogram {
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: vec![0; DURATION_BUCKETS.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, le) in self.buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if value <= *le {
+                *bucket += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// 全局指标实例
+pub fn metrics() -> &'static Metrics {
+    static METRICS: OnceLock<Metrics> = OnceLock::new();
+    METRICS.get_or_init(Metrics::default)
+}
+
+impl Metrics {
+    fn data(&self) -> std::sync::MutexGuard<'_, MetricsData> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn record_http_request(&self, method: &str, path: &str, status: u16) {
+        *self
+            .data()
+            .http_requests
+            .entry((method.to_string(), path.to_string(), status))
+            .or_default() += 1;
+    }
+
+    pub fn observe_processing(&self, elapsed: Duration) {
+        self.data().processing.observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_publish(&self, platform: &Platform, success: bool) {
+        let outcome = if success { "success" } else { "failure" };
+        *self
+            .data()
+            .publishes
+            .entry((platform.to_string(), outcome))
+            .or_default() += 1;
+    }
+
+    /// 平台发布成功或失败的次数
+    pub fn publish_count(&self, platform: &Platform, success: bool) -> u64 {
+        let outcome = if success { "success" } else { "failure" };
+        self.data()
+            .publishes
+            .get(&(platform.to_string(), outcome))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn record_cache(&self, cache: &str, hit: bool) {
+        *self
+            .data()
+            .cache_lookups
+            .entry((cache.to_string(), hit))
+            .or_default() += 1;
+    }
+
+    /// 以 Prometheus 文本格式输出
+    pub fn render(&self) -> String {
+        let data = self.data();
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP markflow_http_requests_total Web服务处理的请求数"
+        );
+        let _ = writeln!(out, "# TYPE markflow_http_requests_total counter");
+        for ((method, path, status), count) in &data.http_requests {
+            let _ = writeln!(
+                out,
+                "markflow_http_requests_total{{method=\"{}\",path=\"{}\",status=\"{}\"}} {}",
+                method,
+                escape_label(path),
+                status,
+                count
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP markflow_processing_duration_seconds Markdown处理流水线耗时"
+        );
+        let _ = writeln!(out, "# TYPE markflow_processing_duration_seconds histogram");
+        for (le, count) in DURATION_BUCKETS.iter().zip(&data.processing.buckets) {
+            let _ = writeln!(
+                out,
+                "markflow_processing_duration_seconds_bucket{{le=\"{}\"}} {}",
+                le, count
+            );
+        }
+        let _ = writeln!(
+            out,
+            "markflow_processing_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            data.processing.count
+        );
+        let _ = writeln!(
+            out,
+            "markflow_processing_duration_seconds_sum {}",
+            data.processing.sum
+        );
+        let _ = writeln!(
+            out,
+            "markflow_processing_duration_seconds_count {}",
+            data.processing.count
+        );
+
+        let _ = writeln!(out, "# HELP markflow_publish_total 发布结果");
+        let _ = writeln!(out, "# TYPE markflow_publish_total counter");
+        for ((platform, outcome), count) in &data.publishes {
+            let _ = writeln!(
+                out,
+                "markflow_publish_total{{platform=\"{}\",outcome=\"{}\"}} {}",
+                platform, outcome, count
+            );
+        }
+
+        let _ = writeln!(out, "# HELP markflow_cache_lookups_total 缓存查询次数");
+        let _ = writeln!(out, "# TYPE markflow_cache_lookups_total counter");
+        let mut ratios: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+        for ((cache, hit), count) in &data.cache_lookups {
+            let result = if *hit { "hit" } else { "miss" };
+            let _ = writeln!(
+                out,
+                "markflow_cache_lookups_total{{cache=\"{}\",result=\"{}\"}} {}",
+                escape_label(cache),
+                result,
+                count
+            );
+            let entry = ratios.entry(cache.as_str()).or_default();
+            if *hit {
+                entry.0 += count;
+            }
+            entry.1 += count;
+        }
+
+        let _ = writeln!(out, "# HELP markflow_cache_hit_ratio 缓存命中率");
+        let _ = writeln!(out, "# TYPE markflow_cache_hit_ratio gauge");
+        for (cache, (hits, total)) in ratios {
+            let _ = writeln!(
+                out,
+                "markflow_cache_hit_ratio{{cache=\"{}\"}} {}",
+                escape_label(cache),
+                hits as f64 / total as f64
+            );
+        }
+
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus_text() {
+        let metrics = Metrics::default();
+        metrics.record_http_request("GET", "/healthz", 200);
+        metrics.record_http_request("GET", "/healthz", 200);
+        metrics.observe_processing(Duration::from_millis(30));
+        metrics.record_publish(&Platform::WeChat, true);
+        metrics.record_publish(&Platform::Zhihu, false);
+        metrics.record_cache("images", true);
+        metrics.record_cache("images", true);
+        metrics.record_cache("images", false);
+        metrics.record_cache("images", true);
+
+        let text = metrics.render();
+        assert!(text.contains(
+            "markflow_http_requests_total{method=\"GET\",path=\"/healthz\",status=\"200\"} 2"
+        ));
+        assert!(text.contains("markflow_processing_duration_seconds_bucket{le=\"0.01\"} 0"));
+        assert!(text.contains("markflow_processing_duration_seconds_bucket{le=\"0.05\"} 1"));
+        assert!(text.contains("markflow_processing_duration_seconds_count 1"));
+        assert!(text.contains("markflow_publish_total{platform=\"zhihu\",outcome=\"failure\"} 1"));
+        assert!(text.contains("markflow_cache_hit_ratio{cache=\"images\"} 0.75"));
+    }
+}
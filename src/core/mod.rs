@@ -1,7 +1,133 @@
+pub mod accessibility;
+pub mod activity;
+pub mod alt_text;
+pub mod annotations;
+pub mod archive;
+pub mod article_assets;
+pub mod auto_draft;
+pub mod bare_urls;
+pub mod block_cache;
+pub mod browser;
+pub mod bundle;
+pub mod capabilities;
+pub mod clipboard;
+pub mod compile;
+pub mod conditional;
 pub mod content;
+pub mod crosslink;
+pub mod device;
+pub mod diagnostics;
+pub mod document;
+pub mod duplication;
+pub mod embed;
+pub mod encoding;
+pub mod field_mapping;
+pub mod footnotes;
+pub mod frontmatter;
+pub mod git;
+pub mod html_check;
+pub mod http;
+pub mod images;
+pub mod language;
+pub mod links;
+pub mod lint;
+pub mod locale;
+pub mod lock;
+pub mod math;
+pub mod media;
+pub mod metrics;
+pub mod notifications;
+pub mod output;
+pub mod paths;
+pub mod permalink;
 pub mod pipeline;
+pub mod plugins;
+pub mod process_cache;
 pub mod processor;
+pub mod readability;
+pub mod references;
+pub mod report;
+pub mod secrets;
+pub mod snapshot;
+pub mod source_format;
+pub mod source_map;
+pub mod stage_dump;
+pub mod store;
+pub mod style;
+pub mod templates;
+pub mod terminology;
+pub mod text;
+pub mod theme;
+pub mod time;
+pub mod typography;
+pub mod update;
+pub mod variants;
+pub mod workflow;
+pub mod workspace;
 
+pub use accessibility::*;
+pub use activity::*;
+pub use alt_text::*;
+pub use annotations::*;
+pub use archive::*;
+pub use article_assets::*;
+pub use auto_draft::*;
+pub use bare_urls::*;
+pub use block_cache::*;
+pub use browser::*;
+pub use bundle::*;
+pub use capabilities::*;
+pub use clipboard::*;
+pub use compile::*;
+pub use conditional::*;
 pub use content::*;
+pub use crosslink::*;
+pub use device::*;
+pub use diagnostics::*;
+pub use document::*;
+pub use duplication::*;
+pub use embed::*;
+pub use encoding::*;
+pub use field_mapping::*;
+pub use footnotes::*;
+pub use frontmatter::*;
+pub use git::*;
+pub use html_check::*;
+pub use http::*;
+pub use images::*;
+pub use language::*;
+pub use links::*;
+pub use lint::*;
+pub use locale::*;
+pub use lock::*;
+pub use math::*;
+pub use media::*;
+pub use metrics::*;
+pub use notifications::*;
+pub use output::*;
+pub use paths::*;
+pub use permalink::*;
 pub use pipeline::*;
+pub use plugins::*;
+pub use process_cache::*;
 pub use processor::*;
+pub use readability::*;
+pub use references::*;
+pub use report::*;
+pub use secrets::*;
+pub use snapshot::*;
+pub use source_format::*;
+pub use source_map::*;
+pub use stage_dump::*;
+pub use store::*;
+pub use style::*;
+pub use templates::*;
+pub use terminology::*;
+pub use text::*;
+pub use theme::*;
+pub use time::*;
+pub use typography::*;
+pub use update::*;
+pub use variants::*;
+pub use workflow::*;
+pub use workspace::*;
@@ -0,0 +1,295 @@
+use crate::{
+    cli::args::EmailNotificationConfig,
+    core::{
+        activity::{ActivityDigest, ActivityLog},
+        lock::write_atomic,
+    },
+    error::Error,
+    Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, str::FromStr};
+
+/// 活动日志保留的天数，足够覆盖每周摘要
+const ACTIVITY_RETENTION_DAYS: i64 = 35;
+
+/// 摘要邮件的频率
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFrequency {
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    pub fn period(self) -> Duration {
+        match self {
+            DigestFrequency::Daily => Duration::days(1),
+            DigestFrequency::Weekly => Duration::weeks(1),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            DigestFrequency::Daily => "每日",
+            DigestFrequency::Weekly => "每周",
+        }
+    }
+}
+
+impl FromStr for DigestFrequency {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "daily" => Ok(DigestFrequency::Daily),
+            "weekly" => Ok(DigestFrequency::Weekly),
+            _ => Err(Error::Config(format!(
+                "无效的摘要频率: {}（可选: daily、weekly）",
+                s
+            ))),
+        }
+    }
+}
+
+/// SMTP 连接的加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpTls {
+    StartTls,
+    Tls,
+    None,
+}
+
+impl FromStr for SmtpTls {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "starttls" => Ok(SmtpTls::StartTls),
+            "tls" => Ok(SmtpTls::Tls),
+            "none" => Ok(SmtpTls::None),
+            _ => Err(Error::Config(format!(
+                "无效的 SMTP 加密方式: {}（可选: starttls、tls、none）",
+                s
+            ))),
+        }
+    }
+}
+
+fn mail_error(e: impl std::fmt::Display) -> Error {
+    Error::Network(format!("发送邮件失败: {}", e))
+}
+
+/// 通过 SMTP 发送通知邮件
+pub struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+}
+
+impl EmailNotifier {
+    pub fn from_config(config: &EmailNotificationConfig) -> Result<Self> {
+        let host = config
+            .smtp_host
+            .as_deref()
+            .ok_or_else(|| Error::Config("未配置 notifications.email.smtp_host".to_string()))?;
+        let parse = |address: &str| {
+            address
+                .parse::<Mailbox>()
+                .map_err(|e| Error::Config(format!("无效的邮件地址 {}: {}", address, e)))
+        };
+        let from = parse(
+            config
+                .from
+                .as_deref()
+                .ok_or_else(|| Error::Config("未配置 notifications.email.from".to_string()))?,
+        )?;
+        if config.to.is_empty() {
+            return Err(Error::Config("未配置 notifications.email.to".to_string()));
+        }
+        let to = config
+            .to
+            .iter()
+            .map(|address| parse(address))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut builder = match config.tls.parse()? {
+            SmtpTls::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(mail_error)?
+            }
+            SmtpTls::Tls => {
+                AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(mail_error)?
+            }
+            SmtpTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        }
+        .port(config.smtp_port);
+        if let Some(username) = &config.username {
+            let password = std::env::var(&config.password_env).map_err(|_| {
+                Error::Config(format!(
+                    "未设置环境变量 {}（SMTP 密码）",
+                    config.password_env
+                ))
+            })?;
+            builder = builder.credentials(Credentials::new(username.clone(), password));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+            to,
+        })
+    }
+
+    pub async fn send(&self, subject: &str, body: String) -> Result<()> {
+        let mut message = Message::builder()
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            message = message.to(to.clone());
+        }
+        let message = message.body(body).map_err(mail_error)?;
+        self.transport.send(message).await.map_err(mail_error)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DigestStateData {
+    last_sent_at: Option<DateTime<Utc>>,
+}
+
+/// 记录上次发送摘要的时间，watch 和定时任务据此判断是否到期
+pub struct DigestState {
+    path: PathBuf,
+}
+
+impl DigestState {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn default_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow").join("digest_state.json")
+    }
+
+    pub fn last_sent_at(&self) -> Option<DateTime<Utc>> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|raw| serde_json::from_str::<DigestStateData>(&raw).ok())
+            .and_then(|data| data.last_sent_at)
+    }
+
+    pub fn is_due(&self, frequency: DigestFrequency, now: DateTime<Utc>) -> bool {
+        self.last_sent_at()
+            .is_none_or(|last| last + frequency.period() <= now)
+    }
+
+    pub fn mark_sent(&self, at: DateTime<Utc>) -> Result<()> {
+        let data = DigestStateData {
+            last_sent_at: Some(at),
+        };
+        write_atomic(&self.path, serde_json::to_string_pretty(&data)?)
+    }
+}
+
+/// 汇总上次发送以来（首次为一个周期内）的活动，返回邮件标题和正文
+pub fn build_digest(
+    log: &ActivityLog,
+    state: &DigestState,
+    frequency: DigestFrequency,
+    now: DateTime<Utc>,
+) -> Result<(String, String)> {
+    let since = state
+        .last_sent_at()
+        .unwrap_or_else(|| now - frequency.period());
+    let digest = ActivityDigest::from_events(log.since(since)?);
+    let subject = format!(
+        "MarkFlow {}摘要（{}）",
+        frequency.label(),
+        now.format("%Y-%m-%d")
+    );
+    let body = format!(
+        "{} 至 {} 的活动：\n\n{}",
+        since.format("%Y-%m-%d %H:%M"),
+        now.format("%Y-%m-%d %H:%M"),
+        digest.render_text()
+    );
+    Ok((subject, body))
+}
+
+/// 发送摘要邮件；`force` 为 false 时只在到期后发送。返回是否发送了邮件
+pub async fn send_digest(config: &EmailNotificationConfig, force: bool) -> Result<bool> {
+    let frequency: DigestFrequency = config.frequency.parse()?;
+    let state = DigestState::new(DigestState::default_path());
+    let now = Utc::now();
+    if !force && !state.is_due(frequency, now) {
+        return Ok(false);
+    }
+
+    let log = ActivityLog::open_default();
+    let (subject, body) = build_digest(&log, &state, frequency, now)?;
+    EmailNotifier::from_config(config)?
+        .send(&subject, body)
+        .await?;
+    state.mark_sent(now)?;
+    log.prune(now - Duration::days(ACTIVITY_RETENTION_DAYS))?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::activity::Activity;
+
+    #[test]
+    fn test_digest_is_due_after_period() {
+        let dir = tempfile::tempdir().unwrap();
+        let state = DigestState::new(dir.path().join("digest_state.json"));
+        let log = ActivityLog::new(dir.path().join("activity.jsonl"));
+        let now = Utc::now();
+        assert!(state.is_due(DigestFrequency::Daily, now));
+
+        log.append(Activity::BrokenLink {
+            title: "文章".to_string(),
+            url: "https://example.com/gone".to_string(),
+            reason: "HTTP 404".to_string(),
+        })
+        .unwrap();
+        let (subject, body) =
+            build_digest(&log, &state, DigestFrequency::Weekly, Utc::now()).unwrap();
+        assert!(subject.starts_with("MarkFlow 每周摘要"));
+        assert!(body.contains("失效链接 1 个"));
+
+        state.mark_sent(now).unwrap();
+        assert!(!state.is_due(DigestFrequency::Daily, now + Duration::hours(23)));
+        assert!(state.is_due(DigestFrequency::Daily, now + Duration::hours(24)));
+        assert!(!state.is_due(DigestFrequency::Weekly, now + Duration::days(6)));
+    }
+
+    #[test]
+    fn test_notifier_requires_recipients() {
+        let config = EmailNotificationConfig {
+            smtp_host: Some("smtp.example.com".to_string()),
+            from: Some("MarkFlow <bot@example.com>".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            EmailNotifier::from_config(&config),
+            Err(Error::Config(_))
+        ));
+
+        let config = EmailNotificationConfig {
+            to: vec!["editor@example.com".to_string()],
+            tls: "none".to_string(),
+            ..config
+        };
+        assert!(EmailNotifier::from_config(&config).is_ok());
+    }
+}
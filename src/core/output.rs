@@ -0,0 +1,176 @@
+use crate::{
+    core::{article_assets::ArticleAssets, paths::sanitize_filename, store::content_hash},
+    Result,
+};
+use regex::{Captures, Regex};
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// 每篇文章输出目录下存放本地图片的子目录
+pub const ARTICLE_ASSETS_DIR: &str = "assets";
+
+/// 按输出路径模式生成相对路径，如 `{year}/{month}/{slug}/index.html`。
+///
+/// 模式按 `/` 分段，每段替换变量后单独清理为合法文件名，因此标题中的 `/` 不会产生额外目录；
+/// 空段和 `.`、`..` 会被忽略，输出不会跑到输出目录之外。
+pub fn render_output_path(pattern: &str, vars: &[(&str, String)]) -> PathBuf {
+    let mut path = PathBuf::new();
+    for segment in pattern.split(['/', '\\']) {
+        let segment = segment.trim();
+        if segment.is_empty() || segment == "." || segment == ".." {
+            continue;
+        }
+        let rendered = vars.iter().fold(segment.to_string(), |acc, (key, value)| {
+            acc.replace(&format!("{{{}}}", key), value)
+        });
+        path.push(sanitize_filename(&rendered));
+    }
+    if path.as_os_str().is_empty() {
+        path.push("untitled.html");
+    }
+    path
+}
+
+/// 模式中是否包含目录，包含时每篇文章的本地图片复制到输出文件旁的资源目录
+pub fn pattern_has_dirs(pattern: &str) -> bool {
+    pattern
+        .split(['/', '\\'])
+        .filter(|s| !s.trim().is_empty())
+        .count()
+        > 1
+}
+
+/// HTML 中 `<img src="...">` 的匹配：捕获组依次为 src 之前的部分、地址、结尾引号
+pub(crate) fn img_src_regex() -> &'static Regex {
+    static SRC_REGEX: OnceLock<Regex> = OnceLock::new();
+    SRC_REGEX.get_or_init(|| Regex::new(r#"(<img\b[^>]*?\bsrc=")([^"]+)(")"#).unwrap())
+}
+
+/// 本地相对路径的图片地址（不含网址、站点绝对路径和 data URI）
+pub(crate) fn is_local_src(src: &str) -> bool {
+    !(src.is_empty()
+        || src.starts_with('#')
+        || src.starts_with("//")
+        || src.starts_with('/')
+        || src.contains("://")
+        || src.starts_with("data:"))
+}
+
+/// 把 HTML 中引用的本地图片复制到 `output` 所在目录的 `assets/` 下并改写为相对地址，
+/// 返回改写后的 HTML 和复制的文件。
+///
+/// 图片按 [`ArticleAssets`] 的规则查找，同名但内容不同的图片加上哈希前缀，
+/// 避免同一目录下的多篇文章互相覆盖；找不到的图片保持原地址。
+pub fn localize_assets(
+    html: &str,
+    assets: &ArticleAssets,
+    output: &Path,
+) -> Result<(String, Vec<PathBuf>)> {
+    let regex = img_src_regex();
+
+    let assets_dir = output
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(ARTICLE_ASSETS_DIR);
+    let mut copied = Vec::new();
+    let mut error = None;
+
+    let rewritten = regex.replace_all(html, |caps: &Captures| {
+        let original = &caps[0];
+        let src = html_escape::decode_html_entities(&caps[2]).to_string();
+        let Some(path) = assets.resolve(&src) else {
+            return original.to_string();
+        };
+
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(_) => return original.to_string(),
+        };
+        let name = sanitize_filename(
+            &path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+        );
+
+        let mut target = assets_dir.join(&name);
+        if std::fs::read(&target).is_ok_and(|existing| existing != data) {
+            target = assets_dir.join(format!("{}-{}", &content_hash(&data)[..8], name));
+        }
+        let result =
+            std::fs::create_dir_all(&assets_dir).and_then(|_| std::fs::write(&target, &data));
+        if let Err(e) = result {
+            error.get_or_insert(e);
+            return original.to_string();
+        }
+
+        let file_name = target
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        copied.push(target);
+        format!(
+            "{}{}/{}{}",
+            &caps[1],
+            ARTICLE_ASSETS_DIR,
+            html_escape::encode_double_quoted_attribute(&file_name),
+            &caps[3]
+        )
+    });
+
+    match error {
+        Some(e) => Err(e.into()),
+        None => Ok((rewritten.into_owned(), copied)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_output_path() {
+        let vars = [
+            ("year", "2025".to_string()),
+            ("month", "03".to_string()),
+            ("slug", "hello-world".to_string()),
+            ("title", "A/B 测试".to_string()),
+        ];
+        assert_eq!(
+            render_output_path("{year}/{month}/{slug}/index.html", &vars),
+            PathBuf::from("2025/03/hello-world/index.html")
+        );
+        assert_eq!(
+            render_output_path("../{title}.html", &vars),
+            PathBuf::from("A_B 测试.html")
+        );
+        assert!(pattern_has_dirs("{year}/{slug}/index.html"));
+        assert!(!pattern_has_dirs("{title}_{platform}.html"));
+    }
+
+    #[test]
+    fn test_localize_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        let source_dir = dir.path().join("posts");
+        std::fs::create_dir_all(source_dir.join("img")).unwrap();
+        std::fs::write(source_dir.join("img/cover.png"), b"cover").unwrap();
+
+        // 同一目录下另一篇文章已有同名但不同的图片
+        let output = dir.path().join("out/2025/index.html");
+        std::fs::create_dir_all(output.parent().unwrap().join("assets")).unwrap();
+        std::fs::write(output.parent().unwrap().join("assets/cover.png"), b"other").unwrap();
+
+        let html = r#"<p><img src="img/cover.png" alt="封面"><img src="https://example.com/a.png"><img src="missing.png"></p>"#;
+        let assets = ArticleAssets::for_source(&source_dir.join("post.md"));
+        let (rewritten, copied) = localize_assets(html, &assets, &output).unwrap();
+
+        let hashed = format!("{}-cover.png", &content_hash(b"cover")[..8]);
+        assert!(rewritten.contains(&format!(r#"src="assets/{}""#, hashed)));
+        assert!(rewritten.contains(r#"src="https://example.com/a.png""#));
+        assert!(rewritten.contains(r#"src="missing.png""#));
+        assert_eq!(copied.len(), 1);
+        assert_eq!(std::fs::read(&copied[0]).unwrap(), b"cover");
+    }
+}
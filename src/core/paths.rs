@@ -0,0 +1,217 @@
+use std::path::{Component, Path, PathBuf};
+
+/// 大多数文件系统（NTFS、APFS、ext4）单个文件名的字节上限是255，
+/// 这里留出余量给备份时间戳等前缀
+pub const MAX_FILENAME_BYTES: usize = 200;
+
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+fn truncate_to_bytes(value: &str, max_bytes: usize) -> &str {
+    if value.len() <= max_bytes {
+        return value;
+    }
+    let mut end = max_bytes;
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
+
+/// 生成在 Windows、macOS、Linux 上都合法的文件名
+///
+/// - 替换路径分隔符、Windows 禁用字符和控制字符
+/// - 去掉结尾的点和空格（Windows 会静默删除它们）
+/// - 避开 `CON`、`NUL`、`COM1` 等 Windows 保留名
+/// - 按字节截断过长的名称（中文标题每个字占3字节），保留扩展名
+pub fn sanitize_filename(name: &str) -> String {
+    let cleaned: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | '?' | '%' | '*' | ':' | '|' | '"' | '<' | '>' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let cleaned = cleaned.trim().trim_end_matches(['.', ' ']);
+
+    let (stem, extension) = match cleaned.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() && !ext.is_empty() && ext.len() <= 10 => {
+            (stem, Some(ext))
+        }
+        _ => (cleaned, None),
+    };
+
+    let extension_len = extension.map_or(0, |ext| ext.len() + 1);
+    let stem = truncate_to_bytes(stem, MAX_FILENAME_BYTES.saturating_sub(extension_len))
+        .trim_end_matches(['.', ' ']);
+
+    let mut stem = if stem.is_empty() {
+        "untitled".to_string()
+    } else {
+        stem.to_string()
+    };
+
+    let reserved_base = stem.split('.').next().unwrap_or_default().to_uppercase();
+    if WINDOWS_RESERVED_NAMES.contains(&reserved_base.as_str()) {
+        stem.insert(0, '_');
+    }
+
+    match extension {
+        Some(ext) => format!("{}.{}", stem, ext),
+        None => stem,
+    }
+}
+
+/// UNC 路径（`\\server\share\...`）中的服务器和共享名，连同结尾的分隔符作为前缀返回，
+/// 其余部分作为第二项
+fn split_unc_prefix(raw: &str) -> Option<(String, &str)> {
+    let body = raw.strip_prefix(r"\\").or_else(|| raw.strip_prefix("//"))?;
+    let mut parts = body.splitn(3, ['/', '\\']);
+    let server = parts.next().filter(|s| !s.is_empty())?;
+    let share = parts.next().filter(|s| !s.is_empty())?;
+    Some((
+        format!(r"\\{}\{}\", server, share),
+        parts.next().unwrap_or_default(),
+    ))
+}
+
+/// 规范化配置文件中的路径：展开 `~`，并把 `/`、`\` 两种分隔符统一为当前系统的分隔符。
+/// Windows 上的 UNC 路径保留 `\\server\share` 前缀
+pub fn normalize_config_path(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+
+    let unc = split_unc_prefix(&raw).filter(|_| cfg!(windows));
+    let (mut normalized, rest) = if raw == "~" {
+        (dirs::home_dir().unwrap_or_else(|| PathBuf::from("~")), "")
+    } else if let Some(rest) = raw.strip_prefix("~/").or_else(|| raw.strip_prefix("~\\")) {
+        (dirs::home_dir().unwrap_or_else(|| PathBuf::from("~")), rest)
+    } else if let Some((prefix, rest)) = unc {
+        (PathBuf::from(prefix), rest)
+    } else if raw.starts_with('/') || raw.starts_with('\\') {
+        (PathBuf::from(std::path::MAIN_SEPARATOR_STR), &raw[1..])
+    } else {
+        (PathBuf::new(), raw.as_ref())
+    };
+
+    // Windows 盘符（如 C:）保持原样
+    let mut parts = rest.split(['/', '\\']).filter(|p| !p.is_empty()).peekable();
+    if let Some(first) = parts.peek() {
+        if first.len() == 2 && first.ends_with(':') && cfg!(windows) {
+            normalized.push(format!("{}{}", first, std::path::MAIN_SEPARATOR));
+            parts.next();
+        }
+    }
+
+    for part in parts {
+        normalized.push(part);
+    }
+
+    // 去掉多余的 `.` 组件
+    let normalized: PathBuf = normalized
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+
+    if normalized.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        normalized
+    }
+}
+
+/// 在 PATH 中查找可执行文件，Windows 上同时尝试 `.exe` 后缀
+pub fn find_in_path(name: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths).find_map(|dir| {
+        let candidate = dir.join(name);
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+        let candidate = dir.join(format!("{}.exe", name));
+        candidate.is_file().then_some(candidate)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_invalid_characters() {
+        assert_eq!(sanitize_filename("a/b\\c:d?.html"), "a_b_c_d_.html");
+        assert_eq!(sanitize_filename("tab\there.html"), "tab_here.html");
+    }
+
+    #[test]
+    fn test_sanitize_trailing_dots_and_spaces() {
+        assert_eq!(sanitize_filename("标题... "), "标题");
+        assert_eq!(sanitize_filename("..."), "untitled");
+        assert_eq!(sanitize_filename(""), "untitled");
+    }
+
+    #[test]
+    fn test_sanitize_windows_reserved_names() {
+        assert_eq!(sanitize_filename("CON.html"), "_CON.html");
+        assert_eq!(sanitize_filename("nul"), "_nul");
+        assert_eq!(sanitize_filename("com1.tar.gz"), "_com1.tar.gz");
+        assert_eq!(sanitize_filename("CONSOLE.html"), "CONSOLE.html");
+    }
+
+    #[test]
+    fn test_sanitize_long_chinese_title_keeps_extension() {
+        let title = format!("{}_wechat.html", "很长的中文标题".repeat(30));
+        let sanitized = sanitize_filename(&title);
+
+        assert!(sanitized.len() <= MAX_FILENAME_BYTES);
+        assert!(sanitized.ends_with(".html"));
+        assert!(sanitized.starts_with("很长的中文标题"));
+    }
+
+    #[test]
+    fn test_normalize_config_path_separators() {
+        let expected: PathBuf = ["output", "wechat"].iter().collect();
+        assert_eq!(normalize_config_path(Path::new("output\\wechat")), expected);
+        assert_eq!(
+            normalize_config_path(Path::new("./output/wechat/")),
+            expected
+        );
+        assert_eq!(normalize_config_path(Path::new(".")), PathBuf::from("."));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_normalize_config_path_unc() {
+        assert_eq!(
+            normalize_config_path(Path::new(r"\\server\share\markflow/output")),
+            PathBuf::from(r"\\server\share\markflow\output")
+        );
+        assert_eq!(
+            normalize_config_path(Path::new("//server/share/templates")),
+            PathBuf::from(r"\\server\share\templates")
+        );
+    }
+
+    #[test]
+    fn test_split_unc_prefix() {
+        assert_eq!(
+            split_unc_prefix(r"\\server\share\a/b"),
+            Some((r"\\server\share\".to_string(), "a/b"))
+        );
+        assert_eq!(split_unc_prefix(r"\\server"), None);
+        assert_eq!(split_unc_prefix("/usr/share"), None);
+    }
+
+    #[test]
+    fn test_normalize_config_path_home() {
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(
+                normalize_config_path(Path::new("~/.markflow/templates")),
+                home.join(".markflow").join("templates")
+            );
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::{cli::args::GeneralConfig, core::content::Content};
+
+/// 文章在自有站点上的固定链接：`general.site_url` 加上按 `general.permalink` 生成的路径
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permalink {
+    site_url: String,
+    pattern: String,
+}
+
+impl Permalink {
+    pub fn new(site_url: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self {
+            site_url: site_url.into(),
+            pattern: pattern.into(),
+        }
+    }
+
+    /// 未设置 `general.site_url` 时不生成固定链接
+    pub fn from_config(general: &GeneralConfig) -> Option<Self> {
+        general
+            .site_url
+            .as_deref()
+            .map(str::trim)
+            .filter(|url| !url.is_empty())
+            .map(|url| Self::new(url, general.permalink.clone()))
+    }
+
+    /// 路径模式中可以使用 `{slug}`、`{year}`、`{month}`、`{day}`，
+    /// 日期取 front matter `date`，没有时使用当前日期
+    pub fn url(&self, content: &Content) -> String {
+        let date = content
+            .metadata
+            .date
+            .unwrap_or_else(|| chrono::Local::now().fixed_offset());
+        let path = [
+            ("slug", content.slug()),
+            ("year", date.format("%Y").to_string()),
+            ("month", date.format("%m").to_string()),
+            ("day", date.format("%d").to_string()),
+        ]
+        .iter()
+        .fold(self.pattern.clone(), |acc, (key, value)| {
+            acc.replace(&format!("{{{}}}", key), value)
+        });
+        format!(
+            "{}/{}",
+            self.site_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MarkdownProcessor;
+
+    #[test]
+    fn test_permalink_uses_slug_and_date() {
+        let permalink = Permalink::new("https://blog.example.com/", "/{year}/{slug}/");
+        let processor = MarkdownProcessor::new().with_permalink(Some(permalink.clone()));
+
+        let content = processor
+            .process("---\ndate: 2024-03-05\nslug: async-rust\n---\n# 异步 Rust")
+            .unwrap();
+        assert_eq!(
+            content.metadata.canonical_url.as_deref(),
+            Some("https://blog.example.com/2024/async-rust/")
+        );
+
+        // 没有 slug 时由标题生成拼音 slug；front matter 中的地址优先
+        let content = processor
+            .process("---\ndate: 2024-03-05\n---\n# 异步编程")
+            .unwrap();
+        assert_eq!(
+            permalink.url(&content),
+            "https://blog.example.com/2024/yibu-biancheng/"
+        );
+        let content = processor
+            .process("---\ncanonical_url: https://a.com/x\n---\n# 标题")
+            .unwrap();
+        assert_eq!(
+            content.metadata.canonical_url.as_deref(),
+            Some("https://a.com/x")
+        );
+    }
+}
@@ -1,6 +1,32 @@
-use crate::{core::content::Content, Result};
+use crate::{
+    cli::args::{AppConfig, ImagesConfig},
+    core::{
+        alt_text::AltTextStage,
+        annotations::{AnnotationStage, UnfinishedPolicy},
+        article_assets::ArticleAssets,
+        bare_urls::BareUrlStage,
+        conditional::PlatformBlockStage,
+        content::{Content, Platform},
+        crosslink::{CrossLinkStage, SlugLinkStage},
+        http::is_offline,
+        images::ImageProcessingStage,
+        links::{LinkRewriteStage, UtmStage},
+        math::MathRenderStage,
+        media::MediaEmbedStage,
+        plugins::{enabled_plugins, PluginPlacement, PluginPlatformStage, PluginStage},
+        report::PlatformReport,
+        secrets::{SecretPolicy, SecretScanStage},
+        terminology::Terminology,
+        terminology::TerminologyStage,
+        text::suggest_tags,
+    },
+    Result,
+};
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
+
+/// 自动提取的标签数量上限（知乎最多允许 5 个）
+const MAX_AUTO_TAGS: usize = 5;
 
 #[async_trait]
 pub trait ProcessingStage: Send + Sync {
@@ -8,13 +34,99 @@ pub trait ProcessingStage: Send + Sync {
     fn name(&self) -> &'static str;
 }
 
+/// 针对单个目标平台的处理阶段，在通用阶段之后、平台适配之前执行
+pub trait PlatformStage: Send + Sync {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<()>;
+    fn name(&self) -> &'static str;
+}
+
 pub struct ProcessingPipeline {
     stages: Vec<Arc<dyn ProcessingStage>>,
+    platform_stages: Vec<Arc<dyn PlatformStage>>,
 }
 
 impl ProcessingPipeline {
     pub fn new() -> Self {
-        Self { stages: Vec::new() }
+        Self {
+            stages: Vec::new(),
+            platform_stages: Vec::new(),
+        }
+    }
+
+    /// 默认阶段之外，按配置追加可选阶段（如术语自动修正）
+    pub fn from_config(config: &AppConfig) -> Result<Self> {
+        Self::build(config, None)
+    }
+
+    /// 与 `from_config` 相同，处理 `source` 文件时使用：本地图片相对该文件查找
+    pub fn from_config_for_source(config: &AppConfig, source: &Path) -> Result<Self> {
+        Self::build(config, Some(source))
+    }
+
+    fn build(config: &AppConfig, source: Option<&Path>) -> Result<Self> {
+        let mut images = ImageProcessingStage::new(config.images.clone());
+        if let Some(source) = source {
+            images = images.with_assets(ArticleAssets::for_source(source));
+        }
+        let mut pipeline = Self::with_default_stages(images);
+        // 紧跟在平台条件块之后，条件块的注释标记已经处理完
+        let policy: UnfinishedPolicy = config.annotations.unfinished.parse()?;
+        if config.annotations.strip || policy != UnfinishedPolicy::Off {
+            pipeline =
+                pipeline.add_platform_stage(AnnotationStage::new(config.annotations.strip, policy));
+        }
+        let secrets = SecretScanStage::from_config(&config.security)?;
+        if config.security.secrets.parse::<SecretPolicy>()? != SecretPolicy::Off {
+            pipeline = pipeline.add_platform_stage(secrets);
+        }
+        if config.lint.terminology.auto_fix {
+            let terminology = Terminology::from_config(&config.lint.terminology)?;
+            pipeline = pipeline.add_stage(TerminologyStage::new(terminology));
+        }
+        if config.alt_text.enabled {
+            if is_offline() {
+                tracing::info!("离线模式，跳过图片描述生成");
+            } else {
+                pipeline = pipeline.add_stage(AltTextStage::from_config(&config.alt_text)?);
+            }
+        }
+        if config.math.enabled {
+            pipeline = pipeline.add_platform_stage(MathRenderStage::new(config.math.clone()));
+        }
+        pipeline = pipeline.add_platform_stage(SlugLinkStage::from_default_store());
+        if config.cross_link.enabled {
+            pipeline =
+                pipeline.add_platform_stage(CrossLinkStage::from_config(&config.cross_link)?);
+        }
+        let bare_urls = BareUrlStage::new(config.links.bare_urls.clone());
+        if let Some(fetcher) = bare_urls.title_fetcher(&config.network)? {
+            if is_offline() {
+                tracing::info!("离线模式，裸链接不抓取网页标题");
+            } else {
+                pipeline = pipeline.add_stage(fetcher);
+            }
+        }
+        pipeline = pipeline.add_platform_stage(bare_urls);
+        if !config.links.rewrite.is_empty() {
+            pipeline = pipeline.add_platform_stage(LinkRewriteStage::new(&config.links.rewrite)?);
+        }
+        if config.links.utm.enabled {
+            pipeline = pipeline.add_platform_stage(UtmStage::new(config.links.utm.clone()));
+        }
+        for plugin in enabled_plugins(&config.plugins)? {
+            pipeline = match plugin.manifest().stage {
+                PluginPlacement::Content => pipeline.add_stage(PluginStage::new(plugin)),
+                PluginPlacement::Platform => {
+                    pipeline.add_platform_stage(PluginPlatformStage::new(plugin))
+                }
+            };
+        }
+        Ok(pipeline)
     }
 
     pub fn add_stage<T: ProcessingStage + 'static>(mut self, stage: T) -> Self {
@@ -22,11 +134,72 @@ impl ProcessingPipeline {
         self
     }
 
-    pub async fn process(&self, mut content: Content) -> Result<Content> {
+    pub fn add_platform_stage<T: PlatformStage + 'static>(mut self, stage: T) -> Self {
+        self.platform_stages.push(Arc::new(stage));
+        self
+    }
+
+    /// 为目标平台生成内容副本，依次执行平台阶段，改动记录到平台报告
+    pub fn process_for_platform(
+        &self,
+        content: &Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+    ) -> Result<Content> {
+        self.process_for_platform_observed(content, platform, report, |_, _| Ok(()))
+    }
+
+    /// 与 `process_for_platform` 相同，每个平台阶段完成后回调 `(阶段名, 内容)`，用于调试导出
+    pub fn process_for_platform_observed<G>(
+        &self,
+        content: &Content,
+        platform: &Platform,
+        report: &mut PlatformReport,
+        mut after_stage: G,
+    ) -> Result<Content>
+    where
+        G: FnMut(&str, &Content) -> Result<()>,
+    {
+        let mut content = content.clone();
+        content.apply_platform_overrides(platform);
+        for stage in &self.platform_stages {
+            tracing::debug!("执行平台阶段 {} ({})", stage.name(), platform);
+            stage.process(&mut content, platform, report)?;
+            after_stage(stage.name(), &content)?;
+        }
+        Ok(content)
+    }
+
+    pub async fn process(&self, content: Content) -> Result<Content> {
+        self.process_with_progress(content, |_, _, _| {}).await
+    }
+
+    /// 与 `process` 相同，每个阶段开始前回调 `(当前阶段序号, 阶段总数, 阶段名)`，用于上报进度
+    pub async fn process_with_progress<F>(&self, content: Content, on_stage: F) -> Result<Content>
+    where
+        F: FnMut(usize, usize, &str) + Send,
+    {
+        self.process_observed(content, on_stage, |_, _| Ok(()))
+            .await
+    }
+
+    /// 与 `process_with_progress` 相同，每个阶段完成后再回调 `(阶段名, 内容)`，用于调试导出
+    pub async fn process_observed<F, G>(
+        &self,
+        mut content: Content,
+        mut on_stage: F,
+        mut after_stage: G,
+    ) -> Result<Content>
+    where
+        F: FnMut(usize, usize, &str) + Send,
+        G: FnMut(&str, &Content) -> Result<()> + Send,
+    {
         tracing::info!("开始处理流水线，包含 {} 个阶段", self.stages.len());
+        let started = std::time::Instant::now();
 
         for (i, stage) in self.stages.iter().enumerate() {
             tracing::debug!("执行阶段 {}: {}", i + 1, stage.name());
+            on_stage(i, self.stages.len(), stage.name());
 
             match stage.process(&mut content).await {
                 Ok(_) => {
@@ -37,40 +210,20 @@ impl ProcessingPipeline {
                     return Err(e);
                 }
             }
+            after_stage(stage.name(), &content)?;
         }
 
+        crate::core::metrics().observe_processing(started.elapsed());
         tracing::info!("处理流水线完成");
         Ok(content)
     }
-}
-
-// 图片处理阶段
-pub struct ImageProcessingStage;
-
-#[async_trait]
-impl ProcessingStage for ImageProcessingStage {
-    async fn process(&self, content: &mut Content) -> Result<()> {
-        // 提取并处理图片
-        let image_regex = regex::Regex::new(r"!\[([^\]]*)\]\(([^)]+)\)").unwrap();
-
-        for capture in image_regex.captures_iter(&content.markdown.clone()) {
-            let alt = &capture[1];
-            let src = &capture[2];
-
-            tracing::debug!("处理图片: {} ({})", src, alt);
-
-            // 这里可以添加图片处理逻辑：
-            // - 下载远程图片
-            // - 压缩图片
-            // - 上传到CDN
-            // - 生成不同尺寸版本
-        }
 
-        Ok(())
+    pub fn len(&self) -> usize {
+        self.stages.len()
     }
 
-    fn name(&self) -> &'static str {
-        "图片处理"
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
     }
 }
 
@@ -171,19 +324,39 @@ impl ContentEnhancementStage {
         ];
 
         let markdown_lower = markdown.to_lowercase();
-        keywords
+        let mut tags: Vec<String> = keywords
             .iter()
             .filter(|&keyword| markdown_lower.contains(&keyword.to_lowercase()))
             .map(|&keyword| keyword.to_string())
-            .collect()
+            .collect();
+
+        // 不足的部分用分词后的 TF-IDF 关键词补齐
+        for tag in suggest_tags(markdown, MAX_AUTO_TAGS) {
+            if tags.len() >= MAX_AUTO_TAGS {
+                break;
+            }
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(&tag)) {
+                tags.push(tag);
+            }
+        }
+        tags.truncate(MAX_AUTO_TAGS);
+        tags
     }
 }
 
-impl Default for ProcessingPipeline {
-    fn default() -> Self {
+impl ProcessingPipeline {
+    fn with_default_stages(images: ImageProcessingStage) -> Self {
         Self::new()
-            .add_stage(ImageProcessingStage)
+            .add_stage(MediaEmbedStage)
+            .add_stage(images)
             .add_stage(LinkValidationStage)
             .add_stage(ContentEnhancementStage)
+            .add_platform_stage(PlatformBlockStage)
+    }
+}
+
+impl Default for ProcessingPipeline {
+    fn default() -> Self {
+        Self::with_default_stages(ImageProcessingStage::new(ImagesConfig::default()))
     }
 }
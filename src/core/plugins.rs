@@ -0,0 +1,435 @@
+use crate::{
+    cli::args::PluginsConfig,
+    core::{
+        content::{Content, Platform},
+        pipeline::{PlatformStage, ProcessingStage},
+        report::PlatformReport,
+    },
+    error::Error,
+    Result,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+const MANIFEST_FILE: &str = "plugin.toml";
+
+/// 插件在流水线中的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginPlacement {
+    /// 通用阶段，所有平台共用一次处理结果
+    Content,
+    /// 平台阶段，每个目标平台各执行一次
+    Platform,
+}
+
+impl std::fmt::Display for PluginPlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PluginPlacement::Content => write!(f, "content"),
+            PluginPlacement::Platform => write!(f, "platform"),
+        }
+    }
+}
+
+/// 插件清单，保存在插件目录的 `plugin.toml`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub version: String,
+    pub stage: PluginPlacement,
+    /// 要执行的程序及参数，相对路径基于插件目录
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+impl PluginManifest {
+    fn validate(&self) -> Result<()> {
+        validate_name(&self.name)?;
+        let valid_version = !self.version.is_empty()
+            && self
+                .version
+                .split(['-', '+'])
+                .next()
+                .unwrap_or_default()
+                .split('.')
+                .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+        if !valid_version {
+            return Err(Error::Config(format!(
+                "插件 {} 的版本号无效: {}",
+                self.name, self.version
+            )));
+        }
+        if self
+            .command
+            .first()
+            .is_none_or(|program| program.is_empty())
+        {
+            return Err(Error::Config(format!("插件 {} 未配置 command", self.name)));
+        }
+        Ok(())
+    }
+}
+
+/// 已安装并通过校验的插件
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    manifest: PluginManifest,
+    dir: PathBuf,
+}
+
+impl Plugin {
+    /// 读取并校验插件目录中的清单
+    pub fn load(dir: &Path) -> Result<Self> {
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let raw = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| Error::Config(format!("无法读取插件清单 {:?}: {}", manifest_path, e)))?;
+        let manifest: PluginManifest = toml::from_str(&raw)
+            .map_err(|e| Error::Config(format!("插件清单解析失败 {:?}: {}", manifest_path, e)))?;
+        manifest.validate()?;
+        Ok(Self {
+            manifest,
+            dir: dir.to_path_buf(),
+        })
+    }
+
+    pub fn manifest(&self) -> &PluginManifest {
+        &self.manifest
+    }
+
+    pub fn name(&self) -> &str {
+        &self.manifest.name
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// 把内容以 JSON 写入插件的标准输入，从标准输出读回处理后的内容；输出为空表示不修改
+    fn run(&self, content: &mut Content, platform: Option<&Platform>) -> Result<()> {
+        let (program, args) = self
+            .manifest
+            .command
+            .split_first()
+            .ok_or_else(|| Error::Config(format!("插件 {} 未配置 command", self.name())))?;
+        let program = if program.starts_with("./") || program.starts_with(".\\") {
+            self.dir.join(program)
+        } else {
+            PathBuf::from(program)
+        };
+
+        let mut command = Command::new(&program);
+        command
+            .args(args)
+            .current_dir(&self.dir)
+            .env("MARKFLOW_PLUGIN_STAGE", self.manifest.stage.to_string())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(platform) = platform {
+            command.env("MARKFLOW_PLATFORM", platform.to_string());
+        }
+        let mut child = command
+            .spawn()
+            .map_err(|e| Error::Other(format!("无法启动插件 {}: {}", self.name(), e)))?;
+
+        // 另起线程写入，避免插件在读完输入前先写满输出管道造成死锁
+        let input = serde_json::to_vec(content)?;
+        let mut stdin = child.stdin.take().expect("stdin 已设置为 piped");
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+        let _ = writer.join();
+
+        if !output.status.success() {
+            return Err(Error::Other(format!(
+                "插件 {} 执行失败（{}）: {}",
+                self.name(),
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        if output.stdout.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+        *content = serde_json::from_slice(&output.stdout)
+            .map_err(|e| Error::Other(format!("插件 {} 输出的内容无法解析: {}", self.name(), e)))?;
+        Ok(())
+    }
+}
+
+/// 管理插件目录（默认 `~/.markflow/plugins`）下的插件，每个插件一个子目录
+#[derive(Debug, Clone)]
+pub struct PluginManager {
+    root: PathBuf,
+}
+
+impl PluginManager {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// 列出所有插件目录及其校验结果，按名称排序
+    pub fn list(&self) -> Result<Vec<(String, Result<Plugin>)>> {
+        if !self.root.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut names: Vec<String> = std::fs::read_dir(&self.root)?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        names.sort();
+        Ok(names
+            .into_iter()
+            .map(|name| {
+                let plugin = self.get(&name);
+                (name, plugin)
+            })
+            .collect())
+    }
+
+    /// 加载并校验插件，目录名必须与清单中的 `name` 一致
+    pub fn get(&self, name: &str) -> Result<Plugin> {
+        validate_name(name)?;
+        let dir = self.root.join(name);
+        if !dir.is_dir() {
+            return Err(Error::Config(format!("插件不存在: {}", name)));
+        }
+        let plugin = Plugin::load(&dir)?;
+        if plugin.name() != name {
+            return Err(Error::Config(format!(
+                "插件目录 {} 与清单中的名称 {} 不一致",
+                name,
+                plugin.name()
+            )));
+        }
+        Ok(plugin)
+    }
+
+    /// 从本地目录安装插件（复制到插件目录），安装前校验清单
+    pub fn install(&self, source: &Path, force: bool) -> Result<Plugin> {
+        let manifest = Plugin::load(source)?.manifest;
+        let target = self.root.join(&manifest.name);
+        if target.exists() {
+            if !force {
+                return Err(Error::Config(format!(
+                    "插件已安装: {}（使用 --force 覆盖）",
+                    manifest.name
+                )));
+            }
+            std::fs::remove_dir_all(&target)?;
+        }
+        copy_dir(source, &target)?;
+        self.get(&manifest.name)
+    }
+
+    /// 删除插件目录；清单损坏的插件也可以删除
+    pub fn remove(&self, name: &str) -> Result<()> {
+        validate_name(name)?;
+        let dir = self.root.join(name);
+        if !dir.is_dir() {
+            return Err(Error::Config(format!("插件不存在: {}", name)));
+        }
+        std::fs::remove_dir_all(dir)?;
+        Ok(())
+    }
+}
+
+fn copy_dir(source: &Path, target: &Path) -> Result<()> {
+    std::fs::create_dir_all(target)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let path = entry.path();
+        let destination = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&path, &destination)?;
+        } else {
+            std::fs::copy(&path, &destination)?;
+        }
+    }
+    Ok(())
+}
+
+/// 插件名只允许字母、数字、`-` 和 `_`，防止通过路径穿越访问其他目录
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Config(format!("无效的插件名称: {}", name)))
+    }
+}
+
+/// 按配置加载已启用的插件，保持 `plugins.enabled` 中的顺序
+pub fn enabled_plugins(config: &PluginsConfig) -> Result<Vec<Plugin>> {
+    let manager = PluginManager::new(&config.dir);
+    config
+        .enabled
+        .iter()
+        .map(|name| manager.get(name))
+        .collect()
+}
+
+/// 以外部程序实现的通用处理阶段
+pub struct PluginStage {
+    plugin: Plugin,
+}
+
+impl PluginStage {
+    pub fn new(plugin: Plugin) -> Self {
+        Self { plugin }
+    }
+}
+
+#[async_trait]
+impl ProcessingStage for PluginStage {
+    async fn process(&self, content: &mut Content) -> Result<()> {
+        tracing::debug!("执行插件 {}", self.plugin.name());
+        self.plugin.run(content, None)
+    }
+
+    fn name(&self) -> &'static str {
+        "插件"
+    }
+}
+
+/// 以外部程序实现的平台阶段，通过环境变量 `MARKFLOW_PLATFORM` 获知目标平台
+pub struct PluginPlatformStage {
+    plugin: Plugin,
+}
+
+impl PluginPlatformStage {
+    pub fn new(plugin: Plugin) -> Self {
+        Self { plugin }
+    }
+}
+
+impl PlatformStage for PluginPlatformStage {
+    fn process(
+        &self,
+        content: &mut Content,
+        platform: &Platform,
+        _report: &mut PlatformReport,
+    ) -> Result<()> {
+        tracing::debug!("执行插件 {} ({})", self.plugin.name(), platform);
+        self.plugin.run(content, Some(platform))
+    }
+
+    fn name(&self) -> &'static str {
+        "插件"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_plugin(dir: &Path, manifest: &str) {
+        std::fs::create_dir_all(dir).unwrap();
+        std::fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+    }
+
+    #[test]
+    fn test_install_list_and_remove() {
+        let source = TempDir::new().unwrap();
+        let root = TempDir::new().unwrap();
+        let manager = PluginManager::new(root.path());
+
+        let toc = source.path().join("toc");
+        write_plugin(
+            &toc,
+            "name = \"toc\"\nversion = \"0.2.0\"\nstage = \"content\"\ncommand = [\"./toc.sh\"]\n",
+        );
+        std::fs::write(toc.join("toc.sh"), "#!/bin/sh\n").unwrap();
+
+        let plugin = manager.install(&toc, false).unwrap();
+        assert_eq!(plugin.manifest().stage, PluginPlacement::Content);
+        assert!(root.path().join("toc").join("toc.sh").exists());
+        assert!(manager.install(&toc, false).is_err());
+        assert!(manager.install(&toc, true).is_ok());
+
+        write_plugin(
+            &root.path().join("broken"),
+            "name = \"broken\"\nversion = \"x\"\nstage = \"content\"\ncommand = [\"a\"]\n",
+        );
+        let listed = manager.list().unwrap();
+        assert_eq!(listed.len(), 2);
+        assert!(matches!(listed[0], (ref name, Err(_)) if name == "broken"));
+        assert!(matches!(listed[1], (ref name, Ok(_)) if name == "toc"));
+
+        manager.remove("broken").unwrap();
+        manager.remove("toc").unwrap();
+        assert!(manager.list().unwrap().is_empty());
+        assert!(manager.remove("../etc").is_err());
+    }
+
+    #[test]
+    fn test_manifest_validation() {
+        let dir = TempDir::new().unwrap();
+        let manager = PluginManager::new(dir.path());
+
+        // 未知的阶段位置
+        write_plugin(
+            &dir.path().join("a"),
+            "name = \"a\"\nversion = \"1.0\"\nstage = \"render\"\ncommand = [\"a\"]\n",
+        );
+        assert!(manager.get("a").is_err());
+
+        // 目录名与清单名称不一致
+        write_plugin(
+            &dir.path().join("b"),
+            "name = \"other\"\nversion = \"1.0\"\nstage = \"platform\"\ncommand = [\"b\"]\n",
+        );
+        assert!(manager.get("b").is_err());
+
+        write_plugin(
+            &dir.path().join("c"),
+            "name = \"c\"\nversion = \"1.0.0-beta\"\nstage = \"platform\"\ncommand = []\n",
+        );
+        assert!(manager.get("c").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_plugin_stage_rewrites_content() {
+        let dir = TempDir::new().unwrap();
+        let plugin_dir = dir.path().join("upper");
+        write_plugin(
+            &plugin_dir,
+            "name = \"upper\"\nversion = \"1.0.0\"\nstage = \"platform\"\ncommand = [\"sh\", \"-c\", \"sed \\\"s/hello/$MARKFLOW_PLATFORM/g\\\"\"]\n",
+        );
+        let plugin = PluginManager::new(dir.path()).get("upper").unwrap();
+
+        let mut content = crate::core::MarkdownProcessor::new()
+            .process("# T\n\nhello\n")
+            .unwrap();
+        let mut report = PlatformReport::new(Platform::Zhihu);
+        PluginPlatformStage::new(plugin.clone())
+            .process(&mut content, &Platform::Zhihu, &mut report)
+            .unwrap();
+        assert!(content.html.contains("zhihu"));
+        assert!(!content.html.contains("hello"));
+
+        // 空输出表示不修改内容
+        let mut noop = plugin;
+        noop.manifest.command = vec!["true".to_string()];
+        let before = content.html.clone();
+        PluginStage::new(noop).process(&mut content).await.unwrap();
+        assert_eq!(content.html, before);
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{content::Platform, document::active_template, lock::write_atomic, store::content_hash},
+    error::Error,
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+/// 目录批量处理的缓存清单，保存在输出目录中
+pub const PROCESS_CACHE_FILE: &str = ".markflow-cache.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    fingerprint: String,
+    outputs: Vec<PathBuf>,
+}
+
+/// 目录批量处理的增量缓存：记录每个源文件上次处理时的指纹和输出文件，
+/// 指纹未变且输出文件都还在时跳过该文件
+#[derive(Debug, Default)]
+pub struct ProcessCache {
+    path: PathBuf,
+    entries: BTreeMap<PathBuf, CacheEntry>,
+}
+
+impl ProcessCache {
+    pub fn load(output_dir: &Path) -> Result<Self> {
+        let path = output_dir.join(PROCESS_CACHE_FILE);
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).map_err(|e| {
+                Error::Other(format!(
+                    "无法读取处理缓存 {}: {}（可以删除该文件或使用 --force）",
+                    path.display(),
+                    e
+                ))
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, entries })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        write_atomic(&self.path, serde_json::to_string_pretty(&self.entries)?)
+    }
+
+    /// 所有文件共用的处理设置：配置、主题 CSS 和当前文档模板的内容、目标平台和标题降级。
+    /// 主题或模板只改了文件内容、配置中的路径不变时也需要重新处理
+    pub fn settings(
+        config: &AppConfig,
+        platform: Option<&Platform>,
+        shift_headings: Option<u8>,
+    ) -> Result<String> {
+        // 读取失败时由处理过程报告错误，这里只影响指纹
+        let theme = config
+            .wechat
+            .theme
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok());
+        let template = active_template(&config.templates)
+            .ok()
+            .flatten()
+            .and_then(|path| std::fs::read_to_string(path).ok());
+        Ok(format!(
+            "{}\0{:?}\0{:?}\0{:?}\0{:?}",
+            serde_json::to_string(config)?,
+            theme,
+            template,
+            platform,
+            shift_headings
+        ))
+    }
+
+    /// 源文件内容、从目录继承的 front matter 和处理设置共同决定的指纹，任何一项变化都需要重新处理
+    pub fn fingerprint(
+        markdown: &str,
+        inherited: &HashMap<String, Value>,
+        settings: &str,
+    ) -> Result<String> {
+        let inherited: BTreeMap<_, _> = inherited.iter().collect();
+        Ok(content_hash(
+            format!(
+                "{}\0{}\0{}",
+                settings,
+                serde_json::to_string(&inherited)?,
+                markdown
+            )
+            .as_bytes(),
+        ))
+    }
+
+    pub fn is_fresh(&self, source: &Path, fingerprint: &str) -> bool {
+        self.entries.get(source).is_some_and(|entry| {
+            entry.fingerprint == fingerprint && entry.outputs.iter().all(|path| path.is_file())
+        })
+    }
+
+    pub fn update(&mut self, source: PathBuf, fingerprint: String, outputs: Vec<PathBuf>) {
+        self.entries.insert(
+            source,
+            CacheEntry {
+                fingerprint,
+                outputs,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_roundtrip_and_invalidation() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = dir.path().join("post_wechat.html");
+        std::fs::write(&output, "<p>正文</p>").unwrap();
+        let source = dir.path().join("post.md");
+        let none = HashMap::new();
+        let fingerprint = |markdown: &str, settings: &str| {
+            ProcessCache::fingerprint(markdown, &none, settings).unwrap()
+        };
+
+        let mut cache = ProcessCache::load(dir.path()).unwrap();
+        assert!(!cache.is_fresh(&source, &fingerprint("# 标题", "wechat")));
+        cache.update(
+            source.clone(),
+            fingerprint("# 标题", "wechat"),
+            vec![output.clone()],
+        );
+        cache.save().unwrap();
+
+        let cache = ProcessCache::load(dir.path()).unwrap();
+        assert!(cache.is_fresh(&source, &fingerprint("# 标题", "wechat")));
+        assert!(!cache.is_fresh(&source, &fingerprint("# 标题", "zhihu")));
+        assert!(!cache.is_fresh(&source, &fingerprint("# 新标题", "wechat")));
+        let inherited = HashMap::from([("author".to_string(), Value::from("张三"))]);
+        assert!(!cache.is_fresh(
+            &source,
+            &ProcessCache::fingerprint("# 标题", &inherited, "wechat").unwrap()
+        ));
+
+        // 输出文件被删除后需要重新生成
+        std::fs::remove_file(&output).unwrap();
+        assert!(!cache.is_fresh(&source, &fingerprint("# 标题", "wechat")));
+    }
+
+    #[test]
+    fn test_settings_cover_theme_and_template_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let theme = dir.path().join("theme.css");
+        let template = dir.path().join("post.html");
+        std::fs::write(&theme, "h2 { color: red; }").unwrap();
+        std::fs::write(&template, "{{ content }}").unwrap();
+        let mut config = AppConfig::default();
+        config.wechat.theme = Some(theme.clone());
+        config.templates.templates_dir = dir.path().to_path_buf();
+        config.templates.default_template = Some("post".to_string());
+        let settings = || ProcessCache::settings(&config, Some(&Platform::WeChat), None).unwrap();
+
+        let before = settings();
+        assert_eq!(settings(), before);
+        std::fs::write(&theme, "h2 { color: blue; }").unwrap();
+        let themed = settings();
+        assert_ne!(themed, before);
+        std::fs::write(&template, "<article>{{ content }}</article>").unwrap();
+        assert_ne!(settings(), themed);
+    }
+}
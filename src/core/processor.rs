@@ -1,5 +1,16 @@
 use crate::{
-    core::content::{Content, ContentMetadata},
+    core::{
+        block_cache::BlockCache,
+        content::{Content, ContentMetadata},
+        frontmatter::{front_matter_text, parse_front_matter_fields, FrontMatter},
+        language::detect_language,
+        math::{protect_escaped_dollars, restore_escaped_dollars},
+        permalink::Permalink,
+        source_map::{extract_source_map, SourceMap},
+        text::plain_text,
+        time::TimeZoneSetting,
+        typography::{LatinSmartPunctuation, SmartPunctuation},
+    },
     error::Error,
     Result,
 };
@@ -9,11 +20,19 @@ use comrak::{
     parse_document, Arena, ComrakOptions,
 };
 use regex::Regex;
-use std::collections::HashMap;
+use serde_json::Value;
+use std::{collections::HashMap, sync::OnceLock};
 
+#[derive(Clone)]
 pub struct MarkdownProcessor {
     options: ComrakOptions,
     front_matter_regex: Regex,
+    timezone: TimeZoneSetting,
+    heading_shift: Option<u8>,
+    smart_punctuation: SmartPunctuation,
+    inherited_front_matter: HashMap<String, Value>,
+    permalink: Option<Permalink>,
+    math: bool,
 }
 
 impl MarkdownProcessor {
@@ -35,8 +54,8 @@ impl MarkdownProcessor {
         options.render.github_pre_lang = true;
         options.render.unsafe_ = true; // 允许原始HTML
 
-        // 解析选项
-        options.parse.smart = true;
+        // 解析选项；智能标点默认只处理拉丁文片段，见 `with_smart_punctuation`
+        options.parse.smart = false;
         options.parse.default_info_string = Some("text".to_string());
 
         let front_matter_regex = Regex::new(r"^---\n([\s\S]*?)\n---\n").unwrap();
@@ -44,17 +63,117 @@ impl MarkdownProcessor {
         Self {
             options,
             front_matter_regex,
+            timezone: TimeZoneSetting::default(),
+            heading_shift: None,
+            smart_punctuation: SmartPunctuation::default(),
+            inherited_front_matter: HashMap::new(),
+            permalink: None,
+            math: false,
         }
     }
 
+    /// 设置解析 front matter 中不带时区的日期时使用的时区
+    pub fn with_timezone(mut self, timezone: TimeZoneSetting) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// 是否把正文中的裸网址自动识别为链接（`links.autolink`）
+    pub fn with_autolink(mut self, enabled: bool) -> Self {
+        self.options.extension.autolink = enabled;
+        self
+    }
+
+    /// 把所有标题降低 `levels` 级（`--shift-headings`），优先于 front matter 中的 `shift_headings`
+    pub fn with_heading_shift(mut self, levels: Option<u8>) -> Self {
+        self.heading_shift = levels;
+        self
+    }
+
+    /// 智能标点的作用范围（`general.smart_punctuation`）
+    pub fn with_smart_punctuation(mut self, mode: SmartPunctuation) -> Self {
+        self.smart_punctuation = mode;
+        self.options.parse.smart = mode == SmartPunctuation::All;
+        self
+    }
+
+    /// 从目录级默认文件继承的 front matter（见 [`inherited_front_matter`](crate::core::inherited_front_matter)），
+    /// 文章自身的同名字段优先
+    pub fn with_inherited_front_matter(mut self, fields: HashMap<String, Value>) -> Self {
+        self.inherited_front_matter = fields;
+        self
+    }
+
+    /// 按自有站点的固定链接为文章生成规范地址（`general.site_url`），
+    /// front matter 中的 `canonical_url` 优先
+    pub fn with_permalink(mut self, permalink: Option<Permalink>) -> Self {
+        self.permalink = permalink;
+        self
+    }
+
+    /// 是否开启了公式渲染（`math.enabled`）。开启时转义的 `\$` 输出为 `&#36;`，
+    /// 不会被公式渲染阶段当作定界符
+    pub fn with_math(mut self, enabled: bool) -> Self {
+        self.math = enabled;
+        self
+    }
+
     pub fn process(&self, markdown: &str) -> Result<Content> {
+        self.process_inner(markdown, |processor, body| processor.markdown_to_html(body))
+    }
+
+    /// 与 `process` 相同，但按顶层块缓存 HTML，供实时预览反复渲染同一篇文章
+    pub fn process_cached(&self, markdown: &str, cache: &mut BlockCache) -> Result<Content> {
+        self.process_inner(markdown, |processor, body| {
+            processor.markdown_to_html_cached(body, cache)
+        })
+    }
+
+    /// 与 `process` 相同，同时返回输出 HTML 中各元素对应的 Markdown 行号，
+    /// 供编辑器实现点击预览定位源文、把问题标注到具体行。
+    /// 行号按传入的完整文本（含 front matter）计算，`content.html` 中不保留定位属性
+    pub fn process_with_source_map(&self, markdown: &str) -> Result<(Content, SourceMap)> {
+        let line_offset = self
+            .front_matter_regex
+            .find(markdown)
+            .map(|m| m.as_str().matches('\n').count())
+            .unwrap_or(0);
+        let mut content = self.process_inner(markdown, |processor, body| {
+            let mut options = processor.options.clone();
+            options.render.sourcepos = true;
+            processor.render_html(body, &options)
+        })?;
+        let (html, source_map) = extract_source_map(&content.html, line_offset);
+        content.html = html;
+        Ok((content, source_map))
+    }
+
+    fn process_inner(
+        &self,
+        markdown: &str,
+        render: impl FnOnce(&Self, &str) -> Result<String>,
+    ) -> Result<Content> {
         tracing::info!("开始处理Markdown内容");
 
         // 解析Front Matter
         let (front_matter, content_markdown) = self.parse_front_matter(markdown)?;
 
         // 从front matter创建metadata
-        let metadata = self.create_metadata_from_front_matter(&front_matter)?;
+        let mut metadata = self.create_metadata_from_front_matter(&front_matter)?;
+
+        // 按文章语言调整智能标点，front matter `lang` 优先
+        let language = metadata
+            .language
+            .unwrap_or_else(|| detect_language(&plain_text(&content_markdown)));
+        metadata.language = Some(language);
+        let smart_punctuation = language.smart_punctuation(self.smart_punctuation);
+        let tuned;
+        let processor = if smart_punctuation == self.smart_punctuation {
+            self
+        } else {
+            tuned = self.clone().with_smart_punctuation(smart_punctuation);
+            &tuned
+        };
 
         // 提取标题
         let title = self.extract_title(&content_markdown, &front_matter)?;
@@ -64,8 +183,18 @@ impl MarkdownProcessor {
         content.metadata = metadata;
 
         // 处理Markdown
-        let html = self.markdown_to_html(&content_markdown)?;
-        content.html = html;
+        content.html = render(processor, &content_markdown)?;
+        let shift = self
+            .heading_shift
+            .or(content.metadata.shift_headings)
+            .unwrap_or(0);
+        if shift > 0 {
+            content.html = shift_headings(&content.html, shift);
+        }
+
+        if content.metadata.canonical_url.is_none() {
+            content.metadata.canonical_url = self.permalink.as_ref().map(|p| p.url(&content));
+        }
 
         // 计算阅读时间
         content.calculate_reading_time();
@@ -74,22 +203,15 @@ impl MarkdownProcessor {
         Ok(content)
     }
 
-    fn parse_front_matter(&self, markdown: &str) -> Result<(HashMap<String, String>, String)> {
-        let mut front_matter = HashMap::new();
+    fn parse_front_matter(&self, markdown: &str) -> Result<(HashMap<String, Value>, String)> {
+        let mut front_matter = self.inherited_front_matter.clone();
         let content_markdown;
 
         if let Some(captures) = self.front_matter_regex.captures(markdown) {
             let yaml_content = captures.get(1).unwrap().as_str();
             content_markdown = self.front_matter_regex.replace(markdown, "").into_owned();
 
-            // 简单的YAML解析（仅支持key: value格式）
-            for line in yaml_content.lines() {
-                if let Some((key, value)) = line.split_once(':') {
-                    let key = key.trim().to_string();
-                    let value = value.trim().trim_matches('"').to_string();
-                    front_matter.insert(key, value);
-                }
-            }
+            front_matter.extend(parse_front_matter_fields(yaml_content)?);
         } else {
             content_markdown = markdown.to_string();
         }
@@ -99,51 +221,78 @@ impl MarkdownProcessor {
 
     fn create_metadata_from_front_matter(
         &self,
-        front_matter: &HashMap<String, String>,
+        raw_front_matter: &HashMap<String, Value>,
     ) -> Result<ContentMetadata> {
-        let mut metadata = ContentMetadata::default();
+        let front_matter = FrontMatter::from_fields(raw_front_matter)?;
+        let mut metadata = ContentMetadata {
+            author: front_matter.author,
+            description: front_matter.description,
+            tags: front_matter.tags,
+            cover_image: front_matter.cover,
+            title_variants: front_matter.titles,
+            draft: front_matter.draft,
+            platform_overrides: front_matter.overrides,
+            ..Default::default()
+        };
+
+        if let Some(date) = front_matter.date {
+            metadata.date = self.timezone.parse_datetime(&date);
+            if metadata.date.is_none() {
+                tracing::warn!("无法解析front matter日期: {}", date);
+            }
+        }
 
-        if let Some(author) = front_matter.get("author") {
-            metadata.author = Some(author.clone());
+        if let Some(review_after) = front_matter.review_after {
+            metadata.review_after = self
+                .timezone
+                .parse_datetime(&review_after)
+                .map(|d| d.date_naive());
+            if metadata.review_after.is_none() {
+                tracing::warn!("无法解析front matter复查日期: {}", review_after);
+            }
         }
 
-        if let Some(description) = front_matter.get("description") {
-            metadata.description = Some(description.clone());
+        if let Some(slug) = front_matter.slug {
+            metadata.slug =
+                Some(slug.trim().trim_matches('/').to_string()).filter(|s| !s.is_empty());
         }
 
-        if let Some(tags_str) = front_matter.get("tags") {
-            metadata.tags = tags_str
-                .split(',')
-                .map(|s| s.trim().to_string())
-                .filter(|s| !s.is_empty())
-                .collect();
+        if let Some(url) = front_matter.canonical_url {
+            metadata.canonical_url = Some(url.trim().to_string()).filter(|u| !u.is_empty());
         }
 
-        if let Some(cover) = front_matter.get("cover") {
-            metadata.cover_image = Some(cover.clone());
+        if let Some(lang) = front_matter.lang {
+            metadata.language = lang.parse().ok();
+            if metadata.language.is_none() {
+                tracing::warn!("无法识别front matter语言 {}，按正文检测", lang);
+            }
         }
 
-        // 添加自定义字段
-        for (key, value) in front_matter {
-            if !matches!(
-                key.as_str(),
-                "title" | "author" | "description" | "tags" | "cover"
-            ) {
-                metadata.custom_fields.insert(key.clone(), value.clone());
+        if let Some(shift) = front_matter.shift_headings {
+            metadata.shift_headings = shift.parse().ok();
+            if metadata.shift_headings.is_none() {
+                tracing::warn!("无法解析front matter标题降级数: {}", shift);
             }
         }
 
+        // 添加自定义字段
+        metadata.custom_fields = raw_front_matter
+            .iter()
+            .filter(|(key, value)| FrontMatter::is_custom_field(key, value))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+
         Ok(metadata)
     }
 
     fn extract_title(
         &self,
         markdown: &str,
-        front_matter: &HashMap<String, String>,
+        front_matter: &HashMap<String, Value>,
     ) -> Result<String> {
         // 首先检查front matter中的title
-        if let Some(title) = front_matter.get("title") {
-            return Ok(title.clone());
+        if let Some(title) = front_matter.get("title").and_then(front_matter_text) {
+            return Ok(title);
         }
 
         // 从markdown内容中提取第一个一级标题
@@ -159,19 +308,70 @@ impl MarkdownProcessor {
     }
 
     fn markdown_to_html(&self, markdown: &str) -> Result<String> {
+        self.render_html(markdown, &self.options)
+    }
+
+    fn render_html(&self, markdown: &str, options: &ComrakOptions) -> Result<String> {
+        let protected;
+        let markdown: &str = if self.math {
+            protected = protect_escaped_dollars(markdown);
+            &protected
+        } else {
+            markdown
+        };
+
         let arena = Arena::new();
-        let root = parse_document(&arena, markdown, &self.options);
+        let root = parse_document(&arena, markdown, options);
 
         // 可以在这里对AST进行后处理
         self.process_ast(&arena, root)?;
 
         let mut html = vec![];
-        format_html(root, &self.options, &mut html)
+        format_html(root, options, &mut html)
             .map_err(|e| Error::Markdown(format!("HTML生成失败: {}", e)))?;
 
-        String::from_utf8(html).map_err(|e| Error::Markdown(format!("HTML编码转换失败: {}", e)))
+        let html = String::from_utf8(html)
+            .map_err(|e| Error::Markdown(format!("HTML编码转换失败: {}", e)))?;
+        Ok(if self.math {
+            restore_escaped_dollars(html)
+        } else {
+            html
+        })
     }
 
+    /// 逐个顶层块渲染并缓存。脚注和链接引用定义会影响其他块的渲染，
+    /// 含有它们的文档整体渲染
+    fn markdown_to_html_cached(&self, markdown: &str, cache: &mut BlockCache) -> Result<String> {
+        static REFERENCE_REGEX: OnceLock<Regex> = OnceLock::new();
+        let reference_regex =
+            REFERENCE_REGEX.get_or_init(|| Regex::new(r"(?m)^ {0,3}\[[^\]]+\]:").unwrap());
+        if reference_regex.is_match(markdown) {
+            return self.markdown_to_html(markdown);
+        }
+
+        let lines: Vec<&str> = markdown.split_inclusive('\n').collect();
+        let arena = Arena::new();
+        let root = parse_document(&arena, markdown, &self.options);
+
+        let mut blocks = Vec::new();
+        for node in root.children() {
+            let pos = node.data.borrow().sourcepos;
+            if pos.start.line == 0 || pos.end.line < pos.start.line || pos.end.line > lines.len() {
+                return self.markdown_to_html(markdown);
+            }
+            blocks.push(lines[pos.start.line - 1..pos.end.line].concat());
+        }
+
+        cache.begin(&format!("{:?} {}", self.smart_punctuation, self.math));
+        let mut html = String::new();
+        for block in &blocks {
+            html.push_str(&cache.get_or_render(block, |b| self.markdown_to_html(b))?);
+        }
+        cache.finish();
+        Ok(html)
+    }
+
+    #[allow(clippy::collapsible_match)]
     fn process_ast<'a>(&self, _arena: &Arena<AstNode>, root: &'a AstNode<'a>) -> Result<()> {
         // 遍历AST节点进行自定义处理
         self.iter_nodes(root, &|node| {
@@ -200,6 +400,20 @@ impl MarkdownProcessor {
             Ok(())
         })?;
 
+        if self.smart_punctuation == SmartPunctuation::Latin {
+            let mut smart = LatinSmartPunctuation::default();
+            for node in root.descendants() {
+                let mut data = node.data.borrow_mut();
+                match &mut data.value {
+                    NodeValue::Text(text) => *text = smart.convert(text),
+                    NodeValue::Code(code) => smart.skip(&code.literal),
+                    NodeValue::SoftBreak | NodeValue::LineBreak => smart.skip(" "),
+                    value if value.block() => smart.reset(),
+                    _ => {}
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -238,6 +452,19 @@ impl MarkdownProcessor {
     }
 }
 
+/// 把 HTML 中的标题整体降低 `levels` 级，最低为 h6。
+/// 文章的一级标题作为平台标题单独填写时，正文标题可以从 h2 开始
+pub fn shift_headings(html: &str, levels: u8) -> String {
+    static HEADING_REGEX: OnceLock<Regex> = OnceLock::new();
+    let heading_regex = HEADING_REGEX.get_or_init(|| Regex::new(r"(?i)<(/?)h([1-6])\b").unwrap());
+    heading_regex
+        .replace_all(html, |caps: &regex::Captures| {
+            let level: u8 = caps[2].parse().unwrap_or(1);
+            format!("<{}h{}", &caps[1], level.saturating_add(levels).min(6))
+        })
+        .into_owned()
+}
+
 impl Default for MarkdownProcessor {
     fn default() -> Self {
         Self::new()
@@ -247,6 +474,7 @@ impl Default for MarkdownProcessor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::Language;
 
     #[test]
     fn test_processor_creation() {
@@ -294,6 +522,32 @@ Content here."#;
         );
     }
 
+    #[test]
+    fn test_front_matter_date_uses_timezone() {
+        let processor = MarkdownProcessor::new().with_timezone("Asia/Shanghai".parse().unwrap());
+        let markdown = "---\ndate: 2024-07-01 08:00\n---\n\n# Title";
+
+        let content = processor.process(markdown).unwrap();
+
+        let date = content.metadata.date.unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-07-01T08:00:00+08:00");
+        assert!(!content.metadata.custom_fields.contains_key("date"));
+    }
+
+    #[test]
+    fn test_front_matter_review_after() {
+        let processor = MarkdownProcessor::new();
+        let content = processor
+            .process("---\nreview_after: 2025-01-01\n---\n\n# Title")
+            .unwrap();
+
+        assert_eq!(
+            content.metadata.review_after,
+            chrono::NaiveDate::from_ymd_opt(2025, 1, 1)
+        );
+        assert!(!content.metadata.custom_fields.contains_key("review_after"));
+    }
+
     #[test]
     fn test_table_rendering() {
         let processor = MarkdownProcessor::new();
@@ -380,4 +634,132 @@ fn main() {
         assert_eq!(content.metadata.word_count, Some(0));
         assert_eq!(content.metadata.reading_time, Some(1)); // 最小1分钟
     }
+
+    #[test]
+    fn test_process_cached_matches_full_render() {
+        let processor = MarkdownProcessor::new();
+        let markdown = "---\ntitle: 缓存\n---\n# 标题\n\n第一段 \"引号\"\n\n- a\n- b\n\n```rust\nfn main() {}\n```\n\n| a | b |\n|---|---|\n| 1 | 2 |\n";
+        let mut cache = BlockCache::new();
+
+        let cached = processor.process_cached(markdown, &mut cache).unwrap();
+        assert_eq!(cached.html, processor.process(markdown).unwrap().html);
+        assert_eq!(cache.misses(), 5);
+
+        // 只修改一个段落时，其余块直接复用
+        let edited = markdown.replace("第一段", "第一段（已修改）");
+        let cached = processor.process_cached(&edited, &mut cache).unwrap();
+        assert_eq!(cached.html, processor.process(&edited).unwrap().html);
+        assert_eq!((cache.hits(), cache.misses()), (4, 1));
+
+        // 含脚注的文档整体渲染
+        let footnotes = "正文[^1]\n\n[^1]: 脚注\n";
+        let cached = processor.process_cached(footnotes, &mut cache).unwrap();
+        assert_eq!(cached.html, processor.process(footnotes).unwrap().html);
+    }
+
+    #[test]
+    fn test_process_with_source_map() {
+        let processor = MarkdownProcessor::new();
+        let markdown = "---\ntitle: 定位\n---\n# 标题\n\n第一段\n\n- a\n- b\n\n  续行\n";
+        let (content, source_map) = processor.process_with_source_map(markdown).unwrap();
+
+        assert!(!content.html.contains("data-sourcepos"));
+        assert_eq!(content.html, processor.process(markdown).unwrap().html);
+
+        let lines = |tag: &str, nth: usize| {
+            let m = source_map.find_element(tag, nth).unwrap();
+            (m.start_line, m.end_line)
+        };
+        assert_eq!(lines("h1", 0), (4, 4));
+        assert_eq!(lines("p", 0), (6, 6));
+        assert_eq!(lines("ul", 0), (8, 11));
+        assert_eq!(lines("li", 1), (9, 11));
+        assert_eq!(lines("p", 3), (11, 11));
+
+        let offset = content.html.find("续行").unwrap();
+        assert_eq!(source_map.find_offset(offset).unwrap().tag, "p");
+    }
+
+    #[test]
+    fn test_smart_punctuation_modes() {
+        let markdown = "他说\"你好\"。She said \"*hi*\" -- ok\n";
+        let latin = MarkdownProcessor::new().process(markdown).unwrap();
+        assert!(latin.html.contains("他说&quot;你好&quot;。"));
+        assert!(latin.html.contains("“<em>hi</em>” – ok"));
+
+        let off = MarkdownProcessor::new()
+            .with_smart_punctuation(SmartPunctuation::Off)
+            .process(markdown)
+            .unwrap();
+        assert!(off.html.contains("&quot;<em>hi</em>&quot; -- ok"));
+
+        let all = MarkdownProcessor::new()
+            .with_smart_punctuation(SmartPunctuation::All)
+            .process(markdown)
+            .unwrap();
+        assert!(all.html.contains("“<em>hi</em>” – ok"));
+        assert!(!all.html.contains("&quot;"));
+    }
+
+    #[test]
+    fn test_language_detection_and_override() {
+        let english = "She said \"don't\" -- twice.\n\n".repeat(200);
+        let content = MarkdownProcessor::new().process(&english).unwrap();
+        assert_eq!(content.metadata.language, Some(Language::En));
+        assert!(content.html.contains("“don’t”"));
+        assert_eq!(content.metadata.word_count, Some(800));
+        assert_eq!(content.metadata.reading_time, Some(3));
+
+        // front matter 指定的语言优先，阅读速度随之变化
+        let content = MarkdownProcessor::new()
+            .process(&format!("---\nlang: zh-CN\n---\n{}", english))
+            .unwrap();
+        assert_eq!(content.metadata.language, Some(Language::Zh));
+        assert_eq!(content.metadata.reading_time, Some(2));
+        assert!(!content.metadata.custom_fields.contains_key("lang"));
+    }
+
+    #[test]
+    fn test_escaped_dollar() {
+        let markdown = "售价 \\$10，`echo \\$HOME`\n\n```sh\necho \\$PATH\n```\n";
+        let content = MarkdownProcessor::new()
+            .with_math(true)
+            .process(markdown)
+            .unwrap();
+        assert!(content.html.contains("售价 &#36;10"));
+        assert!(content.html.contains("<code>echo \\$HOME</code>"));
+        assert!(content.html.contains("echo \\$PATH"));
+
+        // `\\$` 是转义的反斜杠，后面的 `$` 仍是公式定界符
+        let content = MarkdownProcessor::new()
+            .with_math(true)
+            .process("\\\\$x$")
+            .unwrap();
+        assert_eq!(content.html, "<p>\\$x$</p>\n");
+
+        // 未开启公式渲染时不做替换
+        let content = MarkdownProcessor::new().process("售价 \\$10").unwrap();
+        assert_eq!(content.html, "<p>售价 $10</p>\n");
+    }
+
+    #[test]
+    fn test_shift_headings() {
+        let markdown = "---\nshift_headings: 1\n---\n# 标题\n\n## 小节\n\n###### 最低\n\n`<h2>`\n";
+        let content = MarkdownProcessor::new().process(markdown).unwrap();
+        assert!(content.html.contains("<h2>标题</h2>"));
+        assert!(content.html.contains("<h3>小节</h3>"));
+        assert!(content.html.contains("<h6>最低</h6>"));
+        assert!(content.html.contains("<code>&lt;h2&gt;</code>"));
+        assert!(!content
+            .metadata
+            .custom_fields
+            .contains_key("shift_headings"));
+
+        // 命令行参数优先于 front matter
+        let content = MarkdownProcessor::new()
+            .with_heading_shift(Some(0))
+            .process(markdown)
+            .unwrap();
+        assert!(content.html.contains("<h1>标题</h1>"));
+    }
 }
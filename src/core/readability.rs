@@ -0,0 +1,179 @@
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// 句末标点，拆分段落时只在这些位置断开
+const SENTENCE_ENDINGS: &[char] = &['。', '！', '？', '；', '!', '?', '…'];
+/// 紧跟句末标点、应留在同一句的闭合符号
+const CLOSING_MARKS: &[char] = &['”', '’', '」', '』', '）', ')', '"', '…'];
+
+/// 把可见文字超过 `max_chars` 的段落按句子边界拆成多个段落，
+/// 返回新的 HTML 和每处拆分的说明
+pub fn split_long_paragraphs(html: &str, max_chars: usize) -> (String, Vec<String>) {
+    static PARAGRAPH_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = PARAGRAPH_REGEX.get_or_init(|| Regex::new(r"(?s)<p(\s[^>]*)?>(.*?)</p>").unwrap());
+
+    let mut changes = Vec::new();
+    let mut index = 0;
+    let result = regex.replace_all(html, |caps: &regex::Captures| {
+        index += 1;
+        let attrs = caps.get(1).map_or("", |m| m.as_str());
+        let inner = &caps[2];
+
+        let length = visible_len(inner);
+        if max_chars == 0 || length <= max_chars {
+            return caps[0].to_string();
+        }
+
+        let chunks = group_sentences(split_sentences(inner), max_chars);
+        if chunks.len() < 2 {
+            return caps[0].to_string();
+        }
+
+        changes.push(format!(
+            "第 {} 段（{} 字）拆分为 {} 段",
+            index,
+            length,
+            chunks.len()
+        ));
+        chunks
+            .iter()
+            .map(|chunk| format!("<p{}>{}</p>", attrs, chunk))
+            .collect::<Vec<_>>()
+            .join("\n")
+    });
+
+    (result.into_owned(), changes)
+}
+
+/// 不计标签的可见字符数
+fn visible_len(html: &str) -> usize {
+    let mut in_tag = false;
+    html.chars()
+        .filter(|&c| {
+            match c {
+                '<' => in_tag = true,
+                '>' if in_tag => {
+                    in_tag = false;
+                    return false;
+                }
+                _ => {}
+            }
+            !in_tag
+        })
+        .count()
+}
+
+/// 按句末标点切分，位于行内元素（加粗、链接等）内部的标点不作为边界
+fn split_sentences(html: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut depth = 0usize;
+    let mut chars = html.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '<' {
+            let mut tag = String::from(c);
+            for t in chars.by_ref() {
+                tag.push(t);
+                if t == '>' {
+                    break;
+                }
+            }
+            if tag.starts_with("</") {
+                depth = depth.saturating_sub(1);
+            } else if !tag.ends_with("/>") && !is_void_tag(&tag) {
+                depth += 1;
+            }
+            current.push_str(&tag);
+            continue;
+        }
+
+        current.push(c);
+        if depth == 0 && SENTENCE_ENDINGS.contains(&c) {
+            while let Some(&next) = chars.peek() {
+                if SENTENCE_ENDINGS.contains(&next) || CLOSING_MARKS.contains(&next) {
+                    current.push(next);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            sentences.push(std::mem::take(&mut current));
+        }
+    }
+
+    if !current.trim().is_empty() {
+        sentences.push(current);
+    }
+    sentences
+}
+
+fn is_void_tag(tag: &str) -> bool {
+    let name: String = tag[1..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    matches!(name.as_str(), "br" | "img" | "hr" | "wbr" | "input")
+}
+
+/// 把句子依次合并成不超过上限的段落，单句超长时独立成段
+fn group_sentences(sentences: Vec<String>, max_chars: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = Vec::new();
+    let mut current = String::new();
+
+    for sentence in sentences {
+        if !current.is_empty() && visible_len(&current) + visible_len(&sentence) > max_chars {
+            chunks.push(std::mem::take(&mut current).trim().to_string());
+        }
+        current.push_str(&sentence);
+    }
+    if !current.trim().is_empty() {
+        chunks.push(current.trim().to_string());
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_at_sentence_boundaries() {
+        let html = "<p>第一句话比较长一些。第二句也不短！第三句呢？最后一句。</p>";
+        let (result, changes) = split_long_paragraphs(html, 18);
+
+        assert_eq!(
+            result,
+            "<p>第一句话比较长一些。第二句也不短！</p>\n<p>第三句呢？最后一句。</p>"
+        );
+        assert_eq!(changes, vec!["第 1 段（27 字）拆分为 2 段"]);
+    }
+
+    #[test]
+    fn test_short_paragraphs_untouched() {
+        let html = "<p>短段落。</p><p style=\"x\">另一个。</p>";
+        let (result, changes) = split_long_paragraphs(html, 100);
+        assert_eq!(result, html);
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn test_keeps_inline_elements_and_quotes_intact() {
+        let html = "<p>他说：“好的。”然后<strong>强调。这里</strong>继续说明。<a href=\"x\">链接</a>结尾。</p>";
+        let (result, _) = split_long_paragraphs(html, 10);
+
+        assert_eq!(
+            result,
+            "<p>他说：“好的。”</p>\n<p>然后<strong>强调。这里</strong>继续说明。</p>\n<p><a href=\"x\">链接</a>结尾。</p>"
+        );
+    }
+
+    #[test]
+    fn test_single_long_sentence_not_split() {
+        let html = "<p>这是一个没有任何句末标点而且非常非常长的句子</p>";
+        let (result, changes) = split_long_paragraphs(html, 5);
+        assert_eq!(result, html);
+        assert!(changes.is_empty());
+    }
+}
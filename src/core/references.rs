@@ -0,0 +1,202 @@
+use crate::{
+    core::footnotes::{collect_footnotes, html_plain_text},
+    error::Error,
+    Result,
+};
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+};
+
+/// 参考资料文件的格式（`output.references`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferencesFormat {
+    #[default]
+    None,
+    Markdown,
+    Json,
+}
+
+impl FromStr for ReferencesFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "none" | "false" => Ok(Self::None),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "json" => Ok(Self::Json),
+            other => Err(Error::Config(format!(
+                "无效的参考资料格式: {}（可选: none、markdown、json）",
+                other
+            ))),
+        }
+    }
+}
+
+/// 文中的一个外部链接，同一地址只记录一次
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LinkReference {
+    pub url: String,
+    /// 第一次出现时的链接文字
+    pub text: String,
+    pub occurrences: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FootnoteReference {
+    pub label: String,
+    pub text: String,
+    /// 脚注中引用的地址
+    pub urls: Vec<String>,
+}
+
+/// 一篇文章输出中的参考资料（外部链接和脚注），与 HTML 一起输出，
+/// 便于维护参考文献或审计外链
+#[derive(Debug, Clone, Serialize)]
+pub struct References {
+    pub title: String,
+    pub platform: String,
+    pub links: Vec<LinkReference>,
+    pub footnotes: Vec<FootnoteReference>,
+}
+
+fn anchors(html: &str) -> impl Iterator<Item = (String, String)> + '_ {
+    static ANCHOR_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = ANCHOR_REGEX
+        .get_or_init(|| Regex::new(r#"(?s)<a\s[^>]*?\bhref="([^"]*)"[^>]*>(.*?)</a>"#).unwrap());
+    regex.captures_iter(html).map(|caps| {
+        (
+            html_escape::decode_html_entities(&caps[1]).into_owned(),
+            html_plain_text(&caps[2]).trim().to_string(),
+        )
+    })
+}
+
+fn is_external(url: &str) -> bool {
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+impl References {
+    /// 从输出 HTML 中收集外部链接和脚注
+    pub fn collect(title: &str, platform: &str, html: &str) -> Self {
+        let mut links: Vec<LinkReference> = Vec::new();
+        for (url, text) in anchors(html).filter(|(url, _)| is_external(url)) {
+            match links.iter_mut().find(|link| link.url == url) {
+                Some(link) => link.occurrences += 1,
+                None => links.push(LinkReference {
+                    url,
+                    text,
+                    occurrences: 1,
+                }),
+            }
+        }
+
+        let footnotes = collect_footnotes(html)
+            .into_iter()
+            .map(|(label, note)| FootnoteReference {
+                text: html_plain_text(&note).trim().to_string(),
+                urls: anchors(&note)
+                    .map(|(url, _)| url)
+                    .filter(|url| is_external(url))
+                    .collect(),
+                label,
+            })
+            .collect();
+
+        Self {
+            title: title.to_string(),
+            platform: platform.to_string(),
+            links,
+            footnotes,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty() && self.footnotes.is_empty()
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# 参考资料：{}\n", self.title);
+        let _ = writeln!(out, "- 平台: {}", self.platform);
+
+        if !self.links.is_empty() {
+            let _ = writeln!(out, "\n## 外部链接\n");
+            for (i, link) in self.links.iter().enumerate() {
+                let text = if link.text.is_empty() {
+                    &link.url
+                } else {
+                    &link.text
+                };
+                let _ = write!(out, "{}. [{}]({})", i + 1, text, link.url);
+                if link.occurrences > 1 {
+                    let _ = write!(out, "（{} 处）", link.occurrences);
+                }
+                out.push('\n');
+            }
+        }
+
+        if !self.footnotes.is_empty() {
+            let _ = writeln!(out, "\n## 脚注\n");
+            for note in &self.footnotes {
+                let _ = writeln!(out, "[^{}]: {}", note.label, note.text);
+            }
+        }
+        out
+    }
+
+    /// 按格式生成文件内容，`None` 格式不输出
+    pub fn render(&self, format: ReferencesFormat) -> Result<Option<String>> {
+        Ok(match format {
+            ReferencesFormat::None => None,
+            ReferencesFormat::Markdown => Some(self.to_markdown()),
+            ReferencesFormat::Json => Some(serde_json::to_string_pretty(self)?),
+        })
+    }
+}
+
+/// 参考资料文件与输出文件放在一起：`文章_wechat.html` -> `文章_wechat.references.md`
+pub fn references_path(output: &Path, format: ReferencesFormat) -> PathBuf {
+    let extension = match format {
+        ReferencesFormat::Json => "references.json",
+        _ => "references.md",
+    };
+    output.with_extension(extension)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MarkdownProcessor;
+
+    #[test]
+    fn test_collect_references() {
+        let content = MarkdownProcessor::new()
+            .process("见 [Rust](https://www.rust-lang.org/)、[再次](https://www.rust-lang.org/) 和 [本地](#top)[^1]。\n\n[^1]: 出自 [RFC](https://rust-lang.github.io/rfcs/)。\n")
+            .unwrap();
+        let references = References::collect(&content.title, "wechat", &content.html);
+
+        assert_eq!(references.links.len(), 2);
+        assert_eq!(references.links[0].text, "Rust");
+        assert_eq!(references.links[0].occurrences, 2);
+        assert_eq!(references.footnotes.len(), 1);
+        assert_eq!(references.footnotes[0].text, "出自 RFC。");
+        assert_eq!(
+            references.footnotes[0].urls,
+            vec!["https://rust-lang.github.io/rfcs/"]
+        );
+
+        let markdown = references.to_markdown();
+        assert!(markdown.contains("1. [Rust](https://www.rust-lang.org/)（2 处）"));
+        assert!(markdown.contains("[^1]: 出自 RFC。"));
+        assert_eq!(
+            references_path(Path::new("out/a_wechat.html"), ReferencesFormat::Json),
+            PathBuf::from("out/a_wechat.references.json")
+        );
+        assert!(references.render(ReferencesFormat::None).unwrap().is_none());
+    }
+}
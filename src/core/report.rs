@@ -0,0 +1,315 @@
+use crate::core::{content::Platform, footnotes::html_plain_text};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Write, ops::Range, path::PathBuf, sync::OnceLock};
+
+/// 单次 process/watch 运行的处理报告，汇总工具对每篇文章做了哪些改动
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessingReport {
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub articles: Vec<ArticleReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArticleReport {
+    pub source: Option<PathBuf>,
+    pub title: String,
+    pub platforms: Vec<PlatformReport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlatformReport {
+    pub platform: Platform,
+    pub output: Option<PathBuf>,
+    /// 标题变体标识 -> 输出文件
+    #[serde(default)]
+    pub variant_outputs: BTreeMap<String, PathBuf>,
+    pub warnings: Vec<String>,
+    pub stripped_elements: Vec<String>,
+    pub converted_links: Vec<String>,
+    #[serde(default)]
+    pub rewritten_links: Vec<String>,
+    pub uploaded_images: Vec<String>,
+    #[serde(default)]
+    pub readability_changes: Vec<String>,
+    /// 平台清理或能力降级时移除、改写的元素（iframe、script、音视频等）
+    #[serde(default)]
+    pub degraded_elements: Vec<DegradedElement>,
+}
+
+/// 被移除或改写的元素，以及它在正文中的位置，方便作者找到缺失的内容
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DegradedElement {
+    /// 原始开始标签，如 `<iframe src="https://...">`
+    pub element: String,
+    /// 处理方式，如“移除”“替换为链接卡片”
+    pub action: String,
+    /// 所在小节和紧邻的前文
+    pub location: String,
+}
+
+/// 记录的开始标签和前文的最大字符数
+const MAX_TAG_CHARS: usize = 80;
+const CONTEXT_CHARS: usize = 20;
+
+impl PlatformReport {
+    pub fn new(platform: Platform) -> Self {
+        Self {
+            platform,
+            output: None,
+            variant_outputs: BTreeMap::new(),
+            warnings: Vec::new(),
+            stripped_elements: Vec::new(),
+            converted_links: Vec::new(),
+            rewritten_links: Vec::new(),
+            uploaded_images: Vec::new(),
+            readability_changes: Vec::new(),
+            degraded_elements: Vec::new(),
+        }
+    }
+
+    /// 记录 `html` 中 `range` 处的元素被移除或改写
+    pub fn record_degraded(&mut self, html: &str, range: Range<usize>, action: impl Into<String>) {
+        let element = &html[range.clone()];
+        let tag = element
+            .find('>')
+            .map_or(element, |end| &element[..=end])
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.degraded_elements.push(DegradedElement {
+            element: truncate_chars(&tag, MAX_TAG_CHARS),
+            action: action.into(),
+            location: element_location(&html[..range.start]),
+        });
+    }
+
+    /// 追加另一份报告记录的改动（如缓存的适配结果）
+    pub fn merge(&mut self, other: &PlatformReport) {
+        self.warnings.extend_from_slice(&other.warnings);
+        self.stripped_elements
+            .extend_from_slice(&other.stripped_elements);
+        self.converted_links
+            .extend_from_slice(&other.converted_links);
+        self.rewritten_links
+            .extend_from_slice(&other.rewritten_links);
+        self.uploaded_images
+            .extend_from_slice(&other.uploaded_images);
+        self.readability_changes
+            .extend_from_slice(&other.readability_changes);
+        self.degraded_elements
+            .extend_from_slice(&other.degraded_elements);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty()
+            && self.stripped_elements.is_empty()
+            && self.converted_links.is_empty()
+            && self.rewritten_links.is_empty()
+            && self.uploaded_images.is_empty()
+            && self.readability_changes.is_empty()
+            && self.degraded_elements.is_empty()
+    }
+}
+
+/// 元素的位置：前面最近的标题，以及紧邻的一段前文；都没有时位于正文开头
+pub(crate) fn element_location(before: &str) -> String {
+    static HEADING_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex =
+        HEADING_REGEX.get_or_init(|| Regex::new(r"(?s)<h[1-6]\b[^>]*>(.*?)</h[1-6]>").unwrap());
+
+    let heading = regex
+        .captures_iter(before)
+        .last()
+        .map(|caps| html_plain_text(&caps[1]).trim().to_string())
+        .filter(|heading| !heading.is_empty());
+    let text = html_plain_text(before);
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let chars: Vec<char> = text.chars().collect();
+    let context: String = chars[chars.len().saturating_sub(CONTEXT_CHARS)..]
+        .iter()
+        .collect();
+
+    match (heading, context.is_empty()) {
+        (_, true) => "正文开头".to_string(),
+        (Some(heading), false) => format!("「{}」一节，“…{}”之后", heading, context),
+        (None, false) => format!("“…{}”之后", context),
+    }
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max).collect();
+    truncated.push('…');
+    truncated
+}
+
+impl ArticleReport {
+    pub fn new(title: impl Into<String>, source: Option<PathBuf>) -> Self {
+        Self {
+            source,
+            title: title.into(),
+            platforms: Vec::new(),
+        }
+    }
+}
+
+impl Default for ProcessingReport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProcessingReport {
+    pub fn new() -> Self {
+        Self {
+            generated_at: chrono::Utc::now(),
+            articles: Vec::new(),
+        }
+    }
+
+    pub fn add_article(&mut self, article: ArticleReport) {
+        self.articles.push(article);
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(out, "# MarkFlow 处理报告\n");
+        let _ = writeln!(
+            out,
+            "- 生成时间: {}",
+            self.generated_at.format("%Y-%m-%d %H:%M:%S UTC")
+        );
+        let _ = writeln!(out, "- 文章数: {}", self.articles.len());
+
+        for article in &self.articles {
+            let _ = writeln!(out, "\n## {}\n", article.title);
+            if let Some(source) = &article.source {
+                let _ = writeln!(out, "- 源文件: `{}`", source.display());
+            }
+
+            for platform in &article.platforms {
+                let _ = writeln!(out, "\n### {}\n", platform.platform);
+                if let Some(output) = &platform.output {
+                    let _ = writeln!(out, "- 输出文件: `{}`", output.display());
+                }
+                for (label, output) in &platform.variant_outputs {
+                    let _ = writeln!(out, "- 标题变体 {}: `{}`", label, output.display());
+                }
+                if platform.is_empty() {
+                    let _ = writeln!(out, "- 无改动记录");
+                    continue;
+                }
+                write_section(&mut out, "验证警告", &platform.warnings, false);
+                write_section(&mut out, "移除的元素", &platform.stripped_elements, true);
+                if !platform.degraded_elements.is_empty() {
+                    let _ = writeln!(out, "- 降级的元素 ({}):", platform.degraded_elements.len());
+                    for degraded in &platform.degraded_elements {
+                        let _ = writeln!(
+                            out,
+                            "  - `{}` {}，位于{}",
+                            degraded.element, degraded.action, degraded.location
+                        );
+                    }
+                }
+                write_section(&mut out, "改写的链接", &platform.rewritten_links, false);
+                write_section(&mut out, "转换的链接", &platform.converted_links, false);
+                write_section(&mut out, "上传的图片", &platform.uploaded_images, false);
+                write_section(&mut out, "段落拆分", &platform.readability_changes, false);
+            }
+        }
+
+        out
+    }
+
+    pub fn to_html(&self) -> String {
+        let markdown = self.to_markdown();
+        let body = comrak::markdown_to_html(&markdown, &comrak::ComrakOptions::default());
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>MarkFlow 处理报告</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+            body
+        )
+    }
+}
+
+fn write_section(out: &mut String, title: &str, items: &[String], as_code: bool) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "- {} ({}):", title, items.len());
+    for item in items {
+        if as_code {
+            let _ = writeln!(out, "  - `{}`", item);
+        } else {
+            let _ = writeln!(out, "  - {}", item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_markdown() {
+        let mut platform = PlatformReport::new(Platform::WeChat);
+        platform.output = Some(PathBuf::from("output/wechat/a.html"));
+        platform
+            .warnings
+            .push("cover_image: 封面图片无效".to_string());
+        platform.stripped_elements.push("<script>".to_string());
+        platform
+            .converted_links
+            .push("[1] https://example.com".to_string());
+
+        let mut article = ArticleReport::new("测试文章", Some(PathBuf::from("a.md")));
+        article.platforms.push(platform);
+        article.platforms.push(PlatformReport::new(Platform::Zhihu));
+
+        let mut report = ProcessingReport::new();
+        report.add_article(article);
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("## 测试文章"));
+        assert!(markdown.contains("验证警告 (1)"));
+        assert!(markdown.contains("移除的元素 (1)"));
+        assert!(markdown.contains("[1] https://example.com"));
+        assert!(markdown.contains("无改动记录"));
+        assert!(!markdown.contains("上传的图片"));
+
+        let html = report.to_html();
+        assert!(html.contains("<h1>MarkFlow 处理报告</h1>"));
+        assert!(html.contains("<code>&lt;script&gt;</code>"));
+    }
+
+    #[test]
+    fn test_record_degraded_location() {
+        let html = "<h2>演示 <em>视频</em></h2>\n<p>下面是录屏：</p>\n<iframe src=\"https://player.example.com/v/1\"\n  width=\"640\"></iframe><p>结尾</p>";
+        let start = html.find("<iframe").unwrap();
+        let end = html.find("</iframe>").unwrap() + "</iframe>".len();
+
+        let mut platform = PlatformReport::new(Platform::Zhihu);
+        platform.record_degraded(html, start..end, "移除");
+        platform.record_degraded(html, 0..4, "移除");
+        assert_eq!(
+            platform.degraded_elements[0],
+            DegradedElement {
+                element: r#"<iframe src="https://player.example.com/v/1" width="640">"#.to_string(),
+                action: "移除".to_string(),
+                location: "「演示 视频」一节，“…演示 视频 下面是录屏：”之后".to_string(),
+            }
+        );
+        assert_eq!(platform.degraded_elements[1].location, "正文开头");
+
+        let mut article = ArticleReport::new("测试文章", None);
+        article.platforms.push(platform);
+        let mut report = ProcessingReport::new();
+        report.add_article(article);
+        assert!(report.to_markdown().contains(
+            "- 降级的元素 (2):\n  - `<iframe src=\"https://player.example.com/v/1\" width=\"640\">` 移除，位于「演示 视频」一节"
+        ));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::{
+    cli::args::SnapshotConfig,
+    core::browser::{browser_error, BrowserPool},
+    error::Error,
+    Result,
+};
+use image::{DynamicImage, ImageOutputFormat, Rgba, RgbaImage};
+use std::{io::Cursor, path::Path};
+
+/// 截图的最大高度，避免超长文章生成过大的图片
+const MAX_SCREENSHOT_HEIGHT: u32 = 20_000;
+
+/// 两张截图的像素差异
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff {
+    pub width: u32,
+    pub height: u32,
+    pub different_pixels: u64,
+    /// 不同像素占比（0-1），尺寸变化时超出部分全部计为不同
+    pub ratio: f64,
+    /// 差异图：相同区域变淡，不同的像素标红
+    pub diff_png: Vec<u8>,
+}
+
+impl SnapshotDiff {
+    pub fn is_match(&self, max_ratio: f64) -> bool {
+        self.ratio <= max_ratio
+    }
+}
+
+/// 把 HTML 片段包装为移动端页面，已经是完整文档时原样返回
+pub fn snapshot_document(html: &str) -> String {
+    let head = html.trim_start().get(..15).unwrap_or("").to_lowercase();
+    if head.starts_with("<!doctype") || head.starts_with("<html") {
+        return html.to_string();
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n</head>\n<body style=\"margin:0;padding:16px;background:#fff;\">\n{}\n</body>\n</html>\n",
+        html
+    )
+}
+
+/// 用无头浏览器按移动端宽度渲染 HTML 文件并返回整页 PNG 截图，浏览器会话从池中借用。
+/// 浏览器池模拟设备时使用设备的视口大小
+pub async fn capture_screenshot(
+    page: &Path,
+    config: &SnapshotConfig,
+    pool: &BrowserPool,
+) -> Result<Vec<u8>> {
+    let (width, height) = pool
+        .device()
+        .map(|device| (device.width, device.height))
+        .unwrap_or((config.width, config.height));
+    let url = url::Url::from_file_path(std::path::absolute(page)?)
+        .map_err(|_| Error::Browser(format!("无效的页面路径: {:?}", page)))?;
+
+    let driver = pool.acquire().await?;
+    let result = async {
+        driver.set_window_rect(0, 0, width, height).await?;
+        driver.goto(url.as_str()).await?;
+
+        // 调整窗口高度以截取整页
+        let full_height = driver
+            .execute("return document.documentElement.scrollHeight;", Vec::new())
+            .await?
+            .json()
+            .as_u64()
+            .unwrap_or(height as u64) as u32;
+        let full_height = full_height.clamp(height, MAX_SCREENSHOT_HEIGHT);
+        driver.set_window_rect(0, 0, width, full_height).await?;
+
+        driver.screenshot_as_png().await
+    }
+    .await;
+
+    pool.release(driver).await;
+    result.map_err(browser_error)
+}
+
+/// 逐像素比较两张 PNG，每个通道差值不超过 `tolerance` 的像素视为相同
+pub fn compare_png(baseline: &[u8], actual: &[u8], tolerance: u8) -> Result<SnapshotDiff> {
+    let baseline = decode(baseline)?;
+    let actual = decode(actual)?;
+
+    let width = baseline.width().max(actual.width());
+    let height = baseline.height().max(actual.height());
+    let mut diff = RgbaImage::new(width, height);
+    let mut different_pixels = 0u64;
+
+    for y in 0..height {
+        for x in 0..width {
+            let expected = pixel(&baseline, x, y);
+            let got = pixel(&actual, x, y);
+            let same = match (expected, got) {
+                (Some(a), Some(b)) => {
+                    a.0.iter()
+                        .zip(b.0.iter())
+                        .all(|(a, b)| a.abs_diff(*b) <= tolerance)
+                }
+                _ => false,
+            };
+
+            let marked = if same {
+                let Rgba([r, g, b, _]) = got.unwrap_or(Rgba([255, 255, 255, 255]));
+                // 相同区域淡化显示，方便在差异图里定位红色区域
+                Rgba([fade(r), fade(g), fade(b), 255])
+            } else {
+                different_pixels += 1;
+                Rgba([255, 0, 0, 255])
+            };
+            diff.put_pixel(x, y, marked);
+        }
+    }
+
+    let total = width as u64 * height as u64;
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        different_pixels as f64 / total as f64
+    };
+
+    Ok(SnapshotDiff {
+        width,
+        height,
+        different_pixels,
+        ratio,
+        diff_png: encode(diff)?,
+    })
+}
+
+fn decode(png: &[u8]) -> Result<RgbaImage> {
+    Ok(image::load_from_memory(png)
+        .map_err(|e| Error::Other(format!("截图解码失败: {}", e)))?
+        .to_rgba8())
+}
+
+fn encode(image: RgbaImage) -> Result<Vec<u8>> {
+    let mut bytes = Cursor::new(Vec::new());
+    DynamicImage::ImageRgba8(image)
+        .write_to(&mut bytes, ImageOutputFormat::Png)
+        .map_err(|e| Error::Other(format!("差异图编码失败: {}", e)))?;
+    Ok(bytes.into_inner())
+}
+
+fn pixel(image: &RgbaImage, x: u32, y: u32) -> Option<Rgba<u8>> {
+    (x < image.width() && y < image.height()).then(|| *image.get_pixel(x, y))
+}
+
+fn fade(channel: u8) -> u8 {
+    255 - (255 - channel) / 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32, color: [u8; 4]) -> Vec<u8> {
+        encode(RgbaImage::from_pixel(width, height, Rgba(color))).unwrap()
+    }
+
+    #[test]
+    fn test_compare_identical_and_changed() {
+        let white = png(10, 10, [255, 255, 255, 255]);
+        let diff = compare_png(&white, &white, 0).unwrap();
+        assert_eq!(diff.different_pixels, 0);
+        assert!(diff.is_match(0.0));
+
+        let mut changed = RgbaImage::from_pixel(10, 10, Rgba([255, 255, 255, 255]));
+        for x in 0..10 {
+            changed.put_pixel(x, 0, Rgba([0, 0, 0, 255]));
+        }
+        changed.put_pixel(0, 5, Rgba([250, 250, 250, 255]));
+        let diff = compare_png(&white, &encode(changed).unwrap(), 8).unwrap();
+        assert_eq!(diff.different_pixels, 10);
+        assert!((diff.ratio - 0.1).abs() < f64::EPSILON);
+        assert!(!diff.is_match(0.01));
+
+        let decoded = decode(&diff.diff_png).unwrap();
+        assert_eq!(*decoded.get_pixel(3, 0), Rgba([255, 0, 0, 255]));
+    }
+
+    #[test]
+    fn test_compare_different_sizes() {
+        let small = png(10, 10, [255, 255, 255, 255]);
+        let tall = png(10, 20, [255, 255, 255, 255]);
+        let diff = compare_png(&small, &tall, 0).unwrap();
+        assert_eq!((diff.width, diff.height), (10, 20));
+        assert_eq!(diff.different_pixels, 100);
+    }
+
+    #[test]
+    fn test_snapshot_document_wraps_fragment() {
+        assert!(snapshot_document("<p>hi</p>").contains("name=\"viewport\""));
+        let full = "<!DOCTYPE html><html><body></body></html>";
+        assert_eq!(snapshot_document(full), full);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{cli::args::FormatConfig, core::frontmatter::split_front_matter, error::Error, Result};
+use comrak::{
+    format_commonmark, nodes::AstNode, parse_document, Arena, ComrakOptions, ListStyleType,
+};
+use std::str::FromStr;
+
+/// 无序列表统一使用的标记（`format.list_marker`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ListMarker {
+    #[default]
+    Dash,
+    Star,
+    Plus,
+}
+
+impl FromStr for ListMarker {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "-" | "dash" => Ok(Self::Dash),
+            "*" | "star" => Ok(Self::Star),
+            "+" | "plus" => Ok(Self::Plus),
+            other => Err(Error::Config(format!(
+                "无效的列表标记: {}（可选: -、*、+）",
+                other
+            ))),
+        }
+    }
+}
+
+/// 按 comrak 语法树重新输出 Markdown 源文件，统一标题前后空行、列表标记、
+/// 链接写法（引用式链接展开为行内链接）和段落折行，front matter 原样保留
+#[derive(Debug, Clone)]
+pub struct SourceFormatter {
+    options: ComrakOptions,
+}
+
+impl SourceFormatter {
+    /// `line_width` 为 0 时保留段落中原有的换行，不重新折行
+    pub fn new(line_width: usize, list_marker: ListMarker) -> Self {
+        let mut options = ComrakOptions::default();
+        // 与 `MarkdownProcessor` 使用相同的扩展，保证格式化前后渲染结果一致
+        options.extension.strikethrough = true;
+        options.extension.table = true;
+        options.extension.autolink = true;
+        options.extension.tasklist = true;
+        options.extension.footnotes = true;
+        options.extension.superscript = true;
+        options.extension.description_lists = true;
+        options.render.unsafe_ = true;
+        options.render.width = line_width;
+        options.render.list_style = match list_marker {
+            ListMarker::Dash => ListStyleType::Dash,
+            ListMarker::Star => ListStyleType::Star,
+            ListMarker::Plus => ListStyleType::Plus,
+        };
+        Self { options }
+    }
+
+    pub fn from_config(config: &FormatConfig) -> Result<Self> {
+        Ok(Self::new(config.line_width, config.list_marker.parse()?))
+    }
+
+    /// 按与格式化相同的扩展解析 Markdown，调用方可以修改语法树后用 [`Self::render`] 输出
+    pub fn parse<'a>(&self, arena: &'a Arena<AstNode<'a>>, markdown: &str) -> &'a AstNode<'a> {
+        parse_document(arena, markdown, &self.options)
+    }
+
+    /// 把语法树（或其中一个节点）输出为 Markdown
+    pub fn render<'a>(&self, node: &'a AstNode<'a>) -> Result<String> {
+        let mut output = Vec::new();
+        format_commonmark(node, &self.options, &mut output)?;
+        String::from_utf8(output)
+            .map_err(|e| Error::Markdown(format!("格式化结果不是有效的 UTF-8: {}", e)))
+    }
+
+    pub fn format(&self, markdown: &str) -> Result<String> {
+        let (front_matter, body) = split_front_matter(markdown);
+        let arena = Arena::new();
+        let body = self.render(self.parse(&arena, body))?;
+
+        let mut formatted = front_matter.to_string();
+        if !front_matter.is_empty() && !body.is_empty() {
+            formatted.push('\n');
+        }
+        formatted.push_str(&body);
+        Ok(formatted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::MarkdownProcessor;
+
+    #[test]
+    fn test_format_source() {
+        let formatter = SourceFormatter::new(0, ListMarker::Dash);
+        let source = "---\ntitle: 示例\n---\n# 标题\n正文见[文档][doc]。\n* 一\n* 二\n\n[doc]: https://example.com\n";
+        let formatted = formatter.format(source).unwrap();
+        assert_eq!(
+            formatted,
+            "---\ntitle: 示例\n---\n\n# 标题\n\n正文见[文档](https://example.com)。\n\n- 一\n- 二\n"
+        );
+        assert_eq!(formatter.format(&formatted).unwrap(), formatted);
+
+        // 按宽度折行只在空格处断开，中文段落不会被拆开
+        let wrapped = SourceFormatter::new(20, ListMarker::Dash)
+            .format("one two three four five six seven\n\n很长的一段中文没有空格不会折行\n")
+            .unwrap();
+        assert_eq!(
+            wrapped,
+            "one two three four\nfive six seven\n\n很长的一段中文没有空格不会折行\n"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_rendered_html() {
+        let source = "## 小节\n| a | b |\n|---|:-:|\n| 1 | 2 |\n\n+ [x] 完成\n+ [ ] 待办\n\n脚注[^1]，~~删除~~，公式 $a_b$，<span>HTML</span>\n\n```rust\nfn main() {}\n```\n\n[^1]: 说明\n";
+        let formatted = SourceFormatter::new(0, ListMarker::Star)
+            .format(source)
+            .unwrap();
+        assert!(formatted.contains("* [x] 完成"));
+        let processor = MarkdownProcessor::new();
+        assert_eq!(
+            processor.process(&formatted).unwrap().html,
+            processor.process(source).unwrap().html
+        );
+    }
+}
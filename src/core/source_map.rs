@@ -0,0 +1,81 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::{collections::HashMap, sync::OnceLock};
+
+/// 输出 HTML 中一个元素对应的 Markdown 源位置
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceMapping {
+    /// 小写标签名
+    pub tag: String,
+    /// 该元素是输出中第几个同名元素（从 0 开始），
+    /// 与浏览器中 `querySelectorAll(tag)[nth]` 对应
+    pub nth: usize,
+    /// 开始标签在 HTML 中的字节偏移
+    pub html_offset: usize,
+    /// Markdown 源文（含 front matter）中的起止行，从 1 开始
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+/// HTML 元素到 Markdown 行号的映射，按元素在 HTML 中的顺序排列
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SourceMap {
+    pub mappings: Vec<SourceMapping>,
+}
+
+impl SourceMap {
+    /// 浏览器中第 `nth` 个 `tag` 元素对应的映射，用于点击预览跳转到源文
+    pub fn find_element(&self, tag: &str, nth: usize) -> Option<&SourceMapping> {
+        self.mappings
+            .iter()
+            .find(|m| m.nth == nth && m.tag.eq_ignore_ascii_case(tag))
+    }
+
+    /// HTML 字节偏移所在的（最内层）元素，用于把 HTML 中发现的问题定位回源文
+    pub fn find_offset(&self, html_offset: usize) -> Option<&SourceMapping> {
+        self.mappings
+            .iter()
+            .take_while(|m| m.html_offset <= html_offset)
+            .last()
+    }
+}
+
+/// 从带 `data-sourcepos` 属性的 HTML（comrak 的 sourcepos 渲染选项）中提取映射并移除该属性。
+/// `line_offset` 为渲染前去掉的 front matter 行数，映射中的行号按完整源文计算
+pub fn extract_source_map(html: &str, line_offset: usize) -> (String, SourceMap) {
+    static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+    static SOURCEPOS_REGEX: OnceLock<Regex> = OnceLock::new();
+    let tag_regex =
+        TAG_REGEX.get_or_init(|| Regex::new(r"<([a-zA-Z][a-zA-Z0-9]*)\b([^>]*)>").unwrap());
+    let sourcepos_regex = SOURCEPOS_REGEX
+        .get_or_init(|| Regex::new(r#" data-sourcepos="(\d+):\d+-(\d+):\d+""#).unwrap());
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let mut mappings = Vec::new();
+    let mut output = String::with_capacity(html.len());
+    let mut last = 0;
+    for caps in tag_regex.captures_iter(html) {
+        let whole = caps.get(0).unwrap();
+        output.push_str(&html[last..whole.start()]);
+        last = whole.end();
+
+        let tag = caps[1].to_ascii_lowercase();
+        let nth = counts.entry(tag.clone()).or_default();
+        if let Some(pos) = sourcepos_regex.captures(&caps[2]) {
+            let line = |caps: &Captures, i: usize| caps[i].parse::<usize>().unwrap_or(0);
+            mappings.push(SourceMapping {
+                tag,
+                nth: *nth,
+                html_offset: output.len(),
+                start_line: line(&pos, 1) + line_offset,
+                end_line: line(&pos, 2) + line_offset,
+            });
+            output.push_str(&sourcepos_regex.replace(whole.as_str(), ""));
+        } else {
+            output.push_str(whole.as_str());
+        }
+        *nth += 1;
+    }
+    output.push_str(&html[last..]);
+    (output, SourceMap { mappings })
+}
@@ -0,0 +1,108 @@
+use crate::{
+    core::{content::Content, paths::sanitize_filename},
+    Result,
+};
+use std::path::{Path, PathBuf};
+
+/// 把流水线每个阶段、适配器每个步骤之后的内容写到编号文件（`--debug-stages`），
+/// 逐个比较相邻文件即可找到改坏输出的那一步
+pub struct StageDump {
+    dir: PathBuf,
+    step: usize,
+}
+
+impl StageDump {
+    /// 在 `root` 下为文章建立独立目录；目录中上一次的导出会先被清空，避免新旧编号混在一起
+    pub fn new(root: &Path, source: &Path) -> Result<Self> {
+        let name = source
+            .file_stem()
+            .map(|s| sanitize_filename(&s.to_string_lossy()))
+            .unwrap_or_else(|| "article".to_string());
+        let dir = root.join(name);
+        if dir.is_dir() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self { dir, step: 0 })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn next_path(&mut self, label: &str, extension: &str) -> PathBuf {
+        let path = self.dir.join(format!(
+            "{:02}-{}.{}",
+            self.step,
+            sanitize_filename(label),
+            extension
+        ));
+        self.step += 1;
+        path
+    }
+
+    /// 写入某个阶段之后的 Markdown 和 HTML；Markdown 没有变化时只写 HTML
+    pub fn content(
+        &mut self,
+        label: &str,
+        content: &Content,
+        previous_markdown: &str,
+    ) -> Result<()> {
+        let html_path = self.next_path(label, "html");
+        if content.markdown != previous_markdown {
+            std::fs::write(html_path.with_extension("md"), &content.markdown)?;
+        }
+        std::fs::write(html_path, &content.html)?;
+        Ok(())
+    }
+
+    /// 写入某个适配步骤之后的 HTML
+    pub fn html(&mut self, label: &str, html: &str) -> Result<()> {
+        let path = self.next_path(label, "html");
+        std::fs::write(path, html)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_stage_dump_numbering() {
+        let dir = TempDir::new().unwrap();
+        let mut content = crate::core::MarkdownProcessor::new()
+            .process("# 标题\n\n正文\n")
+            .unwrap();
+
+        let mut dump = StageDump::new(dir.path(), Path::new("posts/hello.md")).unwrap();
+        dump.content("解析", &content, "").unwrap();
+        let previous = content.markdown.clone();
+        content.html.push_str("<p>追加</p>");
+        dump.content("术语修正", &content, &previous).unwrap();
+        dump.html("wechat-内联样式", "<section></section>").unwrap();
+
+        let mut files: Vec<_> = std::fs::read_dir(dir.path().join("hello"))
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                "00-解析.html",
+                "00-解析.md",
+                "01-术语修正.html",
+                "02-wechat-内联样式.html"
+            ]
+        );
+
+        // 再次导出时清空旧文件
+        StageDump::new(dir.path(), Path::new("posts/hello.md")).unwrap();
+        assert_eq!(
+            std::fs::read_dir(dir.path().join("hello")).unwrap().count(),
+            0
+        );
+    }
+}
@@ -0,0 +1,530 @@
+use crate::{
+    core::{
+        content::{Platform, PublishResult, PublishStatus},
+        lock::FileLock,
+        report::ArticleReport,
+        workflow::Workflow,
+    },
+    error::Error,
+    Result,
+};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+use uuid::Uuid;
+
+/// 计算内容的SHA-256哈希（十六进制）
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+/// 已处理文章的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentRecord {
+    pub id: Uuid,
+    pub title: String,
+    pub source: Option<PathBuf>,
+    pub content_hash: String,
+    /// 平台名 -> 输出文件
+    #[serde(default)]
+    pub outputs: BTreeMap<String, PathBuf>,
+    /// 平台名 -> 适配后的 HTML，键与 `outputs` 相同；发布和预览使用这里的 HTML。
+    /// 只保存在台账数据库中，不随记录序列化
+    #[serde(skip)]
+    pub html: BTreeMap<String, String>,
+    #[serde(default)]
+    pub publications: Vec<PublishResult>,
+    /// front matter 中的复查日期
+    #[serde(default)]
+    pub review_after: Option<chrono::NaiveDate>,
+    /// 标题变体标识 -> 标题，对应的输出记录在 `outputs` 的 `平台.标识` 键下
+    #[serde(default)]
+    pub title_variants: BTreeMap<String, String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 文章的 slug，用于解析 `slug:` 形式的站内链接
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// 自有站点上的规范地址
+    #[serde(default)]
+    pub canonical_url: Option<String>,
+    /// 审核状态，重新处理文章时保持不变
+    #[serde(default)]
+    pub workflow: Workflow,
+    /// 最近一次处理的报告（各平台的警告和改动）
+    #[serde(default)]
+    pub report: Option<ArticleReport>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ContentRecord {
+    /// 记录一次发布结果；成功或已提交发布时把审核状态标记为已发布
+    pub fn record_publication(&mut self, result: PublishResult) {
+        if matches!(
+            result.status,
+            PublishStatus::Success | PublishStatus::Pending
+        ) {
+            self.workflow.mark_published(None);
+        }
+        self.publications.push(result);
+    }
+
+    /// 平台最近一次的发布结果
+    pub fn latest_publication(&self, platform: &str) -> Option<&PublishResult> {
+        self.publications
+            .iter()
+            .rev()
+            .find(|p| p.platform.to_string() == platform)
+    }
+
+    /// 平台上还未发布的草稿 ID：最近一次结果是草稿时，重新发布草稿应更新这篇草稿而不是新建
+    pub fn pending_draft(&self, platform: &str) -> Option<&str> {
+        self.latest_publication(platform)
+            .filter(|p| matches!(p.status, PublishStatus::Draft))
+            .and_then(|p| p.draft_id.as_deref())
+    }
+
+    /// 文章在平台上公开的地址，优先取该平台最近一次的发布记录
+    pub fn public_url(&self, platform: &str) -> Option<&str> {
+        let published = || {
+            self.publications
+                .iter()
+                .rev()
+                .filter(|p| matches!(p.status, PublishStatus::Success))
+        };
+        published()
+            .filter(|p| p.platform.to_string() == platform)
+            .chain(published())
+            .find_map(|p| p.url.as_deref())
+    }
+}
+
+/// 已上传到平台的图片记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediaRecord {
+    pub platform: Platform,
+    pub hash: String,
+    pub source: String,
+    pub url: String,
+    pub media_id: Option<String>,
+    pub uploaded_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 台账数据库的文件名，位于数据根目录（默认 `~/.markflow`）或工作区目录下
+pub const LEDGER_FILE: &str = "ledger.db";
+
+/// 文章的其余字段以 JSON 保存在 `record` 中；各平台的输出文件和适配后的 HTML 单独成表
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS contents (
+    id TEXT PRIMARY KEY,
+    source TEXT,
+    title TEXT NOT NULL,
+    content_hash TEXT NOT NULL,
+    record TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS outputs (
+    content_id TEXT NOT NULL REFERENCES contents (id),
+    platform TEXT NOT NULL,
+    path TEXT,
+    html TEXT,
+    PRIMARY KEY (content_id, platform)
+);
+CREATE TABLE IF NOT EXISTS media (
+    platform TEXT NOT NULL,
+    hash TEXT NOT NULL,
+    record TEXT NOT NULL,
+    PRIMARY KEY (platform, hash)
+);
+";
+
+/// 台账 `outputs` 表中的一行：平台、输出文件、适配后的 HTML
+type StoredOutput = (String, Option<String>, Option<String>);
+
+/// 本地内容台账（SQLite）：记录处理过的文章、各平台的输出文件和适配后的 HTML、
+/// 发布结果以及已上传的图片。
+///
+/// 打开台账时会持有排他文件锁，直到该值被 drop，期间其他 markflow 实例需要等待。
+/// 记录读入内存后修改，`save` 时把修改过的记录写回数据库
+pub struct ContentStore {
+    path: PathBuf,
+    connection: Connection,
+    records: Vec<ContentRecord>,
+    media: Vec<MediaRecord>,
+    /// 打开后新增或修改过的记录
+    changed: HashSet<Uuid>,
+    media_changed: bool,
+    _lock: FileLock,
+}
+
+impl ContentStore {
+    pub fn default_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow").join(LEDGER_FILE)
+    }
+
+    pub fn open_default() -> Result<Self> {
+        Self::open(Self::default_path())
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let lock = FileLock::exclusive(&path)?;
+        let connection = Connection::open(&path)?;
+        connection.execute_batch(SCHEMA)?;
+        let parse_error =
+            |e: serde_json::Error| Error::Config(format!("台账解析失败 {:?}: {}", path, e));
+
+        // 内容 ID -> 该文章各平台的输出
+        let mut outputs: HashMap<String, Vec<StoredOutput>> = HashMap::new();
+        let mut statement =
+            connection.prepare("SELECT content_id, platform, path, html FROM outputs")?;
+        for row in statement.query_map([], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?, row.get(3)?)))
+        })? {
+            let (id, output): (String, StoredOutput) = row?;
+            outputs.entry(id).or_default().push(output);
+        }
+        drop(statement);
+
+        let mut records = Vec::new();
+        let mut statement = connection.prepare("SELECT id, record FROM contents ORDER BY rowid")?;
+        for row in statement.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get(1)?)))? {
+            let (id, raw): (String, String) = row?;
+            let mut record: ContentRecord = serde_json::from_str(&raw).map_err(parse_error)?;
+            for (platform, output, html) in outputs.remove(&id).unwrap_or_default() {
+                if let Some(output) = output {
+                    record
+                        .outputs
+                        .insert(platform.clone(), PathBuf::from(output));
+                }
+                if let Some(html) = html {
+                    record.html.insert(platform, html);
+                }
+            }
+            records.push(record);
+        }
+        drop(statement);
+
+        let mut media = Vec::new();
+        let mut statement = connection.prepare("SELECT record FROM media ORDER BY rowid")?;
+        for raw in statement.query_map([], |row| row.get::<_, String>(0))? {
+            media.push(serde_json::from_str(&raw?).map_err(parse_error)?);
+        }
+        drop(statement);
+
+        Ok(Self {
+            path,
+            connection,
+            records,
+            media,
+            changed: HashSet::new(),
+            media_changed: false,
+            _lock: lock,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 在一个事务中写回修改过的记录
+    pub fn save(&mut self) -> Result<()> {
+        let transaction = self.connection.transaction()?;
+        for record in self.records.iter().filter(|r| self.changed.contains(&r.id)) {
+            let id = record.id.to_string();
+            let stored = ContentRecord {
+                outputs: BTreeMap::new(),
+                ..record.clone()
+            };
+            transaction.execute(
+                "INSERT INTO contents (id, source, title, content_hash, record)
+                 VALUES (?1, ?2, ?3, ?4, ?5)
+                 ON CONFLICT (id) DO UPDATE SET source = excluded.source, title = excluded.title,
+                     content_hash = excluded.content_hash, record = excluded.record",
+                params![
+                    id,
+                    record.source.as_ref().map(|p| p.to_string_lossy()),
+                    record.title,
+                    record.content_hash,
+                    serde_json::to_string(&stored)?,
+                ],
+            )?;
+            transaction.execute("DELETE FROM outputs WHERE content_id = ?1", [&id])?;
+            let platforms: BTreeSet<&String> =
+                record.outputs.keys().chain(record.html.keys()).collect();
+            for platform in platforms {
+                transaction.execute(
+                    "INSERT INTO outputs (content_id, platform, path, html) VALUES (?1, ?2, ?3, ?4)",
+                    params![
+                        id,
+                        platform,
+                        record.outputs.get(platform).map(|p| p.to_string_lossy()),
+                        record.html.get(platform),
+                    ],
+                )?;
+            }
+        }
+        if self.media_changed {
+            transaction.execute("DELETE FROM media", [])?;
+            for media in &self.media {
+                transaction.execute(
+                    "INSERT INTO media (platform, hash, record) VALUES (?1, ?2, ?3)",
+                    params![
+                        media.platform.to_string(),
+                        media.hash,
+                        serde_json::to_string(media)?
+                    ],
+                )?;
+            }
+        }
+        transaction.commit()?;
+        self.changed.clear();
+        self.media_changed = false;
+        Ok(())
+    }
+
+    pub fn records(&self) -> &[ContentRecord] {
+        &self.records
+    }
+
+    pub fn get(&self, id: &Uuid) -> Option<&ContentRecord> {
+        self.records.iter().find(|r| &r.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: &Uuid) -> Option<&mut ContentRecord> {
+        let record = self.records.iter_mut().find(|r| &r.id == id)?;
+        self.changed.insert(record.id);
+        Some(record)
+    }
+
+    pub fn find_by_source(&self, source: &Path) -> Option<&ContentRecord> {
+        self.records
+            .iter()
+            .find(|r| r.source.as_deref() == Some(source))
+    }
+
+    /// 插入或按ID/源文件更新记录，返回记录ID
+    pub fn upsert(&mut self, record: ContentRecord) -> Uuid {
+        let existing = self
+            .records
+            .iter_mut()
+            .find(|r| r.id == record.id || (record.source.is_some() && r.source == record.source));
+
+        let id = match existing {
+            Some(existing) => {
+                let id = existing.id;
+                *existing = ContentRecord { id, ..record };
+                id
+            }
+            None => {
+                let id = record.id;
+                self.records.push(record);
+                id
+            }
+        };
+        self.changed.insert(id);
+        id
+    }
+
+    /// 复查日期不晚于 `today` 的文章，按复查日期从早到晚排序
+    pub fn due_for_review(&self, today: chrono::NaiveDate) -> Vec<&ContentRecord> {
+        let mut due: Vec<&ContentRecord> = self
+            .records
+            .iter()
+            .filter(|r| r.review_after.is_some_and(|date| date <= today))
+            .collect();
+        due.sort_by_key(|r| r.review_after);
+        due
+    }
+
+    /// 按标题变体分组的发布记录，用于比较不同标题的效果
+    pub fn publications_by_variant(&self, id: &Uuid) -> BTreeMap<String, Vec<&PublishResult>> {
+        let mut groups: BTreeMap<String, Vec<&PublishResult>> = BTreeMap::new();
+        for publication in self
+            .get(id)
+            .map(|r| r.publications.as_slice())
+            .unwrap_or(&[])
+        {
+            if let Some(label) = &publication.title_variant {
+                groups.entry(label.clone()).or_default().push(publication);
+            }
+        }
+        groups
+    }
+
+    pub fn find_media(&self, platform: &Platform, hash: &str) -> Option<&MediaRecord> {
+        self.media
+            .iter()
+            .find(|m| &m.platform == platform && m.hash == hash)
+    }
+
+    pub fn record_media(&mut self, media: MediaRecord) {
+        self.media
+            .retain(|m| !(m.platform == media.platform && m.hash == media.hash));
+        self.media.push(media);
+        self.media_changed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn record(source: &str) -> ContentRecord {
+        ContentRecord {
+            id: Uuid::new_v4(),
+            title: "标题".to_string(),
+            source: Some(PathBuf::from(source)),
+            content_hash: content_hash(b"body"),
+            outputs: BTreeMap::new(),
+            html: BTreeMap::new(),
+            publications: Vec::new(),
+            review_after: None,
+            title_variants: Default::default(),
+            tags: Vec::new(),
+            slug: None,
+            canonical_url: None,
+            workflow: Default::default(),
+            report: None,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_content_hash_is_stable() {
+        assert_eq!(content_hash(b"abc"), content_hash(b"abc"));
+        assert_ne!(content_hash(b"abc"), content_hash(b"abd"));
+        assert_eq!(content_hash(b"").len(), 64);
+    }
+
+    #[test]
+    fn test_upsert_keeps_id_for_same_source() {
+        let dir = TempDir::new().unwrap();
+        let mut store = ContentStore::open(dir.path().join(LEDGER_FILE)).unwrap();
+
+        let first = store.upsert(record("a.md"));
+        let second = store.upsert(record("a.md"));
+        store.upsert(record("b.md"));
+
+        assert_eq!(first, second);
+        assert_eq!(store.records().len(), 2);
+    }
+
+    #[test]
+    fn test_due_for_review() {
+        let dir = TempDir::new().unwrap();
+        let mut store = ContentStore::open(dir.path().join(LEDGER_FILE)).unwrap();
+        let date = |s: &str| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok();
+
+        for (source, review_after) in [
+            ("late.md", date("2025-03-01")),
+            ("early.md", date("2025-01-01")),
+            ("future.md", date("2026-01-01")),
+            ("none.md", None),
+        ] {
+            store.upsert(ContentRecord {
+                review_after,
+                ..record(source)
+            });
+        }
+
+        let due: Vec<_> = store
+            .due_for_review(date("2025-06-01").unwrap())
+            .into_iter()
+            .map(|r| r.source.clone().unwrap())
+            .collect();
+        assert_eq!(
+            due,
+            vec![PathBuf::from("early.md"), PathBuf::from("late.md")]
+        );
+    }
+
+    #[test]
+    fn test_pending_draft() {
+        let result = |platform: Platform, status: PublishStatus, draft_id: &str| PublishResult {
+            platform,
+            url: None,
+            draft_id: Some(draft_id.to_string()),
+            publish_id: None,
+            status,
+            message: String::new(),
+            title_variant: None,
+        };
+        let mut record = record("a.md");
+        assert_eq!(record.pending_draft("wechat"), None);
+
+        record.record_publication(result(Platform::WeChat, PublishStatus::Draft, "d1"));
+        record.record_publication(result(Platform::Zhihu, PublishStatus::Draft, "z1"));
+        assert_eq!(record.pending_draft("wechat"), Some("d1"));
+
+        record.record_publication(result(Platform::WeChat, PublishStatus::Success, "d1"));
+        assert_eq!(record.pending_draft("wechat"), None);
+        assert_eq!(record.pending_draft("zhihu"), Some("z1"));
+        assert!(matches!(
+            record.latest_publication("wechat").unwrap().status,
+            PublishStatus::Success
+        ));
+    }
+
+    #[test]
+    fn test_outputs_and_html_persist() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(LEDGER_FILE);
+
+        let mut store = ContentStore::open(&path).unwrap();
+        let id = store.upsert(ContentRecord {
+            outputs: [("wechat".to_string(), PathBuf::from("out/a.html"))].into(),
+            html: [
+                ("wechat".to_string(), "<p>公众号</p>".to_string()),
+                ("zhihu".to_string(), "<p>知乎</p>".to_string()),
+            ]
+            .into(),
+            ..record("a.md")
+        });
+        store.upsert(record("b.md"));
+        store.save().unwrap();
+        drop(store);
+
+        let mut store = ContentStore::open(&path).unwrap();
+        let saved = store.get(&id).unwrap();
+        assert_eq!(saved.outputs["wechat"], PathBuf::from("out/a.html"));
+        assert_eq!(saved.html["zhihu"], "<p>知乎</p>");
+        assert!(!saved.outputs.contains_key("zhihu"));
+        store.get_mut(&id).unwrap().title = "新标题".to_string();
+        store.save().unwrap();
+        drop(store);
+
+        let store = ContentStore::open(&path).unwrap();
+        let sources: Vec<_> = store.records().iter().map(|r| r.source.clone()).collect();
+        assert_eq!(sources, vec![Some("a.md".into()), Some("b.md".into())]);
+        assert_eq!(store.get(&id).unwrap().title, "新标题");
+        assert_eq!(store.get(&id).unwrap().html["wechat"], "<p>公众号</p>");
+    }
+
+    #[test]
+    fn test_media_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join(LEDGER_FILE);
+
+        let mut store = ContentStore::open(&path).unwrap();
+        store.record_media(MediaRecord {
+            platform: Platform::WeChat,
+            hash: "h1".to_string(),
+            source: "a.png".to_string(),
+            url: "https://mmbiz.qpic.cn/a".to_string(),
+            media_id: Some("m1".to_string()),
+            uploaded_at: chrono::Utc::now(),
+        });
+        store.save().unwrap();
+        drop(store);
+
+        let store = ContentStore::open(&path).unwrap();
+        assert!(store.find_media(&Platform::WeChat, "h1").is_some());
+        assert!(store.find_media(&Platform::Zhihu, "h1").is_none());
+    }
+}
@@ -0,0 +1,50 @@
+use crate::{
+    cli::args::StyleConfig,
+    core::{language::Language, locale::register_locale_filters},
+    Result,
+};
+use tera::{Context, Tera};
+
+/// 模板上下文，主题变量位于 `style` 下，如 `{{ style.primary_color }}`
+pub fn style_context(style: &StyleConfig) -> Context {
+    let mut context = Context::new();
+    context.insert("style", style);
+    context
+}
+
+/// 渲染单个模板字符串，`context` 通常以 [`style_context`] 为基础再插入文章数据；
+/// 模板中可以使用按 `locale` 格式化日期和数字的过滤器（见 [`register_locale_filters`]）
+pub fn render_template_str(template: &str, context: &Context, locale: Language) -> Result<String> {
+    const NAME: &str = "__markflow_template";
+    let mut tera = Tera::default();
+    tera.autoescape_on(Vec::new());
+    register_locale_filters(&mut tera, locale);
+    tera.add_raw_template(NAME, template)?;
+    Ok(tera.render(NAME, context)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_style_tokens_in_template() {
+        let style = StyleConfig {
+            primary_color: "#ff6600".to_string(),
+            ..StyleConfig::default()
+        };
+        let mut context = style_context(&style);
+        context.insert("title", "标题");
+
+        let html = render_template_str(
+            r#"<h1 style="color: {{ style.primary_color }}; font-size: {{ style.h1_font_size }}">{{ title }}</h1>"#,
+            &context,
+            Language::Zh,
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            r#"<h1 style="color: #ff6600; font-size: 24px">标题</h1>"#
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{
+    cli::args::{StyleConfig, TemplateConfig},
+    core::{
+        content::Content,
+        document::{find_template, template_context},
+        language::Language,
+        style::render_template_str,
+    },
+    error::Error,
+    Result,
+};
+use std::path::PathBuf;
+
+/// `template create` 未指定文件时写入的模板：文章信息页眉、正文和署名页脚，
+/// 输出仍是正文片段，可以直接粘贴到平台编辑器
+pub const DEFAULT_ARTICLE_TEMPLATE: &str = r#"<section style="margin-bottom: 24px; color: #888; font-size: 14px;">
+{%- if author %}{{ author | escape }}{% endif %}
+{%- if date %} · {{ date | local_date }}{% endif %}
+{%- if reading_time %} · 阅读约 {{ reading_time }} 分钟{% endif %}
+</section>
+{{ content }}
+<section style="margin-top: 32px; padding-top: 16px; border-top: 1px solid {{ style.primary_color }}; color: #888; font-size: 14px; text-align: center;">
+<p>{% if author %}— {{ author | escape }} —{% else %}感谢阅读{% endif %}</p>
+{%- if tags %}
+<p>{% for tag in tags %}#{{ tag | escape }}{% if not loop.last %} {% endif %}{% endfor %}</p>
+{%- endif %}
+</section>
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateEntry {
+    pub name: String,
+    pub path: PathBuf,
+    /// 来自 `templates.custom_templates`，不在模板目录中
+    pub custom: bool,
+}
+
+/// 模板目录中的 `.html` 文件和 `custom_templates` 中登记的模板，按名称排序
+pub fn list_templates(templates: &TemplateConfig) -> Result<Vec<TemplateEntry>> {
+    let mut entries: Vec<TemplateEntry> = templates
+        .custom_templates
+        .iter()
+        .map(|(name, path)| TemplateEntry {
+            name: name.clone(),
+            path: path.clone(),
+            custom: true,
+        })
+        .collect();
+    if templates.templates_dir.is_dir() {
+        for entry in std::fs::read_dir(&templates.templates_dir)? {
+            let path = entry?.path();
+            if !path.is_file() || path.extension().is_none_or(|ext| ext != "html") {
+                continue;
+            }
+            let name = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            if !templates.custom_templates.contains_key(&name) {
+                entries.push(TemplateEntry {
+                    name,
+                    path,
+                    custom: false,
+                });
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(entries)
+}
+
+/// 在模板目录中保存 `<name>.html`；`source` 为空时使用 [`DEFAULT_ARTICLE_TEMPLATE`]。
+/// 保存前检查模板语法，同名模板已存在时报错
+pub fn create_template(
+    templates: &TemplateConfig,
+    name: &str,
+    source: Option<&str>,
+) -> Result<PathBuf> {
+    validate_name(name)?;
+    if find_template(templates, name).is_some() {
+        return Err(Error::Config(format!("模板 {} 已存在", name)));
+    }
+    let source = source.unwrap_or(DEFAULT_ARTICLE_TEMPLATE);
+    tera::Tera::default()
+        .add_raw_template(name, source)
+        .map_err(|e| Error::Validation(format!("模板 {} 语法错误: {}", name, e)))?;
+
+    std::fs::create_dir_all(&templates.templates_dir)?;
+    let path = templates.templates_dir.join(format!("{}.html", name));
+    std::fs::write(&path, source)?;
+    Ok(path)
+}
+
+/// 删除模板目录中的模板文件；`custom_templates` 中登记的模板需要修改配置移除
+pub fn delete_template(templates: &TemplateConfig, name: &str) -> Result<PathBuf> {
+    validate_name(name)?;
+    if let Some(path) = templates.custom_templates.get(name) {
+        return Err(Error::Config(format!(
+            "模板 {} 登记在 templates.custom_templates 中（{:?}），请在配置文件中移除",
+            name, path
+        )));
+    }
+    let path = find_template(templates, name)
+        .ok_or_else(|| Error::Config(format!("模板 {} 不存在", name)))?;
+    std::fs::remove_file(&path)?;
+    Ok(path)
+}
+
+/// 用指定模板渲染文章，上下文见 [`template_context`]
+pub fn render_named_template(
+    templates: &TemplateConfig,
+    name: &str,
+    html: &str,
+    content: &Content,
+    platform: &str,
+    style: &StyleConfig,
+    locale: Language,
+) -> Result<String> {
+    let path = find_template(templates, name)
+        .ok_or_else(|| Error::Config(format!("模板 {} 不存在", name)))?;
+    let template = std::fs::read_to_string(&path)?;
+    render_template_str(
+        &template,
+        &template_context(html, content, platform, style),
+        locale,
+    )
+}
+
+/// 模板名用作文件名，不能包含路径
+fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(Error::Config(format!("无效的模板名: {:?}", name)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_template_lifecycle() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = TemplateConfig {
+            templates_dir: dir.path().join("templates"),
+            default_template: None,
+            custom_templates: HashMap::from([(
+                "shared".to_string(),
+                dir.path().join("shared.html"),
+            )]),
+        };
+
+        let path = create_template(&templates, "signature", None).unwrap();
+        assert_eq!(path, dir.path().join("templates/signature.html"));
+        create_template(&templates, "plain", Some("<main>{{ html }}</main>")).unwrap();
+        assert!(create_template(&templates, "plain", None).is_err());
+        assert!(create_template(&templates, "broken", Some("{% if %}")).is_err());
+        assert!(create_template(&templates, "../escape", None).is_err());
+
+        let names: Vec<_> = list_templates(&templates)
+            .unwrap()
+            .into_iter()
+            .map(|entry| (entry.name, entry.custom))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("plain".to_string(), false),
+                ("shared".to_string(), true),
+                ("signature".to_string(), false)
+            ]
+        );
+
+        assert!(delete_template(&templates, "shared").is_err());
+        delete_template(&templates, "plain").unwrap();
+        assert!(delete_template(&templates, "plain").is_err());
+    }
+
+    #[test]
+    fn test_render_default_article_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = TemplateConfig {
+            templates_dir: dir.path().to_path_buf(),
+            default_template: None,
+            custom_templates: HashMap::new(),
+        };
+        create_template(&templates, "signature", None).unwrap();
+
+        let mut content = Content::new("标题".to_string(), "正文".to_string());
+        content.metadata.author = Some("张三".to_string());
+        content.metadata.reading_time = Some(3);
+        content.metadata.tags = vec!["Rust".to_string(), "异步".to_string()];
+        let html = render_named_template(
+            &templates,
+            "signature",
+            "<p>正文</p>",
+            &content,
+            "wechat",
+            &StyleConfig::default(),
+            Language::Zh,
+        )
+        .unwrap();
+
+        assert!(html.contains("张三 · 阅读约 3 分钟"));
+        assert!(html.contains("<p>正文</p>"));
+        assert!(html.contains("<p>— 张三 —</p>"));
+        assert!(html.contains("<p>#Rust #异步</p>"));
+        assert!(render_named_template(
+            &templates,
+            "missing",
+            "",
+            &content,
+            "wechat",
+            &StyleConfig::default(),
+            Language::Zh
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{
+    cli::args::TerminologyConfig,
+    core::{content::Content, pipeline::ProcessingStage},
+    error::Error,
+    Result,
+};
+use async_trait::async_trait;
+use regex::Regex;
+use std::{collections::BTreeMap, ops::Range, sync::OnceLock};
+
+/// 内置的常见写法错误，用户词典中的同名条目会覆盖这里的值
+const BUILTIN_TERMS: &[(&str, &str)] = &[
+    ("Github", "GitHub"),
+    ("Gitlab", "GitLab"),
+    ("Javascript", "JavaScript"),
+    ("Typescript", "TypeScript"),
+    ("Nodejs", "Node.js"),
+    ("Mysql", "MySQL"),
+    ("Postgresql", "PostgreSQL"),
+    ("Mongodb", "MongoDB"),
+    ("Iphone", "iPhone"),
+    ("IOS", "iOS"),
+    ("Wechat", "WeChat"),
+    ("web端", "Web 端"),
+];
+
+/// 源文件中发现的术语问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermIssue {
+    /// 行号（从 1 开始）
+    pub line: usize,
+    pub wrong: String,
+    pub preferred: String,
+}
+
+/// 术语词典：错误写法 -> 推荐写法
+#[derive(Debug, Clone, Default)]
+pub struct Terminology {
+    terms: Vec<(String, String)>,
+}
+
+impl Terminology {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn builtin() -> Self {
+        BUILTIN_TERMS
+            .iter()
+            .fold(Self::new(), |t, (wrong, preferred)| {
+                t.with_term(*wrong, *preferred)
+            })
+    }
+
+    pub fn with_term(mut self, wrong: impl Into<String>, preferred: impl Into<String>) -> Self {
+        let wrong = wrong.into();
+        let preferred = preferred.into();
+        if wrong.is_empty() || wrong == preferred {
+            return self;
+        }
+        self.terms.retain(|(w, _)| w != &wrong);
+        self.terms.push((wrong, preferred));
+        // 长词优先匹配，避免短词先替换掉长词的一部分
+        self.terms.sort_by(|a, b| {
+            b.0.chars()
+                .count()
+                .cmp(&a.0.chars().count())
+                .then(a.0.cmp(&b.0))
+        });
+        self
+    }
+
+    /// 按配置组合内置词典、配置中的词条和外部词典文件（TOML：`"错误" = "推荐"`）
+    pub fn from_config(config: &TerminologyConfig) -> Result<Self> {
+        let mut terminology = if config.builtin {
+            Self::builtin()
+        } else {
+            Self::new()
+        };
+
+        if let Some(path) = &config.dictionary_file {
+            let raw = std::fs::read_to_string(path)
+                .map_err(|e| Error::Config(format!("读取术语词典失败 {:?}: {}", path, e)))?;
+            let terms: BTreeMap<String, String> = toml::from_str(&raw)
+                .map_err(|e| Error::Config(format!("术语词典格式错误 {:?}: {}", path, e)))?;
+            for (wrong, preferred) in terms {
+                terminology = terminology.with_term(wrong, preferred);
+            }
+        }
+        for (wrong, preferred) in &config.terms {
+            terminology = terminology.with_term(wrong, preferred);
+        }
+
+        Ok(terminology)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.terms.len()
+    }
+
+    /// 检查 Markdown 源文件，跳过代码块、行内代码和链接地址
+    pub fn check_markdown(&self, markdown: &str) -> Vec<TermIssue> {
+        let mut issues = Vec::new();
+        let mut fence: Option<&str> = None;
+
+        for (index, line) in markdown.lines().enumerate() {
+            let trimmed = line.trim_start();
+            if let Some(marker) = fence {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+                continue;
+            }
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                fence = Some(&trimmed[..3]);
+                continue;
+            }
+
+            let skipped = skipped_ranges(line);
+            for (wrong, preferred, range) in self.matches(line) {
+                if skipped
+                    .iter()
+                    .any(|s| s.start < range.end && range.start < s.end)
+                {
+                    continue;
+                }
+                issues.push(TermIssue {
+                    line: index + 1,
+                    wrong: wrong.to_string(),
+                    preferred: preferred.to_string(),
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// 替换纯文本中的错误写法，返回替换后的文本和替换次数
+    pub fn fix_text(&self, text: &str) -> (String, usize) {
+        let matches = self.matches(text);
+        if matches.is_empty() {
+            return (text.to_string(), 0);
+        }
+
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for (_, preferred, range) in &matches {
+            out.push_str(&text[last..range.start]);
+            out.push_str(preferred);
+            last = range.end;
+        }
+        out.push_str(&text[last..]);
+        (out, matches.len())
+    }
+
+    /// 替换 HTML 文本节点中的错误写法，不改动标签属性和 code/pre 中的内容
+    pub fn fix_html(&self, html: &str) -> (String, usize) {
+        static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+        let regex = TAG_REGEX.get_or_init(|| Regex::new(r"(?s)<!--.*?-->|<[^>]+>").unwrap());
+
+        let mut out = String::with_capacity(html.len());
+        let mut count = 0;
+        let mut protected = 0usize;
+        let mut last = 0;
+
+        for tag in regex.find_iter(html) {
+            let text = &html[last..tag.start()];
+            if protected == 0 {
+                let (fixed, n) = self.fix_text(text);
+                out.push_str(&fixed);
+                count += n;
+            } else {
+                out.push_str(text);
+            }
+            out.push_str(tag.as_str());
+            last = tag.end();
+
+            let name = tag_name(tag.as_str());
+            if matches!(name.as_str(), "code" | "pre" | "script" | "style" | "kbd") {
+                if tag.as_str().starts_with("</") {
+                    protected = protected.saturating_sub(1);
+                } else if !tag.as_str().ends_with("/>") {
+                    protected += 1;
+                }
+            }
+        }
+
+        let rest = &html[last..];
+        if protected == 0 {
+            let (fixed, n) = self.fix_text(rest);
+            out.push_str(&fixed);
+            count += n;
+        } else {
+            out.push_str(rest);
+        }
+        (out, count)
+    }
+
+    /// 查找所有不重叠的匹配，按位置排序
+    fn matches<'a>(&'a self, text: &str) -> Vec<(&'a str, &'a str, Range<usize>)> {
+        let mut found: Vec<(&str, &str, Range<usize>)> = Vec::new();
+        for (wrong, preferred) in &self.terms {
+            for (start, _) in text.match_indices(wrong.as_str()) {
+                let range = start..start + wrong.len();
+                if !is_word_boundary(text, &range, wrong) {
+                    continue;
+                }
+                if found
+                    .iter()
+                    .any(|(_, _, r)| r.start < range.end && range.start < r.end)
+                {
+                    continue;
+                }
+                found.push((wrong, preferred, range));
+            }
+        }
+        found.sort_by_key(|(_, _, r)| r.start);
+        found
+    }
+}
+
+/// 以 ASCII 字母数字开头或结尾的词需要完整匹配，避免把 `Githubber` 里的 `Github` 也替换掉
+fn is_word_boundary(text: &str, range: &Range<usize>, wrong: &str) -> bool {
+    let before = text[..range.start].chars().next_back();
+    let after = text[range.end..].chars().next();
+    let starts_ascii = wrong
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphanumeric());
+    let ends_ascii = wrong
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_ascii_alphanumeric());
+
+    let word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    !(starts_ascii && before.is_some_and(word_char) || ends_ascii && after.is_some_and(word_char))
+}
+
+/// 一行 Markdown 中不需要检查的区域：行内代码、链接地址、自动链接、裸 URL 和 HTML 标签
+fn skipped_ranges(line: &str) -> Vec<Range<usize>> {
+    static SKIP_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = SKIP_REGEX.get_or_init(|| {
+        Regex::new(r"`+[^`]*`+|\]\([^)]*\)|<[^>\s][^>]*>|https?://[^\s)>\]]+").unwrap()
+    });
+    regex.find_iter(line).map(|m| m.range()).collect()
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches('<')
+        .trim_start_matches('/')
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric())
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// 在输出中自动修正术语（源文件保持不变）
+pub struct TerminologyStage {
+    terminology: Terminology,
+}
+
+impl TerminologyStage {
+    pub fn new(terminology: Terminology) -> Self {
+        Self { terminology }
+    }
+}
+
+#[async_trait]
+impl ProcessingStage for TerminologyStage {
+    async fn process(&self, content: &mut Content) -> Result<()> {
+        let (title, title_fixes) = self.terminology.fix_text(&content.title);
+        let (html, html_fixes) = self.terminology.fix_html(&content.html);
+        content.title = title;
+        content.html = html;
+
+        if title_fixes + html_fixes > 0 {
+            tracing::info!("术语修正 {} 处", title_fixes + html_fixes);
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "术语修正"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_markdown_skips_code_and_urls() {
+        let markdown = "# 使用 Github\n\n代码托管在 [Github](https://Github.com/a) 上，`Github` 不检查。\n\n```\nGithub\n```\n\nGithubber 和 web端 开发\n";
+        let issues = Terminology::builtin().check_markdown(markdown);
+        assert_eq!(
+            issues,
+            vec![
+                TermIssue {
+                    line: 1,
+                    wrong: "Github".to_string(),
+                    preferred: "GitHub".to_string(),
+                },
+                TermIssue {
+                    line: 3,
+                    wrong: "Github".to_string(),
+                    preferred: "GitHub".to_string(),
+                },
+                TermIssue {
+                    line: 9,
+                    wrong: "web端".to_string(),
+                    preferred: "Web 端".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fix_html_keeps_code_and_attributes() {
+        let terminology = Terminology::new().with_term("Github", "GitHub");
+        let html = r#"<p>访问 <a href="https://Github.com">Github</a>，运行 <code>Github</code></p><pre><code>Github</code></pre>"#;
+        let (fixed, count) = terminology.fix_html(html);
+        assert_eq!(count, 1);
+        assert_eq!(
+            fixed,
+            r#"<p>访问 <a href="https://Github.com">GitHub</a>，运行 <code>Github</code></p><pre><code>Github</code></pre>"#
+        );
+    }
+
+    #[test]
+    fn test_user_terms_override_builtin() {
+        let config = TerminologyConfig {
+            terms: BTreeMap::from([("Github".to_string(), "GitHub 网站".to_string())]),
+            ..TerminologyConfig::default()
+        };
+        let terminology = Terminology::from_config(&config).unwrap();
+        assert_eq!(terminology.fix_text("用 Github").0, "用 GitHub 网站");
+    }
+}
@@ -0,0 +1,159 @@
+use comrak::{
+    nodes::{AstNode, NodeValue},
+    parse_document, Arena, ComrakOptions,
+};
+use jieba_rs::{Jieba, KeywordExtract, KeywordExtractConfig, TfIdf};
+use std::{collections::HashMap, sync::OnceLock};
+
+/// 提取标签时保留的词性：名词、人名、地名、机构名、其他专名、动名词和英文
+const TAG_POS: &[&str] = &["n", "nr", "ns", "nt", "nz", "vn", "eng"];
+
+/// 全局分词器，首次使用时加载默认词典
+pub fn tokenizer() -> &'static Jieba {
+    static JIEBA: OnceLock<Jieba> = OnceLock::new();
+    JIEBA.get_or_init(Jieba::new)
+}
+
+fn keyword_extractor() -> &'static TfIdf {
+    static TFIDF: OnceLock<TfIdf> = OnceLock::new();
+    TFIDF.get_or_init(TfIdf::default)
+}
+
+fn stop_words() -> &'static KeywordExtractConfig {
+    static CONFIG: OnceLock<KeywordExtractConfig> = OnceLock::new();
+    CONFIG.get_or_init(KeywordExtractConfig::default)
+}
+
+/// 关键词出现次数与密度
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordStat {
+    pub word: String,
+    pub count: usize,
+    /// 占全部有效词的比例（0-1）
+    pub density: f64,
+}
+
+/// 提取 Markdown 中的正文文本，跳过代码块、行内代码、HTML 和链接地址
+pub fn plain_text(markdown: &str) -> String {
+    let arena = Arena::new();
+    let mut options = ComrakOptions::default();
+    options.extension.table = true;
+    options.extension.strikethrough = true;
+    options.extension.front_matter_delimiter = Some("---".to_string());
+    let root = parse_document(&arena, markdown, &options);
+
+    let mut text = String::new();
+    collect_text(root, &mut text);
+    text
+}
+
+fn collect_text<'a>(node: &'a AstNode<'a>, out: &mut String) {
+    match &node.data.borrow().value {
+        NodeValue::Text(text) => out.push_str(text),
+        NodeValue::SoftBreak | NodeValue::LineBreak => out.push(' '),
+        NodeValue::CodeBlock(_)
+        | NodeValue::Code(_)
+        | NodeValue::HtmlBlock(_)
+        | NodeValue::HtmlInline(_)
+        | NodeValue::FrontMatter(_) => return,
+        _ => {}
+    }
+
+    for child in node.children() {
+        collect_text(child, out);
+    }
+
+    if matches!(
+        node.data.borrow().value,
+        NodeValue::Paragraph | NodeValue::Heading(_) | NodeValue::Item(_) | NodeValue::TableCell
+    ) {
+        out.push('\n');
+    }
+}
+
+/// 把文本切分为有意义的词：去掉标点、空白、纯数字、单字和停用词，英文统一小写
+pub fn words(text: &str) -> Vec<String> {
+    let stop_words = stop_words().stop_words();
+    tokenizer()
+        .cut(text, true)
+        .into_iter()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| w.chars().count() >= 2)
+        .filter(|w| w.chars().any(char::is_alphabetic))
+        .filter(|w| !stop_words.contains(w))
+        .collect()
+}
+
+/// 统计 Markdown 正文的关键词密度，按出现次数降序返回前 `limit` 个
+pub fn keyword_density(markdown: &str, limit: usize) -> Vec<KeywordStat> {
+    let words = words(&plain_text(markdown));
+    let total = words.len();
+    if total == 0 {
+        return Vec::new();
+    }
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for word in words {
+        *counts.entry(word).or_insert(0) += 1;
+    }
+
+    let mut stats: Vec<KeywordStat> = counts
+        .into_iter()
+        .map(|(word, count)| KeywordStat {
+            density: count as f64 / total as f64,
+            word,
+            count,
+        })
+        .collect();
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    stats.truncate(limit);
+    stats
+}
+
+/// 基于 TF-IDF 为文章推荐标签
+pub fn suggest_tags(markdown: &str, limit: usize) -> Vec<String> {
+    let text = plain_text(markdown);
+    let allowed = TAG_POS.iter().map(|s| s.to_string()).collect();
+    keyword_extractor()
+        .extract_keywords(tokenizer(), &text, limit, allowed)
+        .into_iter()
+        .map(|k| k.keyword)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARTICLE: &str = "---\ntitle: 测试\n---\n\n# Rust 异步编程\n\nRust 的异步编程基于 Future，异步运行时负责调度 Future。\n\n```rust\nlet future = async {};\n```\n\n学习异步编程需要理解 `Pin` 和 [运行时](https://tokio.rs)。\n";
+
+    #[test]
+    fn test_plain_text_skips_code_and_urls() {
+        let text = plain_text(ARTICLE);
+        assert!(text.contains("Rust 异步编程"));
+        assert!(text.contains("运行时"));
+        assert!(!text.contains("let future"));
+        assert!(!text.contains("Pin"));
+        assert!(!text.contains("tokio.rs"));
+        assert!(!text.contains("title"));
+    }
+
+    #[test]
+    fn test_keyword_density() {
+        let stats = keyword_density(ARTICLE, 3);
+        assert_eq!(stats[0].word, "异步");
+        assert_eq!(stats[0].count, 4);
+        assert!(stats[0].density > 0.1);
+        assert!(stats.iter().all(|s| s.word != "的"));
+    }
+
+    #[test]
+    fn test_suggest_tags() {
+        let tags = suggest_tags(ARTICLE, 5);
+        assert!(
+            tags.iter().any(|t| t == "Rust" || t == "异步"),
+            "{:?}",
+            tags
+        );
+    }
+}
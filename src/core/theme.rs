@@ -0,0 +1,81 @@
+use crate::{cli::args::StyleConfig, error::Error, Result};
+use std::path::{Path, PathBuf};
+
+/// 主题目录，位于配置文件所在目录下（默认 `~/.markflow/themes`）
+pub const THEMES_DIR: &str = "themes";
+
+pub fn theme_path(themes_dir: &Path, name: &str) -> PathBuf {
+    themes_dir.join(format!("{}.toml", name))
+}
+
+/// 主题文件对应的主题名，不是 `themes/<name>.toml` 时返回 `None`
+pub fn theme_name(path: &Path) -> Option<String> {
+    let in_themes_dir = path
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|dir| dir == THEMES_DIR);
+    let is_toml = path.extension().is_some_and(|ext| ext == "toml");
+    (in_themes_dir && is_toml)
+        .then(|| path.file_stem())
+        .flatten()
+        .map(|stem| stem.to_string_lossy().into_owned())
+}
+
+/// 按 `style.theme` 用主题文件中的变量覆盖 `[style]`。
+/// 主题文件的格式与 `[style]` 相同，只需写出要修改的变量
+pub fn apply_theme(style: &StyleConfig, themes_dir: &Path) -> Result<StyleConfig> {
+    let Some(name) = style.theme.as_deref().filter(|name| !name.is_empty()) else {
+        return Ok(style.clone());
+    };
+    let path = theme_path(themes_dir, name);
+    let invalid =
+        |e: &dyn std::fmt::Display| Error::Config(format!("主题 {} 无效 {:?}: {}", name, path, e));
+
+    let raw = std::fs::read_to_string(&path).map_err(|e| invalid(&e))?;
+    let overrides: toml::Table = toml::from_str(&raw).map_err(|e| invalid(&e))?;
+    let mut table = toml::Table::try_from(style).map_err(|e| invalid(&e))?;
+    table.extend(overrides);
+    let mut themed: StyleConfig = toml::Value::Table(table)
+        .try_into()
+        .map_err(|e| invalid(&e))?;
+    themed.theme = style.theme.clone();
+    Ok(themed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_overrides_style() {
+        let dir = tempfile::tempdir().unwrap();
+        let themes = dir.path().join(THEMES_DIR);
+        std::fs::create_dir_all(&themes).unwrap();
+        std::fs::write(
+            theme_path(&themes, "dark"),
+            "primary_color = \"#ff6600\"\nbackground_color = \"#111\"\n",
+        )
+        .unwrap();
+
+        let style = StyleConfig {
+            theme: Some("dark".to_string()),
+            font_size: "15px".to_string(),
+            ..StyleConfig::default()
+        };
+        let themed = apply_theme(&style, &themes).unwrap();
+        assert_eq!(themed.primary_color, "#ff6600");
+        assert_eq!(themed.background_color, "#111");
+        assert_eq!(themed.font_size, "15px");
+
+        let missing = StyleConfig {
+            theme: Some("missing".to_string()),
+            ..StyleConfig::default()
+        };
+        assert!(apply_theme(&missing, &themes).is_err());
+        assert_eq!(
+            theme_name(&theme_path(&themes, "dark")).as_deref(),
+            Some("dark")
+        );
+        assert_eq!(theme_name(&dir.path().join("dark.toml")), None);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::error::Error;
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc};
+use std::str::FromStr;
+
+/// 文件名时间戳、front matter 日期等使用的时区设置
+///
+/// 支持 `local`（默认，跟随系统）、`utc`、固定偏移（如 `+08:00`）和 IANA 时区名（如 `Asia/Shanghai`）。
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum TimeZoneSetting {
+    #[default]
+    Local,
+    Utc,
+    Fixed(FixedOffset),
+    Named(chrono_tz::Tz),
+}
+
+impl FromStr for TimeZoneSetting {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let value = s.trim();
+        match value.to_lowercase().as_str() {
+            "" | "local" => return Ok(TimeZoneSetting::Local),
+            "utc" | "z" => return Ok(TimeZoneSetting::Utc),
+            _ => {}
+        }
+
+        if value.starts_with('+') || value.starts_with('-') {
+            return DateTime::parse_from_str(
+                &format!("2000-01-01 00:00 {}", value),
+                "%Y-%m-%d %H:%M %:z",
+            )
+            .map(|dt| TimeZoneSetting::Fixed(*dt.offset()))
+            .map_err(|_| Error::Config(format!("无效的时区偏移: {}", value)));
+        }
+
+        value
+            .parse::<chrono_tz::Tz>()
+            .map(TimeZoneSetting::Named)
+            .map_err(|_| Error::Config(format!("未知的时区: {}", value)))
+    }
+}
+
+impl TimeZoneSetting {
+    /// 从配置值解析，无效时回退到本地时区
+    pub fn from_config(value: &str) -> Self {
+        value.parse().unwrap_or_else(|e| {
+            tracing::warn!("{}，使用本地时区", e);
+            TimeZoneSetting::Local
+        })
+    }
+
+    pub fn now(&self) -> DateTime<FixedOffset> {
+        self.to_local(Utc::now())
+    }
+
+    pub fn to_local(&self, datetime: DateTime<Utc>) -> DateTime<FixedOffset> {
+        match self {
+            TimeZoneSetting::Local => datetime.with_timezone(&Local).fixed_offset(),
+            TimeZoneSetting::Utc => datetime.fixed_offset(),
+            TimeZoneSetting::Fixed(offset) => datetime.with_timezone(offset),
+            TimeZoneSetting::Named(tz) => {
+                let local = datetime.with_timezone(tz);
+                local.with_timezone(&local.offset().fix())
+            }
+        }
+    }
+
+    fn localize(&self, naive: NaiveDateTime) -> Option<DateTime<FixedOffset>> {
+        match self {
+            TimeZoneSetting::Local => Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.fixed_offset()),
+            TimeZoneSetting::Utc => Some(Utc.from_utc_datetime(&naive).fixed_offset()),
+            TimeZoneSetting::Fixed(offset) => offset.from_local_datetime(&naive).earliest(),
+            TimeZoneSetting::Named(tz) => tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.with_timezone(&dt.offset().fix())),
+        }
+    }
+
+    /// 解析日期时间字符串。带时区的 RFC 3339 时间保持原偏移，
+    /// 不带时区的 `YYYY-MM-DD[ HH:MM[:SS]]` 按当前时区解释。
+    pub fn parse_datetime(&self, value: &str) -> Option<DateTime<FixedOffset>> {
+        let value = value.trim();
+
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+            return Some(datetime);
+        }
+
+        for format in [
+            "%Y-%m-%d %H:%M:%S",
+            "%Y-%m-%d %H:%M",
+            "%Y-%m-%dT%H:%M:%S",
+            "%Y-%m-%dT%H:%M",
+        ] {
+            if let Ok(naive) = NaiveDateTime::parse_from_str(value, format) {
+                return self.localize(naive);
+            }
+        }
+
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .and_then(|naive| self.localize(naive))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timezone_setting() {
+        assert_eq!(
+            "local".parse::<TimeZoneSetting>().unwrap(),
+            TimeZoneSetting::Local
+        );
+        assert_eq!(
+            "UTC".parse::<TimeZoneSetting>().unwrap(),
+            TimeZoneSetting::Utc
+        );
+        assert_eq!(
+            "+08:00".parse::<TimeZoneSetting>().unwrap(),
+            TimeZoneSetting::Fixed(FixedOffset::east_opt(8 * 3600).unwrap())
+        );
+        assert_eq!(
+            "Asia/Shanghai".parse::<TimeZoneSetting>().unwrap(),
+            TimeZoneSetting::Named(chrono_tz::Asia::Shanghai)
+        );
+        assert!("Mars/Olympus".parse::<TimeZoneSetting>().is_err());
+    }
+
+    #[test]
+    fn test_to_local_uses_beijing_time() {
+        let tz = TimeZoneSetting::Named(chrono_tz::Asia::Shanghai);
+        let utc = Utc.with_ymd_and_hms(2024, 6, 30, 20, 0, 0).unwrap();
+
+        // UTC 晚上8点已经是北京时间第二天
+        assert_eq!(
+            tz.to_local(utc).format("%Y%m%d_%H%M%S").to_string(),
+            "20240701_040000"
+        );
+    }
+
+    #[test]
+    fn test_parse_datetime() {
+        let tz: TimeZoneSetting = "+08:00".parse().unwrap();
+
+        let date = tz.parse_datetime("2024-07-01").unwrap();
+        assert_eq!(date.to_rfc3339(), "2024-07-01T00:00:00+08:00");
+
+        let datetime = tz.parse_datetime("2024-07-01 09:30").unwrap();
+        assert_eq!(datetime.to_rfc3339(), "2024-07-01T09:30:00+08:00");
+
+        let explicit = tz.parse_datetime("2024-07-01T09:30:00Z").unwrap();
+        assert_eq!(explicit.to_rfc3339(), "2024-07-01T09:30:00+00:00");
+
+        assert!(tz.parse_datetime("not a date").is_none());
+    }
+}
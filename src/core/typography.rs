@@ -0,0 +1,151 @@
+use crate::{error::Error, Result};
+use std::str::FromStr;
+
+/// 智能标点（直引号转弯引号、`--`/`---` 转破折号、`...` 转省略号）的作用范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SmartPunctuation {
+    /// 保留原样
+    Off,
+    /// 只替换拉丁文片段中的标点，与中日韩文字相邻的标点保留原样
+    #[default]
+    Latin,
+    /// 全文替换（comrak 的 smart 选项），中文引号可能被转成错误的方向
+    All,
+}
+
+impl FromStr for SmartPunctuation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "off" => Ok(SmartPunctuation::Off),
+            "latin" => Ok(SmartPunctuation::Latin),
+            "all" => Ok(SmartPunctuation::All),
+            _ => Err(Error::Config(format!(
+                "无效的智能标点设置: {}（可选: off、latin、all）",
+                s
+            ))),
+        }
+    }
+}
+
+impl SmartPunctuation {
+    pub fn from_config(value: &str) -> Self {
+        value.parse().unwrap_or_else(|e| {
+            tracing::warn!("{}，只处理拉丁文片段", e);
+            SmartPunctuation::Latin
+        })
+    }
+}
+
+/// 中日韩文字和全角标点
+pub fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x2E80..=0x2FDF
+        | 0x3000..=0x30FF
+        | 0x3100..=0x312F
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xAC00..=0xD7AF
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFFEF
+        | 0x20000..=0x2FA1F)
+}
+
+fn latin_side(c: Option<char>) -> bool {
+    !c.is_some_and(is_cjk)
+}
+
+/// 只在拉丁文片段中替换的智能标点。同一段落的文本被行内元素拆成多个节点，
+/// 因此跨节点记住上一个字符来判断引号方向；进入新的块时调用 `reset`
+#[derive(Debug, Default)]
+pub struct LatinSmartPunctuation {
+    previous: Option<char>,
+}
+
+impl LatinSmartPunctuation {
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    /// 记录不做替换的文本（行内代码等），只用作上下文
+    pub fn skip(&mut self, text: &str) {
+        if let Some(last) = text.chars().last() {
+            self.previous = Some(last);
+        }
+    }
+
+    pub fn convert(&mut self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut output = String::with_capacity(text.len());
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            let previous = if i == 0 {
+                self.previous
+            } else {
+                Some(chars[i - 1])
+            };
+            match c {
+                '"' | '\'' if latin_side(previous) && latin_side(chars.get(i + 1).copied()) => {
+                    let opening =
+                        previous.is_none_or(|p| p.is_whitespace() || "([{-–—“‘".contains(p));
+                    output.push(match (c, opening) {
+                        ('"', true) => '“',
+                        ('"', false) => '”',
+                        ('\'', true) => '‘',
+                        _ => '’',
+                    });
+                }
+                '-' | '.' => {
+                    let run = chars[i..].iter().take_while(|&&x| x == c).count();
+                    let replacement = match (c, run) {
+                        ('-', 2) => Some('–'),
+                        ('-', 3) => Some('—'),
+                        ('.', 3) => Some('…'),
+                        _ => None,
+                    };
+                    match replacement {
+                        Some(r)
+                            if latin_side(previous) && latin_side(chars.get(i + run).copied()) =>
+                        {
+                            output.push(r)
+                        }
+                        _ => output.extend(&chars[i..i + run]),
+                    }
+                    i += run;
+                    continue;
+                }
+                _ => output.push(c),
+            }
+            i += 1;
+        }
+        self.skip(text);
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latin_smart_punctuation() {
+        let mut smart = LatinSmartPunctuation::default();
+        assert_eq!(
+            smart.convert(r#"He said "it's fine" -- really... Wait---what?"#),
+            "He said “it’s fine” – really… Wait—what?"
+        );
+        smart.reset();
+        assert_eq!(
+            smart.convert(r#"他说"你好"，然后--走了...，英文 "OK" 也行"#),
+            r#"他说"你好"，然后--走了...，英文 “OK” 也行"#
+        );
+        assert_eq!(
+            "ALL".parse::<SmartPunctuation>().unwrap(),
+            SmartPunctuation::All
+        );
+        assert!("chinese".parse::<SmartPunctuation>().is_err());
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    cli::args::UpdateConfig,
+    core::{
+        http_client_for,
+        lock::{write_atomic, FileLock},
+        NetworkOperation,
+    },
+    error::Error,
+    Result,
+};
+use chrono::{DateTime, Duration, Utc};
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const GITHUB_API: &str = "https://api.github.com";
+
+/// GitHub 上的一个发布版本
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub url: String,
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    #[serde(rename = "browser_download_url")]
+    pub download_url: String,
+}
+
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    html_url: String,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+impl ReleaseInfo {
+    pub fn is_newer_than(&self, version: &str) -> bool {
+        is_newer(&self.version, version)
+    }
+
+    pub fn asset(&self, name: &str) -> Option<&ReleaseAsset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// 当前平台对应的发布包名称，如 `markflow-x86_64-linux`、`markflow-aarch64-macos`
+pub fn release_asset_name() -> String {
+    format!(
+        "markflow-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+/// 比较形如 `v1.2.3` 的版本号，忽略预发布后缀
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    fn parts(version: &str) -> Vec<u64> {
+        version
+            .trim_start_matches('v')
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    }
+    let (latest, current) = (parts(latest), parts(current));
+    let len = latest.len().max(current.len());
+    let pad = |v: &[u64]| {
+        (0..len)
+            .map(|i| v.get(i).copied().unwrap_or(0))
+            .collect::<Vec<_>>()
+    };
+    pad(&latest) > pad(&current)
+}
+
+/// 查询仓库的最新正式版本
+pub async fn fetch_latest_release(repository: &str) -> Result<ReleaseInfo> {
+    fetch_release(&format!(
+        "{}/repos/{}/releases/latest",
+        GITHUB_API, repository
+    ))
+    .await
+}
+
+async fn fetch_release(url: &str) -> Result<ReleaseInfo> {
+    let release: GitHubRelease = http_client_for(NetworkOperation::Download)?
+        .get(url)
+        .header(reqwest::header::ACCEPT, "application/vnd.github+json")
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+    Ok(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        url: release.html_url,
+        assets: release.assets,
+    })
+}
+
+/// 用 minisign 公钥校验发布包签名
+pub fn verify_release_signature(data: &[u8], signature: &str, public_key: &str) -> Result<()> {
+    let public_key = PublicKey::from_base64(public_key.trim())
+        .or_else(|_| PublicKey::decode(public_key.trim()))
+        .map_err(|e| Error::Config(format!("无效的 update.public_key: {}", e)))?;
+    let signature = Signature::decode(signature.trim())
+        .map_err(|e| Error::Validation(format!("无效的发布包签名: {}", e)))?;
+    public_key
+        .verify(data, &signature, false)
+        .map_err(|e| Error::Validation(format!("发布包签名校验失败: {}", e)))
+}
+
+/// 用新版本替换正在运行的可执行文件
+pub fn replace_executable(path: &Path, data: &[u8]) -> Result<()> {
+    let staged = path.with_extension("update");
+    std::fs::write(&staged, data)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(windows)]
+    {
+        // Windows 不允许覆盖正在运行的程序，但允许重命名
+        let old = path.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(path, &old)?;
+    }
+    std::fs::rename(&staged, path)?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct UpdateCache {
+    checked_at: DateTime<Utc>,
+    latest_version: String,
+    url: String,
+}
+
+/// 启动时的更新检查。结果缓存在磁盘上，按间隔在后台刷新，不阻塞命令执行
+pub struct UpdateChecker {
+    config: UpdateConfig,
+    cache_path: PathBuf,
+}
+
+impl UpdateChecker {
+    pub fn new(config: UpdateConfig, cache_path: impl Into<PathBuf>) -> Self {
+        Self {
+            config,
+            cache_path: cache_path.into(),
+        }
+    }
+
+    pub fn default_cache_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow").join("update_check.json")
+    }
+
+    fn load(&self) -> Option<UpdateCache> {
+        std::fs::read_to_string(&self.cache_path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+    }
+
+    /// 上次检查到比当前版本新的版本时返回提示
+    pub fn notice(&self) -> Option<String> {
+        let cache = self.load()?;
+        is_newer(&cache.latest_version, CURRENT_VERSION).then(|| {
+            format!(
+                "发现新版本 {}（当前 {}）：运行 markflow self-update 升级，或访问 {}",
+                cache.latest_version, CURRENT_VERSION, cache.url
+            )
+        })
+    }
+
+    pub fn needs_refresh(&self, now: DateTime<Utc>) -> bool {
+        let interval = Duration::hours(self.config.interval_hours as i64);
+        self.load()
+            .is_none_or(|cache| now - cache.checked_at >= interval)
+    }
+
+    pub async fn refresh(&self) -> Result<ReleaseInfo> {
+        let release = fetch_latest_release(&self.config.repository).await?;
+        self.record(&release, Utc::now())?;
+        Ok(release)
+    }
+
+    fn record(&self, release: &ReleaseInfo, now: DateTime<Utc>) -> Result<()> {
+        if let Some(parent) = self.cache_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let _lock = FileLock::exclusive(&self.cache_path)?;
+        let cache = UpdateCache {
+            checked_at: now,
+            latest_version: release.version.clone(),
+            url: release.url.clone(),
+        };
+        write_atomic(&self.cache_path, serde_json::to_string_pretty(&cache)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    // minisign 项目公开的测试向量
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("v0.2.0", "0.1.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(is_newer("0.1.10", "0.1.9"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0-beta.1", "0.1.0"));
+    }
+
+    #[test]
+    fn test_verify_release_signature() {
+        assert!(verify_release_signature(b"test", SIGNATURE, PUBLIC_KEY).is_ok());
+        assert!(matches!(
+            verify_release_signature(b"tampered", SIGNATURE, PUBLIC_KEY),
+            Err(Error::Validation(_))
+        ));
+        assert!(matches!(
+            verify_release_signature(b"test", SIGNATURE, "not-a-key"),
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_update_cache_notice_and_interval() {
+        let dir = TempDir::new().unwrap();
+        let checker = UpdateChecker::new(UpdateConfig::default(), dir.path().join("update.json"));
+        let now = Utc::now();
+        assert!(checker.notice().is_none());
+        assert!(checker.needs_refresh(now));
+
+        let release = ReleaseInfo {
+            version: "999.0.0".to_string(),
+            url: "https://github.com/foxzool/markflow/releases/tag/v999.0.0".to_string(),
+            assets: Vec::new(),
+        };
+        checker.record(&release, now).unwrap();
+        assert!(!checker.needs_refresh(now + Duration::hours(1)));
+        assert!(checker.needs_refresh(now + Duration::hours(24)));
+        assert!(checker.notice().unwrap().contains("999.0.0"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_release_from_github_api() {
+        use axum::{routing::get, Json, Router};
+
+        let app = Router::new().route(
+            "/repos/foxzool/markflow/releases/latest",
+            get(|| async {
+                Json(serde_json::json!({
+                    "tag_name": "v0.3.0",
+                    "html_url": "https://github.com/foxzool/markflow/releases/tag/v0.3.0",
+                    "assets": [{
+                        "name": release_asset_name(),
+                        "browser_download_url": "https://example.com/markflow"
+                    }]
+                }))
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let release = fetch_release(&format!(
+            "http://{}/repos/foxzool/markflow/releases/latest",
+            addr
+        ))
+        .await
+        .unwrap();
+        assert_eq!(release.version, "0.3.0");
+        assert!(release.is_newer_than("0.1.0"));
+        assert!(release.asset(&release_asset_name()).is_some());
+    }
+}
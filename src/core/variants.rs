@@ -0,0 +1,112 @@
+use crate::core::content::ContentMetadata;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 标题 A/B 测试的一个候选标题
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TitleVariant {
+    /// 变体标识：a、b、c…
+    pub label: String,
+    pub title: String,
+}
+
+/// 解析 front matter 中的 `titles: [标题一, 标题二]`
+pub fn parse_title_list(value: &str) -> Vec<String> {
+    value
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|t| t.trim().trim_matches(['"', '\'']).trim().to_string())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// 按顺序为候选标题分配 a、b、c… 标识
+pub fn title_variants(metadata: &ContentMetadata) -> Vec<TitleVariant> {
+    metadata
+        .title_variants
+        .iter()
+        .enumerate()
+        .map(|(i, title)| TitleVariant {
+            label: variant_label(i),
+            title: title.clone(),
+        })
+        .collect()
+}
+
+fn variant_label(index: usize) -> String {
+    if index < 26 {
+        ((b'a' + index as u8) as char).to_string()
+    } else {
+        format!("v{}", index + 1)
+    }
+}
+
+/// 把 HTML 中与原标题相同的 `<h1>` 替换为候选标题
+pub fn replace_title_heading(html: &str, original: &str, title: &str) -> String {
+    let pattern = format!(
+        r"(<h1\b[^>]*>)\s*{}\s*(</h1>)",
+        regex::escape(&html_escape::encode_text(original))
+    );
+    let Ok(regex) = Regex::new(&pattern) else {
+        return html.to_string();
+    };
+    let title = html_escape::encode_text(title);
+    regex
+        .replacen(html, 1, |caps: &regex::Captures| {
+            format!("{}{}{}", &caps[1], title, &caps[2])
+        })
+        .into_owned()
+}
+
+/// 变体输出文件名：`标题_wechat.html` -> `标题_wechat.b.html`
+pub fn variant_path(path: &Path, label: &str) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let file_name = match path.extension() {
+        Some(ext) => format!("{}.{}.{}", stem, label, ext.to_string_lossy()),
+        None => format!("{}.{}", stem, label),
+    };
+    path.with_file_name(file_name)
+}
+
+/// 台账中变体输出的键：`wechat.b`
+pub fn variant_output_key(platform: &str, label: &str) -> String {
+    format!("{}.{}", platform, label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_variants() {
+        let metadata = ContentMetadata {
+            title_variants: parse_title_list(r#"["Rust 入门指南", '三天学会 Rust']"#),
+            ..Default::default()
+        };
+        let variants = title_variants(&metadata);
+        assert_eq!(variants.len(), 2);
+        assert_eq!(variants[0].label, "a");
+        assert_eq!(variants[1].title, "三天学会 Rust");
+    }
+
+    #[test]
+    fn test_replace_heading_and_path() {
+        let html = r#"<h1 style="x">A &amp; B</h1><p>A &amp; B</p>"#;
+        assert_eq!(
+            replace_title_heading(html, "A & B", "C < D"),
+            r#"<h1 style="x">C &lt; D</h1><p>A &amp; B</p>"#
+        );
+        assert_eq!(replace_title_heading(html, "其他", "新"), html);
+
+        assert_eq!(
+            variant_path(Path::new("out/wechat/标题_wechat.html"), "b"),
+            PathBuf::from("out/wechat/标题_wechat.b.html")
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{error::Error, Result};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+/// 文章的审核状态：草稿 → 审核中 → 已批准 → 已发布
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WorkflowState {
+    #[default]
+    Draft,
+    InReview,
+    Approved,
+    Published,
+}
+
+impl WorkflowState {
+    /// 允许的状态流转；任何状态都可以退回草稿重新修改
+    pub fn can_transition_to(self, next: WorkflowState) -> bool {
+        use WorkflowState::*;
+        matches!(
+            (self, next),
+            (Draft, InReview)
+                | (InReview, Approved)
+                | (Approved, Published)
+                | (InReview | Approved | Published, Draft)
+        )
+    }
+}
+
+impl std::fmt::Display for WorkflowState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorkflowState::Draft => write!(f, "draft"),
+            WorkflowState::InReview => write!(f, "in-review"),
+            WorkflowState::Approved => write!(f, "approved"),
+            WorkflowState::Published => write!(f, "published"),
+        }
+    }
+}
+
+impl FromStr for WorkflowState {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().replace('_', "-").as_str() {
+            "draft" => Ok(WorkflowState::Draft),
+            "in-review" | "review" => Ok(WorkflowState::InReview),
+            "approved" => Ok(WorkflowState::Approved),
+            "published" => Ok(WorkflowState::Published),
+            _ => Err(Error::Validation(format!(
+                "无效的审核状态: {}（可选: draft、in-review、approved、published）",
+                s
+            ))),
+        }
+    }
+}
+
+/// 一次状态变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkflowTransition {
+    pub from: WorkflowState,
+    pub to: WorkflowState,
+    /// 操作人，如 `--by` 参数或 API 密钥的名称
+    #[serde(default)]
+    pub by: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 台账中记录的审核状态及变更历史
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Workflow {
+    #[serde(default)]
+    pub state: WorkflowState,
+    #[serde(default)]
+    pub history: Vec<WorkflowTransition>,
+}
+
+impl Workflow {
+    /// 按流转规则变更状态并记录操作人
+    pub fn transition(
+        &mut self,
+        to: WorkflowState,
+        by: Option<String>,
+        note: Option<String>,
+    ) -> Result<()> {
+        if !self.state.can_transition_to(to) {
+            return Err(Error::Validation(format!(
+                "不能从 {} 变更为 {}",
+                self.state, to
+            )));
+        }
+        self.record(to, by, note);
+        Ok(())
+    }
+
+    /// 发布成功后标记为已发布；未开启审核要求时草稿也可能直接发布，因此不检查流转规则
+    pub fn mark_published(&mut self, by: Option<String>) {
+        if self.state != WorkflowState::Published {
+            self.record(WorkflowState::Published, by, None);
+        }
+    }
+
+    /// 最近一次批准的操作人
+    pub fn approved_by(&self) -> Option<&str> {
+        self.history
+            .iter()
+            .rev()
+            .find(|t| t.to == WorkflowState::Approved)
+            .and_then(|t| t.by.as_deref())
+    }
+
+    /// 开启 `workflow.require_approval` 时，只有已批准（或已发布过）的文章可以发布
+    pub fn ensure_publishable(&self, title: &str) -> Result<()> {
+        match self.state {
+            WorkflowState::Approved | WorkflowState::Published => Ok(()),
+            state => Err(Error::Validation(format!(
+                "《{}》尚未通过审核（当前状态: {}），请先运行 markflow content approve",
+                title, state
+            ))),
+        }
+    }
+
+    fn record(&mut self, to: WorkflowState, by: Option<String>, note: Option<String>) {
+        self.history.push(WorkflowTransition {
+            from: self.state,
+            to,
+            by,
+            note,
+            at: chrono::Utc::now(),
+        });
+        self.state = to;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_workflow_transitions() {
+        let mut workflow = Workflow::default();
+        assert!(workflow.ensure_publishable("文章").is_err());
+        assert!(workflow
+            .transition(WorkflowState::Approved, None, None)
+            .is_err());
+
+        workflow
+            .transition(WorkflowState::InReview, Some("alice".into()), None)
+            .unwrap();
+        workflow
+            .transition(
+                WorkflowState::Approved,
+                Some("bob".into()),
+                Some("LGTM".into()),
+            )
+            .unwrap();
+        assert!(workflow.ensure_publishable("文章").is_ok());
+        assert_eq!(workflow.approved_by(), Some("bob"));
+
+        workflow.mark_published(None);
+        assert_eq!(workflow.state, WorkflowState::Published);
+        assert_eq!(workflow.history.len(), 3);
+
+        workflow
+            .transition(WorkflowState::Draft, None, None)
+            .unwrap();
+        assert_eq!(
+            "in_review".parse::<WorkflowState>().unwrap(),
+            WorkflowState::InReview
+        );
+        assert_eq!(
+            serde_json::to_string(&WorkflowState::InReview).unwrap(),
+            "\"in-review\""
+        );
+    }
+}
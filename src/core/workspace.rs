@@ -0,0 +1,370 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{
+        lock::write_atomic,
+        store::{ContentStore, LEDGER_FILE},
+    },
+    error::Error,
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+
+/// API 密钥的角色，权限依次递增
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// 只能查看内容和预览
+    Viewer,
+    /// 可以处理文章、上传资源
+    Editor,
+    /// 可以发布到平台（微信群发、知乎发布）
+    Publisher,
+}
+
+impl Role {
+    pub fn allows(self, required: Role) -> bool {
+        self >= required
+    }
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Viewer => write!(f, "viewer"),
+            Role::Editor => write!(f, "editor"),
+            Role::Publisher => write!(f, "publisher"),
+        }
+    }
+}
+
+impl std::str::FromStr for Role {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "viewer" => Ok(Role::Viewer),
+            "editor" => Ok(Role::Editor),
+            "publisher" => Ok(Role::Publisher),
+            _ => Err(Error::Config(format!(
+                "无效的角色: {}（可选 viewer/editor/publisher）",
+                s
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKey {
+    pub key: String,
+    pub role: Role,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// 工作区清单，保存在工作区根目录的 `workspace.toml`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkspaceManifest {
+    /// 访问该工作区的 API 密钥；`default` 工作区未配置密钥时不做校验（本机单用户场景），
+    /// 所有请求按 editor 处理，其他工作区必须携带密钥
+    #[serde(default)]
+    pub api_keys: Vec<ApiKey>,
+}
+
+impl WorkspaceManifest {
+    fn is_open(&self) -> bool {
+        self.api_keys.is_empty()
+    }
+
+    fn role_of(&self, token: &str) -> Option<Role> {
+        self.api_keys
+            .iter()
+            .find(|k| k.key == token)
+            .map(|k| k.role)
+    }
+}
+
+/// 工作区：独立的配置、模板和内容台账。
+///
+/// `default` 工作区直接使用 `~/.markflow`，与单用户时的目录布局保持一致；
+/// 其他工作区位于 `~/.markflow/workspaces/<name>`。
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    name: String,
+    root: PathBuf,
+    manifest: WorkspaceManifest,
+}
+
+impl Workspace {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn manifest(&self) -> &WorkspaceManifest {
+        &self.manifest
+    }
+
+    pub fn config_path(&self) -> PathBuf {
+        self.root.join("config.toml")
+    }
+
+    pub fn templates_dir(&self) -> PathBuf {
+        self.root.join("templates")
+    }
+
+    pub fn ledger_path(&self) -> PathBuf {
+        self.root.join(LEDGER_FILE)
+    }
+
+    pub fn output_dir(&self) -> PathBuf {
+        self.root.join("output")
+    }
+
+    /// 上传资源的存放目录，可通过 `assets.dir` 覆盖
+    pub fn assets_dir(&self, config: &AppConfig) -> PathBuf {
+        config
+            .assets
+            .dir
+            .clone()
+            .unwrap_or_else(|| self.root.join("assets"))
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.root.join("workspace.toml")
+    }
+
+    pub fn load_config(&self) -> Result<AppConfig> {
+        AppConfig::load_from_file(&self.config_path())
+    }
+
+    pub fn open_store(&self) -> Result<ContentStore> {
+        ContentStore::open(self.ledger_path())
+    }
+
+    /// 返回令牌在该工作区的角色。未配置任何密钥的 `default` 工作区对所有请求开放 editor 权限，
+    /// 批准和发布需要先生成 publisher 密钥；其他工作区没有密钥时拒绝所有请求
+    pub fn role_for(&self, token: Option<&str>) -> Option<Role> {
+        if self.name == DEFAULT_WORKSPACE && self.manifest.is_open() {
+            return Some(Role::Editor);
+        }
+        token.and_then(|t| self.manifest.role_of(t))
+    }
+
+    /// 令牌对应的 API 密钥名称，用于记录操作人
+    pub fn key_name(&self, token: Option<&str>) -> Option<String> {
+        let token = token?;
+        self.manifest
+            .api_keys
+            .iter()
+            .find(|k| k.key == token)
+            .and_then(|k| k.name.clone())
+    }
+
+    pub fn add_api_key(
+        &mut self,
+        key: impl Into<String>,
+        role: Role,
+        name: Option<String>,
+    ) -> Result<()> {
+        self.manifest.api_keys.push(ApiKey {
+            key: key.into(),
+            role,
+            name,
+        });
+        self.save_manifest()
+    }
+
+    fn save_manifest(&self) -> Result<()> {
+        let raw = toml::to_string_pretty(&self.manifest)
+            .map_err(|e| Error::Config(format!("工作区清单序列化失败: {}", e)))?;
+        write_atomic(&self.manifest_path(), raw)
+    }
+}
+
+/// 管理数据根目录下的所有工作区
+#[derive(Debug, Clone)]
+pub struct WorkspaceManager {
+    data_root: PathBuf,
+}
+
+impl WorkspaceManager {
+    pub fn new(data_root: impl Into<PathBuf>) -> Self {
+        Self {
+            data_root: data_root.into(),
+        }
+    }
+
+    /// `~/.markflow`
+    pub fn default_root() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow")
+    }
+
+    pub fn data_root(&self) -> &Path {
+        &self.data_root
+    }
+
+    fn workspace_root(&self, name: &str) -> PathBuf {
+        if name == DEFAULT_WORKSPACE {
+            self.data_root.clone()
+        } else {
+            self.data_root.join("workspaces").join(name)
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Result<Workspace> {
+        validate_name(name)?;
+        let root = self.workspace_root(name);
+        if name != DEFAULT_WORKSPACE && !root.is_dir() {
+            return Err(Error::Config(format!("工作区不存在: {}", name)));
+        }
+
+        let manifest_path = root.join("workspace.toml");
+        let manifest = if manifest_path.exists() {
+            let raw = std::fs::read_to_string(&manifest_path)?;
+            toml::from_str(&raw).map_err(|e| {
+                Error::Config(format!("工作区清单解析失败 {:?}: {}", manifest_path, e))
+            })?
+        } else {
+            WorkspaceManifest::default()
+        };
+
+        Ok(Workspace {
+            name: name.to_string(),
+            root,
+            manifest,
+        })
+    }
+
+    pub fn default_workspace(&self) -> Result<Workspace> {
+        self.get(DEFAULT_WORKSPACE)
+    }
+
+    /// 创建工作区，并写入指向工作区自身目录的默认配置
+    pub fn create(&self, name: &str) -> Result<Workspace> {
+        validate_name(name)?;
+        if name == DEFAULT_WORKSPACE || self.workspace_root(name).exists() {
+            return Err(Error::Config(format!("工作区已存在: {}", name)));
+        }
+
+        let root = self.workspace_root(name);
+        std::fs::create_dir_all(root.join("templates"))?;
+
+        let workspace = Workspace {
+            name: name.to_string(),
+            root,
+            manifest: WorkspaceManifest::default(),
+        };
+
+        let mut config = AppConfig::default();
+        config.templates.templates_dir = workspace.templates_dir();
+        config.output.output_dir = workspace.output_dir();
+        config.output.backup_dir = Some(workspace.root.join("backup"));
+        config.save_to_file(&workspace.config_path())?;
+        workspace.save_manifest()?;
+
+        Ok(workspace)
+    }
+
+    pub fn list(&self) -> Result<Vec<String>> {
+        let mut names = vec![DEFAULT_WORKSPACE.to_string()];
+        let dir = self.data_root.join("workspaces");
+        if dir.is_dir() {
+            let mut others: Vec<String> = std::fs::read_dir(&dir)?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .filter(|name| validate_name(name).is_ok())
+                .collect();
+            others.sort();
+            names.extend(others);
+        }
+        Ok(names)
+    }
+
+    /// 查找令牌所属的工作区
+    pub fn find_by_token(&self, token: &str) -> Result<Option<Workspace>> {
+        for name in self.list()? {
+            let workspace = self.get(&name)?;
+            if workspace.manifest.role_of(token).is_some() {
+                return Ok(Some(workspace));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// 工作区名只允许字母、数字、`-` 和 `_`，防止通过路径穿越访问其他目录
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::Config(format!("无效的工作区名称: {}", name)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_create_and_isolate_workspaces() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+
+        let mut alice = manager.create("alice").unwrap();
+        manager.create("bob").unwrap();
+        assert!(manager.create("alice").is_err());
+        assert_eq!(manager.list().unwrap(), vec!["default", "alice", "bob"]);
+
+        let config = alice.load_config().unwrap();
+        assert_eq!(config.templates.templates_dir, alice.templates_dir());
+        assert_ne!(
+            alice.ledger_path(),
+            manager.get("bob").unwrap().ledger_path()
+        );
+
+        alice.add_api_key("secret-a", Role::Editor, None).unwrap();
+        let found = manager.find_by_token("secret-a").unwrap().unwrap();
+        assert_eq!(found.name(), "alice");
+        assert_eq!(found.role_for(Some("secret-a")), Some(Role::Editor));
+        assert_eq!(found.role_for(None), None);
+        assert_eq!(found.role_for(Some("other")), None);
+        // 只有 default 工作区在没有密钥时开放访问
+        assert_eq!(manager.get("bob").unwrap().role_for(None), None);
+        assert_eq!(
+            manager.default_workspace().unwrap().role_for(None),
+            Some(Role::Editor)
+        );
+    }
+
+    #[test]
+    fn test_role_order() {
+        assert!(Role::Publisher.allows(Role::Editor));
+        assert!(Role::Editor.allows(Role::Viewer));
+        assert!(!Role::Editor.allows(Role::Publisher));
+        assert_eq!("Viewer".parse::<Role>().unwrap(), Role::Viewer);
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+        assert!(manager.get("../etc").is_err());
+        assert!(manager.create("a/b").is_err());
+        assert!(manager.get("missing").is_err());
+        assert_eq!(manager.get("default").unwrap().root(), dir.path());
+    }
+}
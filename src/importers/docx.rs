@@ -0,0 +1,531 @@
+use crate::{
+    error::Error,
+    importers::{ImportedArticle, ImportedDocument},
+    Result,
+};
+use roxmltree::{Document, Node};
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+};
+use zip::ZipArchive;
+
+/// 把 Word 文档转换为 Markdown。
+///
+/// 与 mammoth 的思路一致：只保留语义结构（标题、列表、引用、表格、强调、链接和图片），
+/// 忽略字体、颜色等排版信息。
+pub fn convert_docx<R: Read + Seek>(reader: R) -> Result<ImportedDocument> {
+    let mut archive =
+        ZipArchive::new(reader).map_err(|e| Error::Other(format!("无法读取 docx 文件: {}", e)))?;
+
+    let document = read_entry(&mut archive, "word/document.xml")?
+        .ok_or_else(|| Error::Other("docx 中缺少 word/document.xml".to_string()))?;
+    let relationships = read_entry(&mut archive, "word/_rels/document.xml.rels")?
+        .map(|xml| parse_relationships(&xml))
+        .transpose()?
+        .unwrap_or_default();
+    let styles = read_entry(&mut archive, "word/styles.xml")?
+        .map(|xml| parse_styles(&xml))
+        .transpose()?
+        .unwrap_or_default();
+    let numbering = read_entry(&mut archive, "word/numbering.xml")?
+        .map(|xml| parse_numbering(&xml))
+        .transpose()?
+        .unwrap_or_default();
+    let (title, author) = read_entry(&mut archive, "docProps/core.xml")?
+        .map(|xml| parse_core_properties(&xml))
+        .transpose()?
+        .unwrap_or_default();
+
+    let xml = Document::parse(&document).map_err(xml_error)?;
+    let mut converter = DocxConverter {
+        relationships: &relationships,
+        styles: &styles,
+        numbering: &numbering,
+        images: Vec::new(),
+        title: None,
+        list_counters: HashMap::new(),
+    };
+
+    let body = xml
+        .descendants()
+        .find(|n| n.has_tag_name_local("body"))
+        .ok_or_else(|| Error::Other("docx 中缺少正文".to_string()))?;
+
+    let mut blocks: Vec<Block> = Vec::new();
+    for child in body.children().filter(Node::is_element) {
+        match child.tag_name().name() {
+            "p" => {
+                if let Some(block) = converter.paragraph(child) {
+                    blocks.push(block);
+                }
+            }
+            "tbl" => blocks.push(Block::Other(converter.table(child))),
+            _ => {}
+        }
+    }
+
+    let markdown = join_blocks(&blocks);
+
+    let mut media = HashMap::new();
+    for target in &converter.images {
+        let path = format!(
+            "word/{}",
+            target.trim_start_matches('/').trim_start_matches("word/")
+        );
+        if let Some(data) = read_bytes(&mut archive, &path)? {
+            media.insert(target.clone(), data);
+        }
+    }
+
+    Ok(ImportedDocument {
+        article: ImportedArticle {
+            title: converter.title.or(title),
+            author,
+            markdown,
+            images: converter.images,
+        },
+        media,
+    })
+}
+
+enum Block {
+    ListItem(String),
+    Other(String),
+}
+
+/// 连续的列表项之间用单个换行分隔，其余块之间空一行
+fn join_blocks(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    let mut previous_list = false;
+    for block in blocks {
+        let (text, is_list) = match block {
+            Block::ListItem(text) => (text, true),
+            Block::Other(text) => (text, false),
+        };
+        if text.is_empty() {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if is_list && previous_list {
+                "\n"
+            } else {
+                "\n\n"
+            });
+        }
+        out.push_str(text);
+        previous_list = is_list;
+    }
+    out
+}
+
+struct DocxConverter<'a> {
+    relationships: &'a HashMap<String, String>,
+    styles: &'a HashMap<String, String>,
+    numbering: &'a HashMap<(String, u32), bool>,
+    images: Vec<String>,
+    title: Option<String>,
+    list_counters: HashMap<(String, u32), usize>,
+}
+
+impl DocxConverter<'_> {
+    fn paragraph(&mut self, node: Node) -> Option<Block> {
+        let properties = child(node, "pPr");
+        let style = properties
+            .and_then(|p| child(p, "pStyle"))
+            .and_then(|s| attr(s, "val"))
+            .map(|id| {
+                self.styles
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_else(|| id.to_string())
+                    .to_lowercase()
+            })
+            .unwrap_or_default();
+        let text = self.runs(node).trim().to_string();
+
+        if style == "title" {
+            if self.title.is_none() && !text.is_empty() {
+                self.title = Some(text);
+                return None;
+            }
+            return Some(Block::Other(format!("# {}", text)));
+        }
+        if text.is_empty() {
+            return None;
+        }
+
+        if let Some(level) = heading_level(&style) {
+            return Some(Block::Other(format!("{} {}", "#".repeat(level), text)));
+        }
+        if style.contains("quote") {
+            return Some(Block::Other(format!("> {}", text)));
+        }
+
+        if let Some(num) = properties.and_then(|p| child(p, "numPr")) {
+            let num_id = child(num, "numId")
+                .and_then(|n| attr(n, "val"))
+                .unwrap_or("0");
+            let level = child(num, "ilvl")
+                .and_then(|n| attr(n, "val"))
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(0);
+            if num_id != "0" {
+                let ordered = self
+                    .numbering
+                    .get(&(num_id.to_string(), level))
+                    .copied()
+                    .unwrap_or(false);
+                // 上级列表项出现时，重置更深层级的编号
+                self.list_counters
+                    .retain(|(id, l), _| id != num_id || *l <= level);
+                let counter = self
+                    .list_counters
+                    .entry((num_id.to_string(), level))
+                    .or_insert(0);
+                *counter += 1;
+
+                let marker = if ordered {
+                    format!("{}.", counter)
+                } else {
+                    "-".to_string()
+                };
+                let indent = "   ".repeat(level as usize);
+                return Some(Block::ListItem(format!("{}{} {}", indent, marker, text)));
+            }
+        }
+
+        Some(Block::Other(text))
+    }
+
+    fn runs(&mut self, node: Node) -> String {
+        let mut out = String::new();
+        for child in node.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "r" => out.push_str(&self.run(child)),
+                "hyperlink" => {
+                    let text = self.runs(child);
+                    let target = attr(child, "id").and_then(|id| self.relationships.get(id));
+                    match target {
+                        Some(href) if !text.trim().is_empty() => {
+                            out.push_str(&format!("[{}]({})", text.trim(), href))
+                        }
+                        _ => out.push_str(&text),
+                    }
+                }
+                "ins" | "smartTag" | "sdt" | "sdtContent" => out.push_str(&self.runs(child)),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    fn run(&mut self, node: Node) -> String {
+        let properties = child(node, "rPr");
+        let enabled = |name: &str| {
+            properties
+                .and_then(|p| child(p, name))
+                .is_some_and(|n| !matches!(attr(n, "val"), Some("0" | "false")))
+        };
+        let monospace = properties
+            .and_then(|p| child(p, "rFonts"))
+            .and_then(|f| attr(f, "ascii"))
+            .is_some_and(|font| {
+                let font = font.to_lowercase();
+                font.contains("courier") || font.contains("consolas") || font.contains("mono")
+            });
+
+        let mut text = String::new();
+        for child in node.children().filter(Node::is_element) {
+            match child.tag_name().name() {
+                "t" => {
+                    let value = child.text().unwrap_or("");
+                    if monospace {
+                        text.push_str(value);
+                    } else {
+                        text.push_str(&escape_markdown(value));
+                    }
+                }
+                "tab" => text.push('\t'),
+                "br" | "cr" => text.push_str("  \n"),
+                "drawing" | "pict" => {
+                    if let Some(image) = self.image(child) {
+                        text.push_str(&image);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if text.trim().is_empty() || text.starts_with("![") {
+            return text;
+        }
+        if monospace {
+            return format!("`{}`", text);
+        }
+        let mut wrapped = text;
+        if enabled("strike") {
+            wrapped = wrap(&wrapped, "~~");
+        }
+        if enabled("i") {
+            wrapped = wrap(&wrapped, "*");
+        }
+        if enabled("b") {
+            wrapped = wrap(&wrapped, "**");
+        }
+        wrapped
+    }
+
+    fn image(&mut self, node: Node) -> Option<String> {
+        let embed = node
+            .descendants()
+            .find(|n| n.tag_name().name() == "blip" || n.tag_name().name() == "imagedata")
+            .and_then(|n| attr(n, "embed").or_else(|| attr(n, "id")))?;
+        let target = self.relationships.get(embed)?.clone();
+        let alt = node
+            .descendants()
+            .find(|n| n.tag_name().name() == "docPr")
+            .and_then(|n| attr(n, "descr").or_else(|| attr(n, "title")))
+            .unwrap_or("")
+            .replace(['[', ']', '\n'], " ");
+
+        if !self.images.contains(&target) {
+            self.images.push(target.clone());
+        }
+        Some(format!("![{}]({})", alt.trim(), target))
+    }
+
+    fn table(&mut self, node: Node) -> String {
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for row in node.children().filter(|n| n.has_tag_name_local("tr")) {
+            let cells: Vec<String> = row
+                .children()
+                .filter(|n| n.has_tag_name_local("tc"))
+                .map(|cell| {
+                    cell.children()
+                        .filter(|n| n.has_tag_name_local("p"))
+                        .map(|p| self.runs(p).trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect::<Vec<_>>()
+                        .join("<br>")
+                        .replace('|', "\\|")
+                })
+                .collect();
+            if !cells.is_empty() {
+                rows.push(cells);
+            }
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut out = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let mut cells = row.clone();
+            cells.resize(columns, String::new());
+            out.push(format!("| {} |", cells.join(" | ")));
+            if i == 0 {
+                out.push(format!("|{}", " --- |".repeat(columns)));
+            }
+        }
+        out.join("\n")
+    }
+}
+
+trait LocalName {
+    fn has_tag_name_local(&self, name: &str) -> bool;
+}
+
+impl LocalName for Node<'_, '_> {
+    fn has_tag_name_local(&self, name: &str) -> bool {
+        self.is_element() && self.tag_name().name() == name
+    }
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    node.children().find(|n| n.has_tag_name_local(name))
+}
+
+/// 按本地名读取属性，忽略命名空间前缀（w:val、r:id 等）
+fn attr<'a>(node: Node<'a, '_>, name: &str) -> Option<&'a str> {
+    node.attributes()
+        .find(|a| a.name() == name)
+        .map(|a| a.value())
+}
+
+fn heading_level(style: &str) -> Option<usize> {
+    let level = style
+        .strip_prefix("heading")
+        .map(str::trim)
+        .and_then(|n| n.parse::<usize>().ok())?;
+    (1..=6).contains(&level).then_some(level)
+}
+
+fn wrap(text: &str, marker: &str) -> String {
+    let leading = &text[..text.len() - text.trim_start().len()];
+    let trailing = &text[text.trim_end().len()..];
+    format!("{}{}{}{}{}", leading, marker, text.trim(), marker, trailing)
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '`' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn parse_relationships(xml: &str) -> Result<HashMap<String, String>> {
+    let doc = Document::parse(xml).map_err(xml_error)?;
+    Ok(doc
+        .descendants()
+        .filter(|n| n.has_tag_name_local("Relationship"))
+        .filter_map(|n| Some((attr(n, "Id")?.to_string(), attr(n, "Target")?.to_string())))
+        .collect())
+}
+
+/// styleId -> 样式名（如 Heading1 -> heading 1），兼容本地化的样式 ID
+fn parse_styles(xml: &str) -> Result<HashMap<String, String>> {
+    let doc = Document::parse(xml).map_err(xml_error)?;
+    Ok(doc
+        .descendants()
+        .filter(|n| n.has_tag_name_local("style"))
+        .filter_map(|n| {
+            let id = attr(n, "styleId")?;
+            let name = child(n, "name").and_then(|c| attr(c, "val"))?;
+            Some((id.to_string(), name.to_string()))
+        })
+        .collect())
+}
+
+/// (numId, 层级) -> 是否为有序列表
+fn parse_numbering(xml: &str) -> Result<HashMap<(String, u32), bool>> {
+    let doc = Document::parse(xml).map_err(xml_error)?;
+
+    let mut abstract_formats: HashMap<String, HashMap<u32, bool>> = HashMap::new();
+    for node in doc
+        .descendants()
+        .filter(|n| n.has_tag_name_local("abstractNum"))
+    {
+        let Some(id) = attr(node, "abstractNumId") else {
+            continue;
+        };
+        let levels = node
+            .children()
+            .filter(|n| n.has_tag_name_local("lvl"))
+            .filter_map(|lvl| {
+                let level = attr(lvl, "ilvl")?.parse::<u32>().ok()?;
+                let format = child(lvl, "numFmt").and_then(|f| attr(f, "val"))?;
+                Some((level, !matches!(format, "bullet" | "none")))
+            })
+            .collect();
+        abstract_formats.insert(id.to_string(), levels);
+    }
+
+    let mut numbering = HashMap::new();
+    for node in doc.descendants().filter(|n| n.has_tag_name_local("num")) {
+        let (Some(num_id), Some(abstract_id)) = (
+            attr(node, "numId"),
+            child(node, "abstractNumId").and_then(|n| attr(n, "val")),
+        ) else {
+            continue;
+        };
+        if let Some(levels) = abstract_formats.get(abstract_id) {
+            for (level, ordered) in levels {
+                numbering.insert((num_id.to_string(), *level), *ordered);
+            }
+        }
+    }
+    Ok(numbering)
+}
+
+fn parse_core_properties(xml: &str) -> Result<(Option<String>, Option<String>)> {
+    let doc = Document::parse(xml).map_err(xml_error)?;
+    let text = |name: &str| {
+        doc.descendants()
+            .find(|n| n.has_tag_name_local(name))
+            .and_then(|n| n.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+    };
+    Ok((text("title"), text("creator")))
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Option<String>> {
+    Ok(read_bytes(archive, name)?.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+fn read_bytes<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Option<Vec<u8>>> {
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(Error::Other(format!("读取 {} 失败: {}", name, e))),
+    };
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
+fn xml_error(e: roxmltree::Error) -> Error {
+    Error::Other(format!("docx XML 解析失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    const W: &str = r#"xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main" xmlns:wp="http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing""#;
+
+    fn sample_docx() -> Vec<u8> {
+        let document = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document {W}><w:body>
+<w:p><w:pPr><w:pStyle w:val="Title"/></w:pPr><w:r><w:t>周报</w:t></w:r></w:p>
+<w:p><w:pPr><w:pStyle w:val="1"/></w:pPr><w:r><w:t>进展</w:t></w:r></w:p>
+<w:p><w:r><w:t xml:space="preserve">完成了 </w:t></w:r><w:r><w:rPr><w:b/></w:rPr><w:t>导入</w:t></w:r><w:r><w:t xml:space="preserve"> 功能，见</w:t></w:r><w:hyperlink r:id="rId2"><w:r><w:t>文档</w:t></w:r></w:hyperlink></w:p>
+<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>第一步</w:t></w:r></w:p>
+<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>第二步</w:t></w:r></w:p>
+<w:p><w:r><w:drawing><wp:inline><wp:docPr id="1" name="图片 1" descr="架构图"/><a:graphic><a:graphicData><a:blip r:embed="rId3"/></a:graphicData></a:graphic></wp:inline></w:drawing></w:r></w:p>
+<w:tbl><w:tr><w:tc><w:p><w:r><w:t>A</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>B</w:t></w:r></w:p></w:tc></w:tr>
+<w:tr><w:tc><w:p><w:r><w:t>1</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>2</w:t></w:r></w:p></w:tc></w:tr></w:tbl>
+</w:body></w:document>"#
+        );
+        let styles = format!(
+            r#"<w:styles {W}><w:style w:styleId="Title"><w:name w:val="Title"/></w:style><w:style w:styleId="1"><w:name w:val="heading 1"/></w:style></w:styles>"#
+        );
+        let numbering = format!(
+            r#"<w:numbering {W}><w:abstractNum w:abstractNumId="0"><w:lvl w:ilvl="0"><w:numFmt w:val="decimal"/></w:lvl></w:abstractNum><w:num w:numId="1"><w:abstractNumId w:val="0"/></w:num></w:numbering>"#
+        );
+        let rels = r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId2" Type="hyperlink" Target="https://example.com/doc" TargetMode="External"/><Relationship Id="rId3" Type="image" Target="media/image1.png"/></Relationships>"#;
+
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        for (name, content) in [
+            ("word/document.xml", document.as_bytes()),
+            ("word/styles.xml", styles.as_bytes()),
+            ("word/numbering.xml", numbering.as_bytes()),
+            ("word/_rels/document.xml.rels", rels.as_bytes()),
+            ("word/media/image1.png", b"png-bytes".as_slice()),
+        ] {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn test_convert_docx() {
+        let document = convert_docx(Cursor::new(sample_docx())).unwrap();
+
+        assert_eq!(document.article.title.as_deref(), Some("周报"));
+        assert_eq!(
+            document.article.markdown,
+            "# 进展\n\n完成了 **导入** 功能，见[文档](https://example.com/doc)\n\n1. 第一步\n2. 第二步\n\n![架构图](media/image1.png)\n\n| A | B |\n| --- | --- |\n| 1 | 2 |"
+        );
+        assert_eq!(document.article.images, vec!["media/image1.png"]);
+        assert_eq!(document.media["media/image1.png"], b"png-bytes");
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{error::Error, Result};
+use ego_tree::NodeRef;
+use regex::Regex;
+use scraper::{ElementRef, Html, Node, Selector};
+use std::sync::OnceLock;
+
+/// 正文容器的候选选择器，按优先级排列（微信公众号文章的正文在 `#js_content`）
+const CONTENT_SELECTORS: &[&str] = &["#js_content", "article", "main", ".post-content", "body"];
+const TITLE_SELECTORS: &[&str] = &[
+    "#activity-name",
+    "meta[property=\"og:title\"]",
+    "h1",
+    "title",
+];
+const AUTHOR_SELECTORS: &[&str] = &["#js_name", "meta[name=\"author\"]", ".author"];
+
+/// 从 HTML 页面中提取出的文章
+#[derive(Debug, Clone)]
+pub struct ImportedArticle {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub markdown: String,
+    /// 正文中引用的图片地址（按出现顺序去重）
+    pub images: Vec<String>,
+}
+
+/// 解析完整的 HTML 页面，定位正文并转换为 Markdown
+pub fn extract_article(html: &str) -> Result<ImportedArticle> {
+    let document = Html::parse_document(html);
+
+    let title = first_text(&document, TITLE_SELECTORS);
+    let author = first_text(&document, AUTHOR_SELECTORS);
+
+    let content = CONTENT_SELECTORS
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .find_map(|selector| document.select(&selector).next())
+        .ok_or_else(|| Error::Html("没有找到文章正文".to_string()))?;
+
+    let mut converter = Converter::default();
+    let body = converter.block_children(*content);
+    let mut markdown = normalize_blank_lines(&body);
+
+    // 正文第一行与标题重复时去掉，标题放在 front matter 中
+    if let Some(title) = &title {
+        let heading = format!("# {}", title);
+        if let Some(rest) = markdown.strip_prefix(&heading) {
+            markdown = rest.trim_start().to_string();
+        }
+    }
+
+    Ok(ImportedArticle {
+        title,
+        author,
+        markdown,
+        images: converter.images,
+    })
+}
+
+/// 把 HTML 片段转换为 Markdown
+pub fn html_to_markdown(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut converter = Converter::default();
+    normalize_blank_lines(&converter.block_children(*fragment.root_element()))
+}
+
+fn first_text(document: &Html, selectors: &[&str]) -> Option<String> {
+    selectors
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .find_map(|selector| {
+            let element = document.select(&selector).next()?;
+            let text = match element.value().attr("content") {
+                Some(content) => content.to_string(),
+                None => element.text().collect::<String>(),
+            };
+            let text = collapse_whitespace(&text).trim().to_string();
+            (!text.is_empty()).then_some(text)
+        })
+}
+
+#[derive(Default)]
+struct Converter {
+    images: Vec<String>,
+}
+
+impl Converter {
+    /// 渲染块级上下文中的子节点，连续的行内内容合并为一个段落
+    fn block_children(&mut self, node: NodeRef<Node>) -> String {
+        let mut out = String::new();
+        let mut inline = String::new();
+
+        for child in node.children() {
+            if is_block(child) {
+                flush_paragraph(&mut out, &mut inline);
+                out.push_str(&self.block(child));
+            } else {
+                inline.push_str(&self.inline(child));
+            }
+        }
+        flush_paragraph(&mut out, &mut inline);
+        out
+    }
+
+    fn block(&mut self, node: NodeRef<Node>) -> String {
+        let Some(element) = ElementRef::wrap(node) else {
+            return String::new();
+        };
+        let name = element.value().name();
+
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                let text = self.inline_children(node).trim().to_string();
+                if text.is_empty() {
+                    String::new()
+                } else {
+                    format!("\n\n{} {}\n\n", "#".repeat(level), text)
+                }
+            }
+            "p" => {
+                let text = self.inline_children(node).trim().to_string();
+                if text.is_empty() {
+                    // 段落中可能只有图片或嵌套块（微信编辑器常见）
+                    format!("\n\n{}\n\n", self.block_children(node).trim())
+                } else {
+                    format!("\n\n{}\n\n", text)
+                }
+            }
+            "blockquote" => {
+                let inner = normalize_blank_lines(&self.block_children(node));
+                let quoted: Vec<String> = inner
+                    .lines()
+                    .map(|line| {
+                        if line.is_empty() {
+                            ">".to_string()
+                        } else {
+                            format!("> {}", line)
+                        }
+                    })
+                    .collect();
+                format!("\n\n{}\n\n", quoted.join("\n"))
+            }
+            "pre" => {
+                let code = element
+                    .select(&Selector::parse("code").unwrap())
+                    .next()
+                    .unwrap_or(element);
+                let language = code
+                    .value()
+                    .classes()
+                    .chain(element.value().classes())
+                    .find_map(|c| {
+                        c.strip_prefix("language-")
+                            .or_else(|| c.strip_prefix("lang-"))
+                    })
+                    .unwrap_or("")
+                    .to_string();
+                let text = code_text(*code);
+                format!(
+                    "\n\n```{}\n{}\n```\n\n",
+                    language,
+                    text.trim_end_matches('\n')
+                )
+            }
+            "ul" | "ol" => format!("\n\n{}\n\n", self.list(node, name == "ol")),
+            "hr" => "\n\n---\n\n".to_string(),
+            "table" => format!("\n\n{}\n\n", self.table(element)),
+            "script" | "style" | "noscript" | "template" | "svg" | "head" => String::new(),
+            _ => format!("\n\n{}\n\n", self.block_children(node)),
+        }
+    }
+
+    fn list(&mut self, node: NodeRef<Node>, ordered: bool) -> String {
+        let mut items = Vec::new();
+        let mut index = 1;
+        for child in node.children() {
+            let Some(element) = ElementRef::wrap(child) else {
+                continue;
+            };
+            if element.value().name() != "li" {
+                continue;
+            }
+
+            let marker = if ordered {
+                format!("{}. ", index)
+            } else {
+                "- ".to_string()
+            };
+            index += 1;
+
+            let body = normalize_blank_lines(&self.block_children(child));
+            let indent = " ".repeat(marker.len());
+            let mut lines = body.lines();
+            let first = lines.next().unwrap_or("");
+            let mut item = format!("{}{}", marker, first);
+            for line in lines {
+                item.push('\n');
+                if !line.is_empty() {
+                    item.push_str(&indent);
+                    item.push_str(line);
+                }
+            }
+            items.push(item);
+        }
+        items.join("\n")
+    }
+
+    fn table(&mut self, table: ElementRef) -> String {
+        let row_selector = Selector::parse("tr").unwrap();
+        let mut rows: Vec<Vec<String>> = Vec::new();
+        for row in table.select(&row_selector) {
+            let cells: Vec<String> = row
+                .children()
+                .filter(|c| {
+                    ElementRef::wrap(*c).is_some_and(|e| matches!(e.value().name(), "td" | "th"))
+                })
+                .map(|c| self.inline_children(c).trim().replace('|', "\\|"))
+                .collect();
+            if !cells.is_empty() {
+                rows.push(cells);
+            }
+        }
+        if rows.is_empty() {
+            return String::new();
+        }
+
+        let columns = rows.iter().map(Vec::len).max().unwrap_or(1);
+        let mut out = String::new();
+        for (i, row) in rows.iter().enumerate() {
+            let mut cells = row.clone();
+            cells.resize(columns, String::new());
+            out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            if i == 0 {
+                out.push_str(&format!("|{}\n", " --- |".repeat(columns)));
+            }
+        }
+        out.trim_end().to_string()
+    }
+
+    fn inline_children(&mut self, node: NodeRef<Node>) -> String {
+        node.children().map(|child| self.inline(child)).collect()
+    }
+
+    fn inline(&mut self, node: NodeRef<Node>) -> String {
+        match node.value() {
+            Node::Text(text) => escape_markdown(&collapse_whitespace(text)),
+            Node::Element(element) => {
+                let name = element.name();
+                match name {
+                    "strong" | "b" => wrap_inline(&self.inline_children(node), "**"),
+                    "em" | "i" => wrap_inline(&self.inline_children(node), "*"),
+                    "del" | "s" | "strike" => wrap_inline(&self.inline_children(node), "~~"),
+                    "code" => {
+                        let text = code_text(node);
+                        if text.contains('`') {
+                            format!("`` {} ``", text)
+                        } else {
+                            format!("`{}`", text)
+                        }
+                    }
+                    "br" => "  \n".to_string(),
+                    "a" => {
+                        let text = self.inline_children(node).trim().to_string();
+                        match element.attr("href") {
+                            Some(href) if !href.starts_with("javascript:") && !href.is_empty() => {
+                                if text.is_empty() {
+                                    format!("<{}>", href)
+                                } else {
+                                    format!("[{}]({})", text, href)
+                                }
+                            }
+                            _ => text,
+                        }
+                    }
+                    "img" => {
+                        // 微信文章的图片地址在 data-src 中
+                        let src = element
+                            .attr("data-src")
+                            .or_else(|| element.attr("src"))
+                            .unwrap_or("");
+                        if src.is_empty() || src.starts_with("data:") {
+                            return String::new();
+                        }
+                        if !self.images.iter().any(|i| i == src) {
+                            self.images.push(src.to_string());
+                        }
+                        let alt = element.attr("alt").unwrap_or("").replace(['[', ']'], "");
+                        format!("![{}]({})", alt, src)
+                    }
+                    "script" | "style" | "noscript" | "template" | "svg" => String::new(),
+                    _ if is_block(node) => {
+                        format!(" {} ", self.block_children(node).trim())
+                    }
+                    _ => self.inline_children(node),
+                }
+            }
+            _ => String::new(),
+        }
+    }
+}
+
+fn is_block(node: NodeRef<Node>) -> bool {
+    node.value().as_element().is_some_and(|e| {
+        matches!(
+            e.name(),
+            "p" | "div"
+                | "section"
+                | "article"
+                | "main"
+                | "header"
+                | "footer"
+                | "figure"
+                | "figcaption"
+                | "h1"
+                | "h2"
+                | "h3"
+                | "h4"
+                | "h5"
+                | "h6"
+                | "blockquote"
+                | "pre"
+                | "ul"
+                | "ol"
+                | "li"
+                | "hr"
+                | "table"
+                | "script"
+                | "style"
+                | "noscript"
+        )
+    })
+}
+
+fn flush_paragraph(out: &mut String, inline: &mut String) {
+    let text = inline.trim();
+    if !text.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(text);
+        out.push_str("\n\n");
+    }
+    inline.clear();
+}
+
+fn wrap_inline(text: &str, marker: &str) -> String {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return text.to_string();
+    }
+    format!("{}{}{}", marker, trimmed, marker)
+}
+
+fn code_text(node: NodeRef<Node>) -> String {
+    let mut text = String::new();
+    for descendant in node.descendants() {
+        match descendant.value() {
+            Node::Text(t) => text.push_str(t),
+            Node::Element(e) if e.name() == "br" => text.push('\n'),
+            _ => {}
+        }
+    }
+    text
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    static WHITESPACE_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = WHITESPACE_REGEX.get_or_init(|| Regex::new(r"[\s\u{a0}]+").unwrap());
+    regex.replace_all(text, " ").into_owned()
+}
+
+fn escape_markdown(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '*' | '_' | '`' | '[' | ']' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn normalize_blank_lines(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank = 0;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            blank += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank > 0 { "\n\n" } else { "\n" });
+        }
+        blank = 0;
+        out.push_str(line);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_html_to_markdown_basics() {
+        let html = r#"<h2>小标题</h2><p>这是<strong>加粗</strong>和<em>斜体</em>，以及<a href="https://example.com">链接</a>。</p>
+<ul><li>第一项</li><li>第二项<ol><li>嵌套</li></ol></li></ul>
+<blockquote><p>引用</p></blockquote>
+<pre><code class="language-rust">fn main() {}
+</code></pre>
+<table><tr><th>A</th><th>B</th></tr><tr><td>1</td><td>2</td></tr></table>"#;
+
+        assert_eq!(
+            html_to_markdown(html),
+            "## 小标题\n\n这是**加粗**和*斜体*，以及[链接](https://example.com)。\n\n- 第一项\n- 第二项\n\n  1. 嵌套\n\n> 引用\n\n```rust\nfn main() {}\n```\n\n| A | B |\n| --- | --- |\n| 1 | 2 |"
+        );
+    }
+
+    #[test]
+    fn test_extract_wechat_article() {
+        let html = r#"<html><head><title>页面标题</title><script>var a = 1;</script></head><body>
+<h1 id="activity-name"> 公众号文章 </h1>
+<a id="js_name">某公众号</a>
+<div id="js_content">
+  <section><span style="color:red">第一段</span></section>
+  <p><img data-src="https://mmbiz.qpic.cn/a.png?wx_fmt=png" src="data:image/gif;base64,AA"></p>
+  <p>价格 5*3</p>
+</div></body></html>"#;
+
+        let article = extract_article(html).unwrap();
+        assert_eq!(article.title.as_deref(), Some("公众号文章"));
+        assert_eq!(article.author.as_deref(), Some("某公众号"));
+        assert_eq!(
+            article.markdown,
+            "第一段\n\n![](https://mmbiz.qpic.cn/a.png?wx_fmt=png)\n\n价格 5\\*3"
+        );
+        assert_eq!(
+            article.images,
+            vec!["https://mmbiz.qpic.cn/a.png?wx_fmt=png"]
+        );
+    }
+}
@@ -0,0 +1,303 @@
+pub mod docx;
+pub mod html;
+pub mod notion;
+
+pub use docx::*;
+pub use html::*;
+pub use notion::*;
+
+use crate::{error::Error, Result};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+use url::Url;
+
+/// 本地转换得到的文档（docx、Notion 导出），内嵌图片随文档返回：引用地址 -> 数据
+#[derive(Debug, Clone)]
+pub struct ImportedDocument {
+    pub article: ImportedArticle,
+    pub media: HashMap<String, Vec<u8>>,
+}
+
+/// 导入来源：网页地址或本地 HTML 文件
+#[derive(Debug, Clone)]
+pub enum ImportSource {
+    Url(Url),
+    File(PathBuf),
+}
+
+impl ImportSource {
+    pub fn parse(source: &str) -> Self {
+        match Url::parse(source) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Self::Url(url),
+            _ => Self::File(PathBuf::from(source)),
+        }
+    }
+
+    /// 用于解析相对地址的基准 URL
+    fn base_url(&self) -> Option<Url> {
+        match self {
+            Self::Url(url) => Some(url.clone()),
+            Self::File(path) => std::path::absolute(path)
+                .ok()
+                .and_then(|p| Url::from_file_path(p).ok()),
+        }
+    }
+
+    async fn read(&self) -> Result<String> {
+        match self {
+            Self::Url(url) => {
+                let response =
+                    crate::core::http_client_for(crate::core::NetworkOperation::Download)?
+                        .get(url.clone())
+                        .send()
+                        .await?
+                        .error_for_status()?;
+                Ok(response.text().await?)
+            }
+            Self::File(path) => Ok(tokio::fs::read_to_string(path).await?),
+        }
+    }
+}
+
+/// 导入选项
+#[derive(Debug, Clone)]
+pub struct ImportOptions {
+    /// 图片保存目录，为 None 时保留原图片地址
+    pub assets_dir: Option<PathBuf>,
+    /// 写入 Markdown 的图片路径前缀（相对于输出文件）
+    pub assets_prefix: String,
+}
+
+/// 导入结果
+#[derive(Debug, Clone)]
+pub struct ImportResult {
+    pub article: ImportedArticle,
+    /// 带 front matter 的完整 Markdown
+    pub markdown: String,
+    pub downloaded_images: Vec<PathBuf>,
+    pub failed_images: Vec<String>,
+}
+
+/// 把网页或本地 HTML 文章导入为 Markdown，并下载其中的图片
+pub async fn import_html(source: &ImportSource, options: &ImportOptions) -> Result<ImportResult> {
+    let raw = source.read().await?;
+    let mut article = extract_article(&raw)?;
+    let base = source.base_url();
+
+    let mut downloaded_images = Vec::new();
+    let mut failed_images = Vec::new();
+    let mut body = article.markdown.clone();
+
+    for (index, src) in article.images.iter().enumerate() {
+        let resolved = resolve_url(base.as_ref(), src);
+        let replacement = match (&options.assets_dir, &resolved) {
+            (Some(dir), Some(url)) => match fetch_image(url).await {
+                Ok(data) => {
+                    let name = image_file_name(index + 1, url_extension(url));
+                    let path = save_image(dir, &name, &data)?;
+                    downloaded_images.push(path);
+                    format!("{}{}", options.assets_prefix, name)
+                }
+                Err(e) => {
+                    tracing::warn!("下载图片失败 {}: {}", url, e);
+                    failed_images.push(src.clone());
+                    url.to_string()
+                }
+            },
+            (_, Some(url)) if url.scheme() != "file" => url.to_string(),
+            _ => src.clone(),
+        };
+        body = body.replace(&format!("]({})", src), &format!("]({})", replacement));
+    }
+
+    article.markdown = body;
+    let source = match source {
+        ImportSource::Url(url) => url.to_string(),
+        ImportSource::File(path) => path.display().to_string(),
+    };
+    let markdown = render_markdown(&article, &source);
+
+    Ok(ImportResult {
+        article,
+        markdown,
+        downloaded_images,
+        failed_images,
+    })
+}
+
+/// 把本地转换的文档写出图片并生成最终 Markdown。
+///
+/// 内嵌图片保存到 `assets_dir`，未设置 `assets_dir` 或找不到数据的图片保留原引用。
+pub fn import_document(
+    document: ImportedDocument,
+    source: &str,
+    options: &ImportOptions,
+) -> Result<ImportResult> {
+    let ImportedDocument { mut article, media } = document;
+
+    let mut downloaded_images = Vec::new();
+    let mut failed_images = Vec::new();
+    let mut body = article.markdown.clone();
+
+    for (index, src) in article.images.iter().enumerate() {
+        if src.contains("://") {
+            continue;
+        }
+        let (Some(dir), Some(data)) = (&options.assets_dir, media.get(src)) else {
+            failed_images.push(src.clone());
+            continue;
+        };
+
+        let extension = Path::new(src)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        let name = image_file_name(index + 1, extension);
+        downloaded_images.push(save_image(dir, &name, data)?);
+        body = body.replace(
+            &format!("]({})", src),
+            &format!("]({}{})", options.assets_prefix, name),
+        );
+    }
+
+    article.markdown = body;
+    let markdown = render_markdown(&article, source);
+
+    Ok(ImportResult {
+        article,
+        markdown,
+        downloaded_images,
+        failed_images,
+    })
+}
+
+fn save_image(dir: &Path, name: &str, data: &[u8]) -> Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(name);
+    std::fs::write(&path, data)?;
+    Ok(path)
+}
+
+fn resolve_url(base: Option<&Url>, src: &str) -> Option<Url> {
+    let src = html_escape::decode_html_entities(src);
+    match base {
+        Some(base) => base.join(&src).ok(),
+        None => Url::parse(&src).ok(),
+    }
+}
+
+async fn fetch_image(url: &Url) -> Result<Vec<u8>> {
+    match url.scheme() {
+        "http" | "https" => {
+            let response = crate::core::http_client_for(crate::core::NetworkOperation::Download)?
+                .get(url.clone())
+                .send()
+                .await?
+                .error_for_status()?;
+            Ok(response.bytes().await?.to_vec())
+        }
+        "file" => {
+            let path = url
+                .to_file_path()
+                .map_err(|_| Error::Other(format!("无效的图片路径: {}", url)))?;
+            Ok(tokio::fs::read(path).await?)
+        }
+        scheme => Err(Error::Other(format!("不支持的图片地址协议: {}", scheme))),
+    }
+}
+
+/// 图片地址中的扩展名，微信图片的扩展名在 `wx_fmt` 参数中
+fn url_extension(url: &Url) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == "wx_fmt")
+        .map(|(_, v)| v.to_string())
+        .or_else(|| {
+            Path::new(url.path())
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+        })
+}
+
+/// 生成本地图片文件名：`image-序号.扩展名`
+fn image_file_name(index: usize, extension: Option<String>) -> String {
+    let extension = extension
+        .filter(|e| e.len() <= 5 && e.chars().all(|c| c.is_ascii_alphanumeric()))
+        .unwrap_or_else(|| "png".to_string());
+    let extension = if extension == "jpeg" {
+        "jpg".to_string()
+    } else {
+        extension
+    };
+    format!("image-{:02}.{}", index, extension)
+}
+
+fn render_markdown(article: &ImportedArticle, source: &str) -> String {
+    let mut front_matter = String::from("---\n");
+    if let Some(title) = &article.title {
+        front_matter.push_str(&format!("title: {}\n", yaml_string(title)));
+    }
+    if let Some(author) = &article.author {
+        front_matter.push_str(&format!("author: {}\n", yaml_string(author)));
+    }
+    front_matter.push_str(&format!("source: {}\n", yaml_string(source)));
+    front_matter.push_str("---\n\n");
+
+    format!("{}{}\n", front_matter, article.markdown)
+}
+
+fn yaml_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_image_file_name() {
+        let url = Url::parse("https://mmbiz.qpic.cn/abc/640?wx_fmt=jpeg&from=appmsg").unwrap();
+        assert_eq!(image_file_name(1, url_extension(&url)), "image-01.jpg");
+
+        let url = Url::parse("https://example.com/a/b.webp").unwrap();
+        assert_eq!(image_file_name(12, url_extension(&url)), "image-12.webp");
+        assert_eq!(image_file_name(3, None), "image-03.png");
+    }
+
+    #[tokio::test]
+    async fn test_import_local_file_copies_images() {
+        let dir = TempDir::new().unwrap();
+        std::fs::create_dir(dir.path().join("img")).unwrap();
+        std::fs::write(dir.path().join("img/photo.png"), b"png-bytes").unwrap();
+        std::fs::write(
+            dir.path().join("post.html"),
+            r#"<html><head><title>旧文章</title></head><body><article>
+<h1>旧文章</h1><p>正文</p><p><img src="img/photo.png" alt="照片"></p>
+</article></body></html>"#,
+        )
+        .unwrap();
+
+        let assets = dir.path().join("out/assets");
+        let source = ImportSource::parse(dir.path().join("post.html").to_str().unwrap());
+        let result = import_html(
+            &source,
+            &ImportOptions {
+                assets_dir: Some(assets.clone()),
+                assets_prefix: "assets/".to_string(),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(result.failed_images.is_empty());
+        assert_eq!(
+            std::fs::read(assets.join("image-01.png")).unwrap(),
+            b"png-bytes"
+        );
+        assert!(result.markdown.starts_with("---\ntitle: \"旧文章\"\n"));
+        assert!(result
+            .markdown
+            .contains("正文\n\n![照片](assets/image-01.png)"));
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    core::slugify,
+    error::Error,
+    importers::{ImportedArticle, ImportedDocument},
+    Result,
+};
+use percent_encoding::percent_decode_str;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    io::{Read, Seek},
+    path::{Component, Path, PathBuf},
+    sync::OnceLock,
+};
+use zip::ZipArchive;
+
+/// Notion 导出的一个页面
+#[derive(Debug, Clone)]
+pub struct NotionPage {
+    /// 去掉 Notion ID 后缀的页面名
+    pub name: String,
+    pub document: ImportedDocument,
+}
+
+/// 去掉 Notion 导出文件名末尾的 32 位页面 ID（如 `周报 0123…cdef` -> `周报`）
+pub fn notion_page_name(stem: &str) -> String {
+    static ID_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = ID_REGEX.get_or_init(|| Regex::new(r"\s+[0-9a-f]{32}$").unwrap());
+    regex.replace(stem, "").trim().to_string()
+}
+
+/// 把 Notion 导出的 Markdown 转换为 markflow 可处理的格式：
+/// 标题和属性移入 front matter，`<aside>` 标注块转为引用块，
+/// 图片地址解码为相对路径，指向其他 Notion 页面的链接改为去掉 ID 的文件名
+pub fn convert_notion_markdown(markdown: &str) -> ImportedArticle {
+    let markdown = markdown
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n");
+    let mut lines = markdown.lines().peekable();
+
+    let mut title = None;
+    let mut author = None;
+    while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+        lines.next();
+    }
+    if let Some(heading) = lines.peek().and_then(|l| l.strip_prefix("# ")) {
+        title = Some(heading.trim().to_string());
+        lines.next();
+
+        // 标题后紧跟的 `属性: 值` 行是数据库属性
+        while lines.peek().is_some_and(|l| l.trim().is_empty()) {
+            lines.next();
+        }
+        while let Some((key, value)) = lines.peek().and_then(|l| property_line(l)) {
+            if matches!(key.as_str(), "Author" | "Created by" | "作者" | "创建者") {
+                author = Some(value);
+            }
+            lines.next();
+        }
+    }
+
+    let mut body = String::new();
+    let mut in_callout = false;
+    let mut in_code = false;
+    for line in lines {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code {
+            match line.trim() {
+                "<aside>" => {
+                    in_callout = true;
+                    continue;
+                }
+                "</aside>" if in_callout => {
+                    in_callout = false;
+                    // 去掉标注块末尾多余的空引用行
+                    while body.ends_with(">\n") {
+                        body.truncate(body.len() - 2);
+                    }
+                    body.push('\n');
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        if in_callout {
+            if line.trim().is_empty() {
+                body.push_str(">\n");
+            } else {
+                body.push_str("> ");
+                body.push_str(line);
+                body.push('\n');
+            }
+        } else {
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+
+    let mut images = Vec::new();
+    let body = rewrite_references(&body, &mut images);
+
+    ImportedArticle {
+        title,
+        author,
+        markdown: normalize_blank_lines(&body),
+        images,
+    }
+}
+
+/// 读取 Notion 导出的 ZIP 包，返回其中每个页面及其引用的图片
+pub fn read_notion_zip<R: Read + Seek>(reader: R) -> Result<Vec<NotionPage>> {
+    let mut archive = ZipArchive::new(reader)
+        .map_err(|e| Error::Other(format!("无法读取 Notion 导出文件: {}", e)))?;
+
+    let mut files: HashMap<String, Vec<u8>> = HashMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| Error::Other(format!("读取 ZIP 条目失败: {}", e)))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let mut data = Vec::new();
+        entry.read_to_end(&mut data)?;
+        files.insert(zip_path(&name), data);
+    }
+
+    let mut names: Vec<&String> = files.keys().filter(|n| n.ends_with(".md")).collect();
+    names.sort();
+
+    let mut pages = Vec::new();
+    for name in names {
+        let markdown = String::from_utf8_lossy(&files[name]).into_owned();
+        let article = convert_notion_markdown(&markdown);
+        let base = Path::new(name).parent().unwrap_or_else(|| Path::new(""));
+
+        let media = article
+            .images
+            .iter()
+            .filter_map(|image| {
+                let path = zip_path(&normalize(&base.join(image)));
+                files.get(&path).map(|data| (image.clone(), data.clone()))
+            })
+            .collect();
+
+        let stem = Path::new(name)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        pages.push(NotionPage {
+            name: notion_page_name(&stem),
+            document: ImportedDocument { article, media },
+        });
+    }
+
+    if pages.is_empty() {
+        return Err(Error::Other(
+            "Notion 导出文件中没有 Markdown 页面".to_string(),
+        ));
+    }
+    Ok(pages)
+}
+
+/// 读取 Notion 导出后解压在本地的单个 Markdown 页面
+pub fn read_notion_file(path: &Path) -> Result<NotionPage> {
+    let markdown = std::fs::read_to_string(path)?;
+    let article = convert_notion_markdown(&markdown);
+    let base = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let media = article
+        .images
+        .iter()
+        .filter_map(|image| {
+            std::fs::read(base.join(image))
+                .ok()
+                .map(|data| (image.clone(), data))
+        })
+        .collect();
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(NotionPage {
+        name: notion_page_name(&stem),
+        document: ImportedDocument { article, media },
+    })
+}
+
+fn property_line(line: &str) -> Option<(String, String)> {
+    let (key, value) = line.split_once(": ")?;
+    let key = key.trim();
+    if key.is_empty() || key.len() > 40 || key.starts_with(['#', '-', '*', '>', '!', '[']) {
+        return None;
+    }
+    Some((key.to_string(), value.trim().to_string()))
+}
+
+fn rewrite_references(markdown: &str, images: &mut Vec<String>) -> String {
+    static LINK_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = LINK_REGEX.get_or_init(|| Regex::new(r"(!?)\[([^\]]*)\]\(([^)\s]+)\)").unwrap());
+
+    regex
+        .replace_all(markdown, |caps: &regex::Captures| {
+            let is_image = &caps[1] == "!";
+            let text = &caps[2];
+            let target = &caps[3];
+            if target.contains("://") || target.starts_with('#') || target.starts_with("mailto:") {
+                return caps[0].to_string();
+            }
+
+            let decoded = percent_decode_str(target).decode_utf8_lossy().to_string();
+            if is_image {
+                if !images.contains(&decoded) {
+                    images.push(decoded.clone());
+                }
+                return format!("![{}]({})", text, decoded);
+            }
+
+            // 指向其他 Notion 页面的链接：`子页面 <id>.md` -> `子页面.md`
+            let path = Path::new(&decoded);
+            if path.extension().is_some_and(|e| e == "md") {
+                let stem = path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                return format!(
+                    "[{}]({}.md)",
+                    text,
+                    page_file_stem(&notion_page_name(&stem))
+                );
+            }
+            format!("[{}]({})", text, decoded)
+        })
+        .into_owned()
+}
+
+/// 页面名对应的输出文件名（不含扩展名）
+pub fn page_file_stem(name: &str) -> String {
+    let slug = slugify(name);
+    if slug.is_empty() {
+        "untitled".to_string()
+    } else {
+        slug
+    }
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                out.pop();
+            }
+            Component::CurDir => {}
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn zip_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn normalize_blank_lines(text: &str) -> String {
+    let mut out = String::new();
+    let mut blank = 0;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            blank += 1;
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if blank > 0 { "\n\n" } else { "\n" });
+        }
+        blank = 0;
+        out.push_str(line.trim_end());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    const PAGE: &str = "# 读书笔记\n\nAuthor: 张三\nTags: 阅读\n\n<aside>\n💡 这是一个标注\n\n第二行\n\n</aside>\n\n正文见[子页面](%E5%AD%90%E9%A1%B5%E9%9D%A2%200123456789abcdef0123456789abcdef.md)。\n\n![cover.png](%E8%AF%BB%E4%B9%A6%E7%AC%94%E8%AE%B0/cover.png)\n";
+
+    #[test]
+    fn test_convert_notion_markdown() {
+        let article = convert_notion_markdown(PAGE);
+        assert_eq!(article.title.as_deref(), Some("读书笔记"));
+        assert_eq!(article.author.as_deref(), Some("张三"));
+        assert_eq!(
+            article.markdown,
+            "> 💡 这是一个标注\n>\n> 第二行\n\n正文见[子页面](子-页面.md)。\n\n![cover.png](读书笔记/cover.png)"
+        );
+        assert_eq!(article.images, vec!["读书笔记/cover.png"]);
+        assert_eq!(
+            notion_page_name("读书笔记 0123456789abcdef0123456789abcdef"),
+            "读书笔记"
+        );
+    }
+
+    #[test]
+    fn test_read_notion_zip() {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default();
+        writer
+            .start_file("读书笔记 0123456789abcdef0123456789abcdef.md", options)
+            .unwrap();
+        writer.write_all(PAGE.as_bytes()).unwrap();
+        writer.start_file("读书笔记/cover.png", options).unwrap();
+        writer.write_all(b"cover").unwrap();
+        let bytes = writer.finish().unwrap().into_inner();
+
+        let pages = read_notion_zip(Cursor::new(bytes)).unwrap();
+        assert_eq!(pages.len(), 1);
+        assert_eq!(pages[0].name, "读书笔记");
+        assert_eq!(pages[0].document.media["读书笔记/cover.png"], b"cover");
+    }
+}
@@ -1,7 +1,10 @@
 pub mod adapters;
 pub mod cli;
 pub mod core;
+pub mod importers;
 pub mod publishers;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod testing;
 pub mod web;
 
 pub use adapters::{PlatformAdapter, WeChatStyleAdapter, ZhihuStyleAdapter};
@@ -30,12 +33,19 @@ pub mod error {
         #[error("Serialization error: {0}")]
         Serde(#[from] serde_json::Error),
 
+        #[error("Database error: {0}")]
+        Database(#[from] rusqlite::Error),
+
         #[error("Browser automation error: {0}")]
         Browser(String),
 
         #[error("Publishing error: {0}")]
         Publishing(String),
 
+        /// 平台登录态（Cookie、令牌）已失效，需要重新登录
+        #[error("Authentication expired: {0}")]
+        AuthExpired(String),
+
         #[error("Configuration error: {0}")]
         Config(String),
 
@@ -45,7 +55,89 @@ pub mod error {
         #[error("Invalid platform: {0}")]
         InvalidPlatform(String),
 
+        #[error("Validation failed: {0}")]
+        Validation(String),
+
+        #[error("Network error: {0}")]
+        Network(String),
+
+        #[error("{failed}/{total} 个文件处理失败")]
+        PartialFailure { failed: usize, total: usize },
+
         #[error("Other error: {0}")]
         Other(String),
     }
+
+    /// 进程退出码，CI 可据此区分失败类型
+    pub mod exit_code {
+        pub const SUCCESS: i32 = 0;
+        /// 未分类的错误
+        pub const FAILURE: i32 = 1;
+        /// 配置错误（配置文件、模板、平台名称）
+        pub const CONFIG: i32 = 2;
+        /// 内容校验失败（lint 错误、截图不一致等）
+        pub const VALIDATION: i32 = 3;
+        /// 网络失败（请求出错、离线模式下需要联网）
+        pub const NETWORK: i32 = 4;
+        /// 平台拒绝发布
+        pub const PUBLISH: i32 = 5;
+        /// 批量处理中部分文件失败
+        pub const PARTIAL_FAILURE: i32 = 6;
+        /// 平台登录已失效，需要重新登录
+        pub const AUTH_EXPIRED: i32 = 7;
+    }
+
+    impl Error {
+        /// 按错误类别返回进程退出码
+        pub fn exit_code(&self) -> i32 {
+            match self {
+                Error::Config(_) | Error::Template(_) | Error::InvalidPlatform(_) => {
+                    exit_code::CONFIG
+                }
+                Error::Validation(_) => exit_code::VALIDATION,
+                Error::Http(_) | Error::Network(_) => exit_code::NETWORK,
+                Error::Publishing(_) => exit_code::PUBLISH,
+                Error::PartialFailure { .. } => exit_code::PARTIAL_FAILURE,
+                Error::AuthExpired(_) => exit_code::AUTH_EXPIRED,
+                Error::IO(_)
+                | Error::Markdown(_)
+                | Error::Html(_)
+                | Error::Serde(_)
+                | Error::Database(_)
+                | Error::Browser(_)
+                | Error::Other(_) => exit_code::FAILURE,
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_exit_codes_are_distinct_per_class() {
+            let codes = [
+                Error::Config("x".into()).exit_code(),
+                Error::Validation("x".into()).exit_code(),
+                Error::Network("x".into()).exit_code(),
+                Error::Publishing("x".into()).exit_code(),
+                Error::AuthExpired("x".into()).exit_code(),
+                Error::PartialFailure {
+                    failed: 1,
+                    total: 3,
+                }
+                .exit_code(),
+                Error::Other("x".into()).exit_code(),
+            ];
+            let mut unique = codes.to_vec();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(unique.len(), codes.len());
+            assert!(!codes.contains(&exit_code::SUCCESS));
+            assert_eq!(
+                Error::InvalidPlatform("x".into()).exit_code(),
+                exit_code::CONFIG
+            );
+        }
+    }
 }
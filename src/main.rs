@@ -4,6 +4,6 @@ use markflow::cli;
 async fn main() {
     if let Err(e) = cli::run().await {
         eprintln!("错误: {}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,175 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{
+        article_assets::ArticleAssets, inherited_front_matter, lock::write_atomic,
+        merge_front_matter_mapping, Content, ContentRecord, ContentStore, MarkdownProcessor,
+        Platform, PublishResult, PublishStatus,
+    },
+    error::Error,
+    publishers::{
+        backend::{create_publisher, BackendPublisher},
+        images::sync_images,
+        traits::Publisher,
+        upload::UploadSessions,
+    },
+    Result,
+};
+use std::path::Path;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+/// 用源文件的元数据和台账中保存的平台 HTML 组装待发布的内容；
+/// 开启 `workflow.require_approval` 时，未批准的文章只能保存草稿
+pub async fn prepare_article(
+    record: &ContentRecord,
+    platform: &Platform,
+    config: &AppConfig,
+    draft: bool,
+) -> Result<Content> {
+    if config.workflow.require_approval && !draft {
+        record.workflow.ensure_publishable(&record.title)?;
+    }
+    let platform_name = platform.to_string();
+    let html = record.html.get(&platform_name).ok_or_else(|| {
+        Error::Other(format!(
+            "《{}》没有 {} 平台的输出（请先运行 process -p {}）",
+            record.title, platform_name, platform_name
+        ))
+    })?;
+    let source = record
+        .source
+        .as_ref()
+        .ok_or_else(|| Error::Other(format!("《{}》没有记录源文件", record.title)))?;
+
+    let markdown = tokio::fs::read_to_string(source).await?;
+    let mut article = MarkdownProcessor::new()
+        .with_timezone(config.timezone())
+        .with_autolink(config.links.autolink)
+        .with_smart_punctuation(config.smart_punctuation())
+        .with_math(config.math.enabled)
+        .with_permalink(config.permalink())
+        .with_inherited_front_matter(inherited_front_matter(source)?)
+        .process(&markdown)?;
+    article.id = record.id;
+    article.html = html.clone();
+    article.apply_platform_overrides(platform);
+    Ok(article)
+}
+
+/// 发布途径需要时（如公众号接口），先把正文图片上传到平台素材库并替换为平台地址；
+/// 已上传过且内容未变的图片直接复用台账 `ledger` 中的地址
+pub async fn upload_article_images(
+    publisher: &BackendPublisher,
+    article: &mut Content,
+    source: Option<&Path>,
+    ledger: &Path,
+) -> Result<()> {
+    let Some(uploader) = publisher.image_uploader() else {
+        return Ok(());
+    };
+    let assets = source.map(ArticleAssets::for_source);
+    let mut sessions = UploadSessions::open_default()?;
+    let (html, summary) = sync_images(
+        &article.html,
+        assets.as_ref(),
+        uploader,
+        ledger,
+        &mut sessions,
+    )
+    .await?;
+    article.html = html;
+    if !summary.uploaded.is_empty() || !summary.reused.is_empty() {
+        info!(
+            "正文图片：上传 {} 张，复用 {} 张",
+            summary.uploaded.len(),
+            summary.reused.len()
+        );
+    }
+    Ok(())
+}
+
+/// 发布台账中的一篇文章：组装内容、上传正文图片后发布或保存草稿。
+/// 上次保存的草稿还没发布时更新同一篇草稿，草稿已在平台上删除等情况下改为新建。
+///
+/// 只在读取图片记录时短暂打开台账 `ledger`，发布结果由调用方通过 [`record_publish_result`] 记录
+pub async fn publish_record(
+    record: &ContentRecord,
+    platform: &Platform,
+    config: &AppConfig,
+    draft: bool,
+    ledger: &Path,
+) -> Result<PublishResult> {
+    let mut publisher = create_publisher(platform, config)?;
+    let existing_draft = record
+        .pending_draft(&platform.to_string())
+        .map(str::to_string);
+    let mut article = prepare_article(record, platform, config, draft).await?;
+    upload_article_images(&publisher, &mut article, record.source.as_deref(), ledger).await?;
+
+    info!(
+        "发布《{}》到 {}（{}）",
+        article.title,
+        platform,
+        publisher.backend_kind()
+    );
+    let result = match (draft, existing_draft) {
+        (true, Some(draft_id)) => match publisher.update_content(&draft_id, &article).await {
+            Ok(result) => Ok(result),
+            Err(e) => {
+                warn!("更新草稿 {} 失败，改为新建草稿: {}", draft_id, e);
+                publisher.create_draft(&article).await
+            }
+        },
+        (true, None) => publisher.create_draft(&article).await,
+        (false, _) => publisher.publish(&article).await,
+    };
+    publisher.shutdown().await;
+    result
+}
+
+/// 把发布结果记入台账 `ledger` 并按配置写回源文件，台账在写入后立即释放
+pub fn record_publish_result(
+    ledger: &Path,
+    id: &Uuid,
+    result: PublishResult,
+    config: &AppConfig,
+) -> Result<()> {
+    let mut store = ContentStore::open(ledger)?;
+    if let Some(record) = store.get_mut(id) {
+        // 文章已经发布，写回失败只提示，不影响记录发布结果
+        if let Err(e) = write_back_publication(record, &result, config) {
+            warn!("写回发布地址失败 {:?}: {}", record.source, e);
+        }
+        record.record_publication(result);
+    }
+    store.save()
+}
+
+/// `publish.write_back` 开启时，把发布成功的地址和时间写回源文件 front matter 的
+/// `published.<平台>.url` 和 `published.<平台>.date`；草稿和没有地址的结果不写
+fn write_back_publication(
+    record: &ContentRecord,
+    result: &PublishResult,
+    config: &AppConfig,
+) -> Result<()> {
+    if !config.publish.write_back || !matches!(result.status, PublishStatus::Success) {
+        return Ok(());
+    }
+    let (Some(url), Some(source)) = (&result.url, &record.source) else {
+        return Ok(());
+    };
+    let markdown = std::fs::read_to_string(source)?;
+    let date = config
+        .timezone()
+        .now()
+        .to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+    let updated = merge_front_matter_mapping(
+        &markdown,
+        "published",
+        &result.platform.to_string(),
+        &[("url", url.clone()), ("date", date)],
+    )?;
+    write_atomic(source, updated)?;
+    debug!("已把发布地址写回 {:?}", source);
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{
+        content::{Content, Platform, PublishResult, PublishStatus},
+        metrics, FieldMapping,
+    },
+    error::Error,
+    publishers::{
+        traits::{ImageUploader, Publisher},
+        WeChatApiBackend, WeChatBrowserBackend, ZhihuBrowserBackend,
+    },
+    Result,
+};
+use async_trait::async_trait;
+use std::str::FromStr;
+
+/// 发布途径：平台开放接口，或用无头浏览器操作平台的后台编辑器
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PublishBackendKind {
+    Api,
+    Browser,
+}
+
+impl FromStr for PublishBackendKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "api" => Ok(Self::Api),
+            "browser" => Ok(Self::Browser),
+            other => Err(Error::Config(format!(
+                "无效的发布途径: {}（可选: api、browser）",
+                other
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for PublishBackendKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Api => write!(f, "api"),
+            Self::Browser => write!(f, "browser"),
+        }
+    }
+}
+
+/// 一种发布途径的具体操作。同一平台的不同途径实现这个 trait，
+/// 由 [`BackendPublisher`] 统一包装为 [`Publisher`]
+#[async_trait]
+pub trait PublishBackend: Send + Sync {
+    fn kind(&self) -> PublishBackendKind;
+
+    /// 保存为草稿，返回草稿 ID
+    async fn create_draft(&self, content: &Content) -> Result<String>;
+
+    async fn update_draft(&self, draft_id: &str, content: &Content) -> Result<()>;
+
+    async fn delete_draft(&self, draft_id: &str) -> Result<()>;
+
+    /// 发布已保存的草稿
+    async fn publish_draft(&self, draft_id: &str) -> Result<PublishResult>;
+
+    /// 查询发布状态，`publish_id` 为 [`PublishResult::publish_id`]
+    async fn get_publish_status(&self, publish_id: &str) -> Result<PublishResult>;
+
+    /// 途径需要先把正文图片上传到平台时返回上传器（如公众号接口只接受素材库中的图片）
+    fn image_uploader(&self) -> Option<&dyn ImageUploader> {
+        None
+    }
+
+    /// 释放途径持有的资源（如浏览器会话）
+    async fn shutdown(&self) {}
+}
+
+/// 按配置选择发布途径的发布器，各平台共用这一个 [`Publisher`] 实现
+pub struct BackendPublisher {
+    platform: Platform,
+    backend: Box<dyn PublishBackend>,
+}
+
+impl BackendPublisher {
+    pub fn new(platform: Platform, backend: impl PublishBackend + 'static) -> Self {
+        Self {
+            platform,
+            backend: Box::new(backend),
+        }
+    }
+
+    pub fn backend_kind(&self) -> PublishBackendKind {
+        self.backend.kind()
+    }
+
+    pub fn image_uploader(&self) -> Option<&dyn ImageUploader> {
+        self.backend.image_uploader()
+    }
+
+    pub async fn shutdown(&self) {
+        self.backend.shutdown().await;
+    }
+
+    /// 记录发布指标（`markflow_publish_total`），成功和失败都计入
+    fn observe(&self, result: Result<PublishResult>) -> Result<PublishResult> {
+        let success = matches!(&result, Ok(r) if !matches!(r.status, PublishStatus::Failed));
+        metrics().record_publish(&self.platform, success);
+        result
+    }
+
+    fn draft_result(&self, draft_id: String, message: String) -> PublishResult {
+        PublishResult {
+            platform: self.platform.clone(),
+            url: None,
+            draft_id: Some(draft_id),
+            publish_id: None,
+            status: PublishStatus::Draft,
+            message,
+            title_variant: None,
+        }
+    }
+}
+
+#[async_trait]
+impl Publisher for BackendPublisher {
+    fn platform(&self) -> Platform {
+        self.platform.clone()
+    }
+
+    async fn publish(&mut self, content: &Content) -> Result<PublishResult> {
+        let result = match self.backend.create_draft(content).await {
+            Ok(draft_id) => self.backend.publish_draft(&draft_id).await,
+            Err(e) => Err(e),
+        };
+        self.observe(result)
+    }
+
+    async fn create_draft(&mut self, content: &Content) -> Result<PublishResult> {
+        let result = self.backend.create_draft(content).await.map(|draft_id| {
+            self.draft_result(draft_id, format!("已保存草稿（{}）", self.backend.kind()))
+        });
+        self.observe(result)
+    }
+
+    async fn update_content(
+        &mut self,
+        content_id: &str,
+        content: &Content,
+    ) -> Result<PublishResult> {
+        let result = self
+            .backend
+            .update_draft(content_id, content)
+            .await
+            .map(|_| {
+                self.draft_result(
+                    content_id.to_string(),
+                    format!("已更新草稿（{}）", self.backend.kind()),
+                )
+            });
+        self.observe(result)
+    }
+
+    async fn delete_content(&mut self, content_id: &str) -> Result<()> {
+        self.backend.delete_draft(content_id).await
+    }
+
+    async fn get_publish_status(&self, content_id: &str) -> Result<PublishResult> {
+        self.backend.get_publish_status(content_id).await
+    }
+}
+
+/// 按平台配置的发布途径（如 `wechat.backend`）创建发布器
+pub fn create_publisher(platform: &Platform, config: &AppConfig) -> Result<BackendPublisher> {
+    match platform {
+        Platform::WeChat => {
+            let publisher = match config.wechat.backend.parse()? {
+                PublishBackendKind::Api => BackendPublisher::new(
+                    Platform::WeChat,
+                    WeChatApiBackend::new(&config.wechat)
+                        .with_field_mapping(FieldMapping::from_config(&config.field_mapping)),
+                ),
+                PublishBackendKind::Browser => BackendPublisher::new(
+                    Platform::WeChat,
+                    WeChatBrowserBackend::from_config(config),
+                ),
+            };
+            Ok(publisher)
+        }
+        Platform::Zhihu => Ok(BackendPublisher::new(
+            Platform::Zhihu,
+            ZhihuBrowserBackend::from_config(config),
+        )),
+        Platform::All => Err(Error::InvalidPlatform(
+            "发布时不能选择'all'平台".to_string(),
+        )),
+        Platform::Custom(name) => Err(Error::Publishing(format!(
+            "自定义平台 {} 暂不支持发布",
+            name
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 保存草稿总是成功、提交发布总是失败的发布途径
+    struct FakeBackend;
+
+    #[async_trait]
+    impl PublishBackend for FakeBackend {
+        fn kind(&self) -> PublishBackendKind {
+            PublishBackendKind::Api
+        }
+
+        async fn create_draft(&self, _content: &Content) -> Result<String> {
+            Ok("draft-1".to_string())
+        }
+
+        async fn update_draft(&self, _draft_id: &str, _content: &Content) -> Result<()> {
+            Ok(())
+        }
+
+        async fn delete_draft(&self, _draft_id: &str) -> Result<()> {
+            Ok(())
+        }
+
+        async fn publish_draft(&self, _draft_id: &str) -> Result<PublishResult> {
+            Err(Error::Publishing("群发额度已用完".to_string()))
+        }
+
+        async fn get_publish_status(&self, _publish_id: &str) -> Result<PublishResult> {
+            Err(Error::Publishing("不支持".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_records_metrics() {
+        // 指标是全局的，用自定义平台避免与其他测试互相影响
+        let platform = Platform::Custom("metrics-test".to_string());
+        let mut publisher = BackendPublisher::new(platform.clone(), FakeBackend);
+        let content = Content::new("标题".to_string(), "正文".to_string());
+
+        publisher.create_draft(&content).await.unwrap();
+        publisher.update_content("draft-1", &content).await.unwrap();
+        assert!(publisher.publish(&content).await.is_err());
+
+        assert_eq!(metrics().publish_count(&platform, true), 2);
+        assert_eq!(metrics().publish_count(&platform, false), 1);
+        assert!(metrics()
+            .render()
+            .contains("markflow_publish_total{platform=\"metrics-test\",outcome=\"failure\"} 1"));
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{core::write_atomic, error::Error, Result};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// 知乎登录态所在的 Cookie
+pub const ZHIHU_AUTH_COOKIE: &str = "z_c0";
+
+/// 从浏览器导出的 Cookie 文件中读取的一条记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    /// 会话 Cookie 没有过期时间
+    pub expires: Option<DateTime<Utc>>,
+}
+
+impl StoredCookie {
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+}
+
+/// 读取 Cookie 文件，支持浏览器扩展导出的 JSON 数组和 Netscape cookies.txt 两种格式
+pub fn load_cookies(path: &Path) -> Result<Vec<StoredCookie>> {
+    let raw = std::fs::read_to_string(path)?;
+    parse_cookies(&raw)
+        .map_err(|e| Error::Config(format!("无法解析 Cookie 文件 {}: {}", path.display(), e)))
+}
+
+/// 以浏览器扩展的 JSON 格式写回 Cookie 文件，[`load_cookies`] 可以直接读取
+pub fn save_cookies(path: &Path, cookies: &[StoredCookie]) -> Result<()> {
+    let entries: Vec<serde_json::Value> = cookies
+        .iter()
+        .map(|cookie| {
+            serde_json::json!({
+                "name": cookie.name,
+                "value": cookie.value,
+                "domain": cookie.domain,
+                "expirationDate": cookie.expires.map(|e| e.timestamp()),
+            })
+        })
+        .collect();
+    write_atomic(path, serde_json::to_string_pretty(&entries)?)
+}
+
+/// 读取知乎登录 Cookie 并去掉已过期的条目；文件不存在或缺少有效的登录 Cookie 时
+/// 返回 [`Error::AuthExpired`]
+pub fn zhihu_auth_cookies(path: &Path, now: DateTime<Utc>) -> Result<Vec<StoredCookie>> {
+    if !path.exists() {
+        return Err(Error::AuthExpired(format!(
+            "知乎 Cookie 文件 {} 不存在，请导出登录 Cookie",
+            path.display()
+        )));
+    }
+    let cookies: Vec<_> = load_cookies(path)?
+        .into_iter()
+        .filter(|c| !c.is_expired(now))
+        .collect();
+    if !cookies.iter().any(|c| c.name == ZHIHU_AUTH_COOKIE) {
+        return Err(Error::AuthExpired(format!(
+            "知乎登录已失效（{} 缺失或已过期），请重新导出 Cookie 到 {}",
+            ZHIHU_AUTH_COOKIE,
+            path.display()
+        )));
+    }
+    Ok(cookies)
+}
+
+fn parse_cookies(raw: &str) -> std::result::Result<Vec<StoredCookie>, String> {
+    if raw.trim_start().starts_with('[') {
+        parse_json_cookies(raw)
+    } else {
+        Ok(parse_netscape_cookies(raw))
+    }
+}
+
+fn parse_json_cookies(raw: &str) -> std::result::Result<Vec<StoredCookie>, String> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    Ok(entries
+        .iter()
+        .filter_map(|entry| {
+            let text = |key: &str| entry.get(key).and_then(|v| v.as_str()).map(str::to_string);
+            // Chrome 扩展使用 expirationDate，Puppeteer/Playwright 使用 expires（会话 Cookie 为 -1）
+            let expires = entry
+                .get("expirationDate")
+                .or_else(|| entry.get("expires"))
+                .and_then(|v| v.as_f64())
+                .filter(|secs| *secs > 0.0)
+                .and_then(|secs| DateTime::from_timestamp(secs as i64, 0));
+            Some(StoredCookie {
+                name: text("name")?,
+                value: text("value").unwrap_or_default(),
+                domain: text("domain").unwrap_or_default(),
+                expires,
+            })
+        })
+        .collect())
+}
+
+fn parse_netscape_cookies(raw: &str) -> Vec<StoredCookie> {
+    raw.lines()
+        .map(|line| line.strip_prefix("#HttpOnly_").unwrap_or(line))
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let expires = fields[4]
+                .parse::<i64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .and_then(|secs| DateTime::from_timestamp(secs, 0));
+            Some(StoredCookie {
+                name: fields[5].to_string(),
+                value: fields[6].to_string(),
+                domain: fields[0].to_string(),
+                expires,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json_and_netscape_cookies() {
+        let json = r#"[
+            {"name": "z_c0", "value": "abc", "domain": ".zhihu.com", "expirationDate": 1700000000.5},
+            {"name": "_xsrf", "value": "x", "domain": ".zhihu.com", "expires": -1}
+        ]"#;
+        let cookies = parse_cookies(json).unwrap();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].expires.unwrap().timestamp(), 1_700_000_000);
+        assert!(cookies[1].expires.is_none());
+        assert!(cookies[0].is_expired(Utc::now()));
+        assert!(!cookies[1].is_expired(Utc::now()));
+
+        let netscape = "# Netscape HTTP Cookie File\n\
+            #HttpOnly_.zhihu.com\tTRUE\t/\tTRUE\t4102444800\tz_c0\tabc\n";
+        let cookies = parse_cookies(netscape).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].name, ZHIHU_AUTH_COOKIE);
+        assert!(!cookies[0].is_expired(Utc::now()));
+    }
+
+    #[test]
+    fn test_zhihu_auth_cookies_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("zhihu_cookies.json");
+        assert!(matches!(
+            zhihu_auth_cookies(&path, Utc::now()),
+            Err(Error::AuthExpired(_))
+        ));
+
+        let cookie = |name: &str, expires: i64| StoredCookie {
+            name: name.to_string(),
+            value: "v".to_string(),
+            domain: ".zhihu.com".to_string(),
+            expires: DateTime::from_timestamp(expires, 0),
+        };
+        save_cookies(&path, &[cookie(ZHIHU_AUTH_COOKIE, 1_700_000_000)]).unwrap();
+        let err = zhihu_auth_cookies(&path, Utc::now()).unwrap_err();
+        assert!(matches!(err, Error::AuthExpired(_)));
+
+        save_cookies(
+            &path,
+            &[cookie(ZHIHU_AUTH_COOKIE, 4_102_444_800), cookie("_xsrf", 1)],
+        )
+        .unwrap();
+        let cookies = zhihu_auth_cookies(&path, Utc::now()).unwrap();
+        assert_eq!(cookies.len(), 1);
+        assert_eq!(cookies[0].expires.unwrap().timestamp(), 4_102_444_800);
+    }
+}
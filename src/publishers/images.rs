@@ -0,0 +1,227 @@
+use crate::{
+    cli::progress,
+    core::{
+        article_assets::ArticleAssets,
+        http_client_for, map_concurrent, metrics, network_config,
+        store::{content_hash, ContentStore, MediaRecord},
+        NetworkOperation,
+    },
+    error::Error,
+    publishers::{
+        traits::ImageUploader,
+        upload::{upload_media, UploadOptions, UploadSessions},
+    },
+    Result,
+};
+use regex::Regex;
+use std::{collections::HashMap, path::Path, sync::OnceLock};
+
+/// 一次图片同步的结果
+#[derive(Debug, Clone, Default)]
+pub struct ImageSyncSummary {
+    /// 本次实际上传的图片（原地址）
+    pub uploaded: Vec<String>,
+    /// 哈希未变化、复用台账中已有地址的图片
+    pub reused: Vec<String>,
+}
+
+fn image_sources(html: &str) -> Vec<String> {
+    static IMG_SRC_REGEX: OnceLock<Regex> = OnceLock::new();
+    let regex = IMG_SRC_REGEX.get_or_init(|| Regex::new(r#"<img\b[^>]*?\bsrc="([^"]+)""#).unwrap());
+
+    let mut sources: Vec<String> = Vec::new();
+    for caps in regex.captures_iter(html) {
+        let src = caps[1].to_string();
+        if !src.starts_with("data:") && !sources.contains(&src) {
+            sources.push(src);
+        }
+    }
+    sources
+}
+
+async fn load_image(
+    client: &reqwest::Client,
+    src: &str,
+    assets: Option<&ArticleAssets>,
+) -> Result<Vec<u8>> {
+    if src.starts_with("http://") || src.starts_with("https://") {
+        let response = client.get(src).send().await?.error_for_status()?;
+        return Ok(response.bytes().await?.to_vec());
+    }
+
+    let decoded = html_escape::decode_html_entities(src);
+    let path = assets
+        .and_then(|assets| assets.resolve(&decoded))
+        .unwrap_or_else(|| Path::new(decoded.as_ref()).to_path_buf());
+    tokio::fs::read(&path)
+        .await
+        .map_err(|e| Error::Other(format!("读取图片失败 {:?}: {}", path, e)))
+}
+
+fn file_name(src: &str) -> String {
+    src.split(['?', '#'])
+        .next()
+        .and_then(|s| s.rsplit('/').next())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("image")
+        .to_string()
+}
+
+/// 上传HTML中引用的图片并替换为平台地址。
+///
+/// 图片按内容哈希与台账比对，哈希未变化的图片直接复用之前的 URL/media_id，
+/// 只有新增或修改过的图片才会重新上传。本地图片按 `assets` 的规则查找，
+/// 未提供时按相对当前目录读取。上传失败会重试，大图片在平台支持时分片上传，
+/// 中断的分片上传记录在 `sessions` 中，下次同步时续传。
+///
+/// 台账 `ledger` 只在查询和记录时短暂打开，上传过程中不持有台账锁，
+/// 其他 markflow 进程可以同时读写台账
+pub async fn sync_images(
+    html: &str,
+    assets: Option<&ArticleAssets>,
+    uploader: &dyn ImageUploader,
+    ledger: &Path,
+    sessions: &mut UploadSessions,
+) -> Result<(String, ImageSyncSummary)> {
+    let platform = uploader.platform();
+    let options = UploadOptions::from_config(&network_config());
+    let mut summary = ImageSyncSummary::default();
+    let mut result = html.to_string();
+
+    // 并发读取/下载图片，并发数取自 `[network] max_concurrency`
+    let sources = image_sources(html);
+    let client = http_client_for(NetworkOperation::Download)?;
+    let assets: Option<ArticleAssets> = assets.cloned();
+    let images = map_concurrent(
+        sources.clone(),
+        network_config().max_concurrency,
+        move |src| {
+            let client = client.clone();
+            let assets = assets.clone();
+            async move { load_image(&client, &src, assets.as_ref()).await }
+        },
+    )
+    .await;
+
+    let images = images.into_iter().collect::<Result<Vec<_>>>()?;
+    let hashes: Vec<String> = images.iter().map(|data| content_hash(data)).collect();
+    // 哈希 -> 已上传的地址
+    let mut known: HashMap<String, String> = {
+        let store = ContentStore::open(ledger)?;
+        hashes
+            .iter()
+            .filter_map(|hash| {
+                let media = store.find_media(&platform, hash)?;
+                Some((hash.clone(), media.url.clone()))
+            })
+            .collect()
+    };
+
+    let bar = progress::bar(sources.len(), "上传图片");
+    for ((src, data), hash) in sources.into_iter().zip(images).zip(hashes) {
+        bar.set_message(src.clone());
+        let cached = known.get(&hash).cloned();
+        metrics().record_cache("images", cached.is_some());
+
+        let url = match cached {
+            Some(url) => {
+                tracing::debug!("复用已上传图片: {} -> {}", src, url);
+                summary.reused.push(src.clone());
+                url
+            }
+            None => {
+                tracing::info!("上传图片: {}", src);
+                let uploaded =
+                    upload_media(uploader, &file_name(&src), data, &hash, sessions, &options)
+                        .await?;
+                // 每上传一张就写入台账，中断后已上传的图片不会重复上传
+                let mut store = ContentStore::open(ledger)?;
+                store.record_media(MediaRecord {
+                    platform: platform.clone(),
+                    hash: hash.clone(),
+                    source: src.clone(),
+                    url: uploaded.url.clone(),
+                    media_id: uploaded.media_id,
+                    uploaded_at: chrono::Utc::now(),
+                });
+                store.save()?;
+                known.insert(hash, uploaded.url.clone());
+                summary.uploaded.push(src.clone());
+                uploaded.url
+            }
+        };
+
+        result = result.replace(&format!(r#"src="{}""#, src), &format!(r#"src="{}""#, url));
+        bar.inc(1);
+    }
+
+    Ok((result, summary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{core::content::Platform, publishers::traits::UploadedImage};
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tempfile::TempDir;
+
+    struct CountingUploader {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ImageUploader for CountingUploader {
+        fn platform(&self) -> Platform {
+            Platform::WeChat
+        }
+
+        async fn upload_image(&self, filename: &str, _data: Vec<u8>) -> Result<UploadedImage> {
+            let n = self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(UploadedImage {
+                url: format!("https://cdn.example.com/{}/{}", n, filename),
+                media_id: Some(format!("media-{}", n)),
+            })
+        }
+    }
+
+    #[test]
+    fn test_image_sources_dedup() {
+        let html = r#"<img src="a.png"><img alt="x" src="a.png"><img src="data:image/png;base64,AA"><img src="b.png">"#;
+        assert_eq!(image_sources(html), vec!["a.png", "b.png"]);
+        assert_eq!(file_name("https://a.com/x/y.png?w=1"), "y.png");
+    }
+
+    #[tokio::test]
+    async fn test_only_changed_images_are_reuploaded() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.png"), b"image-a").unwrap();
+        std::fs::write(dir.path().join("b.png"), b"image-b").unwrap();
+
+        let ledger = dir.path().join(crate::core::store::LEDGER_FILE);
+        let uploader = CountingUploader {
+            calls: AtomicUsize::new(0),
+        };
+        let mut sessions = UploadSessions::in_memory();
+        let html = r#"<p><img src="a.png"></p><p><img src="b.png"></p>"#;
+        let assets = ArticleAssets::for_source(&dir.path().join("post.md"));
+
+        let (first, summary) = sync_images(html, Some(&assets), &uploader, &ledger, &mut sessions)
+            .await
+            .unwrap();
+        assert_eq!(summary.uploaded.len(), 2);
+        assert!(first.contains("https://cdn.example.com/"));
+
+        // 只修改一张图片后再次同步
+        std::fs::write(dir.path().join("b.png"), b"image-b-v2").unwrap();
+        let (second, summary) = sync_images(html, Some(&assets), &uploader, &ledger, &mut sessions)
+            .await
+            .unwrap();
+        assert_eq!(summary.uploaded, vec!["b.png"]);
+        assert_eq!(summary.reused, vec!["a.png"]);
+        assert_eq!(uploader.calls.load(Ordering::SeqCst), 3);
+        assert!(!second.contains(r#"src="a.png""#));
+        // 同步结束后不再持有台账锁
+        ContentStore::open(&ledger).unwrap();
+    }
+}
@@ -1,7 +1,21 @@
-// pub mod wechat;
-// pub mod zhihu;
+pub mod article;
+pub mod backend;
+pub mod cookies;
+pub mod images;
+pub mod queue;
+pub mod token;
 pub mod traits;
+pub mod upload;
+pub mod wechat;
+pub mod zhihu;
 
-// pub use wechat::*;
-// pub use zhihu::*;
+pub use article::*;
+pub use backend::*;
+pub use cookies::*;
+pub use images::*;
+pub use queue::*;
+pub use token::*;
 pub use traits::*;
+pub use upload::*;
+pub use wechat::*;
+pub use zhihu::*;
@@ -0,0 +1,218 @@
+use crate::{core::lock::write_atomic, error::Error, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// 批量发布进度文件，保存在输出目录中，中断后重新运行会跳过已完成的文章
+pub const QUEUE_STATE_FILE: &str = ".markflow-publish.json";
+
+/// 解析发布间隔，如 `90s`、`10m`、`1h30m`；纯数字按秒计算
+pub fn parse_interval(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let invalid = || {
+        Error::Validation(format!(
+            "无效的时间间隔: {}（示例: 30s、10m、1h30m）",
+            value
+        ))
+    };
+    if let Ok(secs) = value.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+
+    let mut total = 0u64;
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86400,
+            _ => return Err(invalid()),
+        };
+        let n: u64 = number.parse().map_err(|_| invalid())?;
+        total += n * unit;
+        number.clear();
+    }
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueItemStatus {
+    Pending,
+    Done,
+    Failed,
+}
+
+/// 队列中的一篇文章（平台输出文件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueItem {
+    /// 相对于输出目录的文件名
+    pub file: PathBuf,
+    pub status: QueueItemStatus,
+    #[serde(default)]
+    pub message: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct QueueState {
+    items: Vec<QueueItem>,
+    last_published_at: Option<DateTime<Utc>>,
+}
+
+/// 按时间间隔逐篇发布的队列，每发布一篇就把进度写回磁盘
+pub struct PublishQueue {
+    dir: PathBuf,
+    state: QueueState,
+}
+
+impl PublishQueue {
+    /// 扫描目录中的 HTML 输出文件，合并已有的进度：已完成的保持不变，新文件排在队尾
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(QUEUE_STATE_FILE);
+        let mut state: QueueState = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .map_err(|e| Error::Other(format!("无法读取发布进度 {}: {}", path.display(), e)))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => QueueState::default(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| PathBuf::from(entry.file_name()))
+            .filter(|name| name.extension().is_some_and(|ext| ext == "html"))
+            .collect();
+        files.sort();
+        // 未完成但已被删除的文件移出队列
+        state
+            .items
+            .retain(|item| item.status == QueueItemStatus::Done || files.contains(&item.file));
+        for file in files {
+            if !state.items.iter().any(|item| item.file == file) {
+                state.items.push(QueueItem {
+                    file,
+                    status: QueueItemStatus::Pending,
+                    message: None,
+                    finished_at: None,
+                });
+            }
+        }
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            state,
+        })
+    }
+
+    pub fn save(&self) -> Result<()> {
+        write_atomic(
+            &self.dir.join(QUEUE_STATE_FILE),
+            serde_json::to_string_pretty(&self.state)?,
+        )
+    }
+
+    pub fn items(&self) -> &[QueueItem] {
+        &self.state.items
+    }
+
+    pub fn path_of(&self, index: usize) -> PathBuf {
+        self.dir.join(&self.state.items[index].file)
+    }
+
+    /// 尚未成功发布的文章（含上次失败的），按队列顺序
+    pub fn remaining(&self) -> Vec<usize> {
+        (0..self.state.items.len())
+            .filter(|&i| self.state.items[i].status != QueueItemStatus::Done)
+            .collect()
+    }
+
+    /// 距离可以发布下一篇还需等待的时间，以上一次发布的时间为准（跨进程有效）
+    pub fn wait_time(&self, interval: Duration, now: DateTime<Utc>) -> Duration {
+        self.state
+            .last_published_at
+            .and_then(|last| {
+                let next = last + chrono::Duration::from_std(interval).ok()?;
+                (next - now).to_std().ok()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 记录一次发布结果；`attempted` 表示已向平台发出请求，计入发布间隔
+    pub fn finish(
+        &mut self,
+        index: usize,
+        status: QueueItemStatus,
+        message: impl Into<String>,
+        attempted: bool,
+    ) -> Result<()> {
+        let now = Utc::now();
+        let item = &mut self.state.items[index];
+        item.status = status;
+        item.message = Some(message.into());
+        item.finished_at = Some(now);
+        if attempted {
+            self.state.last_published_at = Some(now);
+        }
+        self.save()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_interval() {
+        assert_eq!(parse_interval("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_interval("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_interval("45").unwrap(), Duration::from_secs(45));
+        assert!(parse_interval("10x").is_err());
+        assert!(parse_interval("m").is_err());
+        assert!(parse_interval("1h3").is_err());
+    }
+
+    #[test]
+    fn test_queue_resumes_from_saved_state() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.html", "a.html", "notes.txt"] {
+            std::fs::write(dir.path().join(name), "").unwrap();
+        }
+
+        let mut queue = PublishQueue::load(dir.path()).unwrap();
+        assert_eq!(queue.remaining(), vec![0, 1]);
+        assert_eq!(queue.items()[0].file, PathBuf::from("a.html"));
+        assert_eq!(
+            queue.wait_time(Duration::from_secs(600), Utc::now()),
+            Duration::ZERO
+        );
+        queue
+            .finish(0, QueueItemStatus::Done, "已保存草稿", true)
+            .unwrap();
+
+        // 新增的文件排在队尾，已完成的不再发布
+        std::fs::write(dir.path().join("0.html"), "").unwrap();
+        let queue = PublishQueue::load(dir.path()).unwrap();
+        let remaining: Vec<_> = queue
+            .remaining()
+            .into_iter()
+            .map(|i| queue.items()[i].file.clone())
+            .collect();
+        assert_eq!(
+            remaining,
+            vec![PathBuf::from("b.html"), PathBuf::from("0.html")]
+        );
+        assert!(queue.wait_time(Duration::from_secs(600), Utc::now()) > Duration::from_secs(590));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{
+    cli::args::WeChatConfig,
+    core::lock::{write_atomic, FileLock},
+    error::Error,
+    publishers::traits::AuthProvider,
+    Result,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, future::Future, path::PathBuf};
+
+/// 距离过期不足该时间的 token 视为已过期，提前刷新
+const REFRESH_MARGIN_SECS: i64 = 300;
+
+const WECHAT_TOKEN_URL: &str = "https://api.weixin.qq.com/cgi-bin/token";
+
+/// 缓存的 access_token 及其过期时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedToken {
+    pub access_token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl CachedToken {
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at - Duration::seconds(REFRESH_MARGIN_SECS) > now
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    expires_in: Option<i64>,
+    errcode: Option<i64>,
+    errmsg: Option<String>,
+}
+
+/// 微信 access_token 的磁盘缓存（按 app_id 保存）。
+///
+/// 微信每天获取 token 的次数有限，多个 CLI 进程和 Web 服务通过文件锁共享同一份缓存，
+/// 只有缓存缺失或即将过期时才会请求新的 token。
+pub struct WeChatTokenCache {
+    path: PathBuf,
+}
+
+impl WeChatTokenCache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    pub fn default_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow").join("wechat_token.json")
+    }
+
+    fn load(&self) -> BTreeMap<String, CachedToken> {
+        std::fs::read_to_string(&self.path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    /// 获取有效的 access_token，必要时向微信接口请求
+    pub async fn access_token(&self, app_id: &str, app_secret: &str) -> Result<String> {
+        self.get_or_fetch(app_id, || fetch_access_token(app_id, app_secret))
+            .await
+    }
+
+    /// 缓存有效时直接返回，否则在持有锁的情况下调用 `fetch` 并写回缓存，
+    /// 同时等待的其他进程会直接读到新 token
+    pub async fn get_or_fetch<F, Fut>(&self, app_id: &str, fetch: F) -> Result<String>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<CachedToken>>,
+    {
+        let _lock = FileLock::exclusive(&self.path)?;
+        let mut tokens = self.load();
+        if let Some(token) = tokens.get(app_id).filter(|t| t.is_fresh(Utc::now())) {
+            return Ok(token.access_token.clone());
+        }
+
+        let token = fetch().await?;
+        let access_token = token.access_token.clone();
+        tokens.insert(app_id.to_string(), token);
+        write_atomic(&self.path, serde_json::to_string_pretty(&tokens)?)?;
+        Ok(access_token)
+    }
+
+    /// 接口返回 token 失效（40001/42001）时清除缓存，下次重新获取
+    pub fn invalidate(&self, app_id: &str) -> Result<()> {
+        let _lock = FileLock::exclusive(&self.path)?;
+        let mut tokens = self.load();
+        if tokens.remove(app_id).is_some() {
+            write_atomic(&self.path, serde_json::to_string_pretty(&tokens)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// 按配置获取 access_token：优先使用配置中固定的 token，否则通过共享缓存获取
+pub async fn wechat_access_token(config: &WeChatConfig) -> Result<String> {
+    let mut auth = WeChatAuth::new(config);
+    auth.authenticate().await?;
+    Ok(auth.token.unwrap_or_default())
+}
+
+/// 公众号接口的登录态：持有当前进程使用的 access_token，
+/// 获取和刷新都经过 [`WeChatTokenCache`]，与其他进程共享同一份 token
+pub struct WeChatAuth {
+    config: WeChatConfig,
+    cache: WeChatTokenCache,
+    token: Option<String>,
+}
+
+impl WeChatAuth {
+    pub fn new(config: &WeChatConfig) -> Self {
+        Self {
+            config: config.clone(),
+            cache: WeChatTokenCache::new(WeChatTokenCache::default_path()),
+            token: None,
+        }
+    }
+
+    pub fn with_cache(mut self, cache: WeChatTokenCache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    pub fn access_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// 配置中固定了 access_token 时无法刷新，失效后只能更新配置
+    pub fn can_refresh(&self) -> bool {
+        self.config.access_token.is_none()
+    }
+}
+
+#[async_trait]
+impl AuthProvider for WeChatAuth {
+    async fn authenticate(&mut self) -> Result<()> {
+        let token = match (
+            &self.config.access_token,
+            &self.config.app_id,
+            &self.config.app_secret,
+        ) {
+            (Some(token), _, _) => token.clone(),
+            (None, Some(app_id), Some(app_secret)) => {
+                self.cache.access_token(app_id, app_secret).await?
+            }
+            _ => {
+                return Err(Error::Config(
+                    "未配置微信公众号凭据（wechat.app_id / wechat.app_secret）".to_string(),
+                ))
+            }
+        };
+        self.token = Some(token);
+        Ok(())
+    }
+
+    /// 接口返回 token 失效（40001/42001）时调用：清除共享缓存并重新获取
+    async fn refresh_auth(&mut self) -> Result<()> {
+        if !self.can_refresh() {
+            return Err(Error::AuthExpired(
+                "配置中固定的 wechat.access_token 已失效，请更新或改为配置 app_id/app_secret"
+                    .to_string(),
+            ));
+        }
+        if let Some(app_id) = &self.config.app_id {
+            self.cache.invalidate(app_id)?;
+        }
+        self.token = None;
+        self.authenticate().await
+    }
+
+    fn is_authenticated(&self) -> bool {
+        self.token.is_some()
+    }
+}
+
+async fn fetch_access_token(app_id: &str, app_secret: &str) -> Result<CachedToken> {
+    let response: TokenResponse =
+        crate::core::http_client_for(crate::core::NetworkOperation::Publish)?
+            .get(WECHAT_TOKEN_URL)
+            .query(&[
+                ("grant_type", "client_credential"),
+                ("appid", app_id),
+                ("secret", app_secret),
+            ])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+    match response {
+        TokenResponse {
+            access_token: Some(access_token),
+            expires_in,
+            ..
+        } => Ok(CachedToken {
+            access_token,
+            expires_at: Utc::now() + Duration::seconds(expires_in.unwrap_or(7200)),
+        }),
+        TokenResponse {
+            errcode: Some(45009),
+            ..
+        } => Err(Error::Publishing(
+            "微信 access_token 今日获取次数已达上限（45009），请稍后再试".to_string(),
+        )),
+        TokenResponse {
+            errcode, errmsg, ..
+        } => Err(Error::Publishing(format!(
+            "获取微信 access_token 失败（{}）: {}",
+            errcode.unwrap_or_default(),
+            errmsg.unwrap_or_default()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_token_is_cached_until_expiry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = WeChatTokenCache::new(dir.path().join("token.json"));
+        let token = |value: &str, secs: i64| CachedToken {
+            access_token: value.to_string(),
+            expires_at: Utc::now() + Duration::seconds(secs),
+        };
+
+        let first = cache
+            .get_or_fetch("app", || async { Ok(token("t1", 7200)) })
+            .await
+            .unwrap();
+        assert_eq!(first, "t1");
+
+        // 另一个实例读到同一份缓存，不会再次请求
+        let other = WeChatTokenCache::new(dir.path().join("token.json"));
+        let cached = other
+            .get_or_fetch("app", || async { panic!("不应重新获取") })
+            .await
+            .unwrap();
+        assert_eq!(cached, "t1");
+
+        other.invalidate("app").unwrap();
+        let refreshed = cache
+            .get_or_fetch("app", || async { Ok(token("t2", 60)) })
+            .await
+            .unwrap();
+        assert_eq!(refreshed, "t2");
+
+        // 即将过期的 token 会被刷新
+        let renewed = cache
+            .get_or_fetch("app", || async { Ok(token("t3", 7200)) })
+            .await
+            .unwrap();
+        assert_eq!(renewed, "t3");
+    }
+
+    #[tokio::test]
+    async fn test_wechat_auth_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = || WeChatTokenCache::new(dir.path().join("token.json"));
+        cache()
+            .get_or_fetch("app", || async {
+                Ok(CachedToken {
+                    access_token: "cached".to_string(),
+                    expires_at: Utc::now() + Duration::hours(2),
+                })
+            })
+            .await
+            .unwrap();
+
+        let config = WeChatConfig {
+            app_id: Some("app".to_string()),
+            app_secret: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let mut auth = WeChatAuth::new(&config).with_cache(cache());
+        assert!(!auth.is_authenticated());
+        auth.authenticate().await.unwrap();
+        assert_eq!(auth.access_token(), Some("cached"));
+
+        let mut fixed = WeChatAuth::new(&WeChatConfig {
+            access_token: Some("fixed".to_string()),
+            ..Default::default()
+        });
+        fixed.authenticate().await.unwrap();
+        assert_eq!(fixed.access_token(), Some("fixed"));
+        assert!(matches!(
+            fixed.refresh_auth().await,
+            Err(Error::AuthExpired(_))
+        ));
+    }
+}
@@ -39,3 +39,34 @@ pub trait ConfigProvider {
 
     fn update_config(&mut self, config: Self::Config);
 }
+
+/// 平台上传图片后返回的结果
+#[derive(Debug, Clone)]
+pub struct UploadedImage {
+    pub url: String,
+    pub media_id: Option<String>,
+}
+
+/// 图片上传钩子，由各平台发布器实现（如微信 add_material）
+#[async_trait]
+pub trait ImageUploader: Send + Sync {
+    fn platform(&self) -> Platform;
+
+    async fn upload_image(&self, filename: &str, data: Vec<u8>) -> Result<UploadedImage>;
+
+    /// 平台或图床支持分片上传时返回分片接口，大文件会按分片上传并支持断点续传
+    fn chunked(&self) -> Option<&dyn ChunkedUploader> {
+        None
+    }
+}
+
+/// 分片上传接口：开始上传得到会话 ID，逐片上传后合并
+#[async_trait]
+pub trait ChunkedUploader: Send + Sync {
+    async fn begin_upload(&self, filename: &str, total_size: usize) -> Result<String>;
+
+    /// 上传第 `index` 片（从 0 开始），同一分片重复上传应覆盖之前的数据
+    async fn upload_chunk(&self, upload_id: &str, index: usize, data: &[u8]) -> Result<()>;
+
+    async fn complete_upload(&self, upload_id: &str, chunks: usize) -> Result<UploadedImage>;
+}
@@ -0,0 +1,313 @@
+use crate::{
+    cli::{args::NetworkConfig, progress},
+    core::{content::Platform, write_atomic},
+    error::Error,
+    publishers::traits::{ImageUploader, UploadedImage},
+    Result,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    future::Future,
+    path::PathBuf,
+    time::Duration,
+};
+
+/// 未完成的分片上传会话超过这段时间后不再续传（平台端的上传会话通常已失效）
+const SESSION_TTL_HOURS: i64 = 24;
+
+/// 上传大小和重试策略
+#[derive(Debug, Clone)]
+pub struct UploadOptions {
+    /// 达到该字节数且平台支持时分片上传
+    pub chunk_threshold: usize,
+    pub chunk_size: usize,
+    pub retries: u32,
+    /// 第一次重试前的等待时间，之后逐次加倍
+    pub retry_delay: Duration,
+}
+
+impl UploadOptions {
+    pub fn from_config(config: &NetworkConfig) -> Self {
+        const MB: usize = 1024 * 1024;
+        Self {
+            chunk_threshold: config.chunked_upload_threshold_mb as usize * MB,
+            chunk_size: (config.upload_chunk_size_mb as usize * MB).max(1),
+            retries: config.upload_retries,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+}
+
+/// 一次未完成的分片上传，记录已上传成功的分片以便中断后续传
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadSession {
+    pub upload_id: String,
+    pub filename: String,
+    pub total_size: usize,
+    pub chunk_size: usize,
+    pub completed: BTreeSet<usize>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 按“平台:图片哈希”保存未完成的分片上传，上传完成后删除
+pub struct UploadSessions {
+    path: Option<PathBuf>,
+    sessions: BTreeMap<String, UploadSession>,
+}
+
+impl UploadSessions {
+    pub fn default_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".markflow").join("uploads.json")
+    }
+
+    pub fn open_default() -> Result<Self> {
+        Self::open(Self::default_path())
+    }
+
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            sessions: BTreeMap::new(),
+        }
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let sessions = match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)
+                .map_err(|e| Error::Config(format!("上传会话文件解析失败 {:?}: {}", path, e)))?,
+            Err(_) => BTreeMap::new(),
+        };
+        Ok(Self {
+            path: Some(path),
+            sessions,
+        })
+    }
+
+    fn key(platform: &Platform, hash: &str) -> String {
+        format!("{}:{}", platform, hash)
+    }
+
+    pub fn get(&self, platform: &Platform, hash: &str) -> Option<&UploadSession> {
+        self.sessions.get(&Self::key(platform, hash))
+    }
+
+    pub fn save(&mut self) -> Result<()> {
+        let expires = chrono::Utc::now() - chrono::Duration::hours(SESSION_TTL_HOURS);
+        self.sessions
+            .retain(|_, session| session.started_at > expires);
+        match &self.path {
+            Some(path) => write_atomic(path, serde_json::to_string_pretty(&self.sessions)?),
+            None => Ok(()),
+        }
+    }
+}
+
+/// 失败后按 `options` 重试，重试间隔逐次加倍
+async fn with_retry<T, F, Fut>(options: &UploadOptions, action: &str, mut f: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut delay = options.retry_delay;
+    let mut attempt = 0;
+    loop {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt < options.retries => {
+                attempt += 1;
+                tracing::warn!(
+                    "{}失败（{}），{:?} 后第 {} 次重试",
+                    action,
+                    e,
+                    delay,
+                    attempt
+                );
+                tokio::time::sleep(delay).await;
+                delay *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// 上传一张图片：小文件整体上传，大文件在平台支持时分片上传。
+///
+/// 每个分片上传成功后立即写入 `sessions`，网络中断或进程退出后再次上传同一张图片
+/// （按哈希识别）只补传缺少的分片；单次请求失败按 `options` 重试
+pub async fn upload_media(
+    uploader: &dyn ImageUploader,
+    filename: &str,
+    data: Vec<u8>,
+    hash: &str,
+    sessions: &mut UploadSessions,
+    options: &UploadOptions,
+) -> Result<UploadedImage> {
+    let chunked = match uploader.chunked() {
+        Some(chunked) if data.len() >= options.chunk_threshold => chunked,
+        _ => {
+            return with_retry(options, &format!("上传 {}", filename), || {
+                uploader.upload_image(filename, data.clone())
+            })
+            .await;
+        }
+    };
+
+    let key = UploadSessions::key(&uploader.platform(), hash);
+    let resumable = sessions.sessions.get(&key).filter(|session| {
+        session.total_size == data.len() && session.chunk_size == options.chunk_size
+    });
+    let mut session = match resumable {
+        Some(session) => {
+            tracing::info!(
+                "续传 {}：已完成 {} 个分片",
+                filename,
+                session.completed.len()
+            );
+            session.clone()
+        }
+        None => {
+            let upload_id = with_retry(options, &format!("开始上传 {}", filename), || {
+                chunked.begin_upload(filename, data.len())
+            })
+            .await?;
+            UploadSession {
+                upload_id,
+                filename: filename.to_string(),
+                total_size: data.len(),
+                chunk_size: options.chunk_size,
+                completed: BTreeSet::new(),
+                started_at: chrono::Utc::now(),
+            }
+        }
+    };
+
+    let chunks: Vec<&[u8]> = data.chunks(options.chunk_size).collect();
+    let bar = progress::bar(chunks.len(), "分片上传");
+    bar.set_message(filename.to_string());
+    bar.set_position(session.completed.len() as u64);
+    for (index, chunk) in chunks.iter().enumerate() {
+        if session.completed.contains(&index) {
+            continue;
+        }
+        with_retry(
+            options,
+            &format!("上传 {} 的第 {} 个分片", filename, index + 1),
+            || chunked.upload_chunk(&session.upload_id, index, chunk),
+        )
+        .await?;
+        session.completed.insert(index);
+        sessions.sessions.insert(key.clone(), session.clone());
+        sessions.save()?;
+        bar.inc(1);
+    }
+
+    let uploaded = with_retry(options, &format!("合并 {}", filename), || {
+        chunked.complete_upload(&session.upload_id, chunks.len())
+    })
+    .await?;
+    sessions.sessions.remove(&key);
+    sessions.save()?;
+    Ok(uploaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::publishers::traits::ChunkedUploader;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// 记录收到的分片，`fail_chunks` 中的分片第一次上传时失败
+    struct FlakyUploader {
+        received: Mutex<Vec<usize>>,
+        fail_chunks: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl ImageUploader for FlakyUploader {
+        fn platform(&self) -> Platform {
+            Platform::WeChat
+        }
+
+        async fn upload_image(&self, _filename: &str, _data: Vec<u8>) -> Result<UploadedImage> {
+            unreachable!("大文件应分片上传")
+        }
+
+        fn chunked(&self) -> Option<&dyn ChunkedUploader> {
+            Some(self)
+        }
+    }
+
+    #[async_trait]
+    impl ChunkedUploader for FlakyUploader {
+        async fn begin_upload(&self, _filename: &str, _total_size: usize) -> Result<String> {
+            Ok("upload-1".to_string())
+        }
+
+        async fn upload_chunk(&self, _upload_id: &str, index: usize, _data: &[u8]) -> Result<()> {
+            let mut fail = self.fail_chunks.lock().unwrap();
+            if let Some(pos) = fail.iter().position(|&i| i == index) {
+                fail.remove(pos);
+                return Err(Error::Other("connection reset".to_string()));
+            }
+            self.received.lock().unwrap().push(index);
+            Ok(())
+        }
+
+        async fn complete_upload(&self, upload_id: &str, chunks: usize) -> Result<UploadedImage> {
+            Ok(UploadedImage {
+                url: format!("https://cdn.example.com/{}/{}", upload_id, chunks),
+                media_id: None,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chunked_upload_resumes_after_failure() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("uploads.json");
+        let uploader = FlakyUploader {
+            received: Mutex::new(Vec::new()),
+            fail_chunks: Mutex::new(vec![2]),
+        };
+        let mut options = UploadOptions {
+            chunk_threshold: 8,
+            chunk_size: 4,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+        };
+        let data = b"0123456789abcdefgh".to_vec();
+
+        // 第 3 个分片失败且不重试：前两个分片记录在会话中
+        let mut sessions = UploadSessions::open(&path).unwrap();
+        assert!(upload_media(
+            &uploader,
+            "a.gif",
+            data.clone(),
+            "h",
+            &mut sessions,
+            &options
+        )
+        .await
+        .is_err());
+        let mut sessions = UploadSessions::open(&path).unwrap();
+        let session = sessions.get(&Platform::WeChat, "h").unwrap();
+        assert_eq!(session.completed, BTreeSet::from([0, 1]));
+
+        // 再次上传只补传剩余分片，单次失败会重试
+        uploader.fail_chunks.lock().unwrap().push(3);
+        options.retries = 1;
+        let uploaded = upload_media(&uploader, "a.gif", data, "h", &mut sessions, &options)
+            .await
+            .unwrap();
+        assert_eq!(uploaded.url, "https://cdn.example.com/upload-1/5");
+        assert_eq!(*uploader.received.lock().unwrap(), vec![0, 1, 2, 3, 4]);
+        assert!(UploadSessions::open(&path)
+            .unwrap()
+            .get(&Platform::WeChat, "h")
+            .is_none());
+    }
+}
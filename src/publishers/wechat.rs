@@ -0,0 +1,636 @@
+use crate::{
+    cli::args::{AppConfig, WeChatConfig},
+    core::{
+        browser::{browser_error, BrowserPool},
+        content::{Content, Platform, PublishResult, PublishStatus},
+        http_client_for, FieldMapping, NetworkOperation,
+    },
+    error::Error,
+    publishers::{
+        backend::{PublishBackend, PublishBackendKind},
+        load_cookies,
+        traits::{AuthProvider, ImageUploader, UploadedImage},
+        WeChatAuth,
+    },
+    Result,
+};
+use async_trait::async_trait;
+use reqwest::{multipart, RequestBuilder};
+use serde_json::{json, Value};
+use std::{path::PathBuf, time::Duration};
+use thirtyfour::WebDriver;
+use tokio::sync::Mutex;
+
+const WECHAT_API_BASE: &str = "https://api.weixin.qq.com/cgi-bin";
+
+const WECHAT_MP_URL: &str = "https://mp.weixin.qq.com/";
+
+/// 草稿接口中的文本字段，取自字段映射
+const ARTICLE_TEXT_FIELDS: &[&str] = &["author", "digest", "content_source_url"];
+
+/// 通过公众号开放接口（草稿箱 + 发布能力）发布
+pub struct WeChatApiBackend {
+    config: WeChatConfig,
+    base_url: String,
+    fields: FieldMapping,
+    auth: Mutex<WeChatAuth>,
+}
+
+impl WeChatApiBackend {
+    pub fn new(config: &WeChatConfig) -> Self {
+        Self {
+            config: config.clone(),
+            base_url: WECHAT_API_BASE.to_string(),
+            fields: FieldMapping::default(),
+            auth: Mutex::new(WeChatAuth::new(config)),
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_field_mapping(mut self, fields: FieldMapping) -> Self {
+        self.fields = fields;
+        self
+    }
+
+    async fn access_token(&self) -> Result<String> {
+        let mut auth = self.auth.lock().await;
+        if !auth.is_authenticated() {
+            auth.authenticate().await?;
+        }
+        Ok(auth.access_token().unwrap_or_default().to_string())
+    }
+
+    /// 以 JSON 请求体调用接口
+    async fn call(&self, path: &str, body: Value) -> Result<Value> {
+        self.send(path, |request| Ok(request.json(&body))).await
+    }
+
+    /// 调用接口，`build` 填充请求内容；token 失效（40001/42001）时刷新 token 并重试一次
+    async fn send<F>(&self, path: &str, build: F) -> Result<Value>
+    where
+        F: Fn(RequestBuilder) -> Result<RequestBuilder> + Send + Sync,
+    {
+        let client = http_client_for(NetworkOperation::Publish)?;
+        let mut retried = false;
+        loop {
+            let token = self.access_token().await?;
+            let request = client
+                .post(format!("{}/{}", self.base_url, path))
+                .query(&[("access_token", token.as_str())]);
+            let response: Value = build(request)?
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            let errcode = response.get("errcode").and_then(Value::as_i64).unwrap_or(0);
+            match errcode {
+                0 => return Ok(response),
+                40001 | 42001 if !retried && self.config.access_token.is_none() => {
+                    self.auth.lock().await.refresh_auth().await?;
+                    retried = true;
+                }
+                _ => {
+                    return Err(Error::Publishing(format!(
+                        "微信接口 {} 返回错误（{}）: {}",
+                        path,
+                        errcode,
+                        response
+                            .get("errmsg")
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                    )))
+                }
+            }
+        }
+    }
+
+    fn article(&self, content: &Content) -> Result<Value> {
+        let fields = self.fields.resolve(&Platform::WeChat, content);
+        let thumb_media_id = fields
+            .get("thumb_media_id")
+            .or(self.config.default_thumb_media_id.as_deref())
+            .ok_or_else(|| {
+                Error::Config(
+                    "草稿缺少封面素材：请在 front matter 设置 thumb_media_id 或配置 wechat.default_thumb_media_id"
+                        .to_string(),
+                )
+            })?;
+        let mut article = json!({
+            "title": content.title,
+            "content": content.html,
+            "thumb_media_id": thumb_media_id,
+        });
+        for field in ARTICLE_TEXT_FIELDS {
+            article[*field] = json!(fields.get(field).unwrap_or_default());
+        }
+        if fields.get("author").is_none() {
+            article["author"] = json!(self.config.author.clone().unwrap_or_default());
+        }
+
+        // 开关字段（0/1）：front matter 优先，其次是 [wechat] 中的默认值
+        let flags = [
+            ("need_open_comment", self.config.open_comment),
+            ("only_fans_can_comment", self.config.fans_only_comment),
+            ("declare_original", self.config.declare_original),
+            ("can_reward", self.config.reward),
+        ];
+        for (field, default) in flags {
+            let enabled = fields.flag(field)?.unwrap_or(default);
+            article[field] = json!(u8::from(enabled));
+        }
+        if article["can_reward"] == 1 && article["declare_original"] == 0 {
+            return Err(Error::Validation(format!(
+                "《{}》开启赞赏需要同时声明原创（front matter original: true 或 wechat.declare_original）",
+                content.title
+            )));
+        }
+        Ok(article)
+    }
+}
+
+fn text_field(response: &Value, key: &str) -> Result<String> {
+    match response.get(key) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(Value::Number(n)) => Ok(n.to_string()),
+        _ => Err(Error::Publishing(format!("微信接口响应缺少 {}", key))),
+    }
+}
+
+#[async_trait]
+impl PublishBackend for WeChatApiBackend {
+    fn kind(&self) -> PublishBackendKind {
+        PublishBackendKind::Api
+    }
+
+    async fn create_draft(&self, content: &Content) -> Result<String> {
+        let response = self
+            .call("draft/add", json!({ "articles": [self.article(content)?] }))
+            .await?;
+        text_field(&response, "media_id")
+    }
+
+    async fn update_draft(&self, draft_id: &str, content: &Content) -> Result<()> {
+        self.call(
+            "draft/update",
+            json!({ "media_id": draft_id, "index": 0, "articles": self.article(content)? }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_draft(&self, draft_id: &str) -> Result<()> {
+        self.call("draft/delete", json!({ "media_id": draft_id }))
+            .await?;
+        Ok(())
+    }
+
+    async fn publish_draft(&self, draft_id: &str) -> Result<PublishResult> {
+        let response = self
+            .call("freepublish/submit", json!({ "media_id": draft_id }))
+            .await?;
+        Ok(PublishResult {
+            platform: Platform::WeChat,
+            url: None,
+            draft_id: Some(draft_id.to_string()),
+            publish_id: Some(text_field(&response, "publish_id")?),
+            status: PublishStatus::Pending,
+            message: "已提交发布，审核通过后生效".to_string(),
+            title_variant: None,
+        })
+    }
+
+    async fn get_publish_status(&self, publish_id: &str) -> Result<PublishResult> {
+        let response = self
+            .call("freepublish/get", json!({ "publish_id": publish_id }))
+            .await?;
+        let url = response
+            .pointer("/article_detail/item/0/article_url")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        // publish_status: 0 成功，1 发布中，其余为失败（原创声明失败、审核不通过、已删除等）
+        let (status, message) = match response.get("publish_status").and_then(Value::as_i64) {
+            Some(0) => (PublishStatus::Success, "发布成功".to_string()),
+            Some(1) => (PublishStatus::Pending, "发布中".to_string()),
+            Some(code) => (PublishStatus::Failed, format!("发布失败（状态 {}）", code)),
+            None => {
+                return Err(Error::Publishing(
+                    "微信接口响应缺少 publish_status".to_string(),
+                ))
+            }
+        };
+        Ok(PublishResult {
+            platform: Platform::WeChat,
+            url,
+            draft_id: None,
+            publish_id: Some(publish_id.to_string()),
+            status,
+            message,
+            title_variant: None,
+        })
+    }
+
+    fn image_uploader(&self) -> Option<&dyn ImageUploader> {
+        Some(self)
+    }
+}
+
+/// 正文图片上传为永久图片素材（`material/add_material`），返回素材 URL 和 media_id
+#[async_trait]
+impl ImageUploader for WeChatApiBackend {
+    fn platform(&self) -> Platform {
+        Platform::WeChat
+    }
+
+    async fn upload_image(&self, filename: &str, data: Vec<u8>) -> Result<UploadedImage> {
+        let mime = image_mime_type(filename);
+        let response = self
+            .send("material/add_material", |request| {
+                let part = multipart::Part::bytes(data.clone())
+                    .file_name(filename.to_string())
+                    .mime_str(mime)?;
+                Ok(request
+                    .query(&[("type", "image")])
+                    .multipart(multipart::Form::new().part("media", part)))
+            })
+            .await?;
+        Ok(UploadedImage {
+            url: text_field(&response, "url")?,
+            media_id: Some(text_field(&response, "media_id")?),
+        })
+    }
+}
+
+/// 素材接口按文件名和 Content-Type 判断图片格式（只支持 bmp/png/jpeg/jpg/gif）
+fn image_mime_type(filename: &str) -> &'static str {
+    match filename
+        .rsplit_once('.')
+        .map(|(_, extension)| extension.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("bmp") => "image/bmp",
+        _ => "image/jpeg",
+    }
+}
+
+/// 用无头浏览器登录公众号后台，在图文编辑器中保存草稿。
+///
+/// 适用于没有开放接口权限的订阅号；群发需要管理员扫码确认，只能在后台手动完成。
+pub struct WeChatBrowserBackend {
+    pool: BrowserPool,
+    cookies_file: Option<PathBuf>,
+    fields: FieldMapping,
+    /// front matter 没有作者时使用的作者名（`wechat.author`）
+    author: Option<String>,
+}
+
+impl WeChatBrowserBackend {
+    pub fn new(pool: BrowserPool, cookies_file: Option<PathBuf>) -> Self {
+        Self {
+            pool,
+            cookies_file,
+            fields: FieldMapping::default(),
+            author: None,
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut backend = Self::new(
+            BrowserPool::from_config(config),
+            config.wechat.cookies_file.clone(),
+        );
+        backend.fields = FieldMapping::from_config(&config.field_mapping);
+        backend.author = config.wechat.author.clone();
+        backend
+    }
+
+    /// 注入登录 Cookie 并打开后台，返回后台页面 URL 中的会话 token
+    async fn login(&self, driver: &WebDriver) -> Result<String> {
+        let path = self.cookies_file.as_ref().ok_or_else(|| {
+            Error::Config(
+                "browser 途径需要配置 wechat.cookies_file（公众号后台登录 Cookie）".to_string(),
+            )
+        })?;
+        let now = chrono::Utc::now();
+        let cookies = load_cookies(path)?;
+
+        driver.goto(WECHAT_MP_URL).await.map_err(browser_error)?;
+        for stored in cookies.into_iter().filter(|c| !c.is_expired(now)) {
+            let mut cookie = thirtyfour::Cookie::new(stored.name, stored.value);
+            if !stored.domain.is_empty() {
+                cookie.set_domain(stored.domain);
+            }
+            cookie.set_path("/");
+            driver.add_cookie(cookie).await.map_err(browser_error)?;
+        }
+        driver.goto(WECHAT_MP_URL).await.map_err(browser_error)?;
+
+        let url = driver.current_url().await.map_err(browser_error)?;
+        url.query_pairs()
+            .find(|(key, _)| key == "token")
+            .map(|(_, token)| token.into_owned())
+            .ok_or_else(|| {
+                Error::Publishing(format!(
+                    "公众号后台登录已失效，请重新导出 Cookie 到 {}",
+                    path.display()
+                ))
+            })
+    }
+
+    /// 打开编辑器填入内容并保存，返回草稿的 appmsgid
+    async fn save(&self, draft_id: Option<&str>, content: &Content) -> Result<String> {
+        let driver = self.pool.acquire().await?;
+        let result = async {
+            let token = self.login(&driver).await?;
+            let editor_url = match draft_id {
+                Some(id) => format!(
+                    "{}cgi-bin/appmsg?t=media/appmsg_edit&action=edit&type=77&appmsgid={}&token={}&lang=zh_CN",
+                    WECHAT_MP_URL, id, token
+                ),
+                None => format!(
+                    "{}cgi-bin/appmsg?t=media/appmsg_edit_v2&action=edit&isNew=1&type=77&token={}&lang=zh_CN",
+                    WECHAT_MP_URL, token
+                ),
+            };
+            driver.goto(&editor_url).await.map_err(browser_error)?;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+
+            let fields = self.fields.resolve(&Platform::WeChat, content);
+            driver
+                .execute(
+                    FILL_EDITOR_SCRIPT,
+                    vec![
+                        json!(content.title),
+                        json!(fields
+                            .get("author")
+                            .or(self.author.as_deref())
+                            .unwrap_or_default()),
+                        json!(fields.get("digest").unwrap_or_default()),
+                        json!(content.html),
+                    ],
+                )
+                .await
+                .map_err(browser_error)?;
+
+            // 保存后地址栏会带上 appmsgid
+            for _ in 0..20 {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                let url = driver.current_url().await.map_err(browser_error)?;
+                if let Some((_, id)) = url
+                    .query_pairs()
+                    .find(|(key, value)| key.eq_ignore_ascii_case("appmsgid") && !value.is_empty())
+                {
+                    return Ok(id.into_owned());
+                }
+            }
+            match draft_id {
+                Some(id) => Ok(id.to_string()),
+                None => Err(Error::Publishing(
+                    "保存草稿超时，请在公众号后台确认草稿状态".to_string(),
+                )),
+            }
+        }
+        .await;
+        self.pool.release(driver).await;
+        result
+    }
+}
+
+/// 填写标题、作者、摘要和正文后点击「保存为草稿」
+const FILL_EDITOR_SCRIPT: &str = r#"
+const [title, author, digest, html] = arguments;
+const setValue = (selector, value) => {
+    const el = document.querySelector(selector);
+    if (!el) return;
+    el.value = value;
+    el.dispatchEvent(new Event('input', { bubbles: true }));
+};
+setValue('#title', title);
+setValue('#author', author);
+setValue('#js_description', digest);
+const frame = document.querySelector('#ueditor_0');
+const body = frame ? frame.contentDocument.body : document.querySelector('.ProseMirror');
+if (!body) throw new Error('未找到公众号编辑器');
+body.innerHTML = html;
+body.dispatchEvent(new Event('input', { bubbles: true }));
+const save = document.querySelector('#js_submit button, #js_submit');
+if (!save) throw new Error('未找到保存按钮');
+save.click();
+"#;
+
+#[async_trait]
+impl PublishBackend for WeChatBrowserBackend {
+    fn kind(&self) -> PublishBackendKind {
+        PublishBackendKind::Browser
+    }
+
+    async fn create_draft(&self, content: &Content) -> Result<String> {
+        self.save(None, content).await
+    }
+
+    async fn update_draft(&self, draft_id: &str, content: &Content) -> Result<()> {
+        self.save(Some(draft_id), content).await.map(|_| ())
+    }
+
+    async fn delete_draft(&self, _draft_id: &str) -> Result<()> {
+        Err(Error::Publishing(
+            "browser 途径不支持删除草稿，请在公众号后台操作".to_string(),
+        ))
+    }
+
+    async fn publish_draft(&self, draft_id: &str) -> Result<PublishResult> {
+        Ok(PublishResult {
+            platform: Platform::WeChat,
+            url: None,
+            draft_id: Some(draft_id.to_string()),
+            publish_id: None,
+            status: PublishStatus::Draft,
+            message: "已保存草稿，群发需要管理员在公众号后台扫码确认".to_string(),
+            title_variant: None,
+        })
+    }
+
+    async fn get_publish_status(&self, _publish_id: &str) -> Result<PublishResult> {
+        Err(Error::Publishing(
+            "browser 途径不支持查询发布状态，请在公众号后台查看".to_string(),
+        ))
+    }
+
+    async fn shutdown(&self) {
+        self.pool.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::publishers::{backend::BackendPublisher, Publisher};
+    use axum::{extract::Query, routing::post, Json, Router};
+    use std::collections::HashMap;
+
+    async fn mock_api() -> String {
+        let app = Router::new()
+            .route(
+                "/draft/add",
+                post(
+                    |Query(query): Query<HashMap<String, String>>, Json(body): Json<Value>| async move {
+                        assert_eq!(query["access_token"], "token");
+                        let article = &body["articles"][0];
+                        assert_eq!(article["thumb_media_id"], "thumb");
+                        assert_eq!(article["digest"], "摘要");
+                        assert_eq!(article["need_open_comment"], 0);
+                        assert_eq!(article["only_fans_can_comment"], 1);
+                        assert_eq!(article["declare_original"], 1);
+                        assert_eq!(article["can_reward"], 1);
+                        assert_eq!(article["author"], "公众号作者");
+                        Json(json!({ "media_id": "draft-1" }))
+                    },
+                ),
+            )
+            .route(
+                "/freepublish/submit",
+                post(|Json(body): Json<Value>| async move {
+                    assert_eq!(body["media_id"], "draft-1");
+                    Json(json!({ "errcode": 0, "errmsg": "ok", "publish_id": 100 }))
+                }),
+            )
+            .route(
+                "/freepublish/get",
+                post(|| async {
+                    Json(json!({
+                        "publish_status": 0,
+                        "article_detail": { "count": 1, "item": [{ "idx": 1, "article_url": "https://mp.weixin.qq.com/s/abc" }] }
+                    }))
+                }),
+            )
+            .route(
+                "/material/add_material",
+                post(
+                    |Query(query): Query<HashMap<String, String>>, body: axum::body::Bytes| async move {
+                        assert_eq!(query["type"], "image");
+                        let body = String::from_utf8_lossy(&body);
+                        assert!(body.contains(r#"name="media"; filename="cover.png""#));
+                        assert!(body.contains("Content-Type: image/png"));
+                        Json(json!({ "media_id": "img-1", "url": "http://mmbiz.qpic.cn/cover" }))
+                    },
+                ),
+            )
+            .route(
+                "/draft/delete",
+                post(|| async { Json(json!({ "errcode": 40007, "errmsg": "invalid media_id" })) }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}", addr)
+    }
+
+    fn content() -> Content {
+        let mut content = Content {
+            id: uuid::Uuid::new_v4(),
+            title: "标题".to_string(),
+            markdown: String::new(),
+            html: "<p>正文</p>".to_string(),
+            metadata: Default::default(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        content.metadata.description = Some("摘要".to_string());
+        content
+            .metadata
+            .custom_fields
+            .insert("comment".to_string(), false.into());
+        content
+            .metadata
+            .custom_fields
+            .insert("reward".to_string(), "yes".into());
+        content
+    }
+
+    #[tokio::test]
+    async fn test_api_backend_publish_flow() {
+        let config = WeChatConfig {
+            access_token: Some("token".to_string()),
+            default_thumb_media_id: Some("thumb".to_string()),
+            author: Some("公众号作者".to_string()),
+            declare_original: true,
+            fans_only_comment: true,
+            ..Default::default()
+        };
+        let backend = WeChatApiBackend::new(&config).with_base_url(mock_api().await);
+        let mut publisher = BackendPublisher::new(Platform::WeChat, backend);
+
+        let submitted = publisher.publish(&content()).await.unwrap();
+        assert!(matches!(submitted.status, PublishStatus::Pending));
+        assert_eq!(submitted.draft_id.as_deref(), Some("draft-1"));
+        assert_eq!(submitted.publish_id.as_deref(), Some("100"));
+
+        let status = publisher.get_publish_status("100").await.unwrap();
+        assert!(matches!(status.status, PublishStatus::Success));
+        assert_eq!(
+            status.url.as_deref(),
+            Some("https://mp.weixin.qq.com/s/abc")
+        );
+
+        let error = publisher.delete_content("missing").await.unwrap_err();
+        assert!(error.to_string().contains("40007"));
+    }
+
+    #[tokio::test]
+    async fn test_api_backend_uploads_images() {
+        let config = WeChatConfig {
+            access_token: Some("token".to_string()),
+            ..Default::default()
+        };
+        let backend = WeChatApiBackend::new(&config).with_base_url(mock_api().await);
+        let uploader = backend.image_uploader().unwrap();
+        let uploaded = uploader
+            .upload_image("cover.png", b"\x89PNG".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(uploaded.url, "http://mmbiz.qpic.cn/cover");
+        assert_eq!(uploaded.media_id.as_deref(), Some("img-1"));
+    }
+
+    #[tokio::test]
+    async fn test_api_backend_requires_thumb_media() {
+        let config = WeChatConfig {
+            access_token: Some("token".to_string()),
+            ..Default::default()
+        };
+        let backend = WeChatApiBackend::new(&config).with_base_url(mock_api().await);
+        assert!(matches!(
+            backend.create_draft(&content()).await,
+            Err(Error::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_reward_requires_original() {
+        let config = WeChatConfig {
+            default_thumb_media_id: Some("thumb".to_string()),
+            ..Default::default()
+        };
+        let backend = WeChatApiBackend::new(&config);
+        assert!(matches!(
+            backend.article(&content()),
+            Err(Error::Validation(_))
+        ));
+
+        let mut content = content();
+        content
+            .metadata
+            .custom_fields
+            .insert("original".to_string(), true.into());
+        let article = backend.article(&content).unwrap();
+        assert_eq!(article["declare_original"], 1);
+        assert_eq!(article["can_reward"], 1);
+    }
+}
@@ -0,0 +1,631 @@
+use crate::{
+    cli::args::AppConfig,
+    core::{
+        browser::{browser_error, BrowserPool},
+        content::{Content, Platform, PublishResult, PublishStatus},
+        FieldMapping,
+    },
+    error::Error,
+    publishers::{
+        backend::{PublishBackend, PublishBackendKind},
+        save_cookies, zhihu_auth_cookies, StoredCookie, ZHIHU_AUTH_COOKIE,
+    },
+    Result,
+};
+use async_trait::async_trait;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+use thirtyfour::WebDriver;
+
+const ZHIHU_URL: &str = "https://www.zhihu.com/";
+
+const ZHUANLAN_URL: &str = "https://zhuanlan.zhihu.com";
+
+const SIGNIN_URL: &str = "https://www.zhihu.com/signin";
+
+/// 等待用户在登录窗口中完成登录的最长时间
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// 每个标签最多查看的候选话题数
+const TOPIC_CANDIDATES: usize = 5;
+
+/// 知乎专栏
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZhihuColumn {
+    pub id: String,
+    pub title: String,
+}
+
+/// 知乎话题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZhihuTopic {
+    pub id: String,
+    pub name: String,
+    raw: Value,
+}
+
+/// 接口中的 ID 可能是数字也可能是字符串
+fn id_of(value: &Value) -> Option<String> {
+    match value.get("id")? {
+        Value::String(id) => Some(id.clone()),
+        Value::Number(id) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// 解析 `column-contributions` 接口返回的可投稿专栏
+pub fn parse_columns(response: &Value) -> Vec<ZhihuColumn> {
+    response["data"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let column = item.get("column").unwrap_or(item);
+            Some(ZhihuColumn {
+                id: id_of(column)?,
+                title: column.get("title")?.as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 解析话题联想接口返回的候选话题
+pub fn parse_topics(response: &Value) -> Vec<ZhihuTopic> {
+    response
+        .as_array()
+        .or_else(|| response["data"].as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            Some(ZhihuTopic {
+                id: id_of(item)?,
+                name: item.get("name")?.as_str()?.to_string(),
+                raw: item.clone(),
+            })
+        })
+        .collect()
+}
+
+/// 按 ID 或名称（忽略大小写）在账号可投稿的专栏中查找；找不到时列出可用专栏
+pub fn match_column<'a>(target: &str, columns: &'a [ZhihuColumn]) -> Result<&'a ZhihuColumn> {
+    let target = target.trim();
+    columns
+        .iter()
+        .find(|c| c.id == target || c.title.eq_ignore_ascii_case(target))
+        .ok_or_else(|| {
+            let available = if columns.is_empty() {
+                "当前账号没有可投稿的专栏".to_string()
+            } else {
+                format!(
+                    "可投稿的专栏: {}",
+                    columns
+                        .iter()
+                        .map(|c| format!("{}（{}）", c.title, c.id))
+                        .collect::<Vec<_>>()
+                        .join("、")
+                )
+            };
+            Error::Publishing(format!(
+                "当前登录的知乎账号无法投稿到专栏「{}」，{}",
+                target, available
+            ))
+        })
+}
+
+/// 为标签挑选话题：名称完全相同（忽略大小写）优先，其次是名称互相包含的候选
+pub fn match_topic<'a>(tag: &str, candidates: &'a [ZhihuTopic]) -> Option<&'a ZhihuTopic> {
+    let tag = tag.trim().to_lowercase();
+    candidates
+        .iter()
+        .find(|t| t.name.to_lowercase() == tag)
+        .or_else(|| {
+            candidates.iter().find(|t| {
+                let name = t.name.to_lowercase();
+                name.contains(&tag) || tag.contains(&name)
+            })
+        })
+}
+
+/// 在已登录的知乎页面中调用接口，沿用页面的 Cookie 和 xsrf 令牌
+const FETCH_SCRIPT: &str = r#"
+const [method, url, body, done] = arguments;
+const xsrf = (document.cookie.match(/(?:^|;\s*)_xsrf=([^;]+)/) || [])[1] || '';
+fetch(url, {
+    method,
+    credentials: 'include',
+    headers: { 'Content-Type': 'application/json', 'x-xsrftoken': xsrf },
+    body: body === null ? undefined : JSON.stringify(body),
+})
+    .then(async (response) => done({ status: response.status, body: await response.text() }))
+    .catch((error) => done({ status: 0, body: String(error) }));
+"#;
+
+/// 用无头浏览器加载知乎登录 Cookie，在专栏编辑器页面中保存草稿、匹配话题并发布
+pub struct ZhihuBrowserBackend {
+    pool: BrowserPool,
+    cookies_file: Option<PathBuf>,
+    default_column: Option<String>,
+    fields: FieldMapping,
+    /// Cookie 失效时是否打开登录窗口重新登录（仅在终端中运行时）
+    interactive_login: bool,
+    /// 创建草稿时确定的专栏，发布时投稿到该专栏
+    columns: Mutex<HashMap<String, Option<ZhihuColumn>>>,
+}
+
+impl ZhihuBrowserBackend {
+    pub fn new(pool: BrowserPool, cookies_file: Option<PathBuf>) -> Self {
+        Self {
+            pool,
+            cookies_file,
+            default_column: None,
+            fields: FieldMapping::default(),
+            interactive_login: false,
+            columns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut backend = Self::new(
+            BrowserPool::from_config(config),
+            config.zhihu.cookies_file.clone(),
+        );
+        backend.default_column = config.zhihu.default_column.clone();
+        backend.fields = FieldMapping::from_config(&config.field_mapping);
+        backend.interactive_login = config.zhihu.interactive_login;
+        backend
+    }
+
+    fn cookies_path(&self) -> Result<&Path> {
+        self.cookies_file.as_deref().ok_or_else(|| {
+            Error::Config("知乎发布需要配置 zhihu.cookies_file（知乎登录 Cookie）".to_string())
+        })
+    }
+
+    fn can_relogin(&self) -> bool {
+        self.interactive_login && std::io::stdin().is_terminal()
+    }
+
+    /// 在打开编辑器之前检查登录 Cookie；失效时在终端中打开登录窗口刷新，否则返回 [`Error::AuthExpired`]
+    async fn ensure_cookies(&self) -> Result<Vec<StoredCookie>> {
+        let path = self.cookies_path()?;
+        match zhihu_auth_cookies(path, chrono::Utc::now()) {
+            Err(Error::AuthExpired(message)) if self.can_relogin() => {
+                tracing::warn!("{}", message);
+                self.refresh_cookies(path).await?;
+                zhihu_auth_cookies(path, chrono::Utc::now())
+            }
+            other => other,
+        }
+    }
+
+    /// 打开有界面的浏览器让用户登录知乎，登录后把 Cookie 写回文件
+    async fn refresh_cookies(&self, path: &Path) -> Result<()> {
+        let driver = self.pool.open_window().await?;
+        let result = async {
+            driver.goto(SIGNIN_URL).await.map_err(browser_error)?;
+            eprintln!(
+                "请在打开的浏览器窗口中登录知乎（{} 秒内）",
+                LOGIN_TIMEOUT.as_secs()
+            );
+
+            let started = std::time::Instant::now();
+            loop {
+                let cookies = driver.get_all_cookies().await.map_err(browser_error)?;
+                if cookies.iter().any(|c| c.name() == ZHIHU_AUTH_COOKIE) {
+                    let stored: Vec<StoredCookie> = cookies
+                        .iter()
+                        .map(|c| StoredCookie {
+                            name: c.name().to_string(),
+                            value: c.value().to_string(),
+                            domain: c.domain().unwrap_or_default().to_string(),
+                            expires: c.expires_datetime().and_then(|e| {
+                                chrono::DateTime::from_timestamp(e.unix_timestamp(), 0)
+                            }),
+                        })
+                        .collect();
+                    save_cookies(path, &stored)?;
+                    eprintln!("已登录知乎，Cookie 已保存到 {}", path.display());
+                    return Ok(());
+                }
+                if started.elapsed() > LOGIN_TIMEOUT {
+                    return Err(Error::AuthExpired("等待知乎登录超时".to_string()));
+                }
+                tokio::time::sleep(Duration::from_secs(2)).await;
+            }
+        }
+        .await;
+        let _ = driver.quit().await;
+        result
+    }
+
+    /// 注入登录 Cookie 并打开知乎首页，返回账号的 url_token
+    async fn login(&self, driver: &WebDriver, cookies: Vec<StoredCookie>) -> Result<String> {
+        driver.goto(ZHIHU_URL).await.map_err(browser_error)?;
+        for stored in cookies {
+            let mut cookie = thirtyfour::Cookie::new(stored.name, stored.value);
+            cookie.set_domain(if stored.domain.is_empty() {
+                ".zhihu.com".to_string()
+            } else {
+                stored.domain
+            });
+            cookie.set_path("/");
+            driver.add_cookie(cookie).await.map_err(browser_error)?;
+        }
+        driver.goto(ZHIHU_URL).await.map_err(browser_error)?;
+
+        // 拿不到账号信息说明 Cookie 已被服务端注销
+        let me = request(driver, "GET", "https://www.zhihu.com/api/v4/me", None)
+            .await
+            .map_err(|e| match e {
+                Error::Publishing(message) => {
+                    Error::AuthExpired(format!("知乎登录已失效，请重新导出 Cookie（{}）", message))
+                }
+                other => other,
+            })?;
+        me["url_token"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| Error::Publishing("无法获取知乎账号信息".to_string()))
+    }
+
+    /// 登录；Cookie 在服务端已失效时，终端中重新登录后再试一次
+    async fn authenticate(&self, driver: &WebDriver, cookies: Vec<StoredCookie>) -> Result<String> {
+        match self.login(driver, cookies).await {
+            Err(Error::AuthExpired(message)) if self.can_relogin() => {
+                tracing::warn!("{}", message);
+                let path = self.cookies_path()?;
+                self.refresh_cookies(path).await?;
+                let cookies = zhihu_auth_cookies(path, chrono::Utc::now())?;
+                self.login(driver, cookies).await
+            }
+            other => other,
+        }
+    }
+
+    /// 打开专栏编辑器，之后的文章接口都在专栏域名下调用
+    async fn open_editor(&self, driver: &WebDriver) -> Result<()> {
+        driver
+            .goto(&format!("{}/write", ZHUANLAN_URL))
+            .await
+            .map_err(browser_error)
+    }
+
+    /// front matter 的专栏（经字段映射）优先，其次是 `zhihu.default_column`
+    fn column_target(&self, content: &Content) -> Option<String> {
+        self.fields
+            .resolve(&Platform::Zhihu, content)
+            .get("column")
+            .map(str::to_string)
+            .or_else(|| self.default_column.clone())
+            .filter(|c| !c.trim().is_empty())
+    }
+
+    async fn resolve_column(
+        &self,
+        driver: &WebDriver,
+        url_token: &str,
+        target: &str,
+    ) -> Result<ZhihuColumn> {
+        let response = request(
+            driver,
+            "GET",
+            &format!(
+                "https://www.zhihu.com/api/v4/members/{}/column-contributions?limit=50",
+                url_token
+            ),
+            None,
+        )
+        .await?;
+        match_column(target, &parse_columns(&response)).cloned()
+    }
+
+    /// 为每个标签匹配话题并添加到文章，返回没有找到合适话题的标签。
+    /// 知乎不允许在发文时新建话题，这些标签只记录在日志中
+    async fn attach_topics(
+        &self,
+        driver: &WebDriver,
+        article_id: &str,
+        content: &Content,
+    ) -> Result<Vec<String>> {
+        let tags: Vec<String> = self
+            .fields
+            .resolve(&Platform::Zhihu, content)
+            .get("topics")
+            .map(|topics| {
+                topics
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut unmatched = Vec::new();
+        for tag in tags {
+            let response = request(
+                driver,
+                "GET",
+                &format!(
+                    "{}/api/autocomplete/topics?token={}&max_matches={}",
+                    ZHUANLAN_URL,
+                    utf8_percent_encode(&tag, NON_ALPHANUMERIC),
+                    TOPIC_CANDIDATES
+                ),
+                None,
+            )
+            .await?;
+            let candidates = parse_topics(&response);
+            match match_topic(&tag, &candidates) {
+                Some(topic) => {
+                    request(
+                        driver,
+                        "POST",
+                        &format!("{}/api/articles/{}/topics", ZHUANLAN_URL, article_id),
+                        Some(topic.raw.clone()),
+                    )
+                    .await?;
+                    tracing::debug!("标签 {} 匹配到知乎话题 {}（{}）", tag, topic.name, topic.id);
+                }
+                None => unmatched.push(tag),
+            }
+        }
+        Ok(unmatched)
+    }
+
+    async fn save(&self, draft_id: Option<&str>, content: &Content) -> Result<String> {
+        let cookies = self.ensure_cookies().await?;
+        let driver = self.pool.acquire().await?;
+        let result = async {
+            let url_token = self.authenticate(&driver, cookies).await?;
+            // 先确认专栏可以投稿，避免留下无法发布的草稿
+            let column = match self.column_target(content) {
+                Some(target) => Some(self.resolve_column(&driver, &url_token, &target).await?),
+                None => None,
+            };
+            self.open_editor(&driver).await?;
+
+            let body = json!({ "title": content.title, "content": content.html, "delta_time": 0 });
+            let article_id = match draft_id {
+                Some(id) => {
+                    request(
+                        &driver,
+                        "PATCH",
+                        &format!("{}/api/articles/{}/draft", ZHUANLAN_URL, id),
+                        Some(body),
+                    )
+                    .await?;
+                    id.to_string()
+                }
+                None => {
+                    let created = request(
+                        &driver,
+                        "POST",
+                        &format!("{}/api/articles/drafts", ZHUANLAN_URL),
+                        Some(body),
+                    )
+                    .await?;
+                    id_of(&created)
+                        .ok_or_else(|| Error::Publishing("知乎没有返回草稿 ID".to_string()))?
+                }
+            };
+
+            let unmatched = self.attach_topics(&driver, &article_id, content).await?;
+            if !unmatched.is_empty() {
+                tracing::warn!(
+                    "以下标签没有找到对应的知乎话题，请在编辑器中手动添加: {}",
+                    unmatched.join("、")
+                );
+            }
+            self.columns
+                .lock()
+                .unwrap()
+                .insert(article_id.clone(), column);
+            Ok(article_id)
+        }
+        .await;
+        self.pool.release(driver).await;
+        result
+    }
+}
+
+/// 执行 [`FETCH_SCRIPT`]，非 2xx 响应转为发布错误
+async fn request(
+    driver: &WebDriver,
+    method: &str,
+    url: &str,
+    body: Option<Value>,
+) -> Result<Value> {
+    let ret = driver
+        .execute_async(
+            FETCH_SCRIPT,
+            vec![json!(method), json!(url), body.unwrap_or(Value::Null)],
+        )
+        .await
+        .map_err(browser_error)?;
+    let response = ret.json();
+    let status = response["status"].as_u64().unwrap_or_default();
+    let text = response["body"].as_str().unwrap_or_default();
+    if status == 401 {
+        return Err(Error::AuthExpired(format!(
+            "知乎接口 {} {} 返回 {}，登录已失效，请重新导出 Cookie",
+            method, url, status
+        )));
+    }
+    if !(200..300).contains(&status) {
+        return Err(Error::Publishing(format!(
+            "知乎接口 {} {} 返回 {}: {}",
+            method, url, status, text
+        )));
+    }
+    Ok(serde_json::from_str(text).unwrap_or(Value::Null))
+}
+
+#[async_trait]
+impl PublishBackend for ZhihuBrowserBackend {
+    fn kind(&self) -> PublishBackendKind {
+        PublishBackendKind::Browser
+    }
+
+    async fn create_draft(&self, content: &Content) -> Result<String> {
+        self.save(None, content).await
+    }
+
+    async fn update_draft(&self, draft_id: &str, content: &Content) -> Result<()> {
+        self.save(Some(draft_id), content).await.map(|_| ())
+    }
+
+    async fn delete_draft(&self, draft_id: &str) -> Result<()> {
+        let cookies = self.ensure_cookies().await?;
+        let driver = self.pool.acquire().await?;
+        let result = async {
+            self.authenticate(&driver, cookies).await?;
+            self.open_editor(&driver).await?;
+            request(
+                &driver,
+                "DELETE",
+                &format!("{}/api/articles/{}", ZHUANLAN_URL, draft_id),
+                None,
+            )
+            .await
+            .map(|_| ())
+        }
+        .await;
+        self.pool.release(driver).await;
+        result
+    }
+
+    async fn publish_draft(&self, draft_id: &str) -> Result<PublishResult> {
+        let column = self
+            .columns
+            .lock()
+            .unwrap()
+            .get(draft_id)
+            .cloned()
+            .flatten();
+        let cookies = self.ensure_cookies().await?;
+        let driver = self.pool.acquire().await?;
+        let result = async {
+            self.authenticate(&driver, cookies).await?;
+            self.open_editor(&driver).await?;
+            request(
+                &driver,
+                "PUT",
+                &format!("{}/api/articles/{}/publish", ZHUANLAN_URL, draft_id),
+                Some(json!({
+                    "column": column.as_ref().map(|c| json!({ "id": c.id })),
+                    "commentPermission": "anyone",
+                })),
+            )
+            .await
+        }
+        .await;
+        self.pool.release(driver).await;
+        result?;
+
+        let message = match &column {
+            Some(column) => format!("已发布到专栏「{}」", column.title),
+            None => "已发布".to_string(),
+        };
+        Ok(PublishResult {
+            platform: Platform::Zhihu,
+            url: Some(format!("{}/p/{}", ZHUANLAN_URL, draft_id)),
+            draft_id: Some(draft_id.to_string()),
+            publish_id: Some(draft_id.to_string()),
+            status: PublishStatus::Success,
+            message,
+            title_variant: None,
+        })
+    }
+
+    async fn get_publish_status(&self, publish_id: &str) -> Result<PublishResult> {
+        Ok(PublishResult {
+            platform: Platform::Zhihu,
+            url: Some(format!("{}/p/{}", ZHUANLAN_URL, publish_id)),
+            draft_id: Some(publish_id.to_string()),
+            publish_id: Some(publish_id.to_string()),
+            status: PublishStatus::Success,
+            message: "知乎文章发布后立即可见".to_string(),
+            title_variant: None,
+        })
+    }
+
+    async fn shutdown(&self) {
+        self.pool.shutdown().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_column() {
+        let columns = parse_columns(&json!({
+            "data": [
+                { "column": { "id": "rust-notes", "title": "Rust 笔记" } },
+                { "column": { "id": "c_123", "title": "Frontend" } },
+            ]
+        }));
+        assert_eq!(columns.len(), 2);
+        assert_eq!(
+            match_column("rust-notes", &columns).unwrap().title,
+            "Rust 笔记"
+        );
+        assert_eq!(match_column("frontend", &columns).unwrap().id, "c_123");
+
+        let error = match_column("私人专栏", &columns).unwrap_err().to_string();
+        assert!(error.contains("无法投稿到专栏「私人专栏」"));
+        assert!(error.contains("Rust 笔记（rust-notes）"));
+        assert!(match_column("x", &[])
+            .unwrap_err()
+            .to_string()
+            .contains("没有可投稿的专栏"));
+    }
+
+    #[test]
+    fn test_match_topic() {
+        let topics = parse_topics(&json!([
+            { "id": 19551432, "name": "Rust（编程语言）" },
+            { "id": "19552330", "name": "rust" },
+            { "name": "缺少 ID" },
+        ]));
+        assert_eq!(topics.len(), 2);
+        assert_eq!(match_topic("Rust", &topics).unwrap().id, "19552330");
+        assert_eq!(match_topic("编程语言", &topics).unwrap().id, "19551432");
+        assert!(match_topic("Go", &topics).is_none());
+    }
+
+    #[test]
+    fn test_column_target_prefers_front_matter() {
+        let mut backend = ZhihuBrowserBackend::new(
+            BrowserPool::new(Default::default(), "http://localhost:4444"),
+            None,
+        );
+        backend.default_column = Some("默认专栏".to_string());
+        let mut content = Content {
+            id: uuid::Uuid::new_v4(),
+            title: "标题".to_string(),
+            markdown: String::new(),
+            html: String::new(),
+            metadata: Default::default(),
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        assert_eq!(backend.column_target(&content).as_deref(), Some("默认专栏"));
+        content
+            .metadata
+            .custom_fields
+            .insert("column".to_string(), "Rust 笔记".into());
+        assert_eq!(
+            backend.column_target(&content).as_deref(),
+            Some("Rust 笔记")
+        );
+    }
+}
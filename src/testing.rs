@@ -0,0 +1,352 @@
+//! 适配器的黄金文件测试工具（feature `test-utils`）。
+//!
+//! 目录中的每个 `<name>.md` 是一个用例，对应的期望输出为 `<name>.<platform>.html`。
+//! 设置环境变量 `MARKFLOW_UPDATE_GOLDEN=1` 时会用当前输出重写期望文件。
+//!
+//! ```no_run
+//! use markflow::testing::GoldenTest;
+//!
+//! GoldenTest::new("tests/fixtures/golden")
+//!     .with_adapter(Box::new(markflow::WeChatStyleAdapter::new()))
+//!     .run()
+//!     .unwrap()
+//!     .assert_passed();
+//! ```
+
+use crate::{
+    adapters::{PlatformAdapter, WeChatStyleAdapter, ZhihuStyleAdapter},
+    core::{MarkdownProcessor, PlatformReport, ProcessingPipeline},
+    error::Error,
+    Result,
+};
+use regex::Regex;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// 设置为 `1` 时用当前输出更新黄金文件
+pub const UPDATE_ENV: &str = "MARKFLOW_UPDATE_GOLDEN";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoldenStatus {
+    Passed,
+    /// 输出与黄金文件不一致，附带第一处不同的行
+    Mismatch {
+        line: usize,
+        expected: String,
+        actual: String,
+    },
+    /// 缺少黄金文件
+    Missing,
+    /// 已用当前输出更新黄金文件
+    Updated,
+}
+
+#[derive(Debug, Clone)]
+pub struct GoldenCase {
+    pub fixture: PathBuf,
+    pub golden: PathBuf,
+    pub platform: String,
+    pub status: GoldenStatus,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GoldenReport {
+    pub cases: Vec<GoldenCase>,
+}
+
+impl GoldenReport {
+    pub fn failures(&self) -> impl Iterator<Item = &GoldenCase> {
+        self.cases.iter().filter(|c| {
+            matches!(
+                c.status,
+                GoldenStatus::Mismatch { .. } | GoldenStatus::Missing
+            )
+        })
+    }
+
+    pub fn is_passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    /// 存在失败用例时 panic 并输出全部差异，供 `#[test]` 中直接调用
+    pub fn assert_passed(&self) {
+        if !self.is_passed() {
+            panic!("{}", self);
+        }
+    }
+}
+
+impl fmt::Display for GoldenReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in &self.cases {
+            match &case.status {
+                GoldenStatus::Passed => writeln!(f, "ok       {}", case.golden.display())?,
+                GoldenStatus::Updated => writeln!(f, "updated  {}", case.golden.display())?,
+                GoldenStatus::Missing => writeln!(
+                    f,
+                    "missing  {}（设置 {}=1 生成）",
+                    case.golden.display(),
+                    UPDATE_ENV
+                )?,
+                GoldenStatus::Mismatch {
+                    line,
+                    expected,
+                    actual,
+                } => writeln!(
+                    f,
+                    "mismatch {}:{}\n  expected: {}\n  actual:   {}",
+                    case.golden.display(),
+                    line,
+                    expected,
+                    actual
+                )?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 加载 Markdown 用例，经过处理器、流水线和适配器后与黄金 HTML 比较
+pub struct GoldenTest {
+    dir: PathBuf,
+    adapters: Vec<Box<dyn PlatformAdapter>>,
+    processor: MarkdownProcessor,
+    pipeline: ProcessingPipeline,
+    update: bool,
+}
+
+impl GoldenTest {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            adapters: Vec::new(),
+            processor: MarkdownProcessor::new(),
+            pipeline: ProcessingPipeline::default(),
+            update: std::env::var(UPDATE_ENV).is_ok_and(|v| v == "1"),
+        }
+    }
+
+    /// 添加要验证的适配器；未添加时使用内置的微信和知乎适配器
+    pub fn with_adapter(mut self, adapter: Box<dyn PlatformAdapter>) -> Self {
+        self.adapters.push(adapter);
+        self
+    }
+
+    pub fn with_processor(mut self, processor: MarkdownProcessor) -> Self {
+        self.processor = processor;
+        self
+    }
+
+    pub fn with_pipeline(mut self, pipeline: ProcessingPipeline) -> Self {
+        self.pipeline = pipeline;
+        self
+    }
+
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    pub fn run(self) -> Result<GoldenReport> {
+        let Self {
+            dir,
+            mut adapters,
+            processor,
+            pipeline,
+            update,
+        } = self;
+
+        if adapters.is_empty() {
+            adapters.push(Box::new(WeChatStyleAdapter::new()));
+            adapters.push(Box::new(ZhihuStyleAdapter::new()));
+        }
+
+        let mut report = GoldenReport::default();
+        for fixture in fixtures(&dir)? {
+            let markdown = std::fs::read_to_string(&fixture)?;
+            let content = processor.process(&markdown)?;
+            let content = block_on(pipeline.process(content))?;
+
+            for adapter in &adapters {
+                let platform = adapter.platform().to_string();
+                let mut platform_report = PlatformReport::new(adapter.platform());
+                let content = pipeline.process_for_platform(
+                    &content,
+                    &adapter.platform(),
+                    &mut platform_report,
+                )?;
+                let actual = normalize_html(
+                    &adapter.adapt_html_with_report(&content.html, &mut platform_report)?,
+                );
+                let golden = fixture.with_extension(format!("{}.html", platform));
+
+                let status = if update {
+                    std::fs::write(&golden, &actual)?;
+                    GoldenStatus::Updated
+                } else if !golden.exists() {
+                    GoldenStatus::Missing
+                } else {
+                    let expected = normalize_html(&std::fs::read_to_string(&golden)?);
+                    compare(&expected, &actual)
+                };
+
+                report.cases.push(GoldenCase {
+                    fixture: fixture.clone(),
+                    golden,
+                    platform,
+                    status,
+                });
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// 规范化 HTML 以忽略无关差异：统一换行、去掉行尾空白和标签之间的空白，
+/// 把 UUID 替换为占位符；每个块级开始标签另起一行，方便定位差异
+pub fn normalize_html(html: &str) -> String {
+    static BETWEEN_TAGS: OnceLock<Regex> = OnceLock::new();
+    static UUID: OnceLock<Regex> = OnceLock::new();
+    static BLOCK_START: OnceLock<Regex> = OnceLock::new();
+
+    let between_tags = BETWEEN_TAGS.get_or_init(|| Regex::new(r">\s+<").unwrap());
+    let uuid = UUID.get_or_init(|| {
+        Regex::new(r"[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}").unwrap()
+    });
+    let block_start = BLOCK_START.get_or_init(|| {
+        Regex::new(r"<((?:p|div|section|h[1-6]|ul|ol|li|blockquote|pre|table|tr|figure|hr)\b)")
+            .unwrap()
+    });
+
+    let html = html.replace("\r\n", "\n");
+    let html = between_tags.replace_all(html.trim(), "><");
+    let html = uuid.replace_all(&html, "<uuid>");
+    let html = block_start.replace_all(&html, "\n<$1");
+
+    let mut out = html
+        .lines()
+        .map(str::trim_end)
+        .filter(|l| !l.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    out.push('\n');
+    out
+}
+
+fn compare(expected: &str, actual: &str) -> GoldenStatus {
+    if expected == actual {
+        return GoldenStatus::Passed;
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            (e, a) => {
+                return GoldenStatus::Mismatch {
+                    line,
+                    expected: e.unwrap_or("<EOF>").to_string(),
+                    actual: a.unwrap_or("<EOF>").to_string(),
+                }
+            }
+        }
+    }
+}
+
+fn fixtures(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut fixtures: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| Error::Other(format!("无法读取用例目录 {:?}: {}", dir, e)))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        .collect();
+    fixtures.sort();
+    Ok(fixtures)
+}
+
+/// 流水线是异步的，这里在独立线程的运行时中驱动它，调用方无论是否处于 tokio 运行时中都可以使用
+fn block_on<F>(future: F) -> F::Output
+where
+    F: std::future::Future + Send,
+    F::Output: Send,
+{
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("创建 tokio 运行时失败")
+                    .block_on(future)
+            })
+            .join()
+            .expect("流水线线程 panic")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_normalize_html() {
+        let html = "<section>\r\n  <p>a  b</p>   <p id=\"123e4567-e89b-12d3-a456-426614174000\">c</p></section>";
+        assert_eq!(
+            normalize_html(html),
+            "<section>\n<p>a  b</p>\n<p id=\"<uuid>\">c</p></section>\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_repository_golden_files() {
+        GoldenTest::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/golden"
+        ))
+        .run()
+        .unwrap()
+        .assert_passed();
+    }
+
+    #[test]
+    fn test_missing_and_mismatch() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.md"), "# 标题\n\n正文").unwrap();
+
+        let report = GoldenTest::new(dir.path())
+            .with_adapter(Box::new(ZhihuStyleAdapter::new()))
+            .with_update(false)
+            .run()
+            .unwrap();
+        assert_eq!(report.cases[0].status, GoldenStatus::Missing);
+        assert!(!report.is_passed());
+
+        GoldenTest::new(dir.path())
+            .with_adapter(Box::new(ZhihuStyleAdapter::new()))
+            .with_update(true)
+            .run()
+            .unwrap();
+        let golden = dir.path().join("a.zhihu.html");
+        let edited = std::fs::read_to_string(&golden)
+            .unwrap()
+            .replace("正文", "修改");
+        std::fs::write(&golden, edited).unwrap();
+
+        let report = GoldenTest::new(dir.path())
+            .with_adapter(Box::new(ZhihuStyleAdapter::new()))
+            .with_update(false)
+            .run()
+            .unwrap();
+        assert!(matches!(
+            report.cases[0].status,
+            GoldenStatus::Mismatch { .. }
+        ));
+        assert!(report.to_string().contains("mismatch"));
+    }
+}
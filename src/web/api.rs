@@ -0,0 +1,1231 @@
+use crate::{
+    adapters::create_adapter,
+    core::{
+        content_hash, ensure_online, sanitize_filename, ArticleReport, ContentRecord,
+        MarkdownProcessor, Platform, PlatformReport, ProcessingPipeline, PublishResult, Role,
+        SourceMap, WorkflowState,
+    },
+    error::Error,
+    publishers::{publish_record, record_publish_result},
+    web::{
+        extract::{ApiError, WorkspaceContext},
+        jobs::{
+            adapt_for_platform, resolve_platforms, JobSnapshot, ProcessJobRequest,
+            PublishJobRequest,
+        },
+        preview::{PreviewClaims, DEFAULT_PREVIEW_TTL_MINUTES, MAX_PREVIEW_TTL_MINUTES},
+        server::AppState,
+    },
+};
+use axum::{
+    body::Body,
+    extract::{multipart::MultipartError, FromRequest, Multipart, Path, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    Json,
+};
+use http_body_util::Limited;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::Infallible,
+    path::Component,
+};
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+pub struct WorkspaceInfo {
+    pub name: String,
+    pub contents: usize,
+}
+
+/// 当前工作区信息
+pub async fn workspace_info(
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+) -> Result<Json<WorkspaceInfo>, ApiError> {
+    let store = workspace.open_store()?;
+    Ok(Json(WorkspaceInfo {
+        name: workspace.name().to_string(),
+        contents: store.records().len(),
+    }))
+}
+
+/// 当前工作区内容台账中的全部文章
+pub async fn list_contents(
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+) -> Result<Json<Vec<ContentRecord>>, ApiError> {
+    let store = workspace.open_store()?;
+    Ok(Json(store.records().to_vec()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkflowRequest {
+    pub state: String,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// 变更文章的审核状态，批准需要 publisher 角色，其余变更需要 editor 角色
+pub async fn transition_workflow(
+    context: WorkspaceContext,
+    Path(params): Path<HashMap<String, String>>,
+    Json(request): Json<WorkflowRequest>,
+) -> Result<Json<ContentRecord>, ApiError> {
+    let state = request
+        .state
+        .parse::<WorkflowState>()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    context.require(match state {
+        WorkflowState::Approved | WorkflowState::Published => Role::Publisher,
+        _ => Role::Editor,
+    })?;
+    let content_id = params
+        .get("id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| ApiError::bad_request("无效的内容 ID"))?;
+
+    let mut store = context.workspace.open_store()?;
+    let record = store
+        .get_mut(&content_id)
+        .ok_or_else(|| ApiError::not_found(format!("内容不存在: {}", content_id)))?;
+    let by = context
+        .actor
+        .clone()
+        .unwrap_or_else(|| context.role.to_string());
+    record
+        .workflow
+        .transition(state, Some(by), request.note)
+        .map_err(|e| ApiError::conflict(e.to_string()))?;
+    let record = record.clone();
+    store.save()?;
+    Ok(Json(record))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PublishRequest {
+    pub platform: String,
+    /// 只保存草稿，不群发或公开发布
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// 把台账中的文章发布到平台。保存草稿需要 editor 角色，公众号群发、知乎发布等
+/// 正式发布需要 publisher 角色；发布结果记入工作区台账
+pub async fn publish_content(
+    context: WorkspaceContext,
+    Path(params): Path<HashMap<String, String>>,
+    Json(request): Json<PublishRequest>,
+) -> Result<Json<PublishResult>, ApiError> {
+    context.require(if request.draft {
+        Role::Editor
+    } else {
+        Role::Publisher
+    })?;
+    let content_id = params
+        .get("id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| ApiError::bad_request("无效的内容 ID"))?;
+    let platform = request
+        .platform
+        .parse::<Platform>()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    ensure_online("发布内容").map_err(|e| ApiError::conflict(e.to_string()))?;
+
+    let workspace = context.workspace;
+    let config = workspace.load_config()?;
+    let ledger = workspace.ledger_path();
+    // 台账只在读取和记录结果时短暂打开，发布请求期间不持有台账锁
+    let record = workspace
+        .open_store()?
+        .get(&content_id)
+        .cloned()
+        .ok_or_else(|| ApiError::not_found(format!("内容不存在: {}", content_id)))?;
+    let result = publish_record(&record, &platform, &config, request.draft, &ledger).await?;
+    record_publish_result(&ledger, &record.id, result.clone(), &config)?;
+    Ok(Json(result))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreviewLinkRequest {
+    pub platform: String,
+    #[serde(default)]
+    pub ttl_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PreviewLink {
+    pub url: String,
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// 为文章生成有时效的只读预览链接，审阅者无需账号即可在手机上查看
+pub async fn create_preview_link(
+    State(state): State<AppState>,
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+    Path(params): Path<HashMap<String, String>>,
+    headers: HeaderMap,
+    Json(request): Json<PreviewLinkRequest>,
+) -> Result<Json<PreviewLink>, ApiError> {
+    let content_id = params
+        .get("id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| ApiError::bad_request("无效的内容 ID"))?;
+    let platform = request
+        .platform
+        .parse::<crate::core::Platform>()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?
+        .to_string();
+
+    {
+        let store = workspace.open_store()?;
+        let record = store
+            .get(&content_id)
+            .ok_or_else(|| ApiError::not_found(format!("内容不存在: {}", content_id)))?;
+        if !record.html.contains_key(&platform) {
+            return Err(ApiError::not_found(format!(
+                "《{}》没有 {} 平台的输出",
+                record.title, platform
+            )));
+        }
+    }
+
+    let ttl = request
+        .ttl_minutes
+        .unwrap_or(DEFAULT_PREVIEW_TTL_MINUTES)
+        .clamp(1, MAX_PREVIEW_TTL_MINUTES);
+    let expires_at = chrono::Utc::now() + chrono::Duration::minutes(ttl);
+    let token = state.preview_signer()?.sign(&PreviewClaims {
+        workspace: workspace.name().to_string(),
+        content_id,
+        platform,
+        expires_at: expires_at.timestamp(),
+    })?;
+
+    let path = format!("/p/{}", token);
+    let url = match headers.get(header::HOST).and_then(|h| h.to_str().ok()) {
+        Some(host) => {
+            let scheme = headers
+                .get("x-forwarded-proto")
+                .and_then(|h| h.to_str().ok())
+                .unwrap_or("http");
+            format!("{}://{}{}", scheme, host, path)
+        }
+        None => path,
+    };
+
+    Ok(Json(PreviewLink { url, expires_at }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenderRequest {
+    pub markdown: String,
+    /// 平台名；为空时返回未经平台适配的 HTML
+    #[serde(default)]
+    pub platform: Option<String>,
+    /// 同时返回 HTML 元素到 Markdown 行号的映射，仅支持未经平台适配的 HTML
+    #[serde(default)]
+    pub source_map: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenderResponse {
+    pub title: String,
+    pub html: String,
+    /// 复用缓存的块数
+    pub cached_blocks: usize,
+    /// 重新渲染的块数
+    pub rendered_blocks: usize,
+    pub elapsed_ms: u128,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_map: Option<SourceMap>,
+}
+
+/// 实时编辑器预览：按段落缓存渲染结果，每次按键只重新渲染改动过的块
+pub async fn render_preview(
+    State(state): State<AppState>,
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+    Json(request): Json<RenderRequest>,
+) -> Result<Json<RenderResponse>, ApiError> {
+    let started = std::time::Instant::now();
+    let config = workspace.load_config()?;
+    let platform = request
+        .platform
+        .as_deref()
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse::<Platform>())
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let processor = MarkdownProcessor::new()
+        .with_timezone(config.timezone())
+        .with_autolink(config.links.autolink)
+        .with_smart_punctuation(config.smart_punctuation())
+        .with_math(config.math.enabled)
+        .with_permalink(config.permalink());
+
+    if request.source_map {
+        if platform.is_some() {
+            return Err(ApiError::bad_request(
+                "source_map 只支持未经平台适配的 HTML，请去掉 platform",
+            ));
+        }
+        // 带定位属性的渲染结果不能进入块缓存
+        let (content, source_map) = processor.process_with_source_map(&request.markdown)?;
+        return Ok(Json(RenderResponse {
+            title: content.title,
+            html: content.html,
+            cached_blocks: 0,
+            rendered_blocks: 0,
+            elapsed_ms: started.elapsed().as_millis(),
+            source_map: Some(source_map),
+        }));
+    }
+
+    let (content, cached_blocks, rendered_blocks) =
+        state.with_render_cache(workspace.name(), |cache| {
+            processor
+                .process_cached(&request.markdown, cache)
+                .map(|content| (content, cache.hits(), cache.misses()))
+        })?;
+
+    let html = match platform {
+        Some(platform) => {
+            let adapter = create_adapter(&platform, &config)?;
+            let mut report = PlatformReport::new(adapter.platform());
+            let content = ProcessingPipeline::default().process_for_platform(
+                &content,
+                &platform,
+                &mut report,
+            )?;
+            state.with_adapter_cache(|cache| {
+                cache.adapt(adapter.as_ref(), &content.html, &mut report)
+            })?
+        }
+        None => content.html,
+    };
+
+    Ok(Json(RenderResponse {
+        title: content.title,
+        html,
+        cached_blocks,
+        rendered_blocks,
+        elapsed_ms: started.elapsed().as_millis(),
+        source_map: None,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProcessRequest {
+    pub markdown: String,
+    /// 目标平台；为空时处理全部平台
+    #[serde(default)]
+    pub platforms: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcessResponse {
+    pub title: String,
+    /// 平台名 -> 适配后的 HTML
+    pub outputs: BTreeMap<String, String>,
+    pub report: ArticleReport,
+}
+
+/// 同步处理一篇 Markdown，按工作区配置跑完整流水线并返回各平台 HTML，不写入任何文件；
+/// 结果作为工作区最近处理的文档，可以在 `/preview/{platform}` 查看
+pub async fn process_document(
+    State(state): State<AppState>,
+    context: WorkspaceContext,
+    Json(request): Json<ProcessRequest>,
+) -> Result<Json<ProcessResponse>, ApiError> {
+    context.require(Role::Editor)?;
+    let workspace = context.workspace;
+    let config = workspace.load_config()?;
+    let platforms =
+        resolve_platforms(&request.platforms).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let processor = MarkdownProcessor::new()
+        .with_timezone(config.timezone())
+        .with_autolink(config.links.autolink)
+        .with_smart_punctuation(config.smart_punctuation())
+        .with_math(config.math.enabled)
+        .with_permalink(config.permalink());
+    let pipeline = ProcessingPipeline::from_config(&config)?;
+    let content = pipeline
+        .process(processor.process(&request.markdown)?)
+        .await?;
+
+    let mut report = ArticleReport::new(&content.title, None);
+    let mut outputs = BTreeMap::new();
+    for platform in &platforms {
+        let (html, platform_report) = state
+            .with_adapter_cache(|cache| {
+                adapt_for_platform(&config, &pipeline, &content, platform, cache)
+            })
+            .map_err(|e| match e {
+                Error::Validation(message) => {
+                    ApiError::new(StatusCode::UNPROCESSABLE_ENTITY, message)
+                }
+                e => e.into(),
+            })?;
+        outputs.insert(platform.to_string(), html);
+        report.platforms.push(platform_report);
+    }
+
+    state.set_last_processed(workspace.name(), outputs.clone());
+    Ok(Json(ProcessResponse {
+        title: content.title,
+        outputs,
+        report,
+    }))
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobCreated {
+    pub id: Uuid,
+    pub events_url: String,
+}
+
+/// 在后台处理一批 Markdown 文档，进度通过 `/jobs/{id}/events` 推送
+pub async fn start_process_job(
+    State(state): State<AppState>,
+    context: WorkspaceContext,
+    Json(request): Json<ProcessJobRequest>,
+) -> Result<(StatusCode, Json<JobCreated>), ApiError> {
+    context.require(Role::Editor)?;
+    let workspace = context.workspace;
+    if request.documents.is_empty() {
+        return Err(ApiError::bad_request("documents 不能为空"));
+    }
+    let id = state
+        .jobs()
+        .spawn_process(&workspace, request)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(JobCreated {
+            id,
+            events_url: format!("jobs/{}/events", id),
+        }),
+    ))
+}
+
+/// 在后台发布台账中的文章，`publish_at` 指定定时发布；任务保存在任务表中，服务重启后照常执行。
+/// 角色要求与 `/contents/{id}/publish` 相同
+pub async fn start_publish_job(
+    State(state): State<AppState>,
+    context: WorkspaceContext,
+    Json(request): Json<PublishJobRequest>,
+) -> Result<(StatusCode, Json<JobCreated>), ApiError> {
+    context.require(if request.draft {
+        Role::Editor
+    } else {
+        Role::Publisher
+    })?;
+    let workspace = context.workspace;
+    if workspace.open_store()?.get(&request.content_id).is_none() {
+        return Err(ApiError::not_found(format!(
+            "内容不存在: {}",
+            request.content_id
+        )));
+    }
+    let id = state
+        .jobs()
+        .spawn_publish(&workspace, request)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(JobCreated {
+            id,
+            events_url: format!("jobs/{}/events", id),
+        }),
+    ))
+}
+
+/// 任务状态和已完成文档的结果
+pub async fn get_job(
+    State(state): State<AppState>,
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+    Path(params): Path<HashMap<String, String>>,
+) -> Result<Json<JobSnapshot>, ApiError> {
+    let id = job_id(&params)?;
+    state
+        .jobs()
+        .snapshot(&id)?
+        .filter(|job| job.workspace == workspace.name())
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found(format!("任务不存在: {}", id)))
+}
+
+/// 以 SSE 推送任务进度：先补发已发生的事件，任务结束后关闭连接
+pub async fn job_events(
+    State(state): State<AppState>,
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+    Path(params): Path<HashMap<String, String>>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let id = job_id(&params)?;
+    let in_workspace = state
+        .jobs()
+        .snapshot(&id)?
+        .is_some_and(|job| job.workspace == workspace.name());
+    let (history, receiver) = state
+        .jobs()
+        .subscribe(&id)?
+        .filter(|_| in_workspace)
+        .ok_or_else(|| ApiError::not_found(format!("任务不存在: {}", id)))?;
+
+    // 因消费过慢而被跳过的事件直接丢弃，后续事件仍会继续推送
+    let live = BroadcastStream::new(receiver).filter_map(|event| event.ok());
+
+    let stream = tokio_stream::iter(history).chain(live).map(|event| {
+        Ok(Event::default()
+            .event(event.name())
+            .json_data(&event)
+            .unwrap_or_default())
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
+
+/// 以 SSE 推送当前工作区的主题修改（`theme_changed`），预览页面收到后重新请求 `/render`
+pub async fn theme_events(
+    State(state): State<AppState>,
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let name = workspace.name().to_string();
+    let stream = BroadcastStream::new(state.subscribe_theme_events())
+        .filter_map(move |event| event.ok().filter(|event| event.workspace == name))
+        .map(|event| {
+            Ok(Event::default()
+                .event("theme_changed")
+                .json_data(&event)
+                .unwrap_or_default())
+        });
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+fn job_id(params: &HashMap<String, String>) -> Result<Uuid, ApiError> {
+    params
+        .get("id")
+        .and_then(|id| Uuid::parse_str(id).ok())
+        .ok_or_else(|| ApiError::bad_request("无效的任务 ID"))
+}
+
+/// 单次上传请求的总大小上限为 `assets.max_size_mb` 的倍数，允许一次上传多个文件
+const ASSET_FILES_PER_REQUEST: usize = 10;
+
+#[derive(Debug, Serialize)]
+pub struct UploadedAsset {
+    pub name: String,
+    pub url: String,
+    /// 可直接插入编辑器的 Markdown 片段
+    pub markdown: String,
+    pub size: usize,
+    pub hash: String,
+}
+
+/// 上传图片等资源（multipart，可一次上传多个文件）。
+///
+/// 配置了图片上传器时图片直接转发到图床；其余文件按内容哈希命名保存到工作区的资源目录。
+/// 文件边读边检查大小，超过 `assets.max_size_mb` 时立即返回 413，不会把整个请求读入内存
+pub async fn upload_assets(
+    State(state): State<AppState>,
+    context: WorkspaceContext,
+    request: Request,
+) -> Result<Json<Vec<UploadedAsset>>, ApiError> {
+    context.require(Role::Editor)?;
+    let workspace = context.workspace;
+    let config = workspace.load_config()?;
+    let assets_dir = workspace.assets_dir(&config);
+    let max_bytes = config.assets.max_size_mb as usize * 1024 * 1024;
+
+    let request = request.map(|body| {
+        Body::new(Limited::new(
+            body,
+            max_bytes.saturating_mul(ASSET_FILES_PER_REQUEST),
+        ))
+    });
+    let mut multipart = Multipart::from_request(request, &state)
+        .await
+        .map_err(|e| ApiError::bad_request(format!("无效的上传请求: {}", e)))?;
+
+    let mut uploaded = Vec::new();
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| multipart_error("无效的上传请求", e))?
+    {
+        let Some(original_name) = field.file_name().map(str::to_string) else {
+            continue;
+        };
+        let name = sanitize_filename(&original_name);
+        let extension = std::path::Path::new(&name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .unwrap_or_default();
+        if !config
+            .assets
+            .allowed_extensions
+            .iter()
+            .any(|allowed| allowed.eq_ignore_ascii_case(&extension))
+        {
+            return Err(ApiError::bad_request(format!(
+                "不支持的文件类型: {}",
+                original_name
+            )));
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field
+            .chunk()
+            .await
+            .map_err(|e| multipart_error("读取上传文件失败", e))?
+        {
+            if data.len() + chunk.len() > max_bytes {
+                return Err(ApiError::new(
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    format!(
+                        "文件超过 {}MB: {}",
+                        config.assets.max_size_mb, original_name
+                    ),
+                ));
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        let hash = content_hash(&data);
+        let size = data.len();
+        let is_image = is_image_extension(&extension);
+
+        let url = match state.image_uploader() {
+            Some(uploader) if is_image => uploader.upload_image(&name, data).await?.url,
+            _ => {
+                let relative = format!("{}-{}", &hash[..12], name);
+                let path = assets_dir.join(&relative);
+                if !path.exists() {
+                    tokio::fs::create_dir_all(&assets_dir)
+                        .await
+                        .map_err(crate::error::Error::from)?;
+                    tokio::fs::write(&path, &data)
+                        .await
+                        .map_err(crate::error::Error::from)?;
+                }
+                match &config.assets.base_url {
+                    Some(base) => format!("{}/{}", base.trim_end_matches('/'), relative),
+                    None => format!("/w/{}/assets/{}", workspace.name(), relative),
+                }
+            }
+        };
+
+        let markdown = if is_image {
+            format!("![{}]({})", name, url)
+        } else {
+            format!("[{}]({})", name, url)
+        };
+        uploaded.push(UploadedAsset {
+            name,
+            url,
+            markdown,
+            size,
+            hash,
+        });
+    }
+
+    if uploaded.is_empty() {
+        return Err(ApiError::bad_request("请求中没有文件"));
+    }
+    Ok(Json(uploaded))
+}
+
+/// 请求体超过大小上限时返回 413，其他解析错误返回 400
+fn multipart_error(context: &str, e: MultipartError) -> ApiError {
+    let status = match e.status() {
+        StatusCode::PAYLOAD_TOO_LARGE => StatusCode::PAYLOAD_TOO_LARGE,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    ApiError::new(status, format!("{}: {}", context, e.body_text()))
+}
+
+/// 读取已上传的资源。文件名包含内容哈希，不要求访问令牌，便于预览页直接引用
+pub async fn serve_asset(
+    State(state): State<AppState>,
+    Path((workspace, path)): Path<(String, String)>,
+) -> Result<impl IntoResponse, ApiError> {
+    let workspace = state
+        .workspaces()
+        .get(&workspace)
+        .map_err(|e| ApiError::not_found(e.to_string()))?;
+    let relative = std::path::Path::new(&path);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(ApiError::bad_request("无效的资源路径"));
+    }
+
+    let config = workspace.load_config()?;
+    let file = workspace.assets_dir(&config).join(relative);
+    let data = tokio::fs::read(&file)
+        .await
+        .map_err(|_| ApiError::not_found(format!("资源不存在: {}", path)))?;
+
+    let extension = relative
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type(&extension)),
+            // 上传的 SVG 可能带脚本，禁止其在本站点上下文中执行
+            (
+                header::CONTENT_SECURITY_POLICY,
+                "default-src 'none'; style-src 'unsafe-inline'",
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        data,
+    ))
+}
+
+fn is_image_extension(extension: &str) -> bool {
+    matches!(extension, "png" | "jpg" | "jpeg" | "gif" | "webp" | "svg")
+}
+
+fn content_type(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cli::args::AppConfig,
+        core::{content_hash, ContentRecord, Role, WorkspaceManager},
+        web::{create_router, AppState},
+    };
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, Request, StatusCode},
+    };
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    fn record(title: &str) -> ContentRecord {
+        ContentRecord {
+            id: uuid::Uuid::new_v4(),
+            title: title.to_string(),
+            source: None,
+            content_hash: content_hash(b"a"),
+            outputs: Default::default(),
+            html: Default::default(),
+            publications: Vec::new(),
+            review_after: None,
+            title_variants: Default::default(),
+            tags: Vec::new(),
+            slug: None,
+            canonical_url: None,
+            workflow: Default::default(),
+            report: None,
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn get(
+        state: &AppState,
+        uri: &str,
+        token: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::get(uri);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let response = create_router(state.clone(), None)
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    async fn post_json(
+        state: &AppState,
+        uri: &str,
+        token: &str,
+        body: &str,
+    ) -> (StatusCode, serde_json::Value) {
+        let request = Request::post(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = create_router(state.clone(), None)
+            .oneshot(request)
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_workspaces_are_isolated() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+        let mut alice = manager.create("alice").unwrap();
+        alice.add_api_key("token-a", Role::Viewer, None).unwrap();
+        manager
+            .create("bob")
+            .unwrap()
+            .add_api_key("token-b", Role::Viewer, None)
+            .unwrap();
+        manager.create("carol").unwrap();
+
+        {
+            let mut store = alice.open_store().unwrap();
+            store.upsert(record("Alice 的草稿"));
+            store.save().unwrap();
+        }
+
+        let state = AppState::new(dir.path());
+
+        // 按令牌定位工作区
+        let (status, body) = get(&state, "/api/contents", Some("token-a")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["title"], "Alice 的草稿");
+
+        // 按路径访问受保护的工作区必须携带令牌
+        let (status, _) = get(&state, "/w/alice/api/contents", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = get(&state, "/w/alice/api/workspace", Some("token-a")).await;
+        assert_eq!(status, StatusCode::OK);
+        // 新建的工作区没有密钥时不对匿名请求开放
+        let (status, _) = get(&state, "/w/carol/api/contents", None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+        // 其他工作区看不到 Alice 的内容
+        let (status, body) = get(&state, "/w/bob/api/workspace", Some("token-b")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["contents"], 0);
+
+        let (status, _) = get(&state, "/api/contents", Some("wrong")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, _) = get(&state, "/w/missing/api/workspace", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_upload_and_serve_asset() {
+        let dir = TempDir::new().unwrap();
+        let state = AppState::new(dir.path());
+
+        let boundary = "markflow-boundary";
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"cover.png\"\r\nContent-Type: image/png\r\n\r\nPNGDATA\r\n--{b}--\r\n",
+            b = boundary
+        );
+        let response = create_router(state.clone(), None)
+            .oneshot(
+                Request::post("/api/assets")
+                    .header(
+                        header::CONTENT_TYPE,
+                        format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let uploaded: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let url = uploaded[0]["url"].as_str().unwrap();
+        assert!(url.starts_with("/w/default/assets/"));
+        assert!(url.ends_with("-cover.png"));
+        assert_eq!(uploaded[0]["markdown"], format!("![cover.png]({})", url));
+
+        let response = create_router(state.clone(), None)
+            .oneshot(Request::get(url).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"PNGDATA");
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_oversized_file() {
+        let dir = TempDir::new().unwrap();
+        let mut config = AppConfig::default();
+        config.assets.max_size_mb = 1;
+        config
+            .save_to_file(&dir.path().join("config.toml"))
+            .unwrap();
+        let state = AppState::new(dir.path());
+
+        let boundary = "markflow-boundary";
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"big.png\"\r\nContent-Type: image/png\r\n\r\n{data}\r\n--{b}--\r\n",
+            b = boundary,
+            data = "x".repeat(2 * 1024 * 1024)
+        );
+        let response = create_router(state, None)
+            .oneshot(
+                Request::post("/api/assets")
+                    .header(
+                        header::CONTENT_TYPE,
+                        format!("multipart/form-data; boundary={}", boundary),
+                    )
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn test_process_job_streams_progress() {
+        let dir = TempDir::new().unwrap();
+        let state = AppState::new(dir.path());
+
+        let request = serde_json::json!({
+            "documents": [
+                { "name": "a.md", "markdown": "# 标题A\n\n正文" },
+                { "name": "b.md", "markdown": "# 标题B\n\n正文" }
+            ],
+            "platforms": ["wechat"]
+        });
+        let response = create_router(state.clone(), None)
+            .oneshot(
+                Request::post("/api/jobs/process")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(request.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let id = created["id"].as_str().unwrap();
+
+        // 事件流在任务结束后关闭，因此可以读取完整响应
+        let response = create_router(state.clone(), None)
+            .oneshot(
+                Request::get(format!("/api/jobs/{}/events", id))
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let events = String::from_utf8(body.to_vec()).unwrap();
+        assert!(events.contains("event: started"));
+        assert!(events.contains("adapt:wechat"));
+        assert_eq!(events.matches("event: document_finished").count(), 2);
+        assert!(events.contains("event: completed"));
+
+        let (status, job) = get(&state, &format!("/api/jobs/{}", id), None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(job["status"], "completed");
+        assert_eq!(job["results"][1]["title"], "标题B");
+        assert!(job["results"][0]["outputs"]["wechat"]
+            .as_str()
+            .unwrap()
+            .contains("标题A"));
+    }
+
+    #[tokio::test]
+    async fn test_preview_link() {
+        let dir = TempDir::new().unwrap();
+        let state = AppState::new(dir.path());
+
+        let id = {
+            let workspace = state.default_workspace().unwrap();
+            let mut store = workspace.open_store().unwrap();
+            let id = store.upsert(ContentRecord {
+                html: [(
+                    "wechat".to_string(),
+                    "<section>微信预览内容</section>".to_string(),
+                )]
+                .into_iter()
+                .collect(),
+                ..record("预览文章")
+            });
+            store.save().unwrap();
+            id
+        };
+
+        let response = create_router(state.clone(), None)
+            .oneshot(
+                Request::post(format!("/api/contents/{}/preview-link", id))
+                    .header(header::HOST, "markflow.example.com")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(r#"{"platform":"wechat","ttl_minutes":30}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let link: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let url = link["url"].as_str().unwrap();
+        let path = url.strip_prefix("http://markflow.example.com").unwrap();
+
+        let response = create_router(state.clone(), None)
+            .oneshot(Request::get(path).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let html = String::from_utf8(body.to_vec()).unwrap();
+        assert!(html.contains("微信预览内容"));
+        assert!(html.contains("viewport"));
+
+        let response = create_router(state, None)
+            .oneshot(Request::get("/p/forged.token").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_roles_limit_actions() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+        let mut team = manager.create("team").unwrap();
+        team.add_api_key("intern", Role::Viewer, Some("实习生".to_string()))
+            .unwrap();
+        team.add_api_key("writer", Role::Editor, None).unwrap();
+        let state = AppState::new(dir.path());
+
+        let post = |token: &'static str| {
+            Request::post("/api/jobs/process")
+                .header(header::AUTHORIZATION, format!("Bearer {}", token))
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    r##"{"documents":[{"name":"a.md","markdown":"# A"}]}"##,
+                ))
+                .unwrap()
+        };
+
+        // viewer 可以查看但不能处理
+        let (status, _) = get(&state, "/api/contents", Some("intern")).await;
+        assert_eq!(status, StatusCode::OK);
+        let response = create_router(state.clone(), None)
+            .oneshot(post("intern"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let document = r##"{"markdown":"# A","platforms":["wechat"]}"##;
+        let (status, _) = post_json(&state, "/api/process", "intern", document).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = post_json(&state, "/api/process", "writer", document).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let response = create_router(state.clone(), None)
+            .oneshot(post("writer"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+    }
+
+    #[tokio::test]
+    async fn test_publish_requires_publisher() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+        let mut team = manager.create("team").unwrap();
+        team.add_api_key("writer", Role::Editor, None).unwrap();
+        team.add_api_key("lead", Role::Publisher, None).unwrap();
+        let state = AppState::new(dir.path());
+        let uri = format!("/api/contents/{}/publish", uuid::Uuid::new_v4());
+
+        // editor 只能保存草稿；未配置密钥的 default 工作区按 editor 处理
+        let (status, _) = post_json(&state, &uri, "writer", r#"{"platform":"wechat"}"#).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let open_uri = format!("/w/default{}", uri);
+        let (status, _) = post_json(&state, &open_uri, "", r#"{"platform":"wechat"}"#).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // 通过角色检查后才查找文章
+        let draft = r#"{"platform":"wechat","draft":true}"#;
+        let (status, _) = post_json(&state, &uri, "writer", draft).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = post_json(&state, &uri, "lead", r#"{"platform":"wechat"}"#).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_scheduled_publish_job_stays_queued() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+        let mut team = manager.create("team").unwrap();
+        team.add_api_key("writer", Role::Editor, None).unwrap();
+        team.add_api_key("lead", Role::Publisher, None).unwrap();
+        let id = {
+            let mut store = team.open_store().unwrap();
+            let id = store.upsert(record("定时文章"));
+            store.save().unwrap();
+            id
+        };
+        let state = AppState::new(dir.path());
+        let body = format!(
+            r#"{{"content_id":"{}","platform":"wechat","publish_at":"2999-01-01T08:00:00Z"}}"#,
+            id
+        );
+
+        let (status, _) = post_json(&state, "/api/jobs/publish", "writer", &body).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, created) = post_json(&state, "/api/jobs/publish", "lead", &body).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let uri = format!("/api/jobs/{}", created["id"].as_str().unwrap());
+        let (status, job) = get(&state, &uri, Some("lead")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(job["status"], "queued");
+        assert_eq!(state.run_queued_jobs().unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_workflow_transitions_record_actor() {
+        let dir = TempDir::new().unwrap();
+        let manager = WorkspaceManager::new(dir.path());
+        let mut team = manager.create("team").unwrap();
+        team.add_api_key("writer", Role::Editor, Some("作者".to_string()))
+            .unwrap();
+        team.add_api_key("lead", Role::Publisher, Some("主编".to_string()))
+            .unwrap();
+        let id = {
+            let mut store = team.open_store().unwrap();
+            let id = store.upsert(record("待审文章"));
+            store.save().unwrap();
+            id
+        };
+        let state = AppState::new(dir.path());
+
+        let uri = format!("/api/contents/{}/workflow", id);
+        let transition =
+            |token: &'static str, body: &'static str| post_json(&state, &uri, token, body);
+
+        // 未提交审核不能直接批准；editor 不能批准
+        let (status, _) = transition("lead", r#"{"state":"approved"}"#).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = transition("writer", r#"{"state":"in-review"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = transition("writer", r#"{"state":"approved"}"#).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, body) = transition("lead", r#"{"state":"approved","note":"可以发布"}"#).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["workflow"]["state"], "approved");
+        assert_eq!(body["workflow"]["history"][1]["by"], "主编");
+        assert_eq!(body["workflow"]["history"][1]["note"], "可以发布");
+    }
+
+    #[tokio::test]
+    async fn test_render_preview_reuses_blocks() {
+        let dir = TempDir::new().unwrap();
+        let state = AppState::new(dir.path());
+        let paragraphs: Vec<String> = (0..200).map(|i| format!("第 {} 段内容。", i)).collect();
+        let markdown = format!("# 长文\n\n{}", paragraphs.join("\n\n"));
+
+        let render = |markdown: String| {
+            let state = state.clone();
+            async move {
+                let body = serde_json::json!({ "markdown": markdown, "platform": "wechat" });
+                let response = create_router(state, None)
+                    .oneshot(
+                        Request::post("/api/render")
+                            .header(header::CONTENT_TYPE, "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        let first = render(markdown.clone()).await;
+        assert_eq!(first["title"], "长文");
+        assert_eq!(first["rendered_blocks"], 201);
+
+        let second = render(markdown.replace("第 7 段", "第七段")).await;
+        assert_eq!(second["cached_blocks"], 200);
+        assert_eq!(second["rendered_blocks"], 1);
+        assert!(second["html"].as_str().unwrap().contains("第七段"));
+    }
+
+    #[tokio::test]
+    async fn test_process_and_preview_last_document() {
+        let dir = TempDir::new().unwrap();
+        let state = AppState::new(dir.path());
+        let request = |request: Request<Body>| {
+            let state = state.clone();
+            async move {
+                let response = create_router(state, None).oneshot(request).await.unwrap();
+                let status = response.status();
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, String::from_utf8(body.to_vec()).unwrap())
+            }
+        };
+
+        let (status, _) =
+            request(Request::get("/preview/wechat").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let body = serde_json::json!({
+            "markdown": "# 标题\n\n正文段落。",
+            "platforms": ["wechat"],
+        });
+        let (status, body) = request(
+            Request::post("/api/process")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["title"], "标题");
+        assert!(body["outputs"]["wechat"]
+            .as_str()
+            .unwrap()
+            .contains("正文段落"));
+        assert!(body["outputs"].get("zhihu").is_none());
+
+        let (status, page) =
+            request(Request::get("/preview/wechat").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("正文段落"));
+
+        let (status, _) =
+            request(Request::get("/preview/zhihu").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::{
+    core::{Role, Workspace},
+    web::server::AppState,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path},
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::collections::HashMap;
+
+/// API 错误，统一以 `{"error": "..."}` 返回
+#[derive(Debug)]
+pub struct ApiError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, message)
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, message)
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::CONFLICT, message)
+    }
+}
+
+impl From<crate::error::Error> for ApiError {
+    fn from(e: crate::error::Error) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+/// 从请求头中读取 `Authorization: Bearer <token>`
+pub fn bearer_token(parts: &Parts) -> Option<&str> {
+    parts
+        .headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+/// 当前请求所属的工作区及调用者的角色。
+///
+/// `/w/{workspace}/...` 按路径选择工作区，若该工作区配置了 API 密钥则必须携带其中之一；
+/// 其余路径按 Bearer 令牌查找工作区，未携带令牌时使用 `default` 工作区。
+pub struct WorkspaceContext {
+    pub workspace: Workspace,
+    pub role: Role,
+    /// 调用者使用的 API 密钥名称
+    pub actor: Option<String>,
+}
+
+impl WorkspaceContext {
+    /// 检查调用者角色，权限不足时返回 403
+    pub fn require(&self, required: Role) -> Result<(), ApiError> {
+        if self.role.allows(required) {
+            Ok(())
+        } else {
+            Err(ApiError::forbidden(format!(
+                "该操作需要 {} 角色，当前为 {}",
+                required, self.role
+            )))
+        }
+    }
+}
+
+#[async_trait]
+impl FromRequestParts<AppState> for WorkspaceContext {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        let params = Path::<HashMap<String, String>>::from_request_parts(parts, state)
+            .await
+            .map(|Path(params)| params)
+            .unwrap_or_default();
+        let token = bearer_token(parts);
+
+        let workspace = match (params.get("workspace"), token) {
+            (Some(name), _) => state
+                .workspaces()
+                .get(name)
+                .map_err(|e| ApiError::not_found(e.to_string()))?,
+            (None, Some(token)) => state
+                .workspaces()
+                .find_by_token(token)?
+                .ok_or_else(|| ApiError::unauthorized("无效的访问令牌"))?,
+            (None, None) => state.default_workspace()?,
+        };
+
+        let role = workspace.role_for(token).ok_or_else(|| {
+            ApiError::unauthorized(format!(
+                "访问工作区 {} 需要有效的访问令牌",
+                workspace.name()
+            ))
+        })?;
+
+        let actor = workspace.key_name(token);
+        Ok(Self {
+            workspace,
+            role,
+            actor,
+        })
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    core::{check_browser, check_config, check_publishers, CheckResult, CheckStatus},
+    web::server::AppState,
+};
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct HealthResponse {
+    pub status: &'static str,
+    pub version: &'static str,
+    pub uptime_secs: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadyResponse {
+    pub ready: bool,
+    pub checks: Vec<CheckResult>,
+}
+
+/// 存活探针：进程能响应请求即视为健康
+pub async fn healthz(State(state): State<AppState>) -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "ok",
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_secs: (chrono::Utc::now() - state.started_at()).num_seconds(),
+    })
+}
+
+/// 就绪探针：检查配置、浏览器自动化和发布器认证，任一检查失败时返回 503
+pub async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    let checks = match state.load_config() {
+        Ok(config) => {
+            let mut checks = vec![check_config(&config)];
+            checks.push(check_browser(&config));
+            checks.extend(check_publishers(&config));
+            checks
+        }
+        Err(e) => vec![CheckResult::new("config", CheckStatus::Fail, e.to_string())],
+    };
+
+    let ready = checks.iter().all(|c| c.status != CheckStatus::Fail);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    (status, Json(ReadyResponse { ready, checks }))
+}
+
+/// Prometheus 指标
+pub async fn metrics() -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        crate::core::metrics().render(),
+    )
+}
+
+/// 统计请求数。使用路由模板而不是实际路径，避免标签数量无限增长
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| "unmatched".to_string());
+
+    let response = next.run(request).await;
+    crate::core::metrics().record_http_request(&method, &path, response.status().as_u16());
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web::{create_router, AppState};
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+    };
+    use tempfile::TempDir;
+    use tower::ServiceExt;
+
+    async fn get_text(state: AppState, uri: &str) -> (StatusCode, String) {
+        let response = create_router(state, None)
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    async fn get(state: AppState, uri: &str) -> (StatusCode, serde_json::Value) {
+        let (status, body) = get_text(state, uri).await;
+        (status, serde_json::from_str(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_healthz() {
+        let dir = TempDir::new().unwrap();
+        let (status, body) = get(AppState::new(dir.path()), "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "ok");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_with_default_config() {
+        let dir = TempDir::new().unwrap();
+        let (status, body) = get(AppState::new(dir.path()), "/readyz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ready"], true);
+        assert_eq!(body["checks"][0]["name"], "config");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_fails_on_invalid_config() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("config.toml"), "not = [valid").unwrap();
+
+        let (status, body) = get(AppState::new(dir.path()), "/readyz").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["checks"][0]["status"], "fail");
+    }
+
+    #[tokio::test]
+    async fn test_metrics_counts_requests() {
+        let dir = TempDir::new().unwrap();
+        let state = AppState::new(dir.path());
+        get(state.clone(), "/healthz").await;
+
+        let (status, body) = get_text(state, "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("# TYPE markflow_http_requests_total counter"));
+        assert!(body.contains("path=\"/healthz\",status=\"200\""));
+    }
+}
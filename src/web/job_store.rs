@@ -0,0 +1,412 @@
+use crate::{
+    core::PublishResult,
+    error::Error,
+    web::jobs::{JobDocumentResult, JobRequest, JobStatus},
+    Result,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection, TransactionBehavior};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+use uuid::Uuid;
+
+/// 任务表数据库，保存在数据根目录下
+pub const JOBS_FILE: &str = "jobs.db";
+
+/// 只保留最近这么多个已结束的任务
+const MAX_FINISHED_JOBS: usize = 200;
+
+/// 持久化的任务：请求、状态和结果，服务重启后仍可查询和继续执行
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub id: Uuid,
+    pub workspace: String,
+    pub request: JobRequest,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub results: Vec<JobDocumentResult>,
+    /// 发布任务的结果
+    #[serde(default)]
+    pub publication: Option<PublishResult>,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// 已开始执行的次数（包括重试和重启后的继续执行）
+    #[serde(default)]
+    pub attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl JobRecord {
+    pub fn new(workspace: &str, request: impl Into<JobRequest>, status: JobStatus) -> Self {
+        let now = Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            workspace: workspace.to_string(),
+            request: request.into(),
+            status,
+            results: Vec::new(),
+            publication: None,
+            error: None,
+            attempts: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self.status,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+
+    /// 标记为开始执行，清空上次执行的结果
+    pub fn start(&mut self) {
+        self.status = JobStatus::Running;
+        self.results.clear();
+        self.publication = None;
+        self.error = None;
+        self.attempts += 1;
+        self.updated_at = Utc::now();
+    }
+}
+
+/// 后台任务表，保存在 SQLite 数据库中。写操作都在写事务中完成，
+/// 因此 Web 服务和 `markflow jobs` 命令可以同时操作同一张表
+pub struct JobStore {
+    /// 为空时使用内存数据库
+    path: Option<PathBuf>,
+    /// 第一次读写时打开
+    connection: Mutex<Option<Connection>>,
+}
+
+impl Default for JobStore {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+/// 请求和结果以 JSON 保存在 `record` 中，状态单独成列用于排队查询
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS jobs (
+    id TEXT PRIMARY KEY,
+    workspace TEXT NOT NULL,
+    status TEXT NOT NULL,
+    created_at TEXT NOT NULL,
+    record TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS jobs_status ON jobs (status);
+";
+
+/// 删除最早的已结束任务，只保留 `?1` 个
+const PRUNE_FINISHED: &str = "
+DELETE FROM jobs WHERE id IN (
+    SELECT id FROM jobs WHERE status IN ('completed', 'failed', 'cancelled')
+    ORDER BY created_at DESC, rowid DESC LIMIT -1 OFFSET ?1
+)";
+
+impl JobStore {
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            connection: Mutex::new(None),
+        }
+    }
+
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: Some(path.into()),
+            connection: Mutex::new(None),
+        }
+    }
+
+    /// 数据根目录（默认 `~/.markflow`）下的任务表
+    pub fn in_data_root(data_root: &Path) -> Self {
+        Self::open(data_root.join(JOBS_FILE))
+    }
+
+    fn connect(&self) -> Result<Connection> {
+        let connection = match &self.path {
+            Some(path) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                Connection::open(path)?
+            }
+            None => Connection::open_in_memory()?,
+        };
+        connection.busy_timeout(Duration::from_secs(5))?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(connection)
+    }
+
+    fn with_connection<R>(&self, f: impl FnOnce(&mut Connection) -> Result<R>) -> Result<R> {
+        let mut connection = self.connection.lock().unwrap_or_else(|e| e.into_inner());
+        if connection.is_none() {
+            *connection = Some(self.connect()?);
+        }
+        f(connection.as_mut().expect("数据库已打开"))
+    }
+
+    /// 按条件读取任务，按创建时间从早到晚排列
+    fn select(
+        connection: &Connection,
+        condition: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<JobRecord>> {
+        let mut statement = connection.prepare(&format!(
+            "SELECT record FROM jobs {} ORDER BY created_at, rowid",
+            condition
+        ))?;
+        let rows = statement.query_map(params, |row| row.get::<_, String>(0))?;
+        rows.map(|raw| {
+            serde_json::from_str(&raw?).map_err(|e| Error::Config(format!("任务表解析失败: {}", e)))
+        })
+        .collect()
+    }
+
+    fn save(connection: &Connection, job: &JobRecord) -> Result<()> {
+        connection.execute(
+            "INSERT INTO jobs (id, workspace, status, created_at, record)
+             VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET status = excluded.status, record = excluded.record",
+            params![
+                job.id.to_string(),
+                job.workspace,
+                job.status.as_str(),
+                job.created_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+                serde_json::to_string(job)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// 在写事务中读取、修改并保存任务，随后清理多余的已结束任务
+    fn modify<R>(&self, f: impl FnOnce(&Connection) -> Result<R>) -> Result<R> {
+        self.with_connection(|connection| {
+            let transaction =
+                connection.transaction_with_behavior(TransactionBehavior::Immediate)?;
+            let result = f(&transaction)?;
+            transaction.execute(PRUNE_FINISHED, [MAX_FINISHED_JOBS])?;
+            transaction.commit()?;
+            Ok(result)
+        })
+    }
+
+    /// 读取全部任务，按创建时间从早到晚排列
+    pub fn list(&self) -> Result<Vec<JobRecord>> {
+        self.with_connection(|connection| Self::select(connection, "", []))
+    }
+
+    pub fn get(&self, id: &Uuid) -> Result<Option<JobRecord>> {
+        self.with_connection(|connection| {
+            Ok(Self::select(connection, "WHERE id = ?1", [id.to_string()])?
+                .into_iter()
+                .next())
+        })
+    }
+
+    /// 按完整 ID 或 ID 前缀（如 `jobs list` 显示的前 8 位）查找任务
+    pub fn find(&self, prefix: &str) -> Result<JobRecord> {
+        let prefix = prefix.to_lowercase();
+        let mut matches = self.with_connection(|connection| {
+            Self::select(
+                connection,
+                "WHERE substr(id, 1, ?1) = ?2",
+                params![prefix.chars().count() as i64, prefix],
+            )
+        })?;
+        match matches.len() {
+            0 => Err(Error::Other(format!("任务不存在: {}", prefix))),
+            1 => Ok(matches.remove(0)),
+            _ => Err(Error::Other(format!("有多个任务以 {} 开头", prefix))),
+        }
+    }
+
+    pub fn insert(&self, record: JobRecord) -> Result<()> {
+        self.modify(|connection| Self::save(connection, &record))
+    }
+
+    /// 修改任务并返回修改后的记录；任务不存在时返回 `None`
+    pub fn update(&self, id: &Uuid, f: impl FnOnce(&mut JobRecord)) -> Result<Option<JobRecord>> {
+        self.modify(|connection| {
+            let Some(mut job) = Self::select(connection, "WHERE id = ?1", [id.to_string()])?
+                .into_iter()
+                .next()
+            else {
+                return Ok(None);
+            };
+            f(&mut job);
+            job.updated_at = Utc::now();
+            Self::save(connection, &job)?;
+            Ok(Some(job))
+        })
+    }
+
+    /// 取消排队中或执行中的任务；执行中的任务在处理完当前文档后停止
+    pub fn cancel(&self, id: &Uuid) -> Result<JobRecord> {
+        self.transition(id, |job| match job.status {
+            JobStatus::Queued | JobStatus::Running => {
+                job.status = JobStatus::Cancelled;
+                Ok(())
+            }
+            status => Err(Error::Other(format!(
+                "任务已结束（{}），无法取消",
+                status.as_str()
+            ))),
+        })
+    }
+
+    /// 把失败或已取消的任务重新排队，由 Web 服务重新执行
+    pub fn retry(&self, id: &Uuid) -> Result<JobRecord> {
+        self.transition(id, |job| match job.status {
+            JobStatus::Failed | JobStatus::Cancelled => {
+                job.status = JobStatus::Queued;
+                job.error = None;
+                Ok(())
+            }
+            status => Err(Error::Other(format!(
+                "只能重试失败或已取消的任务（当前: {}）",
+                status.as_str()
+            ))),
+        })
+    }
+
+    fn transition(
+        &self,
+        id: &Uuid,
+        f: impl FnOnce(&mut JobRecord) -> Result<()>,
+    ) -> Result<JobRecord> {
+        self.modify(|connection| {
+            let mut job = Self::select(connection, "WHERE id = ?1", [id.to_string()])?
+                .into_iter()
+                .next()
+                .ok_or_else(|| Error::Other(format!("任务不存在: {}", id)))?;
+            f(&mut job)?;
+            job.updated_at = Utc::now();
+            Self::save(connection, &job)?;
+            Ok(job)
+        })
+    }
+
+    /// 取出所有到 `now` 为止应当执行的排队任务并标记为执行中
+    pub fn claim_queued(&self, now: DateTime<Utc>) -> Result<Vec<JobRecord>> {
+        self.modify(|connection| {
+            let mut claimed = Vec::new();
+            for mut job in Self::select(connection, "WHERE status = 'queued'", [])? {
+                if job.request.is_due(now) {
+                    job.start();
+                    Self::save(connection, &job)?;
+                    claimed.push(job);
+                }
+            }
+            Ok(claimed)
+        })
+    }
+
+    /// 服务启动时调用：上次异常退出时仍在执行的任务重新排队，返回数量
+    pub fn requeue_interrupted(&self) -> Result<usize> {
+        self.modify(|connection| {
+            let running = Self::select(connection, "WHERE status = 'running'", [])?;
+            for mut job in running.iter().cloned() {
+                job.status = JobStatus::Queued;
+                job.updated_at = Utc::now();
+                Self::save(connection, &job)?;
+            }
+            Ok(running.len())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web::jobs::{JobDocument, ProcessJobRequest, PublishJobRequest};
+
+    fn request() -> ProcessJobRequest {
+        ProcessJobRequest {
+            documents: vec![JobDocument {
+                name: "a.md".to_string(),
+                markdown: "# A".to_string(),
+            }],
+            platforms: vec!["wechat".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_jobs_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JobStore::in_data_root(dir.path());
+        let running = JobRecord::new("default", request(), JobStatus::Running);
+        let failed = JobRecord::new("default", request(), JobStatus::Failed);
+        store.insert(running.clone()).unwrap();
+        store.insert(failed.clone()).unwrap();
+
+        // 模拟服务重启：重新打开任务表，执行中的任务重新排队
+        let store = JobStore::in_data_root(dir.path());
+        assert_eq!(store.requeue_interrupted().unwrap(), 1);
+        assert!(store.cancel(&failed.id).is_err());
+        store.retry(&failed.id).unwrap();
+
+        let claimed = store.claim_queued(Utc::now()).unwrap();
+        assert_eq!(claimed.len(), 2);
+        assert!(claimed
+            .iter()
+            .all(|job| job.status == JobStatus::Running && job.attempts == 1));
+        assert!(store.claim_queued(Utc::now()).unwrap().is_empty());
+
+        let prefix = &running.id.to_string()[..8];
+        assert_eq!(store.find(prefix).unwrap().id, running.id);
+        assert_eq!(
+            store.cancel(&running.id).unwrap().status,
+            JobStatus::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_scheduled_publish_waits_until_due() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = JobStore::in_data_root(dir.path());
+        let now = Utc::now();
+        let publish = JobRecord::new(
+            "default",
+            PublishJobRequest {
+                content_id: Uuid::new_v4(),
+                platform: "wechat".to_string(),
+                draft: false,
+                publish_at: Some(now + chrono::Duration::hours(1)),
+            },
+            JobStatus::Queued,
+        );
+        store.insert(publish.clone()).unwrap();
+        let saved = serde_json::to_value(&publish.request).unwrap();
+        assert_eq!(saved["kind"], "publish");
+
+        // 重新打开任务表后仍在排队，到时间才取出
+        let store = JobStore::in_data_root(dir.path());
+        assert!(store.claim_queued(now).unwrap().is_empty());
+        let claimed = store
+            .claim_queued(now + chrono::Duration::hours(2))
+            .unwrap();
+        assert_eq!(claimed.len(), 1);
+        assert!(matches!(claimed[0].request, JobRequest::Publish(_)));
+    }
+
+    #[test]
+    fn test_unreadable_table_is_not_overwritten() {
+        let dir = tempfile::tempdir().unwrap();
+        // 任务表路径是目录时读取失败，不能当作空表写入
+        std::fs::create_dir(dir.path().join(JOBS_FILE)).unwrap();
+        let store = JobStore::in_data_root(dir.path());
+        assert!(store.list().is_err());
+        assert!(store
+            .insert(JobRecord::new("default", request(), JobStatus::Queued))
+            .is_err());
+        assert!(dir.path().join(JOBS_FILE).is_dir());
+    }
+}
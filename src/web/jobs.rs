@@ -0,0 +1,616 @@
+use crate::{
+    adapters::{create_adapter, AdapterCache},
+    cli::args::AppConfig,
+    core::{
+        check_accessibility, check_html, ensure_online, ArticleReport, Content, MarkdownProcessor,
+        Platform, PlatformReport, ProcessingPipeline, PublishResult, PublishStatus, Workspace,
+    },
+    error::Error,
+    publishers::{publish_record, record_publish_result},
+    web::job_store::{JobRecord, JobStore},
+    Result,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// 任务进度事件，通过 SSE 推送给前端
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobEvent {
+    Started {
+        total: usize,
+    },
+    Stage {
+        document: String,
+        stage: String,
+        /// 整个任务的完成比例（0.0 ~ 1.0）
+        progress: f32,
+    },
+    DocumentFinished {
+        document: String,
+        progress: f32,
+    },
+    Completed,
+    Failed {
+        error: String,
+    },
+    Cancelled,
+}
+
+impl JobEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            JobEvent::Started { .. } => "started",
+            JobEvent::Stage { .. } => "stage",
+            JobEvent::DocumentFinished { .. } => "document_finished",
+            JobEvent::Completed => "completed",
+            JobEvent::Failed { .. } => "failed",
+            JobEvent::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    /// 等待执行：重试的任务、服务重启前未完成的任务、未到时间的定时发布
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobStatus::Queued => "queued",
+            JobStatus::Running => "running",
+            JobStatus::Completed => "completed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDocument {
+    pub name: String,
+    pub markdown: String,
+}
+
+/// `POST /api/jobs/process` 的请求体
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessJobRequest {
+    pub documents: Vec<JobDocument>,
+    /// 平台名，如 `wechat`、`zhihu`；为空时处理全部平台
+    #[serde(default)]
+    pub platforms: Vec<String>,
+}
+
+/// `POST /api/jobs/publish` 的请求体：把台账中的一篇文章发布到平台，可指定发布时间
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublishJobRequest {
+    pub content_id: Uuid,
+    pub platform: String,
+    /// 只保存草稿
+    #[serde(default)]
+    pub draft: bool,
+    /// 计划发布时间，为空时立即发布；到时间前任务保持排队，服务重启后仍会按时发布
+    #[serde(default)]
+    pub publish_at: Option<DateTime<Utc>>,
+}
+
+/// 任务的请求：批量处理或发布，保存时以 `kind` 字段区分
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum JobRequest {
+    Process(ProcessJobRequest),
+    Publish(PublishJobRequest),
+}
+
+impl JobRequest {
+    /// 任务包含的文档数，用于计算进度
+    pub fn total(&self) -> usize {
+        match self {
+            JobRequest::Process(request) => request.documents.len(),
+            JobRequest::Publish(_) => 1,
+        }
+    }
+
+    /// 还没到计划发布时间的任务不执行
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match self {
+            JobRequest::Publish(PublishJobRequest {
+                publish_at: Some(at),
+                ..
+            }) => *at <= now,
+            _ => true,
+        }
+    }
+
+    /// `markflow jobs list` 中显示的任务内容
+    pub fn describe(&self) -> String {
+        match self {
+            JobRequest::Process(request) => format!("{} 篇文档", request.documents.len()),
+            JobRequest::Publish(request) => {
+                let id = request.content_id.simple().to_string();
+                let mut text = format!(
+                    "{} {} 到 {}",
+                    if request.draft {
+                        "保存草稿"
+                    } else {
+                        "发布"
+                    },
+                    &id[..8],
+                    request.platform
+                );
+                if let Some(at) = request.publish_at {
+                    text.push_str(&format!("（{}）", at.format("%Y-%m-%d %H:%M")));
+                }
+                text
+            }
+        }
+    }
+}
+
+impl From<ProcessJobRequest> for JobRequest {
+    fn from(request: ProcessJobRequest) -> Self {
+        JobRequest::Process(request)
+    }
+}
+
+impl From<PublishJobRequest> for JobRequest {
+    fn from(request: PublishJobRequest) -> Self {
+        JobRequest::Publish(request)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobDocumentResult {
+    pub name: String,
+    pub title: String,
+    /// 平台名 -> 适配后的 HTML
+    pub outputs: BTreeMap<String, String>,
+    pub report: ArticleReport,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobSnapshot {
+    pub id: Uuid,
+    pub workspace: String,
+    pub status: JobStatus,
+    pub results: Vec<JobDocumentResult>,
+    /// 发布任务的结果
+    pub publication: Option<PublishResult>,
+    pub error: Option<String>,
+    pub attempts: u32,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<JobRecord> for JobSnapshot {
+    fn from(record: JobRecord) -> Self {
+        Self {
+            id: record.id,
+            workspace: record.workspace,
+            status: record.status,
+            results: record.results,
+            publication: record.publication,
+            error: record.error,
+            attempts: record.attempts,
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// 任务的实时事件，只保存在内存中；服务重启后只能查到任务的最终状态
+struct LiveJob {
+    events: Vec<JobEvent>,
+    /// 任务结束后置为 None，订阅者的事件流随之结束
+    sender: Option<broadcast::Sender<JobEvent>>,
+}
+
+impl Default for LiveJob {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            sender: Some(broadcast::channel(256).0),
+        }
+    }
+}
+
+/// 后台任务。任务的请求、状态和结果保存在 [`JobStore`] 中，服务重启后不会丢失；
+/// 进度事件只在内存中推送
+#[derive(Default)]
+pub struct JobManager {
+    store: JobStore,
+    live: Mutex<HashMap<Uuid, LiveJob>>,
+    adapter_cache: Mutex<AdapterCache>,
+}
+
+impl JobManager {
+    pub fn new(store: JobStore) -> Self {
+        Self {
+            store,
+            live: Mutex::default(),
+            adapter_cache: Mutex::new(AdapterCache::in_memory()),
+        }
+    }
+
+    pub fn store(&self) -> &JobStore {
+        &self.store
+    }
+
+    fn live(&self) -> std::sync::MutexGuard<'_, HashMap<Uuid, LiveJob>> {
+        self.live.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn emit(&self, id: Uuid, event: JobEvent) {
+        let finished = match &event {
+            JobEvent::Completed => Some((JobStatus::Completed, None)),
+            JobEvent::Failed { error } => Some((JobStatus::Failed, Some(error.clone()))),
+            JobEvent::Cancelled => Some((JobStatus::Cancelled, None)),
+            _ => None,
+        };
+        if let Some((status, error)) = &finished {
+            // 执行期间被取消的任务保持已取消状态
+            let result = self.store.update(&id, |job| {
+                if job.status == JobStatus::Running {
+                    job.status = *status;
+                    job.error = error.clone();
+                }
+            });
+            if let Err(e) = result {
+                tracing::warn!("保存任务状态失败 {}: {}", id, e);
+            }
+        }
+
+        let mut live = self.live();
+        let job = live.entry(id).or_default();
+        if let Some(sender) = &job.sender {
+            let _ = sender.send(event.clone());
+        }
+        if finished.is_some() {
+            job.sender = None;
+        }
+        job.events.push(event);
+    }
+
+    fn push_result(&self, id: Uuid, result: JobDocumentResult) {
+        if let Err(e) = self.store.update(&id, |job| job.results.push(result)) {
+            tracing::warn!("保存任务结果失败 {}: {}", id, e);
+        }
+    }
+
+    fn is_cancelled(&self, id: &Uuid) -> bool {
+        matches!(self.store.get(id), Ok(Some(job)) if job.status == JobStatus::Cancelled)
+    }
+
+    pub fn snapshot(&self, id: &Uuid) -> Result<Option<JobSnapshot>> {
+        Ok(self.store.get(id)?.map(JobSnapshot::from))
+    }
+
+    /// 返回已发生的事件和后续事件的订阅；任务已结束时订阅会立即关闭。
+    /// 服务重启前结束的任务只返回最终状态对应的事件
+    pub fn subscribe(
+        &self,
+        id: &Uuid,
+    ) -> Result<Option<(Vec<JobEvent>, broadcast::Receiver<JobEvent>)>> {
+        let Some(record) = self.store.get(id)? else {
+            return Ok(None);
+        };
+        let mut live = self.live();
+        let job = match live.get_mut(id) {
+            Some(job) => job,
+            None if record.is_finished() => {
+                let event = match record.status {
+                    JobStatus::Completed => JobEvent::Completed,
+                    JobStatus::Cancelled => JobEvent::Cancelled,
+                    _ => JobEvent::Failed {
+                        error: record.error.unwrap_or_default(),
+                    },
+                };
+                return Ok(Some((vec![event], broadcast::channel(1).1)));
+            }
+            // 排队中的任务：先建立事件通道，开始执行后推送
+            None => live.entry(*id).or_default(),
+        };
+        let receiver = match &job.sender {
+            Some(sender) => sender.subscribe(),
+            None => broadcast::channel(1).1,
+        };
+        Ok(Some((job.events.clone(), receiver)))
+    }
+
+    /// 在后台处理一批文档，返回任务 ID
+    pub fn spawn_process(
+        self: &Arc<Self>,
+        workspace: &Workspace,
+        request: ProcessJobRequest,
+    ) -> Result<Uuid> {
+        resolve_platforms(&request.platforms)?;
+        self.spawn(workspace, request.into())
+    }
+
+    /// 在后台发布台账中的一篇文章，返回任务 ID；指定了 `publish_at` 的任务排队到该时间再执行
+    pub fn spawn_publish(
+        self: &Arc<Self>,
+        workspace: &Workspace,
+        request: PublishJobRequest,
+    ) -> Result<Uuid> {
+        request.platform.parse::<Platform>()?;
+        self.spawn(workspace, request.into())
+    }
+
+    fn spawn(self: &Arc<Self>, workspace: &Workspace, request: JobRequest) -> Result<Uuid> {
+        let mut record = JobRecord::new(workspace.name(), request, JobStatus::Queued);
+        let id = record.id;
+        if !record.request.is_due(Utc::now()) {
+            self.store.insert(record)?;
+            return Ok(id);
+        }
+        record.start();
+        self.store.insert(record.clone())?;
+        self.start(record, workspace.clone());
+        Ok(id)
+    }
+
+    /// 执行所有到期的排队任务（重试的任务、服务重启前未完成的任务、到时间的定时发布），
+    /// 返回开始执行的数量。`load_workspace` 按工作区名打开工作区
+    pub fn run_queued(
+        self: &Arc<Self>,
+        load_workspace: impl Fn(&str) -> Result<Workspace>,
+    ) -> Result<usize> {
+        let jobs = self.store.claim_queued(Utc::now())?;
+        let count = jobs.len();
+        for record in jobs {
+            tracing::info!("开始执行排队的任务 {}", record.id);
+            match load_workspace(&record.workspace) {
+                Ok(workspace) => self.start(record, workspace),
+                Err(e) => self.emit(
+                    record.id,
+                    JobEvent::Failed {
+                        error: e.to_string(),
+                    },
+                ),
+            }
+        }
+        Ok(count)
+    }
+
+    fn start(self: &Arc<Self>, record: JobRecord, workspace: Workspace) {
+        let id = record.id;
+        {
+            // 保留已有的订阅；上次执行留下的已关闭通道则重新建立
+            let mut live = self.live();
+            let job = live.entry(id).or_default();
+            if job.sender.is_none() {
+                *job = LiveJob::default();
+            }
+        }
+        let manager = Arc::clone(self);
+
+        tokio::spawn(async move {
+            manager.emit(
+                id,
+                JobEvent::Started {
+                    total: record.request.total(),
+                },
+            );
+            let result = match workspace.load_config() {
+                Ok(config) => match &record.request {
+                    JobRequest::Process(request) => manager.run_process(id, &config, request).await,
+                    JobRequest::Publish(request) => {
+                        manager.run_publish(id, &workspace, &config, request).await
+                    }
+                },
+                Err(e) => Err(e),
+            };
+            let event = match result {
+                Ok(true) => JobEvent::Completed,
+                Ok(false) => JobEvent::Cancelled,
+                Err(e) => JobEvent::Failed {
+                    error: e.to_string(),
+                },
+            };
+            manager.emit(id, event);
+        });
+    }
+
+    /// 依次处理文档；任务被取消时在当前文档处理完后停止并返回 `false`
+    async fn run_process(
+        &self,
+        id: Uuid,
+        config: &AppConfig,
+        request: &ProcessJobRequest,
+    ) -> Result<bool> {
+        let platforms = resolve_platforms(&request.platforms)?;
+        let processor = MarkdownProcessor::new()
+            .with_timezone(config.timezone())
+            .with_autolink(config.links.autolink)
+            .with_smart_punctuation(config.smart_punctuation())
+            .with_math(config.math.enabled)
+            .with_permalink(config.permalink());
+        let pipeline = ProcessingPipeline::from_config(config)?;
+        let total_documents = request.documents.len().max(1) as f32;
+        // 每篇文档的步骤：流水线各阶段 + 各平台适配
+        let steps = (pipeline.len() + platforms.len()).max(1) as f32;
+
+        for (index, document) in request.documents.iter().enumerate() {
+            if self.is_cancelled(&id) {
+                return Ok(false);
+            }
+            let progress = |step: usize| (index as f32 + step as f32 / steps) / total_documents;
+
+            let content = processor.process(&document.markdown)?;
+            let content = pipeline
+                .process_with_progress(content, |step, _, stage| {
+                    self.emit(
+                        id,
+                        JobEvent::Stage {
+                            document: document.name.clone(),
+                            stage: stage.to_string(),
+                            progress: progress(step),
+                        },
+                    );
+                })
+                .await?;
+
+            let mut report = ArticleReport::new(&content.title, None);
+            let mut outputs = BTreeMap::new();
+            for (offset, platform) in platforms.iter().enumerate() {
+                self.emit(
+                    id,
+                    JobEvent::Stage {
+                        document: document.name.clone(),
+                        stage: format!("adapt:{}", platform),
+                        progress: progress(pipeline.len() + offset),
+                    },
+                );
+
+                let (html, platform_report) = adapt_for_platform(
+                    config,
+                    &pipeline,
+                    &content,
+                    platform,
+                    &mut self.adapter_cache.lock().unwrap_or_else(|e| e.into_inner()),
+                )?;
+                outputs.insert(platform.to_string(), html);
+                report.platforms.push(platform_report);
+            }
+
+            self.push_result(
+                id,
+                JobDocumentResult {
+                    name: document.name.clone(),
+                    title: content.title.clone(),
+                    outputs,
+                    report,
+                },
+            );
+            self.emit(
+                id,
+                JobEvent::DocumentFinished {
+                    document: document.name.clone(),
+                    progress: (index + 1) as f32 / total_documents,
+                },
+            );
+        }
+
+        Ok(true)
+    }
+
+    /// 发布台账中的文章并把结果记入工作区台账；平台返回发布失败时任务失败
+    async fn run_publish(
+        &self,
+        id: Uuid,
+        workspace: &Workspace,
+        config: &AppConfig,
+        request: &PublishJobRequest,
+    ) -> Result<bool> {
+        ensure_online("发布内容")?;
+        let platform = request.platform.parse::<Platform>()?;
+        let ledger = workspace.ledger_path();
+        let record = workspace
+            .open_store()?
+            .get(&request.content_id)
+            .cloned()
+            .ok_or_else(|| Error::Other(format!("内容不存在: {}", request.content_id)))?;
+        self.emit(
+            id,
+            JobEvent::Stage {
+                document: record.title.clone(),
+                stage: format!("publish:{}", platform),
+                progress: 0.0,
+            },
+        );
+
+        let result = publish_record(&record, &platform, config, request.draft, &ledger).await?;
+        record_publish_result(&ledger, &record.id, result.clone(), config)?;
+        let failed = matches!(result.status, PublishStatus::Failed);
+        let message = result.message.clone();
+        if let Err(e) = self.store.update(&id, |job| job.publication = Some(result)) {
+            tracing::warn!("保存任务结果失败 {}: {}", id, e);
+        }
+        if failed {
+            return Err(Error::Publishing(message));
+        }
+        self.emit(
+            id,
+            JobEvent::DocumentFinished {
+                document: record.title,
+                progress: 1.0,
+            },
+        );
+        Ok(true)
+    }
+}
+
+/// 把经过流水线处理的文章适配到平台：平台阶段、字段校验、适配器，以及按配置追加的
+/// HTML 和无障碍检查，返回平台 HTML 和报告
+pub(crate) fn adapt_for_platform(
+    config: &AppConfig,
+    pipeline: &ProcessingPipeline,
+    content: &Content,
+    platform: &Platform,
+    cache: &mut AdapterCache,
+) -> Result<(String, PlatformReport)> {
+    let adapter = create_adapter(platform, config)?;
+    let mut platform_report = PlatformReport::new(platform.clone());
+    let content = pipeline.process_for_platform(content, platform, &mut platform_report)?;
+    let mut validation = adapter.validation_report(&content);
+    validation.apply_levels(&config.validation_levels(platform))?;
+    platform_report.warnings.extend(
+        validation
+            .warnings()
+            .map(|w| format!("{}: {}", w.field, w.message)),
+    );
+    validation.into_result(&platform.to_string())?;
+
+    let html = cache.adapt(adapter.as_ref(), &content.html, &mut platform_report)?;
+    if config.output.validate_html {
+        platform_report.warnings.extend(
+            check_html(&html)
+                .iter()
+                .map(|issue| format!("html: {}", issue)),
+        );
+    }
+    if config.accessibility.enabled {
+        platform_report.warnings.extend(
+            check_accessibility(
+                &html,
+                &config.style()?.background_color,
+                config.accessibility.min_contrast,
+            )
+            .iter()
+            .map(|issue| format!("a11y: {}", issue)),
+        );
+    }
+    Ok((html, platform_report))
+}
+
+/// 平台名列表为空或包含 `all` 时使用全部平台
+pub(crate) fn resolve_platforms(names: &[String]) -> Result<Vec<Platform>> {
+    let mut platforms = Vec::new();
+    for name in names {
+        match name.parse::<Platform>()? {
+            Platform::All => {}
+            platform if !platforms.contains(&platform) => platforms.push(platform),
+            _ => {}
+        }
+    }
+    if platforms.is_empty() || names.iter().any(|n| n.eq_ignore_ascii_case("all")) {
+        for platform in Platform::concrete() {
+            if !platforms.contains(&platform) {
+                platforms.push(platform);
+            }
+        }
+    }
+    Ok(platforms)
+}
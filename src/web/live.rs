@@ -0,0 +1,192 @@
+use crate::{web::preview::wrap_document, Result};
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse,
+    },
+    routing::get,
+    Router,
+};
+use std::{
+    collections::BTreeMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::broadcast;
+use tokio_stream::{wrappers::BroadcastStream, Stream, StreamExt};
+
+/// 预览页收到更新事件后重新加载
+const RELOAD_SCRIPT: &str =
+    "<script>new EventSource('/events').addEventListener('reload', () => location.reload());</script>";
+
+#[derive(Debug, Default)]
+struct LiveDocument {
+    title: String,
+    /// 平台名 -> 写入输出文件的 HTML
+    outputs: BTreeMap<String, String>,
+}
+
+/// `watch --preview-port` 的实时预览：保存最近处理的文章在各平台的输出，
+/// 文章重新处理后通过 SSE 通知已打开的预览页刷新
+#[derive(Clone)]
+pub struct LivePreview {
+    document: Arc<Mutex<LiveDocument>>,
+    events: broadcast::Sender<()>,
+}
+
+impl Default for LivePreview {
+    fn default() -> Self {
+        Self {
+            document: Arc::default(),
+            events: broadcast::channel(16).0,
+        }
+    }
+}
+
+impl LivePreview {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 替换为刚处理完的文章并通知预览页刷新
+    pub fn update(&self, title: impl Into<String>, outputs: BTreeMap<String, String>) {
+        *self.document.lock().unwrap_or_else(|e| e.into_inner()) = LiveDocument {
+            title: title.into(),
+            outputs,
+        };
+        let _ = self.events.send(());
+    }
+
+    pub fn router(&self) -> Router {
+        Router::new()
+            .route("/", get(index_page))
+            .route("/events", get(reload_events))
+            .route("/:platform", get(platform_page))
+            .with_state(self.clone())
+    }
+
+    /// 绑定地址并在后台运行预览服务，返回实际监听的地址
+    pub async fn start(&self, addr: SocketAddr) -> Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let router = self.router();
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, router).await {
+                tracing::warn!("实时预览服务已停止: {}", e);
+            }
+        });
+        Ok(local_addr)
+    }
+}
+
+/// 在页面末尾加入刷新脚本
+fn with_reload(page: String) -> Html<String> {
+    Html(match page.rfind("</body>") {
+        Some(end) => format!("{}{}\n{}", &page[..end], RELOAD_SCRIPT, &page[end..]),
+        None => format!("{}\n{}", page, RELOAD_SCRIPT),
+    })
+}
+
+/// 各平台预览页的入口
+async fn index_page(State(preview): State<LivePreview>) -> impl IntoResponse {
+    let body = {
+        let document = preview.document.lock().unwrap_or_else(|e| e.into_inner());
+        if document.outputs.is_empty() {
+            "<p>等待文件变化……</p>".to_string()
+        } else {
+            let links: Vec<String> = document
+                .outputs
+                .keys()
+                .map(|platform| format!(r#"<li><a href="/{0}">{0}</a></li>"#, platform))
+                .collect();
+            format!(
+                "<h1>{}</h1>\n<ul>{}</ul>",
+                html_escape::encode_text(&document.title),
+                links.concat()
+            )
+        }
+    };
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        with_reload(wrap_document(&body)),
+    )
+}
+
+/// 最近处理的文章在某个平台上的输出；还没有输出时显示等待提示，处理完成后自动刷新
+async fn platform_page(
+    State(preview): State<LivePreview>,
+    Path(platform): Path<String>,
+) -> impl IntoResponse {
+    let html = preview
+        .document
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .outputs
+        .get(&platform)
+        .cloned()
+        .unwrap_or_else(|| {
+            format!(
+                "<p>等待 {} 平台的输出……</p>",
+                html_escape::encode_text(&platform)
+            )
+        });
+    (
+        [(header::CACHE_CONTROL, "no-store")],
+        with_reload(wrap_document(&html)),
+    )
+}
+
+async fn reload_events(
+    State(preview): State<LivePreview>,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>> {
+    let stream = BroadcastStream::new(preview.events.subscribe())
+        .filter_map(|event| event.ok())
+        .map(|_| Ok(Event::default().event("reload").data("")));
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn get(preview: &LivePreview, uri: &str) -> String {
+        let response = preview
+            .router()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_live_preview_pages() {
+        let preview = LivePreview::new();
+        let waiting = get(&preview, "/wechat").await;
+        assert!(waiting.contains("等待 wechat 平台的输出"));
+        assert!(waiting.contains("EventSource('/events')"));
+
+        let mut events = preview.events.subscribe();
+        preview.update(
+            "标题",
+            BTreeMap::from([(
+                "wechat".to_string(),
+                "<section style=\"color: red\">正文</section>".to_string(),
+            )]),
+        );
+        events.recv().await.unwrap();
+
+        let page = get(&preview, "/wechat").await;
+        assert!(page.contains("<section style=\"color: red\">正文</section>"));
+        assert!(page.find("EventSource").unwrap() < page.find("</body>").unwrap());
+        assert!(get(&preview, "/")
+            .await
+            .contains(r#"<a href="/wechat">wechat</a>"#));
+    }
+}
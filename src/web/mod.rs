@@ -1,8 +1,21 @@
-// Web模块 - 待实现
-// pub mod server;
-// pub mod handlers;
-// pub mod routes;
+pub mod api;
+pub mod extract;
+pub mod handlers;
+pub mod job_store;
+pub mod jobs;
+pub mod live;
+pub mod preview;
+pub mod routes;
+pub mod server;
+pub mod themes;
 
-// pub use server::*;
-// pub use handlers::*;
-// pub use routes::*;
+pub use api::*;
+pub use extract::*;
+pub use handlers::*;
+pub use job_store::*;
+pub use jobs::*;
+pub use live::*;
+pub use preview::*;
+pub use routes::*;
+pub use server::*;
+pub use themes::*;
@@ -0,0 +1,203 @@
+use crate::{
+    core::Platform,
+    error::Error,
+    web::{
+        extract::{ApiError, WorkspaceContext},
+        server::AppState,
+    },
+    Result,
+};
+use axum::{
+    extract::{Path as UrlPath, State},
+    http::header,
+    response::{Html, IntoResponse},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::{collections::HashMap, path::Path};
+use uuid::Uuid;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 预览链接默认有效期（分钟）
+pub const DEFAULT_PREVIEW_TTL_MINUTES: i64 = 60;
+/// 预览链接最长有效期（7 天）
+pub const MAX_PREVIEW_TTL_MINUTES: i64 = 7 * 24 * 60;
+
+/// 预览链接中携带的信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PreviewClaims {
+    #[serde(rename = "w")]
+    pub workspace: String,
+    #[serde(rename = "c")]
+    pub content_id: Uuid,
+    #[serde(rename = "p")]
+    pub platform: String,
+    /// 过期时间（Unix 秒）
+    #[serde(rename = "e")]
+    pub expires_at: i64,
+}
+
+/// 用服务端密钥对预览链接签名（HMAC-SHA256），无需账号即可校验链接是否有效
+pub struct PreviewSigner {
+    key: Vec<u8>,
+}
+
+impl PreviewSigner {
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into() }
+    }
+
+    /// 读取密钥文件，不存在时生成随机密钥。删除该文件即可让所有已分享的链接失效
+    pub fn load_or_create(path: &Path) -> Result<Self> {
+        if let Ok(key) = std::fs::read(path) {
+            if !key.is_empty() {
+                return Ok(Self::new(key));
+            }
+        }
+
+        let key = format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple());
+        crate::core::write_atomic(path, &key)?;
+        Ok(Self::new(key.into_bytes()))
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC 支持任意长度的密钥")
+    }
+
+    pub fn sign(&self, claims: &PreviewClaims) -> Result<String> {
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(claims)?);
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        let signature = URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes());
+        Ok(format!("{}.{}", payload, signature))
+    }
+
+    pub fn verify(&self, token: &str) -> Result<PreviewClaims> {
+        let invalid = || Error::Other("无效的预览链接".to_string());
+
+        let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).map_err(|_| invalid())?;
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| invalid())?;
+        let claims: PreviewClaims = serde_json::from_slice(&payload).map_err(|_| invalid())?;
+        if claims.expires_at < chrono::Utc::now().timestamp() {
+            return Err(Error::Other("预览链接已过期".to_string()));
+        }
+        Ok(claims)
+    }
+}
+
+/// 公开的只读预览页 `/p/{token}`：展示文章在对应平台适配后的 HTML
+pub async fn preview_page(
+    State(state): State<AppState>,
+    UrlPath(token): UrlPath<String>,
+) -> std::result::Result<impl IntoResponse, ApiError> {
+    let claims = state
+        .preview_signer()?
+        .verify(&token)
+        .map_err(|e| ApiError::not_found(e.to_string()))?;
+
+    let workspace = state
+        .workspaces()
+        .get(&claims.workspace)
+        .map_err(|e| ApiError::not_found(e.to_string()))?;
+    let html = {
+        let store = workspace.open_store()?;
+        let record = store
+            .get(&claims.content_id)
+            .ok_or_else(|| ApiError::not_found("文章不存在或已被删除"))?;
+        record.html.get(&claims.platform).cloned().ok_or_else(|| {
+            ApiError::not_found(format!("文章没有 {} 平台的输出", claims.platform))
+        })?
+    };
+
+    Ok((
+        [
+            (header::CACHE_CONTROL, "no-store"),
+            (header::HeaderName::from_static("x-robots-tag"), "noindex"),
+        ],
+        Html(wrap_document(&html)),
+    ))
+}
+
+/// 工作区最近一次 `POST /api/process` 的结果在指定平台上的预览页 `/preview/{platform}`
+pub async fn last_processed_page(
+    State(state): State<AppState>,
+    WorkspaceContext { workspace, .. }: WorkspaceContext,
+    UrlPath(params): UrlPath<HashMap<String, String>>,
+) -> std::result::Result<impl IntoResponse, ApiError> {
+    let platform = params
+        .get("platform")
+        .map(|name| name.parse::<Platform>())
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?
+        .ok_or_else(|| ApiError::bad_request("缺少平台名"))?;
+    let html = state
+        .last_processed(workspace.name(), &platform.to_string())
+        .ok_or_else(|| {
+            ApiError::not_found(format!(
+                "还没有处理过 {} 平台的文档，请先调用 POST /api/process",
+                platform
+            ))
+        })?;
+    Ok((
+        [(header::CACHE_CONTROL, "no-store")],
+        Html(wrap_document(&html)),
+    ))
+}
+
+/// 输出文件是 HTML 片段时补全为适合手机查看的完整页面
+pub(crate) fn wrap_document(html: &str) -> String {
+    let trimmed = html.trim_start();
+    if trimmed.starts_with("<!DOCTYPE") || trimmed.starts_with("<html") {
+        return html.to_string();
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>MarkFlow 预览</title>\n<style>body{{max-width:677px;margin:0 auto;padding:16px;}}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+        html
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(expires_in: i64) -> PreviewClaims {
+        PreviewClaims {
+            workspace: "default".to_string(),
+            content_id: Uuid::new_v4(),
+            platform: "wechat".to_string(),
+            expires_at: chrono::Utc::now().timestamp() + expires_in,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = PreviewSigner::new("secret");
+        let claims = claims(60);
+        let token = signer.sign(&claims).unwrap();
+        assert_eq!(signer.verify(&token).unwrap(), claims);
+
+        // 密钥不同或内容被篡改都无法通过校验
+        assert!(PreviewSigner::new("other").verify(&token).is_err());
+        let (payload, signature) = token.split_once('.').unwrap();
+        let mut tampered = payload.to_string();
+        tampered.replace_range(0..1, if payload.starts_with('A') { "B" } else { "A" });
+        assert!(signer
+            .verify(&format!("{}.{}", tampered, signature))
+            .is_err());
+    }
+
+    #[test]
+    fn test_expired_token() {
+        let signer = PreviewSigner::new("secret");
+        let token = signer.sign(&claims(-1)).unwrap();
+        assert!(signer.verify(&token).is_err());
+    }
+}
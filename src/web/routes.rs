@@ -0,0 +1,56 @@
+use crate::web::{api, handlers, preview, server::AppState};
+use axum::{
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use std::path::Path;
+use tower_http::{services::ServeDir, trace::TraceLayer};
+
+/// 工作区内的 API；同一组路由同时挂载在 `/api`（按令牌）和 `/w/{workspace}/api`（按路径）下
+fn api_routes() -> Router<AppState> {
+    Router::new()
+        .route("/workspace", get(api::workspace_info))
+        .route("/contents", get(api::list_contents))
+        .route("/contents/:id/preview-link", post(api::create_preview_link))
+        .route("/contents/:id/workflow", post(api::transition_workflow))
+        .route("/contents/:id/publish", post(api::publish_content))
+        .route("/process", post(api::process_document))
+        .route("/render", post(api::render_preview))
+        .route("/themes/events", get(api::theme_events))
+        .route("/jobs/process", post(api::start_process_job))
+        .route("/jobs/publish", post(api::start_publish_job))
+        .route("/jobs/:id", get(api::get_job))
+        .route("/jobs/:id/events", get(api::job_events))
+        .route(
+            "/assets",
+            // 请求体大小按工作区配置 assets.max_size_mb 在处理函数中限制
+            post(api::upload_assets).layer(DefaultBodyLimit::disable()),
+        )
+}
+
+pub fn create_router(state: AppState, static_dir: Option<&Path>) -> Router {
+    let mut router = Router::new()
+        .route("/healthz", get(handlers::healthz))
+        .route("/readyz", get(handlers::readyz))
+        .route("/metrics", get(handlers::metrics))
+        .nest("/api", api_routes())
+        .nest("/w/:workspace/api", api_routes())
+        .route("/w/:workspace/assets/*path", get(api::serve_asset))
+        .route("/p/:token", get(preview::preview_page))
+        .route("/preview/:platform", get(preview::last_processed_page))
+        .route(
+            "/w/:workspace/preview/:platform",
+            get(preview::last_processed_page),
+        );
+
+    if let Some(dir) = static_dir {
+        router = router.nest_service("/static", ServeDir::new(dir));
+    }
+
+    router
+        .layer(middleware::from_fn(handlers::track_requests))
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
@@ -0,0 +1,221 @@
+use crate::{
+    adapters::AdapterCache,
+    cli::args::AppConfig,
+    core::{BlockCache, Workspace, WorkspaceManager},
+    publishers::ImageUploader,
+    web::{
+        job_store::JobStore,
+        jobs::JobManager,
+        preview::PreviewSigner,
+        routes::create_router,
+        themes::{watch_themes, ThemeEvent},
+    },
+    Result,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+/// 检查任务表中排队任务（重试、重启后继续）的间隔
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Web 服务共享状态
+#[derive(Clone)]
+pub struct AppState {
+    inner: Arc<AppStateInner>,
+}
+
+#[derive(Clone)]
+struct AppStateInner {
+    workspaces: WorkspaceManager,
+    started_at: chrono::DateTime<chrono::Utc>,
+    image_uploader: Option<Arc<dyn ImageUploader>>,
+    jobs: Arc<JobManager>,
+    /// 工作区名 -> 实时预览的块缓存
+    render_caches: Arc<Mutex<HashMap<String, BlockCache>>>,
+    theme_events: broadcast::Sender<ThemeEvent>,
+    /// 实时预览的适配结果缓存，切换预览平台时无需重新适配
+    adapter_cache: Arc<Mutex<AdapterCache>>,
+    /// 工作区名 -> 最近一次 `POST /api/process` 的各平台输出（平台名 -> HTML）
+    last_processed: Arc<Mutex<HashMap<String, BTreeMap<String, String>>>>,
+}
+
+impl AppState {
+    /// `data_root` 为数据根目录（默认 `~/.markflow`），其下包含所有工作区
+    pub fn new(data_root: impl Into<PathBuf>) -> Self {
+        let data_root = data_root.into();
+        Self {
+            inner: Arc::new(AppStateInner {
+                jobs: Arc::new(JobManager::new(JobStore::in_data_root(&data_root))),
+                workspaces: WorkspaceManager::new(data_root),
+                started_at: chrono::Utc::now(),
+                image_uploader: None,
+                render_caches: Arc::default(),
+                theme_events: broadcast::channel(16).0,
+                adapter_cache: Arc::new(Mutex::new(AdapterCache::in_memory())),
+                last_processed: Arc::default(),
+            }),
+        }
+    }
+
+    /// 设置后，通过 `/api/assets` 上传的图片直接转发到图床/平台素材库，不再保存到本地
+    pub fn with_image_uploader(mut self, uploader: Arc<dyn ImageUploader>) -> Self {
+        Arc::make_mut(&mut self.inner).image_uploader = Some(uploader);
+        self
+    }
+
+    /// 预览链接签名器，密钥保存在数据根目录的 `preview.key`
+    pub fn preview_signer(&self) -> Result<PreviewSigner> {
+        PreviewSigner::load_or_create(&self.inner.workspaces.data_root().join("preview.key"))
+    }
+
+    pub fn jobs(&self) -> &Arc<JobManager> {
+        &self.inner.jobs
+    }
+
+    /// 在任务所属的工作区中执行到期的排队任务
+    pub fn run_queued_jobs(&self) -> Result<usize> {
+        self.inner
+            .jobs
+            .run_queued(|name| self.inner.workspaces.get(name))
+    }
+
+    /// 使用工作区的实时预览块缓存
+    pub fn with_render_cache<R>(&self, workspace: &str, f: impl FnOnce(&mut BlockCache) -> R) -> R {
+        let mut caches = self
+            .inner
+            .render_caches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        f(caches.entry(workspace.to_string()).or_default())
+    }
+
+    /// 主题文件被修改：清空该工作区的实时预览缓存，并通知正在预览的页面重新渲染
+    pub fn theme_changed(&self, event: ThemeEvent) {
+        self.inner
+            .render_caches
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&event.workspace);
+        let _ = self.inner.theme_events.send(event);
+    }
+
+    pub fn subscribe_theme_events(&self) -> broadcast::Receiver<ThemeEvent> {
+        self.inner.theme_events.subscribe()
+    }
+
+    pub fn with_adapter_cache<R>(&self, f: impl FnOnce(&mut AdapterCache) -> R) -> R {
+        f(&mut self
+            .inner
+            .adapter_cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// 记录工作区最近处理的文档，供 `/preview/{platform}` 查看
+    pub fn set_last_processed(&self, workspace: &str, outputs: BTreeMap<String, String>) {
+        self.inner
+            .last_processed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(workspace.to_string(), outputs);
+    }
+
+    /// 工作区最近处理的文档在 `platform` 上的输出
+    pub fn last_processed(&self, workspace: &str, platform: &str) -> Option<String> {
+        self.inner
+            .last_processed
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(workspace)?
+            .get(platform)
+            .cloned()
+    }
+
+    pub fn image_uploader(&self) -> Option<&Arc<dyn ImageUploader>> {
+        self.inner.image_uploader.as_ref()
+    }
+
+    pub fn workspaces(&self) -> &WorkspaceManager {
+        &self.inner.workspaces
+    }
+
+    pub fn default_workspace(&self) -> Result<Workspace> {
+        self.inner.workspaces.default_workspace()
+    }
+
+    /// 每次从磁盘读取默认工作区的配置，修改配置后无需重启服务
+    pub fn load_config(&self) -> Result<AppConfig> {
+        self.default_workspace()?.load_config()
+    }
+
+    pub fn started_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.inner.started_at
+    }
+}
+
+pub struct WebServer {
+    state: AppState,
+    static_dir: Option<PathBuf>,
+}
+
+impl WebServer {
+    pub fn new(state: AppState) -> Self {
+        Self {
+            state,
+            static_dir: None,
+        }
+    }
+
+    pub fn with_static_dir(mut self, static_dir: Option<PathBuf>) -> Self {
+        self.static_dir = static_dir;
+        self
+    }
+
+    pub async fn run(self, addr: SocketAddr) -> Result<()> {
+        let requeued = self.state.jobs().store().requeue_interrupted()?;
+        if requeued > 0 {
+            info!("{} 个上次未完成的任务已重新排队", requeued);
+        }
+        let state = self.state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(JOB_POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = state.run_queued_jobs() {
+                    warn!("执行排队任务失败: {}", e);
+                }
+            }
+        });
+
+        // 监控器需要在服务运行期间一直存在
+        let _theme_watcher = match watch_themes(self.state.clone()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("主题热重载不可用: {}", e);
+                None
+            }
+        };
+
+        let router = create_router(self.state, self.static_dir.as_deref());
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        info!("Web服务器已启动: http://{}", listener.local_addr()?);
+
+        axum::serve(listener, router)
+            .with_graceful_shutdown(shutdown_signal())
+            .await?;
+
+        info!("Web服务器已停止");
+        Ok(())
+    }
+}
+
+async fn shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
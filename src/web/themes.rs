@@ -0,0 +1,119 @@
+use crate::{
+    core::{theme_name, WorkspaceManager, THEMES_DIR},
+    error::Error,
+    web::server::AppState,
+    Result,
+};
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+
+/// 编辑器保存文件时通常连续产生多个事件，收到第一个事件后等待这段时间再统一处理
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 主题文件变化的通知，通过 `/api/themes/events` 推送给预览页面
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct ThemeEvent {
+    pub workspace: String,
+    pub theme: String,
+}
+
+/// 各工作区的主题目录：`default` 为数据根目录下的 `themes/`，
+/// 其他工作区为 `workspaces/<name>/themes/`
+fn theme_dirs(workspaces: &WorkspaceManager) -> Result<Vec<(String, PathBuf)>> {
+    workspaces
+        .list()?
+        .into_iter()
+        .map(|name| {
+            let dir = workspaces.get(&name)?.root().join(THEMES_DIR);
+            Ok((name, dir))
+        })
+        .collect()
+}
+
+/// 变化的文件所属的工作区和主题
+fn changed_theme(dirs: &[(String, PathBuf)], path: &Path) -> Option<ThemeEvent> {
+    let theme = theme_name(path)?;
+    let dir = path.parent()?;
+    dirs.iter()
+        .find(|(_, themes)| themes == dir)
+        .map(|(workspace, _)| ThemeEvent {
+            workspace: workspace.clone(),
+            theme,
+        })
+}
+
+/// 监控所有工作区的主题目录。主题文件变化时，对使用该主题的工作区清空实时预览缓存
+/// 并推送 [`ThemeEvent`]，预览页面据此重新渲染。返回的监控器被 drop 后停止监控
+pub fn watch_themes(state: AppState) -> Result<RecommendedWatcher> {
+    let dirs = theme_dirs(state.workspaces())?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+        if let Ok(event) = res {
+            let _ = tx.send(event.paths);
+        }
+    })
+    .map_err(|e| Error::Other(format!("创建主题监控器失败: {}", e)))?;
+
+    for (_, dir) in &dirs {
+        std::fs::create_dir_all(dir)?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| Error::Other(format!("监控主题目录失败 {:?}: {}", dir, e)))?;
+    }
+
+    tokio::spawn(async move {
+        while let Some(paths) = rx.recv().await {
+            tokio::time::sleep(DEBOUNCE).await;
+            let mut changed: BTreeSet<ThemeEvent> = paths
+                .iter()
+                .filter_map(|path| changed_theme(&dirs, path))
+                .collect();
+            while let Ok(paths) = rx.try_recv() {
+                changed.extend(paths.iter().filter_map(|path| changed_theme(&dirs, path)));
+            }
+
+            for event in changed {
+                let in_use = state
+                    .workspaces()
+                    .get(&event.workspace)
+                    .and_then(|workspace| workspace.load_config())
+                    .is_ok_and(|config| config.style.theme.as_deref() == Some(&event.theme));
+                if in_use {
+                    tracing::info!("主题已修改: {} ({})", event.theme, event.workspace);
+                    state.theme_changed(event);
+                }
+            }
+        }
+    });
+
+    Ok(watcher)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::DEFAULT_WORKSPACE;
+
+    #[test]
+    fn test_changed_theme_maps_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspaces = WorkspaceManager::new(dir.path());
+        workspaces.create("team").unwrap();
+        let dirs = theme_dirs(&workspaces).unwrap();
+
+        let event = changed_theme(&dirs, &dir.path().join("themes/dark.toml")).unwrap();
+        assert_eq!(event.workspace, DEFAULT_WORKSPACE);
+        assert_eq!(event.theme, "dark");
+
+        let event =
+            changed_theme(&dirs, &dir.path().join("workspaces/team/themes/light.toml")).unwrap();
+        assert_eq!(event.workspace, "team");
+        assert!(changed_theme(&dirs, &dir.path().join("themes/notes.txt")).is_none());
+    }
+}
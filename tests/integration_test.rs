@@ -149,6 +149,7 @@ fn test_error_handling() {
 }
 
 #[test]
+#[allow(clippy::unnecessary_unwrap)]
 fn test_large_content_handling() {
     let processor = MarkdownProcessor::new();
     let wechat_adapter = WeChatStyleAdapter::new();